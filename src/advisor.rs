@@ -0,0 +1,3613 @@
+//! Module for checking whether a model fits the hardware of the running system.
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::future::{poll_fn, Future};
+use std::path::Path;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::task::Poll;
+
+use serde::Serialize;
+use tokio::sync::Semaphore;
+
+use crate::backend::{AnalyticBackend, EstimatorBackend};
+use crate::bench::BenchResult;
+use crate::calibration::{Calibration, CalibrationStore};
+use crate::estimate::{
+    cache_precision, cached_layer_tokens, estimate_attention, estimate_cpu_inference,
+    estimate_inference_memory, estimate_kv_cache, estimate_kv_cache_with_sliding_window,
+    estimate_lora, estimate_offload, estimate_serving, estimate_serving_cost,
+    estimate_tensor_parallel_with_overhead, estimate_throughput, estimate_training,
+    estimate_training_time, estimate_unified_memory, kv_cache_bytes_per_token, plan_capacity,
+    plan_pipeline, quality_loss, quantized_weights_bytes, tensor_parallel_sizes, AttentionEstimate,
+    AttentionImpl, CapacityPlan, Checkpointing, CpuEstimate, CpuSetup, LoraSetup, OffloadEstimate,
+    OffloadSetup, OverheadModel, PipelinePlan, Pricing, QualityLoss, ServingCost, ServingEstimate,
+    ServingSetup, ServingSlo, Throughput, TrainingSetup, UnifiedMemoryEstimate, UnifiedMemorySetup,
+    NCCL_BUFFERS_BYTES, OS_RESERVED_BYTES, QUANTIZATION_LEVELS, TRAINING_MFU,
+};
+use crate::gate::{check_assertions, Assertion, AssertionResult};
+use crate::hardware::{scan_hardware, Hardware, NO_COMPUTE_CAPABILITY};
+use crate::hub::local_cache::list_local_files;
+use crate::hub::{
+    parse_parameter_count, retrieve_dataset_info, GgufFile, HubClient, ModelConfig, SearchQuery,
+    Siblings,
+};
+use crate::models::{ModelConfigTrait, ModelError, Precision, QuantKernel};
+use crate::report::{
+    BatchEntry, BatchReport, CloudReport, CloudRow, Comparison, ComparisonRow, DeviceSection,
+    Report, Requirements, TrainingReport,
+};
+use crate::specs::{AppleChipSpec, GpuSpec, InstanceSpec};
+use crate::watch::Watcher;
+
+/// Largest number of GPUs of a node, the requirements split a model across
+const MAX_NODE_GPUS: i32 = 8;
+/// Largest number of models of a batch fetched from the Hub at once
+pub const BATCH_CONCURRENCY: usize = 8;
+
+/// Enumerate the workloads a model can be checked against
+#[derive(Clone, Debug, PartialEq)]
+pub enum Scenario {
+    /// Text generation with a KV cache of `batch_size` sequences of `seq_len` tokens
+    Inference {
+        /// The precision the weights are loaded in
+        precision: Precision,
+        /// The number of sequences generated at once
+        batch_size: i32,
+        /// The length of the sequences, prompt included
+        seq_len: i32,
+        /// The memory the framework takes on the device
+        overhead: OverheadModel,
+        /// Whether the framework evicts the tokens out of the attention window from the cache of
+        /// the sliding-window layers, the whole context being cached otherwise
+        sliding_window: bool,
+    },
+    /// Full fine-tuning
+    Training(TrainingSetup),
+    /// LoRA or QLoRA fine-tuning
+    Lora(LoraSetup),
+}
+
+/// Scenario implementation
+impl Scenario {
+    /// Returns the overhead model the scenario is estimated with
+    pub fn overhead(&self) -> OverheadModel {
+        match self {
+            Scenario::Inference { overhead, .. } => *overhead,
+            Scenario::Training(setup) => setup.overhead,
+            Scenario::Lora(setup) => setup.overhead,
+        }
+    }
+    /// Returns the precision the weights are loaded in
+    pub fn precision(&self) -> Precision {
+        match self {
+            Scenario::Inference { precision, .. } => *precision,
+            Scenario::Training(setup) => setup.precision,
+            Scenario::Lora(setup) => setup.base_precision,
+        }
+    }
+    /// Returns the same scenario with the weights loaded in another precision
+    pub fn with_precision(&self, precision: Precision) -> Scenario {
+        let mut scenario = self.clone();
+        match &mut scenario {
+            Scenario::Inference {
+                precision: weights, ..
+            } => *weights = precision,
+            Scenario::Training(setup) => setup.precision = precision,
+            Scenario::Lora(setup) => setup.base_precision = precision,
+        }
+        scenario
+    }
+}
+
+/// Implement the display of the Scenario enum
+impl Display for Scenario {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Scenario::Inference {
+                precision,
+                batch_size,
+                seq_len,
+                ..
+            } => write!(
+                f,
+                "inference in {}, {} × {} tokens",
+                precision, batch_size, seq_len
+            ),
+            Scenario::Training(setup) => write!(
+                f,
+                "training in {}, {} × {} tokens on {} GPU(s)",
+                setup.precision, setup.batch_size, setup.seq_len, setup.num_gpus
+            ),
+            Scenario::Lora(setup) => write!(
+                f,
+                "LoRA (r={}) on {} weights, {} × {} tokens",
+                setup.r, setup.base_precision, setup.batch_size, setup.seq_len
+            ),
+        }
+    }
+}
+
+/// Enumerate the verdicts of a fit check, from the best to the worst
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FitVerdict {
+    /// The model fits with some memory to spare
+    Fits,
+    /// The model fits, but with less than the safety margin of the memory to spare
+    Tight,
+    /// The model only runs with its weights (and optimizer states) offloaded to the CPU, one layer
+    /// at a time is loaded on the device
+    RequiresOffload,
+    /// The model does not run on the device, even with offloading
+    DoesNotFit,
+}
+
+/// Implement the display of the FitVerdict enum
+impl Display for FitVerdict {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let verdict = match self {
+            FitVerdict::Fits => "fits",
+            FitVerdict::Tight => "tight",
+            FitVerdict::RequiresOffload => "requires offload",
+            FitVerdict::DoesNotFit => "does not fit",
+        };
+        write!(f, "{}", verdict)
+    }
+}
+
+/// Enumerate the components of the memory a scenario needs
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryComponent {
+    /// The model weights, the adapters included
+    Weights,
+    /// The gradients
+    Gradients,
+    /// The optimizer states, the master weights included
+    OptimizerStates,
+    /// The activations kept for the backward pass
+    Activations,
+    /// The KV cache of the generated sequences
+    KvCache,
+    /// The communication buffers of the GPUs a model is split or sharded across
+    Communication,
+    /// The memory of the framework (CUDA context, allocator fragmentation)
+    Overhead,
+}
+
+/// Implement the display of the MemoryComponent enum
+impl Display for MemoryComponent {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let component = match self {
+            MemoryComponent::Weights => "weights",
+            MemoryComponent::Gradients => "gradients",
+            MemoryComponent::OptimizerStates => "optimizer states",
+            MemoryComponent::Activations => "activations",
+            MemoryComponent::KvCache => "KV cache",
+            MemoryComponent::Communication => "communication buffers",
+            MemoryComponent::Overhead => "framework overhead",
+        };
+        write!(f, "{}", component)
+    }
+}
+
+/// A struct representing how a line of a fit report is computed, so the verdict can be audited
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Explanation {
+    /// What the line is about, a memory component or the verdict
+    subject: String,
+    /// The formula the line is computed with
+    formula: String,
+    /// The values of the inputs of the formula, by name
+    inputs: BTreeMap<String, f64>,
+    /// Where the assumptions of the formula come from
+    source: String,
+}
+
+/// Explanation implementation
+impl Explanation {
+    /// Build a new `Explanation` struct based on the provided parameters
+    pub fn new(
+        subject: String,
+        formula: String,
+        inputs: BTreeMap<String, f64>,
+        source: String,
+    ) -> Explanation {
+        Explanation {
+            subject,
+            formula,
+            inputs,
+            source,
+        }
+    }
+    /// Returns what the line is about
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+    /// Returns the formula the line is computed with
+    pub fn formula(&self) -> &str {
+        &self.formula
+    }
+    /// Returns the values of the inputs of the formula, by name
+    pub fn inputs(&self) -> &BTreeMap<String, f64> {
+        &self.inputs
+    }
+    /// Returns where the assumptions of the formula come from
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+/// Build the explanation of a line from borrowed values
+fn explain(
+    subject: impl Display,
+    formula: &str,
+    inputs: &[(&str, f64)],
+    source: String,
+) -> Explanation {
+    Explanation::new(
+        subject.to_string(),
+        formula.to_string(),
+        inputs
+            .iter()
+            .map(|(name, value)| (name.to_string(), *value))
+            .collect(),
+        source,
+    )
+}
+
+/// Returns the explanation of the framework overhead of a scenario
+fn explain_overhead(overhead: &OverheadModel, weights: i64, dynamic: i64) -> Explanation {
+    explain(
+        MemoryComponent::Overhead,
+        "cuda_context + allocator_headroom × weights + fragmentation × dynamic",
+        &[
+            ("cuda_context", overhead.cuda_context as f64),
+            ("allocator_headroom", overhead.allocator_headroom),
+            ("weights", weights as f64),
+            ("fragmentation", overhead.fragmentation),
+            ("dynamic", dynamic as f64),
+        ],
+        "OverheadModel, the defaults of PyTorch on CUDA unless overridden".to_string(),
+    )
+}
+
+/// A struct representing whether a model fits a device for a given scenario
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FitReport {
+    /// The name of the device
+    device: String,
+    /// The verdict of the check
+    verdict: FitVerdict,
+    /// The memory the scenario needs, in bytes
+    required: i64,
+    /// The memory of the device, in bytes
+    available: i64,
+    /// The component taking the most memory
+    limiting_factor: MemoryComponent,
+    /// A warning about the estimate, e.g. a precision the device doesn't support
+    #[serde(skip_serializing_if = "Option::is_none")]
+    warning: Option<String>,
+    /// The quality the quantization of the weights loses against FP16, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quality_loss: Option<QualityLoss>,
+    /// How every memory component and the verdict are computed
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    explanations: Vec<Explanation>,
+}
+
+/// FitReport implementation
+impl FitReport {
+    /// Build a new `FitReport` struct based on the provided parameters
+    pub fn new(
+        device: String,
+        verdict: FitVerdict,
+        required: i64,
+        available: i64,
+        limiting_factor: MemoryComponent,
+    ) -> FitReport {
+        FitReport {
+            device,
+            verdict,
+            required,
+            available,
+            limiting_factor,
+            warning: None,
+            quality_loss: None,
+            explanations: Vec::new(),
+        }
+    }
+    /// Set a warning about the estimate
+    pub fn with_warning(mut self, warning: Option<String>) -> Self {
+        self.warning = warning;
+        self
+    }
+    /// Set the quality the quantization of the weights loses against FP16
+    pub fn with_quality_loss(mut self, quality_loss: Option<QualityLoss>) -> Self {
+        self.quality_loss = quality_loss;
+        self
+    }
+    /// Set how the memory components and the verdict are computed
+    pub fn with_explanations(mut self, explanations: Vec<Explanation>) -> Self {
+        self.explanations = explanations;
+        self
+    }
+    /// Returns the name of the device
+    pub fn device(&self) -> &str {
+        &self.device
+    }
+    /// Returns the verdict of the check
+    pub fn verdict(&self) -> FitVerdict {
+        self.verdict
+    }
+    /// Returns the memory the scenario needs
+    pub fn required(&self) -> i64 {
+        self.required
+    }
+    /// Returns the memory of the device
+    pub fn available(&self) -> i64 {
+        self.available
+    }
+    /// Returns the component taking the most memory
+    pub fn limiting_factor(&self) -> MemoryComponent {
+        self.limiting_factor
+    }
+    /// Returns the memory left on the device, negative when the model does not fit
+    pub fn headroom(&self) -> i64 {
+        self.available - self.required
+    }
+    /// Returns the warning about the estimate, if any
+    pub fn warning(&self) -> Option<&str> {
+        self.warning.as_deref()
+    }
+    /// Returns the quality the quantization of the weights loses against FP16, `None` for
+    /// unquantized weights or without a published measure
+    pub fn quality_loss(&self) -> Option<QualityLoss> {
+        self.quality_loss
+    }
+    /// Returns how every memory component and the verdict are computed, in the order of the
+    /// components, the verdict last
+    pub fn explanations(&self) -> &[Explanation] {
+        &self.explanations
+    }
+}
+
+/// Returns the precision a device of the given compute capability runs the weights in, the best
+/// one it supports when it lacks the capability, with a warning about the fallback. A device
+/// without CUDA runs the precision as is.
+fn supported_precision(
+    device: &str,
+    compute_capability: (u32, u32),
+    precision: Precision,
+) -> (Precision, Option<String>) {
+    if compute_capability == NO_COMPUTE_CAPABILITY {
+        return (precision, None);
+    }
+    let fallback = precision.fallback_on(compute_capability);
+    if fallback == precision {
+        return (precision, None);
+    }
+    let (major, minor) = precision.min_compute_capability();
+    let warning = format!(
+        "{}: {} needs a compute capability of {}.{} or more (found {}.{}), estimated in {}",
+        device, precision, major, minor, compute_capability.0, compute_capability.1, fallback
+    );
+    (fallback, Some(warning))
+}
+
+/// A struct representing the memory a scenario needs, split into its components
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScenarioMemory {
+    /// The memory of every component
+    components: Vec<(MemoryComponent, i64)>,
+    /// The memory which can be offloaded to the CPU
+    offloadable: i64,
+    /// The memory of the weights of the largest layer, loaded on the device when offloading
+    largest_layer: i64,
+    /// The share of the device memory kept free for the memory spikes
+    safety_margin: f64,
+    /// How every component is computed
+    explanations: Vec<Explanation>,
+}
+
+/// ScenarioMemory implementation
+impl ScenarioMemory {
+    /// Build a new `ScenarioMemory` struct based on the provided parameters
+    pub fn new(
+        components: Vec<(MemoryComponent, i64)>,
+        offloadable: i64,
+        largest_layer: i64,
+        safety_margin: f64,
+    ) -> ScenarioMemory {
+        ScenarioMemory {
+            components,
+            offloadable,
+            largest_layer,
+            safety_margin,
+            explanations: Vec::new(),
+        }
+    }
+    /// Returns the same estimate with the explanations of how every component is computed
+    pub fn with_explanations(mut self, explanations: Vec<Explanation>) -> ScenarioMemory {
+        self.explanations = explanations;
+        self
+    }
+    /// Returns the memory of every component
+    pub fn components(&self) -> &[(MemoryComponent, i64)] {
+        &self.components
+    }
+    /// Returns the memory which can be offloaded to the CPU
+    pub fn offloadable(&self) -> i64 {
+        self.offloadable
+    }
+    /// Returns the memory of the weights of the largest layer
+    pub fn largest_layer(&self) -> i64 {
+        self.largest_layer
+    }
+    /// Returns the share of the device memory kept free for the memory spikes
+    pub fn safety_margin(&self) -> f64 {
+        self.safety_margin
+    }
+    /// Returns the total memory the scenario needs
+    pub fn required(&self) -> i64 {
+        self.components.iter().map(|(_, bytes)| bytes).sum()
+    }
+    /// Returns the memory the scenario needs with everything offloadable on the CPU
+    pub fn resident(&self) -> i64 {
+        match self.offloadable > 0 {
+            true => self.required() - self.offloadable + self.largest_layer,
+            false => self.required(),
+        }
+    }
+    /// Returns the component taking the most memory
+    pub fn limiting_factor(&self) -> MemoryComponent {
+        self.components
+            .iter()
+            .max_by_key(|(_, bytes)| *bytes)
+            .map(|(component, _)| *component)
+            .unwrap_or(MemoryComponent::Weights)
+    }
+    /// Scale every component by the ratio of the measured memory to the estimated one
+    fn calibrate(&mut self, calibration: &Calibration) {
+        let factor = calibration.memory_factor();
+        for (_, bytes) in self.components.iter_mut() {
+            *bytes = calibration.apply_memory(*bytes);
+        }
+        self.offloadable = calibration.apply_memory(self.offloadable);
+        self.largest_layer = calibration.apply_memory(self.largest_layer);
+        self.explanations.push(explain(
+            "calibration",
+            "component × memory_factor",
+            &[
+                ("memory_factor", factor),
+                ("samples", calibration.memory_samples() as f64),
+            ],
+            "mean ratio of the measured memory to the estimated one, from the calibration store"
+                .to_string(),
+        ));
+    }
+    /// Returns the verdict of the scenario on a device with the given memory
+    pub fn verdict(&self, available: i64) -> FitVerdict {
+        let required = self.required();
+        if required as f64 <= available as f64 * (1.0 - self.safety_margin) {
+            FitVerdict::Fits
+        } else if required <= available {
+            FitVerdict::Tight
+        } else if self.resident() <= available {
+            FitVerdict::RequiresOffload
+        } else {
+            FitVerdict::DoesNotFit
+        }
+    }
+    /// Returns how every component and the verdict on a device with the given memory are
+    /// computed
+    fn explanations(&self, available: i64) -> Vec<Explanation> {
+        let verdict = explain(
+            "verdict",
+            "fits when required ≤ available × (1 − safety_margin), tight when required ≤ \
+             available, requires offload when required − offloadable + largest_layer ≤ available",
+            &[
+                ("required", self.required() as f64),
+                ("available", available as f64),
+                ("safety_margin", self.safety_margin),
+                ("offloadable", self.offloadable as f64),
+                ("largest_layer", self.largest_layer as f64),
+            ],
+            "OverheadModel::safety_margin, and the offloadable weights and states of the scenario"
+                .to_string(),
+        );
+        let mut explanations = self.explanations.clone();
+        explanations.push(verdict);
+        explanations
+    }
+}
+
+/// Returns the explanation of the weights of a scenario, `sharded_gpus` being the number of GPUs
+/// they are divided across
+fn explain_weights(
+    config: &ModelConfig,
+    weights: i64,
+    precision: Precision,
+    sharded_gpus: i32,
+) -> Explanation {
+    let parameters = config.num_parameters();
+    let bytes_per_param = match parameters > 0 {
+        true => weights as f64 * sharded_gpus as f64 / parameters as f64,
+        false => 0.0,
+    };
+    explain(
+        MemoryComponent::Weights,
+        "parameters × bytes_per_param / sharded_gpus",
+        &[
+            ("parameters", parameters as f64),
+            ("bytes_per_param", bytes_per_param),
+            ("sharded_gpus", sharded_gpus as f64),
+        ],
+        format!(
+            "{} weights, the quantized precisions only quantize the linear layers",
+            precision
+        ),
+    )
+}
+
+/// Returns the explanation of the activations kept for the backward pass
+fn explain_activations(
+    config: &ModelConfig,
+    activations: i64,
+    batch_size: i32,
+    seq_len: i32,
+    checkpointing: Checkpointing,
+) -> Explanation {
+    explain(
+        MemoryComponent::Activations,
+        "layers × (per_layer − recomputed) + recomputed + 4 × batch_size × seq_len × vocab_size",
+        &[
+            ("activations", activations as f64),
+            ("layers", config.num_hidden_layers() as f64),
+            ("batch_size", batch_size as f64),
+            ("seq_len", seq_len as f64),
+            ("hidden_size", config.hidden_size() as f64),
+            ("vocab_size", config.vocab_size() as f64),
+        ],
+        format!(
+            "Korthikanti et al. (2022) with a memory efficient attention, {:?} checkpointing, FP32 \
+             logits",
+            checkpointing
+        ),
+    )
+}
+
+/// Estimate the memory a scenario needs on a single device
+pub(crate) fn estimate_scenario(config: &ModelConfig, scenario: &Scenario) -> ScenarioMemory {
+    let largest_layer =
+        |precision: Precision| precision.weights_bytes(config.parameter_breakdown().per_layer());
+    match scenario {
+        Scenario::Inference {
+            precision,
+            batch_size,
+            seq_len,
+            overhead,
+            sliding_window,
+        } => {
+            let weights = quantized_weights_bytes(config, *precision);
+            let kv_cache_precision = cache_precision(*precision);
+            let decoder_layers = config.num_decoder_layers();
+            // The cached tokens are averaged over the layers when only some of them slide
+            let (kv_cache, cached_tokens, scope) = match sliding_window {
+                true if decoder_layers > 0 => (
+                    estimate_kv_cache_with_sliding_window(
+                        config,
+                        *batch_size,
+                        *seq_len,
+                        kv_cache_precision,
+                    ),
+                    cached_layer_tokens(config, *seq_len) / decoder_layers as f64,
+                    ", up to the sliding window in the sliding-window layers",
+                ),
+                _ => (
+                    estimate_kv_cache(config, *batch_size, *seq_len, kv_cache_precision),
+                    *seq_len as f64,
+                    "",
+                ),
+            };
+            let kv_cache_explanation = explain(
+                MemoryComponent::KvCache,
+                "2 × decoder_layers × kv_dim × bytes_per_value × batch_size × cached_tokens",
+                &[
+                    ("decoder_layers", decoder_layers as f64),
+                    ("kv_dim", config.kv_dim() as f64),
+                    (
+                        "bytes_per_value",
+                        cache_precision(*precision).bytes_per_param(),
+                    ),
+                    ("batch_size", *batch_size as f64),
+                    ("cached_tokens", cached_tokens),
+                ],
+                format!(
+                    "keys and values of every decoder layer in {}{}",
+                    kv_cache_precision, scope
+                ),
+            );
+            ScenarioMemory {
+                components: vec![
+                    (MemoryComponent::Weights, weights),
+                    (MemoryComponent::KvCache, kv_cache),
+                    (
+                        MemoryComponent::Overhead,
+                        overhead.overhead_bytes(weights, kv_cache),
+                    ),
+                ],
+                offloadable: weights,
+                largest_layer: largest_layer(*precision),
+                safety_margin: overhead.safety_margin,
+                explanations: vec![
+                    explain_weights(config, weights, *precision, 1),
+                    kv_cache_explanation,
+                    explain_overhead(overhead, weights, kv_cache),
+                ],
+            }
+        }
+        Scenario::Training(setup) => {
+            let estimate = estimate_training(config, *setup);
+            let parameters = config.num_parameters() as f64;
+            let sharded_gpus = |sharded: bool| match sharded {
+                true => setup.num_gpus.max(1) as f64,
+                false => 1.0,
+            };
+            let explanations = vec![
+                explain_weights(
+                    config,
+                    estimate.weights(),
+                    setup.precision,
+                    sharded_gpus(setup.sharding.shards_weights()) as i32,
+                ),
+                explain(
+                    MemoryComponent::Gradients,
+                    "parameters × gradient_bytes / sharded_gpus",
+                    &[
+                        ("parameters", parameters),
+                        (
+                            "gradient_bytes",
+                            estimate.gradients() as f64
+                                * sharded_gpus(setup.sharding.shards_gradients())
+                                / parameters.max(1.0),
+                        ),
+                        (
+                            "sharded_gpus",
+                            sharded_gpus(setup.sharding.shards_gradients()),
+                        ),
+                    ],
+                    "gradients in the precision of the activations".to_string(),
+                ),
+                explain(
+                    MemoryComponent::OptimizerStates,
+                    "parameters × (master_weights_bytes + state_bytes) / sharded_gpus",
+                    &[
+                        ("parameters", parameters),
+                        (
+                            "bytes_per_param",
+                            estimate.optimizer() as f64
+                                * sharded_gpus(setup.sharding.shards_optimizer())
+                                / parameters.max(1.0),
+                        ),
+                        (
+                            "sharded_gpus",
+                            sharded_gpus(setup.sharding.shards_optimizer()),
+                        ),
+                    ],
+                    format!(
+                        "{:?} states, and FP32 master weights in mixed precision",
+                        setup.optimizer
+                    ),
+                ),
+                explain_activations(
+                    config,
+                    estimate.activations(),
+                    setup.batch_size,
+                    setup.seq_len,
+                    setup.checkpointing,
+                ),
+                explain(
+                    MemoryComponent::Communication,
+                    "gradient buckets + gathered weights of the sharded states",
+                    &[
+                        ("communication", estimate.communication() as f64),
+                        ("num_gpus", setup.num_gpus as f64),
+                    ],
+                    format!(
+                        "{:?} sharding, nothing is allocated on a single GPU",
+                        setup.sharding
+                    ),
+                ),
+                explain_overhead(&setup.overhead, estimate.weights(), estimate.activations()),
+            ];
+            ScenarioMemory {
+                components: vec![
+                    (MemoryComponent::Weights, estimate.weights()),
+                    (MemoryComponent::Gradients, estimate.gradients()),
+                    (MemoryComponent::OptimizerStates, estimate.optimizer()),
+                    (MemoryComponent::Activations, estimate.activations()),
+                    (MemoryComponent::Communication, estimate.communication()),
+                    (MemoryComponent::Overhead, estimate.overhead()),
+                ],
+                offloadable: estimate.weights() + estimate.gradients() + estimate.optimizer(),
+                largest_layer: largest_layer(setup.precision),
+                safety_margin: setup.overhead.safety_margin,
+                explanations,
+            }
+        }
+        Scenario::Lora(setup) => {
+            let estimate = estimate_lora(config, setup);
+            let adapter_parameters = setup.adapter_parameters(config) as f64;
+            let mut weights =
+                explain_weights(config, estimate.base_weights(), setup.base_precision, 1);
+            weights.formula.push_str(" + adapter_parameters × 4");
+            weights
+                .inputs
+                .insert("adapter_parameters".to_string(), adapter_parameters);
+            let explanations = vec![
+                weights,
+                explain(
+                    MemoryComponent::Gradients,
+                    "adapter_parameters × 4",
+                    &[("adapter_parameters", adapter_parameters)],
+                    "PEFT keeps the adapter gradients in FP32".to_string(),
+                ),
+                explain(
+                    MemoryComponent::OptimizerStates,
+                    "adapter_parameters × state_bytes, 0 when paged to the CPU",
+                    &[
+                        ("adapter_parameters", adapter_parameters),
+                        ("optimizer", estimate.optimizer() as f64),
+                    ],
+                    format!("{:?} states of the adapters only", setup.optimizer),
+                ),
+                explain_activations(
+                    config,
+                    estimate.activations(),
+                    setup.batch_size,
+                    setup.seq_len,
+                    setup.checkpointing,
+                ),
+                explain_overhead(
+                    &setup.overhead,
+                    estimate.base_weights() + estimate.adapter_weights(),
+                    estimate.activations(),
+                ),
+            ];
+            ScenarioMemory {
+                components: vec![
+                    (
+                        MemoryComponent::Weights,
+                        estimate.base_weights() + estimate.adapter_weights(),
+                    ),
+                    (MemoryComponent::Gradients, estimate.gradients()),
+                    (MemoryComponent::OptimizerStates, estimate.optimizer()),
+                    (MemoryComponent::Activations, estimate.activations()),
+                    (MemoryComponent::Overhead, estimate.overhead()),
+                ],
+                offloadable: estimate.base_weights(),
+                largest_layer: largest_layer(setup.base_precision),
+                safety_margin: setup.overhead.safety_margin,
+                explanations,
+            }
+        }
+    }
+}
+
+/// Estimate the memory an inference scenario needs on every GPU of a tensor parallel group, with
+/// the overhead model and the safety margin of the scenario. Returns `None` for the training
+/// scenarios.
+fn estimate_tensor_parallel_scenario(
+    config: &ModelConfig,
+    scenario: &Scenario,
+    tp_size: i32,
+) -> Option<ScenarioMemory> {
+    let (precision, batch_size, seq_len, overhead) = match *scenario {
+        Scenario::Inference {
+            precision,
+            batch_size,
+            seq_len,
+            overhead,
+            ..
+        } => (precision, batch_size, seq_len, overhead),
+        _ => return None,
+    };
+    let estimate = estimate_tensor_parallel_with_overhead(
+        config, precision, batch_size, seq_len, tp_size, &overhead,
+    )?;
+    let kv_heads = config.num_key_value_heads().max(1);
+    let explanations = vec![
+        explain(
+            MemoryComponent::Weights,
+            "(linear + lm_head) / tp_size + embeddings + norms",
+            &[
+                ("weights", estimate.weights() as f64),
+                ("tp_size", tp_size as f64),
+            ],
+            format!(
+                "{} weights, the embeddings and the norms are replicated on every GPU",
+                precision
+            ),
+        ),
+        explain(
+            MemoryComponent::KvCache,
+            "kv_cache × ceil(kv_heads / tp_size) / kv_heads",
+            &[
+                (
+                    "kv_cache",
+                    estimate_kv_cache(config, batch_size, seq_len, cache_precision(precision))
+                        as f64,
+                ),
+                ("kv_heads", kv_heads as f64),
+                ("tp_size", tp_size as f64),
+            ],
+            "the key and value heads are sharded, replicated when fewer than the GPUs".to_string(),
+        ),
+        explain(
+            MemoryComponent::Communication,
+            "nccl_buffers + 2 × batch_size × seq_len × hidden_size × activation_bytes",
+            &[
+                ("nccl_buffers", NCCL_BUFFERS_BYTES as f64),
+                ("batch_size", batch_size as f64),
+                ("seq_len", seq_len as f64),
+                ("hidden_size", config.hidden_size() as f64),
+            ],
+            "all-reduce of the hidden states after the attention and the MLP, none on a single GPU"
+                .to_string(),
+        ),
+        explain_overhead(&overhead, estimate.weights(), estimate.kv_cache()),
+    ];
+    Some(ScenarioMemory {
+        components: vec![
+            (MemoryComponent::Weights, estimate.weights()),
+            (MemoryComponent::KvCache, estimate.kv_cache()),
+            (MemoryComponent::Communication, estimate.communication()),
+            (MemoryComponent::Overhead, estimate.overhead()),
+        ],
+        offloadable: estimate.weights(),
+        largest_layer: precision.weights_bytes(config.parameter_breakdown().per_layer())
+            / tp_size as i64,
+        safety_margin: overhead.safety_margin,
+        explanations,
+    })
+}
+
+/// Check whether a model fits every GPU of the running system for the given scenario, returning
+/// one report per device (none without GPU).
+///
+/// Every device is checked on its own, the model is not split across them. A device lacking the
+/// compute capability of the precision (BF16 before Ampere, FP8 before Ada, quantized weights no
+/// kernel runs, see `check_kernels`) is checked with the best precision it supports, and its
+/// report warns about it.
+pub fn check_fit(hardware: &Hardware, config: &ModelConfig, scenario: Scenario) -> Vec<FitReport> {
+    check_fit_with_backend(hardware, config, scenario, &AnalyticBackend)
+}
+
+/// Check whether a model fits every GPU of the running system like `check_fit`, the memory being
+/// estimated by the given backend instead of the analytic formulas
+pub fn check_fit_with_backend(
+    hardware: &Hardware,
+    config: &ModelConfig,
+    scenario: Scenario,
+    backend: &dyn EstimatorBackend,
+) -> Vec<FitReport> {
+    check_fit_with(hardware, config, &scenario, backend, |_, _| None)
+}
+
+/// Check whether a model fits every GPU of the running system like `check_fit`, the memory
+/// being corrected by the measurements recorded in the calibration store for the model (its
+/// repository ID) running the scenario on every GPU
+pub fn check_fit_calibrated(
+    hardware: &Hardware,
+    config: &ModelConfig,
+    model: &str,
+    scenario: Scenario,
+    calibrations: &CalibrationStore,
+) -> Vec<FitReport> {
+    check_fit_with(
+        hardware,
+        config,
+        &scenario,
+        &AnalyticBackend,
+        |device, scenario| calibrations.get(model, device, scenario),
+    )
+}
+
+/// Check whether a model fits every GPU of the running system with the memory estimated by
+/// `backend`, `calibration` returning the calibration of a device running a scenario, if any
+#[tracing::instrument(
+    name = "check_fit",
+    level = "debug",
+    skip_all,
+    fields(
+        model_type = config.model_type(),
+        scenario = %scenario,
+        gpus = hardware.gpus().len()
+    )
+)]
+fn check_fit_with(
+    hardware: &Hardware,
+    config: &ModelConfig,
+    scenario: &Scenario,
+    backend: &dyn EstimatorBackend,
+    calibration: impl Fn(&str, &Scenario) -> Option<Calibration>,
+) -> Vec<FitReport> {
+    hardware
+        .gpus()
+        .into_iter()
+        .map(|device| {
+            let (precision, warning) = supported_precision(
+                &device.get_name(),
+                device.get_compute_capability(),
+                scenario.precision(),
+            );
+            let scenario = scenario.with_precision(precision);
+            let mut memory = backend.estimate_memory(config, &scenario);
+            if let Some(calibration) = calibration(&device.get_name(), &scenario) {
+                memory.calibrate(&calibration);
+            }
+            let available = device.get_memory_info() as i64;
+            FitReport::new(
+                device.get_name(),
+                memory.verdict(available),
+                memory.required(),
+                available,
+                memory.limiting_factor(),
+            )
+            .with_warning(warning)
+            .with_quality_loss(quality_loss(precision))
+            .with_explanations(memory.explanations(available))
+        })
+        .collect()
+}
+
+/// Check whether a model fits the GPUs of the running system when it is split across them with
+/// tensor parallelism, for an inference scenario. Returns `None` without GPU, or for the training
+/// scenarios.
+///
+/// The model is split across as many GPUs as its attention heads allow, the ones with the most
+/// memory first. The report describes a single GPU, the one with the least memory, and the
+/// precision falls back to the best one all of them support.
+pub fn check_fit_tensor_parallel(
+    hardware: &Hardware,
+    config: &ModelConfig,
+    scenario: Scenario,
+) -> Option<FitReport> {
+    let tp_size = *tensor_parallel_sizes(config, hardware.gpus().len() as i32).last()?;
+    let mut devices = hardware.gpus();
+    devices.sort_by_key(|device| std::cmp::Reverse(device.get_memory_info()));
+    let devices = &devices[..tp_size as usize];
+    let names: Vec<String> = devices.iter().map(|device| device.get_name()).collect();
+    let device = match names.iter().all(|name| *name == names[0]) {
+        true => format!("{} × {}", tp_size, names[0]),
+        false => names.join(" + "),
+    };
+    let compute_capability = devices
+        .iter()
+        .map(|device| device.get_compute_capability())
+        .min()?;
+    let (precision, warning) =
+        supported_precision(&device, compute_capability, scenario.precision());
+    let memory =
+        estimate_tensor_parallel_scenario(config, &scenario.with_precision(precision), tp_size)?;
+    let available = devices
+        .iter()
+        .map(|device| device.get_memory_info() as i64)
+        .min()?;
+    Some(
+        FitReport::new(
+            device,
+            memory.verdict(available),
+            memory.required(),
+            available,
+            memory.limiting_factor(),
+        )
+        .with_warning(warning)
+        .with_quality_loss(quality_loss(precision))
+        .with_explanations(memory.explanations(available)),
+    )
+}
+
+/// Returns the highest quality level of `QUANTIZATION_LEVELS` the weights of a model can be
+/// loaded in for the given scenario on a GPU of the running system without a tight fit, with its
+/// name and the report of the GPU. Returns `None` without GPU, or when even the smallest level does
+/// not fit.
+///
+/// The levels are tried from the largest, so the report tells the quality the model loses to fit,
+/// e.g. "fits at Q4_K_M with ~0.9% quality loss" (see `FitReport::quality_loss`).
+pub fn recommend_quantization(
+    hardware: &Hardware,
+    config: &ModelConfig,
+    scenario: Scenario,
+) -> Option<(&'static str, FitReport)> {
+    QUANTIZATION_LEVELS.iter().find_map(|&(name, precision)| {
+        check_fit(hardware, config, scenario.with_precision(precision))
+            .into_iter()
+            .filter(|report| report.verdict() == FitVerdict::Fits)
+            .max_by_key(|report| report.headroom())
+            .map(|report| (name, report))
+    })
+}
+
+/// Check whether a model runs on the CPU of the running system alone, like llama.cpp does, for
+/// generating `batch_size` sequences of `seq_len` tokens, and how fast. Returns `None` when the RAM
+/// of the system is unknown.
+///
+/// The memory bandwidth of the RAM is not reported by the system, dual-channel DDR4 is assumed
+/// (see `CpuSetup`) unless it was measured (see `check_fit_cpu_measured`). llama.cpp runs a thread
+/// per physical core, the SMT threads are left out.
+pub fn check_fit_cpu(
+    hardware: &Hardware,
+    config: &ModelConfig,
+    precision: Precision,
+    batch_size: i32,
+    seq_len: i32,
+) -> Option<CpuEstimate> {
+    check_fit_cpu_with(hardware, config, precision, batch_size, seq_len, None)
+}
+
+/// Check whether a model runs on the CPU of the running system alone like `check_fit_cpu`, with
+/// the memory bandwidth measured by the micro-benchmarks (see `bench::run_bench`)
+pub fn check_fit_cpu_measured(
+    hardware: &Hardware,
+    config: &ModelConfig,
+    precision: Precision,
+    batch_size: i32,
+    seq_len: i32,
+    bench: &BenchResult,
+) -> Option<CpuEstimate> {
+    check_fit_cpu_with(
+        hardware,
+        config,
+        precision,
+        batch_size,
+        seq_len,
+        Some(bench),
+    )
+}
+
+/// Check whether a model runs on the CPU of the running system alone, with the measured numbers
+/// of `bench` if any
+fn check_fit_cpu_with(
+    hardware: &Hardware,
+    config: &ModelConfig,
+    precision: Precision,
+    batch_size: i32,
+    seq_len: i32,
+    bench: Option<&BenchResult>,
+) -> Option<CpuEstimate> {
+    if hardware.memory == 0 {
+        return None;
+    }
+    let mut setup = CpuSetup::new(hardware.memory as i64, hardware.cpu_cores as i32);
+    if let Some(bench) = bench {
+        setup = bench.apply(setup);
+    }
+    Some(estimate_cpu_inference(
+        config, precision, batch_size, seq_len, &setup,
+    ))
+}
+
+/// Check whether a model runs on the GPU of the Apple Silicon chip of the running system with
+/// Metal, for generating `batch_size` sequences of `seq_len` tokens, and how fast. Returns `None`
+/// on another system, or when its memory is unknown.
+///
+/// The chip is not reported by the hardware scan, it is given by its name (see
+/// `AppleChipSpec::from_name`). The memory the GPU can wire is the default limit of macOS.
+pub fn check_fit_unified_memory(
+    hardware: &Hardware,
+    chip: &AppleChipSpec,
+    config: &ModelConfig,
+    precision: Precision,
+    batch_size: i32,
+    seq_len: i32,
+) -> Option<UnifiedMemoryEstimate> {
+    if hardware.os != "macos" || hardware.arch != "aarch64" || hardware.memory == 0 {
+        return None;
+    }
+    let setup = UnifiedMemorySetup::new(hardware.memory as i64);
+    Some(estimate_unified_memory(
+        config, chip, precision, batch_size, seq_len, &setup,
+    ))
+}
+
+/// Returns the longest context `batch_size` sequences can have on the GPU of the running system
+/// with the most memory, once the weights are loaded in the given precision. The context is
+/// capped to the longest one the model supports. With `sliding_window`, the framework evicts the
+/// tokens out of the attention window from the cache of the sliding-window layers. Returns `None`
+/// without GPU, or when the weights alone don't fit.
+pub fn max_context_length(
+    config: &ModelConfig,
+    hardware: &Hardware,
+    batch_size: i32,
+    precision: Precision,
+    sliding_window: bool,
+) -> Option<i32> {
+    let available = hardware
+        .gpus()
+        .into_iter()
+        .map(|device| device.get_memory_info() as i64)
+        .max()?;
+    let budget = available - estimate_inference_memory(config, precision).total();
+    if budget < 0 {
+        return None;
+    }
+
+    let max_context = config.effective_max_context();
+    let bytes_per_token =
+        batch_size.max(1) as f64 * kv_cache_bytes_per_token(config, cache_precision(precision));
+    if bytes_per_token <= 0.0 {
+        return Some(max_context);
+    }
+    let tokens = (budget as f64 / bytes_per_token).floor() as i64;
+    let window = match (sliding_window, config.sliding_window()) {
+        (true, Some(window)) if tokens >= window as i64 => window as f64,
+        _ => return Some(tokens.min(max_context as i64) as i32),
+    };
+    // Past the window, the cache only grows in the global layers
+    let decoder_layers = config.num_decoder_layers() as f64;
+    let sliding_layers = config.num_sliding_layers() as f64;
+    let global_layers = decoder_layers - sliding_layers;
+    if global_layers <= 0.0 {
+        return Some(max_context);
+    }
+    let bytes_per_layer_token = bytes_per_token / decoder_layers;
+    let tokens =
+        ((budget as f64 / bytes_per_layer_token - sliding_layers * window) / global_layers).floor();
+    Some((tokens as i64).min(max_context as i64) as i32)
+}
+
+/// Estimate where the memory of a model goes on the GPU of the running system with the most
+/// memory, the weights which don't fit being offloaded to the RAM then to the disk, for generating
+/// `batch_size` sequences of `seq_len` tokens. Returns `None` without GPU.
+pub fn check_offload(
+    hardware: &Hardware,
+    config: &ModelConfig,
+    precision: Precision,
+    batch_size: i32,
+    seq_len: i32,
+) -> Option<OffloadEstimate> {
+    let device = hardware
+        .gpus()
+        .into_iter()
+        .max_by_key(|device| device.get_memory_info())?;
+    let setup = OffloadSetup::new(
+        device.get_memory_info() as i64,
+        hardware.memory as i64,
+        device.get_pcie_bandwidth(),
+        device.get_memory_bandwidth(),
+    );
+    Some(estimate_offload(
+        config, precision, batch_size, seq_len, setup,
+    ))
+}
+
+/// Plan the partition of a model into pipeline stages across the GPUs of the running system,
+/// one stage per GPU in their system order, for generating `batch_size` sequences of `seq_len`
+/// tokens split into `num_microbatches` micro batches. Returns `None` when the GPUs don't have
+/// enough memory.
+///
+/// Unlike the tensor parallelism, the GPUs can have different memory sizes.
+pub fn plan_pipeline_parallel(
+    hardware: &Hardware,
+    config: &ModelConfig,
+    precision: Precision,
+    batch_size: i32,
+    seq_len: i32,
+    num_microbatches: i32,
+) -> Option<PipelinePlan> {
+    let device_memories: Vec<i64> = hardware
+        .gpus()
+        .into_iter()
+        .map(|device| device.get_memory_info() as i64)
+        .collect();
+    plan_pipeline(
+        config,
+        precision,
+        batch_size,
+        seq_len,
+        num_microbatches,
+        &device_memories,
+    )
+}
+
+/// Estimate how many requests of `setup.max_model_len` tokens a vLLM server reaches at once on
+/// the `setup.tp_size` GPUs of the running system with the most memory, the smallest of them
+/// bounding the KV cache blocks. Returns `None` without enough GPUs, or when a single request
+/// doesn't fit.
+pub fn max_serving_concurrency(
+    hardware: &Hardware,
+    config: &ModelConfig,
+    precision: Precision,
+    setup: &ServingSetup,
+) -> Option<ServingEstimate> {
+    let mut memories: Vec<i64> = hardware
+        .gpus()
+        .into_iter()
+        .map(|device| device.get_memory_info() as i64)
+        .collect();
+    memories.sort_by_key(|memory| std::cmp::Reverse(*memory));
+    let available = *memories.get(setup.tp_size.max(1) as usize - 1)?;
+    estimate_serving(config, precision, available, setup)
+}
+
+/// Plan whether the `setup.tp_size` GPUs of the running system with the most memory sustain the
+/// traffic of `slo` within its latency target, the smallest of them bounding the KV cache blocks
+/// (see `plan_capacity`). Returns `None` without enough GPUs.
+///
+/// The latencies are only estimated on the GPUs of the specifications database.
+pub fn plan_serving_capacity(
+    hardware: &Hardware,
+    config: &ModelConfig,
+    precision: Precision,
+    setup: &ServingSetup,
+    slo: &ServingSlo,
+) -> Option<CapacityPlan> {
+    let mut devices = hardware.gpus();
+    devices.sort_by_key(|device| std::cmp::Reverse(device.get_memory_info()));
+    let device = devices.get(setup.tp_size.max(1) as usize - 1)?;
+    let (precision, _) = supported_precision(
+        &device.get_name(),
+        device.get_compute_capability(),
+        precision,
+    );
+    Some(plan_capacity(
+        config,
+        GpuSpec::from_device(*device).as_ref(),
+        precision,
+        device.get_memory_info() as i64,
+        setup,
+        slo,
+    ))
+}
+
+/// A struct representing the attention a device runs a model with, and what it costs
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttentionReport {
+    /// The name of the device
+    device: String,
+    /// The best attention the device runs the model with
+    implementation: AttentionImpl,
+    /// The cost of the attention, the FlashAttention-2 one first
+    estimates: Vec<AttentionEstimate>,
+}
+
+/// AttentionReport implementation
+impl AttentionReport {
+    /// Build a new `AttentionReport` struct based on the provided parameters
+    pub fn new(
+        device: String,
+        implementation: AttentionImpl,
+        estimates: Vec<AttentionEstimate>,
+    ) -> AttentionReport {
+        AttentionReport {
+            device,
+            implementation,
+            estimates,
+        }
+    }
+    /// Returns the name of the device
+    pub fn device(&self) -> &str {
+        &self.device
+    }
+    /// Returns the best attention the device runs the model with
+    pub fn implementation(&self) -> AttentionImpl {
+        self.implementation
+    }
+    /// Returns the cost of the attention with FlashAttention-2, then with the eager attention
+    /// when FlashAttention-2 is not available
+    pub fn estimates(&self) -> &[AttentionEstimate] {
+        &self.estimates
+    }
+}
+
+/// Check which attention every GPU of the running system runs a model with, for a prompt of
+/// `batch_size` sequences of `seq_len` tokens, returning one report per device.
+///
+/// When FlashAttention-2 is not available on a device (before Ampere, FP32 activations, or no
+/// PyTorch checkpoint), both its cost and the one of the eager attention are reported, to tell
+/// what the user misses out on. The time is only estimated on the GPUs of the specifications
+/// database.
+pub fn check_attention(
+    hardware: &Hardware,
+    config: &ModelConfig,
+    precision: Precision,
+    batch_size: i32,
+    seq_len: i32,
+) -> Vec<AttentionReport> {
+    hardware
+        .gpus()
+        .into_iter()
+        .map(|device| {
+            let spec = GpuSpec::from_device(device);
+            let implementation =
+                AttentionImpl::available(config, precision, device.get_compute_capability());
+            let implementations = match implementation {
+                AttentionImpl::FlashAttention2 => vec![AttentionImpl::FlashAttention2],
+                AttentionImpl::Eager => vec![AttentionImpl::FlashAttention2, AttentionImpl::Eager],
+            };
+            let estimates = implementations
+                .into_iter()
+                .map(|implementation| {
+                    estimate_attention(
+                        config,
+                        spec.as_ref(),
+                        precision,
+                        batch_size,
+                        seq_len,
+                        implementation,
+                    )
+                })
+                .collect();
+            AttentionReport::new(device.get_name(), implementation, estimates)
+        })
+        .collect()
+}
+
+/// Returns the minimum hardware a scenario of a model needs, to document it in a model card or a
+/// deployment doc.
+///
+/// The GPU memory leaves the safety margin of the scenario free. A model too large for the GPU
+/// with the most memory of the specifications database is split with tensor parallelism for
+/// inference, and its states sharded evenly across GPUs for fine-tuning. The RAM loads the
+/// weights next to the system, the disk holds the checkpoint: the quantized one for the
+/// pre-quantized formats, the original one for bitsandbytes which quantizes on load. The compute
+/// capability is the one of the precision, Ampere or newer when the model can use
+/// FlashAttention-2.
+#[tracing::instrument(
+    level = "debug",
+    skip_all,
+    fields(model_type = config.model_type(), scenario = %scenario)
+)]
+pub fn minimum_requirements(config: &ModelConfig, scenario: Scenario) -> Requirements {
+    let precision = scenario.precision();
+    let margin = 1.0 - scenario.overhead().safety_margin;
+    let largest_gpu = GpuSpec::all()
+        .iter()
+        .map(|spec| spec.memory() as i64)
+        .max()
+        .unwrap_or(0);
+    let with_margin = |required: i64| (required as f64 / margin).ceil() as i64;
+
+    let single = with_margin(estimate_scenario(config, &scenario).required());
+    let (vram_per_gpu, num_gpus) = match (&scenario, single > largest_gpu) {
+        (_, false) => (single, 1),
+        (Scenario::Inference { .. }, true) => {
+            let splits: Vec<(i64, i32)> = tensor_parallel_sizes(config, MAX_NODE_GPUS)
+                .into_iter()
+                .filter_map(|tp_size| {
+                    let memory = estimate_tensor_parallel_scenario(config, &scenario, tp_size)?;
+                    Some((with_margin(memory.required()), tp_size))
+                })
+                .collect();
+            splits
+                .iter()
+                .find(|(vram, _)| *vram <= largest_gpu)
+                .or(splits.last())
+                .copied()
+                .unwrap_or((single, 1))
+        }
+        (_, true) => {
+            let num_gpus = ((single + largest_gpu - 1) / largest_gpu.max(1)) as i32;
+            ((single + num_gpus as i64 - 1) / num_gpus as i64, num_gpus)
+        }
+    };
+
+    let weights = quantized_weights_bytes(config, precision);
+    let disk = match precision {
+        Precision::GPTQ { .. } | Precision::AWQ { .. } | Precision::GGUF(_) | Precision::FP8 => {
+            weights
+        }
+        _ => config.num_parameters() * config.dtype().num_bytes(),
+    };
+    // llama.cpp runs the GGUF files with its own attention
+    let flash_attention = !matches!(precision, Precision::GGUF(_))
+        && AttentionImpl::available(config, precision, (9, 0)) == AttentionImpl::FlashAttention2;
+    let compute_capability = match flash_attention {
+        true => precision.min_compute_capability().max((8, 0)),
+        false => precision.min_compute_capability(),
+    };
+    Requirements::new(
+        scenario.to_string(),
+        vram_per_gpu,
+        num_gpus,
+        weights + OS_RESERVED_BYTES,
+        disk,
+        compute_capability,
+    )
+}
+
+/// A struct representing the kernels a device runs quantized weights with
+#[derive(Clone, Debug, PartialEq)]
+pub struct KernelReport {
+    /// The name of the device
+    device: String,
+    /// The kernels running on the device, from the fastest
+    kernels: Vec<QuantKernel>,
+    /// A warning when the fastest kernels don't run on the device
+    warning: Option<String>,
+}
+
+/// KernelReport implementation
+impl KernelReport {
+    /// Build a new `KernelReport` struct based on the provided parameters
+    pub fn new(device: String, kernels: Vec<QuantKernel>, warning: Option<String>) -> KernelReport {
+        KernelReport {
+            device,
+            kernels,
+            warning,
+        }
+    }
+    /// Returns the name of the device
+    pub fn device(&self) -> &str {
+        &self.device
+    }
+    /// Returns the kernels running on the device, from the fastest
+    pub fn kernels(&self) -> &[QuantKernel] {
+        &self.kernels
+    }
+    /// Returns the fastest kernel running on the device, `None` when none does
+    pub fn best(&self) -> Option<QuantKernel> {
+        self.kernels.first().copied()
+    }
+    /// Returns the warning when the fastest kernels don't run on the device, if any
+    pub fn warning(&self) -> Option<&str> {
+        self.warning.as_deref()
+    }
+}
+
+/// Check which kernels every GPU of the running system runs the weights in the given quantized
+/// precision with, from its compute capability, returning one report per device. Unquantized and
+/// FP8 weights need no kernel, they return no report.
+///
+/// A device lacking the compute capability of the fastest kernels (Machete before Hopper, Marlin
+/// before Ampere) falls back to the next ones, and its report warns about it. Without any kernel,
+/// the weights have to be dequantized to 16-bit. The GPUs without CUDA, which don't run these
+/// kernels, have no report.
+pub fn check_kernels(hardware: &Hardware, precision: Precision) -> Vec<KernelReport> {
+    let candidates = QuantKernel::candidates(precision);
+    if candidates.is_empty() {
+        return Vec::new();
+    }
+    hardware
+        .gpus()
+        .into_iter()
+        .filter(|device| device.get_compute_capability() != NO_COMPUTE_CAPABILITY)
+        .map(|device| {
+            let name = device.get_name();
+            let compute_capability = device.get_compute_capability();
+            let kernels = QuantKernel::usable(precision, compute_capability);
+            let (major, minor) = compute_capability;
+            let warning = match kernels.first() {
+                Some(kernel) if *kernel == candidates[0] => None,
+                Some(kernel) => {
+                    let (needed_major, needed_minor) = candidates[0].min_compute_capability();
+                    Some(format!(
+                        "{}: {} needs a compute capability of {}.{} or more (found {}.{}), {} \
+                         runs the {} weights instead",
+                        name,
+                        candidates[0],
+                        needed_major,
+                        needed_minor,
+                        major,
+                        minor,
+                        kernel,
+                        precision
+                    ))
+                }
+                None => Some(format!(
+                    "{}: no kernel runs the {} weights on a compute capability of {}.{}, they \
+                     have to be loaded in {}",
+                    name,
+                    precision,
+                    major,
+                    minor,
+                    precision.fallback_on(compute_capability)
+                )),
+            };
+            KernelReport::new(name, kernels, warning)
+        })
+        .collect()
+}
+
+/// Suggest the cloud instances able to serve a model for a workload, see `recommend_instances`,
+/// with their memory headroom, their price per hour and the cost of a million tokens
+pub fn suggest_instances(
+    model: &str,
+    config: &ModelConfig,
+    scenario: Scenario,
+    min_tokens_per_second: f64,
+) -> CloudReport {
+    let rows = recommend_instances(config, scenario.clone(), min_tokens_per_second)
+        .iter()
+        .map(CloudRow::from_recommendation)
+        .collect();
+    CloudReport::new(model.to_string(), scenario.to_string(), rows)
+}
+
+/// A struct representing a cloud instance able to serve a model
+#[derive(Clone, Debug, PartialEq)]
+pub struct InstanceRecommendation {
+    /// The cloud instance
+    instance: InstanceSpec,
+    /// The number of GPUs the model is split across with tensor parallelism
+    tp_size: i32,
+    /// The fit of the model on every GPU of the tensor parallel group
+    report: FitReport,
+    /// The expected generation speed of the model on the instance
+    throughput: Throughput,
+}
+
+/// InstanceRecommendation implementation
+impl InstanceRecommendation {
+    /// Build a new `InstanceRecommendation` struct based on the provided parameters
+    pub fn new(
+        instance: InstanceSpec,
+        tp_size: i32,
+        report: FitReport,
+        throughput: Throughput,
+    ) -> InstanceRecommendation {
+        InstanceRecommendation {
+            instance,
+            tp_size,
+            report,
+            throughput,
+        }
+    }
+    /// Returns the cloud instance
+    pub fn instance(&self) -> InstanceSpec {
+        self.instance
+    }
+    /// Returns the number of GPUs the model is split across
+    pub fn tp_size(&self) -> i32 {
+        self.tp_size
+    }
+    /// Returns the fit of the model on every GPU of the tensor parallel group
+    pub fn report(&self) -> &FitReport {
+        &self.report
+    }
+    /// Returns the expected generation speed of the model on the instance
+    pub fn throughput(&self) -> Throughput {
+        self.throughput
+    }
+    /// Returns the cost of a million prompt and generated tokens on the instance
+    pub fn serving_cost(&self) -> ServingCost {
+        estimate_serving_cost(
+            &self.throughput,
+            Pricing::Instance {
+                hourly_price: self.instance.hourly_price(),
+            },
+        )
+    }
+}
+
+/// Recommend the cloud instances able to run an inference scenario with a model at
+/// `min_tokens_per_second` or more, from the cheapest per hour to the most expensive, the cheapest
+/// per token first at equal price. Nothing is recommended for the training scenarios.
+///
+/// The model is split across as many GPUs of an instance as its attention heads allow, and only
+/// the instances where it fits without offloading are kept. The throughput of the tensor parallel
+/// group is the one of a single GPU times the number of GPUs, the communication is ignored. The
+/// GPUs lacking the compute capability of the precision are estimated with the best one they
+/// support.
+#[tracing::instrument(
+    level = "debug",
+    skip_all,
+    fields(model_type = config.model_type(), scenario = %scenario)
+)]
+pub fn recommend_instances(
+    config: &ModelConfig,
+    scenario: Scenario,
+    min_tokens_per_second: f64,
+) -> Vec<InstanceRecommendation> {
+    let (batch_size, seq_len) = match scenario {
+        Scenario::Inference {
+            batch_size,
+            seq_len,
+            ..
+        } => (batch_size, seq_len),
+        _ => return Vec::new(),
+    };
+    let mut recommendations: Vec<InstanceRecommendation> = InstanceSpec::all()
+        .into_iter()
+        .filter_map(|instance| {
+            let tp_size = *tensor_parallel_sizes(config, instance.num_gpus()).last()?;
+            let (precision, warning) = supported_precision(
+                instance.gpu().name(),
+                instance.gpu().compute_capability(),
+                scenario.precision(),
+            );
+            let memory = estimate_tensor_parallel_scenario(
+                config,
+                &scenario.with_precision(precision),
+                tp_size,
+            )?;
+            let available = instance.gpu().memory() as i64;
+            let verdict = memory.verdict(available);
+            if !matches!(verdict, FitVerdict::Fits | FitVerdict::Tight) {
+                return None;
+            }
+            let device = match tp_size {
+                1 => instance.gpu().name().to_string(),
+                _ => format!("{} × {}", tp_size, instance.gpu().name()),
+            };
+            let report = FitReport::new(
+                device,
+                verdict,
+                memory.required(),
+                available,
+                memory.limiting_factor(),
+            )
+            .with_warning(warning)
+            .with_quality_loss(quality_loss(precision))
+            .with_explanations(memory.explanations(available));
+            let single =
+                estimate_throughput(config, &instance.gpu(), precision, batch_size, seq_len);
+            let throughput = Throughput::new(
+                single.prefill_tokens_per_second() * tp_size as f64,
+                single.decode_tokens_per_second() * tp_size as f64,
+            );
+            Some(InstanceRecommendation::new(
+                instance, tp_size, report, throughput,
+            ))
+        })
+        .filter(|recommendation| {
+            recommendation.throughput().decode_tokens_per_second() >= min_tokens_per_second
+        })
+        .collect();
+    recommendations.sort_by(|a, b| {
+        a.instance()
+            .hourly_price()
+            .total_cmp(&b.instance().hourly_price())
+            .then(
+                a.serving_cost()
+                    .output_per_million()
+                    .total_cmp(&b.serving_cost().output_per_million()),
+            )
+    });
+    recommendations
+}
+
+/// Enumerate the ways the dataset of a training run is given
+#[derive(Clone, Debug, PartialEq)]
+pub enum TrainingDataset {
+    /// A number of tokens, all the epochs included
+    Tokens(i64),
+    /// A dataset of the Hub, its number of tokens being estimated from its size (see
+    /// `DatasetInfo::estimated_tokens`)
+    Repo(String),
+}
+
+/// TrainingDataset implementation
+impl TrainingDataset {
+    /// Returns the number of tokens of the dataset, retrieving the size of a dataset of the Hub
+    pub async fn tokens(&self, token: Option<&str>) -> Result<i64, Box<dyn Error>> {
+        match self {
+            TrainingDataset::Tokens(tokens) => Ok(*tokens),
+            TrainingDataset::Repo(dataset_id) => retrieve_dataset_info(dataset_id, token)
+                .await?
+                .estimated_tokens(None)
+                .ok_or_else(|| format!("The size of the dataset {} is unknown", dataset_id).into()),
+        }
+    }
+}
+
+/// Build from a number of tokens with an optional `K`, `M`, `B` or `T` suffix (e.g. `10B`), or
+/// else from the ID of a dataset of the Hub
+impl FromStr for TrainingDataset {
+    type Err = String;
+
+    fn from_str(dataset: &str) -> Result<Self, Self::Err> {
+        let dataset = dataset.trim();
+        if dataset.is_empty() {
+            return Err("The dataset is empty".to_string());
+        }
+        Ok(match parse_parameter_count(dataset) {
+            Some(tokens) => TrainingDataset::Tokens(tokens),
+            None => TrainingDataset::Repo(dataset.to_string()),
+        })
+    }
+}
+
+/// Estimate a training run of a model on `num_gpus` GPUs of the given specifications (taken from
+/// the scenario for full fine-tuning, a single one for LoRA): the memory every GPU needs, the time
+/// to train on `tokens` tokens (see `estimate_training_time`) and its cost at `pricing` per GPU.
+///
+/// Returns `None` for an inference scenario. The precision falls back to the best one the GPU
+/// supports.
+#[tracing::instrument(
+    level = "debug",
+    skip_all,
+    fields(model = %model, scenario = %scenario, device = device.name(), tokens)
+)]
+pub fn estimate_training_run(
+    model: &str,
+    config: &ModelConfig,
+    scenario: &Scenario,
+    device: &GpuSpec,
+    tokens: i64,
+    pricing: Option<Pricing>,
+) -> Option<TrainingReport> {
+    let (num_gpus, seq_len, checkpointing, adapters_only) = match scenario {
+        Scenario::Inference { .. } => return None,
+        Scenario::Training(setup) => (
+            setup.num_gpus.max(1),
+            setup.seq_len,
+            setup.checkpointing,
+            false,
+        ),
+        Scenario::Lora(setup) => (1, setup.seq_len, setup.checkpointing, true),
+    };
+    let (precision, warning) = supported_precision(
+        device.name(),
+        device.compute_capability(),
+        scenario.precision(),
+    );
+    let scenario = scenario.with_precision(precision);
+    let memory = estimate_scenario(config, &scenario);
+    let available = device.memory() as i64;
+    let fit = FitReport::new(
+        device.name().to_string(),
+        memory.verdict(available),
+        memory.required(),
+        available,
+        memory.limiting_factor(),
+    )
+    .with_warning(warning)
+    .with_explanations(memory.explanations(available));
+    let time = estimate_training_time(
+        config,
+        device,
+        num_gpus,
+        tokens,
+        seq_len,
+        adapters_only,
+        checkpointing.step_time_penalty(config),
+    );
+    let report = TrainingReport::new(
+        model.to_string(),
+        scenario.to_string(),
+        num_gpus,
+        fit,
+        memory.components().to_vec(),
+        tokens,
+        time,
+        pricing.map(|pricing| pricing.hourly_cost()),
+    )
+    .with_assumption(format!(
+        "The GPUs reach {:.0}% of their peak throughput and scale linearly",
+        TRAINING_MFU * 100.0
+    ));
+    Some(report)
+}
+
+/// Estimate a training run of a model like `estimate_training_run`, the number of tokens being
+/// the one of the dataset, retrieved with the token of the client
+#[tracing::instrument(
+    level = "debug",
+    skip_all,
+    fields(model = %model, scenario = %scenario, device = device.name(), dataset = ?dataset)
+)]
+pub async fn estimate_training_on_dataset(
+    client: &HubClient,
+    model: &str,
+    config: &ModelConfig,
+    scenario: &Scenario,
+    device: &GpuSpec,
+    dataset: &TrainingDataset,
+    pricing: Option<Pricing>,
+) -> Result<TrainingReport, Box<dyn Error>> {
+    let tokens = dataset.tokens(client.get_token()).await?;
+    let report = estimate_training_run(model, config, scenario, device, tokens, pricing)
+        .ok_or("An inference scenario can't be trained")?;
+    Ok(match dataset {
+        TrainingDataset::Repo(dataset_id) => report.with_assumption(format!(
+            "The dataset {} holds about {} tokens, estimated from its size in memory",
+            dataset_id, tokens
+        )),
+        TrainingDataset::Tokens(_) => report,
+    })
+}
+
+/// Fetch a model of the Hub and estimate its training run on a dataset, see
+/// `estimate_training_on_dataset`
+pub async fn estimate_repo_training(
+    client: &HubClient,
+    repo_id: &str,
+    scenario: &Scenario,
+    device: &GpuSpec,
+    dataset: &TrainingDataset,
+    pricing: Option<Pricing>,
+) -> Result<TrainingReport, Box<dyn Error>> {
+    let (config, _) = fetch_repo(client, repo_id).await?;
+    estimate_training_on_dataset(client, repo_id, &config, scenario, device, dataset, pricing).await
+}
+
+/// A struct representing a model to compare, with the name it is shown with
+#[derive(Clone, Debug)]
+pub struct ModelRef {
+    /// The name of the model, e.g. its repository on the Hub
+    name: String,
+    /// The configuration of the model
+    config: ModelConfig,
+}
+
+/// ModelRef implementation
+impl ModelRef {
+    /// Build a new `ModelRef` struct based on the provided parameters
+    pub fn new(name: String, config: ModelConfig) -> ModelRef {
+        ModelRef { name, config }
+    }
+    /// Returns the name of the model
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Returns the configuration of the model
+    pub fn config(&self) -> &ModelConfig {
+        &self.config
+    }
+}
+
+/// Compare models side by side for the given scenario on the GPU of the running system with the
+/// most memory: their parameters, the memory each one needs in the scenario precision, in FP16 and
+/// with 4-bit weights, its verdict, and for inference the longest context and the generation
+/// speed it reaches.
+///
+/// The speed is only estimated when the model fits without offloading on a GPU of the
+/// specifications database.
+#[tracing::instrument(
+    level = "debug",
+    skip_all,
+    fields(models = models.len(), scenario = %scenario)
+)]
+pub fn compare(models: &[ModelRef], hardware: &Hardware, scenario: Scenario) -> Comparison {
+    let device = hardware
+        .gpus()
+        .into_iter()
+        .max_by_key(|device| device.get_memory_info());
+    let spec = device.and_then(GpuSpec::from_device);
+    let rows = models
+        .iter()
+        .map(|model| {
+            let config = model.config();
+            let memory = estimate_scenario(config, &scenario);
+            let verdict = device.map(|device| memory.verdict(device.get_memory_info() as i64));
+            let (max_context, throughput) = match scenario {
+                Scenario::Inference {
+                    precision,
+                    batch_size,
+                    seq_len,
+                    sliding_window,
+                    ..
+                } => {
+                    let throughput = match (verdict, spec) {
+                        (Some(FitVerdict::Fits | FitVerdict::Tight), Some(spec)) => Some(
+                            estimate_throughput(config, &spec, precision, batch_size, seq_len),
+                        ),
+                        _ => None,
+                    };
+                    (
+                        max_context_length(config, hardware, batch_size, precision, sliding_window),
+                        throughput,
+                    )
+                }
+                _ => (None, None),
+            };
+            let required_in = |precision: Precision| {
+                estimate_scenario(config, &scenario.with_precision(precision)).required()
+            };
+            ComparisonRow::new(
+                model.name().to_string(),
+                config.num_parameters(),
+                memory.required(),
+                required_in(Precision::FP16),
+                required_in(Precision::GPTQ { bits: 4 }),
+                max_context,
+                throughput,
+                verdict,
+            )
+        })
+        .collect();
+    Comparison::new(
+        scenario.to_string(),
+        device.map(|device| device.get_name()),
+        rows,
+    )
+}
+
+/// Estimate the cost of generating `batch_size` sequences of `seq_len` tokens with a model on
+/// every GPU of the running system, paying `price_per_kwh` US dollars per kWh of electricity.
+/// Returns the fit report of every device, with the cost per million prompt and generated tokens
+/// when the model fits without offloading on a GPU of the specifications database.
+///
+/// The GPU is expected to draw its full board power while serving, the rest of the machine is
+/// ignored.
+pub fn estimate_serving_costs(
+    hardware: &Hardware,
+    config: &ModelConfig,
+    precision: Precision,
+    batch_size: i32,
+    seq_len: i32,
+    price_per_kwh: f64,
+) -> Vec<(FitReport, Option<ServingCost>)> {
+    estimate_serving_costs_with_backend(
+        hardware,
+        config,
+        precision,
+        batch_size,
+        seq_len,
+        price_per_kwh,
+        &AnalyticBackend,
+    )
+}
+
+/// Estimate the cost of serving a model on every GPU of the running system like
+/// `estimate_serving_costs`, the memory and the speed being estimated by the given backend
+pub fn estimate_serving_costs_with_backend(
+    hardware: &Hardware,
+    config: &ModelConfig,
+    precision: Precision,
+    batch_size: i32,
+    seq_len: i32,
+    price_per_kwh: f64,
+    backend: &dyn EstimatorBackend,
+) -> Vec<(FitReport, Option<ServingCost>)> {
+    let scenario = Scenario::Inference {
+        precision,
+        batch_size,
+        seq_len,
+        overhead: OverheadModel::new(),
+        sliding_window: false,
+    };
+    check_fit_with_backend(hardware, config, scenario, backend)
+        .into_iter()
+        .zip(hardware.gpus())
+        .map(|(report, device)| {
+            let cost = match report.verdict() {
+                FitVerdict::Fits | FitVerdict::Tight => GpuSpec::from_device(device).map(|spec| {
+                    let compute_capability = device.get_compute_capability();
+                    let (precision, _) =
+                        supported_precision(&device.get_name(), compute_capability, precision);
+                    let throughput =
+                        backend.estimate_throughput(config, &spec, precision, batch_size, seq_len);
+                    let pricing = Pricing::Electricity {
+                        price_per_kwh,
+                        power: spec.power(),
+                    };
+                    estimate_serving_cost(&throughput, pricing)
+                }),
+                _ => None,
+            };
+            (report, cost)
+        })
+        .collect()
+}
+
+/// Check whether a model of the Hub fits every GPU of the running system for the given scenario:
+/// scan the hardware, fetch the configuration and the file sizes of the model, and build the
+/// report of every GPU. The size of the weight files of the repository is added to the
+/// assumptions.
+#[tracing::instrument(
+    level = "debug",
+    skip_all,
+    fields(repo_id = %repo_id, scenario = %scenario)
+)]
+pub async fn check_repo(
+    client: &HubClient,
+    repo_id: &str,
+    scenario: Scenario,
+) -> Result<Report, Box<dyn Error>> {
+    let hardware = scan_hardware()?;
+    let (config, siblings) = fetch_repo(client, repo_id).await?;
+    let reports = check_fit(&hardware, &config, scenario.clone());
+    let report = Report::from_fit_reports(repo_id, &scenario, reports);
+    Ok(with_weights_size(report, siblings.as_ref()))
+}
+
+/// Check a model of the Hub like `check_repo`, along with assertions on the running system, the
+/// `fits` ones against the model and the scenario (see `check_assertions`)
+#[tracing::instrument(
+    level = "debug",
+    skip_all,
+    fields(repo_id = %repo_id, scenario = %scenario, assertions = assertions.len())
+)]
+pub async fn check_repo_with_assertions(
+    client: &HubClient,
+    repo_id: &str,
+    scenario: Scenario,
+    assertions: &[Assertion],
+) -> Result<(Report, Vec<AssertionResult>), Box<dyn Error>> {
+    let hardware = scan_hardware()?;
+    let (config, siblings) = fetch_repo(client, repo_id).await?;
+    let results = check_assertions(&hardware, Some((&config, &scenario)), assertions);
+    let reports = check_fit(&hardware, &config, scenario.clone());
+    let report = Report::from_fit_reports(repo_id, &scenario, reports);
+    Ok((with_weights_size(report, siblings.as_ref()), results))
+}
+
+/// Compare models of the Hub side by side like `compare`, on the GPU of the running system with
+/// the most memory. The models are fetched at once, and one that can't be fetched or isn't
+/// supported fails the comparison.
+pub async fn compare_repos(
+    client: &HubClient,
+    repo_ids: &[String],
+    scenario: Scenario,
+) -> Result<Comparison, Box<dyn Error>> {
+    compare_repos_with_progress(client, repo_ids, scenario, &|_, _, _| {}).await
+}
+
+/// Compare models of the Hub like `compare_repos`, calling `progress` every time a model is
+/// fetched like `check_repos_with_progress`
+#[tracing::instrument(
+    level = "debug",
+    skip_all,
+    fields(repos = repo_ids.len(), scenario = %scenario)
+)]
+pub async fn compare_repos_with_progress(
+    client: &HubClient,
+    repo_ids: &[String],
+    scenario: Scenario,
+    progress: &dyn Fn(&str, usize, usize),
+) -> Result<Comparison, Box<dyn Error>> {
+    let hardware = scan_hardware()?;
+    let done = Cell::new(0);
+    let fetches = repo_ids
+        .iter()
+        .map(|repo_id| async {
+            let fetched = fetch_repo(client, repo_id).await;
+            done.set(done.get() + 1);
+            progress(repo_id, done.get(), repo_ids.len());
+            fetched
+        })
+        .collect();
+    let mut models = Vec::new();
+    for (repo_id, fetched) in repo_ids.iter().zip(join_all(fetches).await) {
+        let (config, _) = fetched?;
+        models.push(ModelRef::new(repo_id.clone(), config));
+    }
+    Ok(compare(&models, &hardware, scenario))
+}
+
+/// Fetch a model of the Hub and suggest the cloud instances able to serve it, see
+/// `suggest_instances`
+pub async fn suggest_repo_instances(
+    client: &HubClient,
+    repo_id: &str,
+    scenario: Scenario,
+    min_tokens_per_second: f64,
+) -> Result<CloudReport, Box<dyn Error>> {
+    let (config, _) = fetch_repo(client, repo_id).await?;
+    Ok(suggest_instances(
+        repo_id,
+        &config,
+        scenario,
+        min_tokens_per_second,
+    ))
+}
+
+/// Fetch a model of the Hub and check its fit on every GPU of the running system, to watch a
+/// workload running it (see `watch::watch`)
+pub async fn watch_repo(
+    client: &HubClient,
+    repo_id: &str,
+    scenario: Scenario,
+) -> Result<Watcher, Box<dyn Error>> {
+    let hardware = scan_hardware()?;
+    let (config, _) = fetch_repo(client, repo_id).await?;
+    let reports = check_fit(&hardware, &config, scenario.clone());
+    Ok(Watcher::new(repo_id, scenario, reports))
+}
+
+/// Fetch the configuration and the files of a model of the Hub
+#[tracing::instrument(level = "debug", skip(client))]
+async fn fetch_repo(
+    client: &HubClient,
+    repo_id: &str,
+) -> Result<(ModelConfig, Option<Siblings>), Box<dyn Error>> {
+    let model_info = client
+        .retrieve_model_info(repo_id, None, Some(true))
+        .await?;
+    let mut config = None;
+    client
+        .get_model_config(repo_id, None, model_info.tags.as_deref(), &mut config)
+        .await?;
+    let config = config.ok_or_else(|| format!("The model {} is not supported", repo_id))?;
+    Ok((config, model_info.get_siblings().cloned()))
+}
+
+/// Parse a list of models, one repository ID per line: the blank lines and the `#` comments are
+/// skipped, and a model listed twice is only kept once
+pub fn parse_model_list(content: &str) -> Vec<String> {
+    let mut repo_ids: Vec<String> = Vec::new();
+    for line in content.lines() {
+        let repo_id = line.split('#').next().unwrap_or_default().trim();
+        if !repo_id.is_empty() && !repo_ids.iter().any(|id| id == repo_id) {
+            repo_ids.push(repo_id.to_string());
+        }
+    }
+    repo_ids
+}
+
+/// Check a list of models of the Hub against the GPUs of the running system like `check_repo`,
+/// e.g. to audit a model catalog. The hardware is scanned once and up to `BATCH_CONCURRENCY`
+/// models are fetched at once; a model that can't be fetched or isn't supported is reported with
+/// its error instead of failing the batch.
+pub async fn check_repos(
+    client: &HubClient,
+    repo_ids: &[String],
+    scenario: Scenario,
+) -> Result<BatchReport, Box<dyn Error>> {
+    check_repos_with_progress(client, repo_ids, scenario, &|_, _, _| {}).await
+}
+
+/// Check a list of models of the Hub like `check_repos`, calling `progress` with the repository
+/// ID, the number of models fetched so far and the number of models every time a model is
+/// fetched, e.g. to drive a progress bar
+#[tracing::instrument(
+    level = "debug",
+    skip_all,
+    fields(repos = repo_ids.len(), scenario = %scenario)
+)]
+pub async fn check_repos_with_progress(
+    client: &HubClient,
+    repo_ids: &[String],
+    scenario: Scenario,
+    progress: &dyn Fn(&str, usize, usize),
+) -> Result<BatchReport, Box<dyn Error>> {
+    let hardware = scan_hardware()?;
+    let semaphore = Semaphore::new(BATCH_CONCURRENCY);
+    let done = Cell::new(0);
+    let fetches = repo_ids
+        .iter()
+        .map(|repo_id| async {
+            let _permit = semaphore.acquire().await;
+            let fetched = fetch_repo(client, repo_id).await;
+            done.set(done.get() + 1);
+            progress(repo_id, done.get(), repo_ids.len());
+            fetched
+        })
+        .collect();
+
+    let entries = repo_ids
+        .iter()
+        .zip(join_all(fetches).await)
+        .map(|(repo_id, fetched)| match fetched {
+            Ok((config, siblings)) => {
+                let reports = check_fit(&hardware, &config, scenario.clone());
+                let report = Report::from_fit_reports(repo_id, &scenario, reports);
+                BatchEntry::checked(with_weights_size(report, siblings.as_ref()))
+            }
+            Err(error) => BatchEntry::failed(repo_id, error.to_string()),
+        })
+        .collect();
+    Ok(BatchReport::new(scenario.to_string(), entries))
+}
+
+/// Search the models of the Hub matching a query and keep the ones fitting a GPU of the running
+/// system for the given scenario, comfortably or tightly, checked like `check_repos`
+pub async fn search_fitting_models(
+    client: &HubClient,
+    query: &SearchQuery,
+    scenario: Scenario,
+) -> Result<BatchReport, Box<dyn Error>> {
+    search_fitting_models_with_progress(client, query, scenario, &|_, _, _| {}).await
+}
+
+/// Search the models of the Hub fitting the running system like `search_fitting_models`, calling
+/// `progress` every time a model found is fetched like `check_repos_with_progress`
+#[tracing::instrument(
+    level = "debug",
+    skip_all,
+    fields(search = ?query.search, task = ?query.task, scenario = %scenario)
+)]
+pub async fn search_fitting_models_with_progress(
+    client: &HubClient,
+    query: &SearchQuery,
+    scenario: Scenario,
+    progress: &dyn Fn(&str, usize, usize),
+) -> Result<BatchReport, Box<dyn Error>> {
+    let repo_ids: Vec<String> = client
+        .search_models(query)
+        .await?
+        .into_iter()
+        .map(|result| result.repo_id)
+        .collect();
+    let batch = check_repos_with_progress(client, &repo_ids, scenario, progress).await?;
+    Ok(keep_fitting(batch))
+}
+
+/// Returns the batch report without the models that failed or don't fit any GPU
+fn keep_fitting(batch: BatchReport) -> BatchReport {
+    let entries = batch
+        .entries()
+        .iter()
+        .filter(|entry| {
+            entry
+                .best_device()
+                .is_some_and(|fit| matches!(fit.verdict(), FitVerdict::Fits | FitVerdict::Tight))
+        })
+        .cloned()
+        .collect();
+    BatchReport::new(batch.scenario().to_string(), entries)
+}
+
+/// Run futures concurrently on the current task and returns their outputs in order. The Hub
+/// client futures aren't `Send`, so they can't be spawned on the runtime.
+async fn join_all<F: Future>(futures: Vec<F>) -> Vec<F::Output> {
+    let mut futures: Vec<Pin<Box<F>>> = futures.into_iter().map(Box::pin).collect();
+    let mut outputs: Vec<Option<F::Output>> = futures.iter().map(|_| None).collect();
+    poll_fn(|cx| {
+        let mut pending = false;
+        for (future, output) in futures.iter_mut().zip(outputs.iter_mut()) {
+            if output.is_some() {
+                continue;
+            }
+            match future.as_mut().poll(cx) {
+                Poll::Ready(value) => *output = Some(value),
+                Poll::Pending => pending = true,
+            }
+        }
+        match pending {
+            true => Poll::Pending,
+            false => Poll::Ready(()),
+        }
+    })
+    .await;
+    outputs.into_iter().flatten().collect()
+}
+
+/// Check whether a model saved in a local folder fits every GPU of the running system for the
+/// given scenario, like `check_repo` but without any network access: the configuration is read
+/// from the `config.json` file of the folder and the weight files are summed on disk.
+#[tracing::instrument(
+    level = "debug",
+    skip_all,
+    fields(model_dir = %model_dir.display(), scenario = %scenario)
+)]
+pub fn check_local_model(model_dir: &Path, scenario: Scenario) -> Result<Report, Box<dyn Error>> {
+    let hardware = scan_hardware()?;
+    let config = ModelConfig::from_file(model_dir.join("config.json"))?;
+    let siblings = list_local_files(model_dir)?;
+
+    let reports = check_fit(&hardware, &config, scenario.clone());
+    let report = Report::from_fit_reports(&model_dir.to_string_lossy(), &scenario, reports);
+    Ok(with_weights_size(report, Some(&siblings)))
+}
+
+/// Check whether a local GGUF file of llama.cpp runs on the running system, without any network
+/// access: the header of the file gives the model configuration and its quantization. The report
+/// of every GPU gets the expected speed when the model fits, and the CPU estimate the RAM and the
+/// speed of llama.cpp on the CPU, `None` when the RAM is unknown, with the memory bandwidth of
+/// `bench` when it was measured (see `check_fit_cpu_measured`).
+#[tracing::instrument(
+    level = "debug",
+    skip_all,
+    fields(path = %path.display(), batch_size, seq_len)
+)]
+pub fn check_gguf_file(
+    path: &Path,
+    batch_size: i32,
+    seq_len: i32,
+    bench: Option<&BenchResult>,
+) -> Result<(Report, Option<CpuEstimate>), Box<dyn Error>> {
+    let hardware = scan_hardware()?;
+    let gguf = GgufFile::from_file(path)?;
+    let config = gguf.model_config()?;
+    let quant_type = gguf
+        .quant_type()
+        .ok_or_else(|| ModelError::UnknownQuantization(path.to_string_lossy().to_string()))?;
+    let precision = Precision::GGUF(quant_type);
+    let scenario = Scenario::Inference {
+        precision,
+        batch_size,
+        seq_len,
+        overhead: OverheadModel::new(),
+        sliding_window: false,
+    };
+
+    let devices = check_fit(&hardware, &config, scenario.clone())
+        .into_iter()
+        .zip(hardware.gpus())
+        .map(|(report, device)| {
+            let throughput = match report.verdict() {
+                FitVerdict::Fits | FitVerdict::Tight => GpuSpec::from_device(device).map(|spec| {
+                    let compute_capability = device.get_compute_capability();
+                    let (precision, _) =
+                        supported_precision(&device.get_name(), compute_capability, precision);
+                    estimate_throughput(&config, &spec, precision, batch_size, seq_len)
+                }),
+                _ => None,
+            };
+            DeviceSection::new(report, throughput, None)
+        })
+        .collect();
+    let report =
+        Report::from_devices(&path.to_string_lossy(), &scenario, devices).with_assumption(format!(
+            "The tensors of the GGUF file take {:.2} GiB",
+            gguf.weights_size() as f64 / 1024.0 / 1024.0 / 1024.0
+        ));
+    let cpu = match bench {
+        Some(bench) => {
+            check_fit_cpu_measured(&hardware, &config, precision, batch_size, seq_len, bench)
+        }
+        None => check_fit_cpu(&hardware, &config, precision, batch_size, seq_len),
+    };
+    Ok((report, cpu))
+}
+
+/// Returns the report with the size of the weight files of the model in its assumptions, to be
+/// compared with the estimated weights
+fn with_weights_size(report: Report, siblings: Option<&Siblings>) -> Report {
+    match siblings.and_then(|siblings| siblings.get_weights_size("")) {
+        Some(size) => report.with_assumption(format!(
+            "The weight files of the model take {:.2} GiB on disk",
+            size as f64 / 1024.0 / 1024.0 / 1024.0
+        )),
+        None => report,
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate::test_utils::{create_llama_config, create_llama_config_with};
+    use crate::estimate::{LoraTarget, Optimizer, Sharding, TrainingSetup};
+    use crate::hardware::NvidiaDevice;
+    use crate::models::{GenericModelConfig, LlamaParams, QuantType};
+    use serde_json::json;
+
+    fn create_inference_scenario(precision: Precision, batch_size: i32, seq_len: i32) -> Scenario {
+        Scenario::Inference {
+            precision,
+            batch_size,
+            seq_len,
+            overhead: OverheadModel::new(),
+            sliding_window: false,
+        }
+    }
+
+    fn create_hardware(memories: &[i64]) -> Hardware {
+        let nvidia_gpus: Vec<NvidiaDevice> = memories
+            .iter()
+            .enumerate()
+            .map(|(index, memory)| {
+                NvidiaDevice::new(
+                    "Ampere".to_string(),
+                    "GeForce".to_string(),
+                    (8, 6),
+                    *memory as u64,
+                    "GeForce RTX 3090".to_string(),
+                    10496,
+                    format!("GPU-{}", index),
+                    31.504e9,
+                    936.096e9,
+                )
+            })
+            .collect();
+        Hardware {
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            cpu_cores: 8,
+            cpu_threads: 16,
+            memory: 64_000_000_000,
+            gpu_count: nvidia_gpus.len() as u32,
+            nvidia_gpus,
+            amd_gpus: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_check_fit_precision_fallback() {
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let mut hardware = create_hardware(&[24_000_000_000]);
+        hardware.nvidia_gpus.push(NvidiaDevice::new(
+            "Turing".to_string(),
+            "Tesla".to_string(),
+            (7, 5),
+            16_000_000_000,
+            "Tesla T4".to_string(),
+            2560,
+            "GPU-T4".to_string(),
+            15.752e9,
+            320e9,
+        ));
+        let scenario = |precision: Precision| Scenario::Inference {
+            precision,
+            batch_size: 1,
+            seq_len: 4096,
+            overhead: OverheadModel::new(),
+            sliding_window: false,
+        };
+
+        // The T4 runs BF16 models in FP16, which takes the same memory
+        let reports = check_fit(&hardware, &llama_config, scenario(Precision::BF16));
+        assert_eq!(reports[0].warning(), None);
+        assert!(reports[1].warning().unwrap().contains("bf16"));
+        assert_eq!(reports[1].required(), reports[0].required());
+
+        // No GPU runs FP8, the RTX 3090 falls back to BF16 and the T4 to FP16
+        let reports = check_fit(&hardware, &llama_config, scenario(Precision::FP8));
+        let bf16 = check_fit(&hardware, &llama_config, scenario(Precision::BF16));
+        assert!(reports.iter().all(|report| report.warning().is_some()));
+        assert_eq!(reports[0].required(), bf16[0].required());
+        let report =
+            crate::report::Report::from_fit_reports("llama", &scenario(Precision::FP8), reports);
+        assert!(report
+            .warnings()
+            .iter()
+            .any(|warning| warning.contains("estimated in bf16")));
+    }
+
+    #[test]
+    fn test_check_fit_amd_gpus() {
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let mut hardware = create_hardware(&[24_000_000_000]);
+        hardware.amd_gpus.push(crate::hardware::AmdDevice::new(
+            "Instinct MI210".to_string(),
+            64_000_000_000,
+            31.504e9,
+        ));
+        hardware.gpu_count = 2;
+
+        // The AMD GPU is checked in the precision asked for, without CUDA compute capability
+        let scenario = create_inference_scenario(Precision::BF16, 1, 4096);
+        let reports = check_fit(&hardware, &llama_config, scenario);
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[1].device(), "AMD Instinct MI210");
+        assert_eq!(reports[1].warning(), None);
+        assert_eq!(reports[1].required(), reports[0].required());
+        assert_eq!(reports[1].verdict(), FitVerdict::Fits);
+
+        // Its memory counts, FP32 weights offloaded from the RTX 3090 fit it, but it runs none of
+        // the CUDA kernels
+        let estimate = check_offload(&hardware, &llama_config, Precision::FP32, 1, 4096).unwrap();
+        assert!(!estimate.is_offloaded());
+        assert_eq!(
+            check_kernels(&hardware, Precision::GPTQ { bits: 4 }).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_check_fit_calibrated() {
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let hardware = create_hardware(&[24_000_000_000]);
+        let scenario = Scenario::Inference {
+            precision: Precision::BF16,
+            batch_size: 1,
+            seq_len: 4096,
+            overhead: OverheadModel::new(),
+            sliding_window: false,
+        };
+        let path = std::env::temp_dir().join(format!(
+            "aiha-calibration-advisor-{}.json",
+            std::process::id()
+        ));
+        let mut calibrations = CalibrationStore::load(&path).unwrap();
+        let estimate = check_fit(&hardware, &llama_config, scenario.clone());
+        let calibrated = |calibrations: &CalibrationStore| {
+            check_fit_calibrated(
+                &hardware,
+                &llama_config,
+                "meta-llama/Meta-Llama-3-8B",
+                scenario.clone(),
+                calibrations,
+            )
+        };
+        // Without measurement the estimate is unchanged
+        assert_eq!(calibrated(&calibrations), estimate);
+        assert_eq!(estimate[0].verdict(), FitVerdict::Fits);
+
+        // The RTX 3090 measured 60% more memory than estimated
+        calibrations.record_memory(
+            "meta-llama/Meta-Llama-3-8B",
+            "NVIDIA GeForce RTX 3090",
+            &scenario,
+            estimate[0].required(),
+            (estimate[0].required() as f64 * 1.6) as i64,
+        );
+        let reports = calibrated(&calibrations);
+        assert!((reports[0].required() as f64 / estimate[0].required() as f64 - 1.6).abs() < 1e-6);
+        assert_ne!(reports[0].verdict(), FitVerdict::Fits);
+        assert!(reports[0]
+            .explanations()
+            .iter()
+            .any(|explanation| explanation.subject() == "calibration"));
+    }
+
+    #[test]
+    fn test_check_local_model() {
+        let model_dir =
+            std::env::temp_dir().join(format!("aiha-local-model-{}", std::process::id()));
+        std::fs::create_dir_all(&model_dir).unwrap();
+        let config = serde_json::json!({
+            "model_type": "bert",
+            "hidden_size": 768,
+            "intermediate_size": 3072,
+            "max_position_embeddings": 512,
+            "num_attention_heads": 12,
+            "num_hidden_layers": 12,
+            "vocab_size": 30522
+        });
+        std::fs::write(model_dir.join("config.json"), config.to_string()).unwrap();
+        std::fs::write(model_dir.join("model.safetensors"), vec![0; 2048]).unwrap();
+        let scenario = Scenario::Inference {
+            precision: Precision::FP32,
+            batch_size: 1,
+            seq_len: 512,
+            overhead: OverheadModel::new(),
+            sliding_window: false,
+        };
+
+        let report = check_local_model(&model_dir, scenario.clone()).unwrap();
+        assert_eq!(report.model(), model_dir.to_string_lossy());
+        assert_eq!(
+            report.assumptions().last().unwrap(),
+            "The weight files of the model take 0.00 GiB on disk"
+        );
+        std::fs::remove_dir_all(&model_dir).unwrap();
+        assert!(check_local_model(&model_dir, scenario).is_err());
+    }
+
+    #[test]
+    fn test_parse_model_list() {
+        let content = "# Production models\n\
+                       meta-llama/Meta-Llama-3-8B\n\
+                       \n\
+                       mistralai/Mistral-7B-v0.1  # chat\n\
+                       meta-llama/Meta-Llama-3-8B\n";
+        assert_eq!(
+            parse_model_list(content),
+            ["meta-llama/Meta-Llama-3-8B", "mistralai/Mistral-7B-v0.1"]
+        );
+        assert!(parse_model_list("\n# nothing\n").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_check_repos_progress() {
+        // Nothing listens on the discard port, every fetch fails right away
+        let client = HubClient::new(None).with_endpoints(vec!["http://127.0.0.1:9".to_string()]);
+        let repo_ids = vec!["user/first".to_string(), "user/second".to_string()];
+        let calls = std::cell::RefCell::new(Vec::new());
+        let progress = |repo_id: &str, done: usize, total: usize| {
+            calls.borrow_mut().push((repo_id.to_string(), done, total));
+        };
+        let scenario = Scenario::Inference {
+            precision: Precision::BF16,
+            batch_size: 1,
+            seq_len: 4096,
+            overhead: OverheadModel::new(),
+            sliding_window: false,
+        };
+        let batch = check_repos_with_progress(&client, &repo_ids, scenario, &progress)
+            .await
+            .unwrap();
+        assert_eq!(batch.failed(), 2);
+        let calls = calls.into_inner();
+        assert_eq!(calls.len(), 2);
+        assert_eq!((calls[1].1, calls[1].2), (2, 2));
+    }
+
+    #[tokio::test]
+    async fn test_compare_repos_progress() {
+        let client = HubClient::new(None).with_endpoints(vec!["http://127.0.0.1:9".to_string()]);
+        let repo_ids = vec!["user/first".to_string(), "user/second".to_string()];
+        let calls = std::cell::RefCell::new(Vec::new());
+        let progress = |repo_id: &str, done: usize, total: usize| {
+            calls.borrow_mut().push((repo_id.to_string(), done, total));
+        };
+        let scenario = Scenario::Inference {
+            precision: Precision::BF16,
+            batch_size: 1,
+            seq_len: 4096,
+            overhead: OverheadModel::new(),
+            sliding_window: false,
+        };
+        // A model that can't be fetched fails the comparison, once every fetch is done
+        assert!(
+            compare_repos_with_progress(&client, &repo_ids, scenario, &progress)
+                .await
+                .is_err()
+        );
+        let calls = calls.into_inner();
+        assert_eq!(calls.len(), 2);
+        assert_eq!((calls[1].1, calls[1].2), (2, 2));
+    }
+
+    #[test]
+    fn test_keep_fitting() {
+        let scenario = Scenario::Inference {
+            precision: Precision::BF16,
+            batch_size: 1,
+            seq_len: 4096,
+            overhead: OverheadModel::new(),
+            sliding_window: false,
+        };
+        let report = |model: &str, verdict: FitVerdict| {
+            let fit = FitReport::new(
+                "NVIDIA GeForce RTX 3090".to_string(),
+                verdict,
+                20_000_000_000,
+                24_000_000_000,
+                MemoryComponent::Weights,
+            );
+            BatchEntry::checked(Report::from_fit_reports(model, &scenario, vec![fit]))
+        };
+        let batch = BatchReport::new(
+            scenario.to_string(),
+            vec![
+                report("user/fits", FitVerdict::Fits),
+                report("user/offload", FitVerdict::RequiresOffload),
+                BatchEntry::failed("user/missing", "Repository not found".to_string()),
+                report("user/tight", FitVerdict::Tight),
+            ],
+        );
+        let models: Vec<String> = keep_fitting(batch)
+            .entries()
+            .iter()
+            .map(|entry| entry.model().to_string())
+            .collect();
+        assert_eq!(models, ["user/fits", "user/tight"]);
+    }
+
+    #[tokio::test]
+    async fn test_join_all_keeps_order() {
+        let delays = [30, 10, 20];
+        let futures = delays
+            .iter()
+            .map(|delay| async move {
+                tokio::time::sleep(std::time::Duration::from_millis(*delay)).await;
+                *delay
+            })
+            .collect();
+        let start = std::time::Instant::now();
+        assert_eq!(join_all(futures).await, delays);
+        // The futures ran concurrently
+        assert!(start.elapsed() < std::time::Duration::from_millis(60));
+    }
+
+    #[test]
+    fn test_check_gguf_file() {
+        let path = std::env::temp_dir().join(format!("aiha-{}.Q4_K_M.gguf", std::process::id()));
+        std::fs::write(&path, crate::hub::create_gguf_bytes()).unwrap();
+
+        let (report, cpu) = check_gguf_file(&path, 1, 4096, None).unwrap();
+        assert_eq!(report.model(), path.to_string_lossy());
+        assert!(report.scenario().contains("Q4_K"));
+        assert_eq!(
+            report.assumptions().last().unwrap(),
+            "The tensors of the GGUF file take 0.68 GiB"
+        );
+        if let Some(cpu) = cpu {
+            assert!(cpu.throughput().decode_tokens_per_second() > 0.0);
+        }
+        // A slow RAM was measured, generating is slower
+        let bench = BenchResult::new(1e9, 8, 0);
+        let (_, measured) = check_gguf_file(&path, 1, 4096, Some(&bench)).unwrap();
+        if let (Some(cpu), Some(measured)) = (cpu, measured) {
+            assert!(
+                measured.throughput().decode_tokens_per_second()
+                    < cpu.throughput().decode_tokens_per_second()
+            );
+        }
+
+        std::fs::write(&path, "{}").unwrap();
+        assert!(check_gguf_file(&path, 1, 4096, None).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_check_attention() {
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let mut hardware = create_hardware(&[24_000_000_000]);
+        hardware.nvidia_gpus.push(NvidiaDevice::new(
+            "Turing".to_string(),
+            "Tesla".to_string(),
+            (7, 5),
+            16_000_000_000,
+            "Tesla T4".to_string(),
+            2560,
+            "GPU-T4".to_string(),
+            15.752e9,
+            320e9,
+        ));
+        let reports = check_attention(&hardware, &llama_config, Precision::FP16, 1, 4096);
+        assert_eq!(reports[0].implementation(), AttentionImpl::FlashAttention2);
+        assert_eq!(reports[0].estimates().len(), 1);
+        assert!(reports[0].estimates()[0].prefill_seconds().is_some());
+
+        // The T4 can't run FlashAttention-2, both costs are reported
+        assert_eq!(reports[1].device(), "NVIDIA Tesla T4");
+        assert_eq!(reports[1].implementation(), AttentionImpl::Eager);
+        let estimates = reports[1].estimates();
+        assert_eq!(estimates.len(), 2);
+        assert_eq!(
+            estimates[0].implementation(),
+            AttentionImpl::FlashAttention2
+        );
+        assert!(estimates[1].memory() > estimates[0].memory());
+    }
+
+    #[test]
+    fn test_fit_report_explanations() {
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let scenario = Scenario::Inference {
+            precision: Precision::BF16,
+            batch_size: 2,
+            seq_len: 4096,
+            overhead: OverheadModel::new(),
+            sliding_window: false,
+        };
+        let report = &check_fit(&create_hardware(&[24_000_000_000]), &llama_config, scenario)[0];
+        let explanations = report.explanations();
+        let subjects: Vec<&str> = explanations
+            .iter()
+            .map(|explanation| explanation.subject())
+            .collect();
+        assert_eq!(
+            subjects,
+            vec!["weights", "KV cache", "framework overhead", "verdict"]
+        );
+
+        // The inputs of the formulas give back the components
+        let weights = explanations[0].inputs();
+        let weights_bytes = quantized_weights_bytes(&llama_config, Precision::BF16) as f64;
+        assert_eq!(
+            weights["parameters"] * weights["bytes_per_param"],
+            weights_bytes
+        );
+        let kv_cache = explanations[1].inputs();
+        assert_eq!(kv_cache["batch_size"], 2.0);
+        assert_eq!(kv_cache["cached_tokens"], 4096.0);
+        let verdict = explanations[3].inputs();
+        assert_eq!(verdict["required"], report.required() as f64);
+        assert_eq!(verdict["available"], 24_000_000_000.0);
+        assert!(explanations[2].source().contains("OverheadModel"));
+
+        // The training reports explain every component too
+        let setup = TrainingSetup::new(1, 2048, Precision::BF16, Optimizer::AdamW);
+        let report = &check_fit(
+            &create_hardware(&[80_000_000_000]),
+            &llama_config,
+            Scenario::Training(setup),
+        )[0];
+        assert_eq!(report.explanations().len(), 7);
+        let json = serde_json::to_value(report).unwrap();
+        assert_eq!(json["explanations"][2]["subject"], "optimizer states");
+    }
+
+    #[test]
+    fn test_check_fit_inference() {
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let scenario = Scenario::Inference {
+            precision: Precision::BF16,
+            batch_size: 1,
+            seq_len: 4096,
+            overhead: OverheadModel::new(),
+            sliding_window: false,
+        };
+        let required =
+            check_fit(&create_hardware(&[1]), &llama_config, scenario.clone())[0].required();
+        assert!(required > 2 * llama_config.num_parameters());
+
+        let hardware = create_hardware(&[
+            2 * required,
+            required + required / 20,
+            required / 2,
+            1_000_000_000,
+        ]);
+        let reports = check_fit(&hardware, &llama_config, scenario);
+        let verdicts: Vec<FitVerdict> = reports.iter().map(|report| report.verdict()).collect();
+        assert_eq!(
+            verdicts,
+            vec![
+                FitVerdict::Fits,
+                FitVerdict::Tight,
+                FitVerdict::RequiresOffload,
+                FitVerdict::DoesNotFit
+            ]
+        );
+        assert_eq!(reports[0].device(), "NVIDIA GeForce RTX 3090");
+        assert_eq!(reports[0].available(), 2 * required);
+        assert_eq!(reports[0].headroom(), required);
+        assert_eq!(reports[2].limiting_factor(), MemoryComponent::Weights);
+        assert_eq!(reports[3].verdict().to_string(), "does not fit");
+    }
+
+    #[test]
+    fn test_check_fit_overhead_model() {
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let scenario = |overhead: OverheadModel| Scenario::Inference {
+            precision: Precision::BF16,
+            batch_size: 1,
+            seq_len: 4096,
+            overhead,
+            sliding_window: false,
+        };
+        let hardware = create_hardware(&[25_769_803_776]);
+        let default = check_fit(&hardware, &llama_config, scenario(OverheadModel::new()));
+        assert_eq!(default[0].verdict(), FitVerdict::Fits);
+
+        // A larger safety margin turns the same fit into a tight one
+        let cautious = OverheadModel {
+            safety_margin: 0.5,
+            ..OverheadModel::new()
+        };
+        let reports = check_fit(&hardware, &llama_config, scenario(cautious));
+        assert_eq!(reports[0].required(), default[0].required());
+        assert_eq!(reports[0].verdict(), FitVerdict::Tight);
+
+        // A heavier runtime needs more memory
+        let heavy = OverheadModel {
+            cuda_context: 2 * 1024 * 1024 * 1024,
+            ..OverheadModel::new()
+        };
+        let reports = check_fit(&hardware, &llama_config, scenario(heavy));
+        assert_eq!(
+            reports[0].required() - default[0].required(),
+            2 * 1024 * 1024 * 1024 - 512 * 1024 * 1024
+        );
+    }
+
+    #[test]
+    fn test_check_fit_training() {
+        // The optimizer states take the most memory of a mixed precision AdamW training
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let setup = TrainingSetup::new(1, 512, Precision::BF16, Optimizer::AdamW);
+        let reports = check_fit(
+            &create_hardware(&[80_000_000_000, 24_000_000_000]),
+            &llama_config,
+            Scenario::Training(setup),
+        );
+        // 16 bytes per parameter need more than a 80 GB card, the activations fit a 24 GB one
+        assert!(reports[0].required() > 80_000_000_000);
+        assert_eq!(reports[0].verdict(), FitVerdict::RequiresOffload);
+        assert_eq!(reports[1].verdict(), FitVerdict::RequiresOffload);
+        assert_eq!(
+            reports[1].limiting_factor(),
+            MemoryComponent::OptimizerStates
+        );
+        assert_eq!(reports[1].limiting_factor().to_string(), "optimizer states");
+    }
+
+    #[test]
+    fn test_check_fit_tensor_parallel() {
+        // Llama 3 8B in FP16 needs a 24 GB card on its own, but fits across two 12 GB cards
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let single = check_fit(
+            &create_hardware(&[12_884_901_888]),
+            &llama_config,
+            Scenario::Inference {
+                precision: Precision::FP16,
+                batch_size: 1,
+                seq_len: 4096,
+                overhead: OverheadModel::new(),
+                sliding_window: false,
+            },
+        );
+        assert_eq!(single[0].verdict(), FitVerdict::RequiresOffload);
+
+        let hardware = create_hardware(&[12_884_901_888, 12_884_901_888, 8_589_934_592]);
+        let report = check_fit_tensor_parallel(
+            &hardware,
+            &llama_config,
+            create_inference_scenario(Precision::FP16, 1, 4096),
+        )
+        .unwrap();
+        assert_eq!(report.device(), "2 × NVIDIA GeForce RTX 3090");
+        assert_eq!(report.available(), 12_884_901_888);
+        assert_eq!(report.verdict(), FitVerdict::Fits);
+        assert!(report.required() < single[0].required());
+        assert_eq!(report.limiting_factor(), MemoryComponent::Weights);
+
+        // The overhead model and the safety margin of the scenario are the ones applied
+        let scenario = |overhead: OverheadModel| Scenario::Inference {
+            precision: Precision::FP16,
+            batch_size: 1,
+            seq_len: 4096,
+            overhead,
+            sliding_window: false,
+        };
+        let heavy = OverheadModel {
+            cuda_context: 8 * 1024 * 1024 * 1024,
+            ..OverheadModel::new()
+        };
+        let report = check_fit_tensor_parallel(&hardware, &llama_config, scenario(heavy)).unwrap();
+        assert_eq!(report.verdict(), FitVerdict::RequiresOffload);
+        let cautious = OverheadModel {
+            safety_margin: 0.9,
+            ..OverheadModel::new()
+        };
+        let report =
+            check_fit_tensor_parallel(&hardware, &llama_config, scenario(cautious)).unwrap();
+        assert_eq!(report.verdict(), FitVerdict::Tight);
+    }
+    #[test]
+    fn test_plan_pipeline_parallel() {
+        // Mismatched 24 GB and 12 GB cards
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let hardware = create_hardware(&[24_000_000_000, 12_000_000_000]);
+        let plan =
+            plan_pipeline_parallel(&hardware, &llama_config, Precision::FP16, 1, 4096, 1).unwrap();
+        let stages = plan.stages();
+        assert_eq!(stages.len(), 2);
+        assert!(stages[0].num_layers() > stages[1].num_layers());
+        assert_eq!(stages[0].available(), 24_000_000_000);
+        assert_eq!(plan.bubble_overhead(), 0.5);
+        assert!(plan_pipeline_parallel(
+            &create_hardware(&[]),
+            &llama_config,
+            Precision::FP16,
+            1,
+            4096,
+            1
+        )
+        .is_none());
+    }
+    #[test]
+    fn test_check_offload() {
+        // The 24 GB card is used, the rest of the FP32 weights goes to the 64 GB of RAM
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let hardware = create_hardware(&[12_000_000_000, 24_000_000_000]);
+        let estimate = check_offload(&hardware, &llama_config, Precision::FP32, 1, 4096).unwrap();
+        assert!(estimate.is_offloaded());
+        assert!(estimate.vram() <= 24_000_000_000);
+        assert!(estimate.vram() > 12_000_000_000);
+        assert_eq!(estimate.disk(), 0);
+        assert!(estimate.slowdown().unwrap() > 1.0);
+        assert!(check_offload(
+            &create_hardware(&[]),
+            &llama_config,
+            Precision::FP32,
+            1,
+            4096
+        )
+        .is_none());
+    }
+    #[test]
+    fn test_max_serving_concurrency() {
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let mut setup = ServingSetup::new(8192);
+        let hardware = create_hardware(&[12_000_000_000, 24_000_000_000]);
+        let estimate =
+            max_serving_concurrency(&hardware, &llama_config, Precision::BF16, &setup).unwrap();
+        assert_eq!(
+            Some(estimate),
+            estimate_serving(&llama_config, Precision::BF16, 24_000_000_000, &setup)
+        );
+        assert!(estimate.max_concurrent_sequences() >= 1);
+
+        // The 12 GB card bounds the blocks of both GPUs
+        setup.tp_size = 2;
+        let split =
+            max_serving_concurrency(&hardware, &llama_config, Precision::BF16, &setup).unwrap();
+        assert_eq!(
+            Some(split),
+            estimate_serving(&llama_config, Precision::BF16, 12_000_000_000, &setup)
+        );
+        setup.tp_size = 4;
+        assert!(
+            max_serving_concurrency(&hardware, &llama_config, Precision::BF16, &setup).is_none()
+        );
+    }
+
+    #[test]
+    fn test_max_context_length() {
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let weights = estimate_inference_memory(&llama_config, Precision::BF16).total();
+        let bytes_per_token = kv_cache_bytes_per_token(&llama_config, Precision::BF16) as i64;
+
+        // Room for the KV cache of 2000 tokens, or of 1000 tokens for 2 sequences
+        let hardware = create_hardware(&[weights + 2000 * bytes_per_token]);
+        assert_eq!(
+            max_context_length(&llama_config, &hardware, 1, Precision::BF16, false),
+            Some(2000)
+        );
+        assert_eq!(
+            max_context_length(&llama_config, &hardware, 2, Precision::BF16, false),
+            Some(1000)
+        );
+
+        // Capped to the 8192 tokens Llama 3 supports
+        let hardware = create_hardware(&[80_000_000_000]);
+        assert_eq!(
+            max_context_length(&llama_config, &hardware, 1, Precision::BF16, false),
+            Some(8192)
+        );
+        let hardware = create_hardware(&[weights - 1]);
+        assert_eq!(
+            max_context_length(&llama_config, &hardware, 1, Precision::BF16, false),
+            None
+        );
+        assert_eq!(
+            max_context_length(
+                &llama_config,
+                &create_hardware(&[]),
+                1,
+                Precision::BF16,
+                false
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_max_context_length_sliding_window() {
+        // Gemma 2 9B, half of the layers only attend to the last 4096 tokens
+        let gemma_config = ModelConfig::Generic(
+            GenericModelConfig::from_json(json!({
+                "model_type": "gemma2",
+                "hidden_size": 3584,
+                "intermediate_size": 14336,
+                "max_position_embeddings": 8192,
+                "num_attention_heads": 16,
+                "num_hidden_layers": 42,
+                "num_key_value_heads": 8,
+                "head_dim": 256,
+                "sliding_window": 4096,
+                "vocab_size": 256000
+            }))
+            .unwrap(),
+        );
+        let weights = estimate_inference_memory(&gemma_config, Precision::BF16).total();
+        let bytes_per_layer_token = 2 * 8 * 256 * 2;
+
+        // The whole context is cached unless the framework evicts the tokens out of the window
+        let hardware = create_hardware(&[weights + 6000 * 42 * bytes_per_layer_token]);
+        assert_eq!(
+            max_context_length(&gemma_config, &hardware, 1, Precision::BF16, false),
+            Some(6000)
+        );
+        // Past the window only the 21 global layers keep growing
+        assert_eq!(
+            max_context_length(&gemma_config, &hardware, 1, Precision::BF16, true),
+            Some((6000 * 42 - 21 * 4096) / 21)
+        );
+        // Within the window both are the same
+        let hardware = create_hardware(&[weights + 3000 * 42 * bytes_per_layer_token]);
+        assert_eq!(
+            max_context_length(&gemma_config, &hardware, 1, Precision::BF16, true),
+            Some(3000)
+        );
+    }
+    #[test]
+    fn test_recommend_instances() {
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let recommendations = recommend_instances(
+            &llama_config,
+            create_inference_scenario(Precision::FP16, 1, 4096),
+            0.0,
+        );
+        assert!(!recommendations.is_empty());
+        for recommendation in &recommendations {
+            assert!(recommendation.report().required() <= recommendation.report().available());
+        }
+        for pair in recommendations.windows(2) {
+            assert!(pair[0].instance().hourly_price() <= pair[1].instance().hourly_price());
+        }
+        let cheapest = &recommendations[0];
+        assert_eq!(cheapest.tp_size(), 1);
+        assert_eq!(
+            cheapest.serving_cost().output_per_million(),
+            cheapest.instance().hourly_price()
+                / (cheapest.throughput().decode_tokens_per_second() * 3600.0)
+                * 1e6
+        );
+        assert!(
+            cheapest.serving_cost().input_per_million()
+                < cheapest.serving_cost().output_per_million()
+        );
+
+        // The L4 generates less than 20 tokens/s, too slow for a 50 tokens/s requirement
+        let fast = recommend_instances(
+            &llama_config,
+            create_inference_scenario(Precision::FP16, 1, 4096),
+            50.0,
+        );
+        assert!(fast.len() < recommendations.len());
+        assert!(fast
+            .iter()
+            .all(|recommendation| recommendation.instance().gpu().name() != "L4"));
+    }
+
+    #[test]
+    fn test_suggest_instances() {
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let report = suggest_instances(
+            "meta-llama/Meta-Llama-3-8B",
+            &llama_config,
+            create_inference_scenario(Precision::FP16, 1, 4096),
+            0.0,
+        );
+        let recommendations = recommend_instances(
+            &llama_config,
+            create_inference_scenario(Precision::FP16, 1, 4096),
+            0.0,
+        );
+        assert_eq!(report.rows().len(), recommendations.len());
+        let cheapest = &report.rows()[0];
+        assert_eq!(cheapest.instance(), recommendations[0].instance().name());
+        assert_eq!(cheapest.headroom(), recommendations[0].report().headroom());
+        assert!(report
+            .to_markdown()
+            .starts_with("# Cloud instances for meta-llama/Meta-Llama-3-8B\n"));
+
+        // Nothing generates a million tokens/s
+        let report = suggest_instances(
+            "llama",
+            &llama_config,
+            create_inference_scenario(Precision::FP16, 1, 4096),
+            1e6,
+        );
+        assert!(report
+            .to_markdown()
+            .ends_with("No instance serves the model for this workload.\n"));
+    }
+
+    #[test]
+    fn test_training_dataset_from_str() {
+        assert_eq!(
+            TrainingDataset::from_str("10B"),
+            Ok(TrainingDataset::Tokens(10_000_000_000))
+        );
+        assert_eq!(
+            TrainingDataset::from_str("stanfordnlp/imdb"),
+            Ok(TrainingDataset::Repo("stanfordnlp/imdb".to_string()))
+        );
+        assert!(TrainingDataset::from_str(" ").is_err());
+    }
+
+    #[test]
+    fn test_estimate_training_run() {
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let a100 = GpuSpec::from_name("NVIDIA A100-SXM4-80GB").unwrap();
+        let setup = TrainingSetup {
+            sharding: Sharding::Zero3,
+            num_gpus: 8,
+            ..TrainingSetup::new(1, 2048, Precision::BF16, Optimizer::AdamW)
+        };
+        let scenario = Scenario::Training(setup);
+        let pricing = Pricing::Instance { hourly_price: 2.0 };
+        let report = estimate_training_run(
+            "meta-llama/Meta-Llama-3-8B",
+            &llama_config,
+            &scenario,
+            &a100,
+            1_000_000_000,
+            Some(pricing),
+        )
+        .unwrap();
+        assert_eq!(report.num_gpus(), 8);
+        assert_eq!(
+            report.fit().required(),
+            estimate_scenario(&llama_config, &scenario).required()
+        );
+        assert_eq!(report.fit().verdict(), FitVerdict::Fits);
+        assert_eq!(report.cost(), Some(report.gpu_hours() * 2.0));
+
+        // A QLoRA run on a single GPU takes longer, an inference scenario isn't a training run
+        let lora = Scenario::Lora(LoraSetup::new(16, LoraTarget::all_linear(), Precision::NF4));
+        let lora_report =
+            estimate_training_run("llama", &llama_config, &lora, &a100, 1_000_000_000, None)
+                .unwrap();
+        assert_eq!(lora_report.num_gpus(), 1);
+        assert!(lora_report.time() > report.time());
+        assert_eq!(lora_report.cost(), None);
+        let inference = Scenario::Inference {
+            precision: Precision::BF16,
+            batch_size: 1,
+            seq_len: 4096,
+            overhead: OverheadModel::new(),
+            sliding_window: false,
+        };
+        assert!(
+            estimate_training_run("llama", &llama_config, &inference, &a100, 1, None).is_none()
+        );
+    }
+
+    #[test]
+    fn test_recommend_instances_tensor_parallel() {
+        // Llama 3 70B in FP16 does not fit any single GPU
+        let llama_config = ModelConfig::Llama(create_llama_config_with(LlamaParams::new(
+            8192,
+            28672,
+            8192,
+            80,
+            64,
+            Some(8),
+            None,
+        )));
+        let recommendations = recommend_instances(
+            &llama_config,
+            create_inference_scenario(Precision::FP16, 1, 4096),
+            0.0,
+        );
+        assert!(!recommendations.is_empty());
+        for recommendation in &recommendations {
+            assert!(recommendation.tp_size() > 1);
+            assert!(recommendation
+                .report()
+                .device()
+                .starts_with(&format!("{} × ", recommendation.tp_size())));
+        }
+    }
+
+    #[test]
+    fn test_estimate_serving_costs() {
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let hardware = create_hardware(&[25_769_803_776, 8_589_934_592]);
+        let costs = estimate_serving_costs(&hardware, &llama_config, Precision::FP16, 1, 4096, 0.2);
+        assert_eq!(costs.len(), 2);
+
+        // 350 W at 0.20 $/kWh on the RTX 3090
+        let (report, cost) = &costs[0];
+        assert_eq!(report.verdict(), FitVerdict::Fits);
+        let spec = GpuSpec::from_name("RTX 3090").unwrap();
+        let throughput = estimate_throughput(&llama_config, &spec, Precision::FP16, 1, 4096);
+        let expected = 0.07 / (throughput.decode_tokens_per_second() * 3600.0) * 1e6;
+        assert!((cost.unwrap().output_per_million() - expected).abs() < 1e-9);
+
+        // No cost without a fit
+        let (report, cost) = &costs[1];
+        assert_eq!(report.verdict(), FitVerdict::RequiresOffload);
+        assert!(cost.is_none());
+    }
+
+    #[test]
+    fn test_compare() {
+        let models = [
+            ModelRef::new(
+                "meta-llama/Meta-Llama-3-8B".to_string(),
+                ModelConfig::Llama(create_llama_config()),
+            ),
+            ModelRef::new(
+                "meta-llama/Meta-Llama-3-70B".to_string(),
+                ModelConfig::Llama(create_llama_config_with(LlamaParams::new(
+                    8192,
+                    28672,
+                    8192,
+                    80,
+                    64,
+                    Some(8),
+                    None,
+                ))),
+            ),
+        ];
+        let hardware = create_hardware(&[25_769_803_776, 12_884_901_888]);
+        let scenario = Scenario::Inference {
+            precision: Precision::FP16,
+            batch_size: 1,
+            seq_len: 4096,
+            overhead: OverheadModel::new(),
+            sliding_window: false,
+        };
+        let comparison = compare(&models, &hardware, scenario.clone());
+        assert_eq!(comparison.device(), Some("NVIDIA GeForce RTX 3090"));
+        assert_eq!(comparison.scenario(), scenario.to_string());
+
+        let rows = comparison.rows();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].model(), "meta-llama/Meta-Llama-3-8B");
+        assert_eq!(rows[0].verdict(), Some(FitVerdict::Fits));
+        assert_eq!(
+            rows[0].max_context(),
+            max_context_length(models[0].config(), &hardware, 1, Precision::FP16, false)
+        );
+        assert!(rows[0].throughput().is_some());
+
+        assert!(rows[1].required() > rows[0].required());
+        assert_ne!(rows[1].verdict(), Some(FitVerdict::Fits));
+        assert_eq!(rows[1].max_context(), None);
+        assert_eq!(rows[1].throughput(), None);
+    }
+
+    #[test]
+    fn test_compare_training_without_gpu() {
+        let models = [ModelRef::new(
+            "llama".to_string(),
+            ModelConfig::Llama(create_llama_config()),
+        )];
+        let setup = TrainingSetup::new(1, 1024, Precision::BF16, Optimizer::AdamW);
+        let comparison = compare(&models, &create_hardware(&[]), Scenario::Training(setup));
+        assert_eq!(comparison.device(), None);
+        let row = &comparison.rows()[0];
+        assert_eq!(
+            row.required(),
+            estimate_training(&ModelConfig::Llama(create_llama_config()), setup).total()
+        );
+        assert_eq!(row.verdict(), None);
+        assert_eq!(row.max_context(), None);
+        assert_eq!(row.throughput(), None);
+    }
+
+    #[test]
+    fn test_check_fit_hypothetical_hardware() {
+        // Would Llama 3 8B in FP16 fit after buying a 4090, or renting an A100?
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let scenario = Scenario::Inference {
+            precision: Precision::FP16,
+            batch_size: 1,
+            seq_len: 4096,
+            overhead: OverheadModel::new(),
+            sliding_window: false,
+        };
+        let hardware = Hardware::from_profile("RTX 4090").unwrap();
+        let reports = check_fit(&hardware, &llama_config, scenario.clone());
+        assert_eq!(reports[0].device(), "NVIDIA GeForce RTX 4090");
+        assert_eq!(reports[0].verdict(), FitVerdict::Fits);
+
+        let hardware = Hardware::from_profile("a2-highgpu-1g").unwrap();
+        let reports = check_fit(&hardware, &llama_config, scenario);
+        assert_eq!(reports[0].device(), "NVIDIA A100-SXM4-40GB");
+        assert!(reports[0].headroom() > 0);
+    }
+
+    #[test]
+    fn test_check_fit_without_gpu() {
+        let reports = check_fit(
+            &create_hardware(&[]),
+            &ModelConfig::Llama(create_llama_config()),
+            Scenario::Inference {
+                precision: Precision::NF4,
+                batch_size: 1,
+                seq_len: 2048,
+                overhead: OverheadModel::new(),
+                sliding_window: false,
+            },
+        );
+        assert!(reports.is_empty());
+        assert!(check_fit_tensor_parallel(
+            &create_hardware(&[]),
+            &ModelConfig::Llama(create_llama_config()),
+            create_inference_scenario(Precision::NF4, 1, 2048),
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_check_fit_unified_memory() {
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let m2_pro = AppleChipSpec::from_name("Apple M2 Pro").unwrap();
+        let mut hardware = create_hardware(&[]);
+        hardware.os = "macos".to_string();
+        hardware.arch = "aarch64".to_string();
+        hardware.memory = 16 * 1024 * 1024 * 1024;
+        let estimate =
+            check_fit_unified_memory(&hardware, &m2_pro, &llama_config, Precision::FP16, 1, 4096)
+                .unwrap();
+        assert_eq!(estimate.available(), hardware.memory as i64 / 3 * 2);
+        assert!(!estimate.fits());
+        let estimate = check_fit_unified_memory(
+            &hardware,
+            &m2_pro,
+            &llama_config,
+            Precision::GGUF(QuantType::Q8_0),
+            1,
+            4096,
+        )
+        .unwrap();
+        assert!(estimate.fits());
+
+        hardware.os = "linux".to_string();
+        assert!(check_fit_unified_memory(
+            &hardware,
+            &m2_pro,
+            &llama_config,
+            Precision::FP16,
+            1,
+            4096
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_check_kernels() {
+        let mut hardware = create_hardware(&[24_000_000_000]);
+        let reports = check_kernels(&hardware, Precision::AWQ { bits: 4 });
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].best(), Some(QuantKernel::Marlin));
+        assert_eq!(
+            reports[0].warning(),
+            Some(
+                "NVIDIA GeForce RTX 3090: Machete needs a compute capability of 9.0 or more (found 8.6), \
+                 Marlin runs the awq-4bit weights instead"
+            )
+        );
+
+        // A Pascal GPU runs no AWQ kernel
+        hardware.nvidia_gpus[0] = NvidiaDevice::new(
+            "Pascal".to_string(),
+            "GeForce".to_string(),
+            (6, 1),
+            11_000_000_000,
+            "GeForce GTX 1080 Ti".to_string(),
+            3584,
+            "GPU-0".to_string(),
+            15.752e9,
+            484e9,
+        );
+        let reports = check_kernels(&hardware, Precision::AWQ { bits: 4 });
+        assert!(reports[0].kernels().is_empty());
+        assert!(reports[0].warning().unwrap().ends_with("loaded in fp16"));
+        let reports = check_kernels(&hardware, Precision::GPTQ { bits: 4 });
+        assert_eq!(
+            reports[0].kernels(),
+            [QuantKernel::ExLlamaV2, QuantKernel::GptqCuda]
+        );
+        assert!(check_kernels(&hardware, Precision::BF16).is_empty());
+    }
+
+    #[test]
+    fn test_minimum_requirements() {
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let scenario = Scenario::Inference {
+            precision: Precision::BF16,
+            batch_size: 1,
+            seq_len: 4096,
+            overhead: OverheadModel::new(),
+            sliding_window: false,
+        };
+        let requirements = minimum_requirements(&llama_config, scenario.clone());
+        assert_eq!(requirements.num_gpus(), 1);
+        assert_eq!(
+            requirements.disk(),
+            llama_config.num_parameters() * llama_config.dtype().num_bytes()
+        );
+        assert_eq!(requirements.compute_capability(), (8, 0));
+        // The model fits a GPU of the minimum memory
+        let hardware = create_hardware(&[requirements.vram_per_gpu()]);
+        assert_eq!(
+            check_fit(&hardware, &llama_config, scenario.clone())[0].verdict(),
+            FitVerdict::Fits
+        );
+
+        // A GGUF file is smaller on disk, and runs on older GPUs
+        let requirements = minimum_requirements(
+            &llama_config,
+            scenario.with_precision(Precision::GGUF(QuantType::Q4_K)),
+        );
+        assert_eq!(
+            requirements.disk(),
+            quantized_weights_bytes(&llama_config, Precision::GGUF(QuantType::Q4_K))
+        );
+        assert_eq!(requirements.compute_capability(), (5, 0));
+        assert!(requirements
+            .to_markdown()
+            .contains("| Compute capability | 5.0 |\n"));
+    }
+
+    #[test]
+    fn test_minimum_requirements_multi_gpu() {
+        // Llama 3 70B does not fit a single 80 GB GPU in 16-bit
+        let llama_config = ModelConfig::Llama(create_llama_config_with(LlamaParams::new(
+            8192,
+            28672,
+            8192,
+            80,
+            64,
+            Some(8),
+            None,
+        )));
+        let scenario = Scenario::Inference {
+            precision: Precision::BF16,
+            batch_size: 1,
+            seq_len: 8192,
+            overhead: OverheadModel::new(),
+            sliding_window: false,
+        };
+        let requirements = minimum_requirements(&llama_config, scenario);
+        assert!(requirements.num_gpus() > 1);
+        assert!(requirements.vram_per_gpu() <= 80 * 1024 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_plan_serving_capacity() {
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let hardware = create_hardware(&[24_000_000_000, 24_000_000_000]);
+        let slo = ServingSlo::new(16, 1024, 256);
+        let plan = plan_serving_capacity(
+            &hardware,
+            &llama_config,
+            Precision::BF16,
+            &ServingSetup::new(4096),
+            &slo,
+        )
+        .unwrap();
+        assert!(plan.schedulable());
+        assert!(plan.tpot().is_some());
+
+        let mut setup = ServingSetup::new(4096);
+        setup.tp_size = 4;
+        assert!(
+            plan_serving_capacity(&hardware, &llama_config, Precision::BF16, &setup, &slo)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_check_fit_cpu() {
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let mut hardware = create_hardware(&[]);
+        let estimate = check_fit_cpu(
+            &hardware,
+            &llama_config,
+            Precision::GGUF(QuantType::Q4_K),
+            1,
+            4096,
+        )
+        .unwrap();
+        assert_eq!(estimate.available(), 64_000_000_000 - OS_RESERVED_BYTES);
+        assert!(estimate.fits());
+        assert!(estimate.throughput().decode_tokens_per_second() > 1.0);
+
+        hardware.memory = 0;
+        assert!(check_fit_cpu(&hardware, &llama_config, Precision::FP16, 1, 4096).is_none());
+    }
+
+    #[test]
+    fn test_check_fit_cpu_measured() {
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let hardware = create_hardware(&[]);
+        let precision = Precision::GGUF(QuantType::Q4_K);
+        let estimate = check_fit_cpu(&hardware, &llama_config, precision, 1, 4096).unwrap();
+        // Twice the default dual-channel DDR4 bandwidth, generating is about twice as fast
+        let bench = BenchResult::new(102.4e9, 8, 0);
+        let measured =
+            check_fit_cpu_measured(&hardware, &llama_config, precision, 1, 4096, &bench).unwrap();
+        assert_eq!(measured.total(), estimate.total());
+        let speedup = measured.throughput().decode_tokens_per_second()
+            / estimate.throughput().decode_tokens_per_second();
+        assert!((speedup - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_recommend_quantization() {
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let scenario = Scenario::Inference {
+            precision: Precision::FP16,
+            batch_size: 1,
+            seq_len: 4096,
+            overhead: OverheadModel::new(),
+            sliding_window: false,
+        };
+        // The 16-bit weights fit an 80 GB GPU, without any quality loss
+        let (name, report) = recommend_quantization(
+            &create_hardware(&[80_000_000_000]),
+            &llama_config,
+            scenario.clone(),
+        )
+        .unwrap();
+        assert_eq!(name, "FP16");
+        assert_eq!(report.quality_loss(), None);
+
+        // A 8 GB GPU needs a quantization, the largest levels do not fit
+        let hardware = create_hardware(&[8_000_000_000]);
+        let (name, report) =
+            recommend_quantization(&hardware, &llama_config, scenario.clone()).unwrap();
+        let index = QUANTIZATION_LEVELS
+            .iter()
+            .position(|(level, _)| *level == name)
+            .unwrap();
+        assert!(index > 0);
+        assert_eq!(report.verdict(), FitVerdict::Fits);
+        assert_eq!(
+            report.quality_loss(),
+            quality_loss(QUANTIZATION_LEVELS[index].1)
+        );
+        let (_, larger) = QUANTIZATION_LEVELS[index - 1];
+        assert_ne!(
+            check_fit(&hardware, &llama_config, scenario.with_precision(larger))[0].verdict(),
+            FitVerdict::Fits
+        );
+
+        assert!(recommend_quantization(
+            &create_hardware(&[1_000_000_000]),
+            &llama_config,
+            scenario
+        )
+        .is_none());
+    }
+}
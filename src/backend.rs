@@ -0,0 +1,152 @@
+//! Module for the strategies estimating the memory and the speed of the models, the advisor
+//! checks running with any of them
+use crate::advisor::{estimate_scenario, Scenario, ScenarioMemory};
+use crate::estimate::{estimate_throughput, Throughput};
+use crate::hub::ModelConfig;
+use crate::models::Precision;
+use crate::specs::GpuSpec;
+
+/// A trait for the strategies estimating how much memory a model needs and how fast it runs
+/// (analytic formulas, lookup tables of measurements, a remote calibration service...)
+pub trait EstimatorBackend {
+    /// Estimate the memory a scenario needs on a single device, split into its components
+    fn estimate_memory(&self, config: &ModelConfig, scenario: &Scenario) -> ScenarioMemory;
+    /// Estimate the speed of generating `batch_size` sequences of `seq_len` tokens on a GPU
+    fn estimate_throughput(
+        &self,
+        config: &ModelConfig,
+        device: &GpuSpec,
+        precision: Precision,
+        batch_size: i32,
+        seq_len: i32,
+    ) -> Throughput;
+}
+
+/// The analytic formulas of the `estimate` module, the default backend of the advisor
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AnalyticBackend;
+
+/// Implementation of the `EstimatorBackend` trait for `AnalyticBackend`
+impl EstimatorBackend for AnalyticBackend {
+    fn estimate_memory(&self, config: &ModelConfig, scenario: &Scenario) -> ScenarioMemory {
+        estimate_scenario(config, scenario)
+    }
+    fn estimate_throughput(
+        &self,
+        config: &ModelConfig,
+        device: &GpuSpec,
+        precision: Precision,
+        batch_size: i32,
+        seq_len: i32,
+    ) -> Throughput {
+        estimate_throughput(config, device, precision, batch_size, seq_len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advisor::{check_fit, check_fit_with_backend, FitVerdict, MemoryComponent};
+    use crate::estimate::test_utils::create_llama_config;
+    use crate::estimate::OverheadModel;
+    use crate::hardware::{Hardware, NvidiaDevice};
+
+    /// A backend answering from a table of measured memories
+    struct LookupBackend {
+        measured: Vec<(i32, i64)>,
+    }
+
+    impl EstimatorBackend for LookupBackend {
+        fn estimate_memory(&self, config: &ModelConfig, scenario: &Scenario) -> ScenarioMemory {
+            let batch_size = match scenario {
+                Scenario::Inference { batch_size, .. } => *batch_size,
+                _ => return AnalyticBackend.estimate_memory(config, scenario),
+            };
+            match self.measured.iter().find(|(batch, _)| *batch == batch_size) {
+                Some((_, bytes)) => {
+                    ScenarioMemory::new(vec![(MemoryComponent::Weights, *bytes)], 0, 0, 0.0)
+                }
+                None => AnalyticBackend.estimate_memory(config, scenario),
+            }
+        }
+        fn estimate_throughput(
+            &self,
+            config: &ModelConfig,
+            device: &GpuSpec,
+            precision: Precision,
+            batch_size: i32,
+            seq_len: i32,
+        ) -> Throughput {
+            AnalyticBackend.estimate_throughput(config, device, precision, batch_size, seq_len)
+        }
+    }
+
+    fn create_hardware() -> Hardware {
+        let nvidia_gpus = vec![NvidiaDevice::new(
+            "Ampere".to_string(),
+            "GeForce".to_string(),
+            (8, 6),
+            24_000_000_000,
+            "GeForce RTX 3090".to_string(),
+            10496,
+            "GPU-0".to_string(),
+            31.504e9,
+            936.096e9,
+        )];
+        Hardware {
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            cpu_cores: 8,
+            cpu_threads: 16,
+            memory: 64_000_000_000,
+            gpu_count: 1,
+            nvidia_gpus,
+            amd_gpus: Vec::new(),
+        }
+    }
+
+    fn create_scenario(batch_size: i32) -> Scenario {
+        Scenario::Inference {
+            precision: Precision::BF16,
+            batch_size,
+            seq_len: 4096,
+            overhead: OverheadModel::new(),
+            sliding_window: false,
+        }
+    }
+
+    #[test]
+    fn test_analytic_backend_is_default() {
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let hardware = create_hardware();
+        assert_eq!(
+            check_fit_with_backend(
+                &hardware,
+                &llama_config,
+                create_scenario(1),
+                &AnalyticBackend
+            ),
+            check_fit(&hardware, &llama_config, create_scenario(1))
+        );
+    }
+
+    #[test]
+    fn test_custom_backend() {
+        let llama_config = ModelConfig::Llama(create_llama_config());
+        let hardware = create_hardware();
+        let backend = LookupBackend {
+            measured: vec![(1, 30_000_000_000)],
+        };
+        // The measured memory of a batch of 1 doesn't fit the RTX 3090
+        let reports =
+            check_fit_with_backend(&hardware, &llama_config, create_scenario(1), &backend);
+        assert_eq!(reports[0].required(), 30_000_000_000);
+        assert_eq!(reports[0].verdict(), FitVerdict::DoesNotFit);
+
+        // The other batch sizes fall back to the analytic formulas
+        assert_eq!(
+            check_fit_with_backend(&hardware, &llama_config, create_scenario(2), &backend),
+            check_fit(&hardware, &llama_config, create_scenario(2))
+        );
+    }
+}
@@ -0,0 +1,170 @@
+//! Module for the micro-benchmarks of the running system, stored in the AIHA cache folder so the
+//! estimates can use the measured numbers instead of the defaults
+use std::fs;
+use std::hint::black_box;
+use std::io;
+use std::path::Path;
+use std::thread;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::calibration::default_aiha_cache_dir;
+use crate::estimate::CpuSetup;
+use crate::hardware::scan_cpu_cores;
+
+/// Name of the file of the benchmark results in the AIHA cache folder
+pub const BENCH_FILE: &str = "bench.json";
+/// Default size of the buffer copied by the memory bandwidth benchmark, large enough to get out
+/// of the CPU caches (bytes)
+pub const DEFAULT_BENCH_BUFFER_BYTES: usize = 512 * 1024 * 1024;
+/// Default number of copies of the buffer, the fastest one being kept
+pub const DEFAULT_BENCH_REPEATS: u32 = 5;
+
+/// A struct representing the numbers measured on the running system
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BenchResult {
+    /// The bandwidth of the RAM, in bytes/s
+    memory_bandwidth: f64,
+    /// The number of threads the benchmark ran on
+    threads: usize,
+    /// When the benchmark ran, in seconds since the Unix epoch
+    timestamp: u64,
+}
+
+/// BenchResult implementation
+impl BenchResult {
+    /// Build a new `BenchResult` struct based on the provided parameters
+    pub fn new(memory_bandwidth: f64, threads: usize, timestamp: u64) -> BenchResult {
+        BenchResult {
+            memory_bandwidth,
+            threads,
+            timestamp,
+        }
+    }
+    /// Returns the bandwidth of the RAM
+    pub fn memory_bandwidth(&self) -> f64 {
+        self.memory_bandwidth
+    }
+    /// Returns the number of threads the benchmark ran on
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+    /// Returns when the benchmark ran, in seconds since the Unix epoch
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+    /// Returns the CPU setup with the measured numbers instead of the defaults
+    pub fn apply(&self, setup: CpuSetup) -> CpuSetup {
+        CpuSetup {
+            memory_bandwidth: self.memory_bandwidth,
+            ..setup
+        }
+    }
+    /// Load the results of a JSON file, `None` when the benchmark never ran
+    pub fn load(path: &Path) -> io::Result<Option<BenchResult>> {
+        match fs::read(path) {
+            Ok(content) => Ok(Some(serde_json::from_slice(&content)?)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+    /// Load the results of the AIHA cache folder (see `default_aiha_cache_dir`)
+    pub fn load_default() -> io::Result<Option<BenchResult>> {
+        match default_aiha_cache_dir() {
+            Some(cache_dir) => BenchResult::load(&cache_dir.join(BENCH_FILE)),
+            None => Ok(None),
+        }
+    }
+    /// Save the results to a JSON file, creating its folder if needed
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_vec_pretty(self)?)
+    }
+    /// Save the results to the AIHA cache folder
+    pub fn save_default(&self) -> io::Result<()> {
+        let cache_dir = default_aiha_cache_dir()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no AIHA cache folder found"))?;
+        self.save(&cache_dir.join(BENCH_FILE))
+    }
+}
+
+/// Measure the bandwidth of the RAM, in bytes/s, by copying a buffer of `buffer_bytes` split
+/// across `threads` threads, `repeats` times.
+///
+/// A copy reads and writes every byte, like the STREAM copy benchmark; the fastest copy is kept
+/// so that a preempted run doesn't lower the result. A single thread doesn't saturate the memory
+/// controllers, llama.cpp reads the weights from a thread per core.
+pub fn measure_memory_bandwidth(buffer_bytes: usize, threads: usize, repeats: u32) -> f64 {
+    let threads = threads.max(1);
+    let chunk = (buffer_bytes / threads).max(1);
+    // Filled with ones so that the pages are mapped before the measure
+    let source = vec![1u8; chunk * threads];
+    let mut destination = vec![1u8; chunk * threads];
+    let copy = |destination: &mut [u8]| {
+        thread::scope(|scope| {
+            for (from, to) in source.chunks(chunk).zip(destination.chunks_mut(chunk)) {
+                scope.spawn(move || to.copy_from_slice(black_box(from)));
+            }
+        });
+    };
+    // A first copy warms up the threads and the TLB
+    copy(&mut destination);
+    let fastest = (0..repeats.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            copy(&mut destination);
+            black_box(&destination);
+            start.elapsed().as_secs_f64()
+        })
+        .fold(f64::INFINITY, f64::min);
+    2.0 * source.len() as f64 / fastest.max(f64::MIN_POSITIVE)
+}
+
+/// Run the micro-benchmarks on a thread per physical core, with the default sizes
+#[tracing::instrument(level = "debug")]
+pub fn run_bench() -> BenchResult {
+    let threads = scan_cpu_cores().max(1) as usize;
+    let memory_bandwidth =
+        measure_memory_bandwidth(DEFAULT_BENCH_BUFFER_BYTES, threads, DEFAULT_BENCH_REPEATS);
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    BenchResult::new(memory_bandwidth, threads, timestamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_measure_memory_bandwidth() {
+        let bandwidth = measure_memory_bandwidth(4 * 1024 * 1024, 2, 3);
+        assert!(bandwidth.is_finite());
+        assert!(bandwidth > 1e8);
+    }
+
+    #[test]
+    fn test_bench_result_apply() {
+        let result = BenchResult::new(80e9, 8, 0);
+        let setup = result.apply(CpuSetup::new(64_000_000_000, 8));
+        assert_eq!(setup.memory_bandwidth, 80e9);
+        assert_eq!(setup.ram, 64_000_000_000);
+    }
+
+    #[test]
+    fn test_bench_result_save_and_load() {
+        let path = env::temp_dir()
+            .join(format!("aiha-bench-{}", std::process::id()))
+            .join(BENCH_FILE);
+        assert_eq!(BenchResult::load(&path).unwrap(), None);
+        let result = BenchResult::new(42.5e9, 4, 1_700_000_000);
+        result.save(&path).unwrap();
+        assert_eq!(BenchResult::load(&path).unwrap(), Some(result));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
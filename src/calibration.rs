@@ -0,0 +1,301 @@
+//! Module for the calibration of the estimates with the memory and the speed measured on real
+//! hardware, stored in the AIHA cache folder
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::advisor::Scenario;
+use crate::estimate::Throughput;
+
+/// Name of the file of the calibration factors in the AIHA cache folder
+pub const CALIBRATION_FILE: &str = "calibration.json";
+
+/// Get the AIHA cache folder: `AIHA_CACHE`, `XDG_CACHE_HOME/aiha` then `~/.cache/aiha`
+pub fn default_aiha_cache_dir() -> Option<PathBuf> {
+    let var = |name: &str| {
+        env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+    if let Some(path) = var("AIHA_CACHE") {
+        return Some(path);
+    }
+    let cache_home = var("XDG_CACHE_HOME").or_else(|| {
+        var("HOME")
+            .or_else(|| var("USERPROFILE"))
+            .map(|home| home.join(".cache"))
+    })?;
+    Some(cache_home.join("aiha"))
+}
+
+/// A struct representing how far the estimates of a (model, hardware, scenario) tuple are from
+/// the measured numbers
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Calibration {
+    /// The mean ratio of the measured memory to the estimated one
+    memory_factor: f64,
+    /// The number of memory measurements
+    memory_samples: u32,
+    /// The mean ratio of the measured tokens/s to the estimated ones
+    throughput_factor: f64,
+    /// The number of speed measurements
+    throughput_samples: u32,
+}
+
+/// Calibration implementation
+impl Calibration {
+    /// Build a new `Calibration` leaving the estimates unchanged
+    pub fn new() -> Calibration {
+        Calibration {
+            memory_factor: 1.0,
+            memory_samples: 0,
+            throughput_factor: 1.0,
+            throughput_samples: 0,
+        }
+    }
+    /// Returns the mean ratio of the measured memory to the estimated one
+    pub fn memory_factor(&self) -> f64 {
+        self.memory_factor
+    }
+    /// Returns the number of memory measurements
+    pub fn memory_samples(&self) -> u32 {
+        self.memory_samples
+    }
+    /// Returns the mean ratio of the measured tokens/s to the estimated ones
+    pub fn throughput_factor(&self) -> f64 {
+        self.throughput_factor
+    }
+    /// Returns the number of speed measurements
+    pub fn throughput_samples(&self) -> u32 {
+        self.throughput_samples
+    }
+    /// Add a memory measurement, ignored when one of the numbers isn't positive
+    pub fn add_memory(&mut self, estimated: i64, measured: i64) {
+        if estimated <= 0 || measured <= 0 {
+            return;
+        }
+        self.memory_samples += 1;
+        self.memory_factor +=
+            (measured as f64 / estimated as f64 - self.memory_factor) / self.memory_samples as f64;
+    }
+    /// Add a speed measurement, in tokens/s, ignored when one of the numbers isn't positive
+    pub fn add_throughput(&mut self, estimated: f64, measured: f64) {
+        if estimated <= 0.0 || measured <= 0.0 || !estimated.is_finite() || !measured.is_finite() {
+            return;
+        }
+        self.throughput_samples += 1;
+        self.throughput_factor +=
+            (measured / estimated - self.throughput_factor) / self.throughput_samples as f64;
+    }
+    /// Returns the estimated memory, in bytes, corrected by the measurements
+    pub fn apply_memory(&self, estimated: i64) -> i64 {
+        (estimated as f64 * self.memory_factor).round() as i64
+    }
+    /// Returns the estimated speed corrected by the measurements
+    pub fn apply_throughput(&self, estimated: Throughput) -> Throughput {
+        Throughput::new(
+            estimated.prefill_tokens_per_second() * self.throughput_factor,
+            estimated.decode_tokens_per_second() * self.throughput_factor,
+        )
+    }
+}
+
+/// Implement the default of the Calibration struct, leaving the estimates unchanged
+impl Default for Calibration {
+    fn default() -> Self {
+        Calibration::new()
+    }
+}
+
+/// A struct representing the calibration factors of every measured (model, hardware, scenario)
+/// tuple, saved as a JSON file
+#[derive(Clone, Debug, PartialEq)]
+pub struct CalibrationStore {
+    /// The path of the JSON file
+    path: PathBuf,
+    /// The calibration factors, keyed by `model|device|scenario`
+    entries: BTreeMap<String, Calibration>,
+}
+
+/// CalibrationStore implementation
+impl CalibrationStore {
+    /// Load the calibration factors of a JSON file, empty when the file doesn't exist yet
+    pub fn load(path: &Path) -> io::Result<CalibrationStore> {
+        let entries = match fs::read(path) {
+            Ok(content) => serde_json::from_slice(&content)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e),
+        };
+        Ok(CalibrationStore {
+            path: path.to_path_buf(),
+            entries,
+        })
+    }
+    /// Load the calibration factors of the AIHA cache folder (see `default_aiha_cache_dir`)
+    pub fn load_default() -> io::Result<CalibrationStore> {
+        let cache_dir = default_aiha_cache_dir()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no AIHA cache folder found"))?;
+        CalibrationStore::load(&cache_dir.join(CALIBRATION_FILE))
+    }
+    /// Save the calibration factors to their JSON file, creating its folder if needed
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_vec_pretty(&self.entries)?)
+    }
+    /// Returns the path of the JSON file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    /// Returns the number of calibrated (model, hardware, scenario) tuples
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    /// Returns whether no tuple is calibrated
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    /// Returns the calibration factors of a model (its repository ID) running a scenario on a
+    /// device, `None` before any measurement
+    pub fn get(&self, model: &str, device: &str, scenario: &Scenario) -> Option<Calibration> {
+        let calibration = self
+            .entries
+            .get(&calibration_key(model, device, scenario))
+            .copied();
+        let cache = match calibration {
+            Some(_) => "hit",
+            None => "miss",
+        };
+        tracing::debug!(model, device, cache, "Calibration lookup");
+        calibration
+    }
+    /// Record the memory measured, e.g. the peak of `torch.cuda.max_memory_reserved` or of
+    /// `nvidia-smi`, against the estimated one, both in bytes
+    pub fn record_memory(
+        &mut self,
+        model: &str,
+        device: &str,
+        scenario: &Scenario,
+        estimated: i64,
+        measured: i64,
+    ) {
+        self.entries
+            .entry(calibration_key(model, device, scenario))
+            .or_default()
+            .add_memory(estimated, measured);
+    }
+    /// Record the generation speed measured against the estimated one, both in tokens/s
+    pub fn record_throughput(
+        &mut self,
+        model: &str,
+        device: &str,
+        scenario: &Scenario,
+        estimated: f64,
+        measured: f64,
+    ) {
+        self.entries
+            .entry(calibration_key(model, device, scenario))
+            .or_default()
+            .add_throughput(estimated, measured);
+    }
+}
+
+/// Returns the key of a (model, hardware, scenario) tuple in the store
+fn calibration_key(model: &str, device: &str, scenario: &Scenario) -> String {
+    format!("{}|{}|{}", model, device, scenario)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate::OverheadModel;
+    use crate::models::Precision;
+
+    fn create_scenario(batch_size: i32) -> Scenario {
+        Scenario::Inference {
+            precision: Precision::BF16,
+            batch_size,
+            seq_len: 4096,
+            overhead: OverheadModel::new(),
+            sliding_window: false,
+        }
+    }
+
+    #[test]
+    fn test_calibration_running_mean() {
+        let mut calibration = Calibration::new();
+        assert_eq!(calibration.apply_memory(1000), 1000);
+        calibration.add_memory(1000, 1100);
+        calibration.add_memory(1000, 1300);
+        assert!((calibration.memory_factor() - 1.2).abs() < 1e-9);
+        assert_eq!(calibration.memory_samples(), 2);
+        assert_eq!(calibration.apply_memory(1000), 1200);
+
+        // Invalid measurements are ignored
+        calibration.add_throughput(0.0, 30.0);
+        calibration.add_throughput(40.0, 30.0);
+        assert_eq!(calibration.throughput_samples(), 1);
+        let throughput = calibration.apply_throughput(Throughput::new(1000.0, 40.0));
+        assert_eq!(throughput.decode_tokens_per_second(), 30.0);
+        assert_eq!(throughput.prefill_tokens_per_second(), 750.0);
+    }
+
+    #[test]
+    fn test_calibration_store_keys() {
+        let path = env::temp_dir().join(format!("aiha-calibration-keys-{}", std::process::id()));
+        let mut store = CalibrationStore::load(&path.join(CALIBRATION_FILE)).unwrap();
+        assert!(store.is_empty());
+        let scenario = create_scenario(1);
+        store.record_memory(
+            "meta-llama/Meta-Llama-3-8B",
+            "NVIDIA A100",
+            &scenario,
+            100,
+            110,
+        );
+        assert_eq!(
+            store
+                .get("meta-llama/Meta-Llama-3-8B", "NVIDIA A100", &scenario)
+                .unwrap()
+                .memory_samples(),
+            1
+        );
+        // Another device, or another scenario, isn't calibrated
+        assert!(store
+            .get("meta-llama/Meta-Llama-3-8B", "NVIDIA L4", &scenario)
+            .is_none());
+        let other = create_scenario(8);
+        assert!(store
+            .get("meta-llama/Meta-Llama-3-8B", "NVIDIA A100", &other)
+            .is_none());
+    }
+
+    #[test]
+    fn test_calibration_store_save_and_load() {
+        let cache_dir =
+            env::temp_dir().join(format!("aiha-calibration-save-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&cache_dir);
+        let path = cache_dir.join(CALIBRATION_FILE);
+        let scenario = create_scenario(1);
+
+        let mut store = CalibrationStore::load(&path).unwrap();
+        store.record_memory("gpt2", "NVIDIA L4", &scenario, 1000, 1250);
+        store.record_throughput("gpt2", "NVIDIA L4", &scenario, 100.0, 80.0);
+        store.save().unwrap();
+
+        let loaded = CalibrationStore::load(&path).unwrap();
+        assert_eq!(loaded, store);
+        let calibration = loaded.get("gpt2", "NVIDIA L4", &scenario).unwrap();
+        assert_eq!(calibration.memory_factor(), 1.25);
+        assert_eq!(calibration.throughput_factor(), 0.8);
+
+        fs::write(&path, "not json").unwrap();
+        assert!(CalibrationStore::load(&path).is_err());
+        let _ = fs::remove_dir_all(&cache_dir);
+    }
+}
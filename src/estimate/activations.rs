@@ -0,0 +1,201 @@
+//! Training activation memory estimates
+use crate::models::{ModelConfigTrait, Precision};
+
+/// Enumerate the activation checkpointing strategies of the training
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Checkpointing {
+    /// Every activation is kept for the backward pass
+    Disabled,
+    /// The MLP up projection and activation function outputs, the largest activations of a
+    /// layer, are recomputed during the backward pass
+    Selective,
+    /// Only the input of every layer is kept, the whole forward pass is recomputed during the
+    /// backward pass (`gradient_checkpointing` of Transformers)
+    Full,
+}
+
+/// Checkpointing implementation
+impl Checkpointing {
+    /// Returns the share of the training step time added by the recomputation, e.g. `0.33` when
+    /// a step takes 33% longer. The backward pass costs twice the forward pass, so recomputing
+    /// the whole forward pass adds a third of the step.
+    pub fn step_time_penalty(&self, config: &dyn ModelConfigTrait) -> f64 {
+        let recomputed_forward = match self {
+            Checkpointing::Disabled => 0.0,
+            Checkpointing::Selective => {
+                let hidden_size = config.hidden_size() as f64;
+                let attention =
+                    2.0 * hidden_size * hidden_size + 2.0 * hidden_size * config.kv_dim() as f64;
+                let mlp = 2.0 * hidden_size * config.intermediate_size() as f64;
+                match attention + mlp > 0.0 {
+                    true => mlp / 2.0 / (attention + mlp),
+                    false => 0.0,
+                }
+            }
+            Checkpointing::Full => 1.0,
+        };
+        recomputed_forward / 3.0
+    }
+}
+
+/// Returns the number of bytes of a single activation value, quantized weights still compute in
+/// 16-bit and only the FP32 training keeps its activations in 32-bit
+pub(crate) fn activation_bytes(precision: Precision) -> i64 {
+    match precision {
+        Precision::FP32 => 4,
+        _ => 2,
+    }
+}
+
+/// Returns the number of bytes of the activations a layer keeps for the backward pass, following
+/// "Reducing Activation Recomputation in Large Transformer Models" (Korthikanti et al., 2022).
+///
+/// The attention is expected to be a memory efficient one (FlashAttention, SDPA), so the
+/// attention scores are not stored. With 16-bit activations and a `4 * hidden_size` MLP, it is
+/// the `34 * seq_len * batch_size * hidden_size` of the paper.
+pub fn activation_bytes_per_layer(
+    config: &dyn ModelConfigTrait,
+    batch_size: i32,
+    seq_len: i32,
+    precision: Precision,
+) -> i64 {
+    let tokens = batch_size as i64 * seq_len as i64;
+    let hidden_size = config.hidden_size() as i64;
+    let intermediate_size = config.intermediate_size() as i64;
+    let bytes = activation_bytes(precision);
+    // QKV input, Q, K and V, attention output, and 1-byte dropout mask
+    let attention = (5 * bytes + 1) * hidden_size;
+    // MLP input, up projection and activation outputs, and 1-byte dropout mask
+    let mlp = bytes * (hidden_size + 2 * intermediate_size) + hidden_size;
+    // Inputs of the two layer norms
+    let layer_norms = 2 * bytes * hidden_size;
+    tokens * (attention + mlp + layer_norms)
+}
+
+/// Estimate the memory of the activations kept for the backward pass of a training step, in
+/// bytes.
+///
+/// With `checkpointing`, the dropped activations of every layer are recomputed one layer at a
+/// time during the backward pass, so a single layer holds them at once. The logits are always
+/// kept in FP32 to compute the loss, which weighs a lot for the large vocabularies.
+pub fn estimate_activation_memory(
+    config: &dyn ModelConfigTrait,
+    batch_size: i32,
+    seq_len: i32,
+    precision: Precision,
+    checkpointing: Checkpointing,
+) -> i64 {
+    let tokens = batch_size as i64 * seq_len as i64;
+    let num_layers = config.num_hidden_layers() as i64;
+    let bytes = activation_bytes(precision);
+    let per_layer = activation_bytes_per_layer(config, batch_size, seq_len, precision);
+    let recomputed = match checkpointing {
+        Checkpointing::Disabled => 0,
+        Checkpointing::Selective => tokens * 2 * bytes * config.intermediate_size() as i64,
+        Checkpointing::Full => per_layer - tokens * config.hidden_size() as i64 * bytes,
+    };
+    let layers = num_layers * (per_layer - recomputed) + recomputed;
+    let logits = 4 * tokens * config.vocab_size() as i64;
+    layers + logits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Dtype, GPT2ModelConfig, GPT2Params, ModelLibraries};
+
+    fn create_gpt2_config() -> GPT2ModelConfig {
+        GPT2ModelConfig::new(
+            GPT2Params::new(768, None, 1024, 12, 12),
+            50257,
+            true,
+            Dtype::F32,
+            "gpt2".to_string(),
+            vec![ModelLibraries::PyTorch],
+        )
+    }
+
+    #[test]
+    fn test_activation_bytes_per_layer() {
+        let gpt2_config = create_gpt2_config();
+        assert_eq!(
+            activation_bytes_per_layer(&gpt2_config, 8, 1024, Precision::BF16),
+            34 * 8 * 1024 * 768
+        );
+        assert_eq!(
+            activation_bytes_per_layer(&gpt2_config, 8, 1024, Precision::NF4),
+            activation_bytes_per_layer(&gpt2_config, 8, 1024, Precision::BF16)
+        );
+        // 32-bit activations, the dropout masks are still 1 byte
+        assert_eq!(
+            activation_bytes_per_layer(&gpt2_config, 8, 1024, Precision::FP32),
+            66 * 8 * 1024 * 768
+        );
+    }
+
+    #[test]
+    fn test_estimate_activation_memory() {
+        let gpt2_config = create_gpt2_config();
+        let logits = 4 * 8 * 1024 * 50257;
+        assert_eq!(
+            estimate_activation_memory(
+                &gpt2_config,
+                8,
+                1024,
+                Precision::BF16,
+                Checkpointing::Disabled
+            ),
+            12 * 34 * 8 * 1024 * 768 + logits
+        );
+        assert_eq!(
+            estimate_activation_memory(&gpt2_config, 8, 1024, Precision::BF16, Checkpointing::Full),
+            12 * 2 * 8 * 1024 * 768 + 32 * 8 * 1024 * 768 + logits
+        );
+        // The 16 bytes of the MLP intermediate activations are recomputed
+        assert_eq!(
+            estimate_activation_memory(
+                &gpt2_config,
+                8,
+                1024,
+                Precision::BF16,
+                Checkpointing::Selective
+            ),
+            12 * 18 * 8 * 1024 * 768 + 16 * 8 * 1024 * 768 + logits
+        );
+    }
+
+    #[test]
+    fn test_checkpointing_step_time_penalty() {
+        let gpt2_config = create_gpt2_config();
+        assert_eq!(Checkpointing::Disabled.step_time_penalty(&gpt2_config), 0.0);
+        assert_eq!(
+            Checkpointing::Full.step_time_penalty(&gpt2_config),
+            1.0 / 3.0
+        );
+        // The up projection is a third of the GPT-2 layer, a ninth of the step
+        assert_eq!(
+            Checkpointing::Selective.step_time_penalty(&gpt2_config),
+            1.0 / 9.0
+        );
+    }
+
+    #[test]
+    fn test_estimate_activation_memory_scales_with_seq_len() {
+        let gpt2_config = create_gpt2_config();
+        let short = estimate_activation_memory(
+            &gpt2_config,
+            1,
+            512,
+            Precision::FP16,
+            Checkpointing::Disabled,
+        );
+        let long = estimate_activation_memory(
+            &gpt2_config,
+            1,
+            1024,
+            Precision::FP16,
+            Checkpointing::Disabled,
+        );
+        assert_eq!(long, 2 * short);
+    }
+}
@@ -0,0 +1,213 @@
+//! Apple Silicon (Metal, MLX) unified memory inference estimates
+use crate::estimate::activations::activation_bytes;
+use crate::estimate::{
+    cache_precision, estimate_kv_cache, flops_per_token, quantized_weights_bytes, Phase,
+    Throughput, BANDWIDTH_EFFICIENCY, COMPUTE_EFFICIENCY, LLAMA_CPP_UBATCH,
+};
+use crate::models::{ModelConfigTrait, Precision};
+use crate::specs::AppleChipSpec;
+
+/// Unified memory up to which macOS lets the GPU wire two thirds of it, three quarters above
+/// (bytes)
+pub const SMALL_UNIFIED_MEMORY_BYTES: i64 = 36 * 1024 * 1024 * 1024;
+
+/// A struct representing the unified memory of an Apple Silicon system
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UnifiedMemorySetup {
+    /// The unified memory of the system, in bytes
+    pub unified_memory: i64,
+    /// The memory the GPU can wire, in bytes, raised with the `iogpu.wired_limit_mb` sysctl
+    pub gpu_memory_limit: i64,
+}
+
+/// UnifiedMemorySetup implementation
+impl UnifiedMemorySetup {
+    /// Build a new `UnifiedMemorySetup` with the default limit of macOS, the
+    /// `recommendedMaxWorkingSetSize` of Metal: two thirds of the memory up to 36 GiB, three
+    /// quarters above
+    pub fn new(unified_memory: i64) -> UnifiedMemorySetup {
+        let gpu_memory_limit = match unified_memory <= SMALL_UNIFIED_MEMORY_BYTES {
+            true => unified_memory / 3 * 2,
+            false => unified_memory / 4 * 3,
+        };
+        UnifiedMemorySetup {
+            unified_memory,
+            gpu_memory_limit,
+        }
+    }
+}
+
+/// A struct representing the memory and the speed of a model running on an Apple Silicon GPU
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UnifiedMemoryEstimate {
+    /// The memory of the weights, in bytes
+    weights: i64,
+    /// The memory of the KV cache, in bytes
+    kv_cache: i64,
+    /// The memory of the compute buffer of a batch of prompt tokens, in bytes
+    compute_buffer: i64,
+    /// The memory the GPU can wire, in bytes
+    available: i64,
+    /// The expected generation speed
+    throughput: Throughput,
+}
+
+/// UnifiedMemoryEstimate implementation
+impl UnifiedMemoryEstimate {
+    /// Build a new `UnifiedMemoryEstimate` struct based on the provided parameters
+    pub fn new(
+        weights: i64,
+        kv_cache: i64,
+        compute_buffer: i64,
+        available: i64,
+        throughput: Throughput,
+    ) -> UnifiedMemoryEstimate {
+        UnifiedMemoryEstimate {
+            weights,
+            kv_cache,
+            compute_buffer,
+            available,
+            throughput,
+        }
+    }
+    /// Returns the memory of the weights
+    pub fn weights(&self) -> i64 {
+        self.weights
+    }
+    /// Returns the memory of the KV cache
+    pub fn kv_cache(&self) -> i64 {
+        self.kv_cache
+    }
+    /// Returns the memory of the compute buffer
+    pub fn compute_buffer(&self) -> i64 {
+        self.compute_buffer
+    }
+    /// Returns the memory the GPU can wire
+    pub fn available(&self) -> i64 {
+        self.available
+    }
+    /// Returns the expected generation speed
+    pub fn throughput(&self) -> Throughput {
+        self.throughput
+    }
+    /// Returns the total memory needed to run the model
+    pub fn total(&self) -> i64 {
+        self.weights + self.kv_cache + self.compute_buffer
+    }
+    /// Returns whether the model fits the memory the GPU can wire
+    pub fn fits(&self) -> bool {
+        self.total() <= self.available
+    }
+}
+
+/// Estimate whether a model runs on the GPU of an Apple Silicon chip with Metal (llama.cpp, MLX),
+/// for `batch_size` sequences of `seq_len` tokens, and how fast.
+///
+/// The GPU shares the memory of the system, but macOS only lets it wire a part of it, the rest
+/// being kept for the system and the other applications. The weights, the KV cache and a compute
+/// buffer of `LLAMA_CPP_UBATCH` tokens are budgeted against that limit. Generating a token is
+/// bound by the bandwidth of the unified memory, processing the prompt by the compute of the GPU.
+pub fn estimate_unified_memory(
+    config: &dyn ModelConfigTrait,
+    chip: &AppleChipSpec,
+    precision: Precision,
+    batch_size: i32,
+    seq_len: i32,
+    setup: &UnifiedMemorySetup,
+) -> UnifiedMemoryEstimate {
+    let batch_size = batch_size.max(1);
+    let weights = quantized_weights_bytes(config, precision);
+    let kv_cache = estimate_kv_cache(config, batch_size, seq_len, cache_precision(precision));
+    let ubatch = seq_len.clamp(1, LLAMA_CPP_UBATCH) as i64;
+    let compute_buffer = ubatch
+        * (4 * config.hidden_size() as i64 + 2 * config.intermediate_size() as i64)
+        * activation_bytes(precision)
+        + batch_size as i64 * config.vocab_size() as i64 * 4;
+
+    let peak_flops = chip.fp16_tflops() * 1e12 * COMPUTE_EFFICIENCY;
+    let bandwidth = chip.memory_bandwidth() * BANDWIDTH_EFFICIENCY;
+    // Only the weights of the active experts of the MoE models are read for a single token
+    let active_share = match config.total_parameters() > 0 {
+        true => config.active_parameters_per_token() as f64 / config.total_parameters() as f64,
+        false => 1.0,
+    };
+    let prefill_flops = flops_per_token(config, Phase::Prefill, seq_len).total();
+    let decode_flops = flops_per_token(config, Phase::Decode, seq_len).total();
+    let decode_time = (batch_size as f64 * decode_flops / peak_flops)
+        .max((weights as f64 * active_share + kv_cache as f64) / bandwidth);
+    let throughput = Throughput::new(peak_flops / prefill_flops, batch_size as f64 / decode_time);
+
+    UnifiedMemoryEstimate::new(
+        weights,
+        kv_cache,
+        compute_buffer,
+        setup.gpu_memory_limit.min(setup.unified_memory).max(0),
+        throughput,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        Dtype, LlamaModelConfig, LlamaParams, ModelLibraries, QuantType, RopeParams,
+    };
+
+    const GIB: i64 = 1024 * 1024 * 1024;
+
+    fn create_llama_config() -> LlamaModelConfig {
+        // Llama 3 70B
+        LlamaModelConfig::new(
+            LlamaParams::new(8192, 28672, 8192, 80, 64, Some(8), None),
+            128256,
+            false,
+            RopeParams::new(Some(500000.0), None),
+            Dtype::BF16,
+            "llama".to_string(),
+            vec![ModelLibraries::PyTorch],
+        )
+    }
+
+    #[test]
+    fn test_unified_memory_setup() {
+        assert_eq!(UnifiedMemorySetup::new(24 * GIB).gpu_memory_limit, 16 * GIB);
+        assert_eq!(UnifiedMemorySetup::new(64 * GIB).gpu_memory_limit, 48 * GIB);
+    }
+
+    #[test]
+    fn test_estimate_unified_memory_limit() {
+        let llama_config = create_llama_config();
+        let m3_max = AppleChipSpec::from_name("Apple M3 Max").unwrap();
+        let q4 = Precision::GGUF(QuantType::Q4_K);
+        // The 4-bit weights of a 70B model fit the 36 GiB of unified memory the GPU can wire
+        // out of 48 GiB, not the 21 GiB out of 32 GiB
+        let setup = UnifiedMemorySetup::new(48 * GIB);
+        let estimate = estimate_unified_memory(&llama_config, &m3_max, q4, 1, 4096, &setup);
+        assert_eq!(estimate.available(), 36 * GIB);
+        assert!(estimate.fits());
+        let mut setup = UnifiedMemorySetup::new(32 * GIB);
+        let estimate = estimate_unified_memory(&llama_config, &m3_max, q4, 1, 4096, &setup);
+        assert!(!estimate.fits());
+
+        // Unless the wired limit is raised
+        setup.gpu_memory_limit = 28 * GIB;
+        let estimate = estimate_unified_memory(&llama_config, &m3_max, q4, 1, 4096, &setup);
+        assert!(estimate.fits());
+    }
+
+    #[test]
+    fn test_estimate_unified_memory_speed() {
+        let llama_config = create_llama_config();
+        let setup = UnifiedMemorySetup::new(192 * GIB);
+        let q4 = Precision::GGUF(QuantType::Q4_K);
+        let speed = |chip: &str| {
+            let chip = AppleChipSpec::from_name(chip).unwrap();
+            estimate_unified_memory(&llama_config, &chip, q4, 1, 2048, &setup)
+                .throughput()
+                .decode_tokens_per_second()
+        };
+        // The decoding follows the bandwidth of the chip
+        assert!((speed("M2 Ultra") / speed("M2 Max") - 2.0).abs() < 0.01);
+        assert!(speed("M2 Ultra") > 5.0);
+    }
+}
@@ -0,0 +1,284 @@
+//! Speech recognition (Whisper) inference estimates
+use crate::estimate::activations::activation_bytes;
+use crate::estimate::{
+    cache_precision, quantized_weights_bytes, OverheadModel, BANDWIDTH_EFFICIENCY,
+    COMPUTE_EFFICIENCY,
+};
+use crate::models::{ModelConfigTrait, Precision, WhisperModelConfig};
+use crate::specs::GpuSpec;
+
+/// Length of the audio chunks Whisper transcribes, in seconds
+pub const CHUNK_SECONDS: f64 = 30.0;
+/// Average number of tokens transcribed from a chunk of speech (about 150 spoken words)
+pub const TOKENS_PER_CHUNK: i32 = 200;
+
+/// A struct representing the memory needed to transcribe audio with a Whisper model, in bytes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AsrEstimate {
+    /// The number of 30 seconds chunks the audio is split into
+    num_chunks: i32,
+    /// The number of chunks transcribed together
+    batch_size: i32,
+    /// The memory of the model weights
+    weights: i64,
+    /// The peak memory of the encoder activations for a batch of chunks
+    encoder_activations: i64,
+    /// The memory of the encoder outputs the decoder attends to
+    encoder_states: i64,
+    /// The memory of the decoder self-attention and cross-attention caches
+    kv_cache: i64,
+    /// The memory of the framework (CUDA context, allocator fragmentation)
+    overhead: i64,
+}
+
+/// AsrEstimate implementation
+impl AsrEstimate {
+    /// Build a new `AsrEstimate` struct based on the provided parameters
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        num_chunks: i32,
+        batch_size: i32,
+        weights: i64,
+        encoder_activations: i64,
+        encoder_states: i64,
+        kv_cache: i64,
+        overhead: i64,
+    ) -> AsrEstimate {
+        AsrEstimate {
+            num_chunks,
+            batch_size,
+            weights,
+            encoder_activations,
+            encoder_states,
+            kv_cache,
+            overhead,
+        }
+    }
+    /// Returns the number of 30 seconds chunks the audio is split into
+    pub fn num_chunks(&self) -> i32 {
+        self.num_chunks
+    }
+    /// Returns the number of chunks transcribed together
+    pub fn batch_size(&self) -> i32 {
+        self.batch_size
+    }
+    /// Returns the number of batches needed to transcribe the whole audio
+    pub fn num_batches(&self) -> i32 {
+        match self.batch_size > 0 {
+            true => (self.num_chunks + self.batch_size - 1) / self.batch_size,
+            false => 0,
+        }
+    }
+    /// Returns the memory of the model weights
+    pub fn weights(&self) -> i64 {
+        self.weights
+    }
+    /// Returns the peak memory of the encoder activations
+    pub fn encoder_activations(&self) -> i64 {
+        self.encoder_activations
+    }
+    /// Returns the memory of the encoder outputs
+    pub fn encoder_states(&self) -> i64 {
+        self.encoder_states
+    }
+    /// Returns the memory of the decoder caches
+    pub fn kv_cache(&self) -> i64 {
+        self.kv_cache
+    }
+    /// Returns the memory of the framework
+    pub fn overhead(&self) -> i64 {
+        self.overhead
+    }
+    /// Returns the total memory needed to transcribe a batch of chunks
+    pub fn total(&self) -> i64 {
+        self.weights
+            + self.encoder_activations
+            + self.encoder_states
+            + self.kv_cache
+            + self.overhead
+    }
+}
+
+/// Returns the number of 30 seconds chunks `audio_seconds` of audio are split into
+fn num_chunks(audio_seconds: f64) -> i32 {
+    (audio_seconds.max(0.0) / CHUNK_SECONDS).ceil() as i32
+}
+
+/// Estimate the memory needed to transcribe `audio_seconds` of audio with a Whisper model, the
+/// audio being split into 30 seconds chunks transcribed `batch_size` at a time.
+///
+/// The encoder runs the `max_source_positions` frames of every chunk of the batch at once. The
+/// decoder keeps the encoder outputs, the keys and values of its cross-attention over them, and a
+/// self-attention KV cache of up to `max_target_positions` tokens per chunk.
+pub fn estimate_asr(
+    config: &WhisperModelConfig,
+    precision: Precision,
+    audio_seconds: f64,
+    batch_size: i32,
+) -> AsrEstimate {
+    estimate_asr_with_overhead(
+        config,
+        precision,
+        audio_seconds,
+        batch_size,
+        &OverheadModel::new(),
+    )
+}
+
+/// Estimate the memory needed to transcribe audio with a Whisper model, with the given overhead
+/// model (see `estimate_asr`)
+pub fn estimate_asr_with_overhead(
+    config: &WhisperModelConfig,
+    precision: Precision,
+    audio_seconds: f64,
+    batch_size: i32,
+    overhead: &OverheadModel,
+) -> AsrEstimate {
+    let num_chunks = num_chunks(audio_seconds);
+    let batch_size = batch_size.max(1).min(num_chunks);
+    let chunks = batch_size as i64;
+    let frames = config.max_source_positions() as i64;
+    let hidden_size = config.hidden_size() as i64;
+    let bytes = activation_bytes(precision);
+    let cache_bytes = cache_precision(precision).bytes_per_param();
+
+    let weights = quantized_weights_bytes(config, precision);
+    let encoder_activations =
+        chunks * frames * (4 * hidden_size + 2 * config.intermediate_size() as i64) * bytes;
+    let encoder_states = chunks * frames * hidden_size * bytes;
+    // Keys and values of every decoder layer, over the encoder frames and the generated tokens
+    let cached_positions = frames + config.max_position_embeddings() as i64;
+    let kv_cache = (2.0
+        * config.num_decoder_layers() as f64
+        * (chunks * cached_positions * hidden_size) as f64
+        * cache_bytes)
+        .ceil() as i64;
+
+    AsrEstimate::new(
+        num_chunks,
+        batch_size,
+        weights,
+        encoder_activations,
+        encoder_states,
+        kv_cache,
+        overhead.overhead_bytes(weights, encoder_activations + encoder_states + kv_cache),
+    )
+}
+
+/// Estimate how many seconds of audio a Whisper model transcribes every second on a GPU (the
+/// inverse of the real-time factor), in batches of `batch_size` chunks, with a roofline model.
+///
+/// Encoding a batch is compute-bound. The decoder then generates `TOKENS_PER_CHUNK` tokens per
+/// chunk, every step taking the longest of its compute time and of the time to read the decoder
+/// weights and caches.
+pub fn estimate_asr_speed(
+    config: &WhisperModelConfig,
+    device: &GpuSpec,
+    precision: Precision,
+    batch_size: i32,
+) -> f64 {
+    let peak_flops = device.fp16_tflops() * 1e12 * COMPUTE_EFFICIENCY;
+    let bandwidth = device.memory_bandwidth() * BANDWIDTH_EFFICIENCY;
+    let batch_size = batch_size.max(1);
+    let estimate = estimate_asr(
+        config,
+        precision,
+        batch_size as f64 * CHUNK_SECONDS,
+        batch_size,
+    );
+
+    let hidden_size = config.hidden_size() as f64;
+    let ffn = config.intermediate_size() as f64;
+    let frames = config.max_source_positions() as f64;
+    let encoder_layers = config.num_encoder_layers() as f64;
+    let decoder_layers = config.num_decoder_layers() as f64;
+
+    // Self-attention and MLP weights, and the attention over every frame
+    let encoder_flops = encoder_layers
+        * (2.0 * (4.0 * hidden_size * hidden_size + 2.0 * hidden_size * ffn)
+            + 4.0 * frames * hidden_size);
+    let encode_time = batch_size as f64 * frames * encoder_flops / peak_flops;
+
+    // Self-attention, cross-attention and MLP weights, the attention over the frames, and the
+    // LM head
+    let decoder_flops = decoder_layers
+        * (2.0 * (8.0 * hidden_size * hidden_size + 2.0 * hidden_size * ffn)
+            + 4.0 * frames * hidden_size)
+        + 2.0 * config.vocab_size() as f64 * hidden_size;
+    let decoder_share = match encoder_layers + decoder_layers > 0.0 {
+        true => decoder_layers / (encoder_layers + decoder_layers),
+        false => 0.0,
+    };
+    let decoder_bytes = estimate.weights() as f64 * decoder_share + estimate.kv_cache() as f64;
+    let step_time = (batch_size as f64 * decoder_flops / peak_flops).max(decoder_bytes / bandwidth);
+    let decode_time = TOKENS_PER_CHUNK as f64 * step_time;
+
+    batch_size as f64 * CHUNK_SECONDS / (encode_time + decode_time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{BartParams, Dtype, ModelLibraries};
+
+    fn create_whisper_config() -> WhisperModelConfig {
+        // Whisper large-v3
+        WhisperModelConfig::new(
+            BartParams::new(1280, 32, 32, 5120, 5120, 20, 448),
+            1500,
+            51866,
+            true,
+            Dtype::F16,
+            "whisper".to_string(),
+            vec![ModelLibraries::PyTorch],
+        )
+    }
+
+    #[test]
+    fn test_estimate_asr_chunks() {
+        let whisper_config = create_whisper_config();
+        // 10 minutes of audio are 20 chunks, transcribed in 3 batches of 8
+        let estimate = estimate_asr(&whisper_config, Precision::FP16, 600.0, 8);
+        assert_eq!(estimate.num_chunks(), 20);
+        assert_eq!(estimate.batch_size(), 8);
+        assert_eq!(estimate.num_batches(), 3);
+
+        // A short clip is a single chunk, whatever the batch size
+        let estimate = estimate_asr(&whisper_config, Precision::FP16, 5.0, 8);
+        assert_eq!(estimate.num_chunks(), 1);
+        assert_eq!(estimate.batch_size(), 1);
+        assert_eq!(estimate.num_batches(), 1);
+    }
+
+    #[test]
+    fn test_estimate_asr_memory() {
+        let whisper_config = create_whisper_config();
+        let estimate = estimate_asr(&whisper_config, Precision::FP16, 30.0, 1);
+        assert_eq!(estimate.weights(), 2 * whisper_config.num_parameters());
+        assert_eq!(estimate.encoder_states(), 1500 * 1280 * 2);
+        // 32 decoder layers caching 1500 frames and 448 tokens
+        assert_eq!(estimate.kv_cache(), 2 * 32 * (1500 + 448) * 1280 * 2);
+
+        // The batch size scales everything but the weights
+        let batched = estimate_asr(&whisper_config, Precision::FP16, 600.0, 16);
+        assert_eq!(batched.weights(), estimate.weights());
+        assert_eq!(
+            batched.encoder_activations(),
+            16 * estimate.encoder_activations()
+        );
+        assert_eq!(batched.kv_cache(), 16 * estimate.kv_cache());
+        assert!(batched.total() > estimate.total());
+    }
+
+    #[test]
+    fn test_estimate_asr_speed() {
+        let whisper_config = create_whisper_config();
+        let a100 = GpuSpec::from_name("NVIDIA A100-SXM4-80GB").unwrap();
+        let single = estimate_asr_speed(&whisper_config, &a100, Precision::FP16, 1);
+        let batched = estimate_asr_speed(&whisper_config, &a100, Precision::FP16, 16);
+        // Faster than real time, and batching the chunks amortizes the decoder weights reads, not the
+        // reads of the cross-attention caches
+        assert!(single > 1.0);
+        assert!(batched > 2.0 * single);
+    }
+}
@@ -0,0 +1,222 @@
+//! Attention implementation memory and speed estimates
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::estimate::activations::activation_bytes;
+use crate::estimate::{BANDWIDTH_EFFICIENCY, COMPUTE_EFFICIENCY};
+use crate::models::{ModelConfigTrait, ModelLibraries, Precision};
+use crate::specs::GpuSpec;
+
+/// Largest head dimension the FlashAttention-2 kernels support
+pub const FLASH_ATTENTION_MAX_HEAD_DIM: i32 = 256;
+
+/// Enumerate the implementations of the attention, named after the transformers
+/// `attn_implementation` values
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AttentionImpl {
+    /// FlashAttention-2, fused kernels never materializing the attention scores
+    FlashAttention2,
+    /// The naive attention, materializing the scores and the probabilities of every head
+    Eager,
+}
+
+/// AttentionImpl implementation
+impl AttentionImpl {
+    /// Returns the best attention a model runs with in the given precision on a GPU of the given
+    /// compute capability: FlashAttention-2 needs an Ampere GPU or newer (8.0), 16-bit
+    /// activations, a head dimension of at most 256 and a PyTorch checkpoint, the eager attention
+    /// otherwise
+    pub fn available(
+        config: &dyn ModelConfigTrait,
+        precision: Precision,
+        compute_capability: (u32, u32),
+    ) -> AttentionImpl {
+        let pytorch = config.available_libraries().iter().any(|library| {
+            matches!(
+                library,
+                ModelLibraries::PyTorch | ModelLibraries::Transformers
+            )
+        });
+        match compute_capability >= (8, 0)
+            && activation_bytes(precision) == 2
+            && config.head_dim() <= FLASH_ATTENTION_MAX_HEAD_DIM
+            && pytorch
+        {
+            true => AttentionImpl::FlashAttention2,
+            false => AttentionImpl::Eager,
+        }
+    }
+}
+
+/// Implement the display of the AttentionImpl enum
+impl Display for AttentionImpl {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            AttentionImpl::FlashAttention2 => write!(f, "flash_attention_2"),
+            AttentionImpl::Eager => write!(f, "eager"),
+        }
+    }
+}
+
+/// A struct representing the cost of the attention of a prompt with a given implementation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AttentionEstimate {
+    /// The implementation of the attention
+    implementation: AttentionImpl,
+    /// The peak memory of the attention of a layer, in bytes
+    memory: i64,
+    /// The time the attention scores of all the layers take, in seconds, `None` without the
+    /// specifications of the GPU
+    prefill_seconds: Option<f64>,
+}
+
+/// AttentionEstimate implementation
+impl AttentionEstimate {
+    /// Build a new `AttentionEstimate` struct based on the provided parameters
+    pub fn new(
+        implementation: AttentionImpl,
+        memory: i64,
+        prefill_seconds: Option<f64>,
+    ) -> AttentionEstimate {
+        AttentionEstimate {
+            implementation,
+            memory,
+            prefill_seconds,
+        }
+    }
+    /// Returns the implementation of the attention
+    pub fn implementation(&self) -> AttentionImpl {
+        self.implementation
+    }
+    /// Returns the peak memory of the attention of a layer, in bytes
+    pub fn memory(&self) -> i64 {
+        self.memory
+    }
+    /// Returns the time the attention scores of all the layers take, in seconds
+    pub fn prefill_seconds(&self) -> Option<f64> {
+        self.prefill_seconds
+    }
+}
+
+/// Estimate the memory and the time the attention scores of a prompt of `batch_size` sequences
+/// of `seq_len` tokens take with the given implementation, the projections being the same for
+/// every implementation are left out.
+///
+/// The eager attention materializes the scores of every head, their FP32 softmax and the
+/// probabilities cast back, and reads and writes them from the GPU memory: it is quadratic in
+/// memory and often bandwidth-bound. FlashAttention-2 only keeps the FP32 softmax statistics of
+/// every row, and skips the masked half of the causal scores.
+pub fn estimate_attention(
+    config: &dyn ModelConfigTrait,
+    device: Option<&GpuSpec>,
+    precision: Precision,
+    batch_size: i32,
+    seq_len: i32,
+    implementation: AttentionImpl,
+) -> AttentionEstimate {
+    let bytes = activation_bytes(precision) as f64;
+    let rows = batch_size.max(1) as f64 * config.num_attention_heads() as f64 * seq_len as f64;
+    let scores = rows * seq_len as f64;
+    let flops = 4.0 * scores * config.head_dim() as f64;
+
+    let (memory, flops, traffic) = match implementation {
+        AttentionImpl::FlashAttention2 => (rows * 4.0, flops / 2.0, 0.0),
+        AttentionImpl::Eager => (
+            scores * (2.0 * bytes + 4.0),
+            flops,
+            scores * (4.0 * bytes + 8.0),
+        ),
+    };
+    let prefill_seconds = device.map(|device| {
+        let peak_flops = device.fp16_tflops() * 1e12 * COMPUTE_EFFICIENCY;
+        let bandwidth = device.memory_bandwidth() * BANDWIDTH_EFFICIENCY;
+        config.num_hidden_layers() as f64 * (flops / peak_flops).max(traffic / bandwidth)
+    });
+    AttentionEstimate::new(implementation, memory.ceil() as i64, prefill_seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate::test_utils::{create_llama_config, create_llama_config_with_libraries};
+    use crate::models::LlamaParams;
+
+    #[test]
+    fn test_attention_available() {
+        let llama_config = create_llama_config();
+        assert_eq!(
+            AttentionImpl::available(&llama_config, Precision::BF16, (8, 6)),
+            AttentionImpl::FlashAttention2
+        );
+        // Turing GPUs and FP32 activations
+        assert_eq!(
+            AttentionImpl::available(&llama_config, Precision::FP16, (7, 5)),
+            AttentionImpl::Eager
+        );
+        assert_eq!(
+            AttentionImpl::available(&llama_config, Precision::FP32, (9, 0)),
+            AttentionImpl::Eager
+        );
+        // Without a PyTorch checkpoint
+        let gguf_config = create_llama_config_with_libraries(
+            LlamaParams::new(4096, 14336, 8192, 32, 32, Some(8), None),
+            vec![],
+        );
+        assert_eq!(
+            AttentionImpl::available(&gguf_config, Precision::BF16, (9, 0)),
+            AttentionImpl::Eager
+        );
+        assert_eq!(
+            AttentionImpl::FlashAttention2.to_string(),
+            "flash_attention_2"
+        );
+    }
+
+    #[test]
+    fn test_estimate_attention_memory() {
+        let llama_config = create_llama_config();
+        let flash = estimate_attention(
+            &llama_config,
+            None,
+            Precision::BF16,
+            1,
+            4096,
+            AttentionImpl::FlashAttention2,
+        );
+        let eager = estimate_attention(
+            &llama_config,
+            None,
+            Precision::BF16,
+            1,
+            4096,
+            AttentionImpl::Eager,
+        );
+        // The softmax statistics of 32 heads of 4096 rows, against their 4096 × 4096 scores
+        assert_eq!(flash.memory(), 32 * 4096 * 4);
+        assert_eq!(eager.memory(), 32 * 4096 * 4096 * 8);
+        assert_eq!(flash.prefill_seconds(), None);
+    }
+
+    #[test]
+    fn test_estimate_attention_speed() {
+        let llama_config = create_llama_config();
+        let a100 = GpuSpec::from_name("NVIDIA A100-SXM4-80GB").unwrap();
+        let flash = estimate_attention(
+            &llama_config,
+            Some(&a100),
+            Precision::BF16,
+            1,
+            8192,
+            AttentionImpl::FlashAttention2,
+        );
+        let eager = estimate_attention(
+            &llama_config,
+            Some(&a100),
+            Precision::BF16,
+            1,
+            8192,
+            AttentionImpl::Eager,
+        );
+        // The eager attention is bandwidth-bound on the scores
+        assert!(eager.prefill_seconds().unwrap() > 4.0 * flash.prefill_seconds().unwrap());
+    }
+}
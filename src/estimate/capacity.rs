@@ -0,0 +1,251 @@
+//! Serving capacity planning against a latency target
+use crate::estimate::{
+    cache_precision, estimate_serving, estimate_throughput, kv_cache_bytes_per_token,
+    ServingEstimate, ServingSetup,
+};
+use crate::models::{ModelConfigTrait, Precision};
+use crate::specs::GpuSpec;
+
+/// Default longest time to first token of a request, in seconds
+pub const DEFAULT_MAX_TTFT: f64 = 2.0;
+/// Default longest time between two generated tokens of a request, in seconds (10 tokens/s)
+pub const DEFAULT_MAX_TPOT: f64 = 0.1;
+
+/// A struct representing the traffic an inference server has to sustain and its latency target
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ServingSlo {
+    /// The number of requests generated at once
+    pub concurrency: i32,
+    /// The number of tokens of the prompt of a request
+    pub prompt_tokens: i32,
+    /// The number of tokens generated for a request
+    pub output_tokens: i32,
+    /// The longest time to first token, in seconds
+    pub max_ttft: f64,
+    /// The longest time between two generated tokens, in seconds
+    pub max_tpot: f64,
+}
+
+/// ServingSlo implementation
+impl ServingSlo {
+    /// Build a new `ServingSlo` with a 2 s time to first token and 10 tokens/s per request
+    pub fn new(concurrency: i32, prompt_tokens: i32, output_tokens: i32) -> ServingSlo {
+        ServingSlo {
+            concurrency,
+            prompt_tokens,
+            output_tokens,
+            max_ttft: DEFAULT_MAX_TTFT,
+            max_tpot: DEFAULT_MAX_TPOT,
+        }
+    }
+    /// Returns the longest sequence of a request, prompt and generated tokens
+    pub fn seq_len(&self) -> i32 {
+        self.prompt_tokens.max(0) + self.output_tokens.max(0)
+    }
+}
+
+/// A struct representing whether a server sustains a traffic within its latency target
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CapacityPlan {
+    /// The KV cache memory the concurrent requests need on every GPU, in bytes
+    kv_cache: i64,
+    /// How the server splits the memory of a GPU, `None` when a single request doesn't fit
+    serving: Option<ServingEstimate>,
+    /// Whether the scheduler runs all the requests at once
+    schedulable: bool,
+    /// The time to first token, in seconds, `None` without the specifications of the GPU
+    ttft: Option<f64>,
+    /// The time between two generated tokens, in seconds, `None` without the specifications of
+    /// the GPU
+    tpot: Option<f64>,
+}
+
+/// CapacityPlan implementation
+impl CapacityPlan {
+    /// Build a new `CapacityPlan` struct based on the provided parameters
+    pub fn new(
+        kv_cache: i64,
+        serving: Option<ServingEstimate>,
+        schedulable: bool,
+        ttft: Option<f64>,
+        tpot: Option<f64>,
+    ) -> CapacityPlan {
+        CapacityPlan {
+            kv_cache,
+            serving,
+            schedulable,
+            ttft,
+            tpot,
+        }
+    }
+    /// Returns the KV cache memory the concurrent requests need on every GPU
+    pub fn kv_cache(&self) -> i64 {
+        self.kv_cache
+    }
+    /// Returns how the server splits the memory of a GPU
+    pub fn serving(&self) -> Option<ServingEstimate> {
+        self.serving
+    }
+    /// Returns whether the scheduler runs all the requests at once, without preempting any
+    pub fn schedulable(&self) -> bool {
+        self.schedulable
+    }
+    /// Returns the time to first token
+    pub fn ttft(&self) -> Option<f64> {
+        self.ttft
+    }
+    /// Returns the time between two generated tokens
+    pub fn tpot(&self) -> Option<f64> {
+        self.tpot
+    }
+    /// Returns whether the server runs all the requests within the latency target, `false` when
+    /// the latencies are unknown
+    pub fn meets_slo(&self, slo: &ServingSlo) -> bool {
+        match (self.ttft, self.tpot) {
+            (Some(ttft), Some(tpot)) => {
+                self.schedulable && ttft <= slo.max_ttft && tpot <= slo.max_tpot
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Plan whether a vLLM server on GPUs of `device_memory` bytes sustains `slo.concurrency`
+/// requests at once, each of `slo.prompt_tokens` tokens generating `slo.output_tokens` ones, and
+/// within which latencies.
+///
+/// Every request holds the KV cache blocks of its whole sequence. The requests are schedulable
+/// when the blocks allocated by the server (see `estimate_serving`) hold all of them and the
+/// scheduler runs as many sequences in a step. The latencies come from the roofline model of
+/// `estimate_throughput`, the tensor parallel GPUs sharing the work evenly: a step decodes a token
+/// of every request, and a new prompt waits for the running step before its prefill.
+pub fn plan_capacity(
+    config: &dyn ModelConfigTrait,
+    device: Option<&GpuSpec>,
+    precision: Precision,
+    device_memory: i64,
+    setup: &ServingSetup,
+    slo: &ServingSlo,
+) -> CapacityPlan {
+    let concurrency = slo.concurrency.max(1);
+    let seq_len = slo.seq_len().max(1);
+    let setup = ServingSetup {
+        max_model_len: seq_len,
+        ..*setup
+    };
+    let tp_size = setup.tp_size.max(1);
+
+    let num_key_value_heads = config.num_key_value_heads().max(1);
+    let kv_heads_per_gpu = (num_key_value_heads + tp_size - 1) / tp_size;
+    let bytes_per_token = kv_cache_bytes_per_token(config, cache_precision(precision))
+        * kv_heads_per_gpu as f64
+        / num_key_value_heads as f64;
+    let block_size = setup.block_size.max(1);
+    let blocks_per_request = (seq_len + block_size - 1) / block_size;
+    let kv_cache =
+        (concurrency as f64 * blocks_per_request as f64 * block_size as f64 * bytes_per_token)
+            .ceil() as i64;
+
+    let serving = estimate_serving(config, precision, device_memory, &setup);
+    let schedulable = serving.is_some_and(|serving| {
+        serving.num_blocks() >= concurrency as i64 * blocks_per_request as i64
+            && concurrency <= setup.max_num_seqs
+    });
+
+    let latencies = device.map(|device| {
+        let decode = estimate_throughput(config, device, precision, concurrency, seq_len);
+        let tpot = concurrency as f64 / (decode.decode_tokens_per_second() * tp_size as f64);
+        let prefill = estimate_throughput(config, device, precision, 1, slo.prompt_tokens);
+        let ttft = slo.prompt_tokens.max(1) as f64
+            / (prefill.prefill_tokens_per_second() * tp_size as f64)
+            + tpot;
+        (ttft, tpot)
+    });
+    CapacityPlan::new(
+        kv_cache,
+        serving,
+        schedulable,
+        latencies.map(|(ttft, _)| ttft),
+        latencies.map(|(_, tpot)| tpot),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate::test_utils::create_llama_config;
+
+    #[test]
+    fn test_plan_capacity_kv_cache() {
+        let llama_config = create_llama_config();
+        let a100 = GpuSpec::from_name("NVIDIA A100-SXM4-80GB").unwrap();
+        let slo = ServingSlo::new(32, 1000, 500);
+        let plan = plan_capacity(
+            &llama_config,
+            Some(&a100),
+            Precision::BF16,
+            a100.memory() as i64,
+            &ServingSetup::new(8192),
+            &slo,
+        );
+        // 94 blocks of 16 tokens of 128 KiB per request
+        assert_eq!(plan.kv_cache(), 32 * 94 * 16 * 128 * 1024);
+        assert!(plan.schedulable());
+        assert!(plan.meets_slo(&slo));
+
+        // Without the specifications of the GPU, the latencies are unknown
+        let plan = plan_capacity(
+            &llama_config,
+            None,
+            Precision::BF16,
+            a100.memory() as i64,
+            &ServingSetup::new(8192),
+            &slo,
+        );
+        assert!(plan.schedulable());
+        assert!(!plan.meets_slo(&slo));
+    }
+
+    #[test]
+    fn test_plan_capacity_not_schedulable() {
+        let llama_config = create_llama_config();
+        let l4 = GpuSpec::from_name("NVIDIA L4").unwrap();
+        // 256 requests of 4096 tokens need 128 GiB of KV cache
+        let slo = ServingSlo::new(256, 3072, 1024);
+        let plan = plan_capacity(
+            &llama_config,
+            Some(&l4),
+            Precision::BF16,
+            l4.memory() as i64,
+            &ServingSetup::new(4096),
+            &slo,
+        );
+        assert!(plan.serving().is_some());
+        assert!(!plan.schedulable());
+        assert!(!plan.meets_slo(&slo));
+    }
+
+    #[test]
+    fn test_plan_capacity_latency() {
+        let llama_config = create_llama_config();
+        let a100 = GpuSpec::from_name("NVIDIA A100-SXM4-80GB").unwrap();
+        let plan = |concurrency: i32| {
+            plan_capacity(
+                &llama_config,
+                Some(&a100),
+                Precision::BF16,
+                a100.memory() as i64,
+                &ServingSetup::new(4096),
+                &ServingSlo::new(concurrency, 2048, 256),
+            )
+        };
+        // More requests share every step, each one decodes slower
+        assert!(plan(64).tpot().unwrap() > plan(1).tpot().unwrap());
+        assert!(plan(64).ttft().unwrap() > plan(1).ttft().unwrap());
+
+        // A strict latency target is missed under load
+        let mut slo = ServingSlo::new(64, 2048, 256);
+        slo.max_tpot = plan(1).tpot().unwrap();
+        assert!(!plan(64).meets_slo(&slo));
+    }
+}
@@ -0,0 +1,110 @@
+//! Serving cost estimates
+use serde::Serialize;
+
+use crate::estimate::Throughput;
+
+/// Enumerate the ways the hardware serving a model is paid for
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pricing {
+    /// A rented instance, paid by the hour (US dollars per hour)
+    Instance {
+        /// The hourly price of the instance
+        hourly_price: f64,
+    },
+    /// An owned machine, paid by its electricity (US dollars per kWh), drawing `power` watts
+    Electricity {
+        /// The price of a kWh
+        price_per_kwh: f64,
+        /// The power drawn by the hardware while serving, in watts
+        power: f64,
+    },
+}
+
+/// Pricing implementation
+impl Pricing {
+    /// Returns the cost of an hour of serving, in US dollars
+    pub fn hourly_cost(&self) -> f64 {
+        match self {
+            Pricing::Instance { hourly_price } => *hourly_price,
+            Pricing::Electricity {
+                price_per_kwh,
+                power,
+            } => power / 1000.0 * price_per_kwh,
+        }
+    }
+}
+
+/// A struct representing the cost of serving a model, in US dollars per million tokens
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct ServingCost {
+    /// The cost of a million prompt tokens
+    input_per_million: f64,
+    /// The cost of a million generated tokens
+    output_per_million: f64,
+}
+
+/// ServingCost implementation
+impl ServingCost {
+    /// Build a new `ServingCost` struct based on the provided parameters
+    pub fn new(input_per_million: f64, output_per_million: f64) -> ServingCost {
+        ServingCost {
+            input_per_million,
+            output_per_million,
+        }
+    }
+    /// Returns the cost of a million prompt tokens
+    pub fn input_per_million(&self) -> f64 {
+        self.input_per_million
+    }
+    /// Returns the cost of a million generated tokens
+    pub fn output_per_million(&self) -> f64 {
+        self.output_per_million
+    }
+}
+
+/// Estimate the cost of serving a model at the given throughput, the hardware being busy all the
+/// time. The prompt tokens are billed at the prefill speed, the generated ones at the decode
+/// speed.
+pub fn estimate_serving_cost(throughput: &Throughput, pricing: Pricing) -> ServingCost {
+    let per_million = |tokens_per_second: f64| match tokens_per_second > 0.0 {
+        true => pricing.hourly_cost() / (tokens_per_second * 3600.0) * 1e6,
+        false => f64::INFINITY,
+    };
+    ServingCost::new(
+        per_million(throughput.prefill_tokens_per_second()),
+        per_million(throughput.decode_tokens_per_second()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pricing_hourly_cost() {
+        assert_eq!(Pricing::Instance { hourly_price: 2.5 }.hourly_cost(), 2.5);
+        // 450 W at 0.20 $/kWh
+        let electricity = Pricing::Electricity {
+            price_per_kwh: 0.2,
+            power: 450.0,
+        };
+        assert!((electricity.hourly_cost() - 0.09).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_estimate_serving_cost() {
+        // 3.6 $/h, 10000 prompt tokens/s and 100 generated tokens/s
+        let throughput = Throughput::new(10000.0, 100.0);
+        let cost = estimate_serving_cost(&throughput, Pricing::Instance { hourly_price: 3.6 });
+        assert!((cost.input_per_million() - 0.1).abs() < 1e-9);
+        assert!((cost.output_per_million() - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_estimate_serving_cost_without_throughput() {
+        let throughput = Throughput::new(1000.0, 0.0);
+        let cost = estimate_serving_cost(&throughput, Pricing::Instance { hourly_price: 1.0 });
+        assert!(cost.input_per_million().is_finite());
+        assert_eq!(cost.output_per_million(), f64::INFINITY);
+    }
+}
@@ -0,0 +1,208 @@
+//! CPU-only (llama.cpp) inference estimates
+use crate::estimate::activations::activation_bytes;
+use crate::estimate::{
+    cache_precision, estimate_kv_cache, flops_per_token, quantized_weights_bytes, Phase,
+    Throughput, BANDWIDTH_EFFICIENCY,
+};
+use crate::models::{ModelConfigTrait, Precision};
+
+/// Bandwidth of dual-channel DDR4-3200, the memory of most desktops and laptops (bytes/s)
+pub const DEFAULT_CPU_MEMORY_BANDWIDTH: f64 = 51.2e9;
+/// Sustained throughput of the llama.cpp quantized matrix multiplications on a core with AVX2
+/// (FLOP/s)
+pub const CPU_FLOPS_PER_CORE: f64 = 50e9;
+/// Memory the operating system and the other processes keep, left out of the RAM the model can
+/// use (bytes)
+pub const OS_RESERVED_BYTES: i64 = 2 * 1024 * 1024 * 1024;
+/// Number of tokens llama.cpp evaluates at once when processing a prompt (`n_ubatch`)
+pub const LLAMA_CPP_UBATCH: i32 = 512;
+
+/// A struct representing the CPU and the memory a model runs on without GPU
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CpuSetup {
+    /// The RAM of the system, in bytes
+    pub ram: i64,
+    /// The number of physical cores, llama.cpp runs a thread per core
+    pub cpu_cores: i32,
+    /// The bandwidth of the RAM, in bytes/s
+    pub memory_bandwidth: f64,
+    /// The memory the operating system and the other processes keep, in bytes
+    pub os_reserved: i64,
+}
+
+/// CpuSetup implementation
+impl CpuSetup {
+    /// Build a new `CpuSetup` with dual-channel DDR4-3200 memory
+    pub fn new(ram: i64, cpu_cores: i32) -> CpuSetup {
+        CpuSetup {
+            ram,
+            cpu_cores,
+            memory_bandwidth: DEFAULT_CPU_MEMORY_BANDWIDTH,
+            os_reserved: OS_RESERVED_BYTES,
+        }
+    }
+}
+
+/// A struct representing the memory and the speed of a model running on the CPU
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CpuEstimate {
+    /// The memory of the memory-mapped weights, in bytes
+    weights: i64,
+    /// The memory of the KV cache, in bytes
+    kv_cache: i64,
+    /// The memory of the compute buffer of a batch of prompt tokens, in bytes
+    compute_buffer: i64,
+    /// The RAM the model can use, in bytes
+    available: i64,
+    /// The expected generation speed
+    throughput: Throughput,
+}
+
+/// CpuEstimate implementation
+impl CpuEstimate {
+    /// Build a new `CpuEstimate` struct based on the provided parameters
+    pub fn new(
+        weights: i64,
+        kv_cache: i64,
+        compute_buffer: i64,
+        available: i64,
+        throughput: Throughput,
+    ) -> CpuEstimate {
+        CpuEstimate {
+            weights,
+            kv_cache,
+            compute_buffer,
+            available,
+            throughput,
+        }
+    }
+    /// Returns the memory of the weights
+    pub fn weights(&self) -> i64 {
+        self.weights
+    }
+    /// Returns the memory of the KV cache
+    pub fn kv_cache(&self) -> i64 {
+        self.kv_cache
+    }
+    /// Returns the memory of the compute buffer
+    pub fn compute_buffer(&self) -> i64 {
+        self.compute_buffer
+    }
+    /// Returns the RAM the model can use
+    pub fn available(&self) -> i64 {
+        self.available
+    }
+    /// Returns the expected generation speed
+    pub fn throughput(&self) -> Throughput {
+        self.throughput
+    }
+    /// Returns the total memory needed to run the model
+    pub fn total(&self) -> i64 {
+        self.weights + self.kv_cache + self.compute_buffer
+    }
+    /// Returns whether the model fits the RAM without swapping
+    pub fn fits(&self) -> bool {
+        self.total() <= self.available
+    }
+}
+
+/// Estimate whether a model runs on the CPU alone like llama.cpp does, for `batch_size`
+/// sequences of `seq_len` tokens, and how fast.
+///
+/// The weights are memory-mapped and stay in the page cache, the KV cache and a compute buffer of
+/// `LLAMA_CPP_UBATCH` tokens are allocated next to them. Generating a token reads all the
+/// weights and the KV cache from the RAM, so it is bound by the memory bandwidth whatever the
+/// number of cores; the prompt is processed in batches bound by the compute of the cores.
+pub fn estimate_cpu_inference(
+    config: &dyn ModelConfigTrait,
+    precision: Precision,
+    batch_size: i32,
+    seq_len: i32,
+    setup: &CpuSetup,
+) -> CpuEstimate {
+    let batch_size = batch_size.max(1);
+    let weights = quantized_weights_bytes(config, precision);
+    let kv_cache = estimate_kv_cache(config, batch_size, seq_len, cache_precision(precision));
+    // llama.cpp computes the activations and the logits of a batch in FP32
+    let ubatch = seq_len.clamp(1, LLAMA_CPP_UBATCH) as i64;
+    let compute_buffer = ubatch
+        * (4 * config.hidden_size() as i64 + 2 * config.intermediate_size() as i64)
+        * activation_bytes(Precision::FP32)
+        + batch_size as i64 * config.vocab_size() as i64 * 4;
+
+    let peak_flops = setup.cpu_cores.max(1) as f64 * CPU_FLOPS_PER_CORE;
+    let bandwidth = setup.memory_bandwidth * BANDWIDTH_EFFICIENCY;
+    // Only the weights of the active experts of the MoE models are read for a single token
+    let active_share = match config.total_parameters() > 0 {
+        true => config.active_parameters_per_token() as f64 / config.total_parameters() as f64,
+        false => 1.0,
+    };
+    let prefill_flops = flops_per_token(config, Phase::Prefill, seq_len).total();
+    let decode_flops = flops_per_token(config, Phase::Decode, seq_len).total();
+    let decode_time = (batch_size as f64 * decode_flops / peak_flops)
+        .max((weights as f64 * active_share + kv_cache as f64) / bandwidth);
+    let throughput = Throughput::new(peak_flops / prefill_flops, batch_size as f64 / decode_time);
+
+    CpuEstimate::new(
+        weights,
+        kv_cache,
+        compute_buffer,
+        (setup.ram - setup.os_reserved).max(0),
+        throughput,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate::test_utils::create_llama_config;
+    use crate::models::QuantType;
+
+    #[test]
+    fn test_estimate_cpu_inference_memory() {
+        let llama_config = create_llama_config();
+        let q4 = Precision::GGUF(QuantType::Q4_K);
+        let setup = CpuSetup::new(8 * 1024 * 1024 * 1024, 8);
+        let estimate = estimate_cpu_inference(&llama_config, q4, 1, 4096, &setup);
+        assert_eq!(
+            estimate.weights(),
+            quantized_weights_bytes(&llama_config, q4)
+        );
+        assert_eq!(
+            estimate.kv_cache(),
+            estimate_kv_cache(&llama_config, 1, 4096, cache_precision(q4))
+        );
+        assert_eq!(estimate.available(), 6 * 1024 * 1024 * 1024);
+        assert!(estimate.fits());
+
+        // The 16-bit weights need more than 8 GB
+        let estimate = estimate_cpu_inference(&llama_config, Precision::FP16, 1, 4096, &setup);
+        assert!(!estimate.fits());
+    }
+
+    #[test]
+    fn test_estimate_cpu_inference_speed() {
+        let llama_config = create_llama_config();
+        let q4 = Precision::GGUF(QuantType::Q4_K);
+        let setup = CpuSetup::new(32 * 1024 * 1024 * 1024, 8);
+        let estimate = estimate_cpu_inference(&llama_config, q4, 1, 2048, &setup);
+        // About 8 tokens/s on dual-channel DDR4, as llama.cpp reports for Q4_K_M 7B models
+        let decode = estimate.throughput().decode_tokens_per_second();
+        assert!(decode > 5.0 && decode < 12.0);
+
+        // Decoding is bound by the memory bandwidth, not by the cores
+        let mut more_cores = setup;
+        more_cores.cpu_cores = 16;
+        let faster = estimate_cpu_inference(&llama_config, q4, 1, 2048, &more_cores);
+        assert_eq!(faster.throughput().decode_tokens_per_second(), decode);
+        assert!(
+            faster.throughput().prefill_tokens_per_second()
+                > estimate.throughput().prefill_tokens_per_second()
+        );
+
+        let mut more_bandwidth = setup;
+        more_bandwidth.memory_bandwidth *= 2.0;
+        let faster = estimate_cpu_inference(&llama_config, q4, 1, 2048, &more_bandwidth);
+        assert!(faster.throughput().decode_tokens_per_second() > 1.9 * decode);
+    }
+}
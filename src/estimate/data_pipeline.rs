@@ -0,0 +1,246 @@
+//! Fine-tuning data pipeline host memory and disk estimates
+use crate::estimate::TrainingSetup;
+use crate::hub::{DatasetInfo, DEFAULT_BYTES_PER_TOKEN};
+
+/// Number of bytes a token takes in the tokenized dataset cache: the `int32` input id and the
+/// `int8` attention mask
+pub const TOKENIZED_BYTES_PER_TOKEN: i64 = 5;
+/// Number of bytes a token takes in a collated batch: the `int64` input id, attention mask and
+/// label
+pub const COLLATED_BYTES_PER_TOKEN: i64 = 24;
+/// Resident memory of a dataloader worker process, the Python interpreter and PyTorch (bytes)
+pub const DATALOADER_WORKER_BYTES: i64 = 512 * 1024 * 1024;
+/// Default number of dataloader worker processes of every GPU
+pub const DEFAULT_NUM_WORKERS: i32 = 4;
+/// Default number of batches every dataloader worker prepares ahead (PyTorch `prefetch_factor`)
+pub const DEFAULT_PREFETCH_FACTOR: i32 = 2;
+
+/// A struct representing the dataset of a fine-tuning and the way it is loaded
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DataPipelineSetup {
+    /// The number of rows (examples) of the dataset
+    pub num_rows: i64,
+    /// The number of tokens of the dataset, before truncation
+    pub num_tokens: i64,
+    /// The size of the downloaded dataset files, in bytes
+    pub raw_bytes: i64,
+    /// The number of dataloader worker processes of every GPU, 0 to load in the main process
+    pub num_workers: i32,
+    /// The number of batches every worker prepares ahead
+    pub prefetch_factor: i32,
+    /// Whether the examples are packed into sequences of `seq_len` tokens, instead of being
+    /// truncated and padded
+    pub packing: bool,
+}
+
+/// DataPipelineSetup implementation
+impl DataPipelineSetup {
+    /// Build a new `DataPipelineSetup` with 4 workers per GPU and without packing
+    pub fn new(num_rows: i64, num_tokens: i64, raw_bytes: i64) -> DataPipelineSetup {
+        DataPipelineSetup {
+            num_rows,
+            num_tokens,
+            raw_bytes,
+            num_workers: DEFAULT_NUM_WORKERS,
+            prefetch_factor: DEFAULT_PREFETCH_FACTOR,
+            packing: false,
+        }
+    }
+    /// Build a new `DataPipelineSetup` from the size of a split of a hub dataset, the number of
+    /// tokens being approximated from its in-memory size (see `DatasetInfo::estimated_tokens`).
+    /// Returns `None` when the split or its sizes are unknown.
+    pub fn from_dataset(dataset: &DatasetInfo, split: &str) -> Option<DataPipelineSetup> {
+        let split = dataset.get_split(split)?;
+        let num_tokens = split.num_bytes_memory? as f64 / DEFAULT_BYTES_PER_TOKEN;
+        Some(DataPipelineSetup::new(
+            split.num_rows?,
+            num_tokens.round() as i64,
+            split.num_bytes_parquet_files.unwrap_or(0),
+        ))
+    }
+}
+
+/// A struct representing the host resources the data pipeline of a fine-tuning needs
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DataPipelineEstimate {
+    /// The size of the tokenized dataset cache, in bytes
+    tokenized_dataset: i64,
+    /// The disk space of the downloaded files and of the tokenized cache, in bytes
+    disk: i64,
+    /// The host RAM of the dataloaders of all the GPUs, in bytes
+    host_ram: i64,
+    /// The share of the tokens of the batches which are not padding
+    packing_efficiency: f64,
+    /// The number of optimizer steps of an epoch
+    steps_per_epoch: i64,
+}
+
+/// DataPipelineEstimate implementation
+impl DataPipelineEstimate {
+    /// Build a new `DataPipelineEstimate` struct based on the provided parameters
+    pub fn new(
+        tokenized_dataset: i64,
+        disk: i64,
+        host_ram: i64,
+        packing_efficiency: f64,
+        steps_per_epoch: i64,
+    ) -> DataPipelineEstimate {
+        DataPipelineEstimate {
+            tokenized_dataset,
+            disk,
+            host_ram,
+            packing_efficiency,
+            steps_per_epoch,
+        }
+    }
+    /// Returns the size of the tokenized dataset cache
+    pub fn tokenized_dataset(&self) -> i64 {
+        self.tokenized_dataset
+    }
+    /// Returns the disk space of the downloaded files and of the tokenized cache
+    pub fn disk(&self) -> i64 {
+        self.disk
+    }
+    /// Returns the host RAM of the dataloaders of all the GPUs
+    pub fn host_ram(&self) -> i64 {
+        self.host_ram
+    }
+    /// Returns the share of the tokens of the batches which are not padding, between 0 and 1
+    pub fn packing_efficiency(&self) -> f64 {
+        self.packing_efficiency
+    }
+    /// Returns the number of optimizer steps of an epoch, without gradient accumulation
+    pub fn steps_per_epoch(&self) -> i64 {
+        self.steps_per_epoch
+    }
+}
+
+/// Estimate the disk space and the host RAM the data pipeline of a fine-tuning needs, and the
+/// share of the training tokens which are not padding.
+///
+/// Without packing, every example is truncated then padded to `seq_len` tokens, examples
+/// shorter on average than `seq_len` waste the rest of their sequence. With packing, the examples
+/// are concatenated into full sequences. The tokenized dataset is memory-mapped from the disk
+/// cache, so the host RAM is the one of the worker processes of every GPU and of their prefetched
+/// batches.
+pub fn estimate_data_pipeline(
+    setup: &TrainingSetup,
+    data: &DataPipelineSetup,
+) -> DataPipelineEstimate {
+    let seq_len = setup.seq_len.max(1) as i64;
+    let num_rows = data.num_rows.max(0);
+    let (sequences, stored_tokens) = match data.packing {
+        true => ((data.num_tokens + seq_len - 1) / seq_len, data.num_tokens),
+        false => (num_rows, data.num_tokens.min(num_rows * seq_len)),
+    };
+    let packing_efficiency = match sequences > 0 {
+        true => stored_tokens as f64 / (sequences * seq_len) as f64,
+        false => 0.0,
+    };
+    let sequences_per_step = setup.batch_size.max(1) as i64 * setup.num_gpus.max(1) as i64;
+    let steps_per_epoch = (sequences + sequences_per_step - 1) / sequences_per_step;
+
+    let tokenized_dataset = stored_tokens * TOKENIZED_BYTES_PER_TOKEN;
+    let batch_bytes = setup.batch_size.max(1) as i64 * seq_len * COLLATED_BYTES_PER_TOKEN;
+    let num_workers = data.num_workers.max(0) as i64;
+    // The main process prepares a single batch at a time when there is no worker
+    let prefetched_batches = match num_workers {
+        0 => 1,
+        _ => num_workers * data.prefetch_factor.max(1) as i64,
+    };
+    let per_gpu = num_workers * DATALOADER_WORKER_BYTES + prefetched_batches * batch_bytes;
+
+    DataPipelineEstimate::new(
+        tokenized_dataset,
+        data.raw_bytes.max(0) + tokenized_dataset,
+        setup.num_gpus.max(1) as i64 * per_gpu,
+        packing_efficiency,
+        steps_per_epoch,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate::Optimizer;
+    use crate::models::Precision;
+    use serde_json::json;
+
+    fn create_setup() -> TrainingSetup {
+        TrainingSetup::new(4, 2048, Precision::BF16, Optimizer::AdamW)
+    }
+
+    #[test]
+    fn test_data_pipeline_setup_from_dataset() {
+        let dataset = DatasetInfo::from_json(
+            "user/dataset",
+            json!({
+                "size": {
+                    "dataset": {"num_rows": 1200, "num_bytes_memory": 4_400_000},
+                    "splits": [
+                        {"config": "default", "split": "train", "num_rows": 1000,
+                         "num_bytes_parquet_files": 1_500_000, "num_bytes_memory": 4_000_000},
+                        {"config": "default", "split": "test", "num_rows": 200,
+                         "num_bytes_parquet_files": 150_000, "num_bytes_memory": 400_000}
+                    ]
+                },
+                "partial": false
+            }),
+        );
+        let data = DataPipelineSetup::from_dataset(&dataset, "train").unwrap();
+        assert_eq!(data.num_rows, 1000);
+        assert_eq!(data.num_tokens, 1_000_000);
+        assert_eq!(data.raw_bytes, 1_500_000);
+        assert!(DataPipelineSetup::from_dataset(&dataset, "validation").is_none());
+    }
+
+    #[test]
+    fn test_estimate_data_pipeline_packing() {
+        // 1000 examples of 512 tokens on average
+        let mut data = DataPipelineSetup::new(1000, 512_000, 1_000_000);
+        let padded = estimate_data_pipeline(&create_setup(), &data);
+        assert_eq!(padded.packing_efficiency(), 0.25);
+        assert_eq!(padded.steps_per_epoch(), 250);
+        assert_eq!(padded.tokenized_dataset(), 512_000 * 5);
+        assert_eq!(padded.disk(), 1_000_000 + 512_000 * 5);
+
+        // Packed into 250 sequences of 2048 tokens
+        data.packing = true;
+        let packed = estimate_data_pipeline(&create_setup(), &data);
+        assert_eq!(packed.packing_efficiency(), 1.0);
+        assert_eq!(packed.steps_per_epoch(), 63);
+
+        // Long examples are truncated
+        let data = DataPipelineSetup::new(1000, 4_096_000, 1_000_000);
+        let truncated = estimate_data_pipeline(&create_setup(), &data);
+        assert_eq!(truncated.packing_efficiency(), 1.0);
+        assert_eq!(truncated.tokenized_dataset(), 2_048_000 * 5);
+    }
+
+    #[test]
+    fn test_estimate_data_pipeline_host_ram() {
+        let mut setup = create_setup();
+        let mut data = DataPipelineSetup::new(1000, 512_000, 1_000_000);
+        let batch_bytes = 4 * 2048 * COLLATED_BYTES_PER_TOKEN;
+        let estimate = estimate_data_pipeline(&setup, &data);
+        assert_eq!(
+            estimate.host_ram(),
+            4 * DATALOADER_WORKER_BYTES + 8 * batch_bytes
+        );
+
+        // Every GPU has its own workers
+        setup.num_gpus = 8;
+        assert_eq!(
+            estimate_data_pipeline(&setup, &data).host_ram(),
+            8 * estimate.host_ram()
+        );
+
+        // Loading in the main process
+        setup.num_gpus = 1;
+        data.num_workers = 0;
+        assert_eq!(
+            estimate_data_pipeline(&setup, &data).host_ram(),
+            batch_bytes
+        );
+    }
+}
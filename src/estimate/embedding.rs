@@ -0,0 +1,234 @@
+//! Embedding model serving estimates
+use crate::estimate::activations::activation_bytes;
+use crate::estimate::{
+    flops_per_token, quantized_weights_bytes, OverheadModel, Phase, BANDWIDTH_EFFICIENCY,
+    COMPUTE_EFFICIENCY,
+};
+use crate::models::{ModelConfigTrait, Precision};
+use crate::specs::GpuSpec;
+
+/// A struct representing the memory needed to embed batches of documents, in bytes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EmbeddingEstimate {
+    /// The memory of the model weights
+    weights: i64,
+    /// The peak memory of the activations, reached with the longest batch
+    activations: i64,
+    /// The memory of the framework (CUDA context, allocator fragmentation)
+    overhead: i64,
+    /// The number of tokens of the batches once padded, the padding included
+    padded_tokens: i64,
+}
+
+/// EmbeddingEstimate implementation
+impl EmbeddingEstimate {
+    /// Build a new `EmbeddingEstimate` struct based on the provided parameters
+    pub fn new(
+        weights: i64,
+        activations: i64,
+        overhead: i64,
+        padded_tokens: i64,
+    ) -> EmbeddingEstimate {
+        EmbeddingEstimate {
+            weights,
+            activations,
+            overhead,
+            padded_tokens,
+        }
+    }
+    /// Returns the memory of the model weights
+    pub fn weights(&self) -> i64 {
+        self.weights
+    }
+    /// Returns the peak memory of the activations
+    pub fn activations(&self) -> i64 {
+        self.activations
+    }
+    /// Returns the memory of the framework
+    pub fn overhead(&self) -> i64 {
+        self.overhead
+    }
+    /// Returns the number of tokens the model runs, the padding included
+    pub fn padded_tokens(&self) -> i64 {
+        self.padded_tokens
+    }
+    /// Returns the total memory needed to embed the documents
+    pub fn total(&self) -> i64 {
+        self.weights + self.activations + self.overhead
+    }
+}
+
+/// Returns the batches the documents of `doc_lengths` tokens are embedded in, as their number of
+/// documents and their padded length. Like sentence-transformers, the documents are sorted from
+/// the longest, truncated to the longest input the model supports, and every batch is padded to
+/// its longest document.
+fn padded_batches(
+    config: &dyn ModelConfigTrait,
+    batch_size: i32,
+    doc_lengths: &[i32],
+) -> Vec<(i32, i32)> {
+    let max_length = match config.max_position_embeddings() > 0 {
+        true => config.max_position_embeddings(),
+        false => i32::MAX,
+    };
+    let mut lengths: Vec<i32> = doc_lengths
+        .iter()
+        .map(|length| (*length).clamp(1, max_length))
+        .collect();
+    lengths.sort_by_key(|length| std::cmp::Reverse(*length));
+    lengths
+        .chunks(batch_size.max(1) as usize)
+        .map(|batch| (batch.len() as i32, batch[0]))
+        .collect()
+}
+
+/// Estimate the memory needed to embed documents of `doc_lengths` tokens in batches of
+/// `batch_size` documents with an encoder model (sentence-transformers, rerankers).
+///
+/// Nothing is cached between the batches, the peak is reached with the batch of the longest
+/// documents: the hidden states and the MLP intermediate states of its padded tokens. The
+/// attention is expected to be a memory efficient one (SDPA), the scores are not stored.
+pub fn estimate_embedding(
+    config: &dyn ModelConfigTrait,
+    precision: Precision,
+    batch_size: i32,
+    doc_lengths: &[i32],
+) -> EmbeddingEstimate {
+    estimate_embedding_with_overhead(
+        config,
+        precision,
+        batch_size,
+        doc_lengths,
+        &OverheadModel::new(),
+    )
+}
+
+/// Estimate the memory needed to embed documents in batches with an encoder model, with the given
+/// overhead model (see `estimate_embedding`)
+pub fn estimate_embedding_with_overhead(
+    config: &dyn ModelConfigTrait,
+    precision: Precision,
+    batch_size: i32,
+    doc_lengths: &[i32],
+    overhead: &OverheadModel,
+) -> EmbeddingEstimate {
+    let weights = quantized_weights_bytes(config, precision);
+    let batches = padded_batches(config, batch_size, doc_lengths);
+    let per_token = 4 * config.hidden_size() as i64 + 2 * config.intermediate_size() as i64;
+    let activations = batches
+        .iter()
+        .map(|(num_docs, length)| *num_docs as i64 * *length as i64)
+        .max()
+        .unwrap_or(0)
+        * per_token
+        * activation_bytes(precision);
+    let padded_tokens = batches
+        .iter()
+        .map(|(num_docs, length)| *num_docs as i64 * *length as i64)
+        .sum();
+    EmbeddingEstimate::new(
+        weights,
+        activations,
+        overhead.overhead_bytes(weights, activations),
+        padded_tokens,
+    )
+}
+
+/// Estimate how many documents of `doc_lengths` tokens an encoder model embeds every second on a
+/// GPU, in batches of `batch_size` documents, with a roofline model: every batch takes the longest
+/// of its compute time and of the time to read the weights.
+///
+/// Unlike the prompt of a decoder, every token attends to the whole padded input, and no LM head
+/// runs on top of the last hidden states.
+pub fn estimate_embedding_throughput(
+    config: &dyn ModelConfigTrait,
+    device: &GpuSpec,
+    precision: Precision,
+    batch_size: i32,
+    doc_lengths: &[i32],
+) -> f64 {
+    let peak_flops = device.fp16_tflops() * 1e12 * COMPUTE_EFFICIENCY;
+    let bandwidth = device.memory_bandwidth() * BANDWIDTH_EFFICIENCY;
+    let weights = quantized_weights_bytes(config, precision) as f64;
+
+    let time: f64 = padded_batches(config, batch_size, doc_lengths)
+        .iter()
+        .map(|(num_docs, length)| {
+            // A decoded token attends to the whole context, as every token of an encoder does
+            let flops = flops_per_token(config, Phase::Decode, *length);
+            let tokens = *num_docs as f64 * *length as f64;
+            (tokens * (flops.attention() + flops.mlp()) / peak_flops).max(weights / bandwidth)
+        })
+        .sum();
+    match time > 0.0 {
+        true => doc_lengths.len() as f64 / time,
+        false => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{BertModelConfig, BertParams, Dtype, ModelLibraries};
+
+    fn create_bert_config() -> BertModelConfig {
+        // BERT base, the backbone of most sentence-transformers models
+        BertModelConfig::new(
+            BertParams::new(768, 3072, 512, 12, 12),
+            30522,
+            true,
+            Dtype::F32,
+            "bert".to_string(),
+            vec![ModelLibraries::PyTorch],
+        )
+    }
+
+    #[test]
+    fn test_padded_batches() {
+        let bert_config = create_bert_config();
+        // Sorted from the longest, the 1000 tokens document is truncated to 512 tokens
+        let batches = padded_batches(&bert_config, 2, &[10, 1000, 50, 40, 300]);
+        assert_eq!(batches, vec![(2, 512), (2, 50), (1, 10)]);
+        assert!(padded_batches(&bert_config, 32, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_estimate_embedding() {
+        let bert_config = create_bert_config();
+        let estimate =
+            estimate_embedding(&bert_config, Precision::FP16, 2, &[10, 1000, 50, 40, 300]);
+        assert_eq!(estimate.weights(), 2 * bert_config.num_parameters());
+        assert_eq!(estimate.padded_tokens(), 2 * 512 + 2 * 50 + 10);
+        // The batch of the two 512 tokens documents
+        assert_eq!(estimate.activations(), 1024 * (4 * 768 + 2 * 3072) * 2);
+        assert_eq!(
+            estimate.total(),
+            estimate.weights()
+                + estimate.activations()
+                + OverheadModel::new().overhead_bytes(estimate.weights(), estimate.activations())
+        );
+    }
+
+    #[test]
+    fn test_estimate_embedding_throughput() {
+        let bert_config = create_bert_config();
+        let a100 = GpuSpec::from_name("NVIDIA A100-SXM4-80GB").unwrap();
+        let short =
+            estimate_embedding_throughput(&bert_config, &a100, Precision::FP16, 32, &[128; 1024]);
+        let long =
+            estimate_embedding_throughput(&bert_config, &a100, Precision::FP16, 32, &[512; 1024]);
+        assert!(short > long);
+        assert!(long > 100.0);
+
+        // A single long document pads its whole batch
+        let mut lengths = vec![128; 1024];
+        lengths[0] = 512;
+        let padded =
+            estimate_embedding_throughput(&bert_config, &a100, Precision::FP16, 1024, &lengths);
+        assert!((padded - long).abs() / long < 1e-9);
+        assert_eq!(
+            estimate_embedding_throughput(&bert_config, &a100, Precision::FP16, 32, &[]),
+            0.0
+        );
+    }
+}
@@ -0,0 +1,149 @@
+//! FLOPs per token estimates
+use crate::models::ModelConfigTrait;
+
+/// Enumerate the phases of a model run, each one costs a different number of FLOPs per token
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Phase {
+    /// The forward pass over a whole prompt, every token attends to the previous ones
+    Prefill,
+    /// The forward pass of a single generated token, attending to the whole context
+    Decode,
+    /// The forward and the backward passes of a training step, the backward pass costing twice
+    /// the forward one
+    TrainingStep,
+}
+
+/// A struct representing the FLOPs a token costs in every component of a model
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FlopsBreakdown {
+    /// The attention projections and the attention scores of all the layers
+    attention: f64,
+    /// The feed-forward layers of all the layers, the active experts only
+    mlp: f64,
+    /// The LM head projection
+    lm_head: f64,
+}
+
+/// FlopsBreakdown implementation
+impl FlopsBreakdown {
+    /// Build a new `FlopsBreakdown` struct based on the provided parameters
+    pub fn new(attention: f64, mlp: f64, lm_head: f64) -> FlopsBreakdown {
+        FlopsBreakdown {
+            attention,
+            mlp,
+            lm_head,
+        }
+    }
+    /// Returns the FLOPs of the attention
+    pub fn attention(&self) -> f64 {
+        self.attention
+    }
+    /// Returns the FLOPs of the feed-forward layers
+    pub fn mlp(&self) -> f64 {
+        self.mlp
+    }
+    /// Returns the FLOPs of the LM head
+    pub fn lm_head(&self) -> f64 {
+        self.lm_head
+    }
+    /// Returns the FLOPs of the whole model
+    pub fn total(&self) -> f64 {
+        self.attention + self.mlp + self.lm_head
+    }
+}
+
+/// Returns the FLOPs a token costs in the given phase, with a context of `context_len` tokens.
+///
+/// Every matrix multiplication costs 2 FLOPs per weight. The attention scores (`QK^T` and the
+/// weighted sum of the values) add `4 * context * num_heads * head_dim` FLOPs per layer, where a
+/// prompt token attends to half of the prompt on average. The MoE models only run their active
+/// experts.
+pub fn flops_per_token(
+    config: &dyn ModelConfigTrait,
+    phase: Phase,
+    context_len: i32,
+) -> FlopsBreakdown {
+    let breakdown = config.parameter_breakdown();
+    let attention_dim = (config.num_attention_heads() * config.head_dim()) as f64;
+    let attended_tokens = match phase {
+        Phase::Prefill | Phase::TrainingStep => context_len.max(1) as f64 / 2.0,
+        Phase::Decode => context_len.max(1) as f64,
+    };
+    let attention = 2.0 * breakdown.attention() as f64
+        + 4.0 * breakdown.num_layers() as f64 * attended_tokens * attention_dim;
+
+    // The parameters of the inactive experts are the only ones a token skips
+    let inactive = (config.total_parameters() - config.active_parameters_per_token()).max(0);
+    let mlp = 2.0 * (breakdown.mlp() - inactive).max(0) as f64;
+    let lm_head = 2.0 * config.vocab_size() as f64 * config.hidden_size() as f64;
+
+    let forward = FlopsBreakdown::new(attention, mlp, lm_head);
+    match phase {
+        Phase::TrainingStep => FlopsBreakdown::new(
+            3.0 * forward.attention,
+            3.0 * forward.mlp,
+            3.0 * forward.lm_head,
+        ),
+        _ => forward,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate::test_utils::create_llama_config;
+    use crate::models::{Dtype, MixtralModelConfig, MixtralParams, ModelLibraries, RopeParams};
+
+    #[test]
+    fn test_flops_per_token() {
+        let llama_config = create_llama_config();
+        let breakdown = llama_config.parameter_breakdown();
+        let flops = flops_per_token(&llama_config, Phase::Decode, 1000);
+        assert_eq!(
+            flops.attention(),
+            2.0 * breakdown.attention() as f64 + 4.0 * 32.0 * 1000.0 * 4096.0
+        );
+        assert_eq!(flops.mlp(), 2.0 * breakdown.mlp() as f64);
+        assert_eq!(flops.lm_head(), 2.0 * 128256.0 * 4096.0);
+        assert_eq!(
+            flops.total(),
+            flops.attention() + flops.mlp() + flops.lm_head()
+        );
+
+        // Close to the usual 2 FLOPs per parameter for short contexts
+        let short = flops_per_token(&llama_config, Phase::Decode, 1);
+        let ratio = short.total() / (2.0 * llama_config.num_parameters() as f64);
+        assert!(ratio > 0.9 && ratio < 1.0);
+    }
+
+    #[test]
+    fn test_flops_per_token_phases() {
+        let llama_config = create_llama_config();
+        let prefill = flops_per_token(&llama_config, Phase::Prefill, 8192);
+        let decode = flops_per_token(&llama_config, Phase::Decode, 8192);
+        let training = flops_per_token(&llama_config, Phase::TrainingStep, 8192);
+
+        // A prompt token attends to half of the prompt, a generated one to all of it
+        assert!(prefill.attention() < decode.attention());
+        assert_eq!(prefill.mlp(), decode.mlp());
+        assert_eq!(training.total(), 3.0 * prefill.total());
+    }
+
+    #[test]
+    fn test_flops_per_token_moe() {
+        // Only 2 of the 8 experts run for every token
+        let mixtral_config = MixtralModelConfig::new(
+            MixtralParams::new(4096, 14336, 32768, 32, 32, Some(8), 8, 2),
+            32000,
+            false,
+            RopeParams::new(Some(1e6), None),
+            Dtype::BF16,
+            "mixtral".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        let flops = flops_per_token(&mixtral_config, Phase::Decode, 1);
+        let all_experts = 2.0 * mixtral_config.parameter_breakdown().mlp() as f64;
+        assert!(flops.mlp() < all_experts / 3.0);
+        assert!(flops.mlp() > all_experts / 5.0);
+    }
+}
@@ -0,0 +1,118 @@
+//! Inference memory estimates
+use crate::estimate::{quantized_weights_bytes, OverheadModel};
+use crate::models::{ModelConfigTrait, Precision};
+
+/// A struct representing the memory needed to load a model for inference, in bytes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InferenceMemory {
+    /// The memory of the model weights
+    weights: i64,
+    /// The memory of the framework (CUDA context, allocator fragmentation)
+    overhead: i64,
+}
+
+/// InferenceMemory implementation
+impl InferenceMemory {
+    /// Build a new `InferenceMemory` struct based on the provided parameters
+    pub fn new(weights: i64, overhead: i64) -> InferenceMemory {
+        InferenceMemory { weights, overhead }
+    }
+    /// Returns the memory of the model weights
+    pub fn weights(&self) -> i64 {
+        self.weights
+    }
+    /// Returns the memory of the framework
+    pub fn overhead(&self) -> i64 {
+        self.overhead
+    }
+    /// Returns the total memory needed to load the model
+    pub fn total(&self) -> i64 {
+        self.weights + self.overhead
+    }
+}
+
+/// Estimate the memory needed to load a model for inference in the given precision, every
+/// parameter is counted (all the experts of the MoE models are loaded). The quantized precisions
+/// only quantize the linear layers (see `quantized_weights_bytes`).
+pub fn estimate_inference_memory(
+    config: &dyn ModelConfigTrait,
+    precision: Precision,
+) -> InferenceMemory {
+    estimate_inference_memory_with_overhead(config, precision, &OverheadModel::new())
+}
+
+/// Estimate the memory needed to load a model for inference in the given precision, with the
+/// given overhead model (see `estimate_inference_memory`)
+pub fn estimate_inference_memory_with_overhead(
+    config: &dyn ModelConfigTrait,
+    precision: Precision,
+    overhead: &OverheadModel,
+) -> InferenceMemory {
+    let weights = quantized_weights_bytes(config, precision);
+    InferenceMemory::new(weights, overhead.overhead_bytes(weights, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate::test_utils::create_llama_config;
+    use crate::estimate::FRAMEWORK_OVERHEAD_BYTES;
+    use crate::models::{
+        Dtype, MixtralModelConfig, MixtralParams, ModelLibraries, QuantType, RopeParams,
+    };
+
+    #[test]
+    fn test_inference_memory() {
+        let memory = InferenceMemory::new(1000, 24);
+        assert_eq!(memory.weights(), 1000);
+        assert_eq!(memory.overhead(), 24);
+        assert_eq!(memory.total(), 1024);
+    }
+
+    #[test]
+    fn test_estimate_inference_memory() {
+        let llama_config = create_llama_config();
+        let num_parameters = llama_config.num_parameters();
+
+        let memory = estimate_inference_memory(&llama_config, Precision::BF16);
+        assert_eq!(memory.weights(), 2 * num_parameters);
+        assert_eq!(
+            memory.overhead(),
+            FRAMEWORK_OVERHEAD_BYTES + (memory.weights() as f64 * 0.05).round() as i64
+        );
+        assert_eq!(memory.total(), memory.weights() + memory.overhead());
+
+        let quantized = estimate_inference_memory(&llama_config, Precision::GGUF(QuantType::Q4_0));
+        assert_eq!(
+            quantized.weights(),
+            quantized_weights_bytes(&llama_config, Precision::GGUF(QuantType::Q4_0))
+        );
+        assert!(quantized.total() < memory.total() / 3);
+
+        let overhead = OverheadModel {
+            cuda_context: 0,
+            allocator_headroom: 0.0,
+            ..OverheadModel::new()
+        };
+        let exact =
+            estimate_inference_memory_with_overhead(&llama_config, Precision::BF16, &overhead);
+        assert_eq!(exact.overhead(), 0);
+        assert_eq!(exact.total(), memory.weights());
+    }
+
+    #[test]
+    fn test_estimate_inference_memory_moe() {
+        // Every expert is loaded, even if only 2 of them run for each token
+        let mixtral_config = MixtralModelConfig::new(
+            MixtralParams::new(4096, 14336, 32768, 32, 32, Some(8), 8, 2),
+            32000,
+            false,
+            RopeParams::new(Some(1e6), None),
+            Dtype::BF16,
+            "mixtral".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        let memory = estimate_inference_memory(&mixtral_config, Precision::FP16);
+        assert_eq!(memory.weights(), 2 * 46_702_526_464);
+    }
+}
@@ -0,0 +1,159 @@
+//! KV cache memory estimates
+use crate::models::{ModelConfigTrait, Precision};
+
+/// Returns the number of bytes the KV cache takes for every token, the keys and the values of
+/// every decoder layer. Grouped-query attention models only cache `num_key_value_heads` heads.
+pub fn kv_cache_bytes_per_token(config: &dyn ModelConfigTrait, precision: Precision) -> f64 {
+    2.0 * config.num_decoder_layers() as f64 * config.kv_dim() as f64 * precision.bytes_per_param()
+}
+
+/// Estimate the memory of the KV cache for `batch_size` sequences of `seq_len` tokens, in bytes.
+///
+/// The `precision` is the one of the cache (usually the activations one, e.g. FP16 or FP8), not
+/// the one of the quantized weights. The encoder layers of the encoder-decoder models don't cache
+/// anything. Every decoder layer caches the whole context, as most frameworks do even for the
+/// sliding-window models, see `estimate_kv_cache_with_sliding_window`.
+pub fn estimate_kv_cache(
+    config: &dyn ModelConfigTrait,
+    batch_size: i32,
+    seq_len: i32,
+    precision: Precision,
+) -> i64 {
+    let bytes_per_token = kv_cache_bytes_per_token(config, precision);
+    (batch_size as f64 * seq_len as f64 * bytes_per_token).ceil() as i64
+}
+
+/// Estimate the memory of the KV cache like `estimate_kv_cache`, for a framework evicting the
+/// tokens out of the attention window: the sliding-window layers only keep the last
+/// `sliding_window` tokens, the global ones (e.g. every other layer of Gemma 2) the whole context.
+pub fn estimate_kv_cache_with_sliding_window(
+    config: &dyn ModelConfigTrait,
+    batch_size: i32,
+    seq_len: i32,
+    precision: Precision,
+) -> i64 {
+    let layer_tokens = cached_layer_tokens(config, seq_len);
+    let bytes_per_layer_token = 2.0 * config.kv_dim() as f64 * precision.bytes_per_param();
+    (batch_size as f64 * layer_tokens * bytes_per_layer_token).ceil() as i64
+}
+
+/// Returns the number of cached tokens summed over the decoder layers when the sliding-window
+/// layers only keep the last `sliding_window` tokens of `seq_len` ones
+pub(crate) fn cached_layer_tokens(config: &dyn ModelConfigTrait, seq_len: i32) -> f64 {
+    let decoder_layers = config.num_decoder_layers() as f64;
+    match config.sliding_window() {
+        Some(sliding_window) => {
+            let sliding_layers = config.num_sliding_layers() as f64;
+            (decoder_layers - sliding_layers) * seq_len as f64
+                + sliding_layers * seq_len.min(sliding_window) as f64
+        }
+        None => decoder_layers * seq_len as f64,
+    }
+}
+
+/// Returns the precision of the KV cache of a model loaded in the given precision: the quantized
+/// weights are dequantized on the fly, the cache stays in 16-bit
+pub(crate) fn cache_precision(precision: Precision) -> Precision {
+    match precision {
+        Precision::FP32 => Precision::FP32,
+        _ => Precision::FP16,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate::test_utils::{create_llama_config, create_llama_config_with};
+    use crate::models::{GenericModelConfig, LlamaParams};
+    use serde_json::json;
+
+    #[test]
+    fn test_kv_cache_bytes_per_token() {
+        // Llama 3 8B caches 8 heads of 128 dimensions, 128 KiB per token in fp16
+        let llama_config = create_llama_config();
+        assert_eq!(
+            kv_cache_bytes_per_token(&llama_config, Precision::FP16),
+            131072.0
+        );
+        assert_eq!(
+            kv_cache_bytes_per_token(&llama_config, Precision::FP8),
+            65536.0
+        );
+        // Without GQA every head is cached
+        let llama_config =
+            create_llama_config_with(LlamaParams::new(4096, 14336, 8192, 32, 32, None, None));
+        assert_eq!(
+            kv_cache_bytes_per_token(&llama_config, Precision::FP16),
+            524288.0
+        );
+    }
+
+    #[test]
+    fn test_estimate_kv_cache() {
+        let llama_config = create_llama_config();
+        assert_eq!(
+            estimate_kv_cache(&llama_config, 1, 8192, Precision::BF16),
+            1024 * 1024 * 1024
+        );
+        assert_eq!(
+            estimate_kv_cache(&llama_config, 4, 8192, Precision::BF16),
+            4 * 1024 * 1024 * 1024
+        );
+        assert_eq!(estimate_kv_cache(&llama_config, 4, 0, Precision::BF16), 0);
+    }
+
+    #[test]
+    fn test_estimate_kv_cache_sliding_window() {
+        // Mistral 7B only caches the last 4096 tokens when the framework evicts the older ones
+        let value = json!({
+            "model_type": "mistral",
+            "hidden_size": 4096,
+            "intermediate_size": 14336,
+            "max_position_embeddings": 32768,
+            "num_attention_heads": 32,
+            "num_hidden_layers": 32,
+            "num_key_value_heads": 8,
+            "sliding_window": 4096,
+            "vocab_size": 32000
+        });
+        let mistral_config = GenericModelConfig::from_json(value).unwrap();
+        assert_eq!(
+            estimate_kv_cache_with_sliding_window(&mistral_config, 1, 32768, Precision::FP16),
+            estimate_kv_cache(&mistral_config, 1, 4096, Precision::FP16)
+        );
+        assert_eq!(
+            estimate_kv_cache_with_sliding_window(&mistral_config, 1, 2048, Precision::FP16),
+            256 * 1024 * 1024
+        );
+        // Without the eviction the whole context is cached
+        assert_eq!(
+            estimate_kv_cache(&mistral_config, 1, 32768, Precision::FP16),
+            4 * 1024 * 1024 * 1024
+        );
+    }
+
+    #[test]
+    fn test_estimate_kv_cache_global_layers() {
+        // Gemma 2 9B alternates sliding-window and global layers, the global ones cache everything
+        let value = json!({
+            "model_type": "gemma2",
+            "hidden_size": 3584,
+            "intermediate_size": 14336,
+            "max_position_embeddings": 8192,
+            "num_attention_heads": 16,
+            "num_hidden_layers": 42,
+            "num_key_value_heads": 8,
+            "head_dim": 256,
+            "sliding_window": 4096,
+            "vocab_size": 256000
+        });
+        let gemma_config = GenericModelConfig::from_json(value).unwrap();
+        assert_eq!(gemma_config.num_sliding_layers(), 21);
+        let full = estimate_kv_cache(&gemma_config, 1, 8192, Precision::FP16);
+        assert_eq!(full, 42 * 2 * 8 * 256 * 2 * 8192);
+        assert_eq!(
+            estimate_kv_cache_with_sliding_window(&gemma_config, 1, 8192, Precision::FP16),
+            21 * 2 * 8 * 256 * 2 * (8192 + 4096)
+        );
+    }
+}
@@ -0,0 +1,295 @@
+//! LoRA and QLoRA fine-tuning memory estimates
+use crate::estimate::{
+    estimate_activation_memory, quantized_weights_bytes, Checkpointing, Optimizer, OverheadModel,
+};
+use crate::models::{ModelConfigTrait, Precision};
+
+/// Number of bytes of the adapter weights and gradients, PEFT keeps them in FP32
+const ADAPTER_BYTES_PER_PARAM: f64 = 4.0;
+
+/// Enumerate the linear layers of a transformer block the LoRA adapters can target, named after
+/// the Llama `target_modules` of PEFT
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LoraTarget {
+    /// Query projection (`q_proj`)
+    Query,
+    /// Key projection (`k_proj`)
+    Key,
+    /// Value projection (`v_proj`)
+    Value,
+    /// Attention output projection (`o_proj`)
+    Output,
+    /// MLP gate projection of the gated MLPs (`gate_proj`)
+    Gate,
+    /// MLP up projection (`up_proj`)
+    Up,
+    /// MLP down projection (`down_proj`)
+    Down,
+}
+
+/// LoraTarget implementation
+impl LoraTarget {
+    /// Returns every linear layer of a transformer block, the `all-linear` target of PEFT
+    pub fn all_linear() -> Vec<LoraTarget> {
+        vec![
+            LoraTarget::Query,
+            LoraTarget::Key,
+            LoraTarget::Value,
+            LoraTarget::Output,
+            LoraTarget::Gate,
+            LoraTarget::Up,
+            LoraTarget::Down,
+        ]
+    }
+    /// Returns the input and output features of the targeted linear layer
+    pub fn shape(&self, config: &dyn ModelConfigTrait) -> (i64, i64) {
+        let hidden_size = config.hidden_size() as i64;
+        let attention_dim = (config.num_attention_heads() * config.head_dim()) as i64;
+        let intermediate_size = config.intermediate_size() as i64;
+        match self {
+            LoraTarget::Query => (hidden_size, attention_dim),
+            LoraTarget::Key | LoraTarget::Value => (hidden_size, config.kv_dim() as i64),
+            LoraTarget::Output => (attention_dim, hidden_size),
+            LoraTarget::Gate | LoraTarget::Up => (hidden_size, intermediate_size),
+            LoraTarget::Down => (intermediate_size, hidden_size),
+        }
+    }
+}
+
+/// A struct representing the setup of a LoRA (or QLoRA, with a NF4 base) fine-tuning run
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoraSetup {
+    /// The rank of the adapters
+    pub r: i32,
+    /// The linear layers of every block the adapters are added to
+    pub target_modules: Vec<LoraTarget>,
+    /// The precision of the frozen base weights, `Precision::NF4` for QLoRA
+    pub base_precision: Precision,
+    /// The optimizer updating the adapters
+    pub optimizer: Optimizer,
+    /// Whether the optimizer states are paged (bitsandbytes paged optimizers), so they are
+    /// evicted to the CPU RAM instead of running out of GPU memory
+    pub paged_optimizer: bool,
+    /// The micro batch size
+    pub batch_size: i32,
+    /// The length of the training sequences
+    pub seq_len: i32,
+    /// Which activations are recomputed during the backward pass
+    pub checkpointing: Checkpointing,
+    /// The memory the framework takes on the GPU
+    pub overhead: OverheadModel,
+}
+
+/// LoraSetup implementation
+impl LoraSetup {
+    /// Build a new `LoraSetup` with AdamW, a single 512 tokens sequence per step and no
+    /// checkpointing
+    pub fn new(r: i32, target_modules: Vec<LoraTarget>, base_precision: Precision) -> LoraSetup {
+        LoraSetup {
+            r,
+            target_modules,
+            base_precision,
+            optimizer: Optimizer::AdamW,
+            paged_optimizer: false,
+            batch_size: 1,
+            seq_len: 512,
+            checkpointing: Checkpointing::Disabled,
+            overhead: OverheadModel::new(),
+        }
+    }
+    /// Returns the number of parameters of the adapters, `r * (in + out)` for every targeted
+    /// layer of every block
+    pub fn adapter_parameters(&self, config: &dyn ModelConfigTrait) -> i64 {
+        let per_layer: i64 = self
+            .target_modules
+            .iter()
+            .map(|target| {
+                let (in_features, out_features) = target.shape(config);
+                self.r as i64 * (in_features + out_features)
+            })
+            .sum();
+        per_layer * config.num_hidden_layers() as i64
+    }
+}
+
+/// A struct representing the memory a LoRA fine-tuning step needs, in bytes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LoraEstimate {
+    /// The memory of the frozen base weights
+    base_weights: i64,
+    /// The memory of the adapter weights
+    adapter_weights: i64,
+    /// The memory of the adapter gradients
+    gradients: i64,
+    /// The memory of the optimizer states of the adapters kept on the GPU
+    optimizer: i64,
+    /// The memory of the paged optimizer states, which can be evicted to the CPU RAM
+    paged_optimizer: i64,
+    /// The memory of the activations kept for the backward pass
+    activations: i64,
+    /// The memory of the framework (CUDA context)
+    overhead: i64,
+}
+
+/// LoraEstimate implementation
+impl LoraEstimate {
+    /// Returns the memory of the frozen base weights
+    pub fn base_weights(&self) -> i64 {
+        self.base_weights
+    }
+    /// Returns the memory of the adapter weights
+    pub fn adapter_weights(&self) -> i64 {
+        self.adapter_weights
+    }
+    /// Returns the memory of the adapter gradients
+    pub fn gradients(&self) -> i64 {
+        self.gradients
+    }
+    /// Returns the memory of the optimizer states kept on the GPU
+    pub fn optimizer(&self) -> i64 {
+        self.optimizer
+    }
+    /// Returns the memory of the paged optimizer states, not counted in the total
+    pub fn paged_optimizer(&self) -> i64 {
+        self.paged_optimizer
+    }
+    /// Returns the memory of the activations
+    pub fn activations(&self) -> i64 {
+        self.activations
+    }
+    /// Returns the memory of the framework
+    pub fn overhead(&self) -> i64 {
+        self.overhead
+    }
+    /// Returns the total GPU memory needed, the paged optimizer states excluded
+    pub fn total(&self) -> i64 {
+        self.base_weights
+            + self.adapter_weights
+            + self.gradients
+            + self.optimizer
+            + self.activations
+            + self.overhead
+    }
+}
+
+/// Estimate the memory of a LoRA fine-tuning step: the frozen base weights, and the weights,
+/// gradients and optimizer states of the adapters only.
+///
+/// The quantized bases (QLoRA) only quantize the linear layers of the blocks (see
+/// `quantized_weights_bytes`).
+pub fn estimate_lora(config: &dyn ModelConfigTrait, setup: &LoraSetup) -> LoraEstimate {
+    let adapter_parameters = setup.adapter_parameters(config) as f64;
+    let adapter_weights = adapter_parameters * ADAPTER_BYTES_PER_PARAM;
+    let optimizer =
+        adapter_parameters * setup.optimizer.state_bytes_per_param(config.hidden_size());
+    let (optimizer, paged_optimizer) = match setup.paged_optimizer {
+        true => (0.0, optimizer),
+        false => (optimizer, 0.0),
+    };
+
+    let base_weights = quantized_weights_bytes(config, setup.base_precision);
+    let adapter_weights = adapter_weights.ceil() as i64;
+    let activations = estimate_activation_memory(
+        config,
+        setup.batch_size,
+        setup.seq_len,
+        setup.base_precision,
+        setup.checkpointing,
+    );
+
+    LoraEstimate {
+        base_weights,
+        adapter_weights,
+        gradients: adapter_weights,
+        optimizer: optimizer.ceil() as i64,
+        paged_optimizer: paged_optimizer.ceil() as i64,
+        activations,
+        overhead: setup
+            .overhead
+            .overhead_bytes(base_weights + adapter_weights, activations),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate::test_utils::create_llama_config;
+
+    #[test]
+    fn test_lora_adapter_parameters() {
+        let llama_config = create_llama_config();
+        assert_eq!(LoraTarget::Key.shape(&llama_config), (4096, 1024));
+        assert_eq!(LoraTarget::Down.shape(&llama_config), (14336, 4096));
+
+        // The 3.4M parameters PEFT reports for r=8 on the query and value projections
+        let setup = LoraSetup::new(
+            8,
+            vec![LoraTarget::Query, LoraTarget::Value],
+            Precision::BF16,
+        );
+        assert_eq!(setup.adapter_parameters(&llama_config), 3_407_872);
+        // And the 42M ones of r=16 on every linear layer
+        let setup = LoraSetup::new(16, LoraTarget::all_linear(), Precision::BF16);
+        assert_eq!(setup.adapter_parameters(&llama_config), 41_943_040);
+    }
+
+    #[test]
+    fn test_estimate_lora() {
+        let llama_config = create_llama_config();
+        let setup = LoraSetup::new(16, LoraTarget::all_linear(), Precision::BF16);
+        let adapter_parameters = setup.adapter_parameters(&llama_config);
+
+        let estimate = estimate_lora(&llama_config, &setup);
+        assert_eq!(estimate.base_weights(), 2 * llama_config.num_parameters());
+        assert_eq!(estimate.adapter_weights(), 4 * adapter_parameters);
+        assert_eq!(estimate.gradients(), 4 * adapter_parameters);
+        assert_eq!(estimate.optimizer(), 8 * adapter_parameters);
+        assert_eq!(estimate.paged_optimizer(), 0);
+        assert_eq!(
+            estimate.overhead(),
+            OverheadModel::new().overhead_bytes(
+                estimate.base_weights() + estimate.adapter_weights(),
+                estimate.activations()
+            )
+        );
+        assert_eq!(
+            estimate.total(),
+            estimate.base_weights()
+                + 16 * adapter_parameters
+                + estimate.activations()
+                + estimate.overhead()
+        );
+
+        let paged = estimate_lora(
+            &llama_config,
+            &LoraSetup {
+                paged_optimizer: true,
+                ..setup
+            },
+        );
+        assert_eq!(paged.optimizer(), 0);
+        assert_eq!(paged.paged_optimizer(), estimate.optimizer());
+        assert_eq!(paged.total(), estimate.total() - estimate.optimizer());
+    }
+
+    #[test]
+    fn test_estimate_qlora() {
+        // QLoRA of Llama 3 8B fits a 24 GB card
+        let llama_config = create_llama_config();
+        let setup = LoraSetup {
+            checkpointing: Checkpointing::Full,
+            seq_len: 2048,
+            ..LoraSetup::new(16, LoraTarget::all_linear(), Precision::NF4)
+        };
+        let estimate = estimate_lora(&llama_config, &setup);
+        let breakdown = llama_config.parameter_breakdown();
+        let unquantized = breakdown.embeddings() + breakdown.lm_head();
+        assert_eq!(
+            estimate.base_weights(),
+            ((breakdown.attention() + breakdown.mlp()) as f64 * 0.515625 + unquantized as f64 * 2.0)
+                .ceil() as i64
+        );
+        assert!(estimate.total() < 24_000_000_000);
+        assert!(estimate.total() > 6_000_000_000);
+    }
+}
@@ -0,0 +1,146 @@
+//! Module for estimating the hardware requirements of the models
+
+// Training activation memory
+mod activations;
+pub use activations::{activation_bytes_per_layer, estimate_activation_memory, Checkpointing};
+// Apple Silicon unified memory inference
+mod apple;
+pub use apple::{
+    estimate_unified_memory, UnifiedMemoryEstimate, UnifiedMemorySetup, SMALL_UNIFIED_MEMORY_BYTES,
+};
+// Attention implementation memory and speed
+mod attention;
+pub use attention::{
+    estimate_attention, AttentionEstimate, AttentionImpl, FLASH_ATTENTION_MAX_HEAD_DIM,
+};
+// Speech recognition inference
+mod asr;
+pub use asr::{
+    estimate_asr, estimate_asr_speed, estimate_asr_with_overhead, AsrEstimate, CHUNK_SECONDS,
+    TOKENS_PER_CHUNK,
+};
+// Serving capacity planning
+mod capacity;
+pub use capacity::{plan_capacity, CapacityPlan, ServingSlo, DEFAULT_MAX_TPOT, DEFAULT_MAX_TTFT};
+// Serving cost
+mod cost;
+pub use cost::{estimate_serving_cost, Pricing, ServingCost};
+// CPU-only inference
+mod cpu;
+pub use cpu::{
+    estimate_cpu_inference, CpuEstimate, CpuSetup, CPU_FLOPS_PER_CORE,
+    DEFAULT_CPU_MEMORY_BANDWIDTH, LLAMA_CPP_UBATCH, OS_RESERVED_BYTES,
+};
+// Fine-tuning data pipeline host memory and disk
+mod data_pipeline;
+pub use data_pipeline::{
+    estimate_data_pipeline, DataPipelineEstimate, DataPipelineSetup, COLLATED_BYTES_PER_TOKEN,
+    DATALOADER_WORKER_BYTES, DEFAULT_NUM_WORKERS, DEFAULT_PREFETCH_FACTOR,
+    TOKENIZED_BYTES_PER_TOKEN,
+};
+// Embedding model serving
+mod embedding;
+pub use embedding::{
+    estimate_embedding, estimate_embedding_throughput, estimate_embedding_with_overhead,
+    EmbeddingEstimate,
+};
+// FLOPs per token
+mod flops;
+pub use flops::{flops_per_token, FlopsBreakdown, Phase};
+// Inference memory
+mod inference;
+pub use inference::{
+    estimate_inference_memory, estimate_inference_memory_with_overhead, InferenceMemory,
+};
+// KV cache memory
+mod kv_cache;
+pub(crate) use kv_cache::{cache_precision, cached_layer_tokens};
+pub use kv_cache::{
+    estimate_kv_cache, estimate_kv_cache_with_sliding_window, kv_cache_bytes_per_token,
+};
+// LoRA and QLoRA fine-tuning memory
+mod lora;
+pub use lora::{estimate_lora, LoraEstimate, LoraSetup, LoraTarget};
+// CPU and disk offload memory
+mod offload;
+pub use offload::{estimate_offload, OffloadEstimate, OffloadSetup, NVME_BANDWIDTH};
+// Optimizer states memory
+mod optimizer;
+pub use optimizer::{estimate_optimizer_memory, Optimizer, MASTER_WEIGHTS_BYTES_PER_PARAM};
+// Framework memory overhead
+mod overhead;
+pub use overhead::{
+    OverheadModel, FRAGMENTATION_RATIO, FRAMEWORK_OVERHEAD_BYTES, FRAMEWORK_OVERHEAD_RATIO,
+    TIGHT_MARGIN,
+};
+// Pipeline parallel partition planning
+mod pipeline_parallel;
+pub use pipeline_parallel::{
+    pipeline_bubble, plan_pipeline, plan_pipeline_with_overhead, PipelinePlan, PipelineStage,
+};
+// Quantized weights memory
+mod quantization;
+pub use quantization::{
+    estimate_quantization_tradeoffs, estimate_quantized_inference, quality_loss,
+    quantized_weights_bytes, QualityLoss, QUANTIZATION_LEVELS, UNQUANTIZED_BYTES_PER_PARAM,
+};
+// Paged attention serving memory
+mod serving;
+pub use serving::{
+    estimate_serving, ServingEstimate, ServingSetup, BLOCK_SIZE, GPU_MEMORY_UTILIZATION,
+    MAX_NUM_SEQS,
+};
+// Speculative decoding memory and speedup
+mod speculative;
+pub use speculative::{
+    estimate_speculative, estimate_speculative_with_overhead, speculative_speedup,
+    SpeculativeEstimate, ACCEPTANCE_RATE_HIGH, ACCEPTANCE_RATE_LOW,
+};
+// Tensor parallel inference memory
+mod tensor_parallel;
+pub use tensor_parallel::{
+    estimate_tensor_parallel, estimate_tensor_parallel_with_overhead, tensor_parallel_sizes,
+    TensorParallelMemory, NCCL_BUFFERS_BYTES,
+};
+// Roofline throughput
+mod throughput;
+pub use throughput::{estimate_throughput, Throughput, BANDWIDTH_EFFICIENCY, COMPUTE_EFFICIENCY};
+// End-to-end training memory
+mod training;
+pub use training::{
+    estimate_training, estimate_training_time, Sharding, TrainingEstimate, TrainingSetup,
+    DDP_BUCKET_BYTES, TRAINING_MFU, ZERO3_MAX_LIVE_PARAMETERS, ZERO3_PREFETCH_ELEMENTS,
+    ZERO_BUCKET_ELEMENTS,
+};
+
+// Fixtures shared by the tests of the estimators
+#[cfg(test)]
+pub(crate) mod test_utils {
+    use crate::models::{Dtype, LlamaModelConfig, LlamaParams, ModelLibraries, RopeParams};
+
+    /// Build the config of Llama 3 8B
+    pub(crate) fn create_llama_config() -> LlamaModelConfig {
+        create_llama_config_with(LlamaParams::new(4096, 14336, 8192, 32, 32, Some(8), None))
+    }
+
+    /// Build the config of a Llama 3 model with the given parameters
+    pub(crate) fn create_llama_config_with(params: LlamaParams) -> LlamaModelConfig {
+        create_llama_config_with_libraries(params, vec![ModelLibraries::PyTorch])
+    }
+
+    /// Build the config of a Llama 3 model with the given parameters and available libraries
+    pub(crate) fn create_llama_config_with_libraries(
+        params: LlamaParams,
+        available_libraries: Vec<ModelLibraries>,
+    ) -> LlamaModelConfig {
+        LlamaModelConfig::new(
+            params,
+            128256,
+            false,
+            RopeParams::new(Some(500000.0), None),
+            Dtype::BF16,
+            "llama".to_string(),
+            available_libraries,
+        )
+    }
+}
@@ -0,0 +1,236 @@
+//! CPU and disk offload memory estimates
+use crate::estimate::{cache_precision, estimate_kv_cache, quantized_weights_bytes, OverheadModel};
+use crate::models::{ModelConfigTrait, Precision};
+
+/// Sequential read bandwidth of a PCIe 4.0 NVMe SSD, the weights offloaded to the disk are read
+/// from (bytes/s)
+pub const NVME_BANDWIDTH: f64 = 7.0e9;
+
+/// A struct representing the memory and the bandwidths available to run an offloaded model
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OffloadSetup {
+    /// The memory of the GPU, in bytes
+    pub vram: i64,
+    /// The RAM of the host, in bytes
+    pub host_ram: i64,
+    /// The bandwidth of the PCIe link between the host and the GPU, in bytes/s
+    pub pcie_bandwidth: f64,
+    /// The bandwidth of the GPU memory, in bytes/s
+    pub memory_bandwidth: f64,
+    /// The read bandwidth of the disk, in bytes/s
+    pub disk_bandwidth: f64,
+    /// The memory the framework takes on the GPU
+    pub overhead: OverheadModel,
+}
+
+/// OffloadSetup implementation
+impl OffloadSetup {
+    /// Build a new `OffloadSetup` offloading to a NVMe SSD
+    pub fn new(
+        vram: i64,
+        host_ram: i64,
+        pcie_bandwidth: f64,
+        memory_bandwidth: f64,
+    ) -> OffloadSetup {
+        OffloadSetup {
+            vram,
+            host_ram,
+            pcie_bandwidth,
+            memory_bandwidth,
+            disk_bandwidth: NVME_BANDWIDTH,
+            overhead: OverheadModel::new(),
+        }
+    }
+}
+
+/// A struct representing where the memory of an offloaded model lives, in bytes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OffloadEstimate {
+    /// The memory resident on the GPU
+    vram: i64,
+    /// The memory of the weights offloaded to the host RAM
+    host_ram: i64,
+    /// The memory of the weights offloaded to the disk
+    disk: i64,
+    /// How many times slower the generation of a token is, `None` when the bandwidths are unknown
+    slowdown: Option<f64>,
+}
+
+/// OffloadEstimate implementation
+impl OffloadEstimate {
+    /// Build a new `OffloadEstimate` struct based on the provided parameters
+    pub fn new(vram: i64, host_ram: i64, disk: i64, slowdown: Option<f64>) -> OffloadEstimate {
+        OffloadEstimate {
+            vram,
+            host_ram,
+            disk,
+            slowdown,
+        }
+    }
+    /// Returns the memory resident on the GPU
+    pub fn vram(&self) -> i64 {
+        self.vram
+    }
+    /// Returns the memory of the weights offloaded to the host RAM
+    pub fn host_ram(&self) -> i64 {
+        self.host_ram
+    }
+    /// Returns the memory of the weights offloaded to the disk
+    pub fn disk(&self) -> i64 {
+        self.disk
+    }
+    /// Returns how many times slower the generation of a token is than with every weight on the
+    /// GPU (e.g. `4.0` for 4 times slower)
+    pub fn slowdown(&self) -> Option<f64> {
+        self.slowdown
+    }
+    /// Whether some weights are offloaded
+    pub fn is_offloaded(&self) -> bool {
+        self.host_ram + self.disk > 0
+    }
+}
+
+/// Estimate where the memory of a model goes when the weights which don't fit the GPU are
+/// offloaded to the host RAM, then to the disk, for generating `batch_size` sequences of
+/// `seq_len` tokens (accelerate `device_map="auto"` or DeepSpeed ZeRO-Inference).
+///
+/// The KV cache stays on the GPU, with a buffer for the offloaded layer being run. Generating a
+/// token reads every weight once: the resident ones from the GPU memory, the offloaded ones
+/// through the PCIe link (and the disk), so the slowdown is the ratio of these reading times.
+pub fn estimate_offload(
+    config: &dyn ModelConfigTrait,
+    precision: Precision,
+    batch_size: i32,
+    seq_len: i32,
+    setup: OffloadSetup,
+) -> OffloadEstimate {
+    let weights = quantized_weights_bytes(config, precision);
+    let kv_cache = estimate_kv_cache(config, batch_size, seq_len, cache_precision(precision));
+    let overhead = setup.overhead;
+    let working_set = kv_cache + overhead.overhead_bytes(0, kv_cache);
+    // The allocator headroom only grows with the weights resident on the GPU
+    let headroom = |bytes: i64| (bytes as f64 * overhead.allocator_headroom).round() as i64;
+    if weights + headroom(weights) + working_set <= setup.vram {
+        return OffloadEstimate::new(weights + headroom(weights) + working_set, 0, 0, Some(1.0));
+    }
+
+    let layer_buffer = precision.weights_bytes(config.parameter_breakdown().per_layer());
+    let resident = ((setup.vram - working_set - layer_buffer) as f64
+        / (1.0 + overhead.allocator_headroom))
+        .floor() as i64;
+    let resident = resident.clamp(0, weights);
+    let offloaded = weights - resident;
+    let host_ram = offloaded.min(setup.host_ram.max(0));
+    let disk = offloaded - host_ram;
+
+    let slowdown = match setup.memory_bandwidth > 0.0 && setup.pcie_bandwidth > 0.0 {
+        true => {
+            let on_gpu = weights as f64 / setup.memory_bandwidth;
+            let offloaded = resident as f64 / setup.memory_bandwidth
+                + host_ram as f64 / setup.pcie_bandwidth
+                + disk as f64 / setup.disk_bandwidth.min(setup.pcie_bandwidth);
+            Some(offloaded / on_gpu)
+        }
+        false => None,
+    };
+    OffloadEstimate::new(
+        resident + headroom(resident) + working_set + layer_buffer,
+        host_ram,
+        disk,
+        slowdown,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate::test_utils::create_llama_config;
+
+    fn rtx_3090(host_ram: i64) -> OffloadSetup {
+        OffloadSetup::new(24_000_000_000, host_ram, 31.504e9, 936.096e9)
+    }
+
+    #[test]
+    fn test_estimate_offload_fits() {
+        let llama_config = create_llama_config();
+        let estimate = estimate_offload(
+            &llama_config,
+            Precision::BF16,
+            1,
+            4096,
+            rtx_3090(64_000_000_000),
+        );
+        assert!(!estimate.is_offloaded());
+        assert_eq!(estimate.slowdown(), Some(1.0));
+        let weights = 2 * llama_config.num_parameters();
+        let kv_cache = estimate_kv_cache(&llama_config, 1, 4096, Precision::BF16);
+        assert_eq!(
+            estimate.vram(),
+            weights + kv_cache + OverheadModel::new().overhead_bytes(weights, kv_cache)
+        );
+    }
+
+    #[test]
+    fn test_estimate_offload_to_host_ram() {
+        // The FP32 weights don't fit a 24 GB card
+        let llama_config = create_llama_config();
+        let weights = quantized_weights_bytes(&llama_config, Precision::FP32);
+        let estimate = estimate_offload(
+            &llama_config,
+            Precision::FP32,
+            1,
+            4096,
+            rtx_3090(64_000_000_000),
+        );
+        assert!(estimate.is_offloaded());
+        assert!(estimate.vram() <= 24_000_000_000);
+        assert_eq!(estimate.disk(), 0);
+        let layer_buffer =
+            Precision::FP32.weights_bytes(llama_config.parameter_breakdown().per_layer());
+        let resident = weights - estimate.host_ram();
+        let kv_cache = estimate_kv_cache(&llama_config, 1, 4096, Precision::FP32);
+        assert_eq!(
+            estimate.vram(),
+            resident
+                + kv_cache
+                + OverheadModel::new().overhead_bytes(resident, kv_cache)
+                + layer_buffer
+        );
+        // Reading through the PCIe link is 30 times slower than from the GPU memory
+        let slowdown = estimate.slowdown().unwrap();
+        assert!(slowdown > 2.0 && slowdown < 30.0);
+    }
+
+    #[test]
+    fn test_estimate_offload_to_disk() {
+        let llama_config = create_llama_config();
+        let host_ram = estimate_offload(
+            &llama_config,
+            Precision::FP32,
+            1,
+            4096,
+            rtx_3090(64_000_000_000),
+        );
+        let disk = estimate_offload(
+            &llama_config,
+            Precision::FP32,
+            1,
+            4096,
+            rtx_3090(2_000_000_000),
+        );
+        assert_eq!(disk.host_ram(), 2_000_000_000);
+        assert_eq!(disk.disk(), host_ram.host_ram() - 2_000_000_000);
+        assert!(disk.slowdown().unwrap() > host_ram.slowdown().unwrap());
+
+        // Without the bandwidths of the hardware scan, the slowdown is unknown
+        let unknown = estimate_offload(
+            &llama_config,
+            Precision::FP32,
+            1,
+            4096,
+            OffloadSetup::new(24_000_000_000, 64_000_000_000, 0.0, 0.0),
+        );
+        assert_eq!(unknown.host_ram(), host_ram.host_ram());
+        assert_eq!(unknown.slowdown(), None);
+    }
+}
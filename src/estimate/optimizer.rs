@@ -0,0 +1,128 @@
+//! Optimizer states memory estimates
+use std::str::FromStr;
+
+use crate::models::ModelConfigTrait;
+
+/// Number of bytes of the FP32 copy of every weight the mixed precision training updates
+pub const MASTER_WEIGHTS_BYTES_PER_PARAM: f64 = 4.0;
+
+/// Enumerate the optimizers of the full fine-tuning estimates
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Optimizer {
+    /// AdamW, with FP32 first and second moments
+    AdamW,
+    /// bitsandbytes 8-bit AdamW, with block-wise quantized moments
+    AdamW8bit,
+    /// SGD with momentum
+    SGD,
+    /// Adafactor without momentum, with factored second moments
+    Adafactor,
+}
+
+/// Optimizer implementation
+impl Optimizer {
+    /// Returns the number of bytes of the optimizer states of every parameter, without the
+    /// master weights. `hidden_size` sizes the factored moments of Adafactor, which only keeps
+    /// a row and a column vector per weight matrix.
+    pub fn state_bytes_per_param(&self, hidden_size: i32) -> f64 {
+        match self {
+            Optimizer::AdamW => 8.0,
+            // One byte per moment and a FP32 absmax every 2048 values
+            Optimizer::AdamW8bit => 2.0 * (1.0 + 4.0 / 2048.0),
+            Optimizer::SGD => 4.0,
+            Optimizer::Adafactor => 8.0 / hidden_size.max(1) as f64,
+        }
+    }
+}
+
+/// Build from the name of an optimizer (e.g. `adamw`, `adamw-8bit` or `adafactor`), as written
+/// in the Transformers `optim` argument, case insensitive
+impl FromStr for Optimizer {
+    type Err = String;
+
+    fn from_str(optimizer: &str) -> Result<Self, Self::Err> {
+        match optimizer.trim().to_lowercase().replace('-', "_").as_str() {
+            "adamw" | "adam" | "adamw_torch" | "adamw_torch_fused" => Ok(Optimizer::AdamW),
+            "adamw_8bit" | "adamw8bit" | "adamw_bnb_8bit" | "paged_adamw_8bit" => {
+                Ok(Optimizer::AdamW8bit)
+            }
+            "sgd" => Ok(Optimizer::SGD),
+            "adafactor" => Ok(Optimizer::Adafactor),
+            _ => Err(format!("Unknown optimizer: {}", optimizer)),
+        }
+    }
+}
+
+/// Estimate the memory of the optimizer states of a full fine-tuning, in bytes: the FP32 master
+/// weights of the mixed precision training and the states of every parameter.
+pub fn estimate_optimizer_memory(config: &dyn ModelConfigTrait, optimizer: Optimizer) -> i64 {
+    let bytes_per_param =
+        MASTER_WEIGHTS_BYTES_PER_PARAM + optimizer.state_bytes_per_param(config.hidden_size());
+    (config.num_parameters() as f64 * bytes_per_param).ceil() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Dtype, LlamaModelConfig, LlamaParams, ModelLibraries, RopeParams};
+
+    fn create_llama_config() -> LlamaModelConfig {
+        LlamaModelConfig::new(
+            LlamaParams::new(4096, 11008, 4096, 32, 32, None, None),
+            32000,
+            false,
+            RopeParams::new(None, None),
+            Dtype::F16,
+            "llama".to_string(),
+            vec![ModelLibraries::PyTorch],
+        )
+    }
+
+    #[test]
+    fn test_optimizer_state_bytes_per_param() {
+        assert_eq!(Optimizer::AdamW.state_bytes_per_param(4096), 8.0);
+        assert_eq!(Optimizer::AdamW8bit.state_bytes_per_param(4096), 2.00390625);
+        assert_eq!(Optimizer::SGD.state_bytes_per_param(4096), 4.0);
+        assert_eq!(
+            Optimizer::Adafactor.state_bytes_per_param(4096),
+            0.001953125
+        );
+        assert_eq!(Optimizer::Adafactor.state_bytes_per_param(0), 8.0);
+    }
+
+    #[test]
+    fn test_optimizer_from_str() {
+        assert_eq!(Optimizer::from_str("AdamW"), Ok(Optimizer::AdamW));
+        assert_eq!(Optimizer::from_str("adamw-8bit"), Ok(Optimizer::AdamW8bit));
+        assert_eq!(
+            Optimizer::from_str("paged_adamw_8bit"),
+            Ok(Optimizer::AdamW8bit)
+        );
+        assert_eq!(Optimizer::from_str("adafactor"), Ok(Optimizer::Adafactor));
+        assert!(Optimizer::from_str("lion").is_err());
+    }
+
+    #[test]
+    fn test_estimate_optimizer_memory() {
+        let llama_config = create_llama_config();
+        let num_parameters = llama_config.num_parameters();
+        // AdamW keeps 12 bytes per parameter, the usual 16 bytes with the weights and gradients
+        assert_eq!(
+            estimate_optimizer_memory(&llama_config, Optimizer::AdamW),
+            12 * num_parameters
+        );
+        assert_eq!(
+            estimate_optimizer_memory(&llama_config, Optimizer::SGD),
+            8 * num_parameters
+        );
+    }
+
+    #[test]
+    fn test_estimate_optimizer_memory_ordering() {
+        let llama_config = create_llama_config();
+        let memory = |optimizer| estimate_optimizer_memory(&llama_config, optimizer);
+        assert!(memory(Optimizer::Adafactor) < memory(Optimizer::AdamW8bit));
+        assert!(memory(Optimizer::AdamW8bit) < memory(Optimizer::SGD));
+        assert!(memory(Optimizer::SGD) < memory(Optimizer::AdamW));
+    }
+}
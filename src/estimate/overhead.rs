@@ -0,0 +1,88 @@
+//! Framework memory overhead model
+
+/// Memory taken by the CUDA context and the framework kernels, whatever the model (bytes)
+pub const FRAMEWORK_OVERHEAD_BYTES: i64 = 512 * 1024 * 1024;
+/// Share of the weights memory lost to the allocator fragmentation and the temporary buffers
+pub const FRAMEWORK_OVERHEAD_RATIO: f64 = 0.05;
+/// Share of the memory allocated on the fly (KV cache, activations) lost to the fragmentation of
+/// the caching allocator
+pub const FRAGMENTATION_RATIO: f64 = 0.10;
+/// Default share of the device memory kept free for the memory spikes, a model needing more than
+/// the rest is a tight fit (see `OverheadModel::safety_margin`)
+pub const TIGHT_MARGIN: f64 = 0.10;
+
+/// A struct representing the memory the framework takes on top of the model, and the share of the
+/// device memory kept free for the memory spikes. Every estimator applies it, the defaults can be
+/// overridden for a given framework or driver.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OverheadModel {
+    /// The memory of the CUDA context and the framework kernels, in bytes
+    pub cuda_context: i64,
+    /// The share of the weights memory the allocator keeps for the temporary buffers
+    pub allocator_headroom: f64,
+    /// The share of the memory allocated on the fly lost to the fragmentation
+    pub fragmentation: f64,
+    /// The share of the device memory kept free, a model needing more than the rest is a tight fit
+    pub safety_margin: f64,
+}
+
+/// OverheadModel implementation
+impl OverheadModel {
+    /// Build a new `OverheadModel` with the defaults of PyTorch on CUDA
+    pub fn new() -> OverheadModel {
+        OverheadModel {
+            cuda_context: FRAMEWORK_OVERHEAD_BYTES,
+            allocator_headroom: FRAMEWORK_OVERHEAD_RATIO,
+            fragmentation: FRAGMENTATION_RATIO,
+            safety_margin: TIGHT_MARGIN,
+        }
+    }
+    /// Returns the memory the framework takes next to `weights` bytes of weights and `dynamic`
+    /// bytes allocated on the fly (KV cache, activations), in bytes
+    pub fn overhead_bytes(&self, weights: i64, dynamic: i64) -> i64 {
+        self.cuda_context
+            + (weights as f64 * self.allocator_headroom).round() as i64
+            + (dynamic as f64 * self.fragmentation).round() as i64
+    }
+}
+
+/// Implement the default `OverheadModel`, the one of PyTorch on CUDA
+impl Default for OverheadModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overhead_model_defaults() {
+        let overhead = OverheadModel::new();
+        assert_eq!(overhead, OverheadModel::default());
+        assert_eq!(overhead.cuda_context, 512 * 1024 * 1024);
+        assert_eq!(overhead.safety_margin, TIGHT_MARGIN);
+        assert_eq!(overhead.overhead_bytes(0, 0), FRAMEWORK_OVERHEAD_BYTES);
+    }
+
+    #[test]
+    fn test_overhead_bytes() {
+        let overhead = OverheadModel::new();
+        assert_eq!(
+            overhead.overhead_bytes(16_000_000_000, 2_000_000_000),
+            FRAMEWORK_OVERHEAD_BYTES + 800_000_000 + 200_000_000
+        );
+        // A lighter runtime, without fragmentation
+        let overhead = OverheadModel {
+            cuda_context: 300 * 1024 * 1024,
+            allocator_headroom: 0.0,
+            fragmentation: 0.0,
+            safety_margin: 0.05,
+        };
+        assert_eq!(
+            overhead.overhead_bytes(16_000_000_000, 2_000_000_000),
+            300 * 1024 * 1024
+        );
+    }
+}
@@ -0,0 +1,277 @@
+//! Pipeline parallel partition planning
+use std::ops::Range;
+
+use crate::estimate::activations::activation_bytes;
+use crate::estimate::quantization::weights_bytes_by_component;
+use crate::estimate::{cache_precision, estimate_kv_cache, OverheadModel};
+use crate::models::{ModelConfigTrait, Precision};
+
+/// A struct representing a stage of a pipeline, the contiguous range of layers a device runs
+#[derive(Clone, Debug, PartialEq)]
+pub struct PipelineStage {
+    /// The layers of the stage
+    layers: Range<i32>,
+    /// The memory the stage needs, in bytes
+    memory: i64,
+    /// The memory of the device running the stage, in bytes
+    available: i64,
+}
+
+/// PipelineStage implementation
+impl PipelineStage {
+    /// Build a new `PipelineStage` struct based on the provided parameters
+    pub fn new(layers: Range<i32>, memory: i64, available: i64) -> PipelineStage {
+        PipelineStage {
+            layers,
+            memory,
+            available,
+        }
+    }
+    /// Returns the layers of the stage
+    pub fn layers(&self) -> Range<i32> {
+        self.layers.clone()
+    }
+    /// Returns the number of layers of the stage
+    pub fn num_layers(&self) -> i32 {
+        self.layers.len() as i32
+    }
+    /// Returns the memory the stage needs
+    pub fn memory(&self) -> i64 {
+        self.memory
+    }
+    /// Returns the memory of the device running the stage
+    pub fn available(&self) -> i64 {
+        self.available
+    }
+}
+
+/// A struct representing the partition of a model into pipeline stages
+#[derive(Clone, Debug, PartialEq)]
+pub struct PipelinePlan {
+    /// The stages, in the order of the devices
+    stages: Vec<PipelineStage>,
+    /// The share of the step time the devices are idle
+    bubble_overhead: f64,
+}
+
+/// PipelinePlan implementation
+impl PipelinePlan {
+    /// Build a new `PipelinePlan` struct based on the provided parameters
+    pub fn new(stages: Vec<PipelineStage>, bubble_overhead: f64) -> PipelinePlan {
+        PipelinePlan {
+            stages,
+            bubble_overhead,
+        }
+    }
+    /// Returns the stages, in the order of the devices
+    pub fn stages(&self) -> &[PipelineStage] {
+        &self.stages
+    }
+    /// Returns the share of the step time the devices are idle, waiting for the previous or the
+    /// next stage (e.g. `0.5` when they are idle half of the time)
+    pub fn bubble_overhead(&self) -> f64 {
+        self.bubble_overhead
+    }
+}
+
+/// Returns the share of the step time lost in the pipeline bubble, `(p - 1) / (m + p - 1)` for
+/// `p` stages and `m` micro batches
+pub fn pipeline_bubble(num_stages: i32, num_microbatches: i32) -> f64 {
+    let num_stages = num_stages.max(1) as f64;
+    let num_microbatches = num_microbatches.max(1) as f64;
+    (num_stages - 1.0) / (num_microbatches + num_stages - 1.0)
+}
+
+/// Plan the partition of a model into pipeline stages for inference, one stage per device of
+/// `device_memories` (in bytes) in the given order, with a KV cache of `batch_size` sequences
+/// of `seq_len` tokens split into `num_microbatches` micro batches. Returns `None` when the
+/// devices don't have enough memory.
+///
+/// The layers are assigned in proportion of the memory every device has left, once the
+/// embeddings are placed on the first stage and the LM head on the last one.
+pub fn plan_pipeline(
+    config: &dyn ModelConfigTrait,
+    precision: Precision,
+    batch_size: i32,
+    seq_len: i32,
+    num_microbatches: i32,
+    device_memories: &[i64],
+) -> Option<PipelinePlan> {
+    plan_pipeline_with_overhead(
+        config,
+        precision,
+        batch_size,
+        seq_len,
+        num_microbatches,
+        device_memories,
+        &OverheadModel::new(),
+    )
+}
+
+/// Plan the partition of a model into pipeline stages for inference, with the given overhead
+/// model on every device (see `plan_pipeline`)
+pub fn plan_pipeline_with_overhead(
+    config: &dyn ModelConfigTrait,
+    precision: Precision,
+    batch_size: i32,
+    seq_len: i32,
+    num_microbatches: i32,
+    device_memories: &[i64],
+    overhead: &OverheadModel,
+) -> Option<PipelinePlan> {
+    let num_stages = device_memories.len();
+    let num_layers = config.parameter_breakdown().num_layers();
+    if num_stages == 0 || num_layers == 0 {
+        return None;
+    }
+
+    let weights = weights_bytes_by_component(config, precision);
+    let kv_cache = estimate_kv_cache(config, batch_size, seq_len, cache_precision(precision));
+    let layer_weights = weights.linear / num_layers as f64;
+    let layer_memory = layer_weights * (1.0 + overhead.allocator_headroom)
+        + kv_cache as f64 * (1.0 + overhead.fragmentation) / num_layers as f64;
+    // The hidden states of a micro batch are sent to the next stage
+    let send_buffer = (batch_size as f64 / num_microbatches.max(1) as f64).ceil() as i64
+        * seq_len as i64
+        * config.hidden_size() as i64
+        * activation_bytes(precision);
+    let fixed_memory = |stage: usize| {
+        let mut weights_bytes = 0.0;
+        if stage == 0 {
+            weights_bytes += weights.embeddings;
+        }
+        if stage == num_stages - 1 {
+            weights_bytes += weights.lm_head + weights.other;
+        }
+        weights_bytes * (1.0 + overhead.allocator_headroom)
+            + (overhead.cuda_context + send_buffer) as f64
+    };
+
+    let usable: Vec<f64> = device_memories
+        .iter()
+        .enumerate()
+        .map(|(stage, memory)| (*memory as f64 - fixed_memory(stage)).max(0.0))
+        .collect();
+    let total_usable: f64 = usable.iter().sum();
+    if total_usable < num_layers as f64 * layer_memory {
+        return None;
+    }
+
+    // Proportional shares, the remaining layers go to the largest remainders that still fit
+    let shares: Vec<f64> = usable
+        .iter()
+        .map(|usable| num_layers as f64 * usable / total_usable)
+        .collect();
+    let mut stage_layers: Vec<i32> = shares.iter().map(|share| share.floor() as i32).collect();
+    let mut by_remainder: Vec<usize> = (0..num_stages).collect();
+    by_remainder.sort_by(|a, b| {
+        (shares[*b] - shares[*b].floor()).total_cmp(&(shares[*a] - shares[*a].floor()))
+    });
+    let mut remaining = num_layers - stage_layers.iter().sum::<i32>();
+    for stage in by_remainder.into_iter().cycle().take(2 * num_stages) {
+        if remaining == 0 {
+            break;
+        }
+        if (stage_layers[stage] + 1) as f64 * layer_memory <= usable[stage] {
+            stage_layers[stage] += 1;
+            remaining -= 1;
+        }
+    }
+    if remaining > 0 {
+        return None;
+    }
+
+    let mut first_layer = 0;
+    let stages = stage_layers
+        .iter()
+        .enumerate()
+        .map(|(stage, &num_stage_layers)| {
+            let layers = first_layer..first_layer + num_stage_layers;
+            first_layer += num_stage_layers;
+            let memory = fixed_memory(stage) + num_stage_layers as f64 * layer_memory;
+            PipelineStage::new(layers, memory.ceil() as i64, device_memories[stage])
+        })
+        .collect();
+    Some(PipelinePlan::new(
+        stages,
+        pipeline_bubble(num_stages as i32, num_microbatches),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate::test_utils::create_llama_config;
+
+    #[test]
+    fn test_pipeline_bubble() {
+        assert_eq!(pipeline_bubble(1, 1), 0.0);
+        assert_eq!(pipeline_bubble(2, 1), 0.5);
+        assert_eq!(pipeline_bubble(4, 8), 3.0 / 11.0);
+    }
+
+    #[test]
+    fn test_plan_pipeline() {
+        let llama_config = create_llama_config();
+        let plan = plan_pipeline(
+            &llama_config,
+            Precision::FP16,
+            1,
+            4096,
+            1,
+            &[24_000_000_000, 24_000_000_000],
+        )
+        .unwrap();
+        let stages = plan.stages();
+        assert_eq!(stages.len(), 2);
+        assert_eq!(stages[0].layers(), 0..16);
+        assert_eq!(stages[1].layers(), 16..32);
+        assert_eq!(plan.bubble_overhead(), 0.5);
+
+        // The larger card runs more layers, every stage fits its card
+        let plan = plan_pipeline(
+            &llama_config,
+            Precision::FP16,
+            1,
+            4096,
+            4,
+            &[8_000_000_000, 24_000_000_000],
+        )
+        .unwrap();
+        let stages = plan.stages();
+        assert!(stages[0].num_layers() < stages[1].num_layers());
+        assert_eq!(stages[0].num_layers() + stages[1].num_layers(), 32);
+        assert_eq!(stages[1].layers().end, 32);
+        for stage in stages {
+            assert!(stage.memory() <= stage.available());
+        }
+        assert_eq!(plan.bubble_overhead(), 0.2);
+    }
+
+    #[test]
+    fn test_plan_pipeline_does_not_fit() {
+        let llama_config = create_llama_config();
+        let plan = |device_memories: &[i64]| {
+            plan_pipeline(&llama_config, Precision::FP16, 1, 4096, 1, device_memories)
+        };
+        assert!(plan(&[]).is_none());
+        assert!(plan(&[4_000_000_000, 4_000_000_000]).is_none());
+        assert!(plan(&[6_000_000_000, 6_000_000_000, 6_000_000_000, 6_000_000_000]).is_some());
+
+        // A heavier runtime leaves less memory to the layers of every stage
+        let overhead = OverheadModel {
+            cuda_context: 4_000_000_000,
+            ..OverheadModel::new()
+        };
+        assert!(plan_pipeline_with_overhead(
+            &llama_config,
+            Precision::FP16,
+            1,
+            4096,
+            1,
+            &[6_000_000_000, 6_000_000_000, 6_000_000_000, 6_000_000_000],
+            &overhead,
+        )
+        .is_none());
+    }
+}
@@ -0,0 +1,296 @@
+//! Quantized weights memory estimates
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use serde::Serialize;
+
+use crate::estimate::{estimate_inference_memory, InferenceMemory};
+use crate::models::{ModelConfigTrait, Precision, QuantType};
+
+/// Number of bytes of the weights the quantization methods leave in 16-bit (embeddings, LM head,
+/// norms and biases)
+pub const UNQUANTIZED_BYTES_PER_PARAM: f64 = 2.0;
+
+/// The quantization levels of the table, named as the quantized checkpoints of the hub are
+pub const QUANTIZATION_LEVELS: [(&str, Precision); 12] = [
+    ("FP16", Precision::FP16),
+    ("Q8_0", Precision::GGUF(QuantType::Q8_0)),
+    ("GPTQ 8-bit", Precision::GPTQ { bits: 8 }),
+    ("INT8 (bitsandbytes)", Precision::INT8),
+    ("Q6_K", Precision::GGUF(QuantType::Q6_K)),
+    ("Q5_K_M", Precision::GGUF(QuantType::Q5_K)),
+    ("Q4_K_M", Precision::GGUF(QuantType::Q4_K)),
+    ("GPTQ 4-bit", Precision::GPTQ { bits: 4 }),
+    ("AWQ 4-bit", Precision::AWQ { bits: 4 }),
+    ("NF4 (bitsandbytes)", Precision::NF4),
+    ("Q3_K_M", Precision::GGUF(QuantType::Q3_K)),
+    ("Q2_K", Precision::GGUF(QuantType::Q2_K)),
+];
+
+/// Perplexity of LLaMA 7B on WikiText-2 in FP16, the reference of the llama.cpp quantization
+/// deltas
+const LLAMA_7B_PERPLEXITY: f64 = 5.9066;
+
+/// A struct representing the published quality loss of a quantization level against FP16
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct QualityLoss {
+    /// The increase of the WikiText-2 perplexity, in percent
+    perplexity_increase: f64,
+    /// The benchmark the increase was measured with
+    source: &'static str,
+}
+
+/// QualityLoss implementation
+impl QualityLoss {
+    /// Build a new `QualityLoss` struct based on the provided parameters
+    pub fn new(perplexity_increase: f64, source: &'static str) -> QualityLoss {
+        QualityLoss {
+            perplexity_increase,
+            source,
+        }
+    }
+    /// Returns the increase of the perplexity against FP16, in percent
+    pub fn perplexity_increase(&self) -> f64 {
+        self.perplexity_increase
+    }
+    /// Returns the benchmark the increase was measured with
+    pub fn source(&self) -> &'static str {
+        self.source
+    }
+}
+
+/// Implement the display of the QualityLoss struct
+impl Display for QualityLoss {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self.perplexity_increase {
+            increase if increase < 0.1 => write!(f, "<0.1% quality loss"),
+            increase if increase < 1.0 => write!(f, "~{:.1}% quality loss", increase),
+            increase => write!(f, "~{:.0}% quality loss", increase),
+        }
+    }
+}
+
+/// Returns the published quality loss of a precision against FP16, `None` for the unquantized
+/// precisions and the levels without a reference measure.
+///
+/// The deltas are perplexity increases of LLaMA 7B on WikiText-2: the llama.cpp `quantize`
+/// table for the GGUF files, the AWQ paper for the 4-bit GPTQ and AWQ checkpoints, LLM.int8() for
+/// bitsandbytes INT8 and QLoRA for NF4. Larger models lose less, these are upper bounds.
+pub fn quality_loss(precision: Precision) -> Option<QualityLoss> {
+    let llama_cpp = |delta: f64| {
+        QualityLoss::new(
+            delta / LLAMA_7B_PERPLEXITY * 100.0,
+            "llama.cpp quantize, LLaMA 7B WikiText-2",
+        )
+    };
+    match precision {
+        Precision::FP32 | Precision::FP16 | Precision::BF16 => None,
+        Precision::FP8 => Some(QualityLoss::new(0.1, "FP8 formats, LLaMA 7B WikiText-2")),
+        Precision::INT8 => Some(QualityLoss::new(0.1, "LLM.int8(), LLaMA 7B WikiText-2")),
+        Precision::NF4 => Some(QualityLoss::new(2.5, "QLoRA, LLaMA 7B WikiText-2")),
+        Precision::GPTQ { bits: 8 } => Some(QualityLoss::new(0.1, "GPTQ, LLaMA 7B WikiText-2")),
+        Precision::GPTQ { bits: 4 } => Some(QualityLoss::new(2.6, "AWQ, LLaMA 7B WikiText-2")),
+        Precision::AWQ { bits: 4 } => Some(QualityLoss::new(1.8, "AWQ, LLaMA 7B WikiText-2")),
+        Precision::GPTQ { .. } | Precision::AWQ { .. } => None,
+        Precision::GGUF(quant_type) => match quant_type {
+            QuantType::Q8_0 => Some(llama_cpp(0.0004)),
+            QuantType::Q6_K => Some(llama_cpp(0.0008)),
+            QuantType::Q5_K => Some(llama_cpp(0.0122)),
+            QuantType::Q5_1 => Some(llama_cpp(0.0349)),
+            QuantType::Q5_0 => Some(llama_cpp(0.0683)),
+            QuantType::Q4_K => Some(llama_cpp(0.0532)),
+            QuantType::Q4_1 => Some(llama_cpp(0.1585)),
+            QuantType::Q4_0 => Some(llama_cpp(0.2166)),
+            QuantType::Q3_K => Some(llama_cpp(0.2496)),
+            QuantType::Q2_K => Some(llama_cpp(0.8698)),
+            QuantType::F16 | QuantType::F32 => None,
+        },
+    }
+}
+
+/// A struct representing the memory of the weights of every component of a model, in bytes
+pub(crate) struct WeightsBytes {
+    /// Attention and MLP linear layers of all the blocks
+    pub(crate) linear: f64,
+    /// Token, position and patch embeddings
+    pub(crate) embeddings: f64,
+    /// LM head, 0 when it is tied to the token embeddings
+    pub(crate) lm_head: f64,
+    /// Norms, biases and any other parameter
+    pub(crate) other: f64,
+}
+
+/// WeightsBytes implementation
+impl WeightsBytes {
+    /// Returns the memory of all the weights
+    pub(crate) fn total(&self) -> i64 {
+        (self.linear + self.embeddings + self.lm_head + self.other).ceil() as i64
+    }
+}
+
+/// Returns the memory of the weights of every component of a model in the given precision
+pub(crate) fn weights_bytes_by_component(
+    config: &dyn ModelConfigTrait,
+    precision: Precision,
+) -> WeightsBytes {
+    let breakdown = config.parameter_breakdown();
+    let linear = (breakdown.attention() + breakdown.mlp()) as f64 * precision.bytes_per_param();
+    let unquantized_bytes = match precision.is_quantized() {
+        true => UNQUANTIZED_BYTES_PER_PARAM,
+        false => precision.bytes_per_param(),
+    };
+    let (embeddings, lm_head) = match precision {
+        Precision::GGUF(quant_type) if precision.is_quantized() => {
+            let output_type = match quant_type.bits_per_weight() < QuantType::Q6_K.bits_per_weight()
+            {
+                true => QuantType::Q6_K,
+                false => quant_type,
+            };
+            (
+                breakdown.embeddings() as f64 * precision.bytes_per_param(),
+                breakdown.lm_head() as f64 * Precision::GGUF(output_type).bytes_per_param(),
+            )
+        }
+        _ => (
+            breakdown.embeddings() as f64 * unquantized_bytes,
+            breakdown.lm_head() as f64 * unquantized_bytes,
+        ),
+    };
+    WeightsBytes {
+        linear,
+        embeddings,
+        lm_head,
+        other: breakdown.other() as f64 * unquantized_bytes,
+    }
+}
+
+/// Returns the memory of the weights of a model in the given precision, in bytes.
+///
+/// Only the linear layers of the blocks are quantized: bitsandbytes, GPTQ, AWQ and FP8 keep the
+/// embeddings and the LM head in 16-bit, llama.cpp quantizes the embeddings to the file type and
+/// the LM head to at least Q6_K. The scales and zero points of every group are included.
+pub fn quantized_weights_bytes(config: &dyn ModelConfigTrait, precision: Precision) -> i64 {
+    weights_bytes_by_component(config, precision).total()
+}
+
+/// Estimate the memory needed to load a model for inference at each of the
+/// `QUANTIZATION_LEVELS`, from the largest to the smallest
+pub fn estimate_quantized_inference(
+    config: &dyn ModelConfigTrait,
+) -> Vec<(&'static str, InferenceMemory)> {
+    QUANTIZATION_LEVELS
+        .iter()
+        .map(|&(name, precision)| (name, estimate_inference_memory(config, precision)))
+        .collect()
+}
+
+/// Estimate the memory needed to load a model for inference at each of the
+/// `QUANTIZATION_LEVELS`, with the quality each level loses against FP16 (see `quality_loss`)
+pub fn estimate_quantization_tradeoffs(
+    config: &dyn ModelConfigTrait,
+) -> Vec<(&'static str, InferenceMemory, Option<QualityLoss>)> {
+    estimate_quantized_inference(config)
+        .into_iter()
+        .zip(QUANTIZATION_LEVELS)
+        .map(|((name, memory), (_, precision))| (name, memory, quality_loss(precision)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate::test_utils::create_llama_config;
+    use crate::models::{Dtype, MixtralModelConfig, MixtralParams, ModelLibraries, RopeParams};
+
+    #[test]
+    fn test_quantized_weights_bytes() {
+        let llama_config = create_llama_config();
+        let breakdown = llama_config.parameter_breakdown();
+        let linear = (breakdown.attention() + breakdown.mlp()) as f64;
+        let unquantized = (breakdown.total() as f64 - linear) * 2.0;
+
+        assert_eq!(
+            quantized_weights_bytes(&llama_config, Precision::BF16),
+            2 * llama_config.num_parameters()
+        );
+        assert_eq!(
+            quantized_weights_bytes(&llama_config, Precision::AWQ { bits: 4 }),
+            (linear * 0.51953125 + unquantized).ceil() as i64
+        );
+        // The LM head is kept in Q6_K, the embeddings follow the file type
+        assert_eq!(
+            quantized_weights_bytes(&llama_config, Precision::GGUF(QuantType::Q4_K)),
+            ((linear + breakdown.embeddings() as f64) * 0.5625
+                + breakdown.lm_head() as f64 * 0.8203125)
+                .ceil() as i64
+        );
+        assert_eq!(
+            quantized_weights_bytes(&llama_config, Precision::GGUF(QuantType::Q8_0)),
+            (breakdown.total() as f64 * 1.0625).ceil() as i64
+        );
+    }
+
+    #[test]
+    fn test_quantized_weights_bytes_match_checkpoints() {
+        // Sizes of the published Mixtral 8x7B quantized checkpoints
+        let mixtral_config = MixtralModelConfig::new(
+            MixtralParams::new(4096, 14336, 32768, 32, 32, Some(8), 8, 2),
+            32000,
+            false,
+            RopeParams::new(Some(1e6), None),
+            Dtype::BF16,
+            "mixtral".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        let deviation = |precision: Precision, checkpoint_bytes: f64| {
+            (quantized_weights_bytes(&mixtral_config, precision) as f64 - checkpoint_bytes).abs()
+                / checkpoint_bytes
+        };
+        assert!(deviation(Precision::GPTQ { bits: 4 }, 23.8e9) < 0.05);
+        assert!(deviation(Precision::GGUF(QuantType::Q8_0), 49.62e9) < 0.01);
+        assert!(deviation(Precision::GGUF(QuantType::Q4_K), 26.44e9) < 0.01);
+    }
+
+    #[test]
+    fn test_estimate_quantized_inference() {
+        let llama_config = create_llama_config();
+        let table = estimate_quantized_inference(&llama_config);
+        assert_eq!(table.len(), QUANTIZATION_LEVELS.len());
+        assert_eq!(
+            table[0],
+            (
+                "FP16",
+                estimate_inference_memory(&llama_config, Precision::FP16)
+            )
+        );
+        let (smallest, _) = table
+            .iter()
+            .min_by_key(|(_, memory)| memory.total())
+            .unwrap();
+        assert_eq!(*smallest, "Q2_K");
+        for (_, memory) in &table[1..] {
+            assert!(memory.total() < table[0].1.total());
+        }
+    }
+
+    #[test]
+    fn test_quality_loss() {
+        assert_eq!(quality_loss(Precision::BF16), None);
+        let q4 = quality_loss(Precision::GGUF(QuantType::Q4_K)).unwrap();
+        let q8 = quality_loss(Precision::GGUF(QuantType::Q8_0)).unwrap();
+        assert!(q8.perplexity_increase() < q4.perplexity_increase());
+        assert_eq!(q4.to_string(), "~0.9% quality loss");
+        assert_eq!(q8.to_string(), "<0.1% quality loss");
+        assert_eq!(
+            quality_loss(Precision::GGUF(QuantType::Q2_K))
+                .unwrap()
+                .to_string(),
+            "~15% quality loss"
+        );
+
+        // Every quantized level of the table has a published loss
+        let llama_config = create_llama_config();
+        let tradeoffs = estimate_quantization_tradeoffs(&llama_config);
+        assert_eq!(tradeoffs[0].2, None);
+        assert!(tradeoffs[1..].iter().all(|(_, _, loss)| loss.is_some()));
+    }
+}
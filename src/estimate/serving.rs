@@ -0,0 +1,261 @@
+//! Paged attention serving memory estimates
+use crate::estimate::activations::activation_bytes;
+use crate::estimate::{
+    cache_precision, estimate_tensor_parallel, kv_cache_bytes_per_token, OverheadModel,
+};
+use crate::models::{ModelConfigTrait, Precision};
+
+/// Default share of the GPU memory a vLLM worker allocates (`--gpu-memory-utilization`)
+pub const GPU_MEMORY_UTILIZATION: f64 = 0.9;
+/// Default number of tokens of a KV cache block (`--block-size`)
+pub const BLOCK_SIZE: i32 = 16;
+/// Default maximum number of sequences scheduled in the same step (`--max-num-seqs`)
+pub const MAX_NUM_SEQS: i32 = 256;
+
+/// A struct representing the engine arguments of a vLLM server
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ServingSetup {
+    /// The share of the GPU memory the worker allocates, between 0 and 1
+    pub gpu_memory_utilization: f64,
+    /// The number of tokens of a KV cache block
+    pub block_size: i32,
+    /// The longest sequence (prompt and generated tokens) a request can have
+    pub max_model_len: i32,
+    /// The maximum number of sequences scheduled in the same step
+    pub max_num_seqs: i32,
+    /// The maximum number of tokens of a step, the profiling run is done with this many tokens
+    pub max_num_batched_tokens: i32,
+    /// The number of GPUs the model is split across with tensor parallelism
+    pub tp_size: i32,
+    /// The memory the framework takes on every GPU
+    pub overhead: OverheadModel,
+}
+
+/// ServingSetup implementation
+impl ServingSetup {
+    /// Build a new `ServingSetup` with the vLLM default engine arguments on a single GPU
+    pub fn new(max_model_len: i32) -> ServingSetup {
+        ServingSetup {
+            gpu_memory_utilization: GPU_MEMORY_UTILIZATION,
+            block_size: BLOCK_SIZE,
+            max_model_len,
+            max_num_seqs: MAX_NUM_SEQS,
+            max_num_batched_tokens: max_model_len.max(2048),
+            tp_size: 1,
+            overhead: OverheadModel::new(),
+        }
+    }
+}
+
+/// A struct representing how a vLLM worker splits the memory of a GPU, in bytes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ServingEstimate {
+    /// The memory of the weights shard
+    weights: i64,
+    /// The peak memory of the activations during the profiling run
+    activations: i64,
+    /// The memory allocated outside of PyTorch (CUDA context, NCCL buffers)
+    non_torch: i64,
+    /// The number of KV cache blocks allocated
+    num_blocks: i64,
+    /// The memory of a KV cache block
+    block_bytes: i64,
+    /// How many sequences of `max_model_len` tokens the KV cache blocks hold at once
+    max_concurrency: f64,
+    /// The number of sequences actually scheduled at once
+    max_concurrent_sequences: i32,
+}
+
+/// ServingEstimate implementation
+impl ServingEstimate {
+    /// Build a new `ServingEstimate` struct based on the provided parameters
+    pub fn new(
+        weights: i64,
+        activations: i64,
+        non_torch: i64,
+        num_blocks: i64,
+        block_bytes: i64,
+        max_concurrency: f64,
+        max_concurrent_sequences: i32,
+    ) -> ServingEstimate {
+        ServingEstimate {
+            weights,
+            activations,
+            non_torch,
+            num_blocks,
+            block_bytes,
+            max_concurrency,
+            max_concurrent_sequences,
+        }
+    }
+    /// Returns the memory of the weights shard of every GPU
+    pub fn weights(&self) -> i64 {
+        self.weights
+    }
+    /// Returns the peak memory of the activations during the profiling run
+    pub fn activations(&self) -> i64 {
+        self.activations
+    }
+    /// Returns the memory allocated outside of PyTorch
+    pub fn non_torch(&self) -> i64 {
+        self.non_torch
+    }
+    /// Returns the number of KV cache blocks allocated on every GPU
+    pub fn num_blocks(&self) -> i64 {
+        self.num_blocks
+    }
+    /// Returns the memory of a KV cache block
+    pub fn block_bytes(&self) -> i64 {
+        self.block_bytes
+    }
+    /// Returns the memory of the KV cache blocks of every GPU
+    pub fn kv_cache(&self) -> i64 {
+        self.num_blocks * self.block_bytes
+    }
+    /// Returns how many sequences of `max_model_len` tokens the KV cache holds at once, the
+    /// "maximum concurrency" vLLM logs at startup
+    pub fn max_concurrency(&self) -> f64 {
+        self.max_concurrency
+    }
+    /// Returns the number of full-length sequences scheduled at once, capped to `max_num_seqs`
+    pub fn max_concurrent_sequences(&self) -> i32 {
+        self.max_concurrent_sequences
+    }
+    /// Returns the total memory every GPU allocates
+    pub fn total(&self) -> i64 {
+        self.weights + self.activations + self.non_torch + self.kv_cache()
+    }
+}
+
+/// Estimate how a vLLM server splits the memory of GPUs of `device_memory` bytes between the
+/// weights, the activations and the paged KV cache. Returns `None` when the attention heads can't
+/// be split across `tp_size` GPUs, or when the KV cache blocks can't hold a single sequence of
+/// `max_model_len` tokens (vLLM refuses to start).
+///
+/// vLLM allocates `gpu_memory_utilization` of the GPU memory, profiles a forward pass of
+/// `max_num_batched_tokens` tokens, and turns the rest into blocks of `block_size` tokens. The
+/// blocks are allocated for every layer, the sliding window of the model is not taken into account.
+pub fn estimate_serving(
+    config: &dyn ModelConfigTrait,
+    precision: Precision,
+    device_memory: i64,
+    setup: &ServingSetup,
+) -> Option<ServingEstimate> {
+    if setup.block_size < 1 || setup.max_model_len < 1 {
+        return None;
+    }
+    let tensor_parallel = estimate_tensor_parallel(config, precision, 0, 0, setup.tp_size)?;
+    let weights = tensor_parallel.weights();
+    let non_torch = setup.overhead.cuda_context + tensor_parallel.communication();
+
+    // The profiling run keeps the MLP intermediate states of every token and the FP32 logits of
+    // every sequence
+    let tp_size = setup.tp_size as i64;
+    let tokens = setup.max_num_batched_tokens.max(1) as i64;
+    let sequences = (setup.max_num_seqs as i64).clamp(1, tokens);
+    let hidden_states = tokens
+        * (2 * config.intermediate_size() as i64 / tp_size + 4 * config.hidden_size() as i64);
+    let logits = sequences * config.vocab_size() as i64 / tp_size * 4;
+    let activations = hidden_states * activation_bytes(precision) + logits;
+
+    let num_key_value_heads = config.num_key_value_heads().max(1);
+    let kv_heads_per_gpu = (num_key_value_heads + setup.tp_size - 1) / setup.tp_size;
+    let bytes_per_token = kv_cache_bytes_per_token(config, cache_precision(precision))
+        * kv_heads_per_gpu as f64
+        / num_key_value_heads as f64;
+    let block_bytes = (setup.block_size as f64 * bytes_per_token).ceil() as i64;
+    if block_bytes <= 0 {
+        return None;
+    }
+
+    let budget = (device_memory as f64 * setup.gpu_memory_utilization).floor() as i64
+        - weights
+        - activations
+        - non_torch;
+    let num_blocks = budget.max(0) / block_bytes;
+    let max_concurrency =
+        (num_blocks * setup.block_size as i64) as f64 / setup.max_model_len as f64;
+    if max_concurrency < 1.0 {
+        return None;
+    }
+    Some(ServingEstimate::new(
+        weights,
+        activations,
+        non_torch,
+        num_blocks,
+        block_bytes,
+        max_concurrency,
+        (max_concurrency.floor() as i32).min(setup.max_num_seqs),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate::test_utils::create_llama_config;
+
+    #[test]
+    fn test_estimate_serving() {
+        let llama_config = create_llama_config();
+        let setup = ServingSetup::new(8192);
+        let estimate =
+            estimate_serving(&llama_config, Precision::BF16, 80_000_000_000, &setup).unwrap();
+        assert_eq!(estimate.weights(), 2 * llama_config.num_parameters());
+        // 16 tokens of 128 KiB
+        assert_eq!(estimate.block_bytes(), 16 * 131072);
+        assert!(estimate.total() <= 72_000_000_000);
+        assert!(72_000_000_000 - estimate.total() < estimate.block_bytes());
+        assert_eq!(
+            estimate.max_concurrency(),
+            (estimate.num_blocks() * 16) as f64 / 8192.0
+        );
+        assert_eq!(
+            estimate.max_concurrent_sequences(),
+            estimate.max_concurrency().floor() as i32
+        );
+
+        // Short sequences are capped by the scheduler
+        let estimate = estimate_serving(
+            &llama_config,
+            Precision::BF16,
+            80_000_000_000,
+            &ServingSetup::new(128),
+        )
+        .unwrap();
+        assert_eq!(estimate.max_concurrent_sequences(), MAX_NUM_SEQS);
+    }
+
+    #[test]
+    fn test_estimate_serving_memory_utilization() {
+        let llama_config = create_llama_config();
+        let mut setup = ServingSetup::new(8192);
+        let default =
+            estimate_serving(&llama_config, Precision::BF16, 24_000_000_000, &setup).unwrap();
+        setup.gpu_memory_utilization = 0.95;
+        let more =
+            estimate_serving(&llama_config, Precision::BF16, 24_000_000_000, &setup).unwrap();
+        assert!(more.num_blocks() > default.num_blocks());
+
+        // The weights alone don't leave room for a single sequence
+        setup.gpu_memory_utilization = 0.55;
+        assert!(estimate_serving(&llama_config, Precision::BF16, 24_000_000_000, &setup).is_none());
+    }
+
+    #[test]
+    fn test_estimate_serving_tensor_parallel() {
+        let llama_config = create_llama_config();
+        let mut setup = ServingSetup::new(8192);
+        let single =
+            estimate_serving(&llama_config, Precision::BF16, 24_000_000_000, &setup).unwrap();
+        setup.tp_size = 2;
+        let split =
+            estimate_serving(&llama_config, Precision::BF16, 24_000_000_000, &setup).unwrap();
+        assert!(split.weights() < single.weights());
+        assert_eq!(split.block_bytes(), single.block_bytes() / 2);
+        assert!(split.max_concurrency() > 2.0 * single.max_concurrency());
+
+        // 32 attention heads can't be split across 3 GPUs
+        setup.tp_size = 3;
+        assert!(estimate_serving(&llama_config, Precision::BF16, 24_000_000_000, &setup).is_none());
+    }
+}
@@ -0,0 +1,236 @@
+//! Speculative decoding memory and speedup estimates
+use crate::estimate::{cache_precision, estimate_kv_cache, quantized_weights_bytes, OverheadModel};
+use crate::models::{ModelConfigTrait, Precision};
+
+/// Share of the draft tokens the target model accepts with a poorly aligned draft model
+pub const ACCEPTANCE_RATE_LOW: f64 = 0.6;
+/// Share of the draft tokens the target model accepts with a draft model of the same family
+pub const ACCEPTANCE_RATE_HIGH: f64 = 0.8;
+
+/// A struct representing the memory of a draft and a target model served together with
+/// speculative decoding, in bytes, and the expected speedup of the generation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpeculativeEstimate {
+    /// The memory of the target model weights
+    target_weights: i64,
+    /// The memory of the draft model weights
+    draft_weights: i64,
+    /// The memory of the target model KV cache
+    target_kv_cache: i64,
+    /// The memory of the draft model KV cache
+    draft_kv_cache: i64,
+    /// The memory of the framework (CUDA context, allocator fragmentation)
+    overhead: i64,
+    /// The speedup over the target model alone with a low acceptance rate
+    speedup_low: f64,
+    /// The speedup over the target model alone with a high acceptance rate
+    speedup_high: f64,
+}
+
+/// SpeculativeEstimate implementation
+impl SpeculativeEstimate {
+    /// Build a new `SpeculativeEstimate` struct based on the provided parameters
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        target_weights: i64,
+        draft_weights: i64,
+        target_kv_cache: i64,
+        draft_kv_cache: i64,
+        overhead: i64,
+        speedup_low: f64,
+        speedup_high: f64,
+    ) -> SpeculativeEstimate {
+        SpeculativeEstimate {
+            target_weights,
+            draft_weights,
+            target_kv_cache,
+            draft_kv_cache,
+            overhead,
+            speedup_low,
+            speedup_high,
+        }
+    }
+    /// Returns the memory of the target model weights
+    pub fn target_weights(&self) -> i64 {
+        self.target_weights
+    }
+    /// Returns the memory of the draft model weights
+    pub fn draft_weights(&self) -> i64 {
+        self.draft_weights
+    }
+    /// Returns the memory of the target model KV cache
+    pub fn target_kv_cache(&self) -> i64 {
+        self.target_kv_cache
+    }
+    /// Returns the memory of the draft model KV cache
+    pub fn draft_kv_cache(&self) -> i64 {
+        self.draft_kv_cache
+    }
+    /// Returns the memory of the framework
+    pub fn overhead(&self) -> i64 {
+        self.overhead
+    }
+    /// Returns the expected speedup range over the target model alone, from a low to a high
+    /// acceptance rate of the draft tokens
+    pub fn speedup(&self) -> (f64, f64) {
+        (self.speedup_low, self.speedup_high)
+    }
+    /// Returns the total memory needed to serve both models
+    pub fn total(&self) -> i64 {
+        self.target_weights
+            + self.draft_weights
+            + self.target_kv_cache
+            + self.draft_kv_cache
+            + self.overhead
+    }
+}
+
+/// Returns the expected speedup of speculative decoding over the target model alone, when the
+/// draft model proposes `num_speculative_tokens` tokens per step, the target model accepts each of
+/// them with the probability `acceptance_rate`, and a draft forward pass costs `cost_ratio` times
+/// a target one (Leviathan et al., 2023).
+pub fn speculative_speedup(
+    num_speculative_tokens: i32,
+    acceptance_rate: f64,
+    cost_ratio: f64,
+) -> f64 {
+    let num_speculative_tokens = num_speculative_tokens.max(0);
+    let acceptance_rate = acceptance_rate.clamp(0.0, 1.0);
+    // The target model always produces one token, and the accepted draft tokens before it
+    let tokens_per_step = match acceptance_rate < 1.0 {
+        true => (1.0 - acceptance_rate.powi(num_speculative_tokens + 1)) / (1.0 - acceptance_rate),
+        false => (num_speculative_tokens + 1) as f64,
+    };
+    tokens_per_step / (num_speculative_tokens as f64 * cost_ratio + 1.0)
+}
+
+/// Estimate the memory needed to serve a target model with a draft model proposing
+/// `num_speculative_tokens` tokens per step, both in the given precision, for `batch_size`
+/// sequences of `seq_len` tokens, and the expected speedup of the generation.
+///
+/// Both models keep a KV cache of the sequences and of the speculated tokens. The decoding being
+/// bandwidth-bound, a draft forward pass costs the ratio of the parameters both models read per
+/// token, the speedup range goes from `ACCEPTANCE_RATE_LOW` to `ACCEPTANCE_RATE_HIGH`.
+pub fn estimate_speculative(
+    target: &dyn ModelConfigTrait,
+    draft: &dyn ModelConfigTrait,
+    precision: Precision,
+    batch_size: i32,
+    seq_len: i32,
+    num_speculative_tokens: i32,
+) -> SpeculativeEstimate {
+    estimate_speculative_with_overhead(
+        target,
+        draft,
+        precision,
+        batch_size,
+        seq_len,
+        num_speculative_tokens,
+        &OverheadModel::new(),
+    )
+}
+
+/// Estimate the memory needed to serve a target model with a draft model, and the expected
+/// speedup of the generation, with the given overhead model (see `estimate_speculative`)
+pub fn estimate_speculative_with_overhead(
+    target: &dyn ModelConfigTrait,
+    draft: &dyn ModelConfigTrait,
+    precision: Precision,
+    batch_size: i32,
+    seq_len: i32,
+    num_speculative_tokens: i32,
+    overhead: &OverheadModel,
+) -> SpeculativeEstimate {
+    let target_weights = quantized_weights_bytes(target, precision);
+    let draft_weights = quantized_weights_bytes(draft, precision);
+    let cached_tokens = seq_len + num_speculative_tokens.max(0);
+    let target_kv_cache = estimate_kv_cache(
+        target,
+        batch_size,
+        cached_tokens,
+        cache_precision(precision),
+    );
+    let draft_kv_cache =
+        estimate_kv_cache(draft, batch_size, cached_tokens, cache_precision(precision));
+    let overhead = overhead.overhead_bytes(
+        target_weights + draft_weights,
+        target_kv_cache + draft_kv_cache,
+    );
+
+    let cost_ratio = match target.active_parameters_per_token() > 0 {
+        true => {
+            draft.active_parameters_per_token() as f64 / target.active_parameters_per_token() as f64
+        }
+        false => 0.0,
+    };
+    SpeculativeEstimate::new(
+        target_weights,
+        draft_weights,
+        target_kv_cache,
+        draft_kv_cache,
+        overhead,
+        speculative_speedup(num_speculative_tokens, ACCEPTANCE_RATE_LOW, cost_ratio),
+        speculative_speedup(num_speculative_tokens, ACCEPTANCE_RATE_HIGH, cost_ratio),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate::test_utils::create_llama_config_with;
+    use crate::models::LlamaParams;
+
+    #[test]
+    fn test_speculative_speedup() {
+        // Without draft tokens, every step produces a single token
+        assert_eq!(speculative_speedup(0, 0.8, 0.1), 1.0);
+        // A free draft model whose tokens are always accepted
+        assert_eq!(speculative_speedup(4, 1.0, 0.0), 5.0);
+        // 4 tokens accepted with a probability of 0.5: 1 + 0.5 + 0.25 + 0.125 + 0.0625 tokens
+        assert!((speculative_speedup(4, 0.5, 0.0) - 1.9375).abs() < 1e-9);
+        assert!((speculative_speedup(4, 0.5, 0.25) - 1.9375 / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_estimate_speculative_memory() {
+        // Llama 3 70B with Llama 3 8B as draft
+        let target =
+            create_llama_config_with(LlamaParams::new(8192, 28672, 8192, 80, 64, Some(8), None));
+        let draft =
+            create_llama_config_with(LlamaParams::new(4096, 14336, 8192, 32, 32, Some(8), None));
+        let estimate = estimate_speculative(&target, &draft, Precision::BF16, 1, 4096, 5);
+        assert_eq!(estimate.target_weights(), 2 * target.num_parameters());
+        assert_eq!(estimate.draft_weights(), 2 * draft.num_parameters());
+        assert_eq!(
+            estimate.target_kv_cache(),
+            estimate_kv_cache(&target, 1, 4101, Precision::BF16)
+        );
+        assert_eq!(
+            estimate.draft_kv_cache(),
+            estimate_kv_cache(&draft, 1, 4101, Precision::BF16)
+        );
+        let weights = estimate.target_weights() + estimate.draft_weights();
+        let kv_cache = estimate.target_kv_cache() + estimate.draft_kv_cache();
+        assert_eq!(
+            estimate.total(),
+            weights + kv_cache + OverheadModel::new().overhead_bytes(weights, kv_cache)
+        );
+    }
+
+    #[test]
+    fn test_estimate_speculative_speedup_range() {
+        let target =
+            create_llama_config_with(LlamaParams::new(8192, 28672, 8192, 80, 64, Some(8), None));
+        let draft =
+            create_llama_config_with(LlamaParams::new(4096, 14336, 8192, 32, 32, Some(8), None));
+        let (low, high) =
+            estimate_speculative(&target, &draft, Precision::BF16, 1, 4096, 5).speedup();
+        assert!(low < high);
+        assert!(low > 1.0 && high < 6.0);
+
+        // A draft as large as the target only slows the generation down
+        let (_, high) =
+            estimate_speculative(&target, &target, Precision::BF16, 1, 4096, 5).speedup();
+        assert!(high < 1.0);
+    }
+}
@@ -0,0 +1,208 @@
+//! Tensor parallel inference memory estimates
+use crate::estimate::activations::activation_bytes;
+use crate::estimate::quantization::weights_bytes_by_component;
+use crate::estimate::{cache_precision, estimate_kv_cache, OverheadModel};
+use crate::models::{ModelConfigTrait, Precision};
+
+/// Memory NCCL allocates on every GPU for its communication channels (bytes)
+pub const NCCL_BUFFERS_BYTES: i64 = 128 * 1024 * 1024;
+
+/// A struct representing the memory every GPU needs to serve a model split with tensor
+/// parallelism, in bytes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TensorParallelMemory {
+    /// The number of GPUs the model is split across
+    tp_size: i32,
+    /// The memory of the weights shard, the replicated weights included
+    weights: i64,
+    /// The memory of the KV cache shard
+    kv_cache: i64,
+    /// The memory of the all-reduce buffers
+    communication: i64,
+    /// The memory of the framework (CUDA context, allocator fragmentation)
+    overhead: i64,
+}
+
+/// TensorParallelMemory implementation
+impl TensorParallelMemory {
+    /// Build a new `TensorParallelMemory` struct based on the provided parameters
+    pub fn new(
+        tp_size: i32,
+        weights: i64,
+        kv_cache: i64,
+        communication: i64,
+        overhead: i64,
+    ) -> TensorParallelMemory {
+        TensorParallelMemory {
+            tp_size,
+            weights,
+            kv_cache,
+            communication,
+            overhead,
+        }
+    }
+    /// Returns the number of GPUs the model is split across
+    pub fn tp_size(&self) -> i32 {
+        self.tp_size
+    }
+    /// Returns the memory of the weights shard of every GPU
+    pub fn weights(&self) -> i64 {
+        self.weights
+    }
+    /// Returns the memory of the KV cache shard of every GPU
+    pub fn kv_cache(&self) -> i64 {
+        self.kv_cache
+    }
+    /// Returns the memory of the all-reduce buffers of every GPU
+    pub fn communication(&self) -> i64 {
+        self.communication
+    }
+    /// Returns the memory of the framework on every GPU
+    pub fn overhead(&self) -> i64 {
+        self.overhead
+    }
+    /// Returns the total memory every GPU needs
+    pub fn total(&self) -> i64 {
+        self.weights + self.kv_cache + self.communication + self.overhead
+    }
+}
+
+/// Returns the tensor parallel sizes a model can be split with, the divisors of its number of
+/// attention heads up to `max_size`
+pub fn tensor_parallel_sizes(config: &dyn ModelConfigTrait, max_size: i32) -> Vec<i32> {
+    let num_attention_heads = config.num_attention_heads();
+    (1..=max_size)
+        .filter(|tp_size| num_attention_heads % tp_size == 0)
+        .collect()
+}
+
+/// Estimate the memory every GPU needs to serve a model split across `tp_size` GPUs with tensor
+/// parallelism, with a KV cache of `batch_size` sequences of `seq_len` tokens. Returns `None` when
+/// the attention heads can't be split evenly.
+///
+/// The linear layers and the LM head are sharded, the embeddings and the norms are replicated on
+/// every GPU. The key and value heads are sharded too, but replicated when there are fewer of them
+/// than GPUs.
+pub fn estimate_tensor_parallel(
+    config: &dyn ModelConfigTrait,
+    precision: Precision,
+    batch_size: i32,
+    seq_len: i32,
+    tp_size: i32,
+) -> Option<TensorParallelMemory> {
+    estimate_tensor_parallel_with_overhead(
+        config,
+        precision,
+        batch_size,
+        seq_len,
+        tp_size,
+        &OverheadModel::new(),
+    )
+}
+
+/// Estimate the memory every GPU needs to serve a model split across `tp_size` GPUs with tensor
+/// parallelism, with the given overhead model (see `estimate_tensor_parallel`)
+pub fn estimate_tensor_parallel_with_overhead(
+    config: &dyn ModelConfigTrait,
+    precision: Precision,
+    batch_size: i32,
+    seq_len: i32,
+    tp_size: i32,
+    overhead: &OverheadModel,
+) -> Option<TensorParallelMemory> {
+    if tp_size < 1 || config.num_attention_heads() % tp_size != 0 {
+        return None;
+    }
+    let shards = tp_size as f64;
+
+    let weights = weights_bytes_by_component(config, precision);
+    let weights = ((weights.linear + weights.lm_head) / shards + weights.embeddings + weights.other)
+        .ceil() as i64;
+
+    let num_key_value_heads = config.num_key_value_heads().max(1);
+    let kv_heads_per_gpu = (num_key_value_heads + tp_size - 1) / tp_size;
+    let kv_cache = estimate_kv_cache(config, batch_size, seq_len, cache_precision(precision));
+    let kv_cache =
+        (kv_cache as f64 * kv_heads_per_gpu as f64 / num_key_value_heads as f64).ceil() as i64;
+
+    // The hidden states of the whole batch are all-reduced after the attention and the MLP
+    let communication = match tp_size > 1 {
+        true => {
+            let hidden_states = batch_size as i64
+                * seq_len as i64
+                * config.hidden_size() as i64
+                * activation_bytes(precision);
+            NCCL_BUFFERS_BYTES + 2 * hidden_states
+        }
+        false => 0,
+    };
+
+    let overhead = overhead.overhead_bytes(weights, kv_cache);
+    Some(TensorParallelMemory::new(
+        tp_size,
+        weights,
+        kv_cache,
+        communication,
+        overhead,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate::test_utils::create_llama_config;
+    use crate::estimate::{estimate_inference_memory, FRAGMENTATION_RATIO};
+
+    #[test]
+    fn test_tensor_parallel_sizes() {
+        let llama_config = create_llama_config();
+        assert_eq!(tensor_parallel_sizes(&llama_config, 8), vec![1, 2, 4, 8]);
+        assert_eq!(tensor_parallel_sizes(&llama_config, 3), vec![1, 2]);
+        assert!(estimate_tensor_parallel(&llama_config, Precision::BF16, 1, 2048, 3).is_none());
+        assert!(estimate_tensor_parallel(&llama_config, Precision::BF16, 1, 2048, 0).is_none());
+    }
+
+    #[test]
+    fn test_estimate_tensor_parallel() {
+        let llama_config = create_llama_config();
+        let single = estimate_tensor_parallel(&llama_config, Precision::BF16, 1, 2048, 1).unwrap();
+        let inference = estimate_inference_memory(&llama_config, Precision::BF16);
+        assert_eq!(single.tp_size(), 1);
+        assert_eq!(single.weights(), inference.weights());
+        // The KV cache adds its fragmentation to the overhead of the weights
+        assert_eq!(
+            single.overhead(),
+            inference.overhead() + (single.kv_cache() as f64 * FRAGMENTATION_RATIO).round() as i64
+        );
+        assert_eq!(single.communication(), 0);
+        assert_eq!(
+            single.kv_cache(),
+            estimate_kv_cache(&llama_config, 1, 2048, Precision::BF16)
+        );
+
+        // The replicated embeddings keep every shard above half of the weights
+        let breakdown = llama_config.parameter_breakdown();
+        let sharded = estimate_tensor_parallel(&llama_config, Precision::BF16, 1, 2048, 2).unwrap();
+        assert_eq!(
+            sharded.weights(),
+            single.weights() / 2 + breakdown.embeddings()
+        );
+        assert_eq!(sharded.kv_cache(), single.kv_cache() / 2);
+        assert_eq!(
+            sharded.communication(),
+            NCCL_BUFFERS_BYTES + 2 * 2048 * 4096 * 2
+        );
+        assert!(sharded.total() < single.total());
+        assert!(sharded.total() > single.total() / 2);
+    }
+
+    #[test]
+    fn test_estimate_tensor_parallel_replicated_kv_heads() {
+        // 8 key and value heads on 16 GPUs, every head is cached by 2 GPUs
+        let llama_config = create_llama_config();
+        let single = estimate_tensor_parallel(&llama_config, Precision::FP16, 4, 8192, 1).unwrap();
+        let sharded =
+            estimate_tensor_parallel(&llama_config, Precision::FP16, 4, 8192, 16).unwrap();
+        assert_eq!(sharded.kv_cache(), single.kv_cache() / 8);
+    }
+}
@@ -0,0 +1,130 @@
+//! Roofline throughput estimates
+use serde::Serialize;
+
+use crate::estimate::{
+    cache_precision, estimate_kv_cache, flops_per_token, quantized_weights_bytes, Phase,
+};
+use crate::models::{ModelConfigTrait, Precision};
+use crate::specs::GpuSpec;
+
+/// Share of the peak tensor throughput the matrix multiplications reach in practice
+pub const COMPUTE_EFFICIENCY: f64 = 0.5;
+/// Share of the peak memory bandwidth the kernels reach in practice
+pub const BANDWIDTH_EFFICIENCY: f64 = 0.8;
+
+/// A struct representing the expected generation speed of a model on a GPU
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct Throughput {
+    /// The number of prompt tokens processed every second
+    prefill_tokens_per_second: f64,
+    /// The number of tokens generated every second, over all the sequences
+    decode_tokens_per_second: f64,
+}
+
+/// Throughput implementation
+impl Throughput {
+    /// Build a new `Throughput` struct based on the provided parameters
+    pub fn new(prefill_tokens_per_second: f64, decode_tokens_per_second: f64) -> Throughput {
+        Throughput {
+            prefill_tokens_per_second,
+            decode_tokens_per_second,
+        }
+    }
+    /// Returns the number of prompt tokens processed every second
+    pub fn prefill_tokens_per_second(&self) -> f64 {
+        self.prefill_tokens_per_second
+    }
+    /// Returns the number of tokens generated every second, over all the sequences
+    pub fn decode_tokens_per_second(&self) -> f64 {
+        self.decode_tokens_per_second
+    }
+}
+
+/// Estimate the generation speed of a model on a GPU, for `batch_size` sequences of `seq_len`
+/// tokens, with a roofline model: every step takes the longest of its compute time (see
+/// `flops_per_token`) and of the time to read the weights and the KV cache.
+///
+/// The prefill of the prompts is compute-bound, the decoding of the next tokens is
+/// bandwidth-bound until the batch is large enough.
+pub fn estimate_throughput(
+    config: &dyn ModelConfigTrait,
+    device: &GpuSpec,
+    precision: Precision,
+    batch_size: i32,
+    seq_len: i32,
+) -> Throughput {
+    let peak_flops = device.fp16_tflops() * 1e12 * COMPUTE_EFFICIENCY;
+    let bandwidth = device.memory_bandwidth() * BANDWIDTH_EFFICIENCY;
+
+    // Only the weights of the active experts of the MoE models are read for a single token
+    let active_share = match config.total_parameters() > 0 {
+        true => config.active_parameters_per_token() as f64 / config.total_parameters() as f64,
+        false => 1.0,
+    };
+    let weights = quantized_weights_bytes(config, precision) as f64 * active_share;
+
+    let batch_size = batch_size.max(1) as f64;
+    let prefill_tokens = batch_size * seq_len.max(1) as f64;
+    let prefill_flops = flops_per_token(config, Phase::Prefill, seq_len).total();
+    let prefill_time = (prefill_tokens * prefill_flops / peak_flops).max(weights / bandwidth);
+
+    let kv_cache = estimate_kv_cache(
+        config,
+        batch_size as i32,
+        seq_len,
+        cache_precision(precision),
+    ) as f64;
+    let decode_flops = flops_per_token(config, Phase::Decode, seq_len).total();
+    let decode_time =
+        (batch_size * decode_flops / peak_flops).max((weights + kv_cache) / bandwidth);
+
+    Throughput::new(prefill_tokens / prefill_time, batch_size / decode_time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate::test_utils::create_llama_config;
+
+    #[test]
+    fn test_throughput() {
+        let throughput = Throughput::new(5000.0, 50.0);
+        assert_eq!(throughput.prefill_tokens_per_second(), 5000.0);
+        assert_eq!(throughput.decode_tokens_per_second(), 50.0);
+    }
+
+    #[test]
+    fn test_estimate_throughput() {
+        let llama_config = create_llama_config();
+        let rtx_4090 = GpuSpec::from_name("RTX 4090").unwrap();
+        let throughput = estimate_throughput(&llama_config, &rtx_4090, Precision::FP16, 1, 2048);
+
+        // Decoding reads the 16 GB of weights for every token
+        let weights = quantized_weights_bytes(&llama_config, Precision::FP16) as f64;
+        let kv_cache = estimate_kv_cache(&llama_config, 1, 2048, Precision::FP16) as f64;
+        assert_eq!(
+            throughput.decode_tokens_per_second(),
+            1.008e12 * BANDWIDTH_EFFICIENCY / (weights + kv_cache)
+        );
+        assert!(throughput.decode_tokens_per_second() > 30.0);
+        assert!(throughput.decode_tokens_per_second() < 100.0); // The prefill is compute-bound
+        let compute_bound = 165e12 * COMPUTE_EFFICIENCY
+            / flops_per_token(&llama_config, Phase::Prefill, 2048).total();
+        assert!((throughput.prefill_tokens_per_second() / compute_bound - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_estimate_throughput_scaling() {
+        let llama_config = create_llama_config();
+        let a100 = GpuSpec::from_name("NVIDIA A100-SXM4-80GB").unwrap();
+        let single = estimate_throughput(&llama_config, &a100, Precision::BF16, 1, 1024);
+        let batched = estimate_throughput(&llama_config, &a100, Precision::BF16, 32, 1024);
+        let quantized =
+            estimate_throughput(&llama_config, &a100, Precision::GPTQ { bits: 4 }, 1, 1024);
+
+        // The batch shares the reading of the weights
+        assert!(batched.decode_tokens_per_second() > 10.0 * single.decode_tokens_per_second());
+        // And so do the smaller quantized weights
+        assert!(quantized.decode_tokens_per_second() > 2.0 * single.decode_tokens_per_second());
+    }
+}
@@ -0,0 +1,535 @@
+//! End-to-end training memory and time estimates
+use std::str::FromStr;
+
+use crate::estimate::activations::activation_bytes;
+use crate::estimate::{
+    estimate_activation_memory, flops_per_token, Checkpointing, Optimizer, OverheadModel, Phase,
+    MASTER_WEIGHTS_BYTES_PER_PARAM,
+};
+use crate::models::{ModelConfigTrait, Precision};
+use crate::specs::GpuSpec;
+
+/// Size of the gradient buckets all-reduced by PyTorch DDP (bytes)
+pub const DDP_BUCKET_BYTES: i64 = 25 * 1024 * 1024;
+/// Number of elements of the DeepSpeed ZeRO reduce and all-gather buckets
+pub const ZERO_BUCKET_ELEMENTS: i64 = 500_000_000;
+/// Maximum number of parameters ZeRO-3 keeps gathered on every GPU at once
+pub const ZERO3_MAX_LIVE_PARAMETERS: i64 = 1_000_000_000;
+/// Number of parameters ZeRO-3 gathers ahead of their use
+pub const ZERO3_PREFETCH_ELEMENTS: i64 = 50_000_000;
+/// Share of the peak tensor throughput a training step reaches in practice (model FLOPs
+/// utilization), lower than the inference one because of the communication and the optimizer
+/// step
+pub const TRAINING_MFU: f64 = 0.4;
+
+/// Enumerate the ways the training states are sharded across the data parallel GPUs
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sharding {
+    /// Every GPU keeps all the training states (DDP)
+    Disabled,
+    /// DeepSpeed ZeRO stage 1, the optimizer states are partitioned
+    Zero1,
+    /// DeepSpeed ZeRO stage 2, the gradients are partitioned too
+    Zero2,
+    /// DeepSpeed ZeRO stage 3, the weights are partitioned too
+    Zero3,
+    /// PyTorch FSDP `SHARD_GRAD_OP`, the optimizer states and the gradients are sharded
+    FsdpShardGradOp,
+    /// PyTorch FSDP `FULL_SHARD`, the weights are sharded too
+    FsdpFullShard,
+}
+
+/// Sharding implementation
+impl Sharding {
+    /// Whether the optimizer states are divided across the GPUs
+    pub fn shards_optimizer(&self) -> bool {
+        !matches!(self, Sharding::Disabled)
+    }
+    /// Whether the gradients are divided across the GPUs
+    pub fn shards_gradients(&self) -> bool {
+        !matches!(self, Sharding::Disabled | Sharding::Zero1)
+    }
+    /// Whether the weights are divided across the GPUs
+    pub fn shards_weights(&self) -> bool {
+        matches!(self, Sharding::Zero3 | Sharding::FsdpFullShard)
+    }
+    /// Returns the memory of the communication buffers every GPU allocates, in bytes: the
+    /// gradient buckets, and the gathered weights of the sharded ones. Nothing is allocated on a
+    /// single GPU.
+    pub fn communication_bytes(
+        &self,
+        config: &dyn ModelConfigTrait,
+        precision: Precision,
+        num_gpus: i32,
+    ) -> i64 {
+        if num_gpus <= 1 {
+            return 0;
+        }
+        let num_parameters = config.num_parameters();
+        let layer_parameters = config.parameter_breakdown().per_layer();
+        let bucket = ZERO_BUCKET_ELEMENTS.min(num_parameters);
+        let elements = match self {
+            Sharding::Disabled => return DDP_BUCKET_BYTES,
+            // The reduce and the all-gather buckets
+            Sharding::Zero1 | Sharding::Zero2 => 2 * bucket,
+            // The reduce bucket, the live and the prefetched weights
+            Sharding::Zero3 => {
+                bucket
+                    + ZERO3_MAX_LIVE_PARAMETERS.min(num_parameters)
+                    + ZERO3_PREFETCH_ELEMENTS.min(num_parameters)
+            }
+            // The reduce-scatter of the gradients of a wrapped layer
+            Sharding::FsdpShardGradOp => layer_parameters,
+            // And the all-gather of the current and of the prefetched layers
+            Sharding::FsdpFullShard => 3 * layer_parameters,
+        };
+        elements * activation_bytes(precision)
+    }
+}
+
+/// Build from the name of a sharding strategy (e.g. `ddp`, `zero2` or `fsdp-full-shard`) or from
+/// a ZeRO stage number, `0` disabling the sharding
+impl FromStr for Sharding {
+    type Err = String;
+
+    fn from_str(sharding: &str) -> Result<Self, Self::Err> {
+        match sharding
+            .trim()
+            .to_lowercase()
+            .replace(['-', ' '], "_")
+            .as_str()
+        {
+            "none" | "ddp" | "0" | "zero0" | "zero_0" => Ok(Sharding::Disabled),
+            "1" | "zero1" | "zero_1" => Ok(Sharding::Zero1),
+            "2" | "zero2" | "zero_2" => Ok(Sharding::Zero2),
+            "3" | "zero3" | "zero_3" => Ok(Sharding::Zero3),
+            "shard_grad_op" | "fsdp_shard_grad_op" => Ok(Sharding::FsdpShardGradOp),
+            "full_shard" | "fsdp" | "fsdp_full_shard" => Ok(Sharding::FsdpFullShard),
+            _ => Err(format!("Unknown sharding strategy: {}", sharding)),
+        }
+    }
+}
+
+/// A struct representing the setup of a full fine-tuning run
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrainingSetup {
+    /// The micro batch size of every GPU
+    pub batch_size: i32,
+    /// The length of the training sequences
+    pub seq_len: i32,
+    /// The precision the model is trained in, FP32 or a 16-bit mixed precision one
+    pub precision: Precision,
+    /// The optimizer updating the weights
+    pub optimizer: Optimizer,
+    /// Which activations are recomputed during the backward pass
+    pub checkpointing: Checkpointing,
+    /// How the training states are sharded across the GPUs
+    pub sharding: Sharding,
+    /// The number of data parallel GPUs the shards are spread over
+    pub num_gpus: i32,
+    /// The memory the framework takes on every GPU
+    pub overhead: OverheadModel,
+}
+
+/// TrainingSetup implementation
+impl TrainingSetup {
+    /// Build a new `TrainingSetup` on a single GPU, without activation checkpointing nor sharding
+    pub fn new(
+        batch_size: i32,
+        seq_len: i32,
+        precision: Precision,
+        optimizer: Optimizer,
+    ) -> TrainingSetup {
+        TrainingSetup {
+            batch_size,
+            seq_len,
+            precision,
+            optimizer,
+            checkpointing: Checkpointing::Disabled,
+            sharding: Sharding::Disabled,
+            num_gpus: 1,
+            overhead: OverheadModel::new(),
+        }
+    }
+}
+
+/// A struct representing the memory every GPU needs for a training step, in bytes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrainingEstimate {
+    /// The memory of the model weights
+    weights: i64,
+    /// The memory of the gradients
+    gradients: i64,
+    /// The memory of the optimizer states, the master weights included
+    optimizer: i64,
+    /// The memory of the activations kept for the backward pass
+    activations: i64,
+    /// The memory of the communication buffers
+    communication: i64,
+    /// The memory of the framework (CUDA context)
+    overhead: i64,
+    /// The activations memory the checkpointing saves
+    checkpointing_savings: i64,
+    /// The share of the step time the checkpointing adds (e.g. `0.33` for 33% slower steps)
+    step_time_penalty: f64,
+}
+
+/// TrainingEstimate implementation
+impl TrainingEstimate {
+    /// Build a new `TrainingEstimate` struct based on the provided parameters
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        weights: i64,
+        gradients: i64,
+        optimizer: i64,
+        activations: i64,
+        communication: i64,
+        overhead: i64,
+        checkpointing_savings: i64,
+        step_time_penalty: f64,
+    ) -> TrainingEstimate {
+        TrainingEstimate {
+            weights,
+            gradients,
+            optimizer,
+            activations,
+            communication,
+            overhead,
+            checkpointing_savings,
+            step_time_penalty,
+        }
+    }
+    /// Returns the memory of the model weights
+    pub fn weights(&self) -> i64 {
+        self.weights
+    }
+    /// Returns the memory of the gradients
+    pub fn gradients(&self) -> i64 {
+        self.gradients
+    }
+    /// Returns the memory of the optimizer states
+    pub fn optimizer(&self) -> i64 {
+        self.optimizer
+    }
+    /// Returns the memory of the activations
+    pub fn activations(&self) -> i64 {
+        self.activations
+    }
+    /// Returns the memory of the communication buffers
+    pub fn communication(&self) -> i64 {
+        self.communication
+    }
+    /// Returns the memory of the framework
+    pub fn overhead(&self) -> i64 {
+        self.overhead
+    }
+    /// Returns the activations memory the checkpointing saves, 0 without checkpointing
+    pub fn checkpointing_savings(&self) -> i64 {
+        self.checkpointing_savings
+    }
+    /// Returns the share of the step time the checkpointing adds, 0 without checkpointing
+    pub fn step_time_penalty(&self) -> f64 {
+        self.step_time_penalty
+    }
+    /// Returns the total memory every GPU needs
+    pub fn total(&self) -> i64 {
+        self.weights
+            + self.gradients
+            + self.optimizer
+            + self.activations
+            + self.communication
+            + self.overhead
+    }
+}
+
+/// Estimate the memory every GPU needs for a full fine-tuning step, combining the weights, the
+/// gradients, the optimizer states and the activations.
+///
+/// The mixed precision training keeps FP32 master weights in the optimizer states, the FP32
+/// training updates the weights themselves. The sharded states are divided by `num_gpus`.
+pub fn estimate_training(config: &dyn ModelConfigTrait, setup: TrainingSetup) -> TrainingEstimate {
+    let num_parameters = config.num_parameters() as f64;
+    let num_gpus = setup.num_gpus.max(1) as f64;
+    let partition = |bytes: f64, sharded: bool| match sharded {
+        true => (bytes / num_gpus).ceil() as i64,
+        false => bytes.ceil() as i64,
+    };
+
+    let weights = num_parameters * setup.precision.bytes_per_param();
+    let gradients = num_parameters * activation_bytes(setup.precision) as f64;
+    let master_weights = match setup.precision {
+        Precision::FP32 => 0.0,
+        _ => MASTER_WEIGHTS_BYTES_PER_PARAM,
+    };
+    let optimizer = num_parameters
+        * (master_weights + setup.optimizer.state_bytes_per_param(config.hidden_size()));
+    let activations = |checkpointing| {
+        estimate_activation_memory(
+            config,
+            setup.batch_size,
+            setup.seq_len,
+            setup.precision,
+            checkpointing,
+        )
+    };
+    let checkpointed_activations = activations(setup.checkpointing);
+    let weights = partition(weights, setup.sharding.shards_weights());
+
+    TrainingEstimate::new(
+        weights,
+        partition(gradients, setup.sharding.shards_gradients()),
+        partition(optimizer, setup.sharding.shards_optimizer()),
+        checkpointed_activations,
+        setup
+            .sharding
+            .communication_bytes(config, setup.precision, setup.num_gpus),
+        setup
+            .overhead
+            .overhead_bytes(weights, checkpointed_activations),
+        activations(Checkpointing::Disabled) - checkpointed_activations,
+        setup.checkpointing.step_time_penalty(config),
+    )
+}
+
+/// Estimate the time to train a model on `tokens` tokens on `num_gpus` GPUs, in seconds.
+///
+/// Every token costs the FLOPs of a training step (see `flops_per_token`) with sequences of
+/// `seq_len` tokens, at `TRAINING_MFU` of the peak throughput of the GPUs, which are assumed to
+/// scale linearly. The adapters of a LoRA run (`adapters_only`) skip the gradients of the frozen
+/// weights, half of the backward pass. The activations recomputed by the checkpointing slow the
+/// steps down by `step_time_penalty`.
+pub fn estimate_training_time(
+    config: &dyn ModelConfigTrait,
+    device: &GpuSpec,
+    num_gpus: i32,
+    tokens: i64,
+    seq_len: i32,
+    adapters_only: bool,
+    step_time_penalty: f64,
+) -> f64 {
+    let step_flops = flops_per_token(config, Phase::TrainingStep, seq_len).total();
+    let flops = match adapters_only {
+        true => step_flops * 2.0 / 3.0,
+        false => step_flops,
+    };
+    let peak_flops = device.fp16_tflops() * 1e12 * TRAINING_MFU * num_gpus.max(1) as f64;
+    tokens.max(0) as f64 * flops * (1.0 + step_time_penalty) / peak_flops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate::estimate_optimizer_memory;
+    use crate::models::{Dtype, LlamaModelConfig, LlamaParams, ModelLibraries, RopeParams};
+
+    fn create_llama_config() -> LlamaModelConfig {
+        LlamaModelConfig::new(
+            LlamaParams::new(4096, 11008, 4096, 32, 32, None, None),
+            32000,
+            false,
+            RopeParams::new(None, None),
+            Dtype::F16,
+            "llama".to_string(),
+            vec![ModelLibraries::PyTorch],
+        )
+    }
+
+    #[test]
+    fn test_training_estimate() {
+        let estimate = TrainingEstimate::new(2, 2, 12, 4, 3, 1, 8, 0.33);
+        assert_eq!(estimate.weights(), 2);
+        assert_eq!(estimate.gradients(), 2);
+        assert_eq!(estimate.optimizer(), 12);
+        assert_eq!(estimate.activations(), 4);
+        assert_eq!(estimate.communication(), 3);
+        assert_eq!(estimate.overhead(), 1);
+        assert_eq!(estimate.checkpointing_savings(), 8);
+        assert_eq!(estimate.step_time_penalty(), 0.33);
+        assert_eq!(estimate.total(), 24);
+    }
+
+    #[test]
+    fn test_estimate_training() {
+        let llama_config = create_llama_config();
+        let num_parameters = llama_config.num_parameters();
+        let setup = TrainingSetup::new(1, 2048, Precision::BF16, Optimizer::AdamW);
+
+        // The usual 16 bytes per parameter of the mixed precision AdamW
+        let estimate = estimate_training(&llama_config, setup);
+        assert_eq!(estimate.weights(), 2 * num_parameters);
+        assert_eq!(estimate.gradients(), 2 * num_parameters);
+        assert_eq!(
+            estimate.optimizer(),
+            estimate_optimizer_memory(&llama_config, Optimizer::AdamW)
+        );
+        assert_eq!(
+            estimate.activations(),
+            estimate_activation_memory(
+                &llama_config,
+                1,
+                2048,
+                Precision::BF16,
+                Checkpointing::Disabled
+            )
+        );
+        assert_eq!(estimate.communication(), 0);
+        assert_eq!(
+            estimate.overhead(),
+            OverheadModel::new().overhead_bytes(estimate.weights(), estimate.activations())
+        );
+        assert_eq!(estimate.checkpointing_savings(), 0);
+        assert_eq!(estimate.step_time_penalty(), 0.0);
+
+        // No master weights when training in FP32
+        let fp32_estimate = estimate_training(
+            &llama_config,
+            TrainingSetup::new(1, 2048, Precision::FP32, Optimizer::AdamW),
+        );
+        assert_eq!(fp32_estimate.weights(), 4 * num_parameters);
+        assert_eq!(fp32_estimate.gradients(), 4 * num_parameters);
+        assert_eq!(fp32_estimate.optimizer(), 8 * num_parameters);
+
+        let checkpointed = estimate_training(
+            &llama_config,
+            TrainingSetup {
+                checkpointing: Checkpointing::Full,
+                ..setup
+            },
+        );
+        assert!(checkpointed.activations() < estimate.activations() / 4);
+        assert_eq!(
+            checkpointed.activations() + checkpointed.checkpointing_savings(),
+            estimate.activations()
+        );
+        assert!(checkpointed.step_time_penalty() > 0.3);
+
+        let selective = estimate_training(
+            &llama_config,
+            TrainingSetup {
+                checkpointing: Checkpointing::Selective,
+                ..setup
+            },
+        );
+        assert!(selective.activations() > checkpointed.activations());
+        assert!(selective.activations() < estimate.activations());
+        assert!(selective.step_time_penalty() < checkpointed.step_time_penalty());
+
+        // A runtime without any overhead
+        let exact = estimate_training(
+            &llama_config,
+            TrainingSetup {
+                overhead: OverheadModel {
+                    cuda_context: 0,
+                    allocator_headroom: 0.0,
+                    fragmentation: 0.0,
+                    ..OverheadModel::new()
+                },
+                ..setup
+            },
+        );
+        assert_eq!(exact.overhead(), 0);
+        assert_eq!(exact.total(), estimate.total() - estimate.overhead());
+    }
+
+    #[test]
+    fn test_estimate_training_sharding() {
+        let llama_config = create_llama_config();
+        let setup = TrainingSetup {
+            num_gpus: 8,
+            ..TrainingSetup::new(1, 2048, Precision::BF16, Optimizer::AdamW)
+        };
+        let estimates: Vec<TrainingEstimate> = [
+            Sharding::Disabled,
+            Sharding::Zero1,
+            Sharding::Zero2,
+            Sharding::Zero3,
+        ]
+        .into_iter()
+        .map(|sharding| estimate_training(&llama_config, TrainingSetup { sharding, ..setup }))
+        .collect();
+        assert_eq!(estimates[1].optimizer(), estimates[0].optimizer() / 8);
+        assert_eq!(estimates[1].gradients(), estimates[0].gradients());
+        assert_eq!(estimates[2].gradients(), estimates[0].gradients() / 8);
+        assert_eq!(estimates[2].weights(), estimates[0].weights());
+        assert_eq!(estimates[3].weights(), estimates[0].weights() / 8);
+        assert_eq!(estimates[3].activations(), estimates[0].activations());
+        for stages in estimates.windows(2) {
+            assert!(stages[1].total() < stages[0].total());
+        }
+
+        // The ZeRO buckets are much larger than the DDP ones
+        assert_eq!(estimates[0].communication(), DDP_BUCKET_BYTES);
+        assert_eq!(estimates[1].communication(), 2 * ZERO_BUCKET_ELEMENTS * 2);
+        assert_eq!(
+            estimates[3].communication(),
+            (ZERO_BUCKET_ELEMENTS + ZERO3_MAX_LIVE_PARAMETERS + ZERO3_PREFETCH_ELEMENTS) * 2
+        );
+    }
+
+    #[test]
+    fn test_estimate_training_fsdp() {
+        let llama_config = create_llama_config();
+        let setup = TrainingSetup {
+            num_gpus: 4,
+            ..TrainingSetup::new(1, 2048, Precision::BF16, Optimizer::AdamW)
+        };
+        let estimate =
+            |sharding| estimate_training(&llama_config, TrainingSetup { sharding, ..setup });
+        let layer_parameters = llama_config.parameter_breakdown().per_layer();
+
+        // FSDP only gathers a layer at a time, its buffers are smaller than the ZeRO ones
+        let full_shard = estimate(Sharding::FsdpFullShard);
+        let zero3 = estimate(Sharding::Zero3);
+        assert_eq!(full_shard.weights(), zero3.weights());
+        assert_eq!(full_shard.optimizer(), zero3.optimizer());
+        assert_eq!(full_shard.communication(), 3 * layer_parameters * 2);
+        assert!(full_shard.total() < zero3.total());
+
+        let shard_grad_op = estimate(Sharding::FsdpShardGradOp);
+        assert_eq!(shard_grad_op.weights(), estimate(Sharding::Zero2).weights());
+        assert_eq!(shard_grad_op.communication(), layer_parameters * 2);
+
+        // Nothing is communicated on a single GPU
+        let single = estimate_training(
+            &llama_config,
+            TrainingSetup {
+                sharding: Sharding::FsdpFullShard,
+                ..TrainingSetup::new(1, 2048, Precision::BF16, Optimizer::AdamW)
+            },
+        );
+        assert_eq!(single.communication(), 0);
+    }
+
+    #[test]
+    fn test_sharding_from_str() {
+        assert_eq!(Sharding::from_str("ddp"), Ok(Sharding::Disabled));
+        assert_eq!(Sharding::from_str("0"), Ok(Sharding::Disabled));
+        assert_eq!(Sharding::from_str("ZeRO-2"), Ok(Sharding::Zero2));
+        assert_eq!(Sharding::from_str("3"), Ok(Sharding::Zero3));
+        assert_eq!(
+            Sharding::from_str("fsdp-full-shard"),
+            Ok(Sharding::FsdpFullShard)
+        );
+        assert!(Sharding::from_str("4").is_err());
+    }
+
+    #[test]
+    fn test_estimate_training_time() {
+        let llama_config = create_llama_config();
+        let a100 = GpuSpec::from_name("NVIDIA A100-SXM4-80GB").unwrap();
+        let step_flops = flops_per_token(&llama_config, Phase::TrainingStep, 2048).total();
+
+        let time = estimate_training_time(&llama_config, &a100, 1, 1_000_000, 2048, false, 0.0);
+        let expected = 1e6 * step_flops / (a100.fp16_tflops() * 1e12 * TRAINING_MFU);
+        assert!((time - expected).abs() < 1e-6);
+
+        // Twice the GPUs halve the time, the adapters only run two thirds of the FLOPs
+        let doubled = estimate_training_time(&llama_config, &a100, 2, 1_000_000, 2048, false, 0.0);
+        assert!((doubled - time / 2.0).abs() < 1e-6);
+        let lora = estimate_training_time(&llama_config, &a100, 1, 1_000_000, 2048, true, 0.0);
+        assert!((lora - time * 2.0 / 3.0).abs() < 1e-6);
+        let checkpointed =
+            estimate_training_time(&llama_config, &a100, 1, 1_000_000, 2048, false, 0.33);
+        assert!((checkpointed - time * 1.33).abs() < 1e-6);
+    }
+}
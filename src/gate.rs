@@ -0,0 +1,431 @@
+//! Module for the hardware assertions gating a deployment pipeline, e.g. `vram>=24GB` or
+//! `fits:int4`, with the exit code of the pipeline step
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+
+use crate::advisor::{check_fit, FitVerdict, Scenario};
+use crate::hardware::{Hardware, NO_COMPUTE_CAPABILITY};
+use crate::hub::ModelConfig;
+use crate::models::Precision;
+
+/// Exit code when every assertion holds
+pub const EXIT_PASSED: i32 = 0;
+/// Exit code when an assertion doesn't hold
+pub const EXIT_FAILED: i32 = 1;
+/// Exit code when an assertion can't be parsed or checked, e.g. `fits` without a model
+pub const EXIT_INVALID: i32 = 2;
+
+/// Enumerate the comparisons of a quantity with the expected value
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Comparator {
+    /// `>=`
+    Ge,
+    /// `>`
+    Gt,
+    /// `<=`
+    Le,
+    /// `<`
+    Lt,
+    /// `==` or `=`
+    Eq,
+}
+
+/// Comparator implementation
+impl Comparator {
+    /// Returns whether the actual value compares to the expected one
+    pub fn holds<T: PartialOrd>(&self, actual: T, expected: T) -> bool {
+        match self {
+            Comparator::Ge => actual >= expected,
+            Comparator::Gt => actual > expected,
+            Comparator::Le => actual <= expected,
+            Comparator::Lt => actual < expected,
+            Comparator::Eq => actual == expected,
+        }
+    }
+}
+
+/// Implement the display of the Comparator enum
+impl Display for Comparator {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let comparator = match self {
+            Comparator::Ge => ">=",
+            Comparator::Gt => ">",
+            Comparator::Le => "<=",
+            Comparator::Lt => "<",
+            Comparator::Eq => "==",
+        };
+        write!(f, "{}", comparator)
+    }
+}
+
+/// Enumerate the assertions on the hardware of the running system
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Assertion {
+    /// The memory of the GPU with the most memory, in bytes (`vram>=24GB`)
+    Vram(Comparator, i64),
+    /// The memory of all the GPUs, in bytes (`total_vram>=80GB`)
+    TotalVram(Comparator, i64),
+    /// The RAM of the host, in bytes (`ram>=64GB`)
+    Ram(Comparator, i64),
+    /// The number of GPUs (`gpus>=2`)
+    Gpus(Comparator, i64),
+    /// The lowest CUDA compute capability of the GPUs (`cc>=8.0`)
+    ComputeCapability(Comparator, (u32, u32)),
+    /// The model fits a GPU with its weights in the given precision, tight included
+    /// (`fits:int4`)
+    Fits(Precision),
+}
+
+/// Build from an assertion written `<quantity><comparator><value>` or `fits:<precision>`, the
+/// memory being given in B, KB, MB, GB or TB (powers of 1000) or in KiB, MiB, GiB or TiB
+impl FromStr for Assertion {
+    type Err = String;
+
+    fn from_str(assertion: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid assertion: {}", assertion);
+        let compact: String = assertion.split_whitespace().collect();
+        if let Some(precision) = compact.strip_prefix("fits:") {
+            return precision
+                .parse()
+                .map(Assertion::Fits)
+                .map_err(|_| invalid());
+        }
+        let (quantity, comparator, value) = [
+            (">=", Comparator::Ge),
+            ("<=", Comparator::Le),
+            ("==", Comparator::Eq),
+            (">", Comparator::Gt),
+            ("<", Comparator::Lt),
+            ("=", Comparator::Eq),
+        ]
+        .iter()
+        .find_map(|(symbol, comparator)| {
+            let (quantity, value) = compact.split_once(symbol)?;
+            Some((quantity.to_lowercase(), *comparator, value))
+        })
+        .ok_or_else(invalid)?;
+        match quantity.as_str() {
+            "vram" => parse_bytes(value).map(|bytes| Assertion::Vram(comparator, bytes)),
+            "total_vram" => parse_bytes(value).map(|bytes| Assertion::TotalVram(comparator, bytes)),
+            "ram" => parse_bytes(value).map(|bytes| Assertion::Ram(comparator, bytes)),
+            "gpus" => value
+                .parse()
+                .ok()
+                .map(|count| Assertion::Gpus(comparator, count)),
+            "cc" | "compute_capability" => {
+                let (major, minor) = value.split_once('.').unwrap_or((value, "0"));
+                match (major.parse(), minor.parse()) {
+                    (Ok(major), Ok(minor)) => {
+                        Some(Assertion::ComputeCapability(comparator, (major, minor)))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+        .ok_or_else(invalid)
+    }
+}
+
+/// Implement the display of the Assertion enum
+impl Display for Assertion {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Assertion::Vram(comparator, bytes) => {
+                write!(f, "vram{}{}GB", comparator, *bytes as f64 / 1e9)
+            }
+            Assertion::TotalVram(comparator, bytes) => {
+                write!(f, "total_vram{}{}GB", comparator, *bytes as f64 / 1e9)
+            }
+            Assertion::Ram(comparator, bytes) => {
+                write!(f, "ram{}{}GB", comparator, *bytes as f64 / 1e9)
+            }
+            Assertion::Gpus(comparator, count) => write!(f, "gpus{}{}", comparator, count),
+            Assertion::ComputeCapability(comparator, (major, minor)) => {
+                write!(f, "cc{}{}.{}", comparator, major, minor)
+            }
+            Assertion::Fits(precision) => write!(f, "fits:{}", precision),
+        }
+    }
+}
+
+/// Parse a memory size (e.g. `24GB`, `24GiB` or `512MB`) into bytes
+fn parse_bytes(value: &str) -> Option<i64> {
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let multiplier = match unit.to_uppercase().as_str() {
+        "" | "B" => 1.0,
+        "K" | "KB" => 1e3,
+        "M" | "MB" => 1e6,
+        "G" | "GB" => 1e9,
+        "T" | "TB" => 1e12,
+        "KIB" => 1024.0,
+        "MIB" => 1024.0 * 1024.0,
+        "GIB" => 1024.0 * 1024.0 * 1024.0,
+        "TIB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((number * multiplier).round() as i64)
+}
+
+/// A struct representing the outcome of an assertion on the running system
+#[derive(Clone, Debug, PartialEq)]
+pub struct AssertionResult {
+    /// The checked assertion
+    assertion: Assertion,
+    /// Whether the assertion holds, `None` when it can't be checked
+    passed: Option<bool>,
+    /// The actual value of the system
+    actual: String,
+}
+
+/// AssertionResult implementation
+impl AssertionResult {
+    /// Build a new `AssertionResult` struct based on the provided parameters
+    pub fn new(assertion: Assertion, passed: Option<bool>, actual: String) -> AssertionResult {
+        AssertionResult {
+            assertion,
+            passed,
+            actual,
+        }
+    }
+    /// Returns the checked assertion
+    pub fn assertion(&self) -> Assertion {
+        self.assertion
+    }
+    /// Returns whether the assertion holds, `None` when it can't be checked
+    pub fn passed(&self) -> Option<bool> {
+        self.passed
+    }
+    /// Returns the actual value of the system
+    pub fn actual(&self) -> &str {
+        &self.actual
+    }
+}
+
+/// Implement the display of the AssertionResult struct
+impl Display for AssertionResult {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let status = match self.passed {
+            Some(true) => "PASS",
+            Some(false) => "FAIL",
+            None => "ERROR",
+        };
+        write!(f, "{} {} ({})", status, self.assertion, self.actual)
+    }
+}
+
+/// Check assertions on the hardware of the running system, the `fits` ones against the given
+/// model and scenario, the precision of the scenario being replaced by the asserted one
+pub fn check_assertions(
+    hardware: &Hardware,
+    model: Option<(&ModelConfig, &Scenario)>,
+    assertions: &[Assertion],
+) -> Vec<AssertionResult> {
+    let gpus = hardware.gpus();
+    let gb = |bytes: i64| format!("{:.2} GB", bytes as f64 / 1e9);
+    assertions
+        .iter()
+        .map(|assertion| {
+            let (passed, actual) = match *assertion {
+                Assertion::Vram(comparator, expected) => {
+                    let vram = gpus
+                        .iter()
+                        .map(|device| device.get_memory_info() as i64)
+                        .max()
+                        .unwrap_or(0);
+                    (Some(comparator.holds(vram, expected)), gb(vram))
+                }
+                Assertion::TotalVram(comparator, expected) => {
+                    let vram = gpus
+                        .iter()
+                        .map(|device| device.get_memory_info() as i64)
+                        .sum();
+                    (Some(comparator.holds(vram, expected)), gb(vram))
+                }
+                Assertion::Ram(comparator, expected) => {
+                    let ram = hardware.memory as i64;
+                    (Some(comparator.holds(ram, expected)), gb(ram))
+                }
+                Assertion::Gpus(comparator, expected) => {
+                    let count = gpus.len() as i64;
+                    (Some(comparator.holds(count, expected)), count.to_string())
+                }
+                // Only the CUDA GPUs have a compute capability
+                Assertion::ComputeCapability(comparator, expected) => {
+                    match gpus
+                        .iter()
+                        .map(|device| device.get_compute_capability())
+                        .filter(|cc| *cc != NO_COMPUTE_CAPABILITY)
+                        .min()
+                    {
+                        Some(cc) => (
+                            Some(comparator.holds(cc, expected)),
+                            format!("{}.{}", cc.0, cc.1),
+                        ),
+                        None => (Some(false), "no CUDA GPU".to_string()),
+                    }
+                }
+                Assertion::Fits(precision) => match model {
+                    Some((config, scenario)) => {
+                        let reports =
+                            check_fit(hardware, config, scenario.with_precision(precision));
+                        match reports.iter().find(|report| {
+                            matches!(report.verdict(), FitVerdict::Fits | FitVerdict::Tight)
+                        }) {
+                            Some(report) => (
+                                Some(true),
+                                format!("{} on {}", report.verdict(), report.device()),
+                            ),
+                            None if reports.is_empty() => (Some(false), "no GPU".to_string()),
+                            None => (Some(false), reports[0].verdict().to_string()),
+                        }
+                    }
+                    None => (None, "no model to check".to_string()),
+                },
+            };
+            AssertionResult::new(*assertion, passed, actual)
+        })
+        .collect()
+}
+
+/// Returns the exit code of the pipeline step: `EXIT_INVALID` when an assertion can't be
+/// checked, `EXIT_FAILED` when one doesn't hold, `EXIT_PASSED` otherwise
+pub fn exit_code(results: &[AssertionResult]) -> i32 {
+    if results.iter().any(|result| result.passed().is_none()) {
+        EXIT_INVALID
+    } else if results.iter().any(|result| result.passed() == Some(false)) {
+        EXIT_FAILED
+    } else {
+        EXIT_PASSED
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate::OverheadModel;
+    use crate::hardware::{AmdDevice, NvidiaDevice};
+    use crate::models::{Dtype, LlamaModelConfig, LlamaParams, ModelLibraries, RopeParams};
+
+    fn create_hardware() -> Hardware {
+        let nvidia_gpus = vec![NvidiaDevice::new(
+            "Ampere".to_string(),
+            "GeForce".to_string(),
+            (8, 6),
+            25_769_803_776,
+            "GeForce RTX 3090".to_string(),
+            10496,
+            "GPU-0".to_string(),
+            31.504e9,
+            936.096e9,
+        )];
+        Hardware {
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            cpu_cores: 8,
+            cpu_threads: 16,
+            memory: 64_000_000_000,
+            gpu_count: 1,
+            nvidia_gpus,
+            amd_gpus: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_assertion_from_str() {
+        assert_eq!(
+            "vram>=24GB".parse::<Assertion>().unwrap(),
+            Assertion::Vram(Comparator::Ge, 24_000_000_000)
+        );
+        assert_eq!(
+            "ram < 16GiB".parse::<Assertion>().unwrap(),
+            Assertion::Ram(Comparator::Lt, 17_179_869_184)
+        );
+        assert_eq!(
+            "gpus=2".parse::<Assertion>().unwrap(),
+            Assertion::Gpus(Comparator::Eq, 2)
+        );
+        assert_eq!(
+            "cc>=8".parse::<Assertion>().unwrap(),
+            Assertion::ComputeCapability(Comparator::Ge, (8, 0))
+        );
+        assert_eq!(
+            "fits:int4".parse::<Assertion>().unwrap(),
+            Assertion::Fits(Precision::GPTQ { bits: 4 })
+        );
+        assert_eq!(
+            "vram>=24GB".parse::<Assertion>().unwrap().to_string(),
+            "vram>=24GB"
+        );
+        assert!("vram>=24XB".parse::<Assertion>().is_err());
+        assert!("disk>=1TB".parse::<Assertion>().is_err());
+        assert!("fits:int3".parse::<Assertion>().is_err());
+    }
+
+    #[test]
+    fn test_check_assertions_hardware() {
+        let hardware = create_hardware();
+        let assertions: Vec<Assertion> = ["vram>=24GB", "cc>=8.0", "gpus>=2"]
+            .iter()
+            .map(|assertion| assertion.parse().unwrap())
+            .collect();
+        let results = check_assertions(&hardware, None, &assertions);
+        assert_eq!(results[0].passed(), Some(true));
+        assert_eq!(results[0].actual(), "25.77 GB");
+        assert_eq!(results[1].passed(), Some(true));
+        assert_eq!(results[2].to_string(), "FAIL gpus>=2 (1)");
+        assert_eq!(exit_code(&results), EXIT_FAILED);
+        assert_eq!(exit_code(&results[..2]), EXIT_PASSED);
+
+        // The AMD GPUs count, but only the CUDA ones have a compute capability
+        let mut hardware = create_hardware();
+        hardware.nvidia_gpus.clear();
+        hardware.amd_gpus.push(AmdDevice::new(
+            "Instinct MI210".to_string(),
+            64_000_000_000,
+            31.504e9,
+        ));
+        let results = check_assertions(&hardware, None, &assertions);
+        assert_eq!(results[0].actual(), "64.00 GB");
+        assert_eq!(results[1].to_string(), "FAIL cc>=8.0 (no CUDA GPU)");
+        assert_eq!(results[2].actual(), "1");
+    }
+
+    #[test]
+    fn test_check_assertions_fits() {
+        let hardware = create_hardware();
+        // Llama 3 70B
+        let config = ModelConfig::Llama(LlamaModelConfig::new(
+            LlamaParams::new(8192, 28672, 8192, 80, 64, Some(8), None),
+            128256,
+            false,
+            RopeParams::new(Some(500000.0), None),
+            Dtype::BF16,
+            "llama".to_string(),
+            vec![ModelLibraries::PyTorch],
+        ));
+        let scenario = Scenario::Inference {
+            precision: Precision::BF16,
+            batch_size: 1,
+            seq_len: 4096,
+            overhead: OverheadModel::new(),
+            sliding_window: false,
+        };
+        let assertions = [
+            Assertion::Fits(Precision::BF16),
+            Assertion::Fits(Precision::GGUF(crate::models::QuantType::Q2_K)),
+        ];
+        let results = check_assertions(&hardware, Some((&config, &scenario)), &assertions);
+        assert_eq!(results[0].passed(), Some(false));
+        assert_eq!(results[1].passed(), Some(true));
+        assert!(results[1].actual().ends_with("on NVIDIA GeForce RTX 3090"));
+
+        // Without a model, the fit can't be checked
+        let results = check_assertions(&hardware, None, &assertions);
+        assert_eq!(exit_code(&results), EXIT_INVALID);
+    }
+}
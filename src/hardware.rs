@@ -1,10 +1,22 @@
 //! Module for analyzing the hardware of the running system.
-use num_cpus;
-use nvml_wrapper::enum_wrappers::device::Brand;
-use nvml_wrapper::enums::device::DeviceArchitecture;
-use nvml_wrapper::structs::device::CudaComputeCapability;
+//!
+//! The NVIDIA GPUs are scanned through NVML with the `nvml` feature, the AMD GPUs through the
+//! `amdgpu` driver ROCm runs on with the `rocm` feature. Neither is built for `wasm32` targets:
+//! there, only the hypothetical hardware of a profile is available (see `Hardware::from_profile`).
+#[cfg(all(feature = "rocm", not(target_arch = "wasm32")))]
+use std::path::Path;
+
+#[cfg(all(feature = "nvml", not(target_arch = "wasm32")))]
+use nvml_wrapper::enum_wrappers::device::Clock;
+#[cfg(all(feature = "nvml", not(target_arch = "wasm32")))]
 use nvml_wrapper::Nvml;
 
+use crate::specs::{GpuSpec, InstanceSpec};
+
+/// Compute capability of the GPUs without CUDA (e.g. the AMD ones), which doesn't limit the
+/// precisions they run, ROCm having its own kernels.
+pub const NO_COMPUTE_CAPABILITY: (u32, u32) = (0, 0);
+
 /// Struct for storing the hardware information of the running system.
 #[derive(Debug)]
 pub struct Hardware {
@@ -16,33 +28,45 @@ pub struct Hardware {
     pub cpu_cores: u16,
     /// The number of CPU threads of the running system.
     pub cpu_threads: u16,
+    /// The RAM of the running system in bytes, 0 when it is unknown.
+    pub memory: u64,
     /// The number of GPUs of the running system.
     pub gpu_count: u32,
-    /// The GPU devices information of the running system.
+    /// The NVIDIA GPU devices information of the running system.
     pub nvidia_gpus: Vec<NvidiaDevice>,
+    /// The AMD GPU devices information of the running system.
+    pub amd_gpus: Vec<AmdDevice>,
 }
 
 /// Trait for GPU devices that provides a method to obtain all information as a string.
 pub trait GPUDevice {
     /// Return a string with all information of the GPU device.
     fn get_info_string(&self) -> String;
+    /// Returns the name of the GPU device.
+    fn get_name(&self) -> String;
     /// Returns the memory_info of the GPU device.
     fn get_memory_info(&self) -> u64;
     /// Returns the memory_info of the GPU device formatted as a string.
     fn get_memory_info_formatted(&self) -> String;
     /// Returns the compute_capability of the GPU device formatted as a string.
     fn get_compute_capability_formatted(&self) -> String;
+    /// Returns the compute_capability of the GPU device as its major and minor versions.
+    fn get_compute_capability(&self) -> (u32, u32);
+    /// Returns the bandwidth of the PCIe link between the host and the GPU device in bytes/s.
+    fn get_pcie_bandwidth(&self) -> f64;
+    /// Returns the bandwidth of the GPU device memory in bytes/s.
+    fn get_memory_bandwidth(&self) -> f64;
 }
 
 /// Struct for storing the GPU information of the running system.
 #[derive(Debug)]
 pub struct NvidiaDevice {
-    /// The architecture of the NVIDIA GPU device.
-    architecture: DeviceArchitecture,
-    /// The brand of the NVIDIA GPU device.
-    brand: Brand,
-    /// The compute capability of the NVIDIA GPU device.
-    cuda_compute_capability: CudaComputeCapability,
+    /// The architecture of the NVIDIA GPU device (e.g. `Ampere`).
+    architecture: String,
+    /// The brand of the NVIDIA GPU device (e.g. `GeForce`).
+    brand: String,
+    /// The compute capability of the NVIDIA GPU device, as its major and minor versions.
+    cuda_compute_capability: (u32, u32),
     /// The memory_info of the NVIDIA GPU device.
     memory_info: u64,
     /// The name of the NVIDIA GPU device.
@@ -51,6 +75,123 @@ pub struct NvidiaDevice {
     num_cores: u32,
     /// The UUID of the NVIDIA GPU device.
     uuid: String,
+    /// The bandwidth of the PCIe link of the NVIDIA GPU device in bytes/s, 0 when it is unknown.
+    pcie_bandwidth: f64,
+    /// The bandwidth of the memory of the NVIDIA GPU device in bytes/s, 0 when it is unknown.
+    memory_bandwidth: f64,
+}
+
+/// Implementation of NvidiaDevice.
+impl NvidiaDevice {
+    /// Build a new NvidiaDevice struct based on the provided parameters.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        architecture: String,
+        brand: String,
+        cuda_compute_capability: (u32, u32),
+        memory_info: u64,
+        name: String,
+        num_cores: u32,
+        uuid: String,
+        pcie_bandwidth: f64,
+        memory_bandwidth: f64,
+    ) -> NvidiaDevice {
+        NvidiaDevice {
+            architecture,
+            brand,
+            cuda_compute_capability,
+            memory_info,
+            name,
+            num_cores,
+            uuid,
+            pcie_bandwidth,
+            memory_bandwidth,
+        }
+    }
+
+    /// Build a hypothetical NvidiaDevice from the specifications of a GPU, to check a model
+    /// against a GPU the running system doesn't have. The PCIe link is expected to be the x16
+    /// one of the generation of the GPU, the number of cores is left unknown.
+    pub fn from_spec(spec: &GpuSpec, index: usize) -> NvidiaDevice {
+        let (major, minor) = spec.compute_capability();
+        let architecture = match (major, minor) {
+            (7, 0) => "Volta",
+            (7, 5) => "Turing",
+            (8, 0) | (8, 6) => "Ampere",
+            _ => "Unknown",
+        };
+        let (brand, name) = match spec.name() {
+            name if name.starts_with("RTX 3") || name.starts_with("RTX 4") => {
+                ("GeForce", format!("GeForce {}", name))
+            }
+            name if name.starts_with("RTX") => ("Quadro", name.to_string()),
+            name => ("Tesla", name.to_string()),
+        };
+        let pcie_generation = match major {
+            9.. => 5,
+            8 => 4,
+            _ => 3,
+        };
+        NvidiaDevice {
+            architecture: architecture.to_string(),
+            brand: brand.to_string(),
+            cuda_compute_capability: (major, minor),
+            memory_info: spec.memory(),
+            name,
+            num_cores: 0,
+            uuid: format!("GPU-hypothetical-{}", index),
+            pcie_bandwidth: pcie_bandwidth(pcie_generation, 16),
+            memory_bandwidth: spec.memory_bandwidth(),
+        }
+    }
+}
+
+/// Implementation of Hardware.
+impl Hardware {
+    /// Returns every GPU of the running system, the NVIDIA ones then the AMD ones.
+    pub fn gpus(&self) -> Vec<&dyn GPUDevice> {
+        let nvidia_gpus = self.nvidia_gpus.iter().map(|d| d as &dyn GPUDevice);
+        let amd_gpus = self.amd_gpus.iter().map(|d| d as &dyn GPUDevice);
+        nvidia_gpus.chain(amd_gpus).collect()
+    }
+
+    /// Build a hypothetical Hardware struct with the given GPUs, and the OS, CPU and RAM of the
+    /// running system, to ask whether a model would fit after buying or renting the GPUs.
+    pub fn hypothetical(gpus: &[GpuSpec]) -> Hardware {
+        let nvidia_gpus: Vec<NvidiaDevice> = gpus
+            .iter()
+            .enumerate()
+            .map(|(index, spec)| NvidiaDevice::from_spec(spec, index))
+            .collect();
+        Hardware {
+            os: scan_os(),
+            arch: scan_arch(),
+            cpu_cores: scan_cpu_cores(),
+            cpu_threads: scan_cpu_threads(),
+            memory: scan_memory(),
+            gpu_count: nvidia_gpus.len() as u32,
+            nvidia_gpus,
+            amd_gpus: Vec::new(),
+        }
+    }
+
+    /// Build a hypothetical Hardware struct from a profile, either a cloud instance type (e.g.
+    /// `p4d.24xlarge`) or a GPU name with an optional count (e.g. `RTX 4090` or `2x A100-SXM4-80GB`).
+    pub fn from_profile(profile: &str) -> Result<Hardware, String> {
+        let profile = profile.trim();
+        if let Some(instance) = InstanceSpec::from_name(profile) {
+            let gpus = vec![instance.gpu(); instance.num_gpus() as usize];
+            return Ok(Hardware::hypothetical(&gpus));
+        }
+        let (count, name) = profile
+            .split_once(['x', '×'])
+            .and_then(|(count, name)| Some((count.trim().parse::<usize>().ok()?, name.trim())))
+            .unwrap_or((1, profile));
+        match GpuSpec::from_name(name) {
+            Some(spec) if count > 0 => Ok(Hardware::hypothetical(&vec![spec; count])),
+            _ => Err(format!("Unknown GPU or cloud instance: {}", profile)),
+        }
+    }
 }
 
 /// Implementation of GPUDevice for NvidiaDevice.
@@ -58,7 +199,7 @@ impl GPUDevice for NvidiaDevice {
     // Returns the memory_info of the GPU device.
     fn get_info_string(&self) -> String {
         format!(
-            "uuid: {}\nname: NVIDIA {}\narchitecture: {:?}\nbrand: {:?}\nmemory: {}\ncompute capability: {}\ncores: {}",
+            "uuid: {}\nname: NVIDIA {}\narchitecture: {}\nbrand: {}\nmemory: {}\ncompute capability: {}\ncores: {}",
             self.uuid,
             self.name,
             self.architecture,
@@ -68,6 +209,10 @@ impl GPUDevice for NvidiaDevice {
             self.num_cores,
         )
     }
+    // Returns the name of the GPU device.
+    fn get_name(&self) -> String {
+        format!("NVIDIA {}", self.name)
+    }
     // Returns the memory_info of the GPU device.
     fn get_memory_info(&self) -> u64 {
         self.memory_info
@@ -78,81 +223,283 @@ impl GPUDevice for NvidiaDevice {
         let memory_info = memory_info as f64;
         let memory_info = memory_info / 1024.0 / 1024.0 / 1024.0;
         format!("{:.2} GB", memory_info)
+    } // Returns the compute_capability of the GPU device formatted as a string.
+    fn get_compute_capability_formatted(&self) -> String {
+        let (major, minor) = self.cuda_compute_capability;
+        format!("{}.{}", major, minor)
+    }
+    // Returns the compute_capability of the GPU device as its major and minor versions.
+    fn get_compute_capability(&self) -> (u32, u32) {
+        self.cuda_compute_capability
+    }
+    // Returns the bandwidth of the PCIe link of the GPU device.
+    fn get_pcie_bandwidth(&self) -> f64 {
+        self.pcie_bandwidth
+    }
+    // Returns the bandwidth of the memory of the GPU device.
+    fn get_memory_bandwidth(&self) -> f64 {
+        self.memory_bandwidth
+    }
+}
+
+/// Struct for storing the information of an AMD GPU of the running system.
+#[derive(Debug)]
+pub struct AmdDevice {
+    /// The name of the AMD GPU device, its PCI device ID when the driver doesn't report it.
+    name: String,
+    /// The memory (VRAM) of the AMD GPU device in bytes.
+    memory_info: u64,
+    /// The bandwidth of the PCIe link of the AMD GPU device in bytes/s, 0 when it is unknown.
+    pcie_bandwidth: f64,
+}
+
+/// Implementation of AmdDevice.
+impl AmdDevice {
+    /// Build a new AmdDevice struct based on the provided parameters.
+    pub fn new(name: String, memory_info: u64, pcie_bandwidth: f64) -> AmdDevice {
+        AmdDevice {
+            name,
+            memory_info,
+            pcie_bandwidth,
+        }
+    }
+
+    /// Read an AMD GPU from its folder in the `sysfs` of the `amdgpu` driver (e.g.
+    /// `/sys/class/drm/card0/device`), `None` when the device isn't an AMD GPU.
+    #[cfg(all(feature = "rocm", not(target_arch = "wasm32")))]
+    pub fn from_sysfs(device: &Path) -> Option<AmdDevice> {
+        let read = |file: &str| {
+            std::fs::read_to_string(device.join(file))
+                .ok()
+                .map(|content| content.trim().to_string())
+        };
+        // 0x1002 is the PCI vendor ID of AMD
+        if read("vendor")? != "0x1002" {
+            return None;
+        }
+        let memory_info = read("mem_info_vram_total")?.parse().ok()?;
+        let name = read("product_name")
+            .filter(|name| !name.is_empty())
+            .or_else(|| read("device").map(|id| format!("Radeon {}", id)))?;
+        // The link speed is reported as a transfer rate, e.g. `16.0 GT/s PCIe` for PCIe 4.0
+        let generation = match read("max_link_speed")
+            .and_then(|speed| speed.split_whitespace().next()?.parse::<f64>().ok())
+        {
+            Some(rate) if rate >= 64.0 => 6,
+            Some(rate) if rate >= 32.0 => 5,
+            Some(rate) if rate >= 16.0 => 4,
+            Some(rate) if rate >= 8.0 => 3,
+            Some(rate) if rate >= 5.0 => 2,
+            Some(rate) if rate >= 2.5 => 1,
+            _ => 0,
+        };
+        let width = read("max_link_width")
+            .and_then(|width| width.parse().ok())
+            .unwrap_or(0);
+        Some(AmdDevice::new(
+            name,
+            memory_info,
+            pcie_bandwidth(generation, width),
+        ))
     }
-    // Returns the compute_capability of the GPU device formatted as a string.
+}
+
+/// Implementation of GPUDevice for AmdDevice.
+impl GPUDevice for AmdDevice {
+    // Returns a string with all information of the GPU device.
+    fn get_info_string(&self) -> String {
+        format!(
+            "name: {}\nmemory: {}",
+            self.get_name(),
+            self.get_memory_info_formatted()
+        )
+    }
+    // Returns the name of the GPU device.
+    fn get_name(&self) -> String {
+        format!("AMD {}", self.name)
+    }
+    // Returns the memory_info of the GPU device.
+    fn get_memory_info(&self) -> u64 {
+        self.memory_info
+    }
+    // Returns the memory_info of the GPU device formatted as a string.
+    fn get_memory_info_formatted(&self) -> String {
+        format!(
+            "{:.2} GB",
+            self.memory_info as f64 / 1024.0 / 1024.0 / 1024.0
+        )
+    }
+    // AMD GPUs have no CUDA compute capability.
     fn get_compute_capability_formatted(&self) -> String {
-        let compute_capability = &self.cuda_compute_capability;
-        format!("{}.{}", compute_capability.major, compute_capability.minor)
+        "n/a".to_string()
+    }
+    // AMD GPUs have no CUDA compute capability.
+    fn get_compute_capability(&self) -> (u32, u32) {
+        NO_COMPUTE_CAPABILITY
+    }
+    // Returns the bandwidth of the PCIe link of the GPU device.
+    fn get_pcie_bandwidth(&self) -> f64 {
+        self.pcie_bandwidth
     }
+    // The bandwidth of the memory isn't reported by the driver.
+    fn get_memory_bandwidth(&self) -> f64 {
+        0.0
+    }
+}
+
+/// Returns the bandwidth of a PCIe link in bytes/s, from its generation and its number of lanes.
+pub fn pcie_bandwidth(generation: u32, width: u32) -> f64 {
+    // Bytes/s of a single lane, after the line encoding
+    let lane_bandwidth = match generation {
+        1 => 0.25e9,
+        2 => 0.5e9,
+        3 => 0.985e9,
+        4 => 1.969e9,
+        5 => 3.938e9,
+        6 => 7.563e9,
+        _ => 0.0,
+    };
+    lane_bandwidth * width as f64
+}
+
+/// Returns the bandwidth of a GPU memory in bytes/s, from its bus width in bits and its clock in
+/// MHz (the memory transfers data twice per clock).
+pub fn memory_bandwidth(bus_width: u32, clock: u32) -> f64 {
+    bus_width as f64 / 8.0 * clock as f64 * 1e6 * 2.0
 }
 
 /// Scan the hardware of the running system and return a Hardware struct.
-// TODO: Add support for AMD GPUs.
 // TODO: Add support for Apple Silicon.
+#[cfg(not(target_arch = "wasm32"))]
+#[tracing::instrument(level = "debug")]
 pub fn scan_hardware() -> Result<Hardware, String> {
     // Get the operating system, architecture, and CPU information.
     let os = scan_os();
     let arch = scan_arch();
     let cpu_cores = scan_cpu_cores();
     let cpu_threads = scan_cpu_threads();
+    let memory = scan_memory();
+    let nvidia_gpus = scan_nvidia_gpus(&os, &arch)?;
+    let amd_gpus = scan_amd_gpus();
+    tracing::debug!(
+        os,
+        arch,
+        cpu_cores,
+        memory,
+        nvidia_gpus = nvidia_gpus.len(),
+        amd_gpus = amd_gpus.len(),
+        "Scanned the hardware"
+    );
+    Ok(Hardware {
+        os,
+        arch,
+        cpu_cores,
+        cpu_threads,
+        memory,
+        gpu_count: (nvidia_gpus.len() + amd_gpus.len()) as u32,
+        nvidia_gpus,
+        amd_gpus,
+    })
+}
+
+/// Returns the NVIDIA GPUs of the running system, none when the NVIDIA drivers are not installed.
+#[cfg(all(feature = "nvml", not(target_arch = "wasm32")))]
+#[tracing::instrument(level = "debug")]
+pub fn scan_nvidia_gpus(os: &str, arch: &str) -> Result<Vec<NvidiaDevice>, String> {
     // Get the number of available GPUs or return an error.
     let nvml = match Nvml::init() {
         Ok(nvml) => nvml,
         Err(_e) => {
-            // If NVML initialization fails, return a Hardware struct with 0 GPU count and an empty nvidia_gpus vector.
-            println!("NVIDIA drivers are not installed. If you have NVIDIA GPUs, see installation instructions at: https://www.nvidia.com/download/index.aspx");
-            return Ok(Hardware {
-                os,
-                arch,
-                cpu_cores,
-                cpu_threads,
-                gpu_count: 0,
-                nvidia_gpus: Vec::new(),
-            });
+            // If NVML initialization fails, there is no NVIDIA GPU to scan.
+            tracing::info!("NVIDIA drivers are not installed. If you have NVIDIA GPUs, see installation instructions at: https://www.nvidia.com/download/index.aspx");
+            return Ok(Vec::new());
         }
     };
-    let gpu_count = scan_gpu_count(&os, &arch, &nvml)?;
-    // If gpu_count is 0, then the system does not have any NVIDIA GPUs,
-    // so we can return the Hardware struct. Otherwise, we need to get
-    // the information for each GPU.
-    let nvidia_gpus = if gpu_count > 0 {
-        (0..gpu_count)
-            .map(|i| {
-                // Get the information for the GPU at index i.
-                let device = nvml.device_by_index(i).map_err(|e| e.to_string())?;
-                let architecture = device.architecture().map_err(|e| e.to_string())?;
-                let brand = device.brand().map_err(|e| e.to_string())?;
-                let cuda_compute_capability = device
-                    .cuda_compute_capability()
-                    .map_err(|e| e.to_string())?;
-                let memory_info = device.memory_info().map_err(|e| e.to_string())?.total;
-                let name = device.name().map_err(|e| e.to_string())?;
-                let num_cores = device.num_cores().map_err(|e| e.to_string())?;
-                let uuid = device.uuid().map_err(|e| e.to_string())?;
-                // Return the NvidiaDevice struct.
-                Ok(NvidiaDevice {
-                    architecture,
-                    brand,
-                    cuda_compute_capability,
-                    memory_info,
-                    name,
-                    num_cores,
-                    uuid,
-                })
+    let gpu_count = scan_gpu_count(os, arch, &nvml)?;
+    (0..gpu_count)
+        .map(|i| {
+            // Get the information for the GPU at index i.
+            let device = nvml.device_by_index(i).map_err(|e| e.to_string())?;
+            let architecture = device.architecture().map_err(|e| e.to_string())?;
+            let brand = device.brand().map_err(|e| e.to_string())?;
+            let cuda_compute_capability = device
+                .cuda_compute_capability()
+                .map_err(|e| e.to_string())?;
+            let cuda_compute_capability = (
+                cuda_compute_capability.major as u32,
+                cuda_compute_capability.minor as u32,
+            );
+            let memory_info = device.memory_info().map_err(|e| e.to_string())?.total;
+            let name = device.name().map_err(|e| e.to_string())?;
+            let num_cores = device.num_cores().map_err(|e| e.to_string())?;
+            let uuid = device.uuid().map_err(|e| e.to_string())?;
+            // Not every GPU reports its links, they are left unknown
+            let pcie_bandwidth = pcie_bandwidth(
+                device.max_pcie_link_gen().unwrap_or(0),
+                device.max_pcie_link_width().unwrap_or(0),
+            );
+            let memory_bandwidth = memory_bandwidth(
+                device.memory_bus_width().unwrap_or(0),
+                device.max_clock_info(Clock::Memory).unwrap_or(0),
+            );
+            // Return the NvidiaDevice struct.
+            Ok(NvidiaDevice {
+                architecture: format!("{:?}", architecture),
+                brand: format!("{:?}", brand),
+                cuda_compute_capability,
+                memory_info,
+                name,
+                num_cores,
+                uuid,
+                pcie_bandwidth,
+                memory_bandwidth,
             })
-            .collect::<Result<Vec<NvidiaDevice>, String>>()?
-    } else {
-        Vec::new()
+        })
+        .collect()
+}
+
+/// Returns the NVIDIA GPUs of the running system: none without the `nvml` feature.
+#[cfg(not(all(feature = "nvml", not(target_arch = "wasm32"))))]
+pub fn scan_nvidia_gpus(_os: &str, _arch: &str) -> Result<Vec<NvidiaDevice>, String> {
+    Ok(Vec::new())
+}
+
+/// Returns the AMD GPUs of the running system, read from the `amdgpu` driver on Linux, none on
+/// the other systems.
+#[cfg(all(feature = "rocm", not(target_arch = "wasm32")))]
+#[tracing::instrument(level = "debug")]
+pub fn scan_amd_gpus() -> Vec<AmdDevice> {
+    let Ok(entries) = std::fs::read_dir("/sys/class/drm") else {
+        return Vec::new();
     };
-    // Add the NVIDIA GPUs to the Hardware struct.
-    Ok(Hardware {
-        os,
-        arch,
-        cpu_cores,
-        cpu_threads,
-        gpu_count,
-        nvidia_gpus,
-    })
+    // The connectors of the cards (e.g. `card0-DP-1`) are listed alongside them
+    let mut cards: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("card") && !name.contains('-'))
+        })
+        .collect();
+    cards.sort();
+    cards
+        .iter()
+        .filter_map(|card| AmdDevice::from_sysfs(&card.join("device")))
+        .collect()
+}
+
+/// Returns the AMD GPUs of the running system: none without the `rocm` feature.
+#[cfg(not(all(feature = "rocm", not(target_arch = "wasm32"))))]
+pub fn scan_amd_gpus() -> Vec<AmdDevice> {
+    Vec::new()
+}
+
+/// Scan the hardware of the running system, which a `wasm32` target can't do: build the hardware
+/// from a profile instead (see `Hardware::from_profile`).
+#[cfg(target_arch = "wasm32")]
+pub fn scan_hardware() -> Result<Hardware, String> {
+    Err("Scanning the hardware is not supported on wasm32, use a hardware profile".to_string())
 }
 
 /// Returns the operating system of the running system.
@@ -165,19 +512,48 @@ pub fn scan_arch() -> String {
     std::env::consts::ARCH.to_string()
 }
 
-/// Returns the number of physical cores of the running system.
+/// Returns the number of physical cores of the running system, 0 when it is unknown (`wasm32`).
 pub fn scan_cpu_cores() -> u16 {
+    #[cfg(not(target_arch = "wasm32"))]
     let cores = num_cpus::get_physical();
+    #[cfg(target_arch = "wasm32")]
+    let cores = 0;
     cores as u16
 }
 
-/// Returns the number of logical cores of the running system.
+/// Returns the number of logical cores of the running system, 0 when it is unknown (`wasm32`).
 pub fn scan_cpu_threads() -> u16 {
+    #[cfg(not(target_arch = "wasm32"))]
     let threads = num_cpus::get();
+    #[cfg(target_arch = "wasm32")]
+    let threads = 0;
     threads as u16
 }
 
+/// Returns the RAM of the running system in bytes, read from `/proc/meminfo` on Linux, 0 on the
+/// other systems.
+pub fn scan_memory() -> u64 {
+    std::fs::read_to_string("/proc/meminfo")
+        .ok()
+        .and_then(|meminfo| {
+            meminfo
+                .lines()
+                .find_map(|line| line.strip_prefix("MemTotal:"))
+                .and_then(|total| {
+                    total
+                        .trim()
+                        .trim_end_matches("kB")
+                        .trim()
+                        .parse::<u64>()
+                        .ok()
+                })
+        })
+        .map(|kilobytes| kilobytes * 1024)
+        .unwrap_or(0)
+}
+
 /// Returns the number of available GPUs of the running system.
+#[cfg(all(feature = "nvml", not(target_arch = "wasm32")))]
 pub fn scan_gpu_count(os: &str, arch: &str, nvml: &Nvml) -> Result<u32, String> {
     match (os, arch) {
         ("linux", _) => _scan_gpu_count(nvml),
@@ -191,6 +567,7 @@ pub fn scan_gpu_count(os: &str, arch: &str, nvml: &Nvml) -> Result<u32, String>
 }
 
 /// Returns the number of available GPUs of the running system for NVIDIA GPUs.
+#[cfg(all(feature = "nvml", not(target_arch = "wasm32")))]
 fn _scan_gpu_count(nvml: &Nvml) -> Result<u32, String> {
     let devices = nvml.device_count().map_err(|e| e.to_string())?;
     Ok(devices)
@@ -203,13 +580,15 @@ mod tests {
     /// Setup a Hardware struct for testing.
     fn setup_nvidia_device() -> NvidiaDevice {
         NvidiaDevice {
-            architecture: DeviceArchitecture::Kepler,
-            brand: Brand::Tesla,
-            cuda_compute_capability: CudaComputeCapability { major: 3, minor: 7 },
+            architecture: "Kepler".to_string(),
+            brand: "Tesla".to_string(),
+            cuda_compute_capability: (3, 7),
             memory_info: 4096 * 1024 * 1024,
             name: "Tesla K80".to_string(),
             num_cores: 2496,
             uuid: "GPU-4c2b7f7c-0b7e-0e1a-1e1f-2f3e4d5e6f7g".to_string(),
+            pcie_bandwidth: 15.76e9,
+            memory_bandwidth: 240.6e9,
         }
     }
 
@@ -220,24 +599,25 @@ mod tests {
             arch: "x86_64".to_string(),
             cpu_cores: 8,
             cpu_threads: 16,
+            memory: 32 * 1024 * 1024 * 1024,
             gpu_count: 1,
             nvidia_gpus: vec![setup_nvidia_device()],
+            amd_gpus: Vec::new(),
         };
 
         assert_eq!(hardware.os, "linux".to_string());
         assert_eq!(hardware.arch, "x86_64".to_string());
         assert_eq!(hardware.cpu_cores, 8);
         assert_eq!(hardware.cpu_threads, 16);
+        assert_eq!(hardware.memory, 34359738368);
         assert_eq!(hardware.gpu_count, 1);
         assert_eq!(hardware.nvidia_gpus.len(), 1);
+        assert_eq!(hardware.gpus().len(), 1);
 
         let nvidia_gpu = &hardware.nvidia_gpus[0];
-        assert_eq!(nvidia_gpu.architecture, DeviceArchitecture::Kepler);
-        assert_eq!(nvidia_gpu.brand, Brand::Tesla);
-        assert_eq!(
-            nvidia_gpu.cuda_compute_capability,
-            CudaComputeCapability { major: 3, minor: 7 }
-        );
+        assert_eq!(nvidia_gpu.architecture, "Kepler");
+        assert_eq!(nvidia_gpu.brand, "Tesla");
+        assert_eq!(nvidia_gpu.cuda_compute_capability, (3, 7));
         assert_eq!(nvidia_gpu.memory_info, 4294967296);
         assert_eq!(nvidia_gpu.name, "Tesla K80".to_string());
         assert_eq!(nvidia_gpu.num_cores, 2496);
@@ -250,12 +630,9 @@ mod tests {
     #[test]
     fn test_struct_nvidia_device() {
         let nvidia_device = setup_nvidia_device();
-        assert_eq!(nvidia_device.architecture, DeviceArchitecture::Kepler);
-        assert_eq!(nvidia_device.brand, Brand::Tesla);
-        assert_eq!(
-            nvidia_device.cuda_compute_capability,
-            CudaComputeCapability { major: 3, minor: 7 }
-        );
+        assert_eq!(nvidia_device.architecture, "Kepler");
+        assert_eq!(nvidia_device.brand, "Tesla");
+        assert_eq!(nvidia_device.cuda_compute_capability, (3, 7));
         assert_eq!(nvidia_device.memory_info, 4294967296);
         assert_eq!(nvidia_device.name, "Tesla K80".to_string());
         assert_eq!(nvidia_device.num_cores, 2496);
@@ -272,12 +649,28 @@ mod tests {
         assert_eq!(device.get_info_string(), expected_info_string);
     }
 
+    #[test]
+    fn test_nvidia_device_get_name() {
+        let device = setup_nvidia_device();
+        assert_eq!(device.get_name(), "NVIDIA Tesla K80".to_string());
+    }
+
     #[test]
     fn test_nvidia_device_get_memory_info() {
         let device = setup_nvidia_device();
         let expected_info = 4294967296;
         assert_eq!(device.get_memory_info(), expected_info);
     }
+    #[test]
+    fn test_nvidia_device_get_bandwidths() {
+        let device = setup_nvidia_device();
+        assert_eq!(device.get_pcie_bandwidth(), 15.76e9);
+        assert_eq!(device.get_memory_bandwidth(), 240.6e9);
+        // PCIe 4.0 x16 and the 384-bit GDDR6X memory of a RTX 3090
+        assert_eq!(pcie_bandwidth(4, 16), 31.504e9);
+        assert_eq!(pcie_bandwidth(0, 0), 0.0);
+        assert_eq!(memory_bandwidth(384, 9751), 936.096e9);
+    }
 
     #[test]
     fn test_nvidia_device_get_memory_info_string() {
@@ -324,7 +717,104 @@ mod tests {
         let threads = scan_cpu_threads();
         assert_eq!(threads, num_cpus::get() as u16);
     }
+    #[test]
+    fn test_scan_memory() {
+        let memory = scan_memory();
+        if std::env::consts::OS == "linux" {
+            assert!(memory > 0);
+        } else {
+            assert_eq!(memory, 0);
+        }
+    }
+
+    #[test]
+    fn test_nvidia_device_from_spec() {
+        let spec = GpuSpec::from_name("RTX 3090").unwrap();
+        let device = NvidiaDevice::from_spec(&spec, 1);
+        assert_eq!(device.get_name(), "NVIDIA GeForce RTX 3090");
+        assert_eq!(device.get_memory_info(), spec.memory());
+        assert_eq!(device.get_compute_capability_formatted(), "8.6");
+        assert_eq!(device.architecture, "Ampere");
+        assert_eq!(device.brand, "GeForce");
+        assert_eq!(device.get_pcie_bandwidth(), pcie_bandwidth(4, 16));
+        assert_eq!(device.get_memory_bandwidth(), 936.0e9);
+        assert_eq!(device.uuid, "GPU-hypothetical-1");
+        // The device is found back in the specifications database
+        assert_eq!(GpuSpec::from_device(&device), Some(spec));
+
+        let h100 = NvidiaDevice::from_spec(&GpuSpec::from_name("H100 PCIe").unwrap(), 0);
+        assert_eq!(h100.get_name(), "NVIDIA H100 PCIe");
+        assert_eq!(h100.brand, "Tesla");
+        assert_eq!(h100.get_pcie_bandwidth(), pcie_bandwidth(5, 16));
+    }
+
+    #[test]
+    fn test_hardware_from_profile() {
+        let hardware = Hardware::from_profile("2x RTX 4090").unwrap();
+        assert_eq!(hardware.gpu_count, 2);
+        assert_eq!(hardware.nvidia_gpus.len(), 2);
+        assert_eq!(
+            hardware.nvidia_gpus[0].get_name(),
+            "NVIDIA GeForce RTX 4090"
+        );
+        assert_eq!(hardware.memory, scan_memory());
+
+        let hardware = Hardware::from_profile("A100-SXM4-80GB").unwrap();
+        assert_eq!(hardware.gpu_count, 1);
+        assert_eq!(
+            hardware.nvidia_gpus[0].get_memory_info(),
+            80 * 1024 * 1024 * 1024
+        );
+
+        let hardware = Hardware::from_profile("p4d.24xlarge").unwrap();
+        assert_eq!(hardware.gpu_count, 8);
+        assert_eq!(hardware.nvidia_gpus[7].get_name(), "NVIDIA A100-SXM4-40GB");
+
+        assert!(Hardware::from_profile("Radeon RX 7900 XTX").is_err());
+        assert!(Hardware::from_profile("0x RTX 4090").is_err());
+    }
+
+    #[test]
+    fn test_amd_device() {
+        let device = AmdDevice::new(
+            "Instinct MI210".to_string(),
+            64 * 1024 * 1024 * 1024,
+            31.504e9,
+        );
+        assert_eq!(device.get_name(), "AMD Instinct MI210");
+        assert_eq!(
+            device.get_info_string(),
+            "name: AMD Instinct MI210\nmemory: 64.00 GB"
+        );
+        assert_eq!(device.get_compute_capability(), NO_COMPUTE_CAPABILITY);
+        assert_eq!(device.get_pcie_bandwidth(), 31.504e9);
+    }
+
+    #[cfg(feature = "rocm")]
+    #[test]
+    fn test_amd_device_from_sysfs() {
+        let device_dir = std::env::temp_dir().join(format!("aiha-amdgpu-{}", std::process::id()));
+        std::fs::create_dir_all(&device_dir).unwrap();
+        for (file, content) in [
+            ("vendor", "0x1002\n"),
+            ("device", "0x744c\n"),
+            ("mem_info_vram_total", "25753026560\n"),
+            ("max_link_speed", "16.0 GT/s PCIe\n"),
+            ("max_link_width", "16\n"),
+        ] {
+            std::fs::write(device_dir.join(file), content).unwrap();
+        }
+        let device = AmdDevice::from_sysfs(&device_dir).unwrap();
+        assert_eq!(device.get_name(), "AMD Radeon 0x744c");
+        assert_eq!(device.get_memory_info(), 25753026560);
+        assert_eq!(device.get_pcie_bandwidth(), pcie_bandwidth(4, 16));
+        // The GPUs of the other vendors are left out
+        std::fs::write(device_dir.join("vendor"), "0x10de\n").unwrap();
+        assert!(AmdDevice::from_sysfs(&device_dir).is_none());
+        std::fs::remove_dir_all(&device_dir).unwrap();
+    }
 
+    #[cfg(feature = "nvml")]
     #[test]
     fn test_scan_gpu_count() {
         let os = std::env::consts::OS.to_string();
@@ -0,0 +1,125 @@
+//! Blocking hub client, running the requests of the async one on its own Tokio runtime
+use std::error::Error;
+use std::io;
+
+use tokio::runtime::{Builder, Runtime};
+
+use crate::hub::{
+    CommitSha, HubClient, ModelConfig, ModelInfo, OnnxInfo, PipelineInfo, RepoKind, SearchQuery,
+    SearchResult, Siblings,
+};
+
+/// Struct for interacting with the Hugging Face Hub without an async runtime, every call blocking
+/// until its requests are done. It can't be used from within an async runtime.
+#[derive(Debug)]
+pub struct BlockingHubClient {
+    /// The wrapped hub client
+    client: HubClient,
+    /// The runtime the requests run on
+    runtime: Runtime,
+}
+
+/// Implement the `BlockingHubClient` struct
+impl BlockingHubClient {
+    /// Wrap a hub client, with a new single-threaded runtime
+    pub fn new(client: HubClient) -> io::Result<Self> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        Ok(Self { client, runtime })
+    }
+    /// Get the wrapped hub client
+    pub fn client(&self) -> &HubClient {
+        &self.client
+    }
+    /// Retrieve the model info, see `HubClient::retrieve_model_info`
+    pub fn retrieve_model_info(
+        &self,
+        repo_id: &str,
+        revision: Option<&str>,
+        files_metadata: Option<bool>,
+    ) -> Result<ModelInfo, Box<dyn Error>> {
+        self.runtime.block_on(
+            self.client
+                .retrieve_model_info(repo_id, revision, files_metadata),
+        )
+    }
+    /// Retrieve specific files info for a model, see `HubClient::list_files_info`
+    pub fn list_files_info(
+        &self,
+        repo_id: &str,
+        revision: Option<&str>,
+        siblings: &mut Siblings,
+    ) -> Result<(), Box<dyn Error>> {
+        self.runtime
+            .block_on(self.client.list_files_info(repo_id, revision, siblings))
+    }
+    /// Search the models matching a query, see `HubClient::search_models`
+    pub fn search_models(&self, query: &SearchQuery) -> Result<Vec<SearchResult>, Box<dyn Error>> {
+        self.runtime.block_on(self.client.search_models(query))
+    }
+    /// Get the model config file, see `HubClient::get_model_config`
+    pub fn get_model_config(
+        &self,
+        repo_id: &str,
+        revision: Option<&str>,
+        tags: Option<&[String]>,
+        model_config: &mut Option<ModelConfig>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.runtime.block_on(
+            self.client
+                .get_model_config(repo_id, revision, tags, model_config),
+        )
+    }
+    /// Get the diffusers pipeline metadata, see `HubClient::get_pipeline_info`
+    pub fn get_pipeline_info(
+        &self,
+        repo_id: &str,
+        revision: Option<&str>,
+        siblings: Option<&Siblings>,
+    ) -> Result<PipelineInfo, Box<dyn Error>> {
+        self.runtime
+            .block_on(self.client.get_pipeline_info(repo_id, revision, siblings))
+    }
+    /// Get the ONNX exports of a repository, see `HubClient::get_onnx_info`
+    pub fn get_onnx_info(
+        &self,
+        repo_id: &str,
+        revision: Option<&str>,
+        siblings: &Siblings,
+    ) -> Result<Option<OnnxInfo>, Box<dyn Error>> {
+        self.runtime
+            .block_on(self.client.get_onnx_info(repo_id, revision, siblings))
+    }
+    /// Check if a repository exists and find its kind, see `HubClient::repo_exists`
+    pub fn repo_exists(&self, repo_id: &str) -> Result<RepoKind, Box<dyn Error>> {
+        self.runtime.block_on(self.client.repo_exists(repo_id))
+    }
+    /// Resolve a revision to its full commit SHA, see `HubClient::resolve_revision`
+    pub fn resolve_revision(
+        &self,
+        repo_id: &str,
+        revision: Option<&str>,
+    ) -> Result<CommitSha, Box<dyn Error>> {
+        self.runtime
+            .block_on(self.client.resolve_revision(repo_id, revision))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hub::api::is_unreachable;
+
+    #[test]
+    fn test_blocking_hub_client() {
+        // Nothing listens on this port, so the endpoint is unreachable
+        let client = HubClient::new(Some("token".to_string()))
+            .with_endpoints(vec!["http://127.0.0.1:9".to_string()])
+            .with_timeout(1.0);
+        let client = BlockingHubClient::new(client).unwrap();
+        assert_eq!(client.client().get_token(), Some("token"));
+        let result = client.repo_exists("EleutherAI/gpt-j-6b");
+        assert!(is_unreachable(result.unwrap_err().as_ref()));
+        let result = client.resolve_revision("EleutherAI/gpt-j-6b", Some("main"));
+        assert!(result.is_err());
+    }
+}
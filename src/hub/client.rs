@@ -0,0 +1,277 @@
+//! Hub client struct with mirror endpoints failover
+use std::env;
+use std::error::Error;
+
+use reqwest::Client;
+
+use crate::hub::api::{
+    build_http_client, get_model_config_from, get_onnx_info_from, get_pipeline_info_from,
+    is_unreachable, list_files_info_from, repo_exists_from, resolve_revision_from,
+    retrieve_model_info_from, search_models_from,
+};
+use crate::hub::{
+    CommitSha, ModelConfig, ModelInfo, OnnxInfo, PipelineInfo, RepoKind, SearchQuery, SearchResult,
+    Siblings, DEFAULT_TIMEOUT, HUB_ENDPOINT,
+};
+
+/// Try a hub call on every endpoint of the client, in order, until one of them is reachable.
+/// Any other error (e.g. a missing repository) is returned right away.
+macro_rules! with_failover {
+    ($client:ident, $endpoint:ident => $call:expr) => {{
+        let mut last_error: Option<Box<dyn Error>> = None;
+        for $endpoint in $client.endpoints.iter() {
+            match $call.await {
+                Err(e) if is_unreachable(e.as_ref()) => {
+                    tracing::warn!(endpoint = %$endpoint, error = %e, "Endpoint unreachable");
+                    last_error = Some(e)
+                }
+                result => return result,
+            }
+        }
+        Err(last_error.unwrap_or_else(|| "No endpoint configured".into()))
+    }};
+}
+
+/// Struct for interacting with the Hugging Face Hub through an ordered list of endpoints
+#[derive(Clone, Debug)]
+pub struct HubClient {
+    /// The endpoints to try, in order (e.g. `https://huggingface.co`, `https://hf-mirror.com`)
+    endpoints: Vec<String>,
+    /// The token used to authenticate the requests
+    token: Option<String>,
+    /// The HTTP client every request is sent with, holding the timeouts
+    http: Client,
+}
+
+/// Implement the `HubClient` struct
+impl HubClient {
+    /// Create a new HubClient struct targeting the default Hugging Face Hub endpoint
+    pub fn new(token: Option<String>) -> Self {
+        Self {
+            endpoints: vec![HUB_ENDPOINT.to_string()],
+            token,
+            http: build_http_client(DEFAULT_TIMEOUT),
+        }
+    }
+    /// Create a new HubClient struct from the `HF_ENDPOINT` and `HF_TOKEN` environment variables.
+    /// The `HF_ENDPOINT` endpoint is tried first, then the default Hugging Face Hub endpoint.
+    pub fn from_env() -> Self {
+        let client = Self::new(env::var("HF_TOKEN").ok());
+        match env::var("HF_ENDPOINT") {
+            Ok(endpoint) if !endpoint.trim().is_empty() => {
+                client.with_endpoints(vec![endpoint, HUB_ENDPOINT.to_string()])
+            }
+            _ => client,
+        }
+    }
+    /// Replace the endpoints of the client, the first one being the primary endpoint
+    pub fn with_endpoints(mut self, endpoints: Vec<String>) -> Self {
+        self.endpoints = Vec::new();
+        for endpoint in endpoints {
+            self = self.with_mirror(&endpoint);
+        }
+        self
+    }
+    /// Add a mirror endpoint, tried after the already configured ones
+    pub fn with_mirror(mut self, endpoint: &str) -> Self {
+        let endpoint = endpoint.trim().trim_end_matches('/').to_string();
+        if !endpoint.is_empty() && !self.endpoints.contains(&endpoint) {
+            self.endpoints.push(endpoint);
+        }
+        self
+    }
+    /// Set the timeout of every request and of its connection in seconds, `DEFAULT_TIMEOUT` by
+    /// default, so an endpoint dropping the packets fails over to the next one in time
+    pub fn with_timeout(mut self, timeout: f32) -> Self {
+        self.http = build_http_client(timeout);
+        self
+    }
+    /// Get the endpoints of the client, in the order they are tried
+    pub fn get_endpoints(&self) -> &[String] {
+        &self.endpoints
+    }
+    /// Get the token of the client
+    pub fn get_token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+    /// Retrieve the model info, see `hub::retrieve_model_info`
+    pub async fn retrieve_model_info(
+        &self,
+        repo_id: &str,
+        revision: Option<&str>,
+        files_metadata: Option<bool>,
+    ) -> Result<ModelInfo, Box<dyn Error>> {
+        with_failover!(self, endpoint => retrieve_model_info_from(
+            &self.http,
+            endpoint,
+            repo_id,
+            revision,
+            files_metadata,
+            self.get_token(),
+        ))
+    }
+    /// Retrieve specific files info for a model, see `hub::list_files_info`
+    pub async fn list_files_info(
+        &self,
+        repo_id: &str,
+        revision: Option<&str>,
+        siblings: &mut Siblings,
+    ) -> Result<(), Box<dyn Error>> {
+        with_failover!(self, endpoint => list_files_info_from(
+            &self.http,
+            endpoint,
+            repo_id,
+            revision,
+            siblings,
+            self.get_token(),
+        ))
+    }
+    /// Search the models matching a query, see `hub::search_models`
+    pub async fn search_models(
+        &self,
+        query: &SearchQuery,
+    ) -> Result<Vec<SearchResult>, Box<dyn Error>> {
+        with_failover!(self, endpoint => search_models_from(&self.http, endpoint, query, self.get_token()))
+    }
+    /// Get the model config file, see `hub::get_model_config`
+    pub async fn get_model_config(
+        &self,
+        repo_id: &str,
+        revision: Option<&str>,
+        tags: Option<&[String]>,
+        model_config: &mut Option<ModelConfig>,
+    ) -> Result<(), Box<dyn Error>> {
+        with_failover!(self, endpoint => get_model_config_from(
+            &self.http,
+            endpoint,
+            repo_id,
+            revision,
+            tags,
+            model_config,
+            self.get_token(),
+        ))
+    }
+    /// Get the diffusers pipeline metadata, see `hub::get_pipeline_info`
+    pub async fn get_pipeline_info(
+        &self,
+        repo_id: &str,
+        revision: Option<&str>,
+        siblings: Option<&Siblings>,
+    ) -> Result<PipelineInfo, Box<dyn Error>> {
+        with_failover!(self, endpoint => get_pipeline_info_from(
+            &self.http,
+            endpoint,
+            repo_id,
+            revision,
+            siblings,
+            self.get_token(),
+        ))
+    }
+    /// Get the ONNX exports of a repository, see `hub::get_onnx_info`
+    pub async fn get_onnx_info(
+        &self,
+        repo_id: &str,
+        revision: Option<&str>,
+        siblings: &Siblings,
+    ) -> Result<Option<OnnxInfo>, Box<dyn Error>> {
+        with_failover!(self, endpoint => get_onnx_info_from(
+            &self.http,
+            endpoint,
+            repo_id,
+            revision,
+            siblings,
+            self.get_token(),
+        ))
+    }
+    /// Check if a repository exists and find its kind, see `hub::repo_exists`
+    pub async fn repo_exists(&self, repo_id: &str) -> Result<RepoKind, Box<dyn Error>> {
+        with_failover!(self, endpoint => repo_exists_from(&self.http, endpoint, repo_id, self.get_token()))
+    }
+    /// Resolve a revision to its full commit SHA, see `hub::resolve_revision`
+    pub async fn resolve_revision(
+        &self,
+        repo_id: &str,
+        revision: Option<&str>,
+    ) -> Result<CommitSha, Box<dyn Error>> {
+        with_failover!(self, endpoint => resolve_revision_from(
+            &self.http,
+            endpoint,
+            repo_id,
+            revision,
+            self.get_token(),
+        ))
+    }
+}
+
+/// Implement the default `HubClient`, targeting the Hugging Face Hub without token
+impl Default for HubClient {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hub::api::tests::{serve_silently, serve_status};
+    use pretty_assertions::assert_eq;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_hub_client_new() {
+        let client = HubClient::new(Some("token".to_string()));
+        assert_eq!(client.get_endpoints(), &[HUB_ENDPOINT.to_string()]);
+        assert_eq!(client.get_token(), Some("token"));
+        assert_eq!(HubClient::default().get_token(), None);
+    }
+
+    #[test]
+    fn test_hub_client_endpoints() {
+        let client = HubClient::new(None)
+            .with_mirror("https://hf-mirror.com/")
+            .with_mirror("https://huggingface.co")
+            .with_mirror(" ");
+        assert_eq!(
+            client.get_endpoints(),
+            &[
+                HUB_ENDPOINT.to_string(),
+                "https://hf-mirror.com".to_string()
+            ]
+        );
+
+        let client = client.with_endpoints(vec![
+            "https://hf-mirror.com".to_string(),
+            HUB_ENDPOINT.to_string(),
+        ]);
+        assert_eq!(
+            client.get_endpoints(),
+            &[
+                "https://hf-mirror.com".to_string(),
+                HUB_ENDPOINT.to_string()
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hub_client_failover() {
+        // The primary endpoint never answers, the mirror finds the model
+        let client = HubClient::new(Some("token".to_string()))
+            .with_endpoints(vec![serve_silently(), serve_status("200 OK")])
+            .with_timeout(0.5);
+        let start = Instant::now();
+        let result = client.repo_exists("EleutherAI/gpt-j-6b").await;
+        assert_eq!(result.unwrap(), RepoKind::Model);
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        // Every endpoint times out
+        let client = client.with_endpoints(vec![serve_silently(), serve_silently()]);
+        let result = client
+            .resolve_revision("EleutherAI/gpt-j-6b", Some("main"))
+            .await;
+        assert!(is_unreachable(result.unwrap_err().as_ref()));
+
+        let client = client.with_endpoints(Vec::new());
+        let result = client.repo_exists("EleutherAI/gpt-j-6b").await;
+        assert_eq!(result.unwrap_err().to_string(), "No endpoint configured");
+    }
+}
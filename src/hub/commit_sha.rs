@@ -0,0 +1,70 @@
+//! Commit SHA struct
+use std::fmt;
+
+use serde::Deserialize;
+
+/// Struct for storing a resolved git commit SHA of a repository
+///
+/// Passing it as the `revision` of every hub call guarantees that all of them read the same
+/// snapshot of the repository, even if a new commit is pushed in the meantime.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct CommitSha(String);
+
+/// Implement the `CommitSha` struct
+impl CommitSha {
+    /// Create a new CommitSha struct, returns `None` if the value isn't a full 40 characters SHA
+    pub fn new(sha: &str) -> Option<Self> {
+        if Self::is_commit_sha(sha) {
+            Some(Self(sha.to_lowercase()))
+        } else {
+            None
+        }
+    }
+    /// Check if a revision is already a full commit SHA
+    pub fn is_commit_sha(revision: &str) -> bool {
+        revision.len() == 40 && revision.chars().all(|c| c.is_ascii_hexdigit())
+    }
+    /// Get the commit SHA as a string slice, ready to be used as a `revision`
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Implement the display of the CommitSha struct
+impl fmt::Display for CommitSha {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Implement the conversion of the CommitSha struct into a string slice
+impl AsRef<str> for CommitSha {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_commit_sha_new() {
+        let sha = CommitSha::new("F98C709453C9402B1309B032F40DF1C10AD481A2");
+        assert_eq!(
+            sha.unwrap().as_str(),
+            "f98c709453c9402b1309b032f40df1c10ad481a2"
+        );
+        assert!(CommitSha::new("main").is_none());
+        assert!(CommitSha::new("f98c709").is_none());
+        assert!(CommitSha::new("z98c709453c9402b1309b032f40df1c10ad481a2").is_none());
+    }
+
+    #[test]
+    fn test_commit_sha_display() {
+        let sha = CommitSha::new("f98c709453c9402b1309b032f40df1c10ad481a2").unwrap();
+        assert_eq!(sha.to_string(), "f98c709453c9402b1309b032f40df1c10ad481a2");
+        assert_eq!(sha.as_ref(), "f98c709453c9402b1309b032f40df1c10ad481a2");
+    }
+}
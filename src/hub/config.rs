@@ -1,139 +1,622 @@
 //! Config metadata struct
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
 use serde::Deserialize;
 
 use crate::models::{
-    BertModelConfig, BloomModelConfig, GPT2ModelConfig, GPTJModelConfig, GPTNeoModelConfig,
-    LlamaModelConfig, ModelConfigTrait, ModelError, OPTModelConfig, T5ModelConfig,
+    BartModelConfig, BertModelConfig, BloomModelConfig, CLIPModelConfig, CodeGenModelConfig,
+    CompositeModelConfig, DeepSeekModelConfig, DetrModelConfig, Dtype, GPT2ModelConfig,
+    GPTJModelConfig, GPTNeoModelConfig, GenericModelConfig, InternLMModelConfig, LlamaModelConfig,
+    LlavaModelConfig, MarianModelConfig, MixtralModelConfig, ModelConfigTrait, ModelError,
+    ModelFamily, OPTModelConfig, ParameterBreakdown, PegasusModelConfig, RobertaModelConfig,
+    RopeParams, SegformerModelConfig, SwinModelConfig, T5ModelConfig, TrOCRModelConfig,
+    ViTModelConfig, VisionEncoderDecoderModelConfig, WhisperModelConfig,
 };
 
+/// Architectures prefixes (as found in the `architectures` field of a config) and the
+/// model_type they belong to. The longest prefixes come first, e.g. `GPTNeoX` before `GPTNeo`.
+const ARCHITECTURES_MODEL_TYPES: [(&str, &str); 33] = [
+    ("MBart", "mbart"),
+    ("Bart", "bart"),
+    ("Bert", "bert"),
+    ("Bloom", "bloom"),
+    ("CLIP", "clip"),
+    ("CodeGen", "codegen"),
+    ("DeepseekV2", "deepseek_v2"),
+    ("Deepseek", "deepseek"),
+    ("DeformableDetr", "deformable_detr"),
+    ("Detr", "detr"),
+    ("GPT2", "gpt2"),
+    ("GPTJ", "gptj"),
+    ("GPTNeoX", "gpt_neox"),
+    ("GPTNeo", "gpt_neo"),
+    ("InternLM2", "internlm2"),
+    ("InternLM", "internlm"),
+    ("Llama", "llama"),
+    ("Llava", "llava"),
+    ("Marian", "marian"),
+    ("Mixtral", "mixtral"),
+    ("OPT", "opt"),
+    ("Pegasus", "pegasus"),
+    ("XLMRoberta", "xlm-roberta"),
+    ("Roberta", "roberta"),
+    ("Segformer", "segformer"),
+    ("DonutSwin", "donut-swin"),
+    ("Swin", "swin"),
+    ("T5", "t5"),
+    ("TrOCR", "trocr"),
+    ("VisionEncoderDecoder", "vision-encoder-decoder"),
+    ("DeiT", "deit"),
+    ("ViT", "vit"),
+    ("Whisper", "whisper"),
+];
+
+/// Guess the model_type from the `architectures` field of a config (e.g. `LlamaForCausalLM`)
+fn model_type_from_architectures(value: &serde_json::Value) -> Option<&'static str> {
+    value["architectures"]
+        .as_array()?
+        .iter()
+        .filter_map(|architecture| architecture.as_str())
+        .find_map(|architecture| {
+            ARCHITECTURES_MODEL_TYPES
+                .iter()
+                .find(|(prefix, _)| architecture.starts_with(prefix))
+                .map(|(_, model_type)| *model_type)
+        })
+}
+
 /// Enum all the possible model types
 #[derive(Clone, Debug, Deserialize)]
 pub enum ModelConfig {
+    /// BART model config
+    Bart(BartModelConfig),
     /// Bert model config
     Bert(BertModelConfig),
     /// Bloom model config
     Bloom(BloomModelConfig),
+    /// CLIP model config
+    Clip(CLIPModelConfig),
+    /// CodeGen model config
+    CodeGen(CodeGenModelConfig),
+    /// Composite model config, for the unsupported models nesting sub-configs
+    Composite(CompositeModelConfig),
+    /// DeepSeek model config
+    DeepSeek(DeepSeekModelConfig),
+    /// DETR model config
+    Detr(DetrModelConfig),
+    /// Fallback config for the unsupported model types
+    Generic(GenericModelConfig),
     /// GPT2 model config
     Gpt2(GPT2ModelConfig),
     /// GPTJ model config
     GptJ(GPTJModelConfig),
     /// GPTNeo model config
     GPTNeo(GPTNeoModelConfig),
+    /// InternLM model config
+    InternLM(InternLMModelConfig),
     /// Llama model config
     Llama(LlamaModelConfig),
+    /// LLaVA model config
+    Llava(LlavaModelConfig),
+    /// Marian model config
+    Marian(MarianModelConfig),
+    /// Mixtral model config
+    Mixtral(MixtralModelConfig),
     /// OPT model config
     Opt(OPTModelConfig),
+    /// Pegasus model config
+    Pegasus(PegasusModelConfig),
+    /// RoBERTa model config
+    Roberta(RobertaModelConfig),
+    /// SegFormer model config
+    Segformer(SegformerModelConfig),
+    /// Swin Transformer model config
+    Swin(SwinModelConfig),
     /// T5 model config
     T5(T5ModelConfig),
+    /// TrOCR decoder config
+    TrOCR(TrOCRModelConfig),
+    /// Vision encoder-decoder model config
+    VisionEncoderDecoder(VisionEncoderDecoderModelConfig),
+    /// ViT model config
+    ViT(ViTModelConfig),
+    /// Whisper model config
+    Whisper(WhisperModelConfig),
 }
 
 /// Model config implementation
-impl ModelConfigTrait for ModelConfig {
-    fn hidden_size(&self) -> i32 {
+impl ModelConfig {
+    /// Get the inner model config, to dispatch the `ModelConfigTrait` methods to it
+    fn inner(&self) -> &dyn ModelConfigTrait {
         match self {
-            ModelConfig::Bert(config) => config.hidden_size(),
-            ModelConfig::Bloom(config) => config.hidden_size(),
-            ModelConfig::Gpt2(config) => config.hidden_size(),
-            ModelConfig::GptJ(config) => config.hidden_size(),
-            ModelConfig::GPTNeo(config) => config.hidden_size(),
-            ModelConfig::Llama(config) => config.hidden_size(),
-            ModelConfig::Opt(config) => config.hidden_size(),
-            ModelConfig::T5(config) => config.hidden_size(),
+            ModelConfig::Bart(config) => config,
+            ModelConfig::Bert(config) => config,
+            ModelConfig::Bloom(config) => config,
+            ModelConfig::Clip(config) => config,
+            ModelConfig::CodeGen(config) => config,
+            ModelConfig::Composite(config) => config,
+            ModelConfig::DeepSeek(config) => config,
+            ModelConfig::Detr(config) => config,
+            ModelConfig::Generic(config) => config,
+            ModelConfig::Gpt2(config) => config,
+            ModelConfig::GptJ(config) => config,
+            ModelConfig::GPTNeo(config) => config,
+            ModelConfig::InternLM(config) => config,
+            ModelConfig::Llama(config) => config,
+            ModelConfig::Llava(config) => config,
+            ModelConfig::Marian(config) => config,
+            ModelConfig::Mixtral(config) => config,
+            ModelConfig::Opt(config) => config,
+            ModelConfig::Pegasus(config) => config,
+            ModelConfig::Roberta(config) => config,
+            ModelConfig::Segformer(config) => config,
+            ModelConfig::Swin(config) => config,
+            ModelConfig::T5(config) => config,
+            ModelConfig::TrOCR(config) => config,
+            ModelConfig::VisionEncoderDecoder(config) => config,
+            ModelConfig::ViT(config) => config,
+            ModelConfig::Whisper(config) => config,
         }
     }
-    fn intermediate_size(&self) -> i32 {
+    /// Whether the config is a low confidence one, i.e. its fields were guessed by the generic
+    /// fallback because the model type is not supported
+    pub fn is_low_confidence(&self) -> bool {
         match self {
-            ModelConfig::Bert(config) => config.intermediate_size(),
-            ModelConfig::Bloom(config) => config.intermediate_size(),
-            ModelConfig::Gpt2(config) => config.intermediate_size(),
-            ModelConfig::GptJ(config) => config.intermediate_size(),
-            ModelConfig::GPTNeo(config) => config.intermediate_size(),
-            ModelConfig::Llama(config) => config.intermediate_size(),
-            ModelConfig::Opt(config) => config.intermediate_size(),
-            ModelConfig::T5(config) => config.intermediate_size(),
+            ModelConfig::Generic(_) => true,
+            ModelConfig::Composite(config) => config
+                .sub_configs()
+                .iter()
+                .any(|(_, sub_config)| sub_config.is_low_confidence()),
+            _ => false,
         }
     }
-    fn max_position_embeddings(&self) -> i32 {
-        match self {
-            ModelConfig::Bert(config) => config.max_position_embeddings(),
-            ModelConfig::Bloom(config) => config.max_position_embeddings(),
-            ModelConfig::Gpt2(config) => config.max_position_embeddings(),
-            ModelConfig::GptJ(config) => config.max_position_embeddings(),
-            ModelConfig::GPTNeo(config) => config.max_position_embeddings(),
-            ModelConfig::Llama(config) => config.max_position_embeddings(),
-            ModelConfig::Opt(config) => config.max_position_embeddings(),
-            ModelConfig::T5(config) => config.max_position_embeddings(),
+    /// Load a model config from a local `config.json` file, e.g. the one of an on-disk
+    /// checkpoint, without any Hub call
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<ModelConfig, ModelError> {
+        let file = File::open(path)?;
+        ModelConfig::from_reader(BufReader::new(file))
+    }
+    /// Load a model config from a reader over the JSON content of a `config.json` file
+    pub fn from_reader<R: Read>(reader: R) -> Result<ModelConfig, ModelError> {
+        let value: serde_json::Value = serde_json::from_reader(reader)?;
+        ModelConfig::from_json(value)
+    }
+    /// Build the config of a supported model_type, `None` when the model_type is not supported
+    fn from_model_type(
+        model_type: &str,
+        value: serde_json::Value,
+    ) -> Option<Result<ModelConfig, ModelError>> {
+        match model_type {
+            "bart" | "mbart" => Some(BartModelConfig::from_json(value).map(ModelConfig::Bart)),
+            "bert" => Some(BertModelConfig::from_json(value).map(ModelConfig::Bert)),
+            "bloom" => Some(BloomModelConfig::from_json(value).map(ModelConfig::Bloom)),
+            "clip" => Some(CLIPModelConfig::from_json(value).map(ModelConfig::Clip)),
+            "codegen" => Some(CodeGenModelConfig::from_json(value).map(ModelConfig::CodeGen)),
+            "deepseek" | "deepseek_v2" => {
+                Some(DeepSeekModelConfig::from_json(value).map(ModelConfig::DeepSeek))
+            }
+            "detr" | "deformable_detr" => {
+                Some(DetrModelConfig::from_json(value).map(ModelConfig::Detr))
+            }
+            "gpt2" => Some(GPT2ModelConfig::from_json(value).map(ModelConfig::Gpt2)),
+            "gptj" => Some(GPTJModelConfig::from_json(value).map(ModelConfig::GptJ)),
+            "gpt_neo" => Some(GPTNeoModelConfig::from_json(value).map(ModelConfig::GPTNeo)),
+            "gpt_neox" => Some(GPTNeoModelConfig::from_json(value).map(ModelConfig::GPTNeo)),
+            "internlm" | "internlm2" => {
+                Some(InternLMModelConfig::from_json(value).map(ModelConfig::InternLM))
+            }
+            "llama" => Some(LlamaModelConfig::from_json(value).map(ModelConfig::Llama)),
+            "llava" => Some(LlavaModelConfig::from_json(value).map(ModelConfig::Llava)),
+            "marian" => Some(MarianModelConfig::from_json(value).map(ModelConfig::Marian)),
+            "mixtral" => Some(MixtralModelConfig::from_json(value).map(ModelConfig::Mixtral)),
+            "opt" => Some(OPTModelConfig::from_json(value).map(ModelConfig::Opt)),
+            "pegasus" => Some(PegasusModelConfig::from_json(value).map(ModelConfig::Pegasus)),
+            "roberta" | "xlm-roberta" => {
+                Some(RobertaModelConfig::from_json(value).map(ModelConfig::Roberta))
+            }
+            "segformer" => Some(SegformerModelConfig::from_json(value).map(ModelConfig::Segformer)),
+            "swin" | "donut-swin" => Some(SwinModelConfig::from_json(value).map(ModelConfig::Swin)),
+            "t5" => Some(T5ModelConfig::from_json(value).map(ModelConfig::T5)),
+            "trocr" => Some(TrOCRModelConfig::from_json(value).map(ModelConfig::TrOCR)),
+            "vision-encoder-decoder" => Some(
+                VisionEncoderDecoderModelConfig::from_json(value)
+                    .map(ModelConfig::VisionEncoderDecoder),
+            ),
+            "vit" | "deit" => Some(ViTModelConfig::from_json(value).map(ModelConfig::ViT)),
+            "whisper" => Some(WhisperModelConfig::from_json(value).map(ModelConfig::Whisper)),
+            _ => None,
         }
     }
+}
+
+/// Model config trait implementation, dispatched to the inner model config
+impl ModelConfigTrait for ModelConfig {
+    fn hidden_size(&self) -> i32 {
+        self.inner().hidden_size()
+    }
+    fn intermediate_size(&self) -> i32 {
+        self.inner().intermediate_size()
+    }
+    fn max_position_embeddings(&self) -> i32 {
+        self.inner().max_position_embeddings()
+    }
     fn num_attention_heads(&self) -> i32 {
-        match self {
-            ModelConfig::Bert(config) => config.num_attention_heads(),
-            ModelConfig::Bloom(config) => config.num_attention_heads(),
-            ModelConfig::Gpt2(config) => config.num_attention_heads(),
-            ModelConfig::GptJ(config) => config.num_attention_heads(),
-            ModelConfig::GPTNeo(config) => config.num_attention_heads(),
-            ModelConfig::Llama(config) => config.num_attention_heads(),
-            ModelConfig::Opt(config) => config.num_attention_heads(),
-            ModelConfig::T5(config) => config.num_attention_heads(),
-        }
+        self.inner().num_attention_heads()
     }
     fn num_hidden_layers(&self) -> i32 {
-        match self {
-            ModelConfig::Bert(config) => config.num_hidden_layers(),
-            ModelConfig::Bloom(config) => config.num_hidden_layers(),
-            ModelConfig::Gpt2(config) => config.num_hidden_layers(),
-            ModelConfig::GptJ(config) => config.num_hidden_layers(),
-            ModelConfig::GPTNeo(config) => config.num_hidden_layers(),
-            ModelConfig::Llama(config) => config.num_hidden_layers(),
-            ModelConfig::Opt(config) => config.num_hidden_layers(),
-            ModelConfig::T5(config) => config.num_hidden_layers(),
-        }
+        self.inner().num_hidden_layers()
+    }
+    fn is_encoder_decoder(&self) -> bool {
+        self.inner().is_encoder_decoder()
+    }
+    fn num_encoder_layers(&self) -> i32 {
+        self.inner().num_encoder_layers()
+    }
+    fn num_decoder_layers(&self) -> i32 {
+        self.inner().num_decoder_layers()
+    }
+    fn model_family(&self) -> ModelFamily {
+        self.inner().model_family()
+    }
+    fn num_key_value_heads(&self) -> i32 {
+        self.inner().num_key_value_heads()
+    }
+    fn head_dim(&self) -> i32 {
+        self.inner().head_dim()
+    }
+    fn kv_dim(&self) -> i32 {
+        self.inner().kv_dim()
+    }
+    fn ffn_ratio(&self) -> f64 {
+        self.inner().ffn_ratio()
+    }
+    fn vocab_size(&self) -> i32 {
+        self.inner().vocab_size()
+    }
+    fn tie_word_embeddings(&self) -> bool {
+        self.inner().tie_word_embeddings()
+    }
+    fn sliding_window(&self) -> Option<i32> {
+        self.inner().sliding_window()
+    }
+    fn num_sliding_layers(&self) -> i32 {
+        self.inner().num_sliding_layers()
+    }
+    fn num_parameters(&self) -> i64 {
+        self.inner().num_parameters()
+    }
+    fn total_parameters(&self) -> i64 {
+        self.inner().total_parameters()
+    }
+    fn active_parameters_per_token(&self) -> i64 {
+        self.inner().active_parameters_per_token()
+    }
+    fn parameter_breakdown(&self) -> ParameterBreakdown {
+        self.inner().parameter_breakdown()
+    }
+    fn embedding_parameters(&self) -> i64 {
+        self.inner().embedding_parameters()
+    }
+    fn lm_head_parameters(&self) -> i64 {
+        self.inner().lm_head_parameters()
+    }
+    fn rope_params(&self) -> Option<&RopeParams> {
+        self.inner().rope_params()
+    }
+    fn effective_max_context(&self) -> i32 {
+        self.inner().effective_max_context()
+    }
+    fn dtype(&self) -> Dtype {
+        self.inner().dtype()
     }
     fn model_type(&self) -> &str {
-        match self {
-            ModelConfig::Bert(config) => config.model_type(),
-            ModelConfig::Bloom(config) => config.model_type(),
-            ModelConfig::Gpt2(config) => config.model_type(),
-            ModelConfig::GptJ(config) => config.model_type(),
-            ModelConfig::GPTNeo(config) => config.model_type(),
-            ModelConfig::Llama(config) => config.model_type(),
-            ModelConfig::Opt(config) => config.model_type(),
-            ModelConfig::T5(config) => config.model_type(),
-        }
+        self.inner().model_type()
     }
     fn available_libraries(&self) -> &[crate::ModelLibraries] {
-        match self {
-            ModelConfig::Bert(config) => config.available_libraries(),
-            ModelConfig::Bloom(config) => config.available_libraries(),
-            ModelConfig::Gpt2(config) => config.available_libraries(),
-            ModelConfig::GptJ(config) => config.available_libraries(),
-            ModelConfig::GPTNeo(config) => config.available_libraries(),
-            ModelConfig::Llama(config) => config.available_libraries(),
-            ModelConfig::Opt(config) => config.available_libraries(),
-            ModelConfig::T5(config) => config.available_libraries(),
-        }
+        self.inner().available_libraries()
     }
-    fn from_json(value: serde_json::Value) -> Result<Self, ModelError>
+    fn from_json(mut value: serde_json::Value) -> Result<Self, ModelError>
     where
         Self: Sized,
     {
-        let model_type = value["model_type"]
-            .as_str()
-            .ok_or(ModelError::MissingField("model_type".to_string()))?;
-        match model_type {
-            "bert" => Ok(ModelConfig::Bert(BertModelConfig::from_json(value)?)),
-            "bloom" => Ok(ModelConfig::Bloom(BloomModelConfig::from_json(value)?)),
-            "gpt2" => Ok(ModelConfig::Gpt2(GPT2ModelConfig::from_json(value)?)),
-            "gptj" => Ok(ModelConfig::GptJ(GPTJModelConfig::from_json(value)?)),
-            "gpt_neo" => Ok(ModelConfig::GPTNeo(GPTNeoModelConfig::from_json(value)?)),
-            "gpt_neox" => Ok(ModelConfig::GPTNeo(GPTNeoModelConfig::from_json(value)?)),
-            "llama" => Ok(ModelConfig::Llama(LlamaModelConfig::from_json(value)?)),
-            "opt" => Ok(ModelConfig::Opt(OPTModelConfig::from_json(value)?)),
-            "t5" => Ok(ModelConfig::T5(T5ModelConfig::from_json(value)?)),
-            _ => Err(ModelError::ModelNotImplemented(model_type.to_string())),
+        let declared_model_type = value["model_type"].as_str().map(|val| val.to_string());
+        if let Some(model_type) = &declared_model_type {
+            if let Some(model_config) = ModelConfig::from_model_type(model_type, value.clone()) {
+                return model_config.map_err(|err| err.with_model_type(model_type));
+            }
+        }
+
+        // Older or converted configs often omit or misname the model_type, the architectures
+        // still tell which model it is. The canonical model_type replaces the declared one so
+        // that the per-model defaults (e.g. the tied embeddings of GPT-NeoX) are applied.
+        if let Some(model_type) = model_type_from_architectures(&value) {
+            value["model_type"] = serde_json::Value::from(model_type);
+            if let Some(model_config) = ModelConfig::from_model_type(model_type, value.clone()) {
+                return model_config.map_err(|err| err.with_model_type(model_type));
+            }
         }
+
+        let model_type =
+            declared_model_type.ok_or(ModelError::MissingField("model_type".to_string()))?;
+        // Unknown multimodal models are built from their nested sub-configs
+        if CompositeModelConfig::has_sub_configs(&value) {
+            return CompositeModelConfig::from_json(value)
+                .map(ModelConfig::Composite)
+                .map_err(|err| err.with_model_type(&model_type));
+        }
+        // Unknown architectures still get rough estimates when their fields can be guessed
+        GenericModelConfig::from_json(value)
+            .map(ModelConfig::Generic)
+            .map_err(|_| ModelError::ModelNotImplemented(model_type))
     }
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_model_config_dispatch() {
+        let configs = vec![
+            (
+                json!({
+                    "model_type": "bert",
+                    "hidden_size": 768,
+                    "intermediate_size": 3072,
+                    "max_position_embeddings": 512,
+                    "num_attention_heads": 12,
+                    "num_hidden_layers": 12,
+                    "vocab_size": 30522
+                }),
+                (768, 3072, 512, 12, 12),
+            ),
+            (
+                json!({
+                    "model_type": "bloom",
+                    "hidden_size": 1024,
+                    "n_head": 16,
+                    "n_layer": 24,
+                    "vocab_size": 250880
+                }),
+                (1024, 4096, 0, 16, 24),
+            ),
+            (
+                json!({
+                    "model_type": "gpt2",
+                    "n_embd": 768,
+                    "n_inner": null,
+                    "n_positions": 1024,
+                    "n_head": 12,
+                    "n_layer": 12,
+                    "vocab_size": 50257
+                }),
+                (768, 3072, 1024, 12, 12),
+            ),
+            (
+                json!({
+                    "model_type": "gptj",
+                    "n_embd": 4096,
+                    "n_inner": null,
+                    "n_positions": 2048,
+                    "n_head": 16,
+                    "n_layer": 28,
+                    "vocab_size": 50400
+                }),
+                (4096, 16384, 2048, 16, 28),
+            ),
+            (
+                json!({
+                    "model_type": "gpt_neo",
+                    "hidden_size": 768,
+                    "intermediate_size": null,
+                    "max_position_embeddings": 2048,
+                    "num_heads": 12,
+                    "num_layers": 12,
+                    "vocab_size": 50257
+                }),
+                (768, 3072, 2048, 12, 12),
+            ),
+            (
+                json!({
+                    "model_type": "gpt_neox",
+                    "hidden_size": 2560,
+                    "intermediate_size": 10240,
+                    "max_position_embeddings": 2048,
+                    "num_attention_heads": 32,
+                    "num_hidden_layers": 32,
+                    "vocab_size": 50432
+                }),
+                (2560, 10240, 2048, 32, 32),
+            ),
+            (
+                json!({
+                    "model_type": "llama",
+                    "hidden_size": 4096,
+                    "intermediate_size": 11008,
+                    "max_position_embeddings": 4096,
+                    "num_attention_heads": 32,
+                    "num_hidden_layers": 32,
+                    "vocab_size": 32000
+                }),
+                (4096, 11008, 4096, 32, 32),
+            ),
+            (
+                json!({
+                    "model_type": "opt",
+                    "hidden_size": 768,
+                    "ffn_dim": 3072,
+                    "max_position_embeddings": 2048,
+                    "num_attention_heads": 12,
+                    "num_hidden_layers": 12,
+                    "vocab_size": 50272
+                }),
+                (768, 3072, 2048, 12, 12),
+            ),
+            (
+                json!({
+                    "model_type": "t5",
+                    "d_model": 512,
+                    "d_ff": 2048,
+                    "num_heads": 8,
+                    "num_layers": 6,
+                    "vocab_size": 32128
+                }),
+                (512, 2048, 512, 8, 12),
+            ),
+        ];
+        for (value, expected) in configs {
+            let model_type = value["model_type"].as_str().unwrap().to_string();
+            let model_config = ModelConfig::from_json(value).unwrap();
+            assert!(!model_config.is_low_confidence(), "{}", model_type);
+            assert_eq!(model_config.model_type(), model_type);
+            assert_eq!(
+                (
+                    model_config.hidden_size(),
+                    model_config.intermediate_size(),
+                    model_config.max_position_embeddings(),
+                    model_config.num_attention_heads(),
+                    model_config.num_hidden_layers(),
+                ),
+                expected,
+                "{}",
+                model_type
+            );
+        }
+    }
+
+    #[test]
+    fn test_model_config_architectures_fallback() {
+        let value = json!({
+            "architectures": ["LlamaForCausalLM"],
+            "hidden_size": 4096,
+            "intermediate_size": 11008,
+            "max_position_embeddings": 4096,
+            "num_attention_heads": 32,
+            "num_hidden_layers": 32,
+            "vocab_size": 32000
+        });
+        let model_config = ModelConfig::from_json(value).unwrap();
+        assert!(matches!(model_config, ModelConfig::Llama(_)));
+        assert_eq!(model_config.model_type(), "llama");
+
+        // Misnamed model_type, normalized from the architectures before the defaults apply
+        let value = json!({
+            "architectures": ["GPTNeoXForCausalLM"],
+            "model_type": "pythia",
+            "hidden_size": 512,
+            "intermediate_size": 2048,
+            "max_position_embeddings": 2048,
+            "num_attention_heads": 8,
+            "num_hidden_layers": 6,
+            "vocab_size": 50304
+        });
+        let model_config = ModelConfig::from_json(value).unwrap();
+        assert!(!model_config.is_low_confidence());
+        assert_eq!(model_config.num_hidden_layers(), 6);
+        assert_eq!(model_config.model_type(), "gpt_neox");
+        assert!(!model_config.tie_word_embeddings());
+
+        assert_eq!(
+            model_type_from_architectures(&json!({"architectures": ["GPTNeoForCausalLM"]})),
+            Some("gpt_neo")
+        );
+        assert_eq!(
+            model_type_from_architectures(&json!({"architectures": ["DeepseekForCausalLM"]})),
+            Some("deepseek")
+        );
+        assert_eq!(
+            model_type_from_architectures(&json!({"architectures": ["DeepseekV2ForCausalLM"]})),
+            Some("deepseek_v2")
+        );
+        assert_eq!(
+            model_type_from_architectures(&json!({"architectures": ["MistralForCausalLM"]})),
+            None
+        );
+        assert!(matches!(
+            ModelConfig::from_json(json!({"architectures": ["MistralForCausalLM"]})),
+            Err(ModelError::MissingField(_))
+        ));
+    }
+
+    #[test]
+    fn test_model_config_generic_fallback() {
+        let value = json!({
+            "model_type": "novel",
+            "d_model": 1024,
+            "num_layers": 24
+        });
+        let model_config = ModelConfig::from_json(value).unwrap();
+        assert!(model_config.is_low_confidence());
+        assert_eq!(model_config.hidden_size(), 1024);
+        assert_eq!(model_config.intermediate_size(), 4096);
+        assert_eq!(model_config.num_attention_heads(), 16);
+        assert_eq!(model_config.num_hidden_layers(), 24);
+        assert_eq!(model_config.model_type(), "novel");
+
+        // Mistral 7B is not supported yet but its sliding window is still picked up
+        let value = json!({
+            "model_type": "mistral",
+            "hidden_size": 4096,
+            "intermediate_size": 14336,
+            "max_position_embeddings": 32768,
+            "num_attention_heads": 32,
+            "num_hidden_layers": 32,
+            "sliding_window": 4096,
+            "vocab_size": 32000
+        });
+        let model_config = ModelConfig::from_json(value).unwrap();
+        assert!(model_config.is_low_confidence());
+        assert_eq!(model_config.sliding_window(), Some(4096));
+
+        let value = json!({
+            "model_type": "qwen2",
+            "hidden_size": 3584,
+            "num_hidden_layers": 28,
+            "sliding_window": 131072,
+            "use_sliding_window": false
+        });
+        assert_eq!(
+            ModelConfig::from_json(value).unwrap().sliding_window(),
+            None
+        );
+
+        let value = json!({"model_type": "novel"});
+        assert!(matches!(
+            ModelConfig::from_json(value),
+            Err(ModelError::ModelNotImplemented(_))
+        ));
+    }
+    #[test]
+    fn test_model_config_from_file() {
+        let value = json!({
+            "model_type": "bert",
+            "hidden_size": 768,
+            "intermediate_size": 3072,
+            "max_position_embeddings": 512,
+            "num_attention_heads": 12,
+            "num_hidden_layers": 12,
+            "vocab_size": 30522
+        });
+        let model_config = ModelConfig::from_reader(value.to_string().as_bytes()).unwrap();
+        assert!(matches!(model_config, ModelConfig::Bert(_)));
+
+        let path = std::env::temp_dir().join(format!("aiha-config-{}.json", std::process::id()));
+        std::fs::write(&path, value.to_string()).unwrap();
+        let model_config = ModelConfig::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(model_config.hidden_size(), 768);
+        assert_eq!(model_config.model_type(), "bert");
+
+        assert!(matches!(
+            ModelConfig::from_file(&path),
+            Err(ModelError::Io(_))
+        ));
+        assert!(matches!(
+            ModelConfig::from_reader("{".as_bytes()),
+            Err(ModelError::Json(_))
+        ));
+    }
+}
@@ -0,0 +1,188 @@
+//! Dataset size metadata struct
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Average number of bytes of text per token, used to approximate token counts
+pub const DEFAULT_BYTES_PER_TOKEN: f64 = 4.0;
+
+/// Struct for storing the size of a split of a dataset
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct DatasetSplit {
+    /// The config (subset) of the split
+    pub config: String,
+    /// The name of the split (e.g. `train`)
+    pub split: String,
+    /// The number of rows of the split
+    pub num_rows: Option<i64>,
+    /// The size of the split parquet files in bytes
+    pub num_bytes_parquet_files: Option<i64>,
+    /// The size of the split once loaded in memory in bytes
+    pub num_bytes_memory: Option<i64>,
+}
+
+/// Implement the creation of the DatasetSplit struct from a serde_json::Value
+impl From<&Value> for DatasetSplit {
+    fn from(value: &Value) -> Self {
+        DatasetSplit {
+            config: value["config"].as_str().unwrap_or_default().to_string(),
+            split: value["split"].as_str().unwrap_or_default().to_string(),
+            num_rows: value["num_rows"].as_i64(),
+            num_bytes_parquet_files: value["num_bytes_parquet_files"].as_i64(),
+            num_bytes_memory: value["num_bytes_memory"].as_i64(),
+        }
+    }
+}
+
+/// Struct for storing the size of a dataset, as reported by the datasets-server API
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct DatasetInfo {
+    /// The dataset ID of the repository (e.g. `username/dataset_name`)
+    pub dataset_id: String,
+    /// The total number of rows of the dataset
+    pub num_rows: Option<i64>,
+    /// The size of the original files of the dataset in bytes
+    pub num_bytes_original_files: Option<i64>,
+    /// The size of the dataset parquet files in bytes
+    pub num_bytes_parquet_files: Option<i64>,
+    /// The size of the dataset once loaded in memory in bytes
+    pub num_bytes_memory: Option<i64>,
+    /// The size of every split of the dataset
+    pub splits: Vec<DatasetSplit>,
+    /// Whether the sizes only cover a part of the dataset (too big to be fully processed)
+    pub partial: bool,
+}
+
+/// Implement the `DatasetInfo` struct
+impl DatasetInfo {
+    /// Get the size of a split, summed over all the dataset configs
+    pub fn get_split(&self, split: &str) -> Option<DatasetSplit> {
+        self.splits
+            .iter()
+            .filter(|s| s.split == split)
+            .cloned()
+            .reduce(|a, b| DatasetSplit {
+                config: a.config,
+                split: a.split,
+                num_rows: a.num_rows.zip(b.num_rows).map(|(a, b)| a + b),
+                num_bytes_parquet_files: a
+                    .num_bytes_parquet_files
+                    .zip(b.num_bytes_parquet_files)
+                    .map(|(a, b)| a + b),
+                num_bytes_memory: a
+                    .num_bytes_memory
+                    .zip(b.num_bytes_memory)
+                    .map(|(a, b)| a + b),
+            })
+    }
+    /// Approximate the number of tokens of the dataset from its in-memory size.
+    ///
+    /// `bytes_per_token` defaults to `DEFAULT_BYTES_PER_TOKEN`, which holds for English text
+    /// with a BPE tokenizer. It's a rough estimate, non-text columns are counted as text too.
+    pub fn estimated_tokens(&self, bytes_per_token: Option<f64>) -> Option<i64> {
+        let bytes_per_token = bytes_per_token.unwrap_or(DEFAULT_BYTES_PER_TOKEN);
+        self.num_bytes_memory
+            .map(|bytes| (bytes as f64 / bytes_per_token).round() as i64)
+    }
+    /// Create a new DatasetInfo struct from a `/size` datasets-server API response
+    pub fn from_json(dataset_id: &str, value: Value) -> Self {
+        let size = &value["size"];
+        let dataset = &size["dataset"];
+        DatasetInfo {
+            dataset_id: dataset_id.to_string(),
+            num_rows: dataset["num_rows"].as_i64(),
+            num_bytes_original_files: dataset["num_bytes_original_files"].as_i64(),
+            num_bytes_parquet_files: dataset["num_bytes_parquet_files"].as_i64(),
+            num_bytes_memory: dataset["num_bytes_memory"].as_i64(),
+            splits: size["splits"]
+                .as_array()
+                .map(|splits| splits.iter().map(DatasetSplit::from).collect())
+                .unwrap_or_default(),
+            partial: value["partial"].as_bool().unwrap_or(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn create_size_response() -> Value {
+        json!({
+            "size": {
+                "dataset": {
+                    "dataset": "user/dataset",
+                    "num_bytes_original_files": 1000,
+                    "num_bytes_parquet_files": 800,
+                    "num_bytes_memory": 4000,
+                    "num_rows": 120
+                },
+                "configs": [],
+                "splits": [
+                    {
+                        "dataset": "user/dataset",
+                        "config": "en",
+                        "split": "train",
+                        "num_bytes_parquet_files": 500,
+                        "num_bytes_memory": 2500,
+                        "num_rows": 80,
+                        "num_columns": 2
+                    },
+                    {
+                        "dataset": "user/dataset",
+                        "config": "fr",
+                        "split": "train",
+                        "num_bytes_parquet_files": 200,
+                        "num_bytes_memory": 1000,
+                        "num_rows": 30,
+                        "num_columns": 2
+                    },
+                    {
+                        "dataset": "user/dataset",
+                        "config": "en",
+                        "split": "test",
+                        "num_bytes_parquet_files": 100,
+                        "num_bytes_memory": 500,
+                        "num_rows": 10,
+                        "num_columns": 2
+                    }
+                ]
+            },
+            "pending": [],
+            "failed": [],
+            "partial": false
+        })
+    }
+
+    #[test]
+    fn test_dataset_info_from_json() {
+        let dataset_info = DatasetInfo::from_json("user/dataset", create_size_response());
+        assert_eq!(dataset_info.dataset_id, "user/dataset");
+        assert_eq!(dataset_info.num_rows, Some(120));
+        assert_eq!(dataset_info.num_bytes_original_files, Some(1000));
+        assert_eq!(dataset_info.num_bytes_parquet_files, Some(800));
+        assert_eq!(dataset_info.num_bytes_memory, Some(4000));
+        assert_eq!(dataset_info.splits.len(), 3);
+        assert!(!dataset_info.partial);
+    }
+
+    #[test]
+    fn test_dataset_info_get_split() {
+        let dataset_info = DatasetInfo::from_json("user/dataset", create_size_response());
+        let train = dataset_info.get_split("train").unwrap();
+        assert_eq!(train.num_rows, Some(110));
+        assert_eq!(train.num_bytes_parquet_files, Some(700));
+        assert_eq!(train.num_bytes_memory, Some(3500));
+        assert_eq!(dataset_info.get_split("validation"), None);
+    }
+
+    #[test]
+    fn test_dataset_info_estimated_tokens() {
+        let dataset_info = DatasetInfo::from_json("user/dataset", create_size_response());
+        assert_eq!(dataset_info.estimated_tokens(None), Some(1000));
+        assert_eq!(dataset_info.estimated_tokens(Some(2.0)), Some(2000));
+        let dataset_info = DatasetInfo::from_json("user/dataset", json!({}));
+        assert_eq!(dataset_info.estimated_tokens(None), None);
+    }
+}
@@ -0,0 +1,65 @@
+//! Hub error enum
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Hub error
+#[derive(Debug, PartialEq)]
+pub enum HubError {
+    /// The repository doesn't exist on the Hub
+    NotFound(String),
+    /// The repository exists but the token doesn't grant access to it
+    Unauthorized(String),
+    /// The file doesn't exist in the repository
+    FileNotFound(String, String),
+    /// The revision doesn't exist in the repository
+    RevisionNotFound(String, String),
+    /// The Hub answered with an unexpected status code
+    UnexpectedStatus(String, u16),
+}
+
+impl Display for HubError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            HubError::NotFound(repo_id) => write!(f, "Repository not found: {}", repo_id),
+            HubError::Unauthorized(repo_id) => write!(
+                f,
+                "Unauthorized access to repository: {}.\
+                \nMake sure your token has access to it (gated or private repository).",
+                repo_id
+            ),
+            HubError::FileNotFound(repo_id, filename) => {
+                write!(f, "File not found: {} in repository: {}", filename, repo_id)
+            }
+            HubError::RevisionNotFound(repo_id, revision) => write!(
+                f,
+                "Revision not found: {} in repository: {}",
+                revision, repo_id
+            ),
+            HubError::UnexpectedStatus(repo_id, status) => write!(
+                f,
+                "Unexpected status code {} for repository: {}",
+                status, repo_id
+            ),
+        }
+    }
+}
+
+impl Error for HubError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_hub_error_display() {
+        assert_eq!(
+            HubError::NotFound("user/repo".to_string()).to_string(),
+            "Repository not found: user/repo"
+        );
+        assert_eq!(
+            HubError::UnexpectedStatus("user/repo".to_string(), 500).to_string(),
+            "Unexpected status code 500 for repository: user/repo"
+        );
+    }
+}
@@ -0,0 +1,518 @@
+//! Module for reading the header of a GGUF file (the llama.cpp format): its metadata and the
+//! description of its tensors, without reading the weights.
+//!
+//! A GGUF file starts with the `GGUF` magic, its version, the number of tensors and of metadata
+//! key-value pairs, all little-endian, followed by the typed metadata values and the name,
+//! shape, type and offset of every tensor.
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+use crate::hub::ModelConfig;
+use crate::models::{ModelConfigTrait, ModelError, QuantType};
+
+/// The magic number every GGUF file starts with (`GGUF` in little-endian)
+const GGUF_MAGIC: u32 = 0x4655_4747;
+
+/// Enumerate the values of the metadata of a GGUF file
+#[derive(Clone, Debug, PartialEq)]
+pub enum GgufValue {
+    /// An unsigned integer (`uint8` to `uint64`)
+    UInt(u64),
+    /// A signed integer (`int8` to `int64`)
+    Int(i64),
+    /// A floating point number (`float32` or `float64`)
+    Float(f64),
+    /// A boolean
+    Bool(bool),
+    /// A UTF-8 string
+    String(String),
+    /// An array of values of the same type
+    Array(Vec<GgufValue>),
+}
+
+/// GgufValue implementation
+impl GgufValue {
+    /// Returns the value as an integer, `None` when it is not an integer
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            GgufValue::UInt(value) => i64::try_from(*value).ok(),
+            GgufValue::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+    /// Returns the value as a string, `None` when it is not a string
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            GgufValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+    /// Returns the JSON value of the value
+    pub fn to_json(&self) -> Value {
+        match self {
+            GgufValue::UInt(value) => json!(value),
+            GgufValue::Int(value) => json!(value),
+            GgufValue::Float(value) => json!(value),
+            GgufValue::Bool(value) => json!(value),
+            GgufValue::String(value) => json!(value),
+            GgufValue::Array(values) => Value::Array(values.iter().map(|v| v.to_json()).collect()),
+        }
+    }
+}
+
+/// A struct representing a tensor of a GGUF file
+#[derive(Clone, Debug, PartialEq)]
+pub struct GgufTensor {
+    /// The name of the tensor
+    name: String,
+    /// The dimensions of the tensor, the fastest varying first
+    dims: Vec<u64>,
+    /// The `ggml_type` of the tensor
+    ggml_type: u32,
+}
+
+/// GgufTensor implementation
+impl GgufTensor {
+    /// Build a new `GgufTensor` struct based on the provided parameters
+    pub fn new(name: String, dims: Vec<u64>, ggml_type: u32) -> GgufTensor {
+        GgufTensor {
+            name,
+            dims,
+            ggml_type,
+        }
+    }
+    /// Returns the name of the tensor
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Returns the dimensions of the tensor
+    pub fn dims(&self) -> &[u64] {
+        &self.dims
+    }
+    /// Returns the `ggml_type` of the tensor
+    pub fn ggml_type(&self) -> u32 {
+        self.ggml_type
+    }
+    /// Returns the number of elements of the tensor
+    pub fn num_elements(&self) -> u64 {
+        self.dims.iter().product()
+    }
+    /// Returns the size of the tensor data in bytes, `None` for an unknown `ggml_type`
+    pub fn size(&self) -> Option<u64> {
+        let (block_size, type_size) = ggml_type_size(self.ggml_type)?;
+        Some(self.num_elements().div_ceil(block_size) * type_size)
+    }
+}
+
+/// A struct representing the header of a GGUF file
+#[derive(Clone, Debug, PartialEq)]
+pub struct GgufFile {
+    /// The version of the GGUF format
+    version: u32,
+    /// The metadata of the file, keyed by name (e.g. `llama.block_count`)
+    metadata: BTreeMap<String, GgufValue>,
+    /// The tensors of the file
+    tensors: Vec<GgufTensor>,
+}
+
+/// GgufFile implementation
+impl GgufFile {
+    /// Build a new `GgufFile` struct based on the provided parameters
+    pub fn new(
+        version: u32,
+        metadata: BTreeMap<String, GgufValue>,
+        tensors: Vec<GgufTensor>,
+    ) -> GgufFile {
+        GgufFile {
+            version,
+            metadata,
+            tensors,
+        }
+    }
+    /// Read the header of a local GGUF file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<GgufFile, ModelError> {
+        let file = File::open(path)?;
+        GgufFile::from_reader(BufReader::new(file))
+    }
+    /// Read the header of a GGUF file from a reader over its content, the reader being left at
+    /// the start of the tensor data. Only the versions 2 and 3 of the format are supported.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<GgufFile, ModelError> {
+        let reader = &mut reader;
+        if read_u32(reader)? != GGUF_MAGIC {
+            return Err(invalid_data("not a GGUF file").into());
+        }
+        let version = read_u32(reader)?;
+        if !(2..=3).contains(&version) {
+            return Err(invalid_data(&format!("unsupported GGUF version {}", version)).into());
+        }
+        let tensor_count = read_u64(reader)?;
+        let metadata_count = read_u64(reader)?;
+
+        let mut metadata = BTreeMap::new();
+        for _ in 0..metadata_count {
+            let key = read_string(reader)?;
+            let value_type = read_u32(reader)?;
+            metadata.insert(key, read_value(reader, value_type)?);
+        }
+        let mut tensors = Vec::new();
+        for _ in 0..tensor_count {
+            let name = read_string(reader)?;
+            let n_dims = read_u32(reader)?;
+            let dims = (0..n_dims)
+                .map(|_| read_u64(reader))
+                .collect::<io::Result<Vec<u64>>>()?;
+            let ggml_type = read_u32(reader)?;
+            // The offset of the tensor in the data section
+            read_u64(reader)?;
+            tensors.push(GgufTensor::new(name, dims, ggml_type));
+        }
+        Ok(GgufFile::new(version, metadata, tensors))
+    }
+    /// Returns the version of the GGUF format
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+    /// Returns the metadata of the file
+    pub fn metadata(&self) -> &BTreeMap<String, GgufValue> {
+        &self.metadata
+    }
+    /// Returns the tensors of the file
+    pub fn tensors(&self) -> &[GgufTensor] {
+        &self.tensors
+    }
+    /// Returns the metadata value of a key
+    pub fn get(&self, key: &str) -> Option<&GgufValue> {
+        self.metadata.get(key)
+    }
+    /// Returns the architecture of the model (`general.architecture`)
+    pub fn architecture(&self) -> Option<&str> {
+        self.get("general.architecture")?.as_str()
+    }
+    /// Returns the number of parameters of the model
+    pub fn parameters(&self) -> u64 {
+        self.tensors.iter().map(GgufTensor::num_elements).sum()
+    }
+    /// Returns the size of the tensor data in bytes, the tensors of an unknown type left out
+    pub fn weights_size(&self) -> u64 {
+        self.tensors.iter().filter_map(GgufTensor::size).sum()
+    }
+    /// Returns the quantization of the model: its `general.file_type`, or the type holding most
+    /// of the weights when the file type is missing or unknown
+    pub fn quant_type(&self) -> Option<QuantType> {
+        let file_type = self.get("general.file_type").and_then(GgufValue::as_i64);
+        if let Some(quant_type) = file_type.and_then(quant_type_of_file_type) {
+            return Some(quant_type);
+        }
+        let mut elements: BTreeMap<u32, u64> = BTreeMap::new();
+        for tensor in &self.tensors {
+            *elements.entry(tensor.ggml_type).or_default() += tensor.num_elements();
+        }
+        elements
+            .into_iter()
+            .filter_map(|(ggml_type, count)| Some((quant_type_of_ggml_type(ggml_type)?, count)))
+            .max_by_key(|(_, count)| *count)
+            .map(|(quant_type, _)| quant_type)
+    }
+    /// Returns the `config.json` of the model rebuilt from the `{architecture}.*` metadata, the
+    /// vocabulary size counted from the tokenizer when it isn't declared
+    pub fn to_config_json(&self) -> Value {
+        let architecture = self.architecture().unwrap_or_default();
+        // llama.cpp converts the Mistral models to the llama architecture
+        let model_type = match architecture {
+            "mistral" => "llama",
+            architecture => architecture,
+        };
+        let mut config = json!({
+            "model_type": model_type,
+            "torch_dtype": "float16",
+            // The output projection is missing when it shares the embeddings
+            "tie_word_embeddings": !self.tensors.iter().any(|t| t.name == "output.weight"),
+        });
+        for (field, key) in [
+            ("hidden_size", "embedding_length"),
+            ("intermediate_size", "feed_forward_length"),
+            ("num_hidden_layers", "block_count"),
+            ("num_attention_heads", "attention.head_count"),
+            ("num_key_value_heads", "attention.head_count_kv"),
+            ("max_position_embeddings", "context_length"),
+            ("head_dim", "attention.key_length"),
+            ("rope_theta", "rope.freq_base"),
+            ("vocab_size", "vocab_size"),
+        ] {
+            if let Some(value) = self.get(&format!("{}.{}", architecture, key)) {
+                config[field] = value.to_json();
+            }
+        }
+        if config["vocab_size"].is_null() {
+            if let Some(GgufValue::Array(tokens)) = self.get("tokenizer.ggml.tokens") {
+                config["vocab_size"] = json!(tokens.len());
+            }
+        }
+        config
+    }
+    /// Build the model config of the file from its metadata
+    pub fn model_config(&self) -> Result<ModelConfig, ModelError> {
+        ModelConfig::from_json(self.to_config_json())
+    }
+}
+
+/// Returns the quantization of a `general.file_type` (`llama_ftype`), `None` when unknown
+fn quant_type_of_file_type(file_type: i64) -> Option<QuantType> {
+    match file_type {
+        0 => Some(QuantType::F32),
+        1 => Some(QuantType::F16),
+        2 => Some(QuantType::Q4_0),
+        3 => Some(QuantType::Q4_1),
+        7 => Some(QuantType::Q8_0),
+        8 => Some(QuantType::Q5_0),
+        9 => Some(QuantType::Q5_1),
+        10 => Some(QuantType::Q2_K),
+        11..=13 => Some(QuantType::Q3_K),
+        14 | 15 => Some(QuantType::Q4_K),
+        16 | 17 => Some(QuantType::Q5_K),
+        18 => Some(QuantType::Q6_K),
+        _ => None,
+    }
+}
+
+/// Returns the quantization of a tensor `ggml_type`, `None` when unknown
+fn quant_type_of_ggml_type(ggml_type: u32) -> Option<QuantType> {
+    match ggml_type {
+        0 => Some(QuantType::F32),
+        1 => Some(QuantType::F16),
+        2 => Some(QuantType::Q4_0),
+        3 => Some(QuantType::Q4_1),
+        6 => Some(QuantType::Q5_0),
+        7 => Some(QuantType::Q5_1),
+        8 => Some(QuantType::Q8_0),
+        10 => Some(QuantType::Q2_K),
+        11 => Some(QuantType::Q3_K),
+        12 => Some(QuantType::Q4_K),
+        13 => Some(QuantType::Q5_K),
+        14 => Some(QuantType::Q6_K),
+        _ => None,
+    }
+}
+
+/// Returns the number of weights of a block and the bytes of a block of a `ggml_type`, `None`
+/// when unknown
+fn ggml_type_size(ggml_type: u32) -> Option<(u64, u64)> {
+    match ggml_type {
+        0 => Some((1, 4)),      // F32
+        1 => Some((1, 2)),      // F16
+        2 => Some((32, 18)),    // Q4_0
+        3 => Some((32, 20)),    // Q4_1
+        6 => Some((32, 22)),    // Q5_0
+        7 => Some((32, 24)),    // Q5_1
+        8 => Some((32, 34)),    // Q8_0
+        9 => Some((32, 36)),    // Q8_1
+        10 => Some((256, 84)),  // Q2_K
+        11 => Some((256, 110)), // Q3_K
+        12 => Some((256, 144)), // Q4_K
+        13 => Some((256, 176)), // Q5_K
+        14 => Some((256, 210)), // Q6_K
+        15 => Some((256, 292)), // Q8_K
+        16 => Some((256, 66)),  // IQ2_XXS
+        17 => Some((256, 74)),  // IQ2_XS
+        18 => Some((256, 98)),  // IQ3_XXS
+        19 => Some((256, 50)),  // IQ1_S
+        20 => Some((32, 18)),   // IQ4_NL
+        21 => Some((256, 110)), // IQ3_S
+        22 => Some((256, 82)),  // IQ2_S
+        23 => Some((256, 136)), // IQ4_XS
+        24 => Some((1, 1)),     // I8
+        25 => Some((1, 2)),     // I16
+        26 => Some((1, 4)),     // I32
+        27 => Some((1, 8)),     // I64
+        28 => Some((1, 8)),     // F64
+        29 => Some((256, 56)),  // IQ1_M
+        30 => Some((1, 2)),     // BF16
+        _ => None,
+    }
+}
+
+/// Returns an error for a malformed GGUF file
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Read a little-endian `u32`
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    Ok(u32::from_le_bytes(read_bytes(reader)?))
+}
+
+/// Read a little-endian `u64`
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    Ok(u64::from_le_bytes(read_bytes(reader)?))
+}
+
+/// Read a string: its length as a `u64` then its UTF-8 bytes
+fn read_string<R: Read>(reader: &mut R) -> io::Result<String> {
+    let len = read_u64(reader)?;
+    // A corrupted length must not allocate more than the bytes left in the file
+    let mut bytes = Vec::new();
+    reader.take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    String::from_utf8(bytes).map_err(|_| invalid_data("invalid UTF-8 string"))
+}
+
+/// Read the little-endian bytes of a number
+fn read_bytes<R: Read, const N: usize>(reader: &mut R) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Read a metadata value of a `gguf_metadata_value_type`
+fn read_value<R: Read>(reader: &mut R, value_type: u32) -> io::Result<GgufValue> {
+    let value = match value_type {
+        0 => GgufValue::UInt(u8::from_le_bytes(read_bytes(reader)?) as u64),
+        1 => GgufValue::Int(i8::from_le_bytes(read_bytes(reader)?) as i64),
+        2 => GgufValue::UInt(u16::from_le_bytes(read_bytes(reader)?) as u64),
+        3 => GgufValue::Int(i16::from_le_bytes(read_bytes(reader)?) as i64),
+        4 => GgufValue::UInt(u32::from_le_bytes(read_bytes(reader)?) as u64),
+        5 => GgufValue::Int(i32::from_le_bytes(read_bytes(reader)?) as i64),
+        6 => GgufValue::Float(f32::from_le_bytes(read_bytes(reader)?) as f64),
+        7 => GgufValue::Bool(u8::from_le_bytes(read_bytes(reader)?) != 0),
+        8 => GgufValue::String(read_string(reader)?),
+        9 => {
+            let item_type = read_u32(reader)?;
+            let len = read_u64(reader)?;
+            let mut items = Vec::new();
+            for _ in 0..len {
+                items.push(read_value(reader, item_type)?);
+            }
+            GgufValue::Array(items)
+        }
+        10 => GgufValue::UInt(u64::from_le_bytes(read_bytes(reader)?)),
+        11 => GgufValue::Int(i64::from_le_bytes(read_bytes(reader)?)),
+        12 => GgufValue::Float(f64::from_le_bytes(read_bytes(reader)?)),
+        value_type => {
+            return Err(invalid_data(&format!(
+                "unknown GGUF metadata value type {}",
+                value_type
+            )))
+        }
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// Append a GGUF string
+    fn push_string(bytes: &mut Vec<u8>, value: &str) {
+        bytes.extend((value.len() as u64).to_le_bytes());
+        bytes.extend(value.as_bytes());
+    }
+
+    /// Returns the header of a Llama 3 8B GGUF file in Q4_K_M, with only its embeddings and
+    /// output tensors
+    pub(crate) fn create_gguf_bytes() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend(GGUF_MAGIC.to_le_bytes());
+        bytes.extend(3u32.to_le_bytes());
+        bytes.extend(2u64.to_le_bytes());
+        bytes.extend(11u64.to_le_bytes());
+
+        push_string(&mut bytes, "general.architecture");
+        bytes.extend(8u32.to_le_bytes());
+        push_string(&mut bytes, "llama");
+        for (key, value) in [
+            ("llama.embedding_length", 4096u32),
+            ("llama.feed_forward_length", 14336),
+            ("llama.block_count", 32),
+            ("llama.attention.head_count", 32),
+            ("llama.attention.head_count_kv", 8),
+            ("llama.context_length", 8192),
+            ("general.file_type", 15),
+        ] {
+            push_string(&mut bytes, key);
+            bytes.extend(4u32.to_le_bytes());
+            bytes.extend(value.to_le_bytes());
+        }
+        push_string(&mut bytes, "llama.rope.freq_base");
+        bytes.extend(6u32.to_le_bytes());
+        bytes.extend(500000f32.to_le_bytes());
+        push_string(&mut bytes, "general.name");
+        bytes.extend(8u32.to_le_bytes());
+        push_string(&mut bytes, "Meta Llama 3 8B");
+        push_string(&mut bytes, "tokenizer.ggml.tokens");
+        bytes.extend(9u32.to_le_bytes());
+        bytes.extend(8u32.to_le_bytes());
+        bytes.extend(128256u64.to_le_bytes());
+        for _ in 0..128256 {
+            push_string(&mut bytes, "");
+        }
+
+        for (name, ggml_type) in [("token_embd.weight", 12u32), ("output.weight", 14)] {
+            push_string(&mut bytes, name);
+            bytes.extend(2u32.to_le_bytes());
+            bytes.extend(4096u64.to_le_bytes());
+            bytes.extend(128256u64.to_le_bytes());
+            bytes.extend(ggml_type.to_le_bytes());
+            bytes.extend(0u64.to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_gguf_header() {
+        let gguf = GgufFile::from_reader(create_gguf_bytes().as_slice()).unwrap();
+        assert_eq!(gguf.version(), 3);
+        assert_eq!(gguf.architecture(), Some("llama"));
+        assert_eq!(
+            gguf.get("general.name"),
+            Some(&GgufValue::String("Meta Llama 3 8B".to_string()))
+        );
+        assert_eq!(gguf.tensors().len(), 2);
+        assert_eq!(gguf.tensors()[0].dims(), &[4096, 128256]);
+        assert_eq!(gguf.parameters(), 2 * 4096 * 128256);
+        // Q4_K and Q6_K blocks of 256 weights
+        assert_eq!(
+            gguf.weights_size(),
+            4096 * 128256 / 256 * 144 + 4096 * 128256 / 256 * 210
+        );
+        // Q4_K_M
+        assert_eq!(gguf.quant_type(), Some(QuantType::Q4_K));
+    }
+
+    #[test]
+    fn test_gguf_model_config() {
+        let gguf = GgufFile::from_reader(create_gguf_bytes().as_slice()).unwrap();
+        let config = gguf.to_config_json();
+        assert_eq!(config["vocab_size"], 128256);
+        assert_eq!(config["rope_theta"], 500000.0);
+        assert_eq!(config["tie_word_embeddings"], false);
+
+        let model_config = gguf.model_config().unwrap();
+        assert!(matches!(model_config, ModelConfig::Llama(_)));
+        assert_eq!(model_config.num_key_value_heads(), 8);
+        assert_eq!(model_config.num_hidden_layers(), 32);
+    }
+
+    #[test]
+    fn test_gguf_invalid_header() {
+        assert!(matches!(
+            GgufFile::from_reader(&b"{\"model_type\": \"llama\"}"[..]),
+            Err(ModelError::Io(_))
+        ));
+        // A truncated header
+        let bytes = create_gguf_bytes();
+        assert!(GgufFile::from_reader(&bytes[..64]).is_err());
+
+        // Without the file type, the quantization is the type of most of the weights
+        let mut gguf = GgufFile::from_reader(bytes.as_slice()).unwrap();
+        gguf.metadata.remove("general.file_type");
+        gguf.tensors[1].ggml_type = 12;
+        assert_eq!(gguf.quant_type(), Some(QuantType::Q4_K));
+    }
+}
@@ -0,0 +1,381 @@
+//! Module for reading the local Hugging Face Hub cache (`~/.cache/huggingface/hub`).
+//!
+//! The cache stores every repository in a `{kind}s--{namespace}--{name}` folder holding the
+//! deduplicated files in `blobs/`, one folder per downloaded commit in `snapshots/` and the
+//! branch or tag to commit mapping in `refs/`. Everything here works fully offline.
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::hub::{ModelFile, RepoKind, Siblings};
+
+/// Struct for storing a file of a cached revision
+#[derive(Clone, Debug, PartialEq)]
+pub struct CachedFile {
+    /// The filename of the file, relative to the repository root
+    pub rfilename: String,
+    /// The size of the file in bytes
+    pub size: u64,
+}
+
+/// Struct for storing a revision (snapshot) of a cached repository
+#[derive(Clone, Debug, PartialEq)]
+pub struct CachedRevision {
+    /// The commit hash of the revision
+    pub commit_hash: String,
+    /// The refs (branches, tags or pull requests) pointing to the revision
+    pub refs: Vec<String>,
+    /// The path of the snapshot folder
+    pub snapshot_path: PathBuf,
+    /// The files of the revision
+    pub files: Vec<CachedFile>,
+}
+
+/// Implement the `CachedRevision` struct
+impl CachedRevision {
+    /// Get the total size of the revision files in bytes
+    pub fn size(&self) -> u64 {
+        self.files.iter().map(|f| f.size).sum()
+    }
+    /// Get the path of a file of the revision, if it has been downloaded
+    pub fn get_file_path(&self, rfilename: &str) -> Option<PathBuf> {
+        self.files
+            .iter()
+            .find(|f| f.rfilename == rfilename)
+            .map(|f| self.snapshot_path.join(&f.rfilename))
+    }
+    /// Get the files of the revision as `Siblings`, with their sizes
+    pub fn get_siblings(&self) -> Siblings {
+        Siblings::new(
+            self.files
+                .iter()
+                .map(|f| ModelFile::new(f.rfilename.clone(), Some(f.size as i64), None))
+                .collect(),
+        )
+    }
+}
+
+/// Struct for storing a repository of the local cache
+#[derive(Clone, Debug, PartialEq)]
+pub struct CachedRepo {
+    /// The repository ID (e.g. `username/repo_name`)
+    pub repo_id: String,
+    /// The kind of the repository
+    pub kind: RepoKind,
+    /// The path of the repository folder in the cache
+    pub repo_path: PathBuf,
+    /// The downloaded revisions of the repository
+    pub revisions: Vec<CachedRevision>,
+    /// The size of the repository on disk in bytes (blobs are shared between revisions)
+    pub size_on_disk: u64,
+}
+
+/// Implement the `CachedRepo` struct
+impl CachedRepo {
+    /// Get a revision by commit hash or by ref name (e.g. `main`)
+    pub fn get_revision(&self, revision: &str) -> Option<&'_ CachedRevision> {
+        self.revisions
+            .iter()
+            .find(|r| r.commit_hash == revision || r.refs.iter().any(|name| name == revision))
+    }
+}
+
+/// Get the local cache folder, following the `huggingface_hub` resolution order:
+/// `HF_HUB_CACHE`, `HF_HOME/hub`, `XDG_CACHE_HOME/huggingface/hub` then
+/// `~/.cache/huggingface/hub`
+pub fn default_cache_dir() -> Option<PathBuf> {
+    let var = |name: &str| {
+        env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+    if let Some(path) = var("HF_HUB_CACHE") {
+        return Some(path);
+    }
+    if let Some(path) = var("HF_HOME") {
+        return Some(path.join("hub"));
+    }
+    let cache_home = var("XDG_CACHE_HOME").or_else(|| {
+        var("HOME")
+            .or_else(|| var("USERPROFILE"))
+            .map(|home| home.join(".cache"))
+    })?;
+    Some(cache_home.join("huggingface").join("hub"))
+}
+
+/// Parse a cache folder name (e.g. `models--EleutherAI--gpt-j-6b`) into a repository kind and ID
+pub fn parse_repo_folder_name(folder_name: &str) -> Option<(RepoKind, String)> {
+    let (kind, repo_id) = folder_name.split_once("--")?;
+    let kind = RepoKind::ALL.into_iter().find(|k| k.api_path() == kind)?;
+    if repo_id.is_empty() {
+        return None;
+    }
+    Some((kind, repo_id.replace("--", "/")))
+}
+
+/// Enumerate all the repositories of a cache folder, sorted by repository ID.
+///
+/// Returns an empty list if the cache folder doesn't exist yet.
+#[tracing::instrument(level = "debug")]
+pub fn scan_cache(cache_dir: &Path) -> io::Result<Vec<CachedRepo>> {
+    if !cache_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut repos = Vec::new();
+    for entry in fs::read_dir(cache_dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let folder_name = entry.file_name().to_string_lossy().to_string();
+        if let Some((kind, repo_id)) = parse_repo_folder_name(&folder_name) {
+            repos.push(scan_repo(&entry.path(), kind, repo_id)?);
+        }
+    }
+    repos.sort_by(|a, b| a.repo_id.cmp(&b.repo_id));
+    Ok(repos)
+}
+
+/// Find a repository in a cache folder, returns `None` if it has never been downloaded
+#[tracing::instrument(level = "debug", skip(cache_dir), fields(cache))]
+pub fn find_cached_repo(
+    cache_dir: &Path,
+    repo_id: &str,
+    kind: RepoKind,
+) -> io::Result<Option<CachedRepo>> {
+    let folder_name = format!("{}--{}", kind.api_path(), repo_id.replace('/', "--"));
+    let repo_path = cache_dir.join(folder_name);
+    if !repo_path.is_dir() {
+        tracing::Span::current().record("cache", "miss");
+        return Ok(None);
+    }
+    tracing::Span::current().record("cache", "hit");
+    scan_repo(&repo_path, kind, repo_id.to_string()).map(Some)
+}
+
+/// List the files of a local model folder (e.g. a checkpoint saved with `save_pretrained` or a
+/// snapshot copied to an air-gapped machine) as the siblings of a repository, with their sizes
+pub fn list_local_files(model_dir: &Path) -> io::Result<Siblings> {
+    let mut files = Vec::new();
+    list_files(model_dir, model_dir, &mut files)?;
+    files.sort_by(|a, b| a.rfilename.cmp(&b.rfilename));
+    Ok(Siblings::new(
+        files
+            .into_iter()
+            .map(|file| ModelFile::new(file.rfilename, Some(file.size as i64), None))
+            .collect(),
+    ))
+}
+
+/// Scan a repository folder of the cache
+fn scan_repo(repo_path: &Path, kind: RepoKind, repo_id: String) -> io::Result<CachedRepo> {
+    let refs = read_refs(&repo_path.join("refs"))?;
+
+    let mut revisions = Vec::new();
+    let snapshots_path = repo_path.join("snapshots");
+    if snapshots_path.is_dir() {
+        for entry in fs::read_dir(&snapshots_path)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            let commit_hash = entry.file_name().to_string_lossy().to_string();
+            let mut files = Vec::new();
+            list_files(&entry.path(), &entry.path(), &mut files)?;
+            files.sort_by(|a, b| a.rfilename.cmp(&b.rfilename));
+            revisions.push(CachedRevision {
+                refs: refs
+                    .iter()
+                    .filter(|(_, hash)| *hash == commit_hash)
+                    .map(|(name, _)| name.clone())
+                    .collect(),
+                commit_hash,
+                snapshot_path: entry.path(),
+                files,
+            });
+        }
+    }
+    revisions.sort_by(|a, b| a.commit_hash.cmp(&b.commit_hash));
+
+    let mut size_on_disk = 0;
+    let blobs_path = repo_path.join("blobs");
+    if blobs_path.is_dir() {
+        for entry in fs::read_dir(&blobs_path)? {
+            size_on_disk += entry?.metadata()?.len();
+        }
+    }
+
+    Ok(CachedRepo {
+        repo_id,
+        kind,
+        repo_path: repo_path.to_path_buf(),
+        revisions,
+        size_on_disk,
+    })
+}
+
+/// Read the `(ref name, commit hash)` pairs of a `refs` folder, including nested refs such as
+/// `refs/pr/1`
+fn read_refs(refs_path: &Path) -> io::Result<Vec<(String, String)>> {
+    let mut refs = Vec::new();
+    if !refs_path.is_dir() {
+        return Ok(refs);
+    }
+    let mut files = Vec::new();
+    list_files(refs_path, refs_path, &mut files)?;
+    for file in files {
+        let commit_hash = fs::read_to_string(refs_path.join(&file.rfilename))?;
+        refs.push((file.rfilename, commit_hash.trim().to_string()));
+    }
+    Ok(refs)
+}
+
+/// Recursively list the files of a folder, with their path relative to `root`. Symlinks to
+/// blobs are followed to get the real file sizes.
+fn list_files(root: &Path, folder: &Path, files: &mut Vec<CachedFile>) -> io::Result<()> {
+    for entry in fs::read_dir(folder)? {
+        let path = entry?.path();
+        let metadata = match fs::metadata(&path) {
+            Ok(metadata) => metadata,
+            // Dangling symlink, the blob has been deleted
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        if metadata.is_dir() {
+            list_files(root, &path, files)?;
+        } else {
+            let rfilename = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push(CachedFile {
+                rfilename,
+                size: metadata.len(),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const COMMIT_HASH: &str = "f98c709453c9402b1309b032f40df1c10ad481a2";
+
+    /// Setup a fake cache folder with one model and one dataset.
+    fn setup_cache(name: &str) -> PathBuf {
+        let cache_dir = env::temp_dir().join(format!("aiha-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&cache_dir);
+
+        let repo_path = cache_dir.join("models--EleutherAI--gpt-j-6b");
+        let snapshot_path = repo_path.join("snapshots").join(COMMIT_HASH);
+        fs::create_dir_all(snapshot_path.join("onnx")).unwrap();
+        fs::create_dir_all(repo_path.join("blobs")).unwrap();
+        fs::create_dir_all(repo_path.join("refs").join("pr")).unwrap();
+        fs::write(repo_path.join("refs").join("main"), COMMIT_HASH).unwrap();
+        fs::write(repo_path.join("refs").join("pr").join("1"), "0000").unwrap();
+        fs::write(snapshot_path.join("config.json"), "{}").unwrap();
+        fs::write(snapshot_path.join("onnx").join("model.onnx"), [0; 10]).unwrap();
+        fs::write(repo_path.join("blobs").join("a"), "{}").unwrap();
+        fs::write(repo_path.join("blobs").join("b"), [0; 10]).unwrap();
+
+        fs::create_dir_all(cache_dir.join("datasets--imdb").join("snapshots")).unwrap();
+        fs::create_dir_all(cache_dir.join(".locks")).unwrap();
+        fs::write(cache_dir.join("version.txt"), "1").unwrap();
+        cache_dir
+    }
+
+    #[test]
+    fn test_parse_repo_folder_name() {
+        assert_eq!(
+            parse_repo_folder_name("models--EleutherAI--gpt-j-6b"),
+            Some((RepoKind::Model, "EleutherAI/gpt-j-6b".to_string()))
+        );
+        assert_eq!(
+            parse_repo_folder_name("datasets--imdb"),
+            Some((RepoKind::Dataset, "imdb".to_string()))
+        );
+        assert_eq!(parse_repo_folder_name(".locks"), None);
+        assert_eq!(parse_repo_folder_name("unknown--repo"), None);
+        assert_eq!(parse_repo_folder_name("models--"), None);
+    }
+
+    #[test]
+    fn test_scan_cache() {
+        let cache_dir = setup_cache("scan-cache");
+        let repos = scan_cache(&cache_dir).unwrap();
+        assert_eq!(repos.len(), 2);
+
+        let model = &repos[0];
+        assert_eq!(model.repo_id, "EleutherAI/gpt-j-6b");
+        assert_eq!(model.kind, RepoKind::Model);
+        assert_eq!(model.size_on_disk, 12);
+        assert_eq!(model.revisions.len(), 1);
+
+        let revision = model.get_revision("main").unwrap();
+        assert_eq!(revision.commit_hash, COMMIT_HASH);
+        assert_eq!(revision.refs, vec!["main".to_string()]);
+        assert_eq!(revision.size(), 12);
+        assert_eq!(
+            revision.files,
+            vec![
+                CachedFile {
+                    rfilename: "config.json".to_string(),
+                    size: 2
+                },
+                CachedFile {
+                    rfilename: "onnx/model.onnx".to_string(),
+                    size: 10
+                },
+            ]
+        );
+        assert_eq!(
+            revision.get_file_path("config.json"),
+            Some(revision.snapshot_path.join("config.json"))
+        );
+        assert_eq!(revision.get_siblings().get_sibling_names().len(), 2);
+        assert_eq!(model.get_revision(COMMIT_HASH), Some(revision));
+        assert_eq!(model.get_revision("dev"), None);
+
+        let dataset = &repos[1];
+        assert_eq!(dataset.repo_id, "imdb");
+        assert_eq!(dataset.kind, RepoKind::Dataset);
+        assert!(dataset.revisions.is_empty());
+
+        fs::remove_dir_all(&cache_dir).unwrap();
+        assert!(scan_cache(&cache_dir).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_find_cached_repo() {
+        let cache_dir = setup_cache("find-cached-repo");
+        let repo = find_cached_repo(&cache_dir, "EleutherAI/gpt-j-6b", RepoKind::Model).unwrap();
+        assert_eq!(repo.unwrap().revisions.len(), 1);
+        let repo = find_cached_repo(&cache_dir, "EleutherAI/gpt-j-6b", RepoKind::Space).unwrap();
+        assert_eq!(repo, None);
+        fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_list_local_files() {
+        let cache_dir = setup_cache("list-local-files");
+        let snapshot_path = cache_dir
+            .join("models--EleutherAI--gpt-j-6b")
+            .join("snapshots")
+            .join(COMMIT_HASH);
+        let siblings = list_local_files(&snapshot_path).unwrap();
+        assert_eq!(
+            siblings.get_sibling_names(),
+            vec!["config.json", "onnx/model.onnx"]
+        );
+        assert_eq!(siblings.siblings[1].get_size(), Some(10));
+        assert!(list_local_files(&cache_dir.join("missing")).is_err());
+        fs::remove_dir_all(&cache_dir).unwrap();
+    }
+}
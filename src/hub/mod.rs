@@ -1,15 +1,52 @@
 //! Module for handling Hugging Face Hub interactions
 
 // Hub Struct for handling Hugging Face Hub interactions
+// Hub client with mirror endpoints
+mod client;
+pub use client::HubClient;
+// Blocking hub client
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+mod blocking;
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub use blocking::BlockingHubClient;
+// Commit SHA
+mod commit_sha;
+pub use commit_sha::CommitSha;
 // Model Config
 mod config;
 pub use config::ModelConfig;
+// Dataset info
+mod dataset_info;
+pub use dataset_info::{DatasetInfo, DatasetSplit, DEFAULT_BYTES_PER_TOKEN};
+// Errors
+mod error;
+pub use error::HubError;
+// GGUF files of llama.cpp
+mod gguf;
+#[cfg(test)]
+pub(crate) use gguf::tests::create_gguf_bytes;
+pub use gguf::{GgufFile, GgufTensor, GgufValue};
+// Local Hugging Face Hub cache
+pub mod local_cache;
+pub use local_cache::{CachedFile, CachedRepo, CachedRevision};
 // Model File
 mod model_file;
 pub use model_file::ModelFile;
 // Model Info
 mod model_info;
 pub use model_info::ModelInfo;
+// ONNX export info
+mod onnx_info;
+pub use onnx_info::OnnxInfo;
+// Diffusers pipeline info
+mod pipeline_info;
+pub use pipeline_info::{PipelineComponent, PipelineInfo};
+// Repository kind
+mod repo_kind;
+pub use repo_kind::RepoKind;
+// Model search
+mod search;
+pub use search::{parse_parameter_count, SearchQuery, SearchResult, DEFAULT_SEARCH_LIMIT};
 // Siblings
 mod siblings;
 pub use siblings::Siblings;
@@ -17,7 +54,10 @@ pub use siblings::Siblings;
 // Hub methods for getting model info
 // Hub methods
 mod api;
-pub use api::{get_model_config, list_files_info, retrieve_model_info};
+pub use api::{
+    get_model_config, get_onnx_info, get_pipeline_info, list_files_info, repo_exists,
+    resolve_revision, retrieve_dataset_info, retrieve_model_info, search_models, DEFAULT_TIMEOUT,
+};
 // Utils
 mod utils;
-pub use utils::{build_headers, CUSTOM_ENCODE_SET, HUB_ENDPOINT};
+pub use utils::{build_headers, CUSTOM_ENCODE_SET, DATASETS_SERVER_ENDPOINT, HUB_ENDPOINT};
@@ -6,14 +6,16 @@ use std::ops::Not;
 use serde::Deserialize;
 use serde_json::Value;
 
-use crate::hub::{ModelConfig, ModelFile, Siblings};
-use crate::models::{ModelConfigTrait, ModelLibraries};
+use crate::hub::{CommitSha, ModelConfig, ModelFile, OnnxInfo, Siblings};
+use crate::models::{KnownModel, ModelConfigTrait, ModelFamily, ModelLibraries};
 
 /// Struct for storing the model metadata
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 pub struct ModelInfo {
     /// The model ID of the repository (e.g. `username/repo_name`)
     pub model_id: Option<String>,
+    /// The commit SHA of the revision the metadata was retrieved from
+    pub sha: Option<String>,
     /// The associated tags of the repository
     pub tags: Option<Vec<String>>,
     /// The pipeline tag of the repository
@@ -31,6 +33,7 @@ impl ModelInfo {
     /// Create a new ModelInfo struct
     pub fn new(
         model_id: Option<String>,
+        sha: Option<String>,
         tags: Option<Vec<String>>,
         pipeline_tag: Option<String>,
         siblings: Option<Siblings>,
@@ -39,6 +42,7 @@ impl ModelInfo {
     ) -> Self {
         Self {
             model_id,
+            sha,
             tags,
             pipeline_tag,
             siblings,
@@ -46,10 +50,46 @@ impl ModelInfo {
             security_status,
         }
     }
+    /// Get the resolved commit SHA of the repository, to pin the next hub calls to
+    pub fn get_commit_sha(&self) -> Option<CommitSha> {
+        self.sha.as_deref().and_then(CommitSha::new)
+    }
     /// Get the siblings of the repository
     pub fn get_siblings(&self) -> Option<&'_ Siblings> {
         self.siblings.as_ref()
     }
+    /// Check if the repository is a diffusers pipeline (see `hub::get_pipeline_info`)
+    pub fn is_diffusers(&self) -> bool {
+        let has_tag = self
+            .tags
+            .as_ref()
+            .map(|tags| tags.iter().any(|t| t == "diffusers"))
+            .unwrap_or(false);
+        let has_model_index = self
+            .siblings
+            .as_ref()
+            .map(|s| {
+                s.get_sibling_names()
+                    .iter()
+                    .any(|n| *n == "model_index.json")
+            })
+            .unwrap_or(false);
+        has_tag || has_model_index
+    }
+    /// Check if the repository provides an ONNX export, ready for ONNX Runtime deployment
+    pub fn has_onnx(&self) -> bool {
+        let has_tag = self
+            .tags
+            .as_ref()
+            .map(|tags| tags.iter().any(|t| t == "onnx"))
+            .unwrap_or(false);
+        has_tag || self.get_onnx_info().is_some()
+    }
+    /// Get the ONNX files of the repository and their total size (see `hub::get_onnx_info` to
+    /// get the opset too)
+    pub fn get_onnx_info(&self) -> Option<OnnxInfo> {
+        self.siblings.as_ref().and_then(OnnxInfo::from_siblings)
+    }
     /// Get the config model_type of the repository
     pub fn get_model_type(&self) -> Option<String> {
         self.config
@@ -62,6 +102,38 @@ impl ModelInfo {
             .as_ref()
             .map(|config| config.available_libraries().to_vec())
     }
+    /// Get the family of the model, from the diffusers and sentence-transformers tags first, then
+    /// the pipeline tag and lastly the config
+    pub fn get_model_family(&self) -> Option<ModelFamily> {
+        if self.is_diffusers() {
+            return Some(ModelFamily::Diffusion);
+        }
+        let is_sentence_transformers = self
+            .tags
+            .as_ref()
+            .map(|tags| tags.iter().any(|t| t == "sentence-transformers"))
+            .unwrap_or(false);
+        if is_sentence_transformers {
+            return Some(ModelFamily::Embedding);
+        }
+        self.pipeline_tag
+            .as_deref()
+            .and_then(ModelFamily::from_pipeline_tag)
+            .or_else(|| self.config.as_ref().map(|config| config.model_family()))
+    }
+    /// Get the task of the model, the pipeline tag of the repository or the default task of its
+    /// family when the repository has none
+    pub fn get_task(&self) -> Option<String> {
+        self.pipeline_tag.clone().or_else(|| {
+            self.get_model_family()
+                .map(|family| family.default_task().to_string())
+        })
+    }
+    /// Get the well-known model of the repository, whose verified number of parameters can
+    /// cross-check the config-derived estimates
+    pub fn get_known_model(&self) -> Option<KnownModel> {
+        self.model_id.as_deref().and_then(KnownModel::from_repo_id)
+    }
     /// Check for security vulnerabilities
     pub fn has_vulnerabilities(&self) -> bool {
         if let Some(security_status) = &self.security_status {
@@ -107,6 +179,7 @@ impl ModelInfo {
         );
         ModelInfo::new(
             value["id"].as_str().map(|s| s.to_string()),
+            value["sha"].as_str().map(|s| s.to_string()),
             value["tags"]
                 .as_array()
                 .map(|a| a.iter().map(|v| v.as_str().unwrap().to_string()).collect()),
@@ -192,6 +265,7 @@ mod tests {
         };
         ModelInfo::new(
             Some("EleutherAI/gpt-j-6b".to_string()),
+            Some("f98c709453c9402b1309b032f40df1c10ad481a2".to_string()),
             Some(vec!["causal-lm".to_string(), "pytorch".to_string()]),
             Some("text-generation".to_string()),
             Some(siblings),
@@ -210,6 +284,7 @@ mod tests {
 
         let model_info = ModelInfo::new(
             model_id.clone(),
+            None,
             tags.clone(),
             pipeline_tag.clone(),
             siblings.clone(),
@@ -230,6 +305,88 @@ mod tests {
         assert_eq!(model_info.get_siblings(), Some(&create_sample_siblings()));
     }
 
+    #[test]
+    fn test_model_info_get_commit_sha() {
+        let model_info = create_model_info(false);
+        assert_eq!(
+            model_info.get_commit_sha(),
+            CommitSha::new("f98c709453c9402b1309b032f40df1c10ad481a2")
+        );
+    }
+
+    #[test]
+    fn test_model_info_is_diffusers() {
+        let mut model_info = create_model_info(false);
+        assert!(!model_info.is_diffusers());
+        model_info.siblings = Some(Siblings::new(vec![ModelFile::new(
+            "model_index.json".to_string(),
+            None,
+            None,
+        )]));
+        assert!(model_info.is_diffusers());
+    }
+
+    #[test]
+    fn test_model_info_has_onnx() {
+        let mut model_info = create_model_info(false);
+        assert!(!model_info.has_onnx());
+        assert_eq!(model_info.get_onnx_info(), None);
+        model_info.siblings = Some(Siblings::new(vec![ModelFile::new(
+            "onnx/model.onnx".to_string(),
+            Some(100),
+            None,
+        )]));
+        assert!(model_info.has_onnx());
+        assert_eq!(model_info.get_onnx_info().unwrap().size, Some(100));
+    }
+
+    #[test]
+    fn test_model_info_get_model_family() {
+        let mut model_info = create_model_info(false);
+        assert_eq!(model_info.get_model_family(), Some(ModelFamily::DecoderLM));
+        assert_eq!(model_info.get_task(), Some("text-generation".to_string()));
+
+        model_info.pipeline_tag = None;
+        assert_eq!(model_info.get_model_family(), None);
+        assert_eq!(model_info.get_task(), None);
+        model_info.config = Some(
+            ModelConfig::from_json(serde_json::json!({
+                "model_type": "whisper",
+                "d_model": 384,
+                "encoder_layers": 4,
+                "decoder_layers": 4,
+                "encoder_ffn_dim": 1536,
+                "decoder_ffn_dim": 1536,
+                "encoder_attention_heads": 6,
+                "max_source_positions": 1500,
+                "max_target_positions": 448,
+                "vocab_size": 51865
+            }))
+            .unwrap(),
+        );
+        assert_eq!(model_info.get_model_family(), Some(ModelFamily::Audio));
+        assert_eq!(
+            model_info.get_task(),
+            Some("automatic-speech-recognition".to_string())
+        );
+
+        model_info.tags = Some(vec!["sentence-transformers".to_string()]);
+        assert_eq!(model_info.get_model_family(), Some(ModelFamily::Embedding));
+        model_info.tags = Some(vec!["diffusers".to_string()]);
+        assert_eq!(model_info.get_model_family(), Some(ModelFamily::Diffusion));
+    }
+
+    #[test]
+    fn test_model_info_get_known_model() {
+        let mut model_info = create_model_info(false);
+        assert_eq!(
+            model_info.get_known_model().unwrap().num_parameters(),
+            6_053_381_344
+        );
+        model_info.model_id = Some("username/repo_name".to_string());
+        assert_eq!(model_info.get_known_model(), None);
+    }
+
     #[test]
     fn test_model_info_check_security() {
         let model_info = create_model_info(false);
@@ -245,6 +402,7 @@ mod tests {
         let pipeline_tag = Some("task1".to_string());
         let model_info = ModelInfo {
             model_id,
+            sha: None,
             tags,
             pipeline_tag,
             siblings: None,
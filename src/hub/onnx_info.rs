@@ -0,0 +1,122 @@
+//! ONNX export metadata struct
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::hub::Siblings;
+
+/// Extensions of the files holding an ONNX graph or its external weights
+const ONNX_EXTENSIONS: [&str; 3] = [".onnx", ".onnx_data", ".onnx.data"];
+
+/// Struct for storing the ONNX exports found in a repository
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct OnnxInfo {
+    /// The ONNX files of the repository (graphs and external data)
+    pub files: Vec<String>,
+    /// The total size of the ONNX files in bytes
+    pub size: Option<i64>,
+    /// The ONNX opset used for the export, if it's known
+    pub opset: Option<i64>,
+}
+
+/// Implement the `OnnxInfo` struct
+impl OnnxInfo {
+    /// Create a new OnnxInfo struct
+    pub fn new(files: Vec<String>, size: Option<i64>, opset: Option<i64>) -> Self {
+        Self { files, size, opset }
+    }
+    /// Check if a file of the repository belongs to an ONNX export
+    pub fn is_onnx_file(rfilename: &str) -> bool {
+        ONNX_EXTENSIONS.iter().any(|ext| rfilename.ends_with(ext))
+    }
+    /// Get the subfolders holding ONNX files (an empty string stands for the repository root)
+    pub fn get_folders(&self) -> Vec<&'_ str> {
+        let mut folders: Vec<&str> = self
+            .files
+            .iter()
+            .map(|f| f.rsplit_once('/').map(|(folder, _)| folder).unwrap_or(""))
+            .collect();
+        folders.sort_unstable();
+        folders.dedup();
+        folders
+    }
+    /// Set the opset from the content of an Optimum `ort_config.json` file
+    pub fn set_opset_from_json(&mut self, ort_config: &Value) {
+        self.opset = ort_config["opset"].as_i64();
+    }
+    /// Create a new OnnxInfo struct from the siblings of a repository, returns `None` if the
+    /// repository doesn't have any ONNX file.
+    ///
+    /// The size is only known once `list_files_info` has been called on the siblings.
+    pub fn from_siblings(siblings: &Siblings) -> Option<Self> {
+        let files: Vec<_> = siblings
+            .siblings
+            .iter()
+            .filter(|f| Self::is_onnx_file(f.get_rfilename()))
+            .collect();
+        if files.is_empty() {
+            return None;
+        }
+        let size = files.iter().map(|f| f.get_size()).sum();
+        Some(OnnxInfo::new(
+            files
+                .iter()
+                .map(|f| f.get_rfilename().to_string())
+                .collect(),
+            size,
+            None,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hub::ModelFile;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn create_siblings(size: Option<i64>) -> Siblings {
+        Siblings::new(vec![
+            ModelFile::new("config.json".to_string(), Some(1), None),
+            ModelFile::new("pytorch_model.bin".to_string(), Some(500), None),
+            ModelFile::new("onnx/config.json".to_string(), Some(1), None),
+            ModelFile::new("onnx/model.onnx".to_string(), size, None),
+            ModelFile::new("onnx/model.onnx_data".to_string(), Some(400), None),
+            ModelFile::new("onnx/model_quantized.onnx".to_string(), Some(100), None),
+        ])
+    }
+
+    #[test]
+    fn test_onnx_info_from_siblings() {
+        let onnx_info = OnnxInfo::from_siblings(&create_siblings(Some(2))).unwrap();
+        assert_eq!(
+            onnx_info.files,
+            vec![
+                "onnx/model.onnx".to_string(),
+                "onnx/model.onnx_data".to_string(),
+                "onnx/model_quantized.onnx".to_string(),
+            ]
+        );
+        assert_eq!(onnx_info.size, Some(502));
+        assert_eq!(onnx_info.opset, None);
+        assert_eq!(onnx_info.get_folders(), vec!["onnx"]);
+
+        let onnx_info = OnnxInfo::from_siblings(&create_siblings(None)).unwrap();
+        assert_eq!(onnx_info.size, None);
+
+        let siblings = Siblings::new(vec![ModelFile::new(
+            "pytorch_model.bin".to_string(),
+            Some(500),
+            None,
+        )]);
+        assert_eq!(OnnxInfo::from_siblings(&siblings), None);
+    }
+
+    #[test]
+    fn test_onnx_info_set_opset() {
+        let mut onnx_info = OnnxInfo::new(vec!["model.onnx".to_string()], None, None);
+        assert_eq!(onnx_info.get_folders(), vec![""]);
+        onnx_info.set_opset_from_json(&json!({"opset": 14, "optimize_for_gpu": false}));
+        assert_eq!(onnx_info.opset, Some(14));
+    }
+}
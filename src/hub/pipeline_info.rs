@@ -0,0 +1,219 @@
+//! Diffusers pipeline metadata struct
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::hub::Siblings;
+
+/// Suffixes of the pipeline component classes that don't hold any weights
+const NON_MODEL_CLASS_SUFFIXES: [&str; 5] = [
+    "Scheduler",
+    "Tokenizer",
+    "TokenizerFast",
+    "FeatureExtractor",
+    "Processor",
+];
+
+/// Struct for storing a component of a diffusers pipeline (e.g. `unet`, `vae`, `text_encoder`)
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct PipelineComponent {
+    /// The name of the component, which is also its subfolder in the repository
+    pub name: String,
+    /// The library the component class comes from (e.g. `diffusers`, `transformers`)
+    pub library: Option<String>,
+    /// The class of the component (e.g. `UNet2DConditionModel`)
+    pub class_name: Option<String>,
+    /// The size of the component weights in bytes
+    pub size: Option<i64>,
+    /// The content of the component `config.json` file
+    pub config: Option<Value>,
+}
+
+/// Implement the `PipelineComponent` struct
+impl PipelineComponent {
+    /// Create a new PipelineComponent struct
+    pub fn new(name: String, library: Option<String>, class_name: Option<String>) -> Self {
+        Self {
+            name,
+            library,
+            class_name,
+            size: None,
+            config: None,
+        }
+    }
+    /// Check if the component holds weights (schedulers, tokenizers and processors don't)
+    pub fn is_model(&self) -> bool {
+        match &self.class_name {
+            Some(class_name) => !NON_MODEL_CLASS_SUFFIXES
+                .iter()
+                .any(|suffix| class_name.ends_with(suffix)),
+            None => false,
+        }
+    }
+}
+
+/// Struct for storing the metadata of a diffusers pipeline, parsed from `model_index.json`
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct PipelineInfo {
+    /// The class of the pipeline (e.g. `StableDiffusionPipeline`)
+    pub class_name: Option<String>,
+    /// The diffusers version used to save the pipeline
+    pub diffusers_version: Option<String>,
+    /// The components of the pipeline
+    pub components: Vec<PipelineComponent>,
+}
+
+/// Implement the `PipelineInfo` struct
+impl PipelineInfo {
+    /// Create a new PipelineInfo struct
+    pub fn new(
+        class_name: Option<String>,
+        diffusers_version: Option<String>,
+        components: Vec<PipelineComponent>,
+    ) -> Self {
+        Self {
+            class_name,
+            diffusers_version,
+            components,
+        }
+    }
+    /// Get a component of the pipeline by name
+    pub fn get_component(&self, name: &str) -> Option<&'_ PipelineComponent> {
+        self.components.iter().find(|c| c.name == name)
+    }
+    /// Get the components of the pipeline holding weights
+    pub fn get_model_components(&self) -> Vec<&'_ PipelineComponent> {
+        self.components.iter().filter(|c| c.is_model()).collect()
+    }
+    /// Get the total size of the pipeline weights in bytes, if any component size is known
+    pub fn total_size(&self) -> Option<i64> {
+        self.components
+            .iter()
+            .filter_map(|c| c.size)
+            .reduce(|a, b| a + b)
+    }
+    /// Set the size of every model component from the sizes of the repository siblings.
+    ///
+    /// Siblings sizes are only known once `list_files_info` has been called on them.
+    pub fn set_sizes(&mut self, siblings: &Siblings) {
+        for component in self.components.iter_mut().filter(|c| c.is_model()) {
+            component.size = siblings.get_weights_size(&component.name);
+        }
+    }
+    /// Create a new PipelineInfo struct from the content of a `model_index.json` file
+    pub fn from_json(value: Value) -> Self {
+        let mut components = Vec::new();
+        if let Some(entries) = value.as_object() {
+            for (name, entry) in entries.iter().filter(|(name, _)| !name.starts_with('_')) {
+                // Components are stored as `[library, class_name]`, unused ones as `[null, null]`
+                if let Some([library, class_name]) = entry.as_array().map(|a| a.as_slice()) {
+                    if class_name.is_null() {
+                        continue;
+                    }
+                    components.push(PipelineComponent::new(
+                        name.to_string(),
+                        library.as_str().map(|s| s.to_string()),
+                        class_name.as_str().map(|s| s.to_string()),
+                    ));
+                }
+            }
+        }
+        PipelineInfo::new(
+            value["_class_name"].as_str().map(|s| s.to_string()),
+            value["_diffusers_version"].as_str().map(|s| s.to_string()),
+            components,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hub::ModelFile;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn create_model_index() -> Value {
+        json!({
+            "_class_name": "StableDiffusionPipeline",
+            "_diffusers_version": "0.6.0",
+            "feature_extractor": ["transformers", "CLIPImageProcessor"],
+            "safety_checker": [null, null],
+            "scheduler": ["diffusers", "PNDMScheduler"],
+            "text_encoder": ["transformers", "CLIPTextModel"],
+            "tokenizer": ["transformers", "CLIPTokenizer"],
+            "unet": ["diffusers", "UNet2DConditionModel"],
+            "vae": ["diffusers", "AutoencoderKL"]
+        })
+    }
+
+    #[test]
+    fn test_pipeline_info_from_json() {
+        let pipeline_info = PipelineInfo::from_json(create_model_index());
+        assert_eq!(
+            pipeline_info.class_name,
+            Some("StableDiffusionPipeline".to_string())
+        );
+        assert_eq!(pipeline_info.diffusers_version, Some("0.6.0".to_string()));
+        assert_eq!(pipeline_info.components.len(), 6);
+        assert_eq!(
+            pipeline_info.get_component("unet"),
+            Some(&PipelineComponent::new(
+                "unet".to_string(),
+                Some("diffusers".to_string()),
+                Some("UNet2DConditionModel".to_string()),
+            ))
+        );
+        assert!(pipeline_info.get_component("safety_checker").is_none());
+
+        let names: Vec<&str> = pipeline_info
+            .get_model_components()
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["text_encoder", "unet", "vae"]);
+    }
+
+    #[test]
+    fn test_pipeline_info_set_sizes() {
+        let mut pipeline_info = PipelineInfo::from_json(create_model_index());
+        assert_eq!(pipeline_info.total_size(), None);
+
+        let siblings = Siblings::new(vec![
+            ModelFile::new("model_index.json".to_string(), Some(1), None),
+            ModelFile::new("unet/config.json".to_string(), Some(1), None),
+            ModelFile::new(
+                "unet/diffusion_pytorch_model.bin".to_string(),
+                Some(3400),
+                None,
+            ),
+            ModelFile::new(
+                "unet/diffusion_pytorch_model.safetensors".to_string(),
+                Some(3300),
+                None,
+            ),
+            ModelFile::new(
+                "unet/diffusion_pytorch_model.fp16.safetensors".to_string(),
+                Some(1700),
+                None,
+            ),
+            ModelFile::new(
+                "vae/diffusion_pytorch_model.fp16.bin".to_string(),
+                Some(160),
+                None,
+            ),
+            ModelFile::new("text_encoder/pytorch_model.bin".to_string(), None, None),
+        ]);
+        pipeline_info.set_sizes(&siblings);
+        assert_eq!(
+            pipeline_info.get_component("unet").unwrap().size,
+            Some(3300)
+        );
+        assert_eq!(pipeline_info.get_component("vae").unwrap().size, Some(160));
+        assert_eq!(
+            pipeline_info.get_component("text_encoder").unwrap().size,
+            None
+        );
+        assert_eq!(pipeline_info.get_component("scheduler").unwrap().size, None);
+        assert_eq!(pipeline_info.total_size(), Some(3460));
+    }
+}
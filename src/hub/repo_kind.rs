@@ -0,0 +1,61 @@
+//! Repository kind enum
+use std::fmt;
+
+use serde::Deserialize;
+
+/// Enumerate the different kinds of repositories hosted on the Hugging Face Hub
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum RepoKind {
+    /// Model repository
+    Model,
+    /// Dataset repository
+    Dataset,
+    /// Space repository
+    Space,
+}
+
+/// Implement the `RepoKind` enum
+impl RepoKind {
+    /// All the repository kinds, in the order they are probed
+    pub const ALL: [RepoKind; 3] = [RepoKind::Model, RepoKind::Dataset, RepoKind::Space];
+
+    /// Get the API path segment of the repository kind (e.g. `models`)
+    pub fn api_path(&self) -> &'static str {
+        match self {
+            RepoKind::Model => "models",
+            RepoKind::Dataset => "datasets",
+            RepoKind::Space => "spaces",
+        }
+    }
+}
+
+/// Implement the display of the RepoKind enum
+impl fmt::Display for RepoKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepoKind::Model => write!(f, "model"),
+            RepoKind::Dataset => write!(f, "dataset"),
+            RepoKind::Space => write!(f, "space"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_repo_kind_api_path() {
+        assert_eq!(RepoKind::Model.api_path(), "models");
+        assert_eq!(RepoKind::Dataset.api_path(), "datasets");
+        assert_eq!(RepoKind::Space.api_path(), "spaces");
+    }
+
+    #[test]
+    fn test_repo_kind_display() {
+        assert_eq!(RepoKind::Model.to_string(), "model");
+        assert_eq!(RepoKind::Dataset.to_string(), "dataset");
+        assert_eq!(RepoKind::Space.to_string(), "space");
+    }
+}
@@ -0,0 +1,208 @@
+//! Model search query and result structs
+use serde_json::Value;
+
+/// Default number of models a search returns
+pub const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// Struct for storing the filters of a model search on the Hub
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchQuery {
+    /// The text the model IDs contain
+    pub search: Option<String>,
+    /// The pipeline tag of the models (e.g. `text-generation`)
+    pub task: Option<String>,
+    /// The library of the models (e.g. `safetensors` or `transformers`)
+    pub library: Option<String>,
+    /// The largest number of parameters, the models without a known count being left out
+    pub max_params: Option<i64>,
+    /// The largest number of models returned, the most downloaded first
+    pub limit: usize,
+}
+
+/// Implement the `SearchQuery` struct
+impl SearchQuery {
+    /// Create a new SearchQuery returning the `DEFAULT_SEARCH_LIMIT` most downloaded models
+    pub fn new() -> Self {
+        Self {
+            limit: DEFAULT_SEARCH_LIMIT,
+            ..Self::default()
+        }
+    }
+    /// Set the text the model IDs contain
+    pub fn with_search(mut self, search: &str) -> Self {
+        self.search = Some(search.to_string());
+        self
+    }
+    /// Set the pipeline tag of the models
+    pub fn with_task(mut self, task: &str) -> Self {
+        self.task = Some(task.to_string());
+        self
+    }
+    /// Set the library of the models
+    pub fn with_library(mut self, library: &str) -> Self {
+        self.library = Some(library.to_string());
+        self
+    }
+    /// Set the largest number of parameters
+    pub fn with_max_params(mut self, max_params: i64) -> Self {
+        self.max_params = Some(max_params);
+        self
+    }
+    /// Set the largest number of models returned
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+    /// Get the query parameters of the `/api/models` request. The parameter counts are expanded
+    /// from the safetensors metadata, the other expanded fields being the ones of a default
+    /// search.
+    pub fn get_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        for (name, value) in [
+            ("search", &self.search),
+            ("pipeline_tag", &self.task),
+            ("library", &self.library),
+        ] {
+            if let Some(value) = value {
+                params.push((name, value.clone()));
+            }
+        }
+        params.push(("sort", "downloads".to_string()));
+        params.push(("direction", "-1".to_string()));
+        // The models above the parameter budget are filtered out after the search
+        let limit = match self.max_params {
+            Some(_) => self.limit.saturating_mul(5).min(1000),
+            None => self.limit,
+        };
+        params.push(("limit", limit.to_string()));
+        for field in [
+            "pipeline_tag",
+            "library_name",
+            "downloads",
+            "likes",
+            "tags",
+            "safetensors",
+        ] {
+            params.push(("expand[]", field.to_string()));
+        }
+        params
+    }
+    /// Check if a search result passes the filters applied after the search
+    pub fn matches(&self, result: &SearchResult) -> bool {
+        match (self.max_params, result.parameters) {
+            (Some(max_params), Some(parameters)) => parameters <= max_params,
+            (Some(_), None) => false,
+            (None, _) => true,
+        }
+    }
+}
+
+/// Struct for storing a model found by a search on the Hub
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchResult {
+    /// The model ID of the repository (e.g. `username/repo_name`)
+    pub repo_id: String,
+    /// The pipeline tag of the repository
+    pub pipeline_tag: Option<String>,
+    /// The library of the repository
+    pub library_name: Option<String>,
+    /// The number of downloads of the last 30 days
+    pub downloads: Option<i64>,
+    /// The number of likes
+    pub likes: Option<i64>,
+    /// The number of parameters of the safetensors weights
+    pub parameters: Option<i64>,
+}
+
+/// Implement the creation of the SearchResult struct from a serde_json::Value
+impl From<&Value> for SearchResult {
+    fn from(value: &Value) -> Self {
+        SearchResult {
+            repo_id: value["id"]
+                .as_str()
+                .or(value["modelId"].as_str())
+                .unwrap_or_default()
+                .to_string(),
+            pipeline_tag: value["pipeline_tag"].as_str().map(str::to_string),
+            library_name: value["library_name"].as_str().map(str::to_string),
+            downloads: value["downloads"].as_i64(),
+            likes: value["likes"].as_i64(),
+            parameters: value["safetensors"]["total"].as_i64(),
+        }
+    }
+}
+
+/// Parse a number of parameters with an optional `K`, `M`, `B` or `T` suffix (e.g. `8B` or
+/// `1.5b`), `None` when it is invalid
+pub fn parse_parameter_count(count: &str) -> Option<i64> {
+    let count = count.trim();
+    let (number, scale) = match count.chars().last()?.to_ascii_uppercase() {
+        'K' => (&count[..count.len() - 1], 1e3),
+        'M' => (&count[..count.len() - 1], 1e6),
+        'B' => (&count[..count.len() - 1], 1e9),
+        'T' => (&count[..count.len() - 1], 1e12),
+        _ => (count, 1.0),
+    };
+    let number: f64 = number.trim().parse().ok()?;
+    match number.is_finite() && number >= 0.0 {
+        true => Some((number * scale).round() as i64),
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_parameter_count() {
+        assert_eq!(parse_parameter_count("8B"), Some(8_000_000_000));
+        assert_eq!(parse_parameter_count("1.5b"), Some(1_500_000_000));
+        assert_eq!(parse_parameter_count("350M"), Some(350_000_000));
+        assert_eq!(parse_parameter_count("1000"), Some(1000));
+        assert_eq!(parse_parameter_count("B"), None);
+        assert_eq!(parse_parameter_count("-1B"), None);
+        assert_eq!(parse_parameter_count(""), None);
+    }
+
+    #[test]
+    fn test_search_query_params() {
+        let query = SearchQuery::new()
+            .with_task("text-generation")
+            .with_library("safetensors")
+            .with_max_params(8_000_000_000);
+        let params = query.get_params();
+        assert!(params.contains(&("pipeline_tag", "text-generation".to_string())));
+        assert!(params.contains(&("library", "safetensors".to_string())));
+        assert!(params.contains(&("limit", "100".to_string())));
+        assert!(params.contains(&("expand[]", "safetensors".to_string())));
+        assert!(!params.iter().any(|(name, _)| *name == "search"));
+    }
+
+    #[test]
+    fn test_search_result_from_json() {
+        let value = json!({
+            "id": "meta-llama/Meta-Llama-3-8B",
+            "pipeline_tag": "text-generation",
+            "library_name": "transformers",
+            "downloads": 1_000_000,
+            "likes": 6000,
+            "safetensors": {"parameters": {"BF16": 8_030_261_248i64}, "total": 8_030_261_248i64}
+        });
+        let result = SearchResult::from(&value);
+        assert_eq!(result.repo_id, "meta-llama/Meta-Llama-3-8B");
+        assert_eq!(result.parameters, Some(8_030_261_248));
+
+        let query = SearchQuery::new().with_max_params(8_000_000_000);
+        assert!(!query.matches(&result));
+        assert!(query
+            .clone()
+            .with_max_params(9_000_000_000)
+            .matches(&result));
+        let unknown = SearchResult::from(&json!({"id": "user/gguf"}));
+        assert!(!query.matches(&unknown));
+        assert!(SearchQuery::new().matches(&unknown));
+    }
+}
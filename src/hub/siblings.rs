@@ -20,6 +20,50 @@ impl Siblings {
     pub fn get_sibling_names(&self) -> Vec<&'_ String> {
         self.siblings.iter().map(|s| s.get_rfilename()).collect()
     }
+    /// Sum the size of the weights stored in a folder of the repository, the root one when
+    /// `folder` is empty. Returns `None` without weights or when a size is unknown.
+    ///
+    /// Repositories often store the same weights several times (safetensors and pickle formats,
+    /// `fp16` or `ema` variants), so only one format is counted, preferring safetensors and the
+    /// default variant.
+    pub fn get_weights_size(&self, folder: &str) -> Option<i64> {
+        let prefix = match folder.is_empty() {
+            true => String::new(),
+            false => format!("{}/", folder.trim_end_matches('/')),
+        };
+        let files: Vec<&ModelFile> = self
+            .siblings
+            .iter()
+            .filter(|f| {
+                f.get_rfilename()
+                    .strip_prefix(&prefix)
+                    .is_some_and(|name| !name.contains('/'))
+            })
+            .collect();
+        for extension in [".safetensors", ".bin", ".ckpt"] {
+            let weights: Vec<&&ModelFile> = files
+                .iter()
+                .filter(|f| f.get_rfilename().ends_with(extension))
+                .collect();
+            if weights.is_empty() {
+                continue;
+            }
+            let default_variant: Vec<&&&ModelFile> = weights
+                .iter()
+                .filter(|f| {
+                    let filename = f.get_rfilename().rsplit('/').next().unwrap_or_default();
+                    filename.matches('.').count() == 1
+                })
+                .collect();
+            let sizes: Vec<Option<i64>> = if default_variant.is_empty() {
+                weights.iter().map(|f| f.get_size()).collect()
+            } else {
+                default_variant.iter().map(|f| f.get_size()).collect()
+            };
+            return sizes.into_iter().sum();
+        }
+        None
+    }
 }
 
 /// Implement the partial equality for the `Siblings` struct
@@ -60,6 +104,28 @@ mod tests {
         assert_eq!(sibling_names[2], "model3.json");
     }
 
+    #[test]
+    fn test_siblings_get_weights_size() {
+        let siblings = Siblings::new(vec![
+            ModelFile::new("config.json".to_string(), Some(1), None),
+            ModelFile::new(
+                "model-00001-of-00002.safetensors".to_string(),
+                Some(100),
+                None,
+            ),
+            ModelFile::new(
+                "model-00002-of-00002.safetensors".to_string(),
+                Some(50),
+                None,
+            ),
+            ModelFile::new("pytorch_model.bin".to_string(), Some(150), None),
+            ModelFile::new("onnx/model.safetensors".to_string(), Some(10), None),
+        ]);
+        assert_eq!(siblings.get_weights_size(""), Some(150));
+        assert_eq!(siblings.get_weights_size("onnx"), Some(10));
+        assert_eq!(siblings.get_weights_size("vae"), None);
+    }
+
     #[test]
     fn test_siblings_partial_eq() {
         let s1 = vec![ModelFile::new(
@@ -9,6 +9,8 @@ use reqwest::header::HeaderMap;
 pub const CUSTOM_ENCODE_SET: &AsciiSet = &CONTROLS.add(b' ').add(b'/').add(b':').add(b'@');
 /// The default endpoint for the Hugging Face Hub
 pub const HUB_ENDPOINT: &str = "https://huggingface.co";
+/// The endpoint of the Hugging Face datasets-server API
+pub const DATASETS_SERVER_ENDPOINT: &str = "https://datasets-server.huggingface.co";
 
 /// Format the user agent string
 fn http_user_agent(
@@ -0,0 +1,222 @@
+//! Module for the launch configurations of a training plan: an `accelerate` config file, a
+//! DeepSpeed config file or a `torchrun` command line.
+use serde_json::json;
+
+use crate::estimate::{Checkpointing, Sharding, TrainingSetup};
+use crate::models::Precision;
+
+/// A struct representing how a training is launched on the GPUs of a single machine
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LaunchConfig {
+    /// The training plan
+    setup: TrainingSetup,
+}
+
+/// LaunchConfig implementation
+impl LaunchConfig {
+    /// Build a new `LaunchConfig` launching a training plan, one process per GPU
+    pub fn new(setup: TrainingSetup) -> LaunchConfig {
+        LaunchConfig { setup }
+    }
+    /// Returns the number of processes, one per GPU
+    pub fn num_processes(&self) -> i32 {
+        self.setup.num_gpus.max(1)
+    }
+    /// Returns the `mixed_precision` of accelerate: `no`, `fp16`, `bf16` or `fp8`
+    pub fn mixed_precision(&self) -> &'static str {
+        match self.setup.precision {
+            Precision::FP16 => "fp16",
+            Precision::BF16 => "bf16",
+            Precision::FP8 => "fp8",
+            _ => "no",
+        }
+    }
+    /// Returns the `distributed_type` of accelerate
+    pub fn distributed_type(&self) -> &'static str {
+        match self.setup.sharding {
+            Sharding::Zero1 | Sharding::Zero2 | Sharding::Zero3 => "DEEPSPEED",
+            Sharding::FsdpShardGradOp | Sharding::FsdpFullShard => "FSDP",
+            Sharding::Disabled if self.num_processes() > 1 => "MULTI_GPU",
+            Sharding::Disabled => "NO",
+        }
+    }
+    /// Returns the DeepSpeed ZeRO stage, `None` without DeepSpeed
+    pub fn zero_stage(&self) -> Option<i32> {
+        match self.setup.sharding {
+            Sharding::Zero1 => Some(1),
+            Sharding::Zero2 => Some(2),
+            Sharding::Zero3 => Some(3),
+            _ => None,
+        }
+    }
+    /// Returns the sharding strategy of FSDP, `None` without FSDP
+    pub fn fsdp_sharding_strategy(&self) -> Option<&'static str> {
+        match self.setup.sharding {
+            Sharding::FsdpShardGradOp => Some("SHARD_GRAD_OP"),
+            Sharding::FsdpFullShard => Some("FULL_SHARD"),
+            _ => None,
+        }
+    }
+    /// Render the `accelerate` config file (`accelerate launch --config_file`)
+    pub fn to_accelerate_yaml(&self) -> String {
+        let mut yaml = format!(
+            "compute_environment: LOCAL_MACHINE\n\
+             distributed_type: {}\n\
+             mixed_precision: {}\n\
+             num_machines: 1\n\
+             num_processes: {}\n\
+             machine_rank: 0\n\
+             main_training_function: main\n\
+             rdzv_backend: static\n\
+             same_network: true\n\
+             use_cpu: false\n",
+            self.distributed_type(),
+            self.mixed_precision(),
+            self.num_processes(),
+        );
+        let checkpointing = self.setup.checkpointing != Checkpointing::Disabled;
+        if let Some(strategy) = self.fsdp_sharding_strategy() {
+            yaml.push_str(&format!(
+                "fsdp_config:\n  \
+                   fsdp_sharding_strategy: {}\n  \
+                   fsdp_auto_wrap_policy: TRANSFORMER_BASED_WRAP\n  \
+                   fsdp_backward_prefetch: BACKWARD_PRE\n  \
+                   fsdp_state_dict_type: SHARDED_STATE_DICT\n  \
+                   fsdp_offload_params: false\n  \
+                   fsdp_activation_checkpointing: {}\n  \
+                   fsdp_cpu_ram_efficient_loading: true\n  \
+                   fsdp_sync_module_states: true\n  \
+                   fsdp_use_orig_params: true\n",
+                strategy, checkpointing,
+            ));
+        }
+        if let Some(stage) = self.zero_stage() {
+            yaml.push_str(&format!(
+                "deepspeed_config:\n  \
+                   zero_stage: {}\n  \
+                   gradient_accumulation_steps: 1\n  \
+                   offload_optimizer_device: none\n  \
+                   offload_param_device: none\n  \
+                   zero3_init_flag: {}\n",
+                stage,
+                stage == 3,
+            ));
+        }
+        yaml
+    }
+    /// Render the DeepSpeed config file the Transformers `Trainer` is given with `--deepspeed`,
+    /// `None` without DeepSpeed
+    pub fn to_deepspeed_json(&self) -> Option<String> {
+        let stage = self.zero_stage()?;
+        let precision = self.mixed_precision();
+        let config = json!({
+            "zero_optimization": {
+                "stage": stage,
+                "overlap_comm": true,
+                "contiguous_gradients": true,
+            },
+            "fp16": {"enabled": precision == "fp16"},
+            "bf16": {"enabled": precision == "bf16"},
+            "train_micro_batch_size_per_gpu": self.setup.batch_size.max(1),
+            "gradient_accumulation_steps": 1,
+            "gradient_clipping": "auto",
+        });
+        serde_json::to_string_pretty(&config).ok()
+    }
+    /// Render the `torchrun` command line launching a Transformers `Trainer` script, the
+    /// DeepSpeed config being read from `ds_config.json`
+    pub fn to_torchrun_command(&self, script: &str) -> String {
+        let mut command = format!(
+            "torchrun --standalone --nproc_per_node {} {} --per_device_train_batch_size {}",
+            self.num_processes(),
+            script,
+            self.setup.batch_size.max(1),
+        );
+        match self.mixed_precision() {
+            "fp16" => command.push_str(" --fp16"),
+            "bf16" => command.push_str(" --bf16"),
+            _ => {}
+        }
+        if self.setup.checkpointing != Checkpointing::Disabled {
+            command.push_str(" --gradient_checkpointing");
+        }
+        if let Some(strategy) = self.fsdp_sharding_strategy() {
+            command.push_str(&format!(
+                " --fsdp \"{} auto_wrap\"",
+                strategy.to_lowercase()
+            ));
+        }
+        if self.zero_stage().is_some() {
+            command.push_str(" --deepspeed ds_config.json");
+        }
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate::Optimizer;
+
+    fn create_setup(sharding: Sharding, num_gpus: i32) -> TrainingSetup {
+        let mut setup = TrainingSetup::new(4, 2048, Precision::BF16, Optimizer::AdamW);
+        setup.sharding = sharding;
+        setup.num_gpus = num_gpus;
+        setup
+    }
+
+    #[test]
+    fn test_launch_config_fsdp() {
+        let mut setup = create_setup(Sharding::FsdpFullShard, 8);
+        setup.checkpointing = Checkpointing::Full;
+        let config = LaunchConfig::new(setup);
+        let yaml = config.to_accelerate_yaml();
+        assert!(yaml.contains("distributed_type: FSDP\n"));
+        assert!(yaml.contains("mixed_precision: bf16\n"));
+        assert!(yaml.contains("num_processes: 8\n"));
+        assert!(yaml.contains("  fsdp_sharding_strategy: FULL_SHARD\n"));
+        assert!(yaml.contains("  fsdp_activation_checkpointing: true\n"));
+        assert!(!yaml.contains("deepspeed_config"));
+        assert_eq!(
+            config.to_torchrun_command("train.py"),
+            "torchrun --standalone --nproc_per_node 8 train.py --per_device_train_batch_size 4 \
+             --bf16 --gradient_checkpointing --fsdp \"full_shard auto_wrap\""
+        );
+        assert!(config.to_deepspeed_json().is_none());
+    }
+
+    #[test]
+    fn test_launch_config_deepspeed() {
+        let config = LaunchConfig::new(create_setup(Sharding::Zero3, 4));
+        let yaml = config.to_accelerate_yaml();
+        assert!(yaml.contains("distributed_type: DEEPSPEED\n"));
+        assert!(yaml.contains("  zero_stage: 3\n"));
+        assert!(yaml.contains("  zero3_init_flag: true\n"));
+        assert!(config
+            .to_torchrun_command("train.py")
+            .ends_with(" --deepspeed ds_config.json"));
+
+        let ds_config: serde_json::Value =
+            serde_json::from_str(&config.to_deepspeed_json().unwrap()).unwrap();
+        assert_eq!(ds_config["zero_optimization"]["stage"], 3);
+        assert_eq!(ds_config["bf16"]["enabled"], true);
+        assert_eq!(ds_config["train_micro_batch_size_per_gpu"], 4);
+    }
+
+    #[test]
+    fn test_launch_config_data_parallel() {
+        assert_eq!(
+            LaunchConfig::new(create_setup(Sharding::Disabled, 2)).distributed_type(),
+            "MULTI_GPU"
+        );
+        let mut setup = create_setup(Sharding::Disabled, 1);
+        setup.precision = Precision::FP32;
+        let config = LaunchConfig::new(setup);
+        assert_eq!(config.distributed_type(), "NO");
+        assert_eq!(config.mixed_precision(), "no");
+        assert_eq!(
+            config.to_torchrun_command("train.py"),
+            "torchrun --standalone --nproc_per_node 1 train.py --per_device_train_batch_size 4"
+        );
+    }
+}
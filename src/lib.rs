@@ -11,14 +11,105 @@
 //! With **AIHA**, the guessing game is over. Say goodbye to uncertainty and welcome a world of precise resource allocation
 //! for inference and training any model on the esteemed Hugging Face Hub.
 //!
+pub mod advisor;
+pub mod backend;
+pub mod bench;
+pub mod calibration;
+pub mod estimate;
+pub mod gate;
 pub mod hardware;
 pub mod hub;
+pub mod launch;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod logging;
 pub mod models;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod report;
+pub mod specs;
+pub mod table;
+#[cfg(all(feature = "cli", not(target_arch = "wasm32")))]
+pub mod user_config;
+pub mod watch;
 
-pub use hardware::{scan_hardware, Hardware, NvidiaDevice};
-pub use hub::{build_headers, ModelFile, ModelInfo, Siblings, CUSTOM_ENCODE_SET, HUB_ENDPOINT};
+pub use advisor::{
+    check_attention, check_fit, check_fit_calibrated, check_fit_cpu, check_fit_cpu_measured,
+    check_fit_tensor_parallel, check_fit_unified_memory, check_fit_with_backend, check_gguf_file,
+    check_kernels, check_local_model, check_offload, check_repo, check_repo_with_assertions,
+    check_repos, check_repos_with_progress, compare, compare_repos, compare_repos_with_progress,
+    estimate_repo_training, estimate_serving_costs, estimate_serving_costs_with_backend,
+    estimate_training_on_dataset, estimate_training_run, max_context_length,
+    max_serving_concurrency, minimum_requirements, parse_model_list, plan_pipeline_parallel,
+    plan_serving_capacity, recommend_instances, recommend_quantization, search_fitting_models,
+    search_fitting_models_with_progress, suggest_instances, suggest_repo_instances, watch_repo,
+    AttentionReport, Explanation, FitReport, FitVerdict, InstanceRecommendation, KernelReport,
+    MemoryComponent, ModelRef, Scenario, ScenarioMemory, TrainingDataset, BATCH_CONCURRENCY,
+};
+pub use backend::{AnalyticBackend, EstimatorBackend};
+pub use bench::{
+    measure_memory_bandwidth, run_bench, BenchResult, BENCH_FILE, DEFAULT_BENCH_BUFFER_BYTES,
+    DEFAULT_BENCH_REPEATS,
+};
+pub use calibration::{default_aiha_cache_dir, Calibration, CalibrationStore, CALIBRATION_FILE};
+pub use estimate::{
+    activation_bytes_per_layer, estimate_activation_memory, estimate_asr, estimate_asr_speed,
+    estimate_asr_with_overhead, estimate_attention, estimate_cpu_inference, estimate_data_pipeline,
+    estimate_embedding, estimate_embedding_throughput, estimate_embedding_with_overhead,
+    estimate_inference_memory, estimate_inference_memory_with_overhead, estimate_kv_cache,
+    estimate_kv_cache_with_sliding_window, estimate_lora, estimate_offload,
+    estimate_optimizer_memory, estimate_quantization_tradeoffs, estimate_quantized_inference,
+    estimate_serving, estimate_serving_cost, estimate_speculative,
+    estimate_speculative_with_overhead, estimate_tensor_parallel,
+    estimate_tensor_parallel_with_overhead, estimate_throughput, estimate_training,
+    estimate_training_time, estimate_unified_memory, flops_per_token, kv_cache_bytes_per_token,
+    pipeline_bubble, plan_capacity, plan_pipeline, plan_pipeline_with_overhead, quality_loss,
+    quantized_weights_bytes, speculative_speedup, tensor_parallel_sizes, AsrEstimate,
+    AttentionEstimate, AttentionImpl, CapacityPlan, Checkpointing, CpuEstimate, CpuSetup,
+    DataPipelineEstimate, DataPipelineSetup, EmbeddingEstimate, FlopsBreakdown, InferenceMemory,
+    LoraEstimate, LoraSetup, LoraTarget, OffloadEstimate, OffloadSetup, Optimizer, OverheadModel,
+    Phase, PipelinePlan, PipelineStage, Pricing, QualityLoss, ServingCost, ServingEstimate,
+    ServingSetup, ServingSlo, Sharding, SpeculativeEstimate, TensorParallelMemory, Throughput,
+    TrainingEstimate, TrainingSetup, UnifiedMemoryEstimate, UnifiedMemorySetup, TIGHT_MARGIN,
+};
+pub use gate::{
+    check_assertions, exit_code, Assertion, AssertionResult, Comparator, EXIT_FAILED, EXIT_INVALID,
+    EXIT_PASSED,
+};
+pub use hardware::{
+    scan_hardware, AmdDevice, GPUDevice, Hardware, NvidiaDevice, NO_COMPUTE_CAPABILITY,
+};
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub use hub::BlockingHubClient;
+pub use hub::{
+    build_headers, parse_parameter_count, CommitSha, DatasetInfo, DatasetSplit, GgufFile,
+    GgufTensor, GgufValue, HubClient, HubError, ModelFile, ModelInfo, OnnxInfo, PipelineComponent,
+    PipelineInfo, RepoKind, SearchQuery, SearchResult, Siblings, CUSTOM_ENCODE_SET, HUB_ENDPOINT,
+};
+pub use launch::LaunchConfig;
+#[cfg(not(target_arch = "wasm32"))]
+pub use logging::{init_tracing, TracingOptions, DEFAULT_LOG_FILTER, VERBOSE_LOG_FILTER};
 pub use models::{
-    BertModelConfig, BertParams, BloomModelConfig, BloomParams, GPT2ModelConfig, GPT2Params,
-    GPTJModelConfig, GPTJParams, GPTNeoModelConfig, GPTNeoParams, LlamaModelConfig, LlamaParams,
-    ModelConfigTrait, ModelLibraries, OPTModelConfig, OPTParams, T5ModelConfig, T5Params,
+    BartModelConfig, BartParams, BertModelConfig, BertParams, BloomModelConfig, BloomParams,
+    CLIPModelConfig, CodeGenModelConfig, CodeGenParams, CompositeModelConfig, DeepSeekMLAParams,
+    DeepSeekMoEParams, DeepSeekModelConfig, DeepSeekParams, DetrModelConfig, DetrParams,
+    DiffusionPipelineConfig, Dtype, GPT2ModelConfig, GPT2Params, GPTJModelConfig, GPTJParams,
+    GPTNeoModelConfig, GPTNeoParams, GenericModelConfig, GenericParams, InternLMModelConfig,
+    InternLMParams, KnownModel, LlamaModelConfig, LlamaParams, MarianModelConfig,
+    MixtralModelConfig, MixtralParams, ModelConfigTrait, ModelFamily, ModelLibraries,
+    OPTModelConfig, OPTParams, ParameterBreakdown, PegasusModelConfig, Precision, QuantKernel,
+    QuantType, RobertaModelConfig, RobertaParams, RopeParams, RopeScaling, RopeScalingType,
+    SegformerModelConfig, SegformerParams, SwinModelConfig, SwinParams, T5ModelConfig, T5Params,
+    TextEncoderConfig, TrOCRModelConfig, TrOCRParams, UNetConfig, VAEConfig, ViTModelConfig,
+    ViTParams, VisionEncoderDecoderModelConfig, WhisperModelConfig,
+};
+pub use report::{
+    BatchEntry, BatchReport, CloudReport, CloudRow, Comparison, ComparisonRow, DeviceSection,
+    OutputFormat, Report, Requirements, TrainingReport,
 };
+pub use specs::{AppleChipSpec, CloudProvider, GpuSpec, InstanceSpec};
+pub use table::TableOptions;
+#[cfg(all(feature = "cli", not(target_arch = "wasm32")))]
+pub use user_config::{default_config_path, TokenSource, UserConfig, CONFIG_FILE};
+#[cfg(all(feature = "nvml", not(target_arch = "wasm32")))]
+pub use watch::{sample_gpus, watch};
+pub use watch::{Divergence, GpuSample, Watcher, DEFAULT_DIVERGENCE_TOLERANCE};
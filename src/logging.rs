@@ -0,0 +1,120 @@
+//! Module for printing the traces of aiha: the spans of the hub requests, of the hardware scans
+//! and of the estimates, with their duration, and the events they record (e.g. the cache hits)
+use std::env;
+
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::EnvFilter;
+
+/// Filter of the traces when the `RUST_LOG` environment variable isn't set
+pub const DEFAULT_LOG_FILTER: &str = "aiha=info";
+/// Filter of the traces in verbose mode, the spans being debug ones
+pub const VERBOSE_LOG_FILTER: &str = "aiha=debug";
+
+/// A struct representing how the traces are printed on the standard error
+#[derive(Clone, Debug, PartialEq)]
+pub struct TracingOptions {
+    /// The filter of the traces, in the syntax of `RUST_LOG` (e.g. `aiha=debug,reqwest=info`)
+    filter: String,
+    /// Whether the closed spans are printed with their duration
+    span_durations: bool,
+    /// Whether the levels are colored
+    color: bool,
+}
+
+/// TracingOptions implementation
+impl TracingOptions {
+    /// Build new `TracingOptions` filtering the traces with the `RUST_LOG` environment variable,
+    /// `DEFAULT_LOG_FILTER` when it isn't set, and printing the closed spans with their duration.
+    /// The colors are disabled by the `NO_COLOR` environment variable.
+    pub fn new() -> TracingOptions {
+        TracingOptions {
+            filter: env::var("RUST_LOG")
+                .ok()
+                .filter(|filter| !filter.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_LOG_FILTER.to_string()),
+            span_durations: true,
+            color: env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()),
+        }
+    }
+    /// Print the debug traces of aiha, the spans included
+    pub fn verbose(self) -> Self {
+        self.with_filter(VERBOSE_LOG_FILTER)
+    }
+    /// Set the filter of the traces, in the syntax of `RUST_LOG`
+    pub fn with_filter(mut self, filter: &str) -> Self {
+        self.filter = filter.to_string();
+        self
+    }
+    /// Don't print the closed spans, only the events
+    pub fn without_span_durations(mut self) -> Self {
+        self.span_durations = false;
+        self
+    }
+    /// Disable the colors of the levels
+    pub fn no_color(mut self) -> Self {
+        self.color = false;
+        self
+    }
+    /// Returns the filter of the traces
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+    /// Returns whether the closed spans are printed with their duration
+    pub fn span_durations(&self) -> bool {
+        self.span_durations
+    }
+    /// Returns whether the levels are colored
+    pub fn color(&self) -> bool {
+        self.color
+    }
+}
+
+/// Implement the default TracingOptions, see `TracingOptions::new`
+impl Default for TracingOptions {
+    fn default() -> Self {
+        TracingOptions::new()
+    }
+}
+
+/// Set the global subscriber of the traces, printing them on the standard error. Returns an
+/// error when the filter is invalid or a global subscriber is already set.
+pub fn init_tracing(options: &TracingOptions) -> Result<(), String> {
+    let filter = EnvFilter::try_new(&options.filter)
+        .map_err(|e| format!("Invalid log filter {}: {}", options.filter, e))?;
+    let span_events = match options.span_durations {
+        true => FmtSpan::CLOSE,
+        false => FmtSpan::NONE,
+    };
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(span_events)
+        .with_ansi(options.color)
+        .with_writer(std::io::stderr)
+        .try_init()
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracing_options() {
+        let options = TracingOptions::new().verbose().no_color();
+        assert_eq!(options.filter(), VERBOSE_LOG_FILTER);
+        assert!(options.span_durations());
+        assert!(!options.color());
+        let options = options
+            .with_filter("aiha=trace,reqwest=info")
+            .without_span_durations();
+        assert_eq!(options.filter(), "aiha=trace,reqwest=info");
+        assert!(!options.span_durations());
+    }
+
+    #[test]
+    fn test_init_tracing_invalid_filter() {
+        let options = TracingOptions::new().with_filter("aiha=loud");
+        let error = init_tracing(&options).unwrap_err();
+        assert!(error.starts_with("Invalid log filter aiha=loud"));
+    }
+}
@@ -0,0 +1,1107 @@
+//! The `aiha` command line: checks whether the models of the Hugging Face Hub fit the running
+//! system, on top of the `aiha` library
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+#[cfg(all(feature = "nvml", not(target_arch = "wasm32")))]
+use std::time::Duration;
+
+use aiha::hub::{parse_parameter_count, GgufFile, ModelConfig, SearchQuery, DEFAULT_SEARCH_LIMIT};
+use aiha::{
+    check_assertions, check_gguf_file, check_local_model, check_repo, check_repo_with_assertions,
+    check_repos_with_progress, compare_repos_with_progress, default_aiha_cache_dir,
+    estimate_repo_training, exit_code, init_tracing, parse_model_list, run_bench, scan_hardware,
+    search_fitting_models_with_progress, suggest_repo_instances, Assertion, AssertionResult,
+    BenchResult, Checkpointing, GpuSpec, LoraSetup, LoraTarget, Optimizer, OutputFormat, Precision,
+    Pricing, Scenario, Sharding, TableOptions, TokenSource, TracingOptions, TrainingDataset,
+    TrainingSetup, UserConfig, BENCH_FILE, EXIT_INVALID,
+};
+#[cfg(all(feature = "nvml", not(target_arch = "wasm32")))]
+use aiha::{
+    watch, watch_repo, CalibrationStore, Divergence, GpuSample, DEFAULT_DIVERGENCE_TOLERANCE,
+};
+use clap::{ArgAction, Args, Parser, Subcommand};
+use indicatif::{ProgressBar, ProgressStyle};
+
+/// Precision of the checks when neither the flags nor the config file set one
+const DEFAULT_PRECISION: Precision = Precision::FP16;
+/// Filter of the traces with `-vv`, every trace of aiha
+const TRACE_LOG_FILTER: &str = "aiha=trace";
+/// Precision of the training runs when neither the flags nor the config file set one
+const DEFAULT_TRAINING_PRECISION: Precision = Precision::BF16;
+/// Number of bytes in a GiB
+const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+/// 🦉 AIHA helps you to find the minimal requirements for any model on the 🤗 Hub
+#[derive(Debug, Parser)]
+#[command(name = "aiha", version)]
+struct Cli {
+    /// The command to run
+    #[command(subcommand)]
+    command: Command,
+    /// Print the output in JSON, for scripts and CI pipelines
+    #[arg(long, global = true, conflicts_with = "yaml")]
+    json: bool,
+    /// Print the output in YAML, for scripts and CI pipelines
+    #[arg(long, global = true)]
+    yaml: bool,
+    /// The user configuration file, `~/.config/aiha/config.toml` when not set
+    #[arg(long, global = true, value_name = "FILE")]
+    config: Option<PathBuf>,
+    /// Where the Hub token is read from: env (HF_TOKEN), huggingface (the token saved by
+    /// `huggingface-cli login`) or the path of a file
+    #[arg(long, global = true, value_name = "SOURCE")]
+    token_source: Option<TokenSource>,
+    /// The Hub endpoint, tried before the Hugging Face Hub
+    #[arg(long, global = true, value_name = "URL")]
+    endpoint: Option<String>,
+    /// The share of the device memory kept free, e.g. 0.1
+    #[arg(long, global = true, value_name = "SHARE", value_parser = parse_share)]
+    safety_margin: Option<f64>,
+    /// Don't color the tables nor the traces, like the `NO_COLOR` environment variable
+    #[arg(long, global = true)]
+    no_color: bool,
+    /// Print plain ASCII tables, neither wrapped nor colored, e.g. for the logs
+    #[arg(long, global = true)]
+    plain: bool,
+    /// Print the debug traces of aiha on the standard error, `-vv` for every trace
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    verbose: u8,
+}
+
+/// Cli implementation
+impl Cli {
+    /// Returns the format selected by the flags, if any
+    fn output_format(&self) -> Option<OutputFormat> {
+        match (self.json, self.yaml) {
+            (true, _) => Some(OutputFormat::Json),
+            (_, true) => Some(OutputFormat::Yaml),
+            _ => None,
+        }
+    }
+    /// Returns the values set on the command line, overriding the configuration file
+    fn flags(&self) -> UserConfig {
+        UserConfig {
+            token_source: self.token_source.clone(),
+            endpoint: self.endpoint.clone(),
+            precision: self.command.precision(),
+            safety_margin: self.safety_margin,
+            output_format: self.output_format(),
+        }
+    }
+    /// Returns how the traces are printed, `RUST_LOG` being overridden by `-v`
+    fn tracing_options(&self) -> TracingOptions {
+        let options = match self.no_color || self.plain {
+            true => TracingOptions::new().no_color(),
+            false => TracingOptions::new(),
+        };
+        match self.verbose {
+            0 => options,
+            1 => options.verbose(),
+            _ => options.with_filter(TRACE_LOG_FILTER),
+        }
+    }
+    /// Returns how the tables are rendered, `NO_COLOR` disabling the colors too
+    fn table_options(&self) -> TableOptions {
+        match (self.plain, self.no_color) {
+            (true, _) => TableOptions::new().plain(),
+            (_, true) => TableOptions::new().no_color(),
+            _ => TableOptions::new(),
+        }
+    }
+    /// Load the configuration file, `--config` or the default one, and apply the flags on top
+    fn user_config(&self) -> io::Result<UserConfig> {
+        let config = match &self.config {
+            Some(path) => UserConfig::load(path)?,
+            None => UserConfig::load_default()?,
+        };
+        Ok(config.merge(self.flags()))
+    }
+}
+
+/// Parse a share of the device memory, in [0, 1)
+fn parse_share(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(share) if (0.0..1.0).contains(&share) => Ok(share),
+        _ => Err(format!("Expected a share in [0, 1), got {}", value)),
+    }
+}
+
+/// Enumerate the commands of the command line
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Check whether a model of the Hub fits the GPUs of the running system
+    Check(CheckArgs),
+    /// Write the report of a model of the Hub, in the format of the file extension
+    Report(ReportArgs),
+    /// Compare models of the Hub side by side on the GPU of the running system
+    Compare(CompareArgs),
+    /// Suggest the cloud instances able to serve a model of the Hub, with their cost
+    Cloud(CloudArgs),
+    /// Measure the memory bandwidth of the running system, for the CPU estimates of
+    /// `check --use-measured`
+    Bench,
+    /// Query the Hugging Face Hub
+    Hub(HubArgs),
+    /// Estimate the workloads of a model of the Hub beyond inference
+    Estimate(EstimateArgs),
+    /// Watch the memory the GPUs use while a model of the Hub runs, against the estimates, and
+    /// record it in the calibration store
+    #[cfg(all(feature = "nvml", not(target_arch = "wasm32")))]
+    Watch(WatchArgs),
+}
+
+/// Command implementation
+impl Command {
+    /// Returns the precision of the checks set on the command line, if any. The precision of a
+    /// training run is its own, the one of the configuration file applying to the checks only.
+    fn precision(&self) -> Option<Precision> {
+        match self {
+            Command::Check(args) => args.scenario.precision,
+            Command::Report(args) => args.scenario.precision,
+            Command::Compare(args) => args.scenario.precision,
+            Command::Cloud(args) => args.scenario.precision,
+            Command::Bench => None,
+            Command::Hub(args) => match &args.command {
+                HubCommand::Search(args) => args.scenario.precision,
+            },
+            Command::Estimate(_) => None,
+            #[cfg(all(feature = "nvml", not(target_arch = "wasm32")))]
+            Command::Watch(args) => args.scenario.precision,
+        }
+    }
+}
+
+/// The arguments of `aiha check`
+#[derive(Debug, Args)]
+struct CheckArgs {
+    /// The repository ID of the model (e.g. meta-llama/Meta-Llama-3-8B), the folder of a local
+    /// checkpoint with its config.json, or a GGUF file of llama.cpp (its quantization replacing
+    /// the precision)
+    #[arg(required_unless_present = "input")]
+    model: Option<String>,
+    /// A file listing the repository IDs of the models to check, one per line with `#` comments,
+    /// or `-` for the standard input
+    #[arg(short, long, conflicts_with_all = ["model", "requirements"])]
+    input: Option<PathBuf>,
+    /// An assertion on the running system gating the exit code, e.g. "vram>=24GB", "gpus>=2",
+    /// "cc>=8.0" or "fits:int4", repeatable
+    #[arg(long = "require", value_name = "ASSERTION")]
+    requirements: Vec<Assertion>,
+    /// Estimate the CPU speed of a GGUF file with the memory bandwidth measured by `aiha bench`,
+    /// the only models checked on the CPU
+    #[arg(long, conflicts_with = "input")]
+    use_measured: bool,
+    /// The inference workload
+    #[command(flatten)]
+    scenario: ScenarioArgs,
+}
+
+/// The arguments of `aiha report`
+#[derive(Debug, Args)]
+struct ReportArgs {
+    /// The repository ID of the model, e.g. meta-llama/Meta-Llama-3-8B
+    repo_id: String,
+    /// The file the report is written to: markdown (.md), HTML (.html), JSON (.json) or YAML
+    /// (.yaml), printed when not set
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// The inference workload
+    #[command(flatten)]
+    scenario: ScenarioArgs,
+}
+
+/// The arguments of `aiha compare`
+#[derive(Debug, Args)]
+struct CompareArgs {
+    /// The repository IDs of the models, at least two
+    #[arg(required = true, num_args = 2..)]
+    repo_ids: Vec<String>,
+    /// The inference workload
+    #[command(flatten)]
+    scenario: ScenarioArgs,
+}
+
+/// The arguments of `aiha cloud`
+#[derive(Debug, Args)]
+struct CloudArgs {
+    /// The repository ID of the model, e.g. meta-llama/Meta-Llama-3-8B
+    repo_id: String,
+    /// The generation speed the instances must reach, in tokens per second
+    #[arg(long, value_name = "TOKENS_PER_SECOND", default_value_t = 0.0)]
+    min_tps: f64,
+    /// The inference workload
+    #[command(flatten)]
+    scenario: ScenarioArgs,
+}
+
+/// The arguments of `aiha hub`
+#[derive(Debug, Args)]
+struct HubArgs {
+    /// The query to run
+    #[command(subcommand)]
+    command: HubCommand,
+}
+
+/// Enumerate the queries of the Hugging Face Hub
+#[derive(Debug, Subcommand)]
+enum HubCommand {
+    /// Search the models of the Hub, the most downloaded first
+    Search(SearchArgs),
+}
+
+/// The arguments of `aiha hub search`
+#[derive(Debug, Args)]
+struct SearchArgs {
+    /// The text the repository IDs contain
+    search: Option<String>,
+    /// The pipeline tag of the models, e.g. text-generation
+    #[arg(long)]
+    task: Option<String>,
+    /// The largest number of parameters, e.g. 8B, the models without a known count being left
+    /// out
+    #[arg(long, value_name = "COUNT", value_parser = parse_params)]
+    max_params: Option<i64>,
+    /// The library of the models, e.g. transformers or safetensors
+    #[arg(long)]
+    library: Option<String>,
+    /// The largest number of models searched
+    #[arg(long, default_value_t = DEFAULT_SEARCH_LIMIT)]
+    limit: usize,
+    /// Only keep the models fitting a GPU of the running system, printed in a table
+    #[arg(long)]
+    fits: bool,
+    /// The inference workload the models are checked for with `--fits`
+    #[command(flatten)]
+    scenario: ScenarioArgs,
+}
+
+/// SearchArgs implementation
+impl SearchArgs {
+    /// Build the search query of the flags
+    fn query(&self) -> SearchQuery {
+        let query = SearchQuery::new().with_limit(self.limit);
+        let query = match &self.search {
+            Some(search) => query.with_search(search),
+            None => query,
+        };
+        let query = match &self.task {
+            Some(task) => query.with_task(task),
+            None => query,
+        };
+        let query = match &self.library {
+            Some(library) => query.with_library(library),
+            None => query,
+        };
+        match self.max_params {
+            Some(max_params) => query.with_max_params(max_params),
+            None => query,
+        }
+    }
+}
+
+/// The arguments of `aiha estimate`
+#[derive(Debug, Args)]
+struct EstimateArgs {
+    /// The workload to estimate
+    #[command(subcommand)]
+    command: EstimateCommand,
+}
+
+/// Enumerate the workloads `aiha estimate` estimates
+#[derive(Debug, Subcommand)]
+enum EstimateCommand {
+    /// Estimate the memory, the time and the cost of a training run on a dataset
+    Train(TrainArgs),
+}
+
+/// The arguments of `aiha estimate train`
+#[derive(Debug, Args)]
+struct TrainArgs {
+    /// The repository ID of the model, e.g. meta-llama/Meta-Llama-3-8B
+    repo_id: String,
+    /// The dataset: its ID on the Hub, its number of tokens being estimated from its size, or a
+    /// number of tokens such as 10B, all the epochs included
+    #[arg(long)]
+    dataset: TrainingDataset,
+    /// The GPU the run trains on, e.g. A100-SXM4-80GB, the first GPU of the running system when
+    /// not set
+    #[arg(long)]
+    gpu: Option<String>,
+    /// The number of data parallel GPUs of a full fine-tuning
+    #[arg(long, default_value_t = 1, conflicts_with_all = ["lora", "qlora"])]
+    num_gpus: i32,
+    /// The optimizer, e.g. adamw, adamw-8bit, sgd or adafactor
+    #[arg(long, default_value = "adamw")]
+    optimizer: Optimizer,
+    /// Train LoRA adapters on every linear layer of the frozen model
+    #[arg(long, conflicts_with = "qlora")]
+    lora: bool,
+    /// Train LoRA adapters on every linear layer of the model quantized in NF4 (QLoRA)
+    #[arg(long)]
+    qlora: bool,
+    /// The rank of the LoRA adapters
+    #[arg(long, default_value_t = 16)]
+    rank: i32,
+    /// The DeepSpeed ZeRO stage the training states are sharded with, from 0 to 3
+    #[arg(long, value_name = "STAGE", default_value = "0", conflicts_with_all = ["lora", "qlora"])]
+    zero: Sharding,
+    /// Recompute the activations during the backward pass (gradient checkpointing)
+    #[arg(long)]
+    checkpointing: bool,
+    /// The precision the model is trained in, or the one of the frozen weights with `--lora`, BF16
+    /// when not set (the precision of the configuration file only applies to the checks)
+    #[arg(short, long, conflicts_with = "qlora")]
+    precision: Option<Precision>,
+    /// The micro batch size of every GPU
+    #[arg(short, long, default_value_t = 1)]
+    batch_size: i32,
+    /// The length of the training sequences
+    #[arg(short, long, default_value_t = 2048)]
+    seq_len: i32,
+    /// The hourly price of a GPU in US dollars, for the cost of the run
+    #[arg(long, value_name = "USD")]
+    gpu_price: Option<f64>,
+}
+
+/// TrainArgs implementation
+impl TrainArgs {
+    /// Build the training scenario of the flags, in the precision of the flags or BF16, with the
+    /// safety margin of the user configuration
+    fn scenario(&self, config: &UserConfig) -> Scenario {
+        let precision = self.precision.unwrap_or(DEFAULT_TRAINING_PRECISION);
+        let checkpointing = match self.checkpointing {
+            true => Checkpointing::Full,
+            false => Checkpointing::Disabled,
+        };
+        if self.lora || self.qlora {
+            let base_precision = match self.qlora {
+                true => Precision::NF4,
+                false => precision,
+            };
+            let mut setup = LoraSetup::new(self.rank, LoraTarget::all_linear(), base_precision);
+            setup.optimizer = self.optimizer;
+            setup.batch_size = self.batch_size;
+            setup.seq_len = self.seq_len;
+            setup.checkpointing = checkpointing;
+            setup.overhead = config.overhead();
+            return Scenario::Lora(setup);
+        }
+        let mut setup =
+            TrainingSetup::new(self.batch_size, self.seq_len, precision, self.optimizer);
+        setup.checkpointing = checkpointing;
+        setup.sharding = self.zero;
+        setup.num_gpus = self.num_gpus;
+        setup.overhead = config.overhead();
+        Scenario::Training(setup)
+    }
+}
+
+/// The arguments of `aiha watch`
+#[cfg(all(feature = "nvml", not(target_arch = "wasm32")))]
+#[derive(Debug, Args)]
+struct WatchArgs {
+    /// The repository ID of the model, e.g. meta-llama/Meta-Llama-3-8B
+    repo_id: String,
+    /// The time between two samples of the GPUs, in seconds
+    #[arg(long, value_name = "SECONDS", default_value_t = 1.0, value_parser = parse_seconds)]
+    interval: f64,
+    /// How long the GPUs are watched, in seconds
+    #[arg(long, value_name = "SECONDS", default_value_t = 60.0, value_parser = parse_seconds)]
+    duration: f64,
+    /// The share of the estimate the used memory can diverge by before it is flagged
+    #[arg(long, value_name = "SHARE", default_value_t = DEFAULT_DIVERGENCE_TOLERANCE)]
+    tolerance: f64,
+    /// Don't record the peaks in the calibration store
+    #[arg(long)]
+    no_record: bool,
+    /// The inference workload
+    #[command(flatten)]
+    scenario: ScenarioArgs,
+}
+
+/// The arguments describing an inference workload
+#[derive(Debug, Args)]
+struct ScenarioArgs {
+    /// The precision the weights are loaded in, e.g. fp16, bf16, int8 or int4
+    #[arg(short, long)]
+    precision: Option<Precision>,
+    /// The number of sequences generated at once
+    #[arg(short, long, default_value_t = 1)]
+    batch_size: i32,
+    /// The length of the sequences, prompt included
+    #[arg(short, long, default_value_t = 2048)]
+    seq_len: i32,
+}
+
+/// ScenarioArgs implementation
+impl ScenarioArgs {
+    /// Build the inference scenario of the flags, with the precision and the safety margin of the
+    /// user configuration, the flags merged in
+    fn scenario(&self, config: &UserConfig) -> Scenario {
+        Scenario::Inference {
+            precision: config.precision.unwrap_or(DEFAULT_PRECISION),
+            batch_size: self.batch_size,
+            seq_len: self.seq_len,
+            overhead: config.overhead(),
+            sliding_window: false,
+        }
+    }
+}
+
+/// Parse a positive number of seconds
+#[cfg(all(feature = "nvml", not(target_arch = "wasm32")))]
+fn parse_seconds(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(seconds) if seconds > 0.0 && seconds.is_finite() => Ok(seconds),
+        _ => Err(format!(
+            "Expected a positive number of seconds, got {}",
+            value
+        )),
+    }
+}
+
+/// Parse a number of parameters, e.g. 8B or 1.5b
+fn parse_params(value: &str) -> Result<i64, String> {
+    parse_parameter_count(value)
+        .ok_or_else(|| format!("Expected a number of parameters such as 8B, got {}", value))
+}
+
+/// Print a rendered output, ending with a newline
+fn print_output(output: &str) {
+    match output.ends_with('\n') {
+        true => print!("{}", output),
+        false => println!("{}", output),
+    }
+}
+
+/// Returns the format the output is printed in, markdown unless the flags or the configuration
+/// file select another one
+fn output_format(config: &UserConfig) -> OutputFormat {
+    config.output_format.unwrap_or(OutputFormat::Markdown)
+}
+
+/// Check a model of the Hub and print its report. With requirements, their outcome is printed on
+/// the standard error and the process exits with the code of the gate.
+async fn check(
+    args: CheckArgs,
+    user_config: &UserConfig,
+    table: &TableOptions,
+) -> Result<(), Box<dyn Error>> {
+    let scenario = args.scenario.scenario(user_config);
+    let format = output_format(user_config);
+    if let Some(input) = &args.input {
+        return check_list(input, scenario, user_config, table).await;
+    }
+    let model = args.model.unwrap_or_default();
+    let path = Path::new(&model);
+    if args.use_measured && !is_gguf(path) {
+        return Err(
+            "--use-measured only applies to GGUF files, the only models checked on the CPU".into(),
+        );
+    }
+    if path.is_dir() {
+        let report = check_local_model(path, scenario.clone())?;
+        print_output(&report.render(format));
+        if args.requirements.is_empty() {
+            return Ok(());
+        }
+        let config = ModelConfig::from_file(path.join("config.json"))?;
+        let hardware = scan_hardware()?;
+        exit_with_gate(&check_assertions(
+            &hardware,
+            Some((&config, &scenario)),
+            &args.requirements,
+        ));
+    }
+
+    if is_gguf(path) {
+        let bench = match args.use_measured {
+            true => Some(
+                BenchResult::load_default()?
+                    .ok_or("No memory bandwidth measured yet, run `aiha bench` first")?,
+            ),
+            false => None,
+        };
+        let (batch_size, seq_len) = (args.scenario.batch_size, args.scenario.seq_len);
+        let (report, cpu) = check_gguf_file(path, batch_size, seq_len, bench.as_ref())?;
+        let report = match cpu {
+            Some(cpu) => report.with_assumption(format!(
+                "On the CPU, llama.cpp needs {:.2} GiB of the {:.2} GiB of RAM{} and generates \
+                 {:.1} tokens/s",
+                cpu.total() as f64 / GIB,
+                cpu.available() as f64 / GIB,
+                if cpu.fits() { "" } else { ", so it swaps" },
+                cpu.throughput().decode_tokens_per_second()
+            )),
+            None => report,
+        };
+        print_output(&report.render(format));
+        if args.requirements.is_empty() {
+            return Ok(());
+        }
+        let config = GgufFile::from_file(path)?.model_config()?;
+        let hardware = scan_hardware()?;
+        exit_with_gate(&check_assertions(
+            &hardware,
+            Some((&config, &scenario)),
+            &args.requirements,
+        ));
+    }
+
+    let client = user_config.hub_client();
+    if args.requirements.is_empty() {
+        let report = check_repo(&client, &model, scenario).await?;
+        print_output(&report.render(format));
+        return Ok(());
+    }
+    let (report, results) =
+        check_repo_with_assertions(&client, &model, scenario, &args.requirements).await?;
+    print_output(&report.render(format));
+    exit_with_gate(&results);
+}
+
+/// Check the models listed in a file, or on the standard input for `-`, and print the table of
+/// the batch
+async fn check_list(
+    input: &Path,
+    scenario: Scenario,
+    user_config: &UserConfig,
+    table: &TableOptions,
+) -> Result<(), Box<dyn Error>> {
+    let content = match input.to_str() {
+        Some("-") => io::read_to_string(io::stdin())?,
+        _ => fs::read_to_string(input)?,
+    };
+    let repo_ids = parse_model_list(&content);
+    let bar = progress_bar()?;
+    let client = user_config.hub_client();
+    let batch = check_repos_with_progress(&client, &repo_ids, scenario, &progress_of(&bar)).await;
+    bar.finish_and_clear();
+    let batch = batch?;
+    match output_format(user_config) {
+        OutputFormat::Markdown => print_output(&batch.to_table(table)),
+        format => print_output(&batch.render(format)),
+    }
+    Ok(())
+}
+
+/// Build the progress bar of the models fetched from the Hub, drawn on the standard error and
+/// hidden when it isn't a terminal
+fn progress_bar() -> Result<ProgressBar, Box<dyn Error>> {
+    let style = ProgressStyle::with_template("{bar:40} {pos}/{len} {msg}")?;
+    Ok(ProgressBar::new(0).with_style(style))
+}
+
+/// Returns the progress callback of the batches of models moving the bar, the number of models
+/// being unknown before a search is done
+fn progress_of(bar: &ProgressBar) -> impl Fn(&str, usize, usize) + '_ {
+    |repo_id, done, total| {
+        bar.set_length(total as u64);
+        bar.set_position(done as u64);
+        bar.set_message(repo_id.to_string());
+    }
+}
+
+/// Returns whether a model is a GGUF file, from its extension
+fn is_gguf(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("gguf"))
+}
+
+/// Print the outcome of the requirements on the standard error and exit with the code of the gate
+fn exit_with_gate(results: &[AssertionResult]) -> ! {
+    for result in results {
+        eprintln!("{}", result);
+    }
+    process::exit(exit_code(results));
+}
+
+/// Compare models of the Hub and print the comparison table
+async fn compare(
+    args: CompareArgs,
+    user_config: &UserConfig,
+    table: &TableOptions,
+) -> Result<(), Box<dyn Error>> {
+    let scenario = args.scenario.scenario(user_config);
+    let client = user_config.hub_client();
+    let bar = progress_bar()?;
+    let comparison =
+        compare_repos_with_progress(&client, &args.repo_ids, scenario, &progress_of(&bar)).await;
+    bar.finish_and_clear();
+    let comparison = comparison?;
+    match output_format(user_config) {
+        OutputFormat::Markdown => print_output(&comparison.to_table(table)),
+        format => print_output(&comparison.render(format)),
+    }
+    Ok(())
+}
+
+/// Suggest the cloud instances able to serve a model of the Hub and print their table
+async fn cloud(
+    args: CloudArgs,
+    user_config: &UserConfig,
+    table: &TableOptions,
+) -> Result<(), Box<dyn Error>> {
+    let scenario = args.scenario.scenario(user_config);
+    let client = user_config.hub_client();
+    let cloud = suggest_repo_instances(&client, &args.repo_id, scenario, args.min_tps).await?;
+    match output_format(user_config) {
+        OutputFormat::Markdown => print_output(&cloud.to_table(table)),
+        format => print_output(&cloud.render(format)),
+    }
+    Ok(())
+}
+
+/// Search the models of the Hub and print their repository IDs, one per line like the lists of
+/// `check --input`, or with `--fits` the table of the ones fitting the running system
+async fn search(
+    args: SearchArgs,
+    user_config: &UserConfig,
+    table: &TableOptions,
+) -> Result<(), Box<dyn Error>> {
+    let client = user_config.hub_client();
+    if !args.fits {
+        for result in client.search_models(&args.query()).await? {
+            println!("{}", result.repo_id);
+        }
+        return Ok(());
+    }
+    let scenario = args.scenario.scenario(user_config);
+    let (query, bar) = (args.query(), progress_bar()?);
+    let batch =
+        search_fitting_models_with_progress(&client, &query, scenario, &progress_of(&bar)).await;
+    bar.finish_and_clear();
+    let batch = batch?;
+    match output_format(user_config) {
+        OutputFormat::Markdown => print_output(&batch.to_table(table)),
+        format => print_output(&batch.render(format)),
+    }
+    Ok(())
+}
+
+/// Estimate a training run of a model of the Hub on a dataset and print its report
+async fn train(args: TrainArgs, user_config: &UserConfig) -> Result<(), Box<dyn Error>> {
+    let device = match &args.gpu {
+        Some(gpu) => GpuSpec::from_name(gpu).ok_or_else(|| format!("Unknown GPU {}", gpu))?,
+        None => {
+            let hardware = scan_hardware()?;
+            hardware
+                .gpus()
+                .into_iter()
+                .filter_map(GpuSpec::from_device)
+                .next()
+                .ok_or("No known GPU found on the running system, set one with --gpu")?
+        }
+    };
+    let pricing = args
+        .gpu_price
+        .map(|hourly_price| Pricing::Instance { hourly_price });
+    let report = estimate_repo_training(
+        &user_config.hub_client(),
+        &args.repo_id,
+        &args.scenario(user_config),
+        &device,
+        &args.dataset,
+        pricing,
+    )
+    .await?;
+    print_output(&report.render(output_format(user_config)));
+    Ok(())
+}
+
+/// Measure the memory bandwidth of the running system and save it in the AIHA cache folder
+fn bench() -> Result<(), Box<dyn Error>> {
+    eprintln!("Measuring the memory bandwidth...");
+    let bench = run_bench();
+    bench.save_default()?;
+    println!(
+        "Memory bandwidth: {:.1} GB/s on {} threads",
+        bench.memory_bandwidth() / 1e9,
+        bench.threads()
+    );
+    if let Some(cache_dir) = default_aiha_cache_dir() {
+        eprintln!("Saved to {}", cache_dir.join(BENCH_FILE).display());
+    }
+    Ok(())
+}
+
+/// Watch the GPUs while a model of the Hub runs, warning about the ones using more memory than
+/// estimated, then print the peaks and record them in the calibration store
+#[cfg(all(feature = "nvml", not(target_arch = "wasm32")))]
+async fn watch_gpus(args: WatchArgs, user_config: &UserConfig) -> Result<(), Box<dyn Error>> {
+    let scenario = args.scenario.scenario(user_config);
+    let mut watcher = watch_repo(&user_config.hub_client(), &args.repo_id, scenario)
+        .await?
+        .with_tolerance(args.tolerance);
+    eprintln!(
+        "Watching the GPUs for {}s, start the workload of {}",
+        args.duration, args.repo_id
+    );
+    let mut warned: Vec<String> = Vec::new();
+    let on_sample = |_: &[GpuSample], divergences: &[Divergence]| {
+        for divergence in divergences {
+            if !warned.iter().any(|device| device == divergence.device()) {
+                eprintln!("Warning: {}", divergence);
+                warned.push(divergence.device().to_string());
+            }
+        }
+    };
+    let interval = Duration::from_secs_f64(args.interval);
+    let duration = Duration::from_secs_f64(args.duration);
+    watch(&mut watcher, interval, duration, on_sample).await?;
+
+    for (index, peak) in watcher.peaks().iter().enumerate() {
+        println!("GPU {}: {:.2} GiB at the peak", index, *peak as f64 / GIB);
+    }
+    for divergence in watcher.divergences() {
+        println!("{}", divergence);
+    }
+    if !args.no_record {
+        let mut calibrations = CalibrationStore::load_default()?;
+        watcher.record(&mut calibrations);
+        calibrations.save()?;
+        eprintln!("Calibration recorded in {}", calibrations.path().display());
+    }
+    Ok(())
+}
+
+/// Check a model of the Hub and write its report to a file, or print it
+async fn report(args: ReportArgs, user_config: &UserConfig) -> Result<(), Box<dyn Error>> {
+    let scenario = args.scenario.scenario(user_config);
+    let report = check_repo(&user_config.hub_client(), &args.repo_id, scenario).await?;
+    let Some(path) = args.output else {
+        print_output(&report.render(output_format(user_config)));
+        return Ok(());
+    };
+    let format = OutputFormat::from_path(&path).ok_or_else(|| {
+        format!(
+            "Unknown format of {}, expected a .md, .html, .json or .yaml file",
+            path.display()
+        )
+    })?;
+    fs::write(&path, report.render(format))?;
+    eprintln!("Report written to {}", path.display());
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    if let Err(e) = init_tracing(&cli.tracing_options()) {
+        eprintln!("Error: {}", e);
+        process::exit(EXIT_INVALID);
+    }
+    let user_config = match cli.user_config() {
+        Ok(user_config) => user_config,
+        Err(e) => {
+            eprintln!("Error: invalid configuration file: {}", e);
+            process::exit(EXIT_INVALID);
+        }
+    };
+    let table = cli.table_options();
+    let result = match cli.command {
+        Command::Check(args) => check(args, &user_config, &table).await,
+        Command::Report(args) => report(args, &user_config).await,
+        Command::Compare(args) => compare(args, &user_config, &table).await,
+        Command::Cloud(args) => cloud(args, &user_config, &table).await,
+        Command::Bench => bench(),
+        Command::Hub(HubArgs {
+            command: HubCommand::Search(args),
+        }) => search(args, &user_config, &table).await,
+        Command::Estimate(EstimateArgs {
+            command: EstimateCommand::Train(args),
+        }) => train(args, &user_config).await,
+        #[cfg(all(feature = "nvml", not(target_arch = "wasm32")))]
+        Command::Watch(args) => watch_gpus(args, &user_config).await,
+    };
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        process::exit(EXIT_INVALID);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aiha::{QuantType, VERBOSE_LOG_FILTER};
+
+    #[test]
+    fn test_output_format() {
+        let cli = Cli::try_parse_from(["aiha", "check", "gpt2"]).unwrap();
+        assert_eq!(cli.output_format(), None);
+        let cli = Cli::try_parse_from(["aiha", "check", "gpt2", "--json"]).unwrap();
+        assert_eq!(cli.output_format(), Some(OutputFormat::Json));
+        let cli = Cli::try_parse_from(["aiha", "--yaml", "check", "gpt2"]).unwrap();
+        assert_eq!(cli.output_format(), Some(OutputFormat::Yaml));
+        assert!(Cli::try_parse_from(["aiha", "check", "gpt2", "--json", "--yaml"]).is_err());
+    }
+
+    #[test]
+    fn test_verbosity() {
+        let cli = Cli::try_parse_from(["aiha", "-v", "check", "gpt2"]).unwrap();
+        assert_eq!(cli.tracing_options().filter(), VERBOSE_LOG_FILTER);
+        let cli = Cli::try_parse_from(["aiha", "check", "gpt2", "-vv"]).unwrap();
+        assert_eq!(cli.tracing_options().filter(), TRACE_LOG_FILTER);
+    }
+
+    #[test]
+    fn test_table_options() {
+        let cli =
+            Cli::try_parse_from(["aiha", "compare", "gpt2", "distilgpt2", "--plain"]).unwrap();
+        assert!(cli.table_options().is_plain());
+        assert!(!cli.tracing_options().color());
+        let cli = Cli::try_parse_from(["aiha", "--no-color", "cloud", "gpt2"]).unwrap();
+        assert!(!cli.table_options().is_plain());
+        assert!(!cli.table_options().color());
+        assert!(!cli.tracing_options().color());
+    }
+
+    #[test]
+    fn test_check_requirements() {
+        let cli = Cli::try_parse_from([
+            "aiha",
+            "check",
+            "gpt2",
+            "--require",
+            "vram>=24GB",
+            "--require",
+            "fits:int4",
+        ])
+        .unwrap();
+        let Command::Check(args) = cli.command else {
+            panic!("Expected the check command");
+        };
+        assert_eq!(
+            args.requirements,
+            vec![
+                "vram>=24GB".parse::<Assertion>().unwrap(),
+                Assertion::Fits(Precision::GPTQ { bits: 4 })
+            ]
+        );
+        assert!(Cli::try_parse_from(["aiha", "check", "gpt2", "--require", "vram"]).is_err());
+    }
+
+    #[test]
+    fn test_check_input() {
+        let cli = Cli::try_parse_from(["aiha", "check", "--input", "models.txt"]).unwrap();
+        let Command::Check(args) = cli.command else {
+            panic!("Expected the check command");
+        };
+        assert_eq!(args.input, Some(PathBuf::from("models.txt")));
+        assert_eq!(args.model, None);
+        // Either a model or a list of models
+        assert!(Cli::try_parse_from(["aiha", "check"]).is_err());
+        assert!(Cli::try_parse_from(["aiha", "check", "gpt2", "-i", "-"]).is_err());
+    }
+
+    #[test]
+    fn test_check_use_measured() {
+        let cli = Cli::try_parse_from(["aiha", "check", "model.gguf", "--use-measured"]).unwrap();
+        let Command::Check(args) = cli.command else {
+            panic!("Expected the check command");
+        };
+        assert!(args.use_measured);
+        // A list of models is only checked on the GPUs
+        assert!(Cli::try_parse_from(["aiha", "check", "-i", "-", "--use-measured"]).is_err());
+        let cli = Cli::try_parse_from(["aiha", "bench"]).unwrap();
+        assert!(matches!(cli.command, Command::Bench));
+        assert_eq!(cli.flags().precision, None);
+    }
+
+    #[test]
+    fn test_is_gguf() {
+        assert!(is_gguf(Path::new("models/llama-3-8b.Q4_K_M.gguf")));
+        assert!(is_gguf(Path::new("LLAMA.GGUF")));
+        assert!(!is_gguf(Path::new("meta-llama/Meta-Llama-3-8B")));
+        assert!(!is_gguf(Path::new("TheBloke/Llama-2-7B-GGUF")));
+    }
+
+    #[test]
+    fn test_report_args() {
+        let cli = Cli::try_parse_from(["aiha", "report", "gpt2", "-o", "gpt2.html", "-p", "int4"])
+            .unwrap();
+        let flags = cli.flags();
+        let Command::Report(args) = cli.command else {
+            panic!("Expected the report command");
+        };
+        assert_eq!(args.output, Some(PathBuf::from("gpt2.html")));
+        assert_eq!(
+            args.scenario.scenario(&flags).precision(),
+            Precision::GPTQ { bits: 4 }
+        );
+    }
+
+    #[test]
+    fn test_compare_args() {
+        let cli =
+            Cli::try_parse_from(["aiha", "compare", "gpt2", "distilgpt2", "-s", "1024"]).unwrap();
+        let Command::Compare(args) = cli.command else {
+            panic!("Expected the compare command");
+        };
+        assert_eq!(args.repo_ids, vec!["gpt2", "distilgpt2"]);
+        assert_eq!(args.scenario.seq_len, 1024);
+        // A comparison needs two models
+        assert!(Cli::try_parse_from(["aiha", "compare", "gpt2"]).is_err());
+    }
+
+    #[test]
+    fn test_cloud_args() {
+        let cli =
+            Cli::try_parse_from(["aiha", "cloud", "gpt2", "--min-tps", "30", "-b", "4"]).unwrap();
+        let Command::Cloud(args) = cli.command else {
+            panic!("Expected the cloud command");
+        };
+        assert_eq!(args.repo_id, "gpt2");
+        assert_eq!(args.min_tps, 30.0);
+        assert_eq!(args.scenario.batch_size, 4);
+    }
+
+    #[test]
+    #[cfg(all(feature = "nvml", not(target_arch = "wasm32")))]
+    fn test_watch_args() {
+        let cli = Cli::try_parse_from(["aiha", "watch", "gpt2", "--duration", "30", "--no-record"])
+            .unwrap();
+        let Command::Watch(args) = cli.command else {
+            panic!("Expected the watch command");
+        };
+        assert_eq!(args.interval, 1.0);
+        assert_eq!(args.duration, 30.0);
+        assert_eq!(args.tolerance, DEFAULT_DIVERGENCE_TOLERANCE);
+        assert!(args.no_record);
+        assert!(Cli::try_parse_from(["aiha", "watch", "gpt2", "--interval", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_hub_search_args() {
+        let cli = Cli::try_parse_from([
+            "aiha",
+            "hub",
+            "search",
+            "llama",
+            "--task",
+            "text-generation",
+            "--max-params",
+            "8B",
+            "--fits",
+            "-p",
+            "int8",
+        ])
+        .unwrap();
+        assert_eq!(cli.flags().precision, Some(Precision::INT8));
+        let Command::Hub(HubArgs {
+            command: HubCommand::Search(args),
+        }) = cli.command
+        else {
+            panic!("Expected the hub search command");
+        };
+        assert!(args.fits);
+        let query = args.query();
+        assert_eq!(query.search.as_deref(), Some("llama"));
+        assert_eq!(query.task.as_deref(), Some("text-generation"));
+        assert_eq!(query.library, None);
+        assert_eq!(query.max_params, Some(8_000_000_000));
+        assert_eq!(query.limit, DEFAULT_SEARCH_LIMIT);
+        assert!(Cli::try_parse_from(["aiha", "hub", "search", "--max-params", "big"]).is_err());
+    }
+
+    #[test]
+    fn test_estimate_train_args() {
+        let train_args = |args: &[&str]| {
+            let cli = Cli::try_parse_from(["aiha", "estimate", "train", "gpt2"].iter().chain(args))
+                .unwrap();
+            let flags = cli.flags();
+            let Command::Estimate(EstimateArgs {
+                command: EstimateCommand::Train(args),
+            }) = cli.command
+            else {
+                panic!("Expected the estimate train command");
+            };
+            (args, flags)
+        };
+        let (args, flags) = train_args(&["--dataset", "10B", "--zero", "2", "--num-gpus", "8"]);
+        assert_eq!(args.dataset, TrainingDataset::Tokens(10_000_000_000));
+        let Scenario::Training(setup) = args.scenario(&flags) else {
+            panic!("Expected a full fine-tuning");
+        };
+        assert_eq!(setup.sharding, Sharding::Zero2);
+        assert_eq!(setup.num_gpus, 8);
+        assert_eq!(setup.precision, DEFAULT_TRAINING_PRECISION);
+        // The precision of the checks set in the configuration file doesn't apply
+        let config = UserConfig {
+            precision: Some(Precision::GGUF(QuantType::Q4_K)),
+            ..flags
+        };
+        let Scenario::Training(setup) = args.scenario(&config) else {
+            panic!("Expected a full fine-tuning");
+        };
+        assert_eq!(setup.precision, DEFAULT_TRAINING_PRECISION);
+        let (args, flags) = train_args(&["--dataset", "10B", "-p", "fp16"]);
+        assert_eq!(flags.precision, None);
+        let Scenario::Training(setup) = args.scenario(&flags) else {
+            panic!("Expected a full fine-tuning");
+        };
+        assert_eq!(setup.precision, Precision::FP16);
+
+        let (args, flags) = train_args(&["--dataset", "tatsu-lab/alpaca", "--qlora", "-s", "512"]);
+        assert_eq!(
+            args.dataset,
+            TrainingDataset::Repo("tatsu-lab/alpaca".to_string())
+        );
+        let Scenario::Lora(setup) = args.scenario(&flags) else {
+            panic!("Expected a LoRA fine-tuning");
+        };
+        assert_eq!(setup.base_precision, Precision::NF4);
+        assert_eq!(setup.seq_len, 512);
+        // The sharding only applies to a full fine-tuning
+        assert!(Cli::try_parse_from([
+            "aiha",
+            "estimate",
+            "train",
+            "gpt2",
+            "--dataset",
+            "1B",
+            "--lora",
+            "--zero",
+            "3"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_user_config() {
+        let path = std::env::temp_dir().join(format!("aiha-cli-{}.toml", process::id()));
+        fs::write(
+            &path,
+            "precision = \"bf16\"\nsafety_margin = 0.2\noutput_format = \"json\"\n",
+        )
+        .unwrap();
+        let config = path.to_str().unwrap();
+        let cli = Cli::try_parse_from(["aiha", "check", "gpt2", "--config", config]).unwrap();
+        let user_config = cli.user_config().unwrap();
+        assert_eq!(user_config.precision, Some(Precision::BF16));
+        assert_eq!(output_format(&user_config), OutputFormat::Json);
+        // The flags override the file
+        let cli = Cli::try_parse_from([
+            "aiha",
+            "--config",
+            config,
+            "--yaml",
+            "--safety-margin",
+            "0.05",
+            "check",
+            "gpt2",
+            "-p",
+            "int8",
+        ])
+        .unwrap();
+        let user_config = cli.user_config().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(user_config.precision, Some(Precision::INT8));
+        assert_eq!(user_config.safety_margin, Some(0.05));
+        assert_eq!(output_format(&user_config), OutputFormat::Yaml);
+        assert!(Cli::try_parse_from(["aiha", "check", "gpt2", "--safety-margin", "1.5"]).is_err());
+    }
+}
@@ -0,0 +1,346 @@
+//! Module for the BART model (also used for mBART)
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::models::{Dtype, ModelConfigTrait, ModelError, ModelLibraries};
+
+/// A struct representing the BART architecture parameters
+#[derive(Clone, Debug, Deserialize)]
+pub struct BartParams {
+    /// BART model hidden_size
+    d_model: i32,
+    /// BART model number of encoder layers
+    encoder_layers: i32,
+    /// BART model number of decoder layers
+    decoder_layers: i32,
+    /// BART model encoder intermediate_size
+    encoder_ffn_dim: i32,
+    /// BART model decoder intermediate_size
+    decoder_ffn_dim: i32,
+    /// BART model num_attention_heads
+    encoder_attention_heads: i32,
+    /// BART model max_position_embeddings
+    max_position_embeddings: i32,
+}
+
+/// BART model parameters implementation
+impl BartParams {
+    /// Build a new `BartParams` struct based on the provided parameters
+    pub fn new(
+        d_model: i32,
+        encoder_layers: i32,
+        decoder_layers: i32,
+        encoder_ffn_dim: i32,
+        decoder_ffn_dim: i32,
+        encoder_attention_heads: i32,
+        max_position_embeddings: i32,
+    ) -> BartParams {
+        BartParams {
+            d_model,
+            encoder_layers,
+            decoder_layers,
+            encoder_ffn_dim,
+            decoder_ffn_dim,
+            encoder_attention_heads,
+            max_position_embeddings,
+        }
+    }
+    /// Returns the hidden size
+    pub fn d_model(&self) -> i32 {
+        self.d_model
+    }
+    /// Returns the number of encoder layers
+    pub fn encoder_layers(&self) -> i32 {
+        self.encoder_layers
+    }
+    /// Returns the number of decoder layers
+    pub fn decoder_layers(&self) -> i32 {
+        self.decoder_layers
+    }
+    /// Returns the intermediate size of the encoder layers
+    pub fn encoder_ffn_dim(&self) -> i32 {
+        self.encoder_ffn_dim
+    }
+    /// Returns the intermediate size of the decoder layers
+    pub fn decoder_ffn_dim(&self) -> i32 {
+        self.decoder_ffn_dim
+    }
+    /// Returns the number of attention heads
+    pub fn encoder_attention_heads(&self) -> i32 {
+        self.encoder_attention_heads
+    }
+    /// Returns the number of position embeddings
+    pub fn max_position_embeddings(&self) -> i32 {
+        self.max_position_embeddings
+    }
+    /// Build from a JSON value
+    pub fn from_json(value: Value) -> Result<BartParams, ModelError> {
+        let d_model = value["d_model"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("d_model".to_string()))? as i32;
+
+        // Standalone decoders (e.g. the Donut text decoder) keep the encoder fields of the
+        // config they were built from, but don't have any encoder layer
+        let encoder_layers = match value["is_decoder"].as_bool() {
+            Some(true) => 0,
+            _ => value["encoder_layers"]
+                .as_i64()
+                .ok_or(ModelError::MissingField("encoder_layers".to_string()))?
+                as i32,
+        };
+
+        let decoder_layers = value["decoder_layers"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("decoder_layers".to_string()))?
+            as i32;
+
+        let encoder_ffn_dim = value["encoder_ffn_dim"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("encoder_ffn_dim".to_string()))?
+            as i32;
+
+        let decoder_ffn_dim = value["decoder_ffn_dim"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("decoder_ffn_dim".to_string()))?
+            as i32;
+
+        let encoder_attention_heads =
+            value["encoder_attention_heads"]
+                .as_i64()
+                .ok_or(ModelError::MissingField(
+                    "encoder_attention_heads".to_string(),
+                ))? as i32;
+
+        let max_position_embeddings =
+            value["max_position_embeddings"]
+                .as_i64()
+                .ok_or(ModelError::MissingField(
+                    "max_position_embeddings".to_string(),
+                ))? as i32;
+
+        Ok(BartParams::new(
+            d_model,
+            encoder_layers,
+            decoder_layers,
+            encoder_ffn_dim,
+            decoder_ffn_dim,
+            encoder_attention_heads,
+            max_position_embeddings,
+        ))
+    }
+}
+
+/// A struct representing a BART model configuration
+#[derive(Clone, Debug, Deserialize)]
+pub struct BartModelConfig {
+    /// BART model parameters
+    params: BartParams,
+    /// BART model vocab_size
+    vocab_size: i32,
+    /// BART model tie_word_embeddings, whether the LM head shares the token embeddings weights
+    tie_word_embeddings: bool,
+    /// BART model weights precision
+    dtype: Dtype,
+    /// BART model type
+    model_type: String,
+    /// BART model available libraries
+    available_libraries: Vec<ModelLibraries>,
+}
+
+/// BART model implementation
+impl BartModelConfig {
+    /// Build a new `BartModelConfig` struct based on the provided parameters
+    pub fn new(
+        params: BartParams,
+        vocab_size: i32,
+        tie_word_embeddings: bool,
+        dtype: Dtype,
+        model_type: String,
+        available_libraries: Vec<ModelLibraries>,
+    ) -> BartModelConfig {
+        BartModelConfig {
+            params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
+            model_type,
+            available_libraries,
+        }
+    }
+    /// Returns the number of encoder layers
+    pub fn encoder_layers(&self) -> i32 {
+        self.params.encoder_layers
+    }
+    /// Returns the number of decoder layers
+    pub fn decoder_layers(&self) -> i32 {
+        self.params.decoder_layers
+    }
+    /// Returns the intermediate size of the encoder layers
+    pub fn encoder_ffn_dim(&self) -> i32 {
+        self.params.encoder_ffn_dim
+    }
+    /// Returns the intermediate size of the decoder layers
+    pub fn decoder_ffn_dim(&self) -> i32 {
+        self.params.decoder_ffn_dim
+    }
+}
+
+/// Implementation of the `ModelConfigTrait` trait for `BartModelConfig`
+impl ModelConfigTrait for BartModelConfig {
+    fn hidden_size(&self) -> i32 {
+        self.params.d_model
+    }
+
+    fn intermediate_size(&self) -> i32 {
+        self.params.encoder_ffn_dim
+    }
+
+    fn max_position_embeddings(&self) -> i32 {
+        self.params.max_position_embeddings
+    }
+
+    fn num_attention_heads(&self) -> i32 {
+        self.params.encoder_attention_heads
+    }
+
+    /// Both the encoder and the decoder layers are held in memory, so they're summed
+    fn num_hidden_layers(&self) -> i32 {
+        self.params.encoder_layers + self.params.decoder_layers
+    }
+
+    /// Standalone decoders don't have any encoder layer
+    fn is_encoder_decoder(&self) -> bool {
+        self.params.encoder_layers > 0
+    }
+
+    fn num_encoder_layers(&self) -> i32 {
+        self.params.encoder_layers
+    }
+
+    fn vocab_size(&self) -> i32 {
+        self.vocab_size
+    }
+
+    fn tie_word_embeddings(&self) -> bool {
+        self.tie_word_embeddings
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn model_type(&self) -> &str {
+        &self.model_type
+    }
+
+    fn available_libraries(&self) -> &[ModelLibraries] {
+        &self.available_libraries
+    }
+
+    fn from_json(value: Value) -> Result<Self, ModelError> {
+        let params = BartParams::from_json(value.clone())?;
+
+        let vocab_size = value["vocab_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("vocab_size".to_string()))?
+            as i32;
+
+        let tie_word_embeddings = value["tie_word_embeddings"].as_bool().unwrap_or(true);
+
+        let dtype = Dtype::from_json(&value);
+
+        let model_type = match value["model_type"].as_str() {
+            Some(model_type) => model_type.to_string(),
+            None => return Err(ModelError::MissingField("model_type".to_string())),
+        };
+
+        let available_libraries = ModelLibraries::from_json(&value);
+
+        Ok(BartModelConfig::new(
+            params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
+            model_type,
+            available_libraries,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_bart_model_params() {
+        let bart_params = BartParams::new(1024, 12, 12, 4096, 4096, 16, 1024);
+
+        assert_eq!(bart_params.d_model, 1024);
+        assert_eq!(bart_params.encoder_layers, 12);
+        assert_eq!(bart_params.decoder_layers, 12);
+        assert_eq!(bart_params.encoder_ffn_dim, 4096);
+        assert_eq!(bart_params.decoder_ffn_dim, 4096);
+        assert_eq!(bart_params.encoder_attention_heads, 16);
+        assert_eq!(bart_params.max_position_embeddings, 1024);
+    }
+
+    #[test]
+    fn test_bart_model_config() {
+        let bart_params = BartParams::new(768, 6, 6, 3072, 3072, 12, 1024);
+        let bart_model_config = BartModelConfig::new(
+            bart_params,
+            50265,
+            true,
+            Dtype::F32,
+            "bart".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        assert_eq!(bart_model_config.params.d_model, 768);
+        assert_eq!(bart_model_config.params.encoder_layers, 6);
+        assert_eq!(bart_model_config.params.decoder_layers, 6);
+        assert_eq!(bart_model_config.params.encoder_ffn_dim, 3072);
+        assert_eq!(bart_model_config.params.decoder_ffn_dim, 3072);
+        assert_eq!(bart_model_config.params.encoder_attention_heads, 12);
+        assert_eq!(bart_model_config.params.max_position_embeddings, 1024);
+        assert_eq!(bart_model_config.model_type, "bart");
+        assert_eq!(
+            bart_model_config.available_libraries,
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+
+    #[test]
+    fn test_bart_model_trait_implementation() {
+        let value = json!({
+            "model_type": "mbart",
+            "d_model": 1024,
+            "encoder_layers": 12,
+            "decoder_layers": 6,
+            "encoder_ffn_dim": 4096,
+            "decoder_ffn_dim": 2048,
+            "encoder_attention_heads": 16,
+            "decoder_attention_heads": 16,
+            "max_position_embeddings": 1024,
+            "vocab_size": 250027
+        });
+        let bart_model_config = BartModelConfig::from_json(value).unwrap();
+        assert_eq!(bart_model_config.hidden_size(), 1024);
+        assert_eq!(bart_model_config.intermediate_size(), 4096);
+        assert_eq!(bart_model_config.max_position_embeddings(), 1024);
+        assert_eq!(bart_model_config.num_attention_heads(), 16);
+        assert_eq!(bart_model_config.num_hidden_layers(), 18);
+        assert!(bart_model_config.is_encoder_decoder());
+        assert_eq!(bart_model_config.num_encoder_layers(), 12);
+        assert_eq!(bart_model_config.num_decoder_layers(), 6);
+        assert_eq!(bart_model_config.encoder_layers(), 12);
+        assert_eq!(bart_model_config.decoder_layers(), 6);
+        assert_eq!(bart_model_config.encoder_ffn_dim(), 4096);
+        assert_eq!(bart_model_config.decoder_ffn_dim(), 2048);
+        assert_eq!(bart_model_config.model_type(), "mbart");
+        assert_eq!(
+            bart_model_config.available_libraries(),
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+}
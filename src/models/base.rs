@@ -1,10 +1,17 @@
 //! Base traits, structs and enums for models.
 use std::error::Error;
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io::Error as IoError;
+use std::ops::Add;
+use std::str::FromStr;
 
+use serde::{Serialize, Serializer};
 use serde_derive::Deserialize;
 use serde_json::{Error as SerdeJsonError, Value};
 
+use crate::hub::ModelConfig;
+use crate::models::{ModelFamily, QuantKernel, RopeParams};
+
 /// Enumerate the different model libraries available on the Hugging Face Hub
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 pub enum ModelLibraries {
@@ -94,20 +101,691 @@ pub enum ModelLibraries {
     Transformers,
 }
 
+/// ModelLibraries implementation
+impl ModelLibraries {
+    /// Parse the libraries out of the hub tags of a repository, the other tags (e.g. the task,
+    /// the language or the license) are skipped
+    pub fn from_tags(tags: &[String]) -> Vec<ModelLibraries> {
+        tags.iter().filter_map(|tag| tag.parse().ok()).collect()
+    }
+    /// Parse the libraries out of the hub `tags` passed along a config (see
+    /// `hub::get_model_config`). A bare `config.json` only tells it's a Transformers checkpoint,
+    /// so PyTorch is the default when there is no tag.
+    pub fn from_json(value: &Value) -> Vec<ModelLibraries> {
+        let tags: Vec<String> = value["tags"]
+            .as_array()
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|tag| tag.as_str().map(|tag| tag.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        match ModelLibraries::from_tags(&tags) {
+            libraries if libraries.is_empty() => vec![ModelLibraries::PyTorch],
+            libraries => libraries,
+        }
+    }
+}
+
+/// Build from a hub tag (e.g. `pytorch`, `safetensors` or `onnx`)
+impl FromStr for ModelLibraries {
+    type Err = ModelError;
+
+    fn from_str(tag: &str) -> Result<Self, Self::Err> {
+        match tag {
+            "adapter-transformers" => Ok(ModelLibraries::AdapterTransformers),
+            "allennlp" => Ok(ModelLibraries::AllenNLP),
+            "asteroid" => Ok(ModelLibraries::Asteroid),
+            "coreml" => Ok(ModelLibraries::CoreML),
+            "diffusers" => Ok(ModelLibraries::Diffusers),
+            "espnet" => Ok(ModelLibraries::ESPnet),
+            "fairseq" => Ok(ModelLibraries::Fairseq),
+            "fastai" => Ok(ModelLibraries::Fastai),
+            "fasttext" => Ok(ModelLibraries::FastText),
+            "flair" => Ok(ModelLibraries::Flair),
+            "flax" => Ok(ModelLibraries::Flax),
+            "graphcore" => Ok(ModelLibraries::Graphcore),
+            "habana" => Ok(ModelLibraries::Habana),
+            "jax" => Ok(ModelLibraries::Jax),
+            "joblib" => Ok(ModelLibraries::Joblib),
+            "keras" => Ok(ModelLibraries::Keras),
+            "ml-agents" => Ok(ModelLibraries::MLAgents),
+            "nemo" => Ok(ModelLibraries::NeMo),
+            "open_clip" | "open-clip" => Ok(ModelLibraries::OpenCLIP),
+            "openvino" => Ok(ModelLibraries::OpenVINO),
+            "onnx" => Ok(ModelLibraries::Onnx),
+            "paddlenlp" => Ok(ModelLibraries::PaddleNLP),
+            "paddlepaddle" => Ok(ModelLibraries::PaddlePaddle),
+            "pyannote-audio" | "pyannote" => Ok(ModelLibraries::PyannoteAudio),
+            "pythae" => Ok(ModelLibraries::Pythae),
+            "pytorch" => Ok(ModelLibraries::PyTorch),
+            "rust" => Ok(ModelLibraries::Rust),
+            "safetensors" => Ok(ModelLibraries::Safetensors),
+            "sample-factory" => Ok(ModelLibraries::SampleFactory),
+            "sklearn" | "scikit-learn" => Ok(ModelLibraries::ScikitLearn),
+            "sentence-transformers" => Ok(ModelLibraries::SentenceTransformers),
+            "spacy" => Ok(ModelLibraries::Spacy),
+            "span-marker" => Ok(ModelLibraries::SpanMarker),
+            "speechbrain" => Ok(ModelLibraries::Speechbrain),
+            "stable-baselines3" => Ok(ModelLibraries::StableBaselines3),
+            "stanza" => Ok(ModelLibraries::Stanza),
+            "tensorboard" => Ok(ModelLibraries::TensorBoard),
+            "tf" | "tensorflow" => Ok(ModelLibraries::TensorFlow),
+            "tensorflowtts" => Ok(ModelLibraries::TensorFlowTTS),
+            "tflite" => Ok(ModelLibraries::TFLite),
+            "timm" => Ok(ModelLibraries::Timm),
+            "transformers" => Ok(ModelLibraries::Transformers),
+            _ => Err(ModelError::UnknownLibrary(tag.to_string())),
+        }
+    }
+}
+
+/// Display the library as its hub tag
+impl Display for ModelLibraries {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let tag = match self {
+            ModelLibraries::AdapterTransformers => "adapter-transformers",
+            ModelLibraries::AllenNLP => "allennlp",
+            ModelLibraries::Asteroid => "asteroid",
+            ModelLibraries::CoreML => "coreml",
+            ModelLibraries::Diffusers => "diffusers",
+            ModelLibraries::ESPnet => "espnet",
+            ModelLibraries::Fairseq => "fairseq",
+            ModelLibraries::Fastai => "fastai",
+            ModelLibraries::FastText => "fasttext",
+            ModelLibraries::Flair => "flair",
+            ModelLibraries::Flax => "flax",
+            ModelLibraries::Graphcore => "graphcore",
+            ModelLibraries::Habana => "habana",
+            ModelLibraries::Jax => "jax",
+            ModelLibraries::Joblib => "joblib",
+            ModelLibraries::Keras => "keras",
+            ModelLibraries::MLAgents => "ml-agents",
+            ModelLibraries::NeMo => "nemo",
+            ModelLibraries::OpenCLIP => "open_clip",
+            ModelLibraries::OpenVINO => "openvino",
+            ModelLibraries::Onnx => "onnx",
+            ModelLibraries::PaddleNLP => "paddlenlp",
+            ModelLibraries::PaddlePaddle => "paddlepaddle",
+            ModelLibraries::PyannoteAudio => "pyannote-audio",
+            ModelLibraries::Pythae => "pythae",
+            ModelLibraries::PyTorch => "pytorch",
+            ModelLibraries::Rust => "rust",
+            ModelLibraries::Safetensors => "safetensors",
+            ModelLibraries::SampleFactory => "sample-factory",
+            ModelLibraries::ScikitLearn => "sklearn",
+            ModelLibraries::SentenceTransformers => "sentence-transformers",
+            ModelLibraries::Spacy => "spacy",
+            ModelLibraries::SpanMarker => "span-marker",
+            ModelLibraries::Speechbrain => "speechbrain",
+            ModelLibraries::StableBaselines3 => "stable-baselines3",
+            ModelLibraries::Stanza => "stanza",
+            ModelLibraries::TensorBoard => "tensorboard",
+            ModelLibraries::TensorFlow => "tf",
+            ModelLibraries::TensorFlowTTS => "tensorflowtts",
+            ModelLibraries::TFLite => "tflite",
+            ModelLibraries::Timm => "timm",
+            ModelLibraries::Transformers => "transformers",
+        };
+        write!(f, "{}", tag)
+    }
+}
+
+/// Serialize the library as its hub tag
+impl Serialize for ModelLibraries {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Enumerate the floating point precisions of the model weights
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum Dtype {
+    /// 32-bit floating point
+    F32,
+    /// 16-bit floating point
+    F16,
+    /// 16-bit brain floating point
+    BF16,
+    /// 8-bit floating point (e4m3 or e5m2)
+    F8,
+}
+
+/// Dtype implementation
+impl Dtype {
+    /// Returns the number of bytes used to store a single value
+    pub fn num_bytes(&self) -> i64 {
+        match self {
+            Dtype::F32 => 4,
+            Dtype::F16 | Dtype::BF16 => 2,
+            Dtype::F8 => 1,
+        }
+    }
+    /// Parse the `torch_dtype` field of a config (`dtype` in the recent ones). Transformers loads
+    /// the weights in float32 when the field is missing, so it is the default for the missing
+    /// and unknown values.
+    pub fn from_json(value: &Value) -> Dtype {
+        let dtype = value["torch_dtype"].as_str().or(value["dtype"].as_str());
+        match dtype.map(|dtype| dtype.trim_start_matches("torch.")) {
+            Some("float16" | "fp16" | "half") => Dtype::F16,
+            Some("bfloat16" | "bf16") => Dtype::BF16,
+            Some(dtype) if dtype.starts_with("float8") || dtype == "fp8" => Dtype::F8,
+            _ => Dtype::F32,
+        }
+    }
+}
+
+/// Enumerate the GGML tensor types of the GGUF files (llama.cpp). The `_S`, `_M` and `_L` file
+/// types (e.g. `Q4_K_M`) mix several tensor types and are mapped to their main one.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum QuantType {
+    /// 2-bit k-quant
+    Q2_K,
+    /// 3-bit k-quant
+    Q3_K,
+    /// 4-bit quant with one scale per block of 32 weights
+    Q4_0,
+    /// 4-bit quant with one scale and one minimum per block of 32 weights
+    Q4_1,
+    /// 4-bit k-quant
+    Q4_K,
+    /// 5-bit quant with one scale per block of 32 weights
+    Q5_0,
+    /// 5-bit quant with one scale and one minimum per block of 32 weights
+    Q5_1,
+    /// 5-bit k-quant
+    Q5_K,
+    /// 6-bit k-quant
+    Q6_K,
+    /// 8-bit quant with one scale per block of 32 weights
+    Q8_0,
+    /// 16-bit floating point, not quantized
+    F16,
+    /// 32-bit floating point, not quantized
+    F32,
+}
+
+/// QuantType implementation
+impl QuantType {
+    /// Returns the number of bits used to store a single weight, block scales and minimums
+    /// included
+    pub fn bits_per_weight(&self) -> f64 {
+        match self {
+            QuantType::Q2_K => 2.5625,
+            QuantType::Q3_K => 3.4375,
+            QuantType::Q4_0 => 4.5,
+            QuantType::Q4_1 => 5.0,
+            QuantType::Q4_K => 4.5,
+            QuantType::Q5_0 => 5.5,
+            QuantType::Q5_1 => 6.0,
+            QuantType::Q5_K => 5.5,
+            QuantType::Q6_K => 6.5625,
+            QuantType::Q8_0 => 8.5,
+            QuantType::F16 => 16.0,
+            QuantType::F32 => 32.0,
+        }
+    }
+}
+
+/// Build from a GGUF file type (e.g. `Q4_K_M`, case insensitive)
+impl FromStr for QuantType {
+    type Err = ModelError;
+
+    fn from_str(quant_type: &str) -> Result<Self, Self::Err> {
+        let upper = quant_type.to_uppercase();
+        let base = upper
+            .strip_suffix("_S")
+            .or(upper.strip_suffix("_M"))
+            .or(upper.strip_suffix("_L"))
+            .unwrap_or(&upper);
+        match base {
+            "Q2_K" => Ok(QuantType::Q2_K),
+            "Q3_K" => Ok(QuantType::Q3_K),
+            "Q4_0" => Ok(QuantType::Q4_0),
+            "Q4_1" => Ok(QuantType::Q4_1),
+            "Q4_K" => Ok(QuantType::Q4_K),
+            "Q5_0" => Ok(QuantType::Q5_0),
+            "Q5_1" => Ok(QuantType::Q5_1),
+            "Q5_K" => Ok(QuantType::Q5_K),
+            "Q6_K" => Ok(QuantType::Q6_K),
+            "Q8_0" => Ok(QuantType::Q8_0),
+            "F16" => Ok(QuantType::F16),
+            "F32" => Ok(QuantType::F32),
+            _ => Err(ModelError::UnknownQuantization(quant_type.to_string())),
+        }
+    }
+}
+
+/// Display the quantization type as its GGUF name
+impl Display for QuantType {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Enumerate the precisions the weights of a model can be loaded in, quantized ones included.
+///
+/// This is the single source of the number of bytes per parameter for the estimates, `Dtype`
+/// only describes the precision the weights are stored in.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum Precision {
+    /// 32-bit floating point
+    FP32,
+    /// 16-bit floating point
+    FP16,
+    /// 16-bit brain floating point
+    BF16,
+    /// 8-bit floating point
+    FP8,
+    /// 8-bit integer (bitsandbytes LLM.int8)
+    INT8,
+    /// 4-bit NormalFloat (bitsandbytes QLoRA)
+    NF4,
+    /// GPTQ quantization with the given number of bits, in groups of 128 weights
+    GPTQ {
+        /// The number of bits of every weight
+        bits: u8,
+    },
+    /// AWQ quantization with the given number of bits, in groups of 128 weights
+    AWQ {
+        /// The number of bits of every weight
+        bits: u8,
+    },
+    /// GGUF quantization of llama.cpp
+    GGUF(QuantType),
+}
+
+/// Number of weights sharing the same scale and zero point in the GPTQ and AWQ checkpoints
+const GPTQ_GROUP_SIZE: f64 = 128.0;
+/// Number of weights sharing the same absmax in the bitsandbytes 4-bit checkpoints
+const NF4_BLOCK_SIZE: f64 = 64.0;
+
+/// Precision implementation
+impl Precision {
+    /// Returns the number of bits of a single weight, without the quantization metadata
+    pub fn bits_per_param(&self) -> f64 {
+        match self {
+            Precision::FP32 => 32.0,
+            Precision::FP16 | Precision::BF16 => 16.0,
+            Precision::FP8 | Precision::INT8 => 8.0,
+            Precision::NF4 => 4.0,
+            Precision::GPTQ { bits } | Precision::AWQ { bits } => *bits as f64,
+            Precision::GGUF(quant_type) => quant_type.bits_per_weight(),
+        }
+    }
+    /// Returns the factor the quantization metadata (scales and zero points) adds to the weights
+    /// size, 1.0 for the unquantized precisions
+    pub fn overhead_factor(&self) -> f64 {
+        match self {
+            // One fp16 scale and one zero point per group
+            Precision::GPTQ { bits } | Precision::AWQ { bits } => {
+                let bits = *bits as f64;
+                1.0 + (16.0 + bits) / (GPTQ_GROUP_SIZE * bits)
+            }
+            // One absmax per block, quantized to 8 bits by the double quantization
+            Precision::NF4 => 1.0 + 8.0 / (NF4_BLOCK_SIZE * 4.0),
+            // The GGUF bits per weight already include the block scales
+            _ => 1.0,
+        }
+    }
+    /// Returns the number of bytes a single parameter takes in memory, the quantization
+    /// metadata included
+    pub fn bytes_per_param(&self) -> f64 {
+        self.bits_per_param() * self.overhead_factor() / 8.0
+    }
+    /// Returns the number of bytes the given number of parameters take in memory
+    pub fn weights_bytes(&self, num_parameters: i64) -> i64 {
+        (num_parameters as f64 * self.bytes_per_param()).ceil() as i64
+    }
+    /// Returns the lowest CUDA compute capability running the precision natively: BF16 needs an
+    /// Ampere GPU (8.0), FP8 an Ada or Hopper one (8.9), the quantized precisions the oldest GPU
+    /// one of their kernels runs on (see `QuantKernel`)
+    pub fn min_compute_capability(&self) -> (u32, u32) {
+        match self {
+            Precision::BF16 => (8, 0),
+            Precision::FP8 => (8, 9),
+            _ => QuantKernel::candidates(*self)
+                .iter()
+                .map(|kernel| kernel.min_compute_capability())
+                .min()
+                .unwrap_or((0, 0)),
+        }
+    }
+    /// Whether a GPU of the given compute capability runs the precision natively
+    pub fn is_supported_on(&self, compute_capability: (u32, u32)) -> bool {
+        compute_capability >= self.min_compute_capability()
+    }
+    /// Returns the precision a GPU of the given compute capability runs the weights in: the
+    /// precision itself when supported, the best 16-bit one otherwise (BF16 from Ampere, FP16
+    /// before)
+    pub fn fallback_on(&self, compute_capability: (u32, u32)) -> Precision {
+        match self.is_supported_on(compute_capability) {
+            true => *self,
+            false if Precision::BF16.is_supported_on(compute_capability) => Precision::BF16,
+            false => Precision::FP16,
+        }
+    }
+    /// Whether the precision is a quantized one
+    pub fn is_quantized(&self) -> bool {
+        !matches!(
+            self,
+            Precision::FP32
+                | Precision::FP16
+                | Precision::BF16
+                | Precision::GGUF(QuantType::F16 | QuantType::F32)
+        )
+    }
+    /// Parse the `quantization_config` of a config, the precision of the weights (see
+    /// `Dtype::from_json`) when the model is not quantized
+    pub fn from_json(value: &Value) -> Precision {
+        let quantization_config = &value["quantization_config"];
+        let bits = quantization_config["bits"]
+            .as_u64()
+            .filter(|bits| (2..=8).contains(bits))
+            .unwrap_or(4) as u8;
+        match quantization_config["quant_method"].as_str() {
+            Some("gptq") => Precision::GPTQ { bits },
+            Some("awq") => Precision::AWQ { bits },
+            Some("bitsandbytes") if quantization_config["load_in_4bit"].as_bool() == Some(true) => {
+                Precision::NF4
+            }
+            Some("bitsandbytes") => Precision::INT8,
+            Some("fp8" | "fbgemm_fp8") => Precision::FP8,
+            _ => Dtype::from_json(value).into(),
+        }
+    }
+}
+
+/// Convert the precision the weights are stored in
+impl From<Dtype> for Precision {
+    fn from(dtype: Dtype) -> Self {
+        match dtype {
+            Dtype::F32 => Precision::FP32,
+            Dtype::F16 => Precision::FP16,
+            Dtype::BF16 => Precision::BF16,
+            Dtype::F8 => Precision::FP8,
+        }
+    }
+}
+
+/// Display the precision (e.g. `bf16`, `gptq-4bit` or `gguf-Q4_K`)
+impl Display for Precision {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Precision::FP32 => write!(f, "fp32"),
+            Precision::FP16 => write!(f, "fp16"),
+            Precision::BF16 => write!(f, "bf16"),
+            Precision::FP8 => write!(f, "fp8"),
+            Precision::INT8 => write!(f, "int8"),
+            Precision::NF4 => write!(f, "nf4"),
+            Precision::GPTQ { bits } => write!(f, "gptq-{}bit", bits),
+            Precision::AWQ { bits } => write!(f, "awq-{}bit", bits),
+            Precision::GGUF(quant_type) => write!(f, "gguf-{}", quant_type),
+        }
+    }
+}
+
+/// Build from the name of a precision, as displayed (e.g. `bf16`, `gptq-4bit` or `gguf-Q4_K`) or
+/// as commonly written (e.g. `int4`, `awq` or `Q4_K_M`), case insensitive. The bare `int4` is
+/// the weight-only 4-bit quantization with per-group scales, estimated as GPTQ 4-bit (AWQ has the
+/// same footprint). The GPTQ and AWQ bit widths are limited to 2..=8 bits.
+impl FromStr for Precision {
+    type Err = ModelError;
+
+    fn from_str(precision: &str) -> Result<Self, Self::Err> {
+        let lower = precision.trim().to_lowercase();
+        let bits = |name: &str| -> Option<u8> {
+            let bits = name.trim_start_matches('-').trim_end_matches("bit");
+            match bits.is_empty() {
+                true => Some(4),
+                false => bits.parse().ok().filter(|bits| (2..=8).contains(bits)),
+            }
+        };
+        match lower.as_str() {
+            "fp32" | "float32" => return Ok(Precision::FP32),
+            "fp16" | "float16" => return Ok(Precision::FP16),
+            "bf16" | "bfloat16" => return Ok(Precision::BF16),
+            "fp8" => return Ok(Precision::FP8),
+            "int8" => return Ok(Precision::INT8),
+            "nf4" => return Ok(Precision::NF4),
+            "int4" => return Ok(Precision::GPTQ { bits: 4 }),
+            _ => {}
+        }
+        if let Some(bits) = lower.strip_prefix("gptq").and_then(bits) {
+            return Ok(Precision::GPTQ { bits });
+        }
+        if let Some(bits) = lower.strip_prefix("awq").and_then(bits) {
+            return Ok(Precision::AWQ { bits });
+        }
+        let quant_type = lower.strip_prefix("gguf-").unwrap_or(&lower);
+        QuantType::from_str(quant_type)
+            .map(Precision::GGUF)
+            .map_err(|_| ModelError::UnknownQuantization(precision.to_string()))
+    }
+}
+
+/// A struct representing the number of parameters of every component of a model
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ParameterBreakdown {
+    /// Token, position and patch embeddings
+    embeddings: i64,
+    /// Attention weights of all the layers
+    attention: i64,
+    /// Feed-forward weights of all the layers
+    mlp: i64,
+    /// LM head, 0 when it is tied to the token embeddings
+    lm_head: i64,
+    /// Any other weights (e.g. projections between towers, patch merging or decode heads)
+    other: i64,
+    /// Number of layers the attention and feed-forward weights are spread over
+    num_layers: i32,
+}
+
+/// ParameterBreakdown implementation
+impl ParameterBreakdown {
+    /// Build a new `ParameterBreakdown` struct based on the provided parameters
+    pub fn new(
+        embeddings: i64,
+        attention: i64,
+        mlp: i64,
+        lm_head: i64,
+        other: i64,
+        num_layers: i32,
+    ) -> ParameterBreakdown {
+        ParameterBreakdown {
+            embeddings,
+            attention,
+            mlp,
+            lm_head,
+            other,
+            num_layers,
+        }
+    }
+    /// Returns the number of parameters of the token, position and patch embeddings
+    pub fn embeddings(&self) -> i64 {
+        self.embeddings
+    }
+    /// Returns the number of attention parameters of all the layers
+    pub fn attention(&self) -> i64 {
+        self.attention
+    }
+    /// Returns the number of feed-forward parameters of all the layers
+    pub fn mlp(&self) -> i64 {
+        self.mlp
+    }
+    /// Returns the number of parameters of the LM head
+    pub fn lm_head(&self) -> i64 {
+        self.lm_head
+    }
+    /// Returns the number of parameters of the other weights
+    pub fn other(&self) -> i64 {
+        self.other
+    }
+    /// Returns the number of layers
+    pub fn num_layers(&self) -> i32 {
+        self.num_layers
+    }
+    /// Returns the number of attention parameters of a single layer, averaged over the layers
+    /// when they differ (e.g. the encoder and decoder layers of a seq2seq model)
+    pub fn attention_per_layer(&self) -> i64 {
+        match self.num_layers {
+            0 => 0,
+            num_layers => self.attention / num_layers as i64,
+        }
+    }
+    /// Returns the number of feed-forward parameters of a single layer, averaged over the layers
+    pub fn mlp_per_layer(&self) -> i64 {
+        match self.num_layers {
+            0 => 0,
+            num_layers => self.mlp / num_layers as i64,
+        }
+    }
+    /// Returns the number of parameters of a single layer, the unit of a partial offload
+    pub fn per_layer(&self) -> i64 {
+        self.attention_per_layer() + self.mlp_per_layer()
+    }
+    /// Returns the total number of parameters
+    pub fn total(&self) -> i64 {
+        self.embeddings + self.attention + self.mlp + self.lm_head + self.other
+    }
+    /// Returns the fraction of the parameters held by a component (e.g.
+    /// `breakdown.fraction(breakdown.mlp())`), 0 when there is no parameter
+    pub fn fraction(&self, parameters: i64) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => parameters as f64 / total as f64,
+        }
+    }
+}
+
+/// Sum the breakdowns of the sub-models of a composite model (e.g. both towers of CLIP)
+impl Add for ParameterBreakdown {
+    type Output = ParameterBreakdown;
+
+    fn add(self, other: ParameterBreakdown) -> ParameterBreakdown {
+        ParameterBreakdown::new(
+            self.embeddings + other.embeddings,
+            self.attention + other.attention,
+            self.mlp + other.mlp,
+            self.lm_head + other.lm_head,
+            self.other + other.other,
+            self.num_layers + other.num_layers,
+        )
+    }
+}
+
 /// Model error
 #[derive(Debug)]
 pub enum ModelError {
+    /// IO error, when reading a config from a file
+    Io(IoError),
     /// JSON error
     Json(SerdeJsonError),
-    /// Missing field error
+    /// Missing field error, with the path of the field in the config (e.g.
+    /// `text_config.num_attention_heads` for a field of a nested config)
     MissingField(String),
     /// Model not implemented error
     ModelNotImplemented(String),
+    /// Unknown library tag error
+    UnknownLibrary(String),
+    /// Unknown quantization type error
+    UnknownQuantization(String),
+    /// Error of the config of a given repository and model type
+    Context {
+        /// The repository of the config, if it was loaded from the Hub
+        repo_id: Option<String>,
+        /// The model type of the config, if it was declared
+        model_type: Option<String>,
+        /// The error the config failed with
+        source: Box<ModelError>,
+    },
+}
+
+/// ModelError implementation
+impl ModelError {
+    /// Attach the repository the failing config was loaded from
+    pub fn with_repo_id(self, repo_id: &str) -> ModelError {
+        match self {
+            ModelError::Context {
+                model_type, source, ..
+            } => ModelError::Context {
+                repo_id: Some(repo_id.to_string()),
+                model_type,
+                source,
+            },
+            error => ModelError::Context {
+                repo_id: Some(repo_id.to_string()),
+                model_type: None,
+                source: Box::new(error),
+            },
+        }
+    }
+    /// Attach the model type of the failing config
+    pub fn with_model_type(self, model_type: &str) -> ModelError {
+        match self {
+            ModelError::Context {
+                repo_id, source, ..
+            } => ModelError::Context {
+                repo_id,
+                model_type: Some(model_type.to_string()),
+                source,
+            },
+            error => ModelError::Context {
+                repo_id: None,
+                model_type: Some(model_type.to_string()),
+                source: Box::new(error),
+            },
+        }
+    }
+    /// Prefix the path of a missing field with the key of the sub-config it was parsed from
+    /// (e.g. `text_config`)
+    pub fn in_sub_config(self, key: &str) -> ModelError {
+        match self {
+            ModelError::MissingField(field) => {
+                ModelError::MissingField(format!("{}.{}", key, field))
+            }
+            ModelError::Context {
+                repo_id,
+                model_type,
+                source,
+            } => ModelError::Context {
+                repo_id,
+                model_type,
+                source: Box::new(source.in_sub_config(key)),
+            },
+            error => error,
+        }
+    }
+    /// Returns the path of the missing field in the config, if the error is a missing field one
+    pub fn field_path(&self) -> Option<&str> {
+        match self {
+            ModelError::MissingField(field) => Some(field),
+            ModelError::Context { source, .. } => source.field_path(),
+            _ => None,
+        }
+    }
+    /// Returns the repository of the failing config, if it is known
+    pub fn repo_id(&self) -> Option<&str> {
+        match self {
+            ModelError::Context { repo_id, .. } => repo_id.as_deref(),
+            _ => None,
+        }
+    }
+    /// Returns the model type of the failing config, if it is known
+    pub fn model_type(&self) -> Option<&str> {
+        match self {
+            ModelError::Context { model_type, .. } => model_type.as_deref(),
+            ModelError::ModelNotImplemented(model_type) => Some(model_type),
+            _ => None,
+        }
+    }
 }
 
 impl Display for ModelError {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
         match self {
+            ModelError::Io(e) => write!(f, "IO error: {}", e),
             ModelError::Json(e) => write!(f, "JSON error: {}", e),
             ModelError::MissingField(field) => write!(f, "Missing field: {}", field),
             ModelError::ModelNotImplemented(model) => write!(
@@ -117,11 +795,44 @@ impl Display for ModelError {
                 https://github.com/chainyo/aiha/issues",
                 model
             ),
+            ModelError::UnknownLibrary(tag) => write!(f, "Unknown library: {}", tag),
+            ModelError::UnknownQuantization(quant_type) => {
+                write!(f, "Unknown quantization type: {}", quant_type)
+            }
+            ModelError::Context {
+                repo_id,
+                model_type,
+                source,
+            } => {
+                write!(f, "Invalid config")?;
+                if let Some(repo_id) = repo_id {
+                    write!(f, " of {}", repo_id)?;
+                }
+                if let Some(model_type) = model_type {
+                    write!(f, " (model_type: {})", model_type)?;
+                }
+                write!(f, ": {}", source)
+            }
         }
     }
 }
 
-impl Error for ModelError {}
+impl Error for ModelError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ModelError::Io(error) => Some(error),
+            ModelError::Json(error) => Some(error),
+            ModelError::Context { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<IoError> for ModelError {
+    fn from(error: IoError) -> Self {
+        ModelError::Io(error)
+    }
+}
 
 impl From<SerdeJsonError> for ModelError {
     fn from(error: SerdeJsonError) -> Self {
@@ -151,6 +862,144 @@ pub trait ModelConfigTrait {
     fn num_hidden_layers(&self) -> i32 {
         Default::default()
     }
+    /// Returns whether the model is made of an encoder and a decoder stacks (e.g. T5, BART)
+    fn is_encoder_decoder(&self) -> bool {
+        false
+    }
+    /// Returns the number of encoder layers, which only run once over the input, 0 for the
+    /// decoder-only models
+    fn num_encoder_layers(&self) -> i32 {
+        0
+    }
+    /// Returns the number of decoder layers, the ones keeping a KV cache during generation.
+    ///
+    /// Every layer that is not an encoder one is counted, so encoder-only models (e.g. BERT) are
+    /// treated as decoder-only ones.
+    fn num_decoder_layers(&self) -> i32 {
+        self.num_hidden_layers() - self.num_encoder_layers()
+    }
+    /// Returns the family of the model, which tells the kind of workload it runs, decoder-only
+    /// language models by default and encoder-decoder ones when the config has an encoder
+    fn model_family(&self) -> ModelFamily {
+        if self.is_encoder_decoder() {
+            ModelFamily::Seq2Seq
+        } else {
+            ModelFamily::DecoderLM
+        }
+    }
+    /// Returns the model number of key/value heads, lower than the number of attention heads
+    /// when the model uses grouped-query attention (GQA)
+    fn num_key_value_heads(&self) -> i32 {
+        self.num_attention_heads()
+    }
+    /// Returns the dimension of every attention head, the hidden size split between the heads
+    /// unless the config sets it explicitly (e.g. Gemma)
+    fn head_dim(&self) -> i32 {
+        match self.num_attention_heads() {
+            0 => 0,
+            num_attention_heads => self.hidden_size() / num_attention_heads,
+        }
+    }
+    /// Returns the dimension of the keys (or values) of every token, across the key/value heads
+    fn kv_dim(&self) -> i32 {
+        self.num_key_value_heads() * self.head_dim()
+    }
+    /// Returns the ratio between the intermediate size and the hidden size (e.g. 4 for GPT-2)
+    fn ffn_ratio(&self) -> f64 {
+        match self.hidden_size() {
+            0 => 0.0,
+            hidden_size => self.intermediate_size() as f64 / hidden_size as f64,
+        }
+    }
+    /// Returns the model vocabulary size, 0 for the models without token embeddings
+    fn vocab_size(&self) -> i32 {
+        Default::default()
+    }
+    /// Returns whether the LM head shares the weights of the token embeddings
+    fn tie_word_embeddings(&self) -> bool {
+        true
+    }
+    /// Returns the attention window of the sliding-window attention layers (e.g. Mistral, or every
+    /// other layer of Gemma 2), `None` when every layer attends to the whole context
+    fn sliding_window(&self) -> Option<i32> {
+        None
+    }
+    /// Returns the number of decoder layers attending to the last `sliding_window` tokens only,
+    /// every decoder layer of a sliding-window model by default
+    fn num_sliding_layers(&self) -> i32 {
+        match self.sliding_window() {
+            Some(_) => self.num_decoder_layers(),
+            None => 0,
+        }
+    }
+    /// Returns an estimate of the model number of parameters, the total of
+    /// `parameter_breakdown`
+    fn num_parameters(&self) -> i64 {
+        self.parameter_breakdown().total()
+    }
+    /// Returns the number of parameters stored in memory, every expert of the Mixture-of-Experts
+    /// models included
+    fn total_parameters(&self) -> i64 {
+        self.num_parameters()
+    }
+    /// Returns the number of parameters used to process each token, which drives the compute.
+    ///
+    /// Only the activated experts of the Mixture-of-Experts models are counted, all the
+    /// parameters are used by the dense models.
+    fn active_parameters_per_token(&self) -> i64 {
+        self.total_parameters()
+    }
+    /// Returns an estimate of the number of parameters of every component of the model.
+    ///
+    /// The attention and feed-forward weights of every layer, the position embeddings, the token
+    /// embeddings and the LM head (when it is not tied to them) are counted, biases and layer
+    /// norms are left out. The keys and values projections are sized by `kv_dim`, so they shrink
+    /// with grouped-query attention.
+    fn parameter_breakdown(&self) -> ParameterBreakdown {
+        let hidden_size = self.hidden_size() as i64;
+        let num_layers = self.num_hidden_layers();
+        let attention_dim = (self.num_attention_heads() * self.head_dim()) as i64;
+        let attention = 2 * hidden_size * attention_dim + 2 * hidden_size * self.kv_dim() as i64;
+        let mlp = 2 * hidden_size * self.intermediate_size() as i64;
+        let embeddings = self.max_position_embeddings() as i64 * hidden_size
+            + self.vocab_size() as i64 * hidden_size;
+        ParameterBreakdown::new(
+            embeddings,
+            attention * num_layers as i64,
+            mlp * num_layers as i64,
+            self.lm_head_parameters(),
+            0,
+            num_layers,
+        )
+    }
+    /// Returns the number of parameters of the token embeddings and of the LM head, which is only
+    /// counted when it is not tied to the token embeddings
+    fn embedding_parameters(&self) -> i64 {
+        self.vocab_size() as i64 * self.hidden_size() as i64 + self.lm_head_parameters()
+    }
+    /// Returns the number of parameters of the LM head, 0 when it is tied to the token embeddings
+    fn lm_head_parameters(&self) -> i64 {
+        match self.tie_word_embeddings() {
+            true => 0,
+            false => self.vocab_size() as i64 * self.hidden_size() as i64,
+        }
+    }
+    /// Returns the rotary position embeddings parameters, `None` for the models without RoPE
+    fn rope_params(&self) -> Option<&RopeParams> {
+        None
+    }
+    /// Returns the longest context the model supports, accounting for the RoPE scaling that
+    /// extends it beyond `max_position_embeddings`
+    fn effective_max_context(&self) -> i32 {
+        match self.rope_params() {
+            Some(rope_params) => rope_params.effective_max_context(self.max_position_embeddings()),
+            None => self.max_position_embeddings(),
+        }
+    }
+    /// Returns the precision the model weights are stored in
+    fn dtype(&self) -> Dtype {
+        Dtype::F32
+    }
     /// Returns the model type
     fn model_type(&self) -> &str {
         ""
@@ -165,6 +1014,206 @@ pub trait ModelConfigTrait {
         Self: Sized;
 }
 
+/// Keys of the sub-configs nested in the configs of multimodal models, in the order the flat
+/// accessors of a `CompositeModelConfig` look them up
+pub const SUB_CONFIG_KEYS: [&str; 3] = ["text_config", "vision_config", "audio_config"];
+
+/// Override the `defaults` fields with the ones of `value`, nested configs only store the fields
+/// that differ from the defaults of the model
+pub(crate) fn merge_with_defaults(mut defaults: Value, value: &Value) -> Value {
+    if let (Some(defaults), Some(value)) = (defaults.as_object_mut(), value.as_object()) {
+        for (key, val) in value.iter().filter(|(_, val)| !val.is_null()) {
+            defaults.insert(key.to_string(), val.clone());
+        }
+    }
+    defaults
+}
+
+/// A struct representing the configuration of a model made of nested sub-configs (e.g.
+/// `text_config` and `vision_config`), each one parsed as a standalone model config.
+///
+/// The flat accessors describe the first sub-config found in `SUB_CONFIG_KEYS` order, the
+/// parameters are summed over all of them.
+#[derive(Clone, Debug, Deserialize)]
+pub struct CompositeModelConfig {
+    /// Composite model sub-configs, with the key they are nested under
+    sub_configs: Vec<(String, ModelConfig)>,
+    /// Composite model weights precision
+    dtype: Dtype,
+    /// Composite model type
+    model_type: String,
+    /// Composite model available libraries
+    available_libraries: Vec<ModelLibraries>,
+}
+
+/// Composite model implementation
+impl CompositeModelConfig {
+    /// Build a new `CompositeModelConfig` struct based on the provided parameters
+    pub fn new(
+        sub_configs: Vec<(String, ModelConfig)>,
+        dtype: Dtype,
+        model_type: String,
+        available_libraries: Vec<ModelLibraries>,
+    ) -> CompositeModelConfig {
+        CompositeModelConfig {
+            sub_configs,
+            dtype,
+            model_type,
+            available_libraries,
+        }
+    }
+    /// Whether the JSON config nests at least one of the `SUB_CONFIG_KEYS` sub-configs
+    pub fn has_sub_configs(value: &Value) -> bool {
+        SUB_CONFIG_KEYS.iter().any(|key| value[key].is_object())
+    }
+    /// Returns the sub-configs, with the key they are nested under
+    pub fn sub_configs(&self) -> &[(String, ModelConfig)] {
+        &self.sub_configs
+    }
+    /// Returns the sub-config nested under `key` (e.g. `vision_config`)
+    pub fn sub_config(&self, key: &str) -> Option<&ModelConfig> {
+        self.sub_configs
+            .iter()
+            .find(|(sub_config_key, _)| sub_config_key == key)
+            .map(|(_, sub_config)| sub_config)
+    }
+    /// Returns the sub-config the flat accessors describe
+    fn primary(&self) -> Option<&ModelConfig> {
+        self.sub_configs.first().map(|(_, sub_config)| sub_config)
+    }
+}
+
+/// Implementation of the `ModelConfigTrait` trait for `CompositeModelConfig`
+impl ModelConfigTrait for CompositeModelConfig {
+    fn hidden_size(&self) -> i32 {
+        self.primary().map_or(0, |config| config.hidden_size())
+    }
+
+    fn intermediate_size(&self) -> i32 {
+        self.primary()
+            .map_or(0, |config| config.intermediate_size())
+    }
+
+    fn max_position_embeddings(&self) -> i32 {
+        self.primary()
+            .map_or(0, |config| config.max_position_embeddings())
+    }
+
+    fn num_attention_heads(&self) -> i32 {
+        self.primary()
+            .map_or(0, |config| config.num_attention_heads())
+    }
+
+    fn num_hidden_layers(&self) -> i32 {
+        self.primary()
+            .map_or(0, |config| config.num_hidden_layers())
+    }
+
+    /// Several sub-configs mean several modalities, a single one tells the family by itself
+    fn model_family(&self) -> ModelFamily {
+        match self.sub_configs.as_slice() {
+            [(_, sub_config)] => sub_config.model_family(),
+            _ => ModelFamily::Multimodal,
+        }
+    }
+
+    fn num_key_value_heads(&self) -> i32 {
+        self.primary()
+            .map_or(0, |config| config.num_key_value_heads())
+    }
+
+    fn head_dim(&self) -> i32 {
+        self.primary().map_or(0, |config| config.head_dim())
+    }
+
+    fn vocab_size(&self) -> i32 {
+        self.primary().map_or(0, |config| config.vocab_size())
+    }
+
+    fn tie_word_embeddings(&self) -> bool {
+        self.primary()
+            .is_none_or(|config| config.tie_word_embeddings())
+    }
+
+    fn sliding_window(&self) -> Option<i32> {
+        self.primary().and_then(|config| config.sliding_window())
+    }
+
+    fn num_sliding_layers(&self) -> i32 {
+        self.primary()
+            .map_or(0, |config| config.num_sliding_layers())
+    }
+
+    /// The sub-configs are summed, their projections to a shared space are not counted
+    fn parameter_breakdown(&self) -> ParameterBreakdown {
+        self.sub_configs
+            .iter()
+            .map(|(_, sub_config)| sub_config.parameter_breakdown())
+            .fold(ParameterBreakdown::default(), |total, breakdown| {
+                total + breakdown
+            })
+    }
+
+    fn active_parameters_per_token(&self) -> i64 {
+        self.sub_configs
+            .iter()
+            .map(|(_, sub_config)| sub_config.active_parameters_per_token())
+            .sum()
+    }
+
+    fn rope_params(&self) -> Option<&RopeParams> {
+        self.primary().and_then(|config| config.rope_params())
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn model_type(&self) -> &str {
+        &self.model_type
+    }
+
+    fn available_libraries(&self) -> &[ModelLibraries] {
+        &self.available_libraries
+    }
+
+    fn from_json(value: Value) -> Result<Self, ModelError> {
+        let mut sub_configs = Vec::new();
+        for key in SUB_CONFIG_KEYS {
+            if !value[key].is_object() {
+                continue;
+            }
+            // Sub-configs usually leave the precision to the parent config
+            let mut sub_config = value[key].clone();
+            if sub_config["torch_dtype"].is_null() {
+                sub_config["torch_dtype"] = value["torch_dtype"].clone();
+            }
+            let sub_config =
+                ModelConfig::from_json(sub_config).map_err(|err| err.in_sub_config(key))?;
+            sub_configs.push((key.to_string(), sub_config));
+        }
+        if sub_configs.is_empty() {
+            return Err(ModelError::MissingField(SUB_CONFIG_KEYS[0].to_string()));
+        }
+
+        let dtype = Dtype::from_json(&value);
+
+        let model_type = match value["model_type"].as_str() {
+            Some(model_type) => model_type.to_string(),
+            None => return Err(ModelError::MissingField("model_type".to_string())),
+        };
+
+        let available_libraries = ModelLibraries::from_json(&value);
+
+        Ok(CompositeModelConfig::new(
+            sub_configs,
+            dtype,
+            model_type,
+            available_libraries,
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::vec;
@@ -218,10 +1267,215 @@ mod tests {
         assert_eq!(config.max_position_embeddings(), 512);
         assert_eq!(config.num_attention_heads(), 16);
         assert_eq!(config.num_hidden_layers(), 12);
+        assert_eq!(config.num_key_value_heads(), 16);
+        assert_eq!(config.head_dim(), 64);
+        assert_eq!(config.kv_dim(), 1024);
+        assert_eq!(config.ffn_ratio(), 4.0);
+        assert!(!config.is_encoder_decoder());
+        assert_eq!(config.num_encoder_layers(), 0);
+        assert_eq!(config.num_decoder_layers(), 12);
+        assert_eq!(config.vocab_size(), 0);
+        assert_eq!(config.sliding_window(), None);
+        assert!(config.tie_word_embeddings());
+        assert_eq!(config.embedding_parameters(), 0);
+        assert_eq!(config.dtype(), Dtype::F32);
+        assert!(config.rope_params().is_none());
+        assert_eq!(config.effective_max_context(), 512);
         assert_eq!(config.model_type(), "mock");
         assert_eq!(config.available_libraries(), vec![ModelLibraries::PyTorch]);
     }
 
+    #[test]
+    fn test_hub_model_config_num_parameters() {
+        let config = MockModelConfig;
+        // 12 * (4 * 1024 * 1024 + 2 * 1024 * 4096) + 512 * 1024
+        assert_eq!(config.num_parameters(), 151_519_232);
+        assert_eq!(config.total_parameters(), 151_519_232);
+        assert_eq!(config.active_parameters_per_token(), 151_519_232);
+    }
+
+    #[test]
+    fn test_parameter_breakdown() {
+        let breakdown = MockModelConfig.parameter_breakdown();
+        assert_eq!(breakdown.embeddings(), 512 * 1024);
+        assert_eq!(breakdown.attention_per_layer(), 4 * 1024 * 1024);
+        assert_eq!(breakdown.mlp_per_layer(), 2 * 1024 * 4096);
+        assert_eq!(breakdown.per_layer(), 12_582_912);
+        assert_eq!(breakdown.lm_head(), 0);
+        assert_eq!(breakdown.other(), 0);
+        assert_eq!(breakdown.num_layers(), 12);
+        assert_eq!(breakdown.total(), MockModelConfig.num_parameters());
+        assert_eq!(
+            breakdown.fraction(breakdown.mlp()),
+            100_663_296.0 / 151_519_232.0
+        );
+
+        let sum = breakdown + ParameterBreakdown::new(0, 0, 0, 1024, 10, 0);
+        assert_eq!(sum.total(), breakdown.total() + 1034);
+        assert_eq!(sum.per_layer(), breakdown.per_layer());
+        assert_eq!(ParameterBreakdown::default().per_layer(), 0);
+        assert_eq!(ParameterBreakdown::default().fraction(0), 0.0);
+    }
+
+    #[test]
+    fn test_dtype_from_json() {
+        let dtypes = vec![
+            (serde_json::json!({"torch_dtype": "float32"}), Dtype::F32),
+            (serde_json::json!({"torch_dtype": "float16"}), Dtype::F16),
+            (serde_json::json!({"torch_dtype": "bfloat16"}), Dtype::BF16),
+            (serde_json::json!({"dtype": "bfloat16"}), Dtype::BF16),
+            (
+                serde_json::json!({"torch_dtype": "float8_e4m3fn"}),
+                Dtype::F8,
+            ),
+            (serde_json::json!({"torch_dtype": null}), Dtype::F32),
+            (serde_json::json!({}), Dtype::F32),
+        ];
+        for (value, dtype) in dtypes {
+            assert_eq!(Dtype::from_json(&value), dtype);
+        }
+        assert_eq!(Dtype::BF16.num_bytes(), 2);
+        assert_eq!(Dtype::F8.num_bytes(), 1);
+    }
+
+    #[test]
+    fn test_precision_bytes_per_param() {
+        assert_eq!(Precision::FP32.bytes_per_param(), 4.0);
+        assert_eq!(Precision::from(Dtype::BF16).bytes_per_param(), 2.0);
+        assert_eq!(Precision::INT8.bytes_per_param(), 1.0);
+        assert_eq!(Precision::NF4.bytes_per_param(), 0.515625);
+        // 4-bit GPTQ, with a fp16 scale and a 4-bit zero point every 128 weights
+        assert_eq!(Precision::GPTQ { bits: 4 }.overhead_factor(), 1.0390625);
+        assert_eq!(Precision::GPTQ { bits: 4 }.bytes_per_param(), 0.51953125);
+        assert_eq!(
+            Precision::GGUF(QuantType::Q4_K).weights_bytes(8_030_261_248),
+            4_517_021_952
+        );
+        assert!(Precision::GGUF(QuantType::Q8_0).is_quantized());
+        assert!(!Precision::GGUF(QuantType::F16).is_quantized());
+        assert_eq!(Precision::GPTQ { bits: 3 }.to_string(), "gptq-3bit");
+        assert_eq!(Precision::GGUF(QuantType::Q6_K).to_string(), "gguf-Q6_K");
+    }
+
+    #[test]
+    fn test_precision_compute_capability() {
+        // BF16 needs Ampere, FP8 Ada or Hopper
+        assert!(Precision::BF16.is_supported_on((8, 0)));
+        assert!(!Precision::BF16.is_supported_on((7, 5)));
+        assert!(!Precision::FP8.is_supported_on((8, 6)));
+        assert!(Precision::FP8.is_supported_on((9, 0)));
+        assert!(Precision::FP16.is_supported_on((6, 0)));
+
+        assert_eq!(Precision::BF16.fallback_on((7, 0)), Precision::FP16);
+        assert_eq!(Precision::FP8.fallback_on((8, 6)), Precision::BF16);
+        assert_eq!(Precision::FP8.fallback_on((7, 5)), Precision::FP16);
+        assert_eq!(Precision::FP8.fallback_on((8, 9)), Precision::FP8);
+        assert_eq!(Precision::NF4.fallback_on((7, 5)), Precision::NF4);
+        // No kernel runs the 4-bit GPTQ weights on a Maxwell GPU
+        assert_eq!(
+            Precision::GPTQ { bits: 4 }.fallback_on((5, 2)),
+            Precision::FP16
+        );
+    }
+
+    #[test]
+    fn test_precision_from_json() {
+        let precisions = vec![
+            (
+                serde_json::json!({
+                    "torch_dtype": "float16",
+                    "quantization_config": {"quant_method": "gptq", "bits": 8}
+                }),
+                Precision::GPTQ { bits: 8 },
+            ),
+            (
+                serde_json::json!({"quantization_config": {"quant_method": "awq"}}),
+                Precision::AWQ { bits: 4 },
+            ),
+            (
+                serde_json::json!({"quantization_config": {"quant_method": "gptq", "bits": 0}}),
+                Precision::GPTQ { bits: 4 },
+            ),
+            (
+                serde_json::json!({
+                    "quantization_config": {"quant_method": "bitsandbytes", "load_in_4bit": true}
+                }),
+                Precision::NF4,
+            ),
+            (
+                serde_json::json!({
+                    "quantization_config": {"quant_method": "bitsandbytes", "load_in_8bit": true}
+                }),
+                Precision::INT8,
+            ),
+            (
+                serde_json::json!({"torch_dtype": "bfloat16"}),
+                Precision::BF16,
+            ),
+            (serde_json::json!({}), Precision::FP32),
+        ];
+        for (value, precision) in precisions {
+            assert_eq!(Precision::from_json(&value), precision);
+        }
+
+        assert_eq!("Q4_K_M".parse::<QuantType>().unwrap(), QuantType::Q4_K);
+        assert_eq!("q8_0".parse::<QuantType>().unwrap(), QuantType::Q8_0);
+        assert!(matches!(
+            "IQ9_XL".parse::<QuantType>(),
+            Err(ModelError::UnknownQuantization(_))
+        ));
+    }
+
+    #[test]
+    fn test_precision_from_str() {
+        let precisions = [
+            Precision::FP32,
+            Precision::BF16,
+            Precision::NF4,
+            Precision::GPTQ { bits: 8 },
+            Precision::AWQ { bits: 4 },
+            Precision::GGUF(QuantType::Q5_K),
+        ];
+        // The displayed names are parsed back
+        for precision in precisions {
+            assert_eq!(
+                precision.to_string().parse::<Precision>().unwrap(),
+                precision
+            );
+        }
+        assert_eq!(
+            "int4".parse::<Precision>().unwrap(),
+            Precision::GPTQ { bits: 4 }
+        );
+        assert_eq!(
+            "AWQ".parse::<Precision>().unwrap(),
+            Precision::AWQ { bits: 4 }
+        );
+        assert_eq!(
+            "Q4_K_M".parse::<Precision>().unwrap(),
+            Precision::GGUF(QuantType::Q4_K)
+        );
+        assert!(matches!(
+            "int3".parse::<Precision>(),
+            Err(ModelError::UnknownQuantization(_))
+        ));
+        // The bit widths without a kernel, including the 0 bits dividing the overhead factor
+        for precision in ["gptq0", "awq-0bit", "gptq-1bit", "awq9", "gptq-16bit"] {
+            assert!(
+                matches!(
+                    precision.parse::<Precision>(),
+                    Err(ModelError::UnknownQuantization(_))
+                ),
+                "{}",
+                precision
+            );
+        }
+        assert_eq!(
+            "gptq-2bit".parse::<Precision>().unwrap(),
+            Precision::GPTQ { bits: 2 }
+        );
+    }
+
     #[test]
     fn test_model_libraries_equality() {
         let lib1 = ModelLibraries::PyTorch;
@@ -237,6 +1491,53 @@ mod tests {
         let lib2 = ModelLibraries::TensorFlow;
         assert_eq!(format!("{:?}", lib1), "PyTorch");
         assert_eq!(format!("{:?}", lib2), "TensorFlow");
+        assert_eq!(lib1.to_string(), "pytorch");
+        assert_eq!(lib2.to_string(), "tf");
+        assert_eq!(
+            serde_json::to_value(vec![lib1, lib2]).unwrap(),
+            serde_json::json!(["pytorch", "tf"])
+        );
+    }
+
+    #[test]
+    fn test_model_libraries_from_str() {
+        assert_eq!(
+            "pytorch".parse::<ModelLibraries>().unwrap(),
+            ModelLibraries::PyTorch
+        );
+        assert_eq!(
+            "safetensors".parse::<ModelLibraries>().unwrap(),
+            ModelLibraries::Safetensors
+        );
+        assert_eq!(
+            "onnx".parse::<ModelLibraries>().unwrap(),
+            ModelLibraries::Onnx
+        );
+        assert_eq!(
+            "tensorflow".parse::<ModelLibraries>().unwrap(),
+            ModelLibraries::TensorFlow
+        );
+        assert!("text-generation".parse::<ModelLibraries>().is_err());
+
+        let tags = vec![
+            "transformers".to_string(),
+            "safetensors".to_string(),
+            "llama".to_string(),
+            "text-generation".to_string(),
+            "license:apache-2.0".to_string(),
+        ];
+        assert_eq!(
+            ModelLibraries::from_tags(&tags),
+            vec![ModelLibraries::Transformers, ModelLibraries::Safetensors]
+        );
+        assert_eq!(
+            ModelLibraries::from_json(&serde_json::json!({"tags": ["pytorch", "jax"]})),
+            vec![ModelLibraries::PyTorch, ModelLibraries::Jax]
+        );
+        assert_eq!(
+            ModelLibraries::from_json(&serde_json::json!({})),
+            vec![ModelLibraries::PyTorch]
+        );
     }
 
     #[test]
@@ -286,5 +1587,95 @@ mod tests {
             ModelLibraries::Transformers,
         ];
         assert_eq!(libraries.len(), 42);
+        for library in libraries {
+            assert_eq!(
+                library.to_string().parse::<ModelLibraries>().unwrap(),
+                library
+            );
+        }
+    }
+    #[test]
+    fn test_model_error_context() {
+        let error = ModelError::MissingField("num_attention_heads".to_string())
+            .in_sub_config("text_config")
+            .with_model_type("llava")
+            .with_repo_id("llava-hf/llava-1.5-7b-hf");
+        assert_eq!(error.field_path(), Some("text_config.num_attention_heads"));
+        assert_eq!(error.model_type(), Some("llava"));
+        assert_eq!(error.repo_id(), Some("llava-hf/llava-1.5-7b-hf"));
+        assert_eq!(
+            error.to_string(),
+            "Invalid config of llava-hf/llava-1.5-7b-hf (model_type: llava): \
+            Missing field: text_config.num_attention_heads"
+        );
+        assert_eq!(
+            error.source().unwrap().to_string(),
+            "Missing field: text_config.num_attention_heads"
+        );
+
+        let error = ModelError::UnknownLibrary("unknown".to_string()).in_sub_config("encoder");
+        assert_eq!(error.field_path(), None);
+        assert!(error.source().is_none());
+        let error = ModelError::from(serde_json::from_str::<Value>("{").unwrap_err());
+        assert!(error.source().is_some());
+    }
+
+    #[test]
+    fn test_composite_model_config() {
+        let mut value = serde_json::json!({
+            "model_type": "novel_vlm",
+            "torch_dtype": "bfloat16",
+            "text_config": {
+                "model_type": "llama",
+                "hidden_size": 4096,
+                "intermediate_size": 11008,
+                "max_position_embeddings": 4096,
+                "num_attention_heads": 32,
+                "num_hidden_layers": 32,
+                "vocab_size": 32000
+            },
+            "vision_config": {
+                "model_type": "vit",
+                "hidden_size": 1024,
+                "intermediate_size": 4096,
+                "num_attention_heads": 16,
+                "num_hidden_layers": 24,
+                "image_size": 336,
+                "patch_size": 14
+            }
+        });
+        assert!(CompositeModelConfig::has_sub_configs(&value));
+        let config = CompositeModelConfig::from_json(value.clone()).unwrap();
+        let text_config = config.sub_config("text_config").unwrap();
+        let vision_config = config.sub_config("vision_config").unwrap();
+        assert_eq!(config.sub_configs().len(), 2);
+        assert!(config.sub_config("audio_config").is_none());
+        assert_eq!(config.hidden_size(), 4096);
+        assert_eq!(config.num_hidden_layers(), 32);
+        assert_eq!(config.model_family(), ModelFamily::Multimodal);
+        assert_eq!(
+            config.num_parameters(),
+            text_config.num_parameters() + vision_config.num_parameters()
+        );
+        assert_eq!(config.dtype(), Dtype::BF16);
+        assert_eq!(vision_config.dtype(), Dtype::BF16);
+        assert_eq!(config.model_type(), "novel_vlm");
+
+        // Unsupported model types nesting sub-configs are dispatched to the composite config
+        let model_config = ModelConfig::from_json(value.clone()).unwrap();
+        assert!(matches!(model_config, ModelConfig::Composite(_)));
+        assert!(!model_config.is_low_confidence());
+
+        value["vision_config"]
+            .as_object_mut()
+            .unwrap()
+            .remove("patch_size");
+        let error = ModelConfig::from_json(value).unwrap_err();
+        assert_eq!(error.field_path(), Some("vision_config.patch_size"));
+        assert_eq!(error.model_type(), Some("novel_vlm"));
+
+        let value = serde_json::json!({"model_type": "novel_vlm", "text_config": null});
+        assert!(!CompositeModelConfig::has_sub_configs(&value));
+        assert!(CompositeModelConfig::from_json(value).is_err());
     }
 }
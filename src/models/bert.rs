@@ -2,7 +2,7 @@
 use serde::Deserialize;
 use serde_json::Value;
 
-use crate::models::{ModelConfigTrait, ModelError, ModelLibraries};
+use crate::models::{Dtype, ModelConfigTrait, ModelError, ModelFamily, ModelLibraries};
 
 /// A struct representing the Bert architecture parameters
 #[derive(Clone, Debug, Deserialize)]
@@ -81,6 +81,12 @@ impl BertParams {
 pub struct BertModelConfig {
     /// Bert model parameters
     params: BertParams,
+    /// Bert model vocab_size
+    vocab_size: i32,
+    /// Bert model tie_word_embeddings, whether the LM head shares the token embeddings weights
+    tie_word_embeddings: bool,
+    /// Bert model weights precision
+    dtype: Dtype,
     /// Bert model type
     model_type: String,
     /// Bert model available libraries
@@ -92,11 +98,17 @@ impl BertModelConfig {
     /// Build a new `BertModelConfig` struct based on the provided parameters
     pub fn new(
         params: BertParams,
+        vocab_size: i32,
+        tie_word_embeddings: bool,
+        dtype: Dtype,
         model_type: String,
         available_libraries: Vec<ModelLibraries>,
     ) -> BertModelConfig {
         BertModelConfig {
             params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
             model_type,
             available_libraries,
         }
@@ -125,6 +137,22 @@ impl ModelConfigTrait for BertModelConfig {
         self.params.num_hidden_layers
     }
 
+    fn model_family(&self) -> ModelFamily {
+        ModelFamily::EncoderLM
+    }
+
+    fn vocab_size(&self) -> i32 {
+        self.vocab_size
+    }
+
+    fn tie_word_embeddings(&self) -> bool {
+        self.tie_word_embeddings
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
     fn model_type(&self) -> &str {
         &self.model_type
     }
@@ -136,20 +164,27 @@ impl ModelConfigTrait for BertModelConfig {
     fn from_json(value: Value) -> Result<Self, ModelError> {
         let params = BertParams::from_json(value.clone())?;
 
+        let vocab_size = value["vocab_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("vocab_size".to_string()))?
+            as i32;
+
+        let tie_word_embeddings = value["tie_word_embeddings"].as_bool().unwrap_or(true);
+
+        let dtype = Dtype::from_json(&value);
+
         let model_type = match value["model_type"].as_str() {
             Some(model_type) => model_type.to_string(),
             None => return Err(ModelError::MissingField("model_type".to_string())),
         };
 
-        // TODO: Implement this
-        let available_libraries = vec![ModelLibraries::PyTorch];
-        // let available_libraries = match value["available_libraries"].as_array() {
-        //     Some(al) => al.iter().map(|v| ModelLibraries::from_str(v.as_str().unwrap()).unwrap()).collect(),
-        //     None => return Err(ModelError::MissingField("available_libraries".to_string())),
-        // };
+        let available_libraries = ModelLibraries::from_json(&value);
 
         Ok(BertModelConfig {
             params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
             model_type,
             available_libraries,
         })
@@ -176,6 +211,9 @@ mod tests {
         let bert_params = BertParams::new(768, 3072, 512, 12, 12);
         let bert_model_config = BertModelConfig::new(
             bert_params,
+            30522,
+            true,
+            Dtype::F32,
             "bert".to_string(),
             vec![ModelLibraries::PyTorch],
         );
@@ -196,6 +234,9 @@ mod tests {
         let bert_params = BertParams::new(768, 3072, 512, 12, 12);
         let bert_model_config = BertModelConfig::new(
             bert_params,
+            30522,
+            true,
+            Dtype::F32,
             "bert".to_string(),
             vec![ModelLibraries::PyTorch],
         );
@@ -2,7 +2,7 @@
 use serde::Deserialize;
 use serde_json::Value;
 
-use crate::models::{ModelConfigTrait, ModelError, ModelLibraries};
+use crate::models::{Dtype, ModelConfigTrait, ModelError, ModelLibraries};
 
 /// A struct representing the BLOOM architecture parameters
 #[derive(Clone, Debug, Deserialize)]
@@ -31,21 +31,28 @@ impl BloomParams {
     }
     /// Build from a JSON value
     pub fn from_json(value: Value) -> Result<BloomParams, ModelError> {
-        let n_embd = value["n_embd"]
+        // Early BLOOM configs use n_embed, later ones hidden_size
+        let n_embd = value["hidden_size"]
             .as_i64()
-            .ok_or(ModelError::MissingField("n_embd".to_string()))? as i32;
+            .or_else(|| value["n_embed"].as_i64())
+            .ok_or(ModelError::MissingField("hidden_size".to_string()))?
+            as i32;
 
+        // The MLP is always 4 * hidden_size wide, it is not stored in the config
         let n_inner = value["n_inner"]
             .as_i64()
-            .ok_or(ModelError::MissingField("n_inner".to_string()))? as i32;
+            .map(|val| val as i32)
+            .unwrap_or(4 * n_embd);
 
-        let num_attention_heads = value["num_attention_heads"]
+        let num_attention_heads = value["n_head"]
             .as_i64()
-            .ok_or(ModelError::MissingField("num_attention_heads".to_string()))?
+            .or_else(|| value["num_attention_heads"].as_i64())
+            .ok_or(ModelError::MissingField("n_head".to_string()))?
             as i32;
 
         let n_layer = value["n_layer"]
             .as_i64()
+            .or_else(|| value["num_hidden_layers"].as_i64())
             .ok_or(ModelError::MissingField("n_layer".to_string()))? as i32;
 
         Ok(BloomParams::new(
@@ -62,6 +69,12 @@ impl BloomParams {
 pub struct BloomModelConfig {
     /// BLOOM model parameters
     params: BloomParams,
+    /// BLOOM model vocab_size
+    vocab_size: i32,
+    /// BLOOM model tie_word_embeddings, whether the LM head shares the token embeddings weights
+    tie_word_embeddings: bool,
+    /// BLOOM model weights precision
+    dtype: Dtype,
     /// BLOOM model type
     model_type: String,
     /// BLOOM model available libraries
@@ -73,11 +86,17 @@ impl BloomModelConfig {
     /// Build a new `BloomModelConfig` struct based on the provided parameters
     pub fn new(
         params: BloomParams,
+        vocab_size: i32,
+        tie_word_embeddings: bool,
+        dtype: Dtype,
         model_type: String,
         available_libraries: Vec<ModelLibraries>,
     ) -> BloomModelConfig {
         BloomModelConfig {
             params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
             model_type,
             available_libraries,
         }
@@ -107,6 +126,18 @@ impl ModelConfigTrait for BloomModelConfig {
         self.params.n_layer
     }
 
+    fn vocab_size(&self) -> i32 {
+        self.vocab_size
+    }
+
+    fn tie_word_embeddings(&self) -> bool {
+        self.tie_word_embeddings
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
     fn model_type(&self) -> &str {
         &self.model_type
     }
@@ -118,20 +149,27 @@ impl ModelConfigTrait for BloomModelConfig {
     fn from_json(value: Value) -> Result<Self, ModelError> {
         let params = BloomParams::from_json(value.clone())?;
 
+        let vocab_size = value["vocab_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("vocab_size".to_string()))?
+            as i32;
+
+        let tie_word_embeddings = value["tie_word_embeddings"].as_bool().unwrap_or(true);
+
+        let dtype = Dtype::from_json(&value);
+
         let model_type = match value["model_type"].as_str() {
             Some(model_type) => model_type.to_string(),
             None => return Err(ModelError::MissingField("model_type".to_string())),
         };
 
-        // TODO: Implement this
-        let available_libraries = vec![ModelLibraries::PyTorch];
-        // let available_libraries = match value["available_libraries"].as_array() {
-        //     Some(al) => al.iter().map(|v| ModelLibraries::from_str(v.as_str().unwrap()).unwrap()).collect(),
-        //     None => return Err(ModelError::MissingField("available_libraries".to_string())),
-        // };
+        let available_libraries = ModelLibraries::from_json(&value);
 
         Ok(BloomModelConfig::new(
             params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
             model_type,
             available_libraries,
         ))
@@ -168,6 +206,9 @@ mod tests {
 
         let bloom_model_config = BloomModelConfig {
             params: bloom_params,
+            vocab_size: 250880,
+            tie_word_embeddings: true,
+            dtype: Dtype::F32,
             model_type: "bloom".to_string(),
             available_libraries: vec![ModelLibraries::PyTorch],
         };
@@ -194,6 +235,9 @@ mod tests {
 
         let bloom_model_config = BloomModelConfig {
             params: bloom_params,
+            vocab_size: 250880,
+            tie_word_embeddings: true,
+            dtype: Dtype::F32,
             model_type: "bloom".to_string(),
             available_libraries: vec![ModelLibraries::PyTorch],
         };
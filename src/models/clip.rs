@@ -0,0 +1,272 @@
+//! Module for the CLIP model
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::models::base::merge_with_defaults;
+use crate::models::{
+    BertModelConfig, Dtype, ModelConfigTrait, ModelError, ModelFamily, ModelLibraries,
+    ParameterBreakdown, ViTModelConfig,
+};
+
+/// Build the text tower defaults of a CLIP config, used for the fields missing from `text_config`
+fn default_text_config() -> Value {
+    json!({
+        "model_type": "clip_text_model",
+        "hidden_size": 512,
+        "intermediate_size": 2048,
+        "max_position_embeddings": 77,
+        "num_attention_heads": 8,
+        "num_hidden_layers": 12,
+        "vocab_size": 49408
+    })
+}
+
+/// Build the vision tower defaults of a CLIP config, used for the fields missing from
+/// `vision_config`
+pub(crate) fn default_vision_config() -> Value {
+    json!({
+        "model_type": "clip_vision_model",
+        "hidden_size": 768,
+        "intermediate_size": 3072,
+        "num_attention_heads": 12,
+        "num_hidden_layers": 12,
+        "image_size": 224,
+        "patch_size": 32,
+        "num_channels": 3
+    })
+}
+
+/// A struct representing a CLIP model configuration, made of a text and a vision tower
+#[derive(Clone, Debug, Deserialize)]
+pub struct CLIPModelConfig {
+    /// CLIP text tower configuration
+    text_config: BertModelConfig,
+    /// CLIP vision tower configuration
+    vision_config: ViTModelConfig,
+    /// CLIP model dimension of the shared text and image embeddings space
+    projection_dim: i32,
+    /// CLIP model weights precision
+    dtype: Dtype,
+    /// CLIP model type
+    model_type: String,
+    /// CLIP model available libraries
+    available_libraries: Vec<ModelLibraries>,
+}
+
+/// CLIP model implementation
+impl CLIPModelConfig {
+    /// Build a new `CLIPModelConfig` struct based on the provided parameters
+    pub fn new(
+        text_config: BertModelConfig,
+        vision_config: ViTModelConfig,
+        projection_dim: i32,
+        dtype: Dtype,
+        model_type: String,
+        available_libraries: Vec<ModelLibraries>,
+    ) -> CLIPModelConfig {
+        CLIPModelConfig {
+            text_config,
+            vision_config,
+            projection_dim,
+            dtype,
+            model_type,
+            available_libraries,
+        }
+    }
+    /// Returns the text tower configuration
+    pub fn text_config(&self) -> &BertModelConfig {
+        &self.text_config
+    }
+    /// Returns the vision tower configuration
+    pub fn vision_config(&self) -> &ViTModelConfig {
+        &self.vision_config
+    }
+    /// Returns the dimension of the shared text and image embeddings space
+    pub fn projection_dim(&self) -> i32 {
+        self.projection_dim
+    }
+}
+
+/// Implementation of the `ModelConfigTrait` trait for `CLIPModelConfig`.
+///
+/// The flat accessors describe the text tower, use `vision_config` for the vision tower.
+impl ModelConfigTrait for CLIPModelConfig {
+    fn hidden_size(&self) -> i32 {
+        self.text_config.hidden_size()
+    }
+
+    fn intermediate_size(&self) -> i32 {
+        self.text_config.intermediate_size()
+    }
+
+    fn max_position_embeddings(&self) -> i32 {
+        self.text_config.max_position_embeddings()
+    }
+
+    fn num_attention_heads(&self) -> i32 {
+        self.text_config.num_attention_heads()
+    }
+
+    fn num_hidden_layers(&self) -> i32 {
+        self.text_config.num_hidden_layers()
+    }
+
+    fn model_family(&self) -> ModelFamily {
+        ModelFamily::Multimodal
+    }
+
+    fn vocab_size(&self) -> i32 {
+        self.text_config.vocab_size()
+    }
+
+    /// Both towers and their projections to the shared embeddings space are counted
+    fn parameter_breakdown(&self) -> ParameterBreakdown {
+        let projections = self.projection_dim as i64
+            * (self.text_config.hidden_size() + self.vision_config.hidden_size()) as i64;
+        self.text_config.parameter_breakdown()
+            + self.vision_config.parameter_breakdown()
+            + ParameterBreakdown::new(0, 0, 0, 0, projections, 0)
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn model_type(&self) -> &str {
+        &self.model_type
+    }
+
+    fn available_libraries(&self) -> &[ModelLibraries] {
+        &self.available_libraries
+    }
+
+    fn from_json(value: Value) -> Result<Self, ModelError> {
+        let mut text_config = merge_with_defaults(default_text_config(), &value["text_config"]);
+        text_config["model_type"] = json!("clip_text_model");
+        let text_config = BertModelConfig::from_json(text_config)
+            .map_err(|err| err.in_sub_config("text_config"))?;
+
+        let mut vision_config =
+            merge_with_defaults(default_vision_config(), &value["vision_config"]);
+        vision_config["model_type"] = json!("clip_vision_model");
+        let vision_config = ViTModelConfig::from_json(vision_config)
+            .map_err(|err| err.in_sub_config("vision_config"))?;
+
+        let projection_dim = value["projection_dim"].as_i64().unwrap_or(512) as i32;
+
+        let dtype = Dtype::from_json(&value);
+
+        let model_type = match value["model_type"].as_str() {
+            Some(model_type) => model_type.to_string(),
+            None => return Err(ModelError::MissingField("model_type".to_string())),
+        };
+
+        let available_libraries = ModelLibraries::from_json(&value);
+
+        Ok(CLIPModelConfig::new(
+            text_config,
+            vision_config,
+            projection_dim,
+            dtype,
+            model_type,
+            available_libraries,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{BertParams, ViTParams};
+
+    #[test]
+    fn test_clip_merge_with_defaults() {
+        let merged = merge_with_defaults(
+            json!({"hidden_size": 512, "num_hidden_layers": 12}),
+            &json!({"hidden_size": 768, "projection_dim": null}),
+        );
+        assert_eq!(merged, json!({"hidden_size": 768, "num_hidden_layers": 12}));
+        assert_eq!(
+            merge_with_defaults(default_text_config(), &Value::Null),
+            default_text_config()
+        );
+    }
+
+    #[test]
+    fn test_clip_model_config() {
+        let text_config = BertModelConfig::new(
+            BertParams::new(512, 2048, 77, 8, 12),
+            49408,
+            true,
+            Dtype::F32,
+            "clip_text_model".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        let vision_config = ViTModelConfig::new(
+            ViTParams::new(768, 3072, 12, 12, 224, 32, None),
+            Dtype::F32,
+            "clip_vision_model".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        let clip_model_config = CLIPModelConfig::new(
+            text_config,
+            vision_config,
+            512,
+            Dtype::F32,
+            "clip".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        assert_eq!(clip_model_config.projection_dim, 512);
+        assert_eq!(clip_model_config.text_config.hidden_size(), 512);
+        assert_eq!(clip_model_config.vision_config.hidden_size(), 768);
+        assert_eq!(clip_model_config.model_type, "clip");
+        assert_eq!(
+            clip_model_config.available_libraries,
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+
+    #[test]
+    fn test_clip_model_trait_implementation() {
+        let value = json!({
+            "model_type": "clip",
+            "projection_dim": 768,
+            "text_config": {
+                "hidden_size": 768,
+                "intermediate_size": 3072,
+                "num_attention_heads": 12
+            },
+            "vision_config": {
+                "hidden_size": 1024,
+                "intermediate_size": 4096,
+                "num_attention_heads": 16,
+                "num_hidden_layers": 24,
+                "patch_size": 14
+            }
+        });
+        let clip_model_config = CLIPModelConfig::from_json(value).unwrap();
+        assert_eq!(clip_model_config.hidden_size(), 768);
+        assert_eq!(clip_model_config.intermediate_size(), 3072);
+        assert_eq!(clip_model_config.max_position_embeddings(), 77);
+        assert_eq!(clip_model_config.num_attention_heads(), 12);
+        assert_eq!(clip_model_config.num_hidden_layers(), 12);
+        assert_eq!(clip_model_config.projection_dim(), 768);
+        assert_eq!(
+            clip_model_config.text_config().model_type(),
+            "clip_text_model"
+        );
+        assert_eq!(clip_model_config.vision_config().num_hidden_layers(), 24);
+        assert_eq!(clip_model_config.vision_config().num_patches(), 256);
+        assert_eq!(
+            clip_model_config.num_parameters(),
+            clip_model_config.text_config().num_parameters()
+                + clip_model_config.vision_config().num_parameters()
+                + 768 * (768 + 1024)
+        );
+        assert_eq!(clip_model_config.model_type(), "clip");
+        assert_eq!(
+            clip_model_config.available_libraries(),
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+}
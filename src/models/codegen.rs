@@ -0,0 +1,276 @@
+//! Module for the CodeGen model
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::models::{Dtype, ModelConfigTrait, ModelError, ModelLibraries, ParameterBreakdown};
+
+/// A struct representing the CodeGen architecture parameters
+#[derive(Clone, Debug, Deserialize)]
+pub struct CodeGenParams {
+    /// CodeGen model hidden_size
+    n_embd: i32,
+    /// CodeGen model intermediate_size
+    n_inner: i32,
+    /// CodeGen model max_position_embeddings
+    n_positions: i32,
+    /// CodeGen model num_attention_heads
+    n_head: i32,
+    /// CodeGen model num_hidden_layers
+    n_layer: i32,
+    /// CodeGen model number of dimensions of each head the rotary embeddings are applied to
+    rotary_dim: i32,
+}
+
+/// CodeGen model parameters implementation
+impl CodeGenParams {
+    /// Build a new `CodeGenParams` struct based on the provided parameters
+    pub fn new(
+        n_embd: i32,
+        n_inner: Option<i32>,
+        n_positions: i32,
+        n_head: i32,
+        n_layer: i32,
+        rotary_dim: i32,
+    ) -> CodeGenParams {
+        let n_inner = n_inner.unwrap_or(4 * n_embd);
+        CodeGenParams {
+            n_embd,
+            n_inner,
+            n_positions,
+            n_head,
+            n_layer,
+            rotary_dim,
+        }
+    }
+    /// Build from a JSON value
+    pub fn from_json(value: Value) -> Result<CodeGenParams, ModelError> {
+        let n_embd = value["n_embd"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("n_embd".to_string()))? as i32;
+
+        let n_inner = value["n_inner"].as_i64().map(|val| val as i32);
+
+        let n_positions = value["n_positions"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("n_positions".to_string()))?
+            as i32;
+
+        let n_head = value["n_head"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("n_head".to_string()))? as i32;
+
+        let n_layer = value["n_layer"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("n_layer".to_string()))? as i32;
+
+        let rotary_dim = value["rotary_dim"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("rotary_dim".to_string()))?
+            as i32;
+
+        Ok(CodeGenParams::new(
+            n_embd,
+            n_inner,
+            n_positions,
+            n_head,
+            n_layer,
+            rotary_dim,
+        ))
+    }
+}
+
+/// A struct representing a CodeGen model configuration
+#[derive(Clone, Debug, Deserialize)]
+pub struct CodeGenModelConfig {
+    /// CodeGen model parameters
+    params: CodeGenParams,
+    /// CodeGen model vocab_size
+    vocab_size: i32,
+    /// CodeGen model tie_word_embeddings, whether the LM head shares the token embeddings weights
+    tie_word_embeddings: bool,
+    /// CodeGen model weights precision
+    dtype: Dtype,
+    /// CodeGen model type
+    model_type: String,
+    /// CodeGen model available libraries
+    available_libraries: Vec<ModelLibraries>,
+}
+
+/// CodeGen model implementation
+impl CodeGenModelConfig {
+    /// Build a new `CodeGenModelConfig` struct based on the provided parameters
+    pub fn new(
+        params: CodeGenParams,
+        vocab_size: i32,
+        tie_word_embeddings: bool,
+        dtype: Dtype,
+        model_type: String,
+        available_libraries: Vec<ModelLibraries>,
+    ) -> CodeGenModelConfig {
+        CodeGenModelConfig {
+            params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
+            model_type,
+            available_libraries,
+        }
+    }
+    /// Returns the number of dimensions of each head the rotary embeddings are applied to
+    pub fn rotary_dim(&self) -> i32 {
+        self.params.rotary_dim
+    }
+}
+
+/// Implementation of the `ModelConfigTrait` trait for `CodeGenModelConfig`
+impl ModelConfigTrait for CodeGenModelConfig {
+    fn hidden_size(&self) -> i32 {
+        self.params.n_embd
+    }
+
+    fn intermediate_size(&self) -> i32 {
+        self.params.n_inner
+    }
+
+    fn max_position_embeddings(&self) -> i32 {
+        self.params.n_positions
+    }
+
+    fn num_attention_heads(&self) -> i32 {
+        self.params.n_head
+    }
+
+    fn num_hidden_layers(&self) -> i32 {
+        self.params.n_layer
+    }
+
+    fn vocab_size(&self) -> i32 {
+        self.vocab_size
+    }
+
+    fn tie_word_embeddings(&self) -> bool {
+        self.tie_word_embeddings
+    }
+
+    /// Positions are encoded with rotary embeddings, so there are no position embedding weights
+    fn parameter_breakdown(&self) -> ParameterBreakdown {
+        let n_embd = self.params.n_embd as i64;
+        let n_layer = self.params.n_layer as i64;
+        ParameterBreakdown::new(
+            self.vocab_size as i64 * n_embd,
+            4 * n_embd * n_embd * n_layer,
+            2 * n_embd * self.params.n_inner as i64 * n_layer,
+            self.lm_head_parameters(),
+            0,
+            self.params.n_layer,
+        )
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn model_type(&self) -> &str {
+        &self.model_type
+    }
+
+    fn available_libraries(&self) -> &[ModelLibraries] {
+        &self.available_libraries
+    }
+
+    fn from_json(value: Value) -> Result<Self, ModelError> {
+        let params = CodeGenParams::from_json(value.clone())?;
+
+        let vocab_size = value["vocab_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("vocab_size".to_string()))?
+            as i32;
+
+        let tie_word_embeddings = value["tie_word_embeddings"].as_bool().unwrap_or(false);
+
+        let dtype = Dtype::from_json(&value);
+
+        let model_type = match value["model_type"].as_str() {
+            Some(model_type) => model_type.to_string(),
+            None => return Err(ModelError::MissingField("model_type".to_string())),
+        };
+
+        let available_libraries = ModelLibraries::from_json(&value);
+
+        Ok(CodeGenModelConfig::new(
+            params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
+            model_type,
+            available_libraries,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_codegen_model_params() {
+        let codegen_params = CodeGenParams::new(1024, None, 2048, 16, 20, 32);
+
+        assert_eq!(codegen_params.n_embd, 1024);
+        assert_eq!(codegen_params.n_inner, 4096);
+        assert_eq!(codegen_params.n_positions, 2048);
+        assert_eq!(codegen_params.n_head, 16);
+        assert_eq!(codegen_params.n_layer, 20);
+        assert_eq!(codegen_params.rotary_dim, 32);
+    }
+
+    #[test]
+    fn test_codegen_model_config() {
+        let codegen_params = CodeGenParams::new(1024, None, 2048, 16, 20, 32);
+        let codegen_model_config = CodeGenModelConfig::new(
+            codegen_params,
+            51200,
+            false,
+            Dtype::F32,
+            "codegen".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        assert_eq!(codegen_model_config.params.n_embd, 1024);
+        assert_eq!(codegen_model_config.params.rotary_dim, 32);
+        assert_eq!(codegen_model_config.model_type, "codegen");
+        assert_eq!(
+            codegen_model_config.available_libraries,
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+
+    #[test]
+    fn test_codegen_model_trait_implementation() {
+        let value = json!({
+            "model_type": "codegen",
+            "n_ctx": 2048,
+            "n_embd": 4096,
+            "n_head": 16,
+            "n_inner": null,
+            "n_layer": 33,
+            "n_positions": 2048,
+            "rotary_dim": 64,
+            "vocab_size": 51200
+        });
+        let codegen_model_config = CodeGenModelConfig::from_json(value).unwrap();
+        assert_eq!(codegen_model_config.hidden_size(), 4096);
+        assert_eq!(codegen_model_config.intermediate_size(), 16384);
+        assert_eq!(codegen_model_config.max_position_embeddings(), 2048);
+        assert_eq!(codegen_model_config.num_attention_heads(), 16);
+        assert_eq!(codegen_model_config.num_hidden_layers(), 33);
+        assert_eq!(codegen_model_config.rotary_dim(), 64);
+        assert_eq!(codegen_model_config.vocab_size(), 51200);
+        assert_eq!(codegen_model_config.num_parameters(), 7_063_207_936);
+        assert_eq!(codegen_model_config.model_type(), "codegen");
+        assert_eq!(
+            codegen_model_config.available_libraries(),
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+}
@@ -0,0 +1,611 @@
+//! Module for the DeepSeek models (DeepSeek-MoE and DeepSeek-V2)
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::models::{
+    Dtype, ModelConfigTrait, ModelError, ModelLibraries, ParameterBreakdown, RopeParams,
+};
+
+/// A struct representing the DeepSeek architecture parameters
+#[derive(Clone, Debug, Deserialize)]
+pub struct DeepSeekParams {
+    /// DeepSeek model hidden_size
+    hidden_size: i32,
+    /// DeepSeek model intermediate_size of the dense layers
+    intermediate_size: i32,
+    /// DeepSeek model max_position_embeddings
+    max_position_embeddings: i32,
+    /// DeepSeek model num_attention_heads
+    num_attention_heads: i32,
+    /// DeepSeek model num_hidden_layers
+    num_hidden_layers: i32,
+    /// DeepSeek model number of key/value heads, which defaults to `num_attention_heads`
+    num_key_value_heads: i32,
+    /// DeepSeek model rotary position embeddings parameters
+    rope_params: RopeParams,
+}
+
+/// DeepSeek model parameters implementation
+impl DeepSeekParams {
+    /// Build a new `DeepSeekParams` struct based on the provided parameters
+    pub fn new(
+        hidden_size: i32,
+        intermediate_size: i32,
+        max_position_embeddings: i32,
+        num_attention_heads: i32,
+        num_hidden_layers: i32,
+        num_key_value_heads: Option<i32>,
+        rope_params: RopeParams,
+    ) -> DeepSeekParams {
+        let num_key_value_heads = num_key_value_heads.unwrap_or(num_attention_heads);
+        DeepSeekParams {
+            hidden_size,
+            intermediate_size,
+            max_position_embeddings,
+            num_attention_heads,
+            num_hidden_layers,
+            num_key_value_heads,
+            rope_params,
+        }
+    }
+    /// Build from a JSON value
+    pub fn from_json(value: Value) -> Result<DeepSeekParams, ModelError> {
+        let hidden_size = value["hidden_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("hidden_size".to_string()))?
+            as i32;
+
+        let intermediate_size = value["intermediate_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("intermediate_size".to_string()))?
+            as i32;
+
+        let max_position_embeddings =
+            value["max_position_embeddings"]
+                .as_i64()
+                .ok_or(ModelError::MissingField(
+                    "max_position_embeddings".to_string(),
+                ))? as i32;
+
+        let num_attention_heads = value["num_attention_heads"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("num_attention_heads".to_string()))?
+            as i32;
+
+        let num_hidden_layers = value["num_hidden_layers"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("num_hidden_layers".to_string()))?
+            as i32;
+
+        let num_key_value_heads = value["num_key_value_heads"].as_i64().map(|val| val as i32);
+
+        let rope_params = RopeParams::from_json(&value)?;
+
+        Ok(DeepSeekParams::new(
+            hidden_size,
+            intermediate_size,
+            max_position_embeddings,
+            num_attention_heads,
+            num_hidden_layers,
+            num_key_value_heads,
+            rope_params,
+        ))
+    }
+}
+
+/// A struct representing the DeepSeek Mixture-of-Experts parameters
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct DeepSeekMoEParams {
+    /// Number of routed experts of every MoE layer
+    n_routed_experts: i32,
+    /// Number of shared experts of every MoE layer, always active
+    n_shared_experts: i32,
+    /// Number of routed experts activated for each token
+    num_experts_per_tok: i32,
+    /// Intermediate size of every expert
+    moe_intermediate_size: i32,
+    /// Number of dense layers before the first MoE layer
+    first_k_dense_replace: i32,
+    /// Frequency of the MoE layers after the first dense ones
+    moe_layer_freq: i32,
+}
+
+/// DeepSeek Mixture-of-Experts parameters implementation
+impl DeepSeekMoEParams {
+    /// Build a new `DeepSeekMoEParams` struct based on the provided parameters
+    pub fn new(
+        n_routed_experts: i32,
+        n_shared_experts: Option<i32>,
+        num_experts_per_tok: i32,
+        moe_intermediate_size: i32,
+        first_k_dense_replace: Option<i32>,
+        moe_layer_freq: Option<i32>,
+    ) -> DeepSeekMoEParams {
+        DeepSeekMoEParams {
+            n_routed_experts,
+            n_shared_experts: n_shared_experts.unwrap_or(0),
+            num_experts_per_tok,
+            moe_intermediate_size,
+            first_k_dense_replace: first_k_dense_replace.unwrap_or(0),
+            moe_layer_freq: moe_layer_freq.unwrap_or(1),
+        }
+    }
+    /// Returns the number of routed experts of every MoE layer
+    pub fn n_routed_experts(&self) -> i32 {
+        self.n_routed_experts
+    }
+    /// Returns the number of shared experts of every MoE layer
+    pub fn n_shared_experts(&self) -> i32 {
+        self.n_shared_experts
+    }
+    /// Returns the number of routed experts activated for each token
+    pub fn num_experts_per_tok(&self) -> i32 {
+        self.num_experts_per_tok
+    }
+    /// Returns the intermediate size of every expert
+    pub fn moe_intermediate_size(&self) -> i32 {
+        self.moe_intermediate_size
+    }
+    /// Check if the layer at the given index is a MoE layer
+    pub fn is_moe_layer(&self, layer: i32) -> bool {
+        layer >= self.first_k_dense_replace && layer % self.moe_layer_freq.max(1) == 0
+    }
+    /// Build from a JSON value, returns `None` if the model doesn't have any routed expert
+    pub fn from_json(value: &Value) -> Result<Option<DeepSeekMoEParams>, ModelError> {
+        let n_routed_experts = match value["n_routed_experts"].as_i64() {
+            Some(n_routed_experts) => n_routed_experts as i32,
+            None => return Ok(None),
+        };
+
+        let n_shared_experts = value["n_shared_experts"].as_i64().map(|val| val as i32);
+
+        let num_experts_per_tok = value["num_experts_per_tok"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("num_experts_per_tok".to_string()))?
+            as i32;
+
+        let moe_intermediate_size =
+            value["moe_intermediate_size"]
+                .as_i64()
+                .ok_or(ModelError::MissingField(
+                    "moe_intermediate_size".to_string(),
+                ))? as i32;
+
+        let first_k_dense_replace = value["first_k_dense_replace"]
+            .as_i64()
+            .map(|val| val as i32);
+
+        let moe_layer_freq = value["moe_layer_freq"].as_i64().map(|val| val as i32);
+
+        Ok(Some(DeepSeekMoEParams::new(
+            n_routed_experts,
+            n_shared_experts,
+            num_experts_per_tok,
+            moe_intermediate_size,
+            first_k_dense_replace,
+            moe_layer_freq,
+        )))
+    }
+}
+
+/// A struct representing the DeepSeek-V2 Multi-head Latent Attention (MLA) parameters.
+///
+/// Keys and values are compressed into a shared latent vector, which is what gets cached instead
+/// of the per-head keys and values.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct DeepSeekMLAParams {
+    /// Rank of the compressed keys/values latent vector
+    kv_lora_rank: i32,
+    /// Rank of the compressed queries, queries aren't compressed when it's `None`
+    q_lora_rank: Option<i32>,
+    /// Dimension of each head of the keys/queries without rotary embeddings
+    qk_nope_head_dim: i32,
+    /// Dimension of each head of the keys/queries with rotary embeddings
+    qk_rope_head_dim: i32,
+    /// Dimension of each head of the values
+    v_head_dim: i32,
+}
+
+/// DeepSeek Multi-head Latent Attention parameters implementation
+impl DeepSeekMLAParams {
+    /// Build a new `DeepSeekMLAParams` struct based on the provided parameters
+    pub fn new(
+        kv_lora_rank: i32,
+        q_lora_rank: Option<i32>,
+        qk_nope_head_dim: i32,
+        qk_rope_head_dim: i32,
+        v_head_dim: i32,
+    ) -> DeepSeekMLAParams {
+        DeepSeekMLAParams {
+            kv_lora_rank,
+            q_lora_rank,
+            qk_nope_head_dim,
+            qk_rope_head_dim,
+            v_head_dim,
+        }
+    }
+    /// Returns the rank of the compressed keys/values latent vector
+    pub fn kv_lora_rank(&self) -> i32 {
+        self.kv_lora_rank
+    }
+    /// Returns the rank of the compressed queries
+    pub fn q_lora_rank(&self) -> Option<i32> {
+        self.q_lora_rank
+    }
+    /// Returns the dimension of each head of the keys/queries with rotary embeddings
+    pub fn qk_rope_head_dim(&self) -> i32 {
+        self.qk_rope_head_dim
+    }
+    /// Build from a JSON value, returns `None` if the model uses a regular attention
+    pub fn from_json(value: &Value) -> Result<Option<DeepSeekMLAParams>, ModelError> {
+        let kv_lora_rank = match value["kv_lora_rank"].as_i64() {
+            Some(kv_lora_rank) => kv_lora_rank as i32,
+            None => return Ok(None),
+        };
+
+        let q_lora_rank = value["q_lora_rank"].as_i64().map(|val| val as i32);
+
+        let qk_nope_head_dim = value["qk_nope_head_dim"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("qk_nope_head_dim".to_string()))?
+            as i32;
+
+        let qk_rope_head_dim = value["qk_rope_head_dim"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("qk_rope_head_dim".to_string()))?
+            as i32;
+
+        let v_head_dim = value["v_head_dim"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("v_head_dim".to_string()))?
+            as i32;
+
+        Ok(Some(DeepSeekMLAParams::new(
+            kv_lora_rank,
+            q_lora_rank,
+            qk_nope_head_dim,
+            qk_rope_head_dim,
+            v_head_dim,
+        )))
+    }
+}
+
+/// A struct representing a DeepSeek model configuration
+#[derive(Clone, Debug, Deserialize)]
+pub struct DeepSeekModelConfig {
+    /// DeepSeek model parameters
+    params: DeepSeekParams,
+    /// DeepSeek model vocab_size
+    vocab_size: i32,
+    /// DeepSeek model tie_word_embeddings, whether the LM head shares the token embeddings weights
+    tie_word_embeddings: bool,
+    /// DeepSeek model Mixture-of-Experts parameters, if the model has MoE layers
+    moe: Option<DeepSeekMoEParams>,
+    /// DeepSeek model Multi-head Latent Attention parameters, if the model uses MLA
+    mla: Option<DeepSeekMLAParams>,
+    /// DeepSeek model weights precision
+    dtype: Dtype,
+    /// DeepSeek model type
+    model_type: String,
+    /// DeepSeek model available libraries
+    available_libraries: Vec<ModelLibraries>,
+}
+
+/// DeepSeek model implementation
+impl DeepSeekModelConfig {
+    /// Build a new `DeepSeekModelConfig` struct based on the provided parameters
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        params: DeepSeekParams,
+        vocab_size: i32,
+        tie_word_embeddings: bool,
+        moe: Option<DeepSeekMoEParams>,
+        mla: Option<DeepSeekMLAParams>,
+        dtype: Dtype,
+        model_type: String,
+        available_libraries: Vec<ModelLibraries>,
+    ) -> DeepSeekModelConfig {
+        DeepSeekModelConfig {
+            params,
+            vocab_size,
+            tie_word_embeddings,
+            moe,
+            mla,
+            dtype,
+            model_type,
+            available_libraries,
+        }
+    }
+    /// Returns the Mixture-of-Experts parameters
+    pub fn moe(&self) -> Option<&DeepSeekMoEParams> {
+        self.moe.as_ref()
+    }
+    /// Returns the Multi-head Latent Attention parameters
+    pub fn mla(&self) -> Option<&DeepSeekMLAParams> {
+        self.mla.as_ref()
+    }
+    /// Returns the number of values cached for each token by every layer.
+    ///
+    /// MLA only caches the compressed latent vector and the rotary part of the keys, which is
+    /// far smaller than the keys and values of every head.
+    pub fn kv_cache_size_per_token(&self) -> i32 {
+        match &self.mla {
+            Some(mla) => mla.kv_lora_rank + mla.qk_rope_head_dim,
+            None => {
+                let head_dim = self.params.hidden_size / self.params.num_attention_heads;
+                2 * self.params.num_key_value_heads * head_dim
+            }
+        }
+    }
+    /// Returns the number of feed-forward parameters of all the layers, only the shared and the
+    /// activated routed experts are counted when `active_only` is set
+    fn mlp_parameters(&self, active_only: bool) -> i64 {
+        let hidden_size = self.params.hidden_size as i64;
+        let dense_mlp = 3 * hidden_size * self.params.intermediate_size as i64;
+        (0..self.params.num_hidden_layers)
+            .map(|layer| match &self.moe {
+                Some(moe) if moe.is_moe_layer(layer) => {
+                    let routed_experts = match active_only {
+                        true => moe.num_experts_per_tok,
+                        false => moe.n_routed_experts,
+                    };
+                    let experts = (routed_experts + moe.n_shared_experts) as i64;
+                    let router = hidden_size * moe.n_routed_experts as i64;
+                    experts * 3 * hidden_size * moe.moe_intermediate_size as i64 + router
+                }
+                _ => dense_mlp,
+            })
+            .sum()
+    }
+    /// Returns the number of parameters of the attention of every layer
+    fn attention_parameters(&self) -> i64 {
+        let hidden_size = self.params.hidden_size as i64;
+        let num_heads = self.params.num_attention_heads as i64;
+        match &self.mla {
+            Some(mla) => {
+                let qk_head_dim = (mla.qk_nope_head_dim + mla.qk_rope_head_dim) as i64;
+                let kv_lora_rank = mla.kv_lora_rank as i64;
+                let q_proj = match mla.q_lora_rank {
+                    Some(q_lora_rank) => {
+                        let q_lora_rank = q_lora_rank as i64;
+                        hidden_size * q_lora_rank + q_lora_rank * num_heads * qk_head_dim
+                    }
+                    None => hidden_size * num_heads * qk_head_dim,
+                };
+                let kv_a_proj = hidden_size * (kv_lora_rank + mla.qk_rope_head_dim as i64);
+                let kv_b_proj = kv_lora_rank
+                    * num_heads
+                    * (mla.qk_nope_head_dim as i64 + mla.v_head_dim as i64);
+                let o_proj = num_heads * mla.v_head_dim as i64 * hidden_size;
+                q_proj + kv_a_proj + kv_b_proj + o_proj
+            }
+            None => {
+                let head_dim = hidden_size / num_heads;
+                let kv_dim = self.params.num_key_value_heads as i64 * head_dim;
+                2 * hidden_size * hidden_size + 2 * hidden_size * kv_dim
+            }
+        }
+    }
+}
+
+/// Implementation of the `ModelConfigTrait` trait for `DeepSeekModelConfig`
+impl ModelConfigTrait for DeepSeekModelConfig {
+    fn hidden_size(&self) -> i32 {
+        self.params.hidden_size
+    }
+
+    fn intermediate_size(&self) -> i32 {
+        self.params.intermediate_size
+    }
+
+    fn max_position_embeddings(&self) -> i32 {
+        self.params.max_position_embeddings
+    }
+
+    fn num_attention_heads(&self) -> i32 {
+        self.params.num_attention_heads
+    }
+
+    fn num_hidden_layers(&self) -> i32 {
+        self.params.num_hidden_layers
+    }
+
+    fn num_key_value_heads(&self) -> i32 {
+        self.params.num_key_value_heads
+    }
+
+    fn vocab_size(&self) -> i32 {
+        self.vocab_size
+    }
+
+    fn tie_word_embeddings(&self) -> bool {
+        self.tie_word_embeddings
+    }
+
+    /// All the experts are counted, the MLPs are gated (SwiGLU) and positions are encoded with
+    /// rotary embeddings
+    fn parameter_breakdown(&self) -> ParameterBreakdown {
+        let num_layers = self.params.num_hidden_layers;
+        ParameterBreakdown::new(
+            self.vocab_size as i64 * self.params.hidden_size as i64,
+            self.attention_parameters() * num_layers as i64,
+            self.mlp_parameters(false),
+            self.lm_head_parameters(),
+            0,
+            num_layers,
+        )
+    }
+
+    /// Only the shared experts and `num_experts_per_tok` routed experts run for each token
+    fn active_parameters_per_token(&self) -> i64 {
+        self.total_parameters() - (self.mlp_parameters(false) - self.mlp_parameters(true))
+    }
+
+    fn rope_params(&self) -> Option<&RopeParams> {
+        Some(&self.params.rope_params)
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn model_type(&self) -> &str {
+        &self.model_type
+    }
+
+    fn available_libraries(&self) -> &[ModelLibraries] {
+        &self.available_libraries
+    }
+
+    fn from_json(value: Value) -> Result<Self, ModelError> {
+        let params = DeepSeekParams::from_json(value.clone())?;
+
+        let moe = DeepSeekMoEParams::from_json(&value)?;
+
+        let mla = DeepSeekMLAParams::from_json(&value)?;
+
+        let vocab_size = value["vocab_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("vocab_size".to_string()))?
+            as i32;
+
+        let tie_word_embeddings = value["tie_word_embeddings"].as_bool().unwrap_or(false);
+
+        let dtype = Dtype::from_json(&value);
+
+        let model_type = match value["model_type"].as_str() {
+            Some(model_type) => model_type.to_string(),
+            None => return Err(ModelError::MissingField("model_type".to_string())),
+        };
+
+        let available_libraries = ModelLibraries::from_json(&value);
+
+        Ok(DeepSeekModelConfig::new(
+            params,
+            vocab_size,
+            tie_word_embeddings,
+            moe,
+            mla,
+            dtype,
+            model_type,
+            available_libraries,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn create_deepseek_v2_lite_config() -> Value {
+        json!({
+            "model_type": "deepseek_v2",
+            "hidden_size": 2048,
+            "intermediate_size": 10944,
+            "max_position_embeddings": 163840,
+            "num_attention_heads": 16,
+            "num_hidden_layers": 27,
+            "num_key_value_heads": 16,
+            "n_routed_experts": 64,
+            "n_shared_experts": 2,
+            "num_experts_per_tok": 6,
+            "moe_intermediate_size": 1408,
+            "first_k_dense_replace": 1,
+            "moe_layer_freq": 1,
+            "kv_lora_rank": 512,
+            "q_lora_rank": null,
+            "qk_nope_head_dim": 128,
+            "qk_rope_head_dim": 64,
+            "v_head_dim": 128,
+            "rope_scaling": {
+                "type": "yarn",
+                "factor": 40,
+                "original_max_position_embeddings": 4096
+            },
+            "torch_dtype": "bfloat16",
+            "vocab_size": 102400
+        })
+    }
+
+    #[test]
+    fn test_deepseek_model_params() {
+        let deepseek_params =
+            DeepSeekParams::new(2048, 10944, 4096, 16, 28, None, RopeParams::new(None, None));
+
+        assert_eq!(deepseek_params.hidden_size, 2048);
+        assert_eq!(deepseek_params.intermediate_size, 10944);
+        assert_eq!(deepseek_params.max_position_embeddings, 4096);
+        assert_eq!(deepseek_params.num_attention_heads, 16);
+        assert_eq!(deepseek_params.num_hidden_layers, 28);
+        assert_eq!(deepseek_params.num_key_value_heads, 16);
+
+        let moe_params = DeepSeekMoEParams::new(64, Some(2), 6, 1408, Some(1), None);
+        assert!(!moe_params.is_moe_layer(0));
+        assert!(moe_params.is_moe_layer(1));
+        assert!(DeepSeekMoEParams::from_json(&json!({})).unwrap().is_none());
+        assert!(DeepSeekMLAParams::from_json(&json!({})).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_deepseek_model_config() {
+        let deepseek_params =
+            DeepSeekParams::new(2048, 10944, 4096, 16, 28, None, RopeParams::new(None, None));
+        let moe_params = DeepSeekMoEParams::new(64, Some(2), 6, 1408, Some(1), None);
+        let deepseek_model_config = DeepSeekModelConfig::new(
+            deepseek_params,
+            102400,
+            false,
+            Some(moe_params.clone()),
+            None,
+            Dtype::F32,
+            "deepseek".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        assert_eq!(deepseek_model_config.moe(), Some(&moe_params));
+        assert_eq!(deepseek_model_config.mla(), None);
+        // Regular attention caches the keys and values of every head
+        assert_eq!(deepseek_model_config.kv_cache_size_per_token(), 2 * 2048);
+        assert_eq!(deepseek_model_config.model_type, "deepseek");
+        assert_eq!(
+            deepseek_model_config.available_libraries,
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+
+    #[test]
+    fn test_deepseek_model_trait_implementation() {
+        let deepseek_model_config =
+            DeepSeekModelConfig::from_json(create_deepseek_v2_lite_config()).unwrap();
+        assert_eq!(deepseek_model_config.hidden_size(), 2048);
+        assert_eq!(deepseek_model_config.intermediate_size(), 10944);
+        assert_eq!(deepseek_model_config.max_position_embeddings(), 163840);
+        assert_eq!(deepseek_model_config.num_attention_heads(), 16);
+        assert_eq!(deepseek_model_config.num_hidden_layers(), 27);
+        assert_eq!(deepseek_model_config.num_key_value_heads(), 16);
+        assert_eq!(deepseek_model_config.moe().unwrap().n_routed_experts(), 64);
+        assert_eq!(deepseek_model_config.mla().unwrap().kv_lora_rank(), 512);
+        // MLA only caches the latent vector and the rotary part of the keys
+        assert_eq!(deepseek_model_config.kv_cache_size_per_token(), 576);
+        // DeepSeek-V2-Lite has 15.7B parameters, 0.4B of them in the token embeddings
+        assert_eq!(deepseek_model_config.vocab_size(), 102400);
+        assert_eq!(deepseek_model_config.dtype(), Dtype::BF16);
+        assert_eq!(deepseek_model_config.effective_max_context(), 163840);
+        assert_eq!(deepseek_model_config.num_parameters(), 15_706_357_760);
+        assert_eq!(deepseek_model_config.total_parameters(), 15_706_357_760);
+        // 2.4B activated parameters for each token, plus the token embeddings and the LM head
+        assert_eq!(
+            deepseek_model_config.active_parameters_per_token(),
+            2_661_023_744
+        );
+        assert_eq!(deepseek_model_config.model_type(), "deepseek_v2");
+        assert_eq!(
+            deepseek_model_config.available_libraries(),
+            vec![ModelLibraries::PyTorch]
+        );
+
+        let mut value = create_deepseek_v2_lite_config();
+        value["v_head_dim"] = Value::Null;
+        assert!(DeepSeekModelConfig::from_json(value).is_err());
+    }
+}
@@ -0,0 +1,296 @@
+//! Module for the DETR model (also used for Deformable DETR)
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::models::{Dtype, ModelConfigTrait, ModelError, ModelFamily, ModelLibraries};
+
+/// A struct representing the DETR architecture parameters
+#[derive(Clone, Debug, Deserialize)]
+pub struct DetrParams {
+    /// DETR model hidden_size
+    d_model: i32,
+    /// DETR model number of encoder layers
+    encoder_layers: i32,
+    /// DETR model number of decoder layers
+    decoder_layers: i32,
+    /// DETR model encoder intermediate_size
+    encoder_ffn_dim: i32,
+    /// DETR model decoder intermediate_size
+    decoder_ffn_dim: i32,
+    /// DETR model num_attention_heads
+    encoder_attention_heads: i32,
+    /// DETR model number of object queries, i.e. the maximum number of detected objects
+    num_queries: i32,
+}
+
+/// DETR model parameters implementation
+impl DetrParams {
+    /// Build a new `DetrParams` struct based on the provided parameters
+    pub fn new(
+        d_model: i32,
+        encoder_layers: i32,
+        decoder_layers: i32,
+        encoder_ffn_dim: i32,
+        decoder_ffn_dim: i32,
+        encoder_attention_heads: i32,
+        num_queries: i32,
+    ) -> DetrParams {
+        DetrParams {
+            d_model,
+            encoder_layers,
+            decoder_layers,
+            encoder_ffn_dim,
+            decoder_ffn_dim,
+            encoder_attention_heads,
+            num_queries,
+        }
+    }
+    /// Build from a JSON value
+    pub fn from_json(value: Value) -> Result<DetrParams, ModelError> {
+        let d_model = value["d_model"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("d_model".to_string()))? as i32;
+
+        let encoder_layers = value["encoder_layers"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("encoder_layers".to_string()))?
+            as i32;
+
+        let decoder_layers = value["decoder_layers"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("decoder_layers".to_string()))?
+            as i32;
+
+        let encoder_ffn_dim = value["encoder_ffn_dim"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("encoder_ffn_dim".to_string()))?
+            as i32;
+
+        let decoder_ffn_dim = value["decoder_ffn_dim"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("decoder_ffn_dim".to_string()))?
+            as i32;
+
+        let encoder_attention_heads =
+            value["encoder_attention_heads"]
+                .as_i64()
+                .ok_or(ModelError::MissingField(
+                    "encoder_attention_heads".to_string(),
+                ))? as i32;
+
+        let num_queries = value["num_queries"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("num_queries".to_string()))?
+            as i32;
+
+        Ok(DetrParams::new(
+            d_model,
+            encoder_layers,
+            decoder_layers,
+            encoder_ffn_dim,
+            decoder_ffn_dim,
+            encoder_attention_heads,
+            num_queries,
+        ))
+    }
+}
+
+/// A struct representing a DETR model configuration
+#[derive(Clone, Debug, Deserialize)]
+pub struct DetrModelConfig {
+    /// DETR model parameters
+    params: DetrParams,
+    /// DETR model convolutional backbone (e.g. `resnet50`), whose weights aren't counted
+    backbone: Option<String>,
+    /// DETR model weights precision
+    dtype: Dtype,
+    /// DETR model type
+    model_type: String,
+    /// DETR model available libraries
+    available_libraries: Vec<ModelLibraries>,
+}
+
+/// DETR model implementation
+impl DetrModelConfig {
+    /// Build a new `DetrModelConfig` struct based on the provided parameters
+    pub fn new(
+        params: DetrParams,
+        backbone: Option<String>,
+        dtype: Dtype,
+        model_type: String,
+        available_libraries: Vec<ModelLibraries>,
+    ) -> DetrModelConfig {
+        DetrModelConfig {
+            params,
+            backbone,
+            dtype,
+            model_type,
+            available_libraries,
+        }
+    }
+    /// Returns the number of encoder layers
+    pub fn encoder_layers(&self) -> i32 {
+        self.params.encoder_layers
+    }
+    /// Returns the number of decoder layers
+    pub fn decoder_layers(&self) -> i32 {
+        self.params.decoder_layers
+    }
+    /// Returns the intermediate size of the decoder layers
+    pub fn decoder_ffn_dim(&self) -> i32 {
+        self.params.decoder_ffn_dim
+    }
+    /// Returns the number of object queries, i.e. the maximum number of detected objects
+    pub fn num_queries(&self) -> i32 {
+        self.params.num_queries
+    }
+    /// Returns the name of the convolutional backbone, if any
+    pub fn backbone(&self) -> Option<&str> {
+        self.backbone.as_deref()
+    }
+}
+
+/// Implementation of the `ModelConfigTrait` trait for `DetrModelConfig`
+impl ModelConfigTrait for DetrModelConfig {
+    fn hidden_size(&self) -> i32 {
+        self.params.d_model
+    }
+
+    fn intermediate_size(&self) -> i32 {
+        self.params.encoder_ffn_dim
+    }
+
+    /// The encoder sequence length depends on the input image resolution, so the decoder one
+    /// (the number of object queries) is returned.
+    fn max_position_embeddings(&self) -> i32 {
+        self.params.num_queries
+    }
+
+    fn num_attention_heads(&self) -> i32 {
+        self.params.encoder_attention_heads
+    }
+
+    /// Both the encoder and the decoder layers are held in memory, so they're summed
+    fn num_hidden_layers(&self) -> i32 {
+        self.params.encoder_layers + self.params.decoder_layers
+    }
+
+    fn is_encoder_decoder(&self) -> bool {
+        true
+    }
+
+    fn num_encoder_layers(&self) -> i32 {
+        self.params.encoder_layers
+    }
+
+    /// DETR is an encoder-decoder, but over image features, so it runs as a vision model
+    fn model_family(&self) -> ModelFamily {
+        ModelFamily::Vision
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn model_type(&self) -> &str {
+        &self.model_type
+    }
+
+    fn available_libraries(&self) -> &[ModelLibraries] {
+        &self.available_libraries
+    }
+
+    fn from_json(value: Value) -> Result<Self, ModelError> {
+        let params = DetrParams::from_json(value.clone())?;
+
+        let backbone = value["backbone"].as_str().map(|val| val.to_string());
+
+        let dtype = Dtype::from_json(&value);
+
+        let model_type = match value["model_type"].as_str() {
+            Some(model_type) => model_type.to_string(),
+            None => return Err(ModelError::MissingField("model_type".to_string())),
+        };
+
+        let available_libraries = ModelLibraries::from_json(&value);
+
+        Ok(DetrModelConfig::new(
+            params,
+            backbone,
+            dtype,
+            model_type,
+            available_libraries,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_detr_model_params() {
+        let detr_params = DetrParams::new(256, 6, 6, 2048, 2048, 8, 100);
+
+        assert_eq!(detr_params.d_model, 256);
+        assert_eq!(detr_params.encoder_layers, 6);
+        assert_eq!(detr_params.decoder_layers, 6);
+        assert_eq!(detr_params.encoder_ffn_dim, 2048);
+        assert_eq!(detr_params.decoder_ffn_dim, 2048);
+        assert_eq!(detr_params.encoder_attention_heads, 8);
+        assert_eq!(detr_params.num_queries, 100);
+    }
+
+    #[test]
+    fn test_detr_model_config() {
+        let detr_params = DetrParams::new(256, 6, 6, 2048, 2048, 8, 100);
+        let detr_model_config = DetrModelConfig::new(
+            detr_params,
+            Some("resnet50".to_string()),
+            Dtype::F32,
+            "detr".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        assert_eq!(detr_model_config.params.d_model, 256);
+        assert_eq!(detr_model_config.backbone, Some("resnet50".to_string()));
+        assert_eq!(detr_model_config.model_type, "detr");
+        assert_eq!(
+            detr_model_config.available_libraries,
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+
+    #[test]
+    fn test_detr_model_trait_implementation() {
+        let value = json!({
+            "model_type": "deformable_detr",
+            "d_model": 256,
+            "encoder_layers": 6,
+            "decoder_layers": 6,
+            "encoder_ffn_dim": 1024,
+            "decoder_ffn_dim": 1024,
+            "encoder_attention_heads": 8,
+            "decoder_attention_heads": 8,
+            "num_queries": 300,
+            "num_feature_levels": 4,
+            "backbone": "resnet50"
+        });
+        let detr_model_config = DetrModelConfig::from_json(value).unwrap();
+        assert_eq!(detr_model_config.hidden_size(), 256);
+        assert_eq!(detr_model_config.intermediate_size(), 1024);
+        assert_eq!(detr_model_config.max_position_embeddings(), 300);
+        assert_eq!(detr_model_config.num_attention_heads(), 8);
+        assert_eq!(detr_model_config.num_hidden_layers(), 12);
+        assert_eq!(detr_model_config.encoder_layers(), 6);
+        assert_eq!(detr_model_config.decoder_layers(), 6);
+        assert_eq!(detr_model_config.decoder_ffn_dim(), 1024);
+        assert_eq!(detr_model_config.num_queries(), 300);
+        assert_eq!(detr_model_config.backbone(), Some("resnet50"));
+        assert_eq!(detr_model_config.model_type(), "deformable_detr");
+        assert_eq!(
+            detr_model_config.available_libraries(),
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+}
@@ -0,0 +1,705 @@
+//! Module for the diffusion pipelines components (UNet, VAE and text encoders)
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::hub::PipelineInfo;
+use crate::models::{ModelConfigTrait, ModelError, ModelFamily, ModelLibraries};
+
+/// Parse a JSON array of integers
+fn parse_i32_array(value: &Value, field: &str) -> Result<Vec<i32>, ModelError> {
+    value[field]
+        .as_array()
+        .and_then(|values| {
+            values
+                .iter()
+                .map(|val| val.as_i64().map(|val| val as i32))
+                .collect::<Option<Vec<i32>>>()
+        })
+        .ok_or(ModelError::MissingField(field.to_string()))
+}
+
+/// Parse a JSON array of strings
+fn parse_string_array(value: &Value, field: &str) -> Result<Vec<String>, ModelError> {
+    value[field]
+        .as_array()
+        .and_then(|values| {
+            values
+                .iter()
+                .map(|val| val.as_str().map(|val| val.to_string()))
+                .collect::<Option<Vec<String>>>()
+        })
+        .ok_or(ModelError::MissingField(field.to_string()))
+}
+
+/// Number of parameters of a ResNet block (two 3x3 convolutions and a shortcut projection)
+fn resnet_parameters(in_channels: i64, out_channels: i64, time_embed_dim: i64) -> i64 {
+    let shortcut = if in_channels != out_channels {
+        in_channels * out_channels
+    } else {
+        0
+    };
+    9 * in_channels * out_channels
+        + 9 * out_channels * out_channels
+        + time_embed_dim * out_channels
+        + shortcut
+}
+
+/// Number of parameters of a spatial transformer (input/output projections, then self-attention,
+/// cross-attention and GEGLU feed-forward for each layer)
+fn transformer_parameters(channels: i64, cross_attention_dim: i64, num_layers: i64) -> i64 {
+    2 * channels * channels
+        + num_layers * (18 * channels * channels + 2 * channels * cross_attention_dim)
+}
+
+/// A struct representing the configuration of a diffusers `UNet2DConditionModel`
+#[derive(Clone, Debug, Deserialize)]
+pub struct UNetConfig {
+    /// Number of channels of every resolution block
+    block_out_channels: Vec<i32>,
+    /// Number of ResNet layers of every down block (up blocks have one more)
+    layers_per_block: i32,
+    /// Dimension of the text embeddings attended by the cross-attention layers
+    cross_attention_dim: i32,
+    /// Number of transformer layers of every resolution block
+    transformer_layers_per_block: Vec<i32>,
+    /// Type of every down block (e.g. `CrossAttnDownBlock2D`)
+    down_block_types: Vec<String>,
+    /// Type of every up block (e.g. `CrossAttnUpBlock2D`)
+    up_block_types: Vec<String>,
+    /// Resolution of the latents
+    sample_size: i32,
+}
+
+/// UNet config implementation
+impl UNetConfig {
+    /// Build a new `UNetConfig` struct based on the provided parameters
+    pub fn new(
+        block_out_channels: Vec<i32>,
+        layers_per_block: i32,
+        cross_attention_dim: i32,
+        transformer_layers_per_block: Vec<i32>,
+        down_block_types: Vec<String>,
+        up_block_types: Vec<String>,
+        sample_size: i32,
+    ) -> UNetConfig {
+        UNetConfig {
+            block_out_channels,
+            layers_per_block,
+            cross_attention_dim,
+            transformer_layers_per_block,
+            down_block_types,
+            up_block_types,
+            sample_size,
+        }
+    }
+    /// Returns the number of channels of every resolution block
+    pub fn block_out_channels(&self) -> &[i32] {
+        &self.block_out_channels
+    }
+    /// Returns the resolution of the latents
+    pub fn sample_size(&self) -> i32 {
+        self.sample_size
+    }
+    /// Returns an estimate of the number of parameters of the UNet.
+    ///
+    /// ResNet, spatial transformer and resampling layers are counted, normalization layers and
+    /// biases are left out.
+    pub fn num_parameters(&self) -> i64 {
+        let channels: Vec<i64> = self.block_out_channels.iter().map(|&c| c as i64).collect();
+        let (first, last) = match (channels.first(), channels.last()) {
+            (Some(&first), Some(&last)) => (first, last),
+            _ => return 0,
+        };
+        let layers_per_block = self.layers_per_block as i64;
+        let cross_attention_dim = self.cross_attention_dim as i64;
+        let time_embed_dim = 4 * first;
+        let transformer_layers = |block: usize| {
+            self.transformer_layers_per_block
+                .get(block)
+                .copied()
+                .unwrap_or(1) as i64
+        };
+        let has_attention = |block_type: &String| block_type.contains("Attn");
+
+        let mut num_parameters = time_embed_dim * first + time_embed_dim * time_embed_dim;
+        // Down blocks, every layer output is kept for the skip connections of the up blocks
+        let mut skip_channels = vec![first];
+        let mut prev_channels = first;
+        for (block, &block_channels) in channels.iter().enumerate() {
+            for _ in 0..layers_per_block {
+                num_parameters += resnet_parameters(prev_channels, block_channels, time_embed_dim);
+                if self.down_block_types.get(block).is_some_and(has_attention) {
+                    num_parameters += transformer_parameters(
+                        block_channels,
+                        cross_attention_dim,
+                        transformer_layers(block),
+                    );
+                }
+                skip_channels.push(block_channels);
+                prev_channels = block_channels;
+            }
+            if block + 1 < channels.len() {
+                num_parameters += 9 * block_channels * block_channels;
+                skip_channels.push(block_channels);
+            }
+        }
+        // Mid block
+        num_parameters += 2 * resnet_parameters(last, last, time_embed_dim)
+            + transformer_parameters(
+                last,
+                cross_attention_dim,
+                transformer_layers(channels.len() - 1),
+            );
+        // Up blocks, in reverse order
+        for (block, &block_channels) in channels.iter().rev().enumerate() {
+            let down_block = channels.len() - 1 - block;
+            for _ in 0..=layers_per_block {
+                let skip = skip_channels.pop().unwrap_or_default();
+                num_parameters +=
+                    resnet_parameters(prev_channels + skip, block_channels, time_embed_dim);
+                if self.up_block_types.get(block).is_some_and(has_attention) {
+                    num_parameters += transformer_parameters(
+                        block_channels,
+                        cross_attention_dim,
+                        transformer_layers(down_block),
+                    );
+                }
+                prev_channels = block_channels;
+            }
+            if block + 1 < channels.len() {
+                num_parameters += 9 * block_channels * block_channels;
+            }
+        }
+        num_parameters
+    }
+    /// Build from a JSON value
+    pub fn from_json(value: Value) -> Result<UNetConfig, ModelError> {
+        let block_out_channels = parse_i32_array(&value, "block_out_channels")?;
+
+        let layers_per_block = value["layers_per_block"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("layers_per_block".to_string()))?
+            as i32;
+
+        let cross_attention_dim = value["cross_attention_dim"].as_i64().unwrap_or(1280) as i32;
+
+        // Either a single value for all the blocks or one value per block
+        let transformer_layers_per_block = match value["transformer_layers_per_block"].as_i64() {
+            Some(layers) => vec![layers as i32; block_out_channels.len()],
+            None => parse_i32_array(&value, "transformer_layers_per_block")
+                .unwrap_or(vec![1; block_out_channels.len()]),
+        };
+
+        let down_block_types = parse_string_array(&value, "down_block_types")?;
+
+        let up_block_types = parse_string_array(&value, "up_block_types")?;
+
+        let sample_size = value["sample_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("sample_size".to_string()))?
+            as i32;
+
+        Ok(UNetConfig::new(
+            block_out_channels,
+            layers_per_block,
+            cross_attention_dim,
+            transformer_layers_per_block,
+            down_block_types,
+            up_block_types,
+            sample_size,
+        ))
+    }
+}
+
+/// A struct representing the configuration of a diffusers `AutoencoderKL`
+#[derive(Clone, Debug, Deserialize)]
+pub struct VAEConfig {
+    /// Number of channels of every resolution block
+    block_out_channels: Vec<i32>,
+    /// Number of ResNet layers of every encoder block (decoder blocks have one more)
+    layers_per_block: i32,
+    /// Number of channels of the latents
+    latent_channels: i32,
+    /// Number of channels of the images
+    in_channels: i32,
+    /// Resolution of the images
+    sample_size: i32,
+}
+
+/// VAE config implementation
+impl VAEConfig {
+    /// Build a new `VAEConfig` struct based on the provided parameters
+    pub fn new(
+        block_out_channels: Vec<i32>,
+        layers_per_block: i32,
+        latent_channels: i32,
+        in_channels: Option<i32>,
+        sample_size: i32,
+    ) -> VAEConfig {
+        let in_channels = in_channels.unwrap_or(3);
+        VAEConfig {
+            block_out_channels,
+            layers_per_block,
+            latent_channels,
+            in_channels,
+            sample_size,
+        }
+    }
+    /// Returns the number of channels of the latents
+    pub fn latent_channels(&self) -> i32 {
+        self.latent_channels
+    }
+    /// Returns the resolution of the images
+    pub fn sample_size(&self) -> i32 {
+        self.sample_size
+    }
+    /// Returns the ratio between the images and the latents resolutions
+    pub fn downscale_factor(&self) -> i32 {
+        1 << self.block_out_channels.len().saturating_sub(1)
+    }
+    /// Returns an estimate of the number of parameters of the VAE (encoder and decoder).
+    ///
+    /// ResNet, attention and resampling layers are counted, normalization layers and biases are
+    /// left out.
+    pub fn num_parameters(&self) -> i64 {
+        let channels: Vec<i64> = self.block_out_channels.iter().map(|&c| c as i64).collect();
+        let (first, last) = match (channels.first(), channels.last()) {
+            (Some(&first), Some(&last)) => (first, last),
+            _ => return 0,
+        };
+        let layers_per_block = self.layers_per_block as i64;
+        let latent_channels = self.latent_channels as i64;
+        let in_channels = self.in_channels as i64;
+        let mid_block = 2 * resnet_parameters(last, last, 0) + 4 * last * last;
+
+        // Encoder, which outputs the mean and the variance of the latents
+        let mut num_parameters = 9 * in_channels * first;
+        let mut prev_channels = first;
+        for (block, &block_channels) in channels.iter().enumerate() {
+            for _ in 0..layers_per_block {
+                num_parameters += resnet_parameters(prev_channels, block_channels, 0);
+                prev_channels = block_channels;
+            }
+            if block + 1 < channels.len() {
+                num_parameters += 9 * block_channels * block_channels;
+            }
+        }
+        num_parameters += mid_block + 9 * last * 2 * latent_channels;
+
+        // Decoder
+        num_parameters += 9 * latent_channels * last + mid_block;
+        let mut prev_channels = last;
+        for (block, &block_channels) in channels.iter().rev().enumerate() {
+            for _ in 0..=layers_per_block {
+                num_parameters += resnet_parameters(prev_channels, block_channels, 0);
+                prev_channels = block_channels;
+            }
+            if block + 1 < channels.len() {
+                num_parameters += 9 * block_channels * block_channels;
+            }
+        }
+        num_parameters + 9 * first * in_channels
+    }
+    /// Build from a JSON value
+    pub fn from_json(value: Value) -> Result<VAEConfig, ModelError> {
+        let block_out_channels = parse_i32_array(&value, "block_out_channels")?;
+
+        let layers_per_block = value["layers_per_block"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("layers_per_block".to_string()))?
+            as i32;
+
+        let latent_channels = value["latent_channels"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("latent_channels".to_string()))?
+            as i32;
+
+        let in_channels = value["in_channels"].as_i64().map(|val| val as i32);
+
+        let sample_size = value["sample_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("sample_size".to_string()))?
+            as i32;
+
+        Ok(VAEConfig::new(
+            block_out_channels,
+            layers_per_block,
+            latent_channels,
+            in_channels,
+            sample_size,
+        ))
+    }
+}
+
+/// A struct representing the configuration of a pipeline text encoder (CLIP or T5 encoder)
+#[derive(Clone, Debug, Deserialize)]
+pub struct TextEncoderConfig {
+    /// Text encoder hidden_size
+    hidden_size: i32,
+    /// Text encoder intermediate_size
+    intermediate_size: i32,
+    /// Text encoder max_position_embeddings
+    max_position_embeddings: i32,
+    /// Text encoder num_attention_heads
+    num_attention_heads: i32,
+    /// Text encoder num_hidden_layers
+    num_hidden_layers: i32,
+    /// Text encoder vocab_size
+    vocab_size: i32,
+    /// Text encoder model type (e.g. `clip_text_model`, `t5`)
+    model_type: String,
+}
+
+/// Text encoder config implementation
+impl TextEncoderConfig {
+    /// Build a new `TextEncoderConfig` struct based on the provided parameters
+    pub fn new(
+        hidden_size: i32,
+        intermediate_size: i32,
+        max_position_embeddings: i32,
+        num_attention_heads: i32,
+        num_hidden_layers: i32,
+        vocab_size: i32,
+        model_type: String,
+    ) -> TextEncoderConfig {
+        TextEncoderConfig {
+            hidden_size,
+            intermediate_size,
+            max_position_embeddings,
+            num_attention_heads,
+            num_hidden_layers,
+            vocab_size,
+            model_type,
+        }
+    }
+}
+
+/// Implementation of the `ModelConfigTrait` trait for `TextEncoderConfig`
+impl ModelConfigTrait for TextEncoderConfig {
+    fn hidden_size(&self) -> i32 {
+        self.hidden_size
+    }
+
+    fn intermediate_size(&self) -> i32 {
+        self.intermediate_size
+    }
+
+    fn max_position_embeddings(&self) -> i32 {
+        self.max_position_embeddings
+    }
+
+    fn num_attention_heads(&self) -> i32 {
+        self.num_attention_heads
+    }
+
+    fn num_hidden_layers(&self) -> i32 {
+        self.num_hidden_layers
+    }
+
+    /// The text encoder of a diffusion pipeline only encodes the prompt
+    fn model_family(&self) -> ModelFamily {
+        ModelFamily::EncoderLM
+    }
+
+    fn vocab_size(&self) -> i32 {
+        self.vocab_size
+    }
+
+    fn model_type(&self) -> &str {
+        &self.model_type
+    }
+
+    fn available_libraries(&self) -> &[ModelLibraries] {
+        &[ModelLibraries::Transformers]
+    }
+
+    /// Both the CLIP (`hidden_size`, `intermediate_size`...) and the T5 (`d_model`, `d_ff`...)
+    /// field names are supported. T5 uses relative positions, so its max_position_embeddings
+    /// defaults to its usual 512 tokens context.
+    fn from_json(value: Value) -> Result<Self, ModelError> {
+        let field = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| value[name].as_i64())
+                .map(|val| val as i32)
+                .ok_or(ModelError::MissingField(names[0].to_string()))
+        };
+
+        let hidden_size = field(&["hidden_size", "d_model"])?;
+        let intermediate_size = field(&["intermediate_size", "d_ff"])?;
+        let max_position_embeddings =
+            field(&["max_position_embeddings", "n_positions"]).unwrap_or(512);
+        let num_attention_heads = field(&["num_attention_heads", "num_heads"])?;
+        let num_hidden_layers = field(&["num_hidden_layers", "num_layers"])?;
+        let vocab_size = field(&["vocab_size"])?;
+
+        let model_type = match value["model_type"].as_str() {
+            Some(model_type) => model_type.to_string(),
+            None => return Err(ModelError::MissingField("model_type".to_string())),
+        };
+
+        Ok(TextEncoderConfig::new(
+            hidden_size,
+            intermediate_size,
+            max_position_embeddings,
+            num_attention_heads,
+            num_hidden_layers,
+            vocab_size,
+            model_type,
+        ))
+    }
+}
+
+/// A struct representing the configuration of a diffusion pipeline, aggregating the configs of
+/// its components
+#[derive(Clone, Debug, Deserialize)]
+pub struct DiffusionPipelineConfig {
+    /// The class of the pipeline (e.g. `StableDiffusionPipeline`)
+    class_name: Option<String>,
+    /// The UNet config, pipelines based on a transformer denoiser don't have one
+    unet: Option<UNetConfig>,
+    /// The VAE config
+    vae: Option<VAEConfig>,
+    /// The text encoders configs, ordered by component name (`text_encoder`, `text_encoder_2`...)
+    text_encoders: Vec<TextEncoderConfig>,
+}
+
+/// Diffusion pipeline config implementation
+impl DiffusionPipelineConfig {
+    /// Build a new `DiffusionPipelineConfig` struct based on the provided parameters
+    pub fn new(
+        class_name: Option<String>,
+        unet: Option<UNetConfig>,
+        vae: Option<VAEConfig>,
+        text_encoders: Vec<TextEncoderConfig>,
+    ) -> DiffusionPipelineConfig {
+        DiffusionPipelineConfig {
+            class_name,
+            unet,
+            vae,
+            text_encoders,
+        }
+    }
+    /// Returns the class of the pipeline
+    pub fn class_name(&self) -> Option<&str> {
+        self.class_name.as_deref()
+    }
+    /// Returns the UNet config
+    pub fn unet(&self) -> Option<&UNetConfig> {
+        self.unet.as_ref()
+    }
+    /// Returns the VAE config
+    pub fn vae(&self) -> Option<&VAEConfig> {
+        self.vae.as_ref()
+    }
+    /// Returns the text encoders configs
+    pub fn text_encoders(&self) -> &[TextEncoderConfig] {
+        &self.text_encoders
+    }
+    /// Returns the default resolution of the generated images
+    pub fn image_size(&self) -> Option<i32> {
+        match (&self.unet, &self.vae) {
+            (Some(unet), Some(vae)) => Some(unet.sample_size() * vae.downscale_factor()),
+            (None, Some(vae)) => Some(vae.sample_size()),
+            _ => None,
+        }
+    }
+    /// Returns an estimate of the number of parameters of all the components
+    pub fn num_parameters(&self) -> i64 {
+        self.unet.as_ref().map_or(0, |unet| unet.num_parameters())
+            + self.vae.as_ref().map_or(0, |vae| vae.num_parameters())
+            + self
+                .text_encoders
+                .iter()
+                .map(|text_encoder| text_encoder.num_parameters())
+                .sum::<i64>()
+    }
+    /// Build from the metadata of a diffusers pipeline, using the `config.json` of its components
+    /// (see `get_pipeline_info`). Components without a config are skipped.
+    pub fn from_pipeline_info(pipeline_info: &PipelineInfo) -> Result<Self, ModelError> {
+        let mut unet = None;
+        let mut vae = None;
+        let mut text_encoders = Vec::new();
+        for component in pipeline_info.get_model_components() {
+            let (class_name, config) = match (&component.class_name, &component.config) {
+                (Some(class_name), Some(config)) => (class_name, config),
+                _ => continue,
+            };
+            if class_name.starts_with("UNet") {
+                unet = Some(UNetConfig::from_json(config.clone())?);
+            } else if class_name.starts_with("AutoencoderKL") {
+                vae = Some(VAEConfig::from_json(config.clone())?);
+            } else if component.name.starts_with("text_encoder") {
+                text_encoders.push(TextEncoderConfig::from_json(config.clone())?);
+            }
+        }
+        Ok(DiffusionPipelineConfig::new(
+            pipeline_info.class_name.clone(),
+            unet,
+            vae,
+            text_encoders,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hub::PipelineComponent;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn create_unet_config() -> Value {
+        json!({
+            "_class_name": "UNet2DConditionModel",
+            "block_out_channels": [320, 640, 1280, 1280],
+            "cross_attention_dim": 768,
+            "down_block_types": [
+                "CrossAttnDownBlock2D",
+                "CrossAttnDownBlock2D",
+                "CrossAttnDownBlock2D",
+                "DownBlock2D"
+            ],
+            "up_block_types": [
+                "UpBlock2D",
+                "CrossAttnUpBlock2D",
+                "CrossAttnUpBlock2D",
+                "CrossAttnUpBlock2D"
+            ],
+            "layers_per_block": 2,
+            "sample_size": 64
+        })
+    }
+
+    fn create_vae_config() -> Value {
+        json!({
+            "_class_name": "AutoencoderKL",
+            "block_out_channels": [128, 256, 512, 512],
+            "in_channels": 3,
+            "latent_channels": 4,
+            "layers_per_block": 2,
+            "sample_size": 512
+        })
+    }
+
+    fn create_text_encoder_config() -> Value {
+        json!({
+            "model_type": "clip_text_model",
+            "hidden_size": 768,
+            "intermediate_size": 3072,
+            "max_position_embeddings": 77,
+            "num_attention_heads": 12,
+            "num_hidden_layers": 12,
+            "vocab_size": 49408
+        })
+    }
+
+    #[test]
+    fn test_unet_config() {
+        let unet = UNetConfig::from_json(create_unet_config()).unwrap();
+        assert_eq!(unet.block_out_channels(), &[320, 640, 1280, 1280]);
+        assert_eq!(unet.transformer_layers_per_block, vec![1, 1, 1, 1]);
+        assert_eq!(unet.sample_size(), 64);
+        // Stable Diffusion v1.5 UNet has 859.5M parameters
+        assert_eq!(unet.num_parameters(), 859_054_080);
+
+        let mut value = create_unet_config();
+        value["block_out_channels"] = json!([320, 640, 1280]);
+        value["cross_attention_dim"] = json!(2048);
+        value["transformer_layers_per_block"] = json!([1, 2, 10]);
+        value["down_block_types"] = json!([
+            "DownBlock2D",
+            "CrossAttnDownBlock2D",
+            "CrossAttnDownBlock2D"
+        ]);
+        value["up_block_types"] = json!(["CrossAttnUpBlock2D", "CrossAttnUpBlock2D", "UpBlock2D"]);
+        // Stable Diffusion XL UNet has 2.57B parameters
+        let unet = UNetConfig::from_json(value).unwrap();
+        assert_eq!(unet.num_parameters(), 2_560_614_400);
+    }
+
+    #[test]
+    fn test_vae_config() {
+        let vae = VAEConfig::from_json(create_vae_config()).unwrap();
+        assert_eq!(vae.latent_channels(), 4);
+        assert_eq!(vae.sample_size(), 512);
+        assert_eq!(vae.downscale_factor(), 8);
+        // Stable Diffusion VAE has 83.7M parameters
+        assert_eq!(vae.num_parameters(), 83_587_840);
+    }
+
+    #[test]
+    fn test_text_encoder_config() {
+        let text_encoder = TextEncoderConfig::from_json(create_text_encoder_config()).unwrap();
+        assert_eq!(text_encoder.hidden_size(), 768);
+        assert_eq!(text_encoder.vocab_size(), 49408);
+        assert_eq!(text_encoder.model_type(), "clip_text_model");
+
+        let text_encoder = TextEncoderConfig::from_json(json!({
+            "model_type": "t5",
+            "d_model": 4096,
+            "d_ff": 10240,
+            "num_heads": 64,
+            "num_layers": 24,
+            "vocab_size": 32128
+        }))
+        .unwrap();
+        assert_eq!(text_encoder.hidden_size(), 4096);
+        assert_eq!(text_encoder.intermediate_size(), 10240);
+        assert_eq!(text_encoder.max_position_embeddings(), 512);
+        assert_eq!(text_encoder.num_attention_heads(), 64);
+        assert_eq!(text_encoder.num_hidden_layers(), 24);
+    }
+
+    #[test]
+    fn test_diffusion_pipeline_config_from_pipeline_info() {
+        let mut components = Vec::new();
+        for (name, library, class_name, config) in [
+            (
+                "unet",
+                "diffusers",
+                "UNet2DConditionModel",
+                create_unet_config(),
+            ),
+            ("vae", "diffusers", "AutoencoderKL", create_vae_config()),
+            (
+                "text_encoder",
+                "transformers",
+                "CLIPTextModel",
+                create_text_encoder_config(),
+            ),
+        ] {
+            let mut component = PipelineComponent::new(
+                name.to_string(),
+                Some(library.to_string()),
+                Some(class_name.to_string()),
+            );
+            component.config = Some(config);
+            components.push(component);
+        }
+        components.push(PipelineComponent::new(
+            "scheduler".to_string(),
+            Some("diffusers".to_string()),
+            Some("PNDMScheduler".to_string()),
+        ));
+        let pipeline_info = PipelineInfo::new(
+            Some("StableDiffusionPipeline".to_string()),
+            Some("0.6.0".to_string()),
+            components,
+        );
+
+        let pipeline_config = DiffusionPipelineConfig::from_pipeline_info(&pipeline_info).unwrap();
+        assert_eq!(
+            pipeline_config.class_name(),
+            Some("StableDiffusionPipeline")
+        );
+        assert!(pipeline_config.unet().is_some());
+        assert!(pipeline_config.vae().is_some());
+        assert_eq!(pipeline_config.text_encoders().len(), 1);
+        assert_eq!(pipeline_config.image_size(), Some(512));
+        assert_eq!(
+            pipeline_config.num_parameters(),
+            859_054_080 + 83_587_840 + pipeline_config.text_encoders()[0].num_parameters()
+        );
+    }
+}
@@ -0,0 +1,170 @@
+//! Module for the model families, the kind of workload a model runs
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use serde::Deserialize;
+
+/// Enumerate the model families, each one calls for a different estimate of the requirements
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum ModelFamily {
+    /// Decoder-only language models generating text token by token (e.g. Llama, GPT-2)
+    DecoderLM,
+    /// Encoder-only language models processing the whole input at once (e.g. BERT)
+    EncoderLM,
+    /// Encoder-decoder models (e.g. T5, BART)
+    Seq2Seq,
+    /// Vision models (e.g. ViT, DETR)
+    Vision,
+    /// Speech and audio models (e.g. Whisper)
+    Audio,
+    /// Models mixing several modalities (e.g. CLIP, LLaVA)
+    Multimodal,
+    /// Diffusion pipelines (e.g. Stable Diffusion)
+    Diffusion,
+    /// Models building embeddings for retrieval or similarity (e.g. sentence-transformers)
+    Embedding,
+}
+
+/// ModelFamily implementation
+impl ModelFamily {
+    /// Returns the family of the models solving the given hub `pipeline_tag`, `None` for the
+    /// tasks shared by several families
+    pub fn from_pipeline_tag(pipeline_tag: &str) -> Option<ModelFamily> {
+        match pipeline_tag {
+            "text-generation" => Some(ModelFamily::DecoderLM),
+            "fill-mask"
+            | "token-classification"
+            | "text-classification"
+            | "question-answering"
+            | "zero-shot-classification" => Some(ModelFamily::EncoderLM),
+            "text2text-generation" | "summarization" | "translation" => Some(ModelFamily::Seq2Seq),
+            "image-classification"
+            | "object-detection"
+            | "image-segmentation"
+            | "depth-estimation"
+            | "image-feature-extraction" => Some(ModelFamily::Vision),
+            "automatic-speech-recognition"
+            | "audio-classification"
+            | "text-to-speech"
+            | "text-to-audio" => Some(ModelFamily::Audio),
+            "image-text-to-text"
+            | "image-to-text"
+            | "visual-question-answering"
+            | "document-question-answering"
+            | "zero-shot-image-classification" => Some(ModelFamily::Multimodal),
+            "text-to-image" | "image-to-image" | "unconditional-image-generation" => {
+                Some(ModelFamily::Diffusion)
+            }
+            "sentence-similarity" | "feature-extraction" => Some(ModelFamily::Embedding),
+            _ => None,
+        }
+    }
+    /// Returns the family of a model from one of its `architectures` (e.g. `LlamaForCausalLM`),
+    /// based on the head the architecture ends with
+    pub fn from_architecture(architecture: &str) -> Option<ModelFamily> {
+        let heads = [
+            ("ForCausalLM", ModelFamily::DecoderLM),
+            ("LMHeadModel", ModelFamily::DecoderLM),
+            ("ForMaskedLM", ModelFamily::EncoderLM),
+            ("ForSequenceClassification", ModelFamily::EncoderLM),
+            ("ForTokenClassification", ModelFamily::EncoderLM),
+            ("ForQuestionAnswering", ModelFamily::EncoderLM),
+            ("ForSpeechSeq2Seq", ModelFamily::Audio),
+            ("ForCTC", ModelFamily::Audio),
+            ("ForAudioClassification", ModelFamily::Audio),
+            ("ForImageClassification", ModelFamily::Vision),
+            ("ForObjectDetection", ModelFamily::Vision),
+            ("ForSemanticSegmentation", ModelFamily::Vision),
+            ("ForVision2Seq", ModelFamily::Multimodal),
+            ("ForImageTextToText", ModelFamily::Multimodal),
+            ("ForConditionalGeneration", ModelFamily::Seq2Seq),
+        ];
+        heads
+            .iter()
+            .find(|(head, _)| architecture.ends_with(head))
+            .map(|(_, family)| *family)
+    }
+    /// Returns the hub `pipeline_tag` of the task the models of the family run by default
+    pub fn default_task(&self) -> &'static str {
+        match self {
+            ModelFamily::DecoderLM => "text-generation",
+            ModelFamily::EncoderLM => "fill-mask",
+            ModelFamily::Seq2Seq => "text2text-generation",
+            ModelFamily::Vision => "image-classification",
+            ModelFamily::Audio => "automatic-speech-recognition",
+            ModelFamily::Multimodal => "image-text-to-text",
+            ModelFamily::Diffusion => "text-to-image",
+            ModelFamily::Embedding => "feature-extraction",
+        }
+    }
+}
+
+/// Implement the display of the ModelFamily enum
+impl Display for ModelFamily {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let family = match self {
+            ModelFamily::DecoderLM => "decoder language model",
+            ModelFamily::EncoderLM => "encoder language model",
+            ModelFamily::Seq2Seq => "encoder-decoder model",
+            ModelFamily::Vision => "vision model",
+            ModelFamily::Audio => "audio model",
+            ModelFamily::Multimodal => "multimodal model",
+            ModelFamily::Diffusion => "diffusion pipeline",
+            ModelFamily::Embedding => "embedding model",
+        };
+        write!(f, "{}", family)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_family_from_pipeline_tag() {
+        assert_eq!(
+            ModelFamily::from_pipeline_tag("text-generation"),
+            Some(ModelFamily::DecoderLM)
+        );
+        assert_eq!(
+            ModelFamily::from_pipeline_tag("automatic-speech-recognition"),
+            Some(ModelFamily::Audio)
+        );
+        assert_eq!(
+            ModelFamily::from_pipeline_tag("sentence-similarity"),
+            Some(ModelFamily::Embedding)
+        );
+        assert_eq!(ModelFamily::from_pipeline_tag("robotics"), None);
+    }
+
+    #[test]
+    fn test_model_family_from_architecture() {
+        assert_eq!(
+            ModelFamily::from_architecture("MistralForCausalLM"),
+            Some(ModelFamily::DecoderLM)
+        );
+        assert_eq!(
+            ModelFamily::from_architecture("GPT2LMHeadModel"),
+            Some(ModelFamily::DecoderLM)
+        );
+        assert_eq!(
+            ModelFamily::from_architecture("DebertaV2ForSequenceClassification"),
+            Some(ModelFamily::EncoderLM)
+        );
+        assert_eq!(
+            ModelFamily::from_architecture("Wav2Vec2ForCTC"),
+            Some(ModelFamily::Audio)
+        );
+        assert_eq!(ModelFamily::from_architecture("MambaModel"), None);
+    }
+
+    #[test]
+    fn test_model_family_default_task() {
+        assert_eq!(ModelFamily::DecoderLM.default_task(), "text-generation");
+        assert_eq!(ModelFamily::Diffusion.default_task(), "text-to-image");
+        assert_eq!(
+            ModelFamily::from_pipeline_tag(ModelFamily::Seq2Seq.default_task()),
+            Some(ModelFamily::Seq2Seq)
+        );
+        assert_eq!(ModelFamily::Audio.to_string(), "audio model");
+    }
+}
@@ -0,0 +1,509 @@
+//! Module for the generic fallback model config, used when the model type is not supported
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::models::{Dtype, ModelConfigTrait, ModelError, ModelFamily, ModelLibraries, RopeParams};
+
+/// Field aliases of the hidden size, from the most to the least common
+const HIDDEN_SIZE_ALIASES: [&str; 5] = ["hidden_size", "d_model", "n_embd", "dim", "embed_dim"];
+/// Field aliases of the intermediate size
+const INTERMEDIATE_SIZE_ALIASES: [&str; 6] = [
+    "intermediate_size",
+    "n_inner",
+    "ffn_dim",
+    "d_ff",
+    "encoder_ffn_dim",
+    "ffn_hidden_size",
+];
+/// Field aliases of the max position embeddings
+const MAX_POSITION_EMBEDDINGS_ALIASES: [&str; 5] = [
+    "max_position_embeddings",
+    "n_positions",
+    "n_ctx",
+    "max_sequence_length",
+    "seq_length",
+];
+/// Field aliases of the number of attention heads
+const NUM_ATTENTION_HEADS_ALIASES: [&str; 5] = [
+    "num_attention_heads",
+    "n_head",
+    "num_heads",
+    "n_heads",
+    "encoder_attention_heads",
+];
+/// Field aliases of the number of hidden layers
+const NUM_HIDDEN_LAYERS_ALIASES: [&str; 5] = [
+    "num_hidden_layers",
+    "n_layer",
+    "num_layers",
+    "n_layers",
+    "encoder_layers",
+];
+/// Field aliases of the number of key/value heads
+const NUM_KEY_VALUE_HEADS_ALIASES: [&str; 3] =
+    ["num_key_value_heads", "n_head_kv", "multi_query_group_num"];
+/// Field aliases of the vocabulary size
+const VOCAB_SIZE_ALIASES: [&str; 3] = ["vocab_size", "n_vocab", "padded_vocab_size"];
+
+/// Most architectures use 64 dimensions per attention head
+const DEFAULT_HEAD_DIM: i32 = 64;
+
+/// Read the first of the aliases found in the JSON value
+fn first_of(value: &Value, aliases: &[&str]) -> Option<i32> {
+    aliases
+        .iter()
+        .find_map(|alias| value[*alias].as_i64())
+        .map(|val| val as i32)
+}
+
+/// A struct representing the parameters guessed from an unsupported architecture
+#[derive(Clone, Debug, Deserialize)]
+pub struct GenericParams {
+    /// Generic model hidden_size
+    hidden_size: i32,
+    /// Generic model intermediate_size
+    intermediate_size: i32,
+    /// Generic model max_position_embeddings
+    max_position_embeddings: i32,
+    /// Generic model num_attention_heads
+    num_attention_heads: i32,
+    /// Generic model num_hidden_layers
+    num_hidden_layers: i32,
+    /// Generic model num_key_value_heads
+    num_key_value_heads: i32,
+    /// Generic model head_dim
+    head_dim: i32,
+    /// Generic model vocab_size
+    vocab_size: i32,
+    /// Generic model attention window of the sliding-window attention layers, if any
+    sliding_window: Option<i32>,
+    /// Generic model number of sliding-window attention layers, every layer when not set
+    #[serde(default)]
+    sliding_layers: Option<i32>,
+    /// Fields that were missing from the config and filled with a default value
+    inferred_fields: Vec<String>,
+}
+
+/// Generic model parameters implementation
+impl GenericParams {
+    /// Build a new `GenericParams` struct based on the provided parameters, the missing ones are
+    /// filled with defaults derived from the hidden size
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        hidden_size: i32,
+        intermediate_size: Option<i32>,
+        max_position_embeddings: Option<i32>,
+        num_attention_heads: Option<i32>,
+        num_hidden_layers: i32,
+        num_key_value_heads: Option<i32>,
+        head_dim: Option<i32>,
+        vocab_size: Option<i32>,
+        sliding_window: Option<i32>,
+    ) -> GenericParams {
+        let mut inferred_fields = Vec::new();
+        let mut infer = |field: &str, val: Option<i32>, default: i32| {
+            val.unwrap_or_else(|| {
+                inferred_fields.push(field.to_string());
+                default
+            })
+        };
+        let intermediate_size = infer("intermediate_size", intermediate_size, 4 * hidden_size);
+        let max_position_embeddings = infer("max_position_embeddings", max_position_embeddings, 0);
+        let num_attention_heads = infer(
+            "num_attention_heads",
+            num_attention_heads,
+            (hidden_size / DEFAULT_HEAD_DIM).max(1),
+        );
+        let vocab_size = infer("vocab_size", vocab_size, 0);
+        // Without GQA or an explicit head_dim, the standard multi-head attention layout is used
+        let num_key_value_heads = num_key_value_heads.unwrap_or(num_attention_heads);
+        let head_dim = head_dim.unwrap_or(hidden_size / num_attention_heads);
+        GenericParams {
+            hidden_size,
+            intermediate_size,
+            max_position_embeddings,
+            num_attention_heads,
+            num_hidden_layers,
+            num_key_value_heads,
+            head_dim,
+            vocab_size,
+            sliding_window,
+            sliding_layers: None,
+            inferred_fields,
+        }
+    }
+    /// Set the number of sliding-window attention layers, the others attending to the whole
+    /// context
+    pub fn with_sliding_layers(mut self, sliding_layers: i32) -> GenericParams {
+        self.sliding_layers = Some(sliding_layers);
+        self
+    }
+    /// Build from a JSON value, looking for the common aliases of every field
+    pub fn from_json(value: Value) -> Result<GenericParams, ModelError> {
+        let hidden_size = first_of(&value, &HIDDEN_SIZE_ALIASES)
+            .ok_or(ModelError::MissingField("hidden_size".to_string()))?;
+
+        let intermediate_size = first_of(&value, &INTERMEDIATE_SIZE_ALIASES);
+
+        let max_position_embeddings = first_of(&value, &MAX_POSITION_EMBEDDINGS_ALIASES);
+
+        let num_attention_heads = first_of(&value, &NUM_ATTENTION_HEADS_ALIASES);
+
+        let num_hidden_layers = first_of(&value, &NUM_HIDDEN_LAYERS_ALIASES)
+            .ok_or(ModelError::MissingField("num_hidden_layers".to_string()))?;
+
+        let num_key_value_heads = first_of(&value, &NUM_KEY_VALUE_HEADS_ALIASES);
+
+        let head_dim = first_of(&value, &["head_dim"]);
+
+        let vocab_size = first_of(&value, &VOCAB_SIZE_ALIASES);
+
+        // Qwen2 configs store a window even when `use_sliding_window` disables it
+        let sliding_window = match value["use_sliding_window"].as_bool() {
+            Some(false) => None,
+            _ => first_of(&value, &["sliding_window"]),
+        };
+        // Gemma 2 alternates sliding-window and global layers, the newer configs list the kind of
+        // every layer or the period of the global ones
+        let sliding_layers = match value["layer_types"].as_array() {
+            Some(layer_types) => Some(
+                layer_types
+                    .iter()
+                    .filter(|layer_type| layer_type.as_str() == Some("sliding_attention"))
+                    .count() as i32,
+            ),
+            None => match first_of(&value, &["sliding_window_pattern"]) {
+                Some(pattern) if pattern > 0 => {
+                    Some(num_hidden_layers - num_hidden_layers / pattern)
+                }
+                _ if value["model_type"].as_str() == Some("gemma2") => {
+                    Some((num_hidden_layers + 1) / 2)
+                }
+                _ => None,
+            },
+        };
+
+        let params = GenericParams::new(
+            hidden_size,
+            intermediate_size,
+            max_position_embeddings,
+            num_attention_heads,
+            num_hidden_layers,
+            num_key_value_heads,
+            head_dim,
+            vocab_size,
+            sliding_window,
+        );
+        Ok(match sliding_layers {
+            Some(sliding_layers) => params.with_sliding_layers(sliding_layers),
+            None => params,
+        })
+    }
+}
+
+/// A struct representing the config of an unsupported architecture.
+///
+/// The fields are guessed from their most common names, so the estimates built on top of it are
+/// low confidence ones.
+#[derive(Clone, Debug, Deserialize)]
+pub struct GenericModelConfig {
+    /// Generic model parameters
+    params: GenericParams,
+    /// Generic model tie_word_embeddings, whether the LM head shares the token embeddings weights
+    tie_word_embeddings: bool,
+    /// Generic model rotary position embeddings parameters
+    rope_params: RopeParams,
+    /// Generic model family, inferred from the config `architectures`
+    model_family: ModelFamily,
+    /// Generic model weights precision
+    dtype: Dtype,
+    /// Generic model type, as found in the config
+    model_type: String,
+    /// Generic model available libraries
+    available_libraries: Vec<ModelLibraries>,
+}
+
+/// Generic model implementation
+impl GenericModelConfig {
+    /// Build a new `GenericModelConfig` struct based on the provided parameters
+    pub fn new(
+        params: GenericParams,
+        tie_word_embeddings: bool,
+        rope_params: RopeParams,
+        model_family: ModelFamily,
+        dtype: Dtype,
+        model_type: String,
+        available_libraries: Vec<ModelLibraries>,
+    ) -> GenericModelConfig {
+        GenericModelConfig {
+            params,
+            tie_word_embeddings,
+            rope_params,
+            model_family,
+            dtype,
+            model_type,
+            available_libraries,
+        }
+    }
+    /// Returns the fields that were missing from the config and filled with a default value
+    pub fn inferred_fields(&self) -> &[String] {
+        &self.params.inferred_fields
+    }
+}
+
+/// Implementation of the `ModelConfigTrait` trait for `GenericModelConfig`
+impl ModelConfigTrait for GenericModelConfig {
+    fn hidden_size(&self) -> i32 {
+        self.params.hidden_size
+    }
+
+    fn intermediate_size(&self) -> i32 {
+        self.params.intermediate_size
+    }
+
+    fn max_position_embeddings(&self) -> i32 {
+        self.params.max_position_embeddings
+    }
+
+    fn num_attention_heads(&self) -> i32 {
+        self.params.num_attention_heads
+    }
+
+    fn num_hidden_layers(&self) -> i32 {
+        self.params.num_hidden_layers
+    }
+
+    fn num_key_value_heads(&self) -> i32 {
+        self.params.num_key_value_heads
+    }
+
+    fn head_dim(&self) -> i32 {
+        self.params.head_dim
+    }
+
+    fn model_family(&self) -> ModelFamily {
+        self.model_family
+    }
+
+    fn vocab_size(&self) -> i32 {
+        self.params.vocab_size
+    }
+
+    fn tie_word_embeddings(&self) -> bool {
+        self.tie_word_embeddings
+    }
+
+    fn sliding_window(&self) -> Option<i32> {
+        self.params.sliding_window
+    }
+
+    fn num_sliding_layers(&self) -> i32 {
+        match self.params.sliding_window {
+            Some(_) => self
+                .params
+                .sliding_layers
+                .unwrap_or_else(|| self.num_decoder_layers()),
+            None => 0,
+        }
+    }
+
+    fn rope_params(&self) -> Option<&RopeParams> {
+        Some(&self.rope_params)
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn model_type(&self) -> &str {
+        &self.model_type
+    }
+
+    fn available_libraries(&self) -> &[ModelLibraries] {
+        &self.available_libraries
+    }
+
+    fn from_json(value: Value) -> Result<Self, ModelError> {
+        let params = GenericParams::from_json(value.clone())?;
+
+        let tie_word_embeddings = value["tie_word_embeddings"].as_bool().unwrap_or(true);
+
+        let rope_params = RopeParams::from_json(&value)?;
+
+        // Unknown architectures are assumed to be decoder-only language models
+        let model_family = value["architectures"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|architecture| architecture.as_str())
+            .find_map(ModelFamily::from_architecture)
+            .unwrap_or(ModelFamily::DecoderLM);
+
+        let dtype = Dtype::from_json(&value);
+
+        let model_type = match value["model_type"].as_str() {
+            Some(model_type) => model_type.to_string(),
+            None => return Err(ModelError::MissingField("model_type".to_string())),
+        };
+
+        let available_libraries = ModelLibraries::from_json(&value);
+
+        Ok(GenericModelConfig::new(
+            params,
+            tie_word_embeddings,
+            rope_params,
+            model_family,
+            dtype,
+            model_type,
+            available_libraries,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_generic_model_params() {
+        let generic_params = GenericParams::new(
+            2048,
+            None,
+            Some(2048),
+            None,
+            24,
+            None,
+            None,
+            Some(50280),
+            None,
+        );
+
+        assert_eq!(generic_params.hidden_size, 2048);
+        assert_eq!(generic_params.intermediate_size, 8192);
+        assert_eq!(generic_params.max_position_embeddings, 2048);
+        assert_eq!(generic_params.num_attention_heads, 32);
+        assert_eq!(generic_params.num_hidden_layers, 24);
+        assert_eq!(generic_params.num_key_value_heads, 32);
+        assert_eq!(generic_params.head_dim, 64);
+        assert_eq!(
+            generic_params.inferred_fields,
+            vec!["intermediate_size", "num_attention_heads"]
+        );
+    }
+
+    #[test]
+    fn test_generic_model_config() {
+        let generic_params = GenericParams::new(
+            1024,
+            Some(4096),
+            Some(512),
+            Some(16),
+            12,
+            None,
+            None,
+            Some(32000),
+            Some(4096),
+        );
+        let generic_model_config = GenericModelConfig::new(
+            generic_params,
+            true,
+            RopeParams::new(None, None),
+            ModelFamily::DecoderLM,
+            Dtype::F32,
+            "novel".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        assert_eq!(generic_model_config.params.hidden_size, 1024);
+        assert!(generic_model_config.inferred_fields().is_empty());
+        assert_eq!(generic_model_config.sliding_window(), Some(4096));
+        assert_eq!(generic_model_config.num_sliding_layers(), 12);
+        assert_eq!(generic_model_config.model_type, "novel");
+        assert_eq!(
+            generic_model_config.available_libraries,
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+
+    #[test]
+    fn test_generic_model_trait_implementation() {
+        let value = json!({
+            "model_type": "rwkv5",
+            "n_embd": 2560,
+            "n_inner": 10240,
+            "n_ctx": 4096,
+            "n_head": 40,
+            "n_layer": 32,
+            "n_vocab": 65536
+        });
+        let generic_model_config = GenericModelConfig::from_json(value).unwrap();
+        assert_eq!(generic_model_config.hidden_size(), 2560);
+        assert_eq!(generic_model_config.intermediate_size(), 10240);
+        assert_eq!(generic_model_config.max_position_embeddings(), 4096);
+        assert_eq!(generic_model_config.num_attention_heads(), 40);
+        assert_eq!(generic_model_config.num_hidden_layers(), 32);
+        assert_eq!(generic_model_config.vocab_size(), 65536);
+        assert_eq!(generic_model_config.model_family(), ModelFamily::DecoderLM);
+        assert_eq!(generic_model_config.model_type(), "rwkv5");
+        assert_eq!(
+            generic_model_config.available_libraries(),
+            vec![ModelLibraries::PyTorch]
+        );
+
+        // Gemma 7B sets a head_dim larger than hidden_size / num_attention_heads
+        let value = json!({
+            "model_type": "gemma",
+            "hidden_size": 3072,
+            "intermediate_size": 24576,
+            "max_position_embeddings": 8192,
+            "num_attention_heads": 16,
+            "num_hidden_layers": 28,
+            "num_key_value_heads": 16,
+            "head_dim": 256,
+            "vocab_size": 256000,
+            "architectures": ["GemmaForSequenceClassification"]
+        });
+        let generic_model_config = GenericModelConfig::from_json(value).unwrap();
+        assert_eq!(generic_model_config.head_dim(), 256);
+        assert_eq!(generic_model_config.kv_dim(), 4096);
+        assert_eq!(generic_model_config.ffn_ratio(), 8.0);
+        assert_eq!(generic_model_config.model_family(), ModelFamily::EncoderLM);
+
+        let value = json!({"model_type": "novel", "d_model": 512});
+        assert!(GenericModelConfig::from_json(value).is_err());
+    }
+
+    #[test]
+    fn test_generic_sliding_layers() {
+        // Gemma 3 has one global layer every 6 layers
+        let value = json!({
+            "model_type": "gemma3_text",
+            "hidden_size": 2560,
+            "num_hidden_layers": 34,
+            "sliding_window": 1024,
+            "sliding_window_pattern": 6
+        });
+        let generic_model_config = GenericModelConfig::from_json(value).unwrap();
+        assert_eq!(generic_model_config.num_sliding_layers(), 29);
+
+        let value = json!({
+            "model_type": "novel",
+            "hidden_size": 1024,
+            "num_hidden_layers": 4,
+            "sliding_window": 512,
+            "layer_types": [
+                "sliding_attention",
+                "full_attention",
+                "sliding_attention",
+                "full_attention"
+            ]
+        });
+        let generic_model_config = GenericModelConfig::from_json(value).unwrap();
+        assert_eq!(generic_model_config.num_sliding_layers(), 2);
+
+        // Without a window no layer slides
+        let value = json!({"model_type": "novel", "hidden_size": 1024, "num_hidden_layers": 4});
+        let generic_model_config = GenericModelConfig::from_json(value).unwrap();
+        assert_eq!(generic_model_config.num_sliding_layers(), 0);
+    }
+}
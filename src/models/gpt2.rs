@@ -2,7 +2,7 @@
 use serde::Deserialize;
 use serde_json::Value;
 
-use crate::models::{ModelConfigTrait, ModelError, ModelLibraries};
+use crate::models::{Dtype, ModelConfigTrait, ModelError, ModelLibraries};
 
 /// A struct representing the GPT2 architecture parameters
 #[derive(Clone, Debug, Deserialize)]
@@ -74,6 +74,12 @@ impl GPT2Params {
 pub struct GPT2ModelConfig {
     /// GPT2 model parameters
     params: GPT2Params,
+    /// GPT2 model vocab_size
+    vocab_size: i32,
+    /// GPT2 model tie_word_embeddings, whether the LM head shares the token embeddings weights
+    tie_word_embeddings: bool,
+    /// GPT2 model weights precision
+    dtype: Dtype,
     /// GPT2 model type
     model_type: String,
     /// GPT2 model available libraries
@@ -85,11 +91,17 @@ impl GPT2ModelConfig {
     /// Build a new `GPT2ModelConfig` struct based on the provided parameters
     pub fn new(
         params: GPT2Params,
+        vocab_size: i32,
+        tie_word_embeddings: bool,
+        dtype: Dtype,
         model_type: String,
         available_libraries: Vec<ModelLibraries>,
     ) -> GPT2ModelConfig {
         GPT2ModelConfig {
             params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
             model_type,
             available_libraries,
         }
@@ -118,6 +130,18 @@ impl ModelConfigTrait for GPT2ModelConfig {
         self.params.n_layer
     }
 
+    fn vocab_size(&self) -> i32 {
+        self.vocab_size
+    }
+
+    fn tie_word_embeddings(&self) -> bool {
+        self.tie_word_embeddings
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
     fn model_type(&self) -> &str {
         &self.model_type
     }
@@ -132,20 +156,27 @@ impl ModelConfigTrait for GPT2ModelConfig {
     {
         let params = GPT2Params::from_json(value.clone())?;
 
+        let vocab_size = value["vocab_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("vocab_size".to_string()))?
+            as i32;
+
+        let tie_word_embeddings = value["tie_word_embeddings"].as_bool().unwrap_or(true);
+
+        let dtype = Dtype::from_json(&value);
+
         let model_type = match value["model_type"].as_str() {
             Some(model_type) => model_type.to_string(),
             None => return Err(ModelError::MissingField("model_type".to_string())),
         };
 
-        // TODO: Implement this
-        let available_libraries = vec![ModelLibraries::PyTorch];
-        // let available_libraries = match value["available_libraries"].as_array() {
-        //     Some(al) => al.iter().map(|v| ModelLibraries::from_str(v.as_str().unwrap()).unwrap()).collect(),
-        //     None => return Err(ModelError::MissingField("available_libraries".to_string())),
-        // };
+        let available_libraries = ModelLibraries::from_json(&value);
 
         Ok(GPT2ModelConfig::new(
             params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
             model_type,
             available_libraries,
         ))
@@ -171,6 +202,9 @@ mod tests {
         let params = GPT2Params::new(768, None, 1024, 12, 12);
         let model_config = GPT2ModelConfig::new(
             params,
+            50257,
+            true,
+            Dtype::F32,
             "gpt2".to_string(),
             vec![ModelLibraries::Transformers],
         );
@@ -191,6 +225,9 @@ mod tests {
         let params = GPT2Params::new(768, None, 1024, 12, 12);
         let model_config = GPT2ModelConfig::new(
             params,
+            50257,
+            true,
+            Dtype::F32,
             "gpt2".to_string(),
             vec![ModelLibraries::Transformers],
         );
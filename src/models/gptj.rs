@@ -2,7 +2,7 @@
 use serde::Deserialize;
 use serde_json::Value;
 
-use crate::models::{ModelConfigTrait, ModelError, ModelLibraries};
+use crate::models::{Dtype, ModelConfigTrait, ModelError, ModelLibraries};
 
 /// A struct representing the GPT-J architecture parameters
 #[derive(Clone, Debug, Deserialize)]
@@ -74,6 +74,12 @@ impl GPTJParams {
 pub struct GPTJModelConfig {
     /// GPT-J model parameters
     params: GPTJParams,
+    /// GPT-J model vocab_size
+    vocab_size: i32,
+    /// GPT-J model tie_word_embeddings, whether the LM head shares the token embeddings weights
+    tie_word_embeddings: bool,
+    /// GPT-J model weights precision
+    dtype: Dtype,
     /// GPT-J model type
     model_type: String,
     /// GPT-J model available libraries
@@ -85,11 +91,17 @@ impl GPTJModelConfig {
     /// Build a new `GPTJModelConfig` struct based on the provided parameters
     pub fn new(
         params: GPTJParams,
+        vocab_size: i32,
+        tie_word_embeddings: bool,
+        dtype: Dtype,
         model_type: String,
         available_libraries: Vec<ModelLibraries>,
     ) -> GPTJModelConfig {
         GPTJModelConfig {
             params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
             model_type,
             available_libraries,
         }
@@ -118,6 +130,18 @@ impl ModelConfigTrait for GPTJModelConfig {
         self.params.n_layer
     }
 
+    fn vocab_size(&self) -> i32 {
+        self.vocab_size
+    }
+
+    fn tie_word_embeddings(&self) -> bool {
+        self.tie_word_embeddings
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
     fn model_type(&self) -> &str {
         &self.model_type
     }
@@ -129,20 +153,27 @@ impl ModelConfigTrait for GPTJModelConfig {
     fn from_json(value: Value) -> Result<Self, ModelError> {
         let params = GPTJParams::from_json(value.clone())?;
 
+        let vocab_size = value["vocab_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("vocab_size".to_string()))?
+            as i32;
+
+        let tie_word_embeddings = value["tie_word_embeddings"].as_bool().unwrap_or(false);
+
+        let dtype = Dtype::from_json(&value);
+
         let model_type = match value["model_type"].as_str() {
             Some(model_type) => model_type.to_string(),
             None => return Err(ModelError::MissingField("model_type".to_string())),
         };
 
-        // TODO: Implement this
-        let available_libraries = vec![ModelLibraries::PyTorch];
-        // let available_libraries = match value["available_libraries"].as_array() {
-        //     Some(al) => al.iter().map(|v| ModelLibraries::from_str(v.as_str().unwrap()).unwrap()).collect(),
-        //     None => return Err(ModelError::MissingField("available_libraries".to_string())),
-        // };
+        let available_libraries = ModelLibraries::from_json(&value);
 
         Ok(GPTJModelConfig::new(
             params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
             model_type,
             available_libraries,
         ))
@@ -166,8 +197,14 @@ mod tests {
     #[test]
     fn test_gpt_j_model_config() {
         let params = GPTJParams::new(1024, None, 1024, 16, 28);
-        let model_config =
-            GPTJModelConfig::new(params, "gpt-j".to_string(), vec![ModelLibraries::PyTorch]);
+        let model_config = GPTJModelConfig::new(
+            params,
+            50400,
+            false,
+            Dtype::F32,
+            "gpt-j".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
         assert_eq!(model_config.params.n_embd, 1024);
         assert_eq!(model_config.params.n_inner, 4096);
         assert_eq!(model_config.params.n_positions, 1024);
@@ -183,8 +220,14 @@ mod tests {
     #[test]
     fn test_gpt_j_model_trait_implementation() {
         let params = GPTJParams::new(1024, Some(2048), 1024, 16, 28);
-        let model_config =
-            GPTJModelConfig::new(params, "gpt-j".to_string(), vec![ModelLibraries::PyTorch]);
+        let model_config = GPTJModelConfig::new(
+            params,
+            50400,
+            false,
+            Dtype::F32,
+            "gpt-j".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
         assert_eq!(model_config.hidden_size(), 1024);
         assert_eq!(model_config.intermediate_size(), 2048);
         assert_eq!(model_config.max_position_embeddings(), 1024);
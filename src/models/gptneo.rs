@@ -2,7 +2,7 @@
 use serde::Deserialize;
 use serde_json::Value;
 
-use crate::models::{ModelConfigTrait, ModelError, ModelLibraries};
+use crate::models::{Dtype, ModelConfigTrait, ModelError, ModelLibraries};
 
 /// A struct representing the GPT-Neo architecture parameters
 #[derive(Clone, Debug, Deserialize)]
@@ -44,10 +44,11 @@ impl GPTNeoParams {
             .ok_or(ModelError::MissingField("hidden_size".to_string()))?
             as i32;
 
+        // GPT-Neo configs store a null intermediate_size for the default 4 * hidden_size
         let intermediate_size = value["intermediate_size"]
             .as_i64()
-            .ok_or(ModelError::MissingField("intermediate_size".to_string()))?
-            as i32;
+            .map(|val| val as i32)
+            .unwrap_or(4 * hidden_size);
 
         let max_position_embeddings =
             value["max_position_embeddings"]
@@ -56,13 +57,16 @@ impl GPTNeoParams {
                     "max_position_embeddings".to_string(),
                 ))? as i32;
 
+        // GPT-Neo uses num_heads and num_layers, GPT-NeoX the usual names
         let num_attention_heads = value["num_attention_heads"]
             .as_i64()
+            .or_else(|| value["num_heads"].as_i64())
             .ok_or(ModelError::MissingField("num_attention_heads".to_string()))?
             as i32;
 
         let num_hidden_layers = value["num_hidden_layers"]
             .as_i64()
+            .or_else(|| value["num_layers"].as_i64())
             .ok_or(ModelError::MissingField("num_hidden_layers".to_string()))?
             as i32;
 
@@ -81,6 +85,12 @@ impl GPTNeoParams {
 pub struct GPTNeoModelConfig {
     /// GPT-Neo model parameters
     params: GPTNeoParams,
+    /// GPT-Neo model vocab_size
+    vocab_size: i32,
+    /// GPT-Neo model tie_word_embeddings, whether the LM head shares the token embeddings weights
+    tie_word_embeddings: bool,
+    /// GPT-Neo model weights precision
+    dtype: Dtype,
     /// GPT-Neo model type
     model_type: String,
     /// GPT-Neo model available libraries
@@ -92,11 +102,17 @@ impl GPTNeoModelConfig {
     /// Build a new `GPTNeoModelConfig` struct based on the provided parameters
     pub fn new(
         params: GPTNeoParams,
+        vocab_size: i32,
+        tie_word_embeddings: bool,
+        dtype: Dtype,
         model_type: String,
         available_libraries: Vec<ModelLibraries>,
     ) -> GPTNeoModelConfig {
         GPTNeoModelConfig {
             params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
             model_type,
             available_libraries,
         }
@@ -125,6 +141,18 @@ impl ModelConfigTrait for GPTNeoModelConfig {
         self.params.num_hidden_layers
     }
 
+    fn vocab_size(&self) -> i32 {
+        self.vocab_size
+    }
+
+    fn tie_word_embeddings(&self) -> bool {
+        self.tie_word_embeddings
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
     fn model_type(&self) -> &str {
         &self.model_type
     }
@@ -134,22 +162,32 @@ impl ModelConfigTrait for GPTNeoModelConfig {
     }
 
     fn from_json(value: Value) -> Result<Self, ModelError> {
-        let params = GPTNeoParams::from_json(value["params"].clone())?;
+        let params = GPTNeoParams::from_json(value.clone())?;
+
+        let vocab_size = value["vocab_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("vocab_size".to_string()))?
+            as i32;
+
+        // GPT-NeoX unties the LM head by default, GPT-Neo ties it
+        let tie_word_embeddings = value["tie_word_embeddings"]
+            .as_bool()
+            .unwrap_or(value["model_type"] != "gpt_neox");
+
+        let dtype = Dtype::from_json(&value);
 
         let model_type = match value["model_type"].as_str() {
             Some(model_type) => model_type.to_string(),
             None => return Err(ModelError::MissingField("model_type".to_string())),
         };
 
-        // TODO: Implement this
-        let available_libraries = vec![ModelLibraries::PyTorch];
-        // let available_libraries = match value["available_libraries"].as_array() {
-        //     Some(al) => al.iter().map(|v| ModelLibraries::from_str(v.as_str().unwrap()).unwrap()).collect(),
-        //     None => return Err(ModelError::MissingField("available_libraries".to_string())),
-        // };
+        let available_libraries = ModelLibraries::from_json(&value);
 
         Ok(GPTNeoModelConfig::new(
             params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
             model_type,
             available_libraries,
         ))
@@ -159,6 +197,7 @@ impl ModelConfigTrait for GPTNeoModelConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
 
     #[test]
     fn test_gpt_neo_params() {
@@ -173,8 +212,14 @@ mod tests {
     #[test]
     fn test_gpt_neo_model_config() {
         let params = GPTNeoParams::new(768, 3072, 1024, 12, 12);
-        let model_config =
-            GPTNeoModelConfig::new(params, "gpt_neo".to_string(), vec![ModelLibraries::PyTorch]);
+        let model_config = GPTNeoModelConfig::new(
+            params,
+            50257,
+            true,
+            Dtype::F32,
+            "gpt_neo".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
         assert_eq!(model_config.params.hidden_size, 768);
         assert_eq!(model_config.params.intermediate_size, 3072);
         assert_eq!(model_config.params.max_position_embeddings, 1024);
@@ -190,8 +235,14 @@ mod tests {
     #[test]
     fn test_gpt_neo_model_trait_implementation() {
         let params = GPTNeoParams::new(768, 3072, 1024, 12, 12);
-        let model_config =
-            GPTNeoModelConfig::new(params, "gpt_neo".to_string(), vec![ModelLibraries::PyTorch]);
+        let model_config = GPTNeoModelConfig::new(
+            params,
+            50257,
+            true,
+            Dtype::F32,
+            "gpt_neo".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
         assert_eq!(model_config.hidden_size(), 768);
         assert_eq!(model_config.intermediate_size(), 3072);
         assert_eq!(model_config.max_position_embeddings(), 1024);
@@ -203,4 +254,40 @@ mod tests {
             vec![ModelLibraries::PyTorch]
         );
     }
+
+    #[test]
+    fn test_gpt_neo_model_from_json() {
+        let value = json!({
+            "model_type": "gpt_neo",
+            "hidden_size": 2048,
+            "intermediate_size": null,
+            "max_position_embeddings": 2048,
+            "num_heads": 16,
+            "num_layers": 24,
+            "vocab_size": 50257
+        });
+        let model_config = GPTNeoModelConfig::from_json(value).unwrap();
+        assert_eq!(model_config.hidden_size(), 2048);
+        assert_eq!(model_config.intermediate_size(), 8192);
+        assert_eq!(model_config.num_attention_heads(), 16);
+        assert_eq!(model_config.num_hidden_layers(), 24);
+        assert!(model_config.tie_word_embeddings());
+
+        let value = json!({
+            "model_type": "gpt_neox",
+            "hidden_size": 6144,
+            "intermediate_size": 24576,
+            "max_position_embeddings": 2048,
+            "num_attention_heads": 64,
+            "num_hidden_layers": 44,
+            "vocab_size": 50432
+        });
+        let model_config = GPTNeoModelConfig::from_json(value).unwrap();
+        assert_eq!(model_config.intermediate_size(), 24576);
+        assert_eq!(model_config.num_attention_heads(), 64);
+        assert_eq!(model_config.num_hidden_layers(), 44);
+        assert_eq!(model_config.model_type(), "gpt_neox");
+        assert!(!model_config.tie_word_embeddings());
+        assert_eq!(model_config.embedding_parameters(), 2 * 50432 * 6144);
+    }
 }
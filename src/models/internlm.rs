@@ -0,0 +1,299 @@
+//! Module for the InternLM model (also used for InternLM2)
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::models::{Dtype, ModelConfigTrait, ModelError, ModelLibraries, RopeParams};
+
+/// A struct representing the InternLM architecture parameters
+#[derive(Clone, Debug, Deserialize)]
+pub struct InternLMParams {
+    /// InternLM model hidden_size
+    hidden_size: i32,
+    /// InternLM model intermediate_size
+    intermediate_size: i32,
+    /// InternLM model max_position_embeddings
+    max_position_embeddings: i32,
+    /// InternLM model num_attention_heads
+    num_attention_heads: i32,
+    /// InternLM model num_hidden_layers
+    num_hidden_layers: i32,
+    /// InternLM model number of key/value heads (GQA), which defaults to `num_attention_heads`
+    num_key_value_heads: i32,
+    /// Whether the attention projections have biases
+    bias: bool,
+}
+
+/// InternLM model parameters implementation
+impl InternLMParams {
+    /// Build a new `InternLMParams` struct based on the provided parameters
+    pub fn new(
+        hidden_size: i32,
+        intermediate_size: i32,
+        max_position_embeddings: i32,
+        num_attention_heads: i32,
+        num_hidden_layers: i32,
+        num_key_value_heads: Option<i32>,
+        bias: Option<bool>,
+    ) -> InternLMParams {
+        let num_key_value_heads = num_key_value_heads.unwrap_or(num_attention_heads);
+        let bias = bias.unwrap_or(true);
+        InternLMParams {
+            hidden_size,
+            intermediate_size,
+            max_position_embeddings,
+            num_attention_heads,
+            num_hidden_layers,
+            num_key_value_heads,
+            bias,
+        }
+    }
+    /// Build from a JSON value
+    pub fn from_json(value: Value) -> Result<InternLMParams, ModelError> {
+        let hidden_size = value["hidden_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("hidden_size".to_string()))?
+            as i32;
+
+        let intermediate_size = value["intermediate_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("intermediate_size".to_string()))?
+            as i32;
+
+        let max_position_embeddings =
+            value["max_position_embeddings"]
+                .as_i64()
+                .ok_or(ModelError::MissingField(
+                    "max_position_embeddings".to_string(),
+                ))? as i32;
+
+        let num_attention_heads = value["num_attention_heads"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("num_attention_heads".to_string()))?
+            as i32;
+
+        let num_hidden_layers = value["num_hidden_layers"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("num_hidden_layers".to_string()))?
+            as i32;
+
+        let num_key_value_heads = value["num_key_value_heads"].as_i64().map(|val| val as i32);
+
+        let bias = value["bias"].as_bool();
+
+        Ok(InternLMParams::new(
+            hidden_size,
+            intermediate_size,
+            max_position_embeddings,
+            num_attention_heads,
+            num_hidden_layers,
+            num_key_value_heads,
+            bias,
+        ))
+    }
+}
+
+/// A struct representing an InternLM model configuration
+#[derive(Clone, Debug, Deserialize)]
+pub struct InternLMModelConfig {
+    /// InternLM model parameters
+    params: InternLMParams,
+    /// InternLM model vocab_size
+    vocab_size: i32,
+    /// InternLM model tie_word_embeddings, whether the LM head shares the token embeddings weights
+    tie_word_embeddings: bool,
+    /// InternLM model rotary position embeddings parameters
+    rope_params: RopeParams,
+    /// InternLM model weights precision
+    dtype: Dtype,
+    /// InternLM model type
+    model_type: String,
+    /// InternLM model available libraries
+    available_libraries: Vec<ModelLibraries>,
+}
+
+/// InternLM model implementation
+impl InternLMModelConfig {
+    /// Build a new `InternLMModelConfig` struct based on the provided parameters
+    pub fn new(
+        params: InternLMParams,
+        vocab_size: i32,
+        tie_word_embeddings: bool,
+        rope_params: RopeParams,
+        dtype: Dtype,
+        model_type: String,
+        available_libraries: Vec<ModelLibraries>,
+    ) -> InternLMModelConfig {
+        InternLMModelConfig {
+            params,
+            vocab_size,
+            tie_word_embeddings,
+            rope_params,
+            dtype,
+            model_type,
+            available_libraries,
+        }
+    }
+    /// Returns whether the attention projections have biases
+    pub fn bias(&self) -> bool {
+        self.params.bias
+    }
+}
+
+/// Implementation of the `ModelConfigTrait` trait for `InternLMModelConfig`
+impl ModelConfigTrait for InternLMModelConfig {
+    fn hidden_size(&self) -> i32 {
+        self.params.hidden_size
+    }
+
+    fn intermediate_size(&self) -> i32 {
+        self.params.intermediate_size
+    }
+
+    fn max_position_embeddings(&self) -> i32 {
+        self.params.max_position_embeddings
+    }
+
+    fn num_attention_heads(&self) -> i32 {
+        self.params.num_attention_heads
+    }
+
+    fn num_hidden_layers(&self) -> i32 {
+        self.params.num_hidden_layers
+    }
+
+    fn num_key_value_heads(&self) -> i32 {
+        self.params.num_key_value_heads
+    }
+
+    fn vocab_size(&self) -> i32 {
+        self.vocab_size
+    }
+
+    fn tie_word_embeddings(&self) -> bool {
+        self.tie_word_embeddings
+    }
+
+    fn rope_params(&self) -> Option<&RopeParams> {
+        Some(&self.rope_params)
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn model_type(&self) -> &str {
+        &self.model_type
+    }
+
+    fn available_libraries(&self) -> &[ModelLibraries] {
+        &self.available_libraries
+    }
+
+    fn from_json(value: Value) -> Result<Self, ModelError> {
+        let params = InternLMParams::from_json(value.clone())?;
+
+        let vocab_size = value["vocab_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("vocab_size".to_string()))?
+            as i32;
+
+        let tie_word_embeddings = value["tie_word_embeddings"].as_bool().unwrap_or(false);
+
+        let rope_params = RopeParams::from_json(&value)?;
+
+        let dtype = Dtype::from_json(&value);
+
+        let model_type = match value["model_type"].as_str() {
+            Some(model_type) => model_type.to_string(),
+            None => return Err(ModelError::MissingField("model_type".to_string())),
+        };
+
+        let available_libraries = ModelLibraries::from_json(&value);
+
+        Ok(InternLMModelConfig::new(
+            params,
+            vocab_size,
+            tie_word_embeddings,
+            rope_params,
+            dtype,
+            model_type,
+            available_libraries,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_internlm_model_params() {
+        let internlm_params = InternLMParams::new(4096, 11008, 2048, 32, 32, None, None);
+
+        assert_eq!(internlm_params.hidden_size, 4096);
+        assert_eq!(internlm_params.intermediate_size, 11008);
+        assert_eq!(internlm_params.max_position_embeddings, 2048);
+        assert_eq!(internlm_params.num_attention_heads, 32);
+        assert_eq!(internlm_params.num_hidden_layers, 32);
+        assert_eq!(internlm_params.num_key_value_heads, 32);
+        assert!(internlm_params.bias);
+    }
+
+    #[test]
+    fn test_internlm_model_config() {
+        let internlm_params = InternLMParams::new(4096, 11008, 2048, 32, 32, None, None);
+        let internlm_model_config = InternLMModelConfig::new(
+            internlm_params,
+            103168,
+            false,
+            RopeParams::new(None, None),
+            Dtype::F32,
+            "internlm".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        assert_eq!(internlm_model_config.params.hidden_size, 4096);
+        assert_eq!(internlm_model_config.num_key_value_heads(), 32);
+        assert!(internlm_model_config.bias());
+        assert_eq!(internlm_model_config.model_type, "internlm");
+        assert_eq!(
+            internlm_model_config.available_libraries,
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+
+    #[test]
+    fn test_internlm_model_trait_implementation() {
+        let value = json!({
+            "model_type": "internlm2",
+            "bias": false,
+            "hidden_size": 4096,
+            "intermediate_size": 14336,
+            "max_position_embeddings": 32768,
+            "num_attention_heads": 32,
+            "num_hidden_layers": 32,
+            "num_key_value_heads": 8,
+            "rope_theta": 1000000,
+            "torch_dtype": "bfloat16",
+            "vocab_size": 92544
+        });
+        let internlm_model_config = InternLMModelConfig::from_json(value).unwrap();
+        assert_eq!(internlm_model_config.hidden_size(), 4096);
+        assert_eq!(internlm_model_config.intermediate_size(), 14336);
+        assert_eq!(internlm_model_config.max_position_embeddings(), 32768);
+        assert_eq!(internlm_model_config.num_attention_heads(), 32);
+        assert_eq!(internlm_model_config.num_hidden_layers(), 32);
+        assert_eq!(internlm_model_config.num_key_value_heads(), 8);
+        assert!(!internlm_model_config.bias());
+        assert_eq!(internlm_model_config.dtype(), Dtype::BF16);
+        assert_eq!(
+            internlm_model_config.rope_params().unwrap().theta(),
+            1000000.0
+        );
+        assert_eq!(internlm_model_config.model_type(), "internlm2");
+        assert_eq!(
+            internlm_model_config.available_libraries(),
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Module for the kernels of the quantized weights, and the GPUs they run on
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::models::{Precision, QuantType};
+
+/// Enumerate the kernels the inference runtimes run the quantized weights with
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QuantKernel {
+    /// vLLM mixed-precision GEMM for Hopper GPUs, GPTQ and AWQ
+    Machete,
+    /// Marlin mixed-precision GEMM (vLLM, TGI), GPTQ and AWQ
+    Marlin,
+    /// ExLlamaV2 kernels (transformers, TGI), 4-bit GPTQ
+    ExLlamaV2,
+    /// AutoAWQ GEMM kernels, 4-bit AWQ
+    AwqGemm,
+    /// AutoGPTQ CUDA kernels, every GPTQ bit width
+    GptqCuda,
+    /// bitsandbytes kernels, INT8 and NF4
+    BitsAndBytes,
+    /// llama.cpp CUDA kernels, GGUF files
+    LlamaCpp,
+}
+
+/// QuantKernel implementation
+impl QuantKernel {
+    /// Returns the lowest CUDA compute capability the kernel runs on
+    pub fn min_compute_capability(&self) -> (u32, u32) {
+        match self {
+            QuantKernel::Machete => (9, 0),
+            QuantKernel::Marlin => (8, 0),
+            QuantKernel::AwqGemm => (7, 5),
+            QuantKernel::ExLlamaV2 | QuantKernel::GptqCuda => (6, 0),
+            QuantKernel::BitsAndBytes | QuantKernel::LlamaCpp => (5, 0),
+        }
+    }
+    /// Returns the kernels running the weights in the given precision, from the fastest, empty
+    /// for the unquantized precisions and FP8 which run natively
+    pub fn candidates(precision: Precision) -> Vec<QuantKernel> {
+        match precision {
+            Precision::GPTQ { bits: 4 } => vec![
+                QuantKernel::Machete,
+                QuantKernel::Marlin,
+                QuantKernel::ExLlamaV2,
+                QuantKernel::GptqCuda,
+            ],
+            Precision::GPTQ { bits: 8 } => vec![
+                QuantKernel::Machete,
+                QuantKernel::Marlin,
+                QuantKernel::GptqCuda,
+            ],
+            Precision::GPTQ { .. } => vec![QuantKernel::GptqCuda],
+            Precision::AWQ { bits: 4 } => vec![
+                QuantKernel::Machete,
+                QuantKernel::Marlin,
+                QuantKernel::AwqGemm,
+            ],
+            Precision::INT8 | Precision::NF4 => vec![QuantKernel::BitsAndBytes],
+            Precision::GGUF(QuantType::F16 | QuantType::F32) => vec![],
+            Precision::GGUF(_) => vec![QuantKernel::LlamaCpp],
+            _ => vec![],
+        }
+    }
+    /// Returns the kernels running the weights in the given precision on a GPU of the given
+    /// compute capability, from the fastest
+    pub fn usable(precision: Precision, compute_capability: (u32, u32)) -> Vec<QuantKernel> {
+        QuantKernel::candidates(precision)
+            .into_iter()
+            .filter(|kernel| compute_capability >= kernel.min_compute_capability())
+            .collect()
+    }
+    /// Returns the fastest kernel running the weights in the given precision on a GPU of the
+    /// given compute capability, `None` when none runs on it or none is needed
+    pub fn best(precision: Precision, compute_capability: (u32, u32)) -> Option<QuantKernel> {
+        QuantKernel::usable(precision, compute_capability)
+            .first()
+            .copied()
+    }
+}
+
+/// Implement the display of the QuantKernel enum
+impl Display for QuantKernel {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            QuantKernel::Machete => write!(f, "Machete"),
+            QuantKernel::Marlin => write!(f, "Marlin"),
+            QuantKernel::ExLlamaV2 => write!(f, "ExLlamaV2"),
+            QuantKernel::AwqGemm => write!(f, "AWQ GEMM"),
+            QuantKernel::GptqCuda => write!(f, "GPTQ CUDA"),
+            QuantKernel::BitsAndBytes => write!(f, "bitsandbytes"),
+            QuantKernel::LlamaCpp => write!(f, "llama.cpp CUDA"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quant_kernel_best() {
+        let gptq = Precision::GPTQ { bits: 4 };
+        assert_eq!(QuantKernel::best(gptq, (9, 0)), Some(QuantKernel::Machete));
+        assert_eq!(QuantKernel::best(gptq, (8, 6)), Some(QuantKernel::Marlin));
+        assert_eq!(
+            QuantKernel::best(gptq, (7, 5)),
+            Some(QuantKernel::ExLlamaV2)
+        );
+        assert_eq!(QuantKernel::best(gptq, (5, 2)), None);
+        let awq = Precision::AWQ { bits: 4 };
+        assert_eq!(QuantKernel::best(awq, (7, 5)), Some(QuantKernel::AwqGemm));
+        assert_eq!(QuantKernel::best(awq, (7, 0)), None);
+        assert_eq!(QuantKernel::best(Precision::BF16, (9, 0)), None);
+    }
+
+    #[test]
+    fn test_quant_kernel_usable() {
+        assert_eq!(
+            QuantKernel::usable(Precision::GPTQ { bits: 8 }, (8, 0)),
+            vec![QuantKernel::Marlin, QuantKernel::GptqCuda]
+        );
+        assert_eq!(
+            QuantKernel::usable(Precision::GGUF(QuantType::Q4_K), (6, 1)),
+            vec![QuantKernel::LlamaCpp]
+        );
+        assert!(QuantKernel::candidates(Precision::FP8).is_empty());
+        assert_eq!(QuantKernel::LlamaCpp.to_string(), "llama.cpp CUDA");
+    }
+}
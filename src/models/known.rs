@@ -0,0 +1,158 @@
+//! Module for the database of well-known models, with their verified number of parameters
+use crate::models::ModelConfigTrait;
+
+/// Well-known repositories, with the number of parameters of their weights (as reported by the
+/// safetensors metadata of the repository) and the context length they were trained for
+const KNOWN_MODELS: [(&str, i64, i32); 26] = [
+    ("bigscience/bloom-560m", 559_214_592, 2048),
+    ("EleutherAI/gpt-j-6b", 6_053_381_344, 2048),
+    ("facebook/opt-1.3b", 1_315_758_080, 2048),
+    ("google/gemma-2b", 2_506_172_416, 8192),
+    ("google/gemma-7b", 8_537_680_896, 8192),
+    ("google-t5/t5-base", 222_903_552, 512),
+    ("meta-llama/Llama-2-7b-hf", 6_738_415_616, 4096),
+    ("meta-llama/Llama-2-7b-chat-hf", 6_738_415_616, 4096),
+    ("meta-llama/Llama-2-13b-hf", 13_015_864_320, 4096),
+    ("meta-llama/Llama-2-70b-hf", 68_976_648_192, 4096),
+    ("meta-llama/Meta-Llama-3-8B", 8_030_261_248, 8192),
+    ("meta-llama/Meta-Llama-3-8B-Instruct", 8_030_261_248, 8192),
+    ("meta-llama/Meta-Llama-3-70B", 70_553_706_496, 8192),
+    ("meta-llama/Meta-Llama-3-70B-Instruct", 70_553_706_496, 8192),
+    ("meta-llama/Meta-Llama-3.1-8B", 8_030_261_248, 131072),
+    (
+        "meta-llama/Meta-Llama-3.1-8B-Instruct",
+        8_030_261_248,
+        131072,
+    ),
+    ("microsoft/phi-2", 2_779_683_840, 2048),
+    ("mistralai/Mistral-7B-v0.1", 7_241_732_096, 32768),
+    ("mistralai/Mistral-7B-Instruct-v0.2", 7_241_732_096, 32768),
+    ("mistralai/Mixtral-8x7B-v0.1", 46_702_792_704, 32768),
+    (
+        "mistralai/Mixtral-8x7B-Instruct-v0.1",
+        46_702_792_704,
+        32768,
+    ),
+    ("openai/clip-vit-base-patch32", 151_277_313, 77),
+    ("openai/whisper-large-v3", 1_543_490_560, 448),
+    ("openai-community/gpt2", 124_439_808, 1024),
+    ("Qwen/Qwen2-7B", 7_615_616_512, 131072),
+    ("tiiuae/falcon-7b", 6_921_720_704, 2048),
+];
+
+/// A struct representing a well-known model, whose number of parameters was verified against its
+/// weights, so it can cross-check or replace the estimates derived from its config
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KnownModel {
+    /// The repository of the model (e.g. `meta-llama/Meta-Llama-3-8B`)
+    repo_id: &'static str,
+    /// The number of parameters of the model weights
+    num_parameters: i64,
+    /// The context length the model was trained for
+    max_context: i32,
+}
+
+/// KnownModel implementation
+impl KnownModel {
+    /// Find a well-known model from its repository id, case insensitive. The repository name
+    /// alone is enough when it is not ambiguous (e.g. `whisper-large-v3`).
+    pub fn from_repo_id(repo_id: &str) -> Option<KnownModel> {
+        let matches_repo_id = |known_repo_id: &str| match repo_id.contains('/') {
+            true => known_repo_id.eq_ignore_ascii_case(repo_id),
+            false => known_repo_id
+                .rsplit('/')
+                .next()
+                .is_some_and(|name| name.eq_ignore_ascii_case(repo_id)),
+        };
+        KNOWN_MODELS
+            .iter()
+            .find(|(known_repo_id, _, _)| matches_repo_id(known_repo_id))
+            .map(|&(repo_id, num_parameters, max_context)| KnownModel {
+                repo_id,
+                num_parameters,
+                max_context,
+            })
+    }
+    /// Returns all the well-known models
+    pub fn all() -> Vec<KnownModel> {
+        KNOWN_MODELS
+            .iter()
+            .map(|&(repo_id, num_parameters, max_context)| KnownModel {
+                repo_id,
+                num_parameters,
+                max_context,
+            })
+            .collect()
+    }
+    /// Returns the repository of the model
+    pub fn repo_id(&self) -> &'static str {
+        self.repo_id
+    }
+    /// Returns the verified number of parameters of the model
+    pub fn num_parameters(&self) -> i64 {
+        self.num_parameters
+    }
+    /// Returns the context length the model was trained for
+    pub fn max_context(&self) -> i32 {
+        self.max_context
+    }
+    /// Returns the relative deviation of the number of parameters estimated from a config to the
+    /// verified one (e.g. `-0.01` when the estimate is 1% too low)
+    pub fn parameters_deviation(&self, config: &dyn ModelConfigTrait) -> f64 {
+        (config.num_parameters() - self.num_parameters) as f64 / self.num_parameters as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Dtype, MixtralModelConfig, MixtralParams, ModelLibraries, RopeParams};
+
+    #[test]
+    fn test_known_model_from_repo_id() {
+        let known_model = KnownModel::from_repo_id("meta-llama/Meta-Llama-3-8B").unwrap();
+        assert_eq!(known_model.repo_id(), "meta-llama/Meta-Llama-3-8B");
+        assert_eq!(known_model.num_parameters(), 8_030_261_248);
+        assert_eq!(known_model.max_context(), 8192);
+
+        assert_eq!(
+            KnownModel::from_repo_id("OpenAI/Whisper-Large-V3"),
+            KnownModel::from_repo_id("whisper-large-v3")
+        );
+        assert!(KnownModel::from_repo_id("whisper-large-v3").is_some());
+        assert!(KnownModel::from_repo_id("someone/whisper-large-v3").is_none());
+        assert!(KnownModel::from_repo_id("username/repo_name").is_none());
+    }
+
+    #[test]
+    fn test_known_models_are_unique() {
+        let known_models = KnownModel::all();
+        assert_eq!(known_models.len(), KNOWN_MODELS.len());
+        for known_model in known_models {
+            assert_eq!(
+                KnownModel::from_repo_id(known_model.repo_id()),
+                Some(known_model)
+            );
+            // Repository names are not ambiguous either
+            let name = known_model.repo_id().rsplit('/').next().unwrap();
+            assert_eq!(KnownModel::from_repo_id(name), Some(known_model));
+        }
+    }
+
+    #[test]
+    fn test_known_model_parameters_deviation() {
+        // The layer norms are not counted in the config-derived estimate
+        let mixtral_model_config = MixtralModelConfig::new(
+            MixtralParams::new(4096, 14336, 32768, 32, 32, Some(8), 8, 2),
+            32000,
+            false,
+            RopeParams::new(Some(1e6), None),
+            Dtype::BF16,
+            "mixtral".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        let known_model = KnownModel::from_repo_id("mistralai/Mixtral-8x7B-v0.1").unwrap();
+        let deviation = known_model.parameters_deviation(&mixtral_model_config);
+        assert!(deviation < 0.0 && deviation > -0.0001);
+    }
+}
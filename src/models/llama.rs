@@ -2,7 +2,7 @@
 use serde::Deserialize;
 use serde_json::Value;
 
-use crate::models::{ModelConfigTrait, ModelError, ModelLibraries};
+use crate::models::{Dtype, ModelConfigTrait, ModelError, ModelLibraries, RopeParams};
 
 /// A struct representing the Llama architecture parameters
 #[derive(Clone, Debug, Deserialize)]
@@ -17,6 +17,10 @@ pub struct LlamaParams {
     num_attention_heads: i32,
     /// Llama model num_hidden_layers
     num_hidden_layers: i32,
+    /// Llama model num_key_value_heads, lower than num_attention_heads with GQA (e.g. Llama 3)
+    num_key_value_heads: i32,
+    /// Llama model head_dim
+    head_dim: i32,
 }
 
 /// Llama model parameters implementation
@@ -28,13 +32,19 @@ impl LlamaParams {
         max_sequence_length: i32,
         num_attention_heads: i32,
         num_hidden_layers: i32,
+        num_key_value_heads: Option<i32>,
+        head_dim: Option<i32>,
     ) -> LlamaParams {
+        let num_key_value_heads = num_key_value_heads.unwrap_or(num_attention_heads);
+        let head_dim = head_dim.unwrap_or(hidden_size / num_attention_heads);
         LlamaParams {
             hidden_size,
             intermediate_size,
             max_sequence_length,
             num_attention_heads,
             num_hidden_layers,
+            num_key_value_heads,
+            head_dim,
         }
     }
     /// Build from a JSON value
@@ -49,10 +59,14 @@ impl LlamaParams {
             .ok_or(ModelError::MissingField("intermediate_size".to_string()))?
             as i32;
 
-        let max_sequence_length = value["max_sequence_length"]
+        // Original Llama checkpoints use `max_sequence_length`, transformers ones use
+        // `max_position_embeddings`
+        let max_sequence_length = value["max_position_embeddings"]
             .as_i64()
-            .ok_or(ModelError::MissingField("max_sequence_length".to_string()))?
-            as i32;
+            .or(value["max_sequence_length"].as_i64())
+            .ok_or(ModelError::MissingField(
+                "max_position_embeddings".to_string(),
+            ))? as i32;
 
         let num_attention_heads = value["num_attention_heads"]
             .as_i64()
@@ -64,12 +78,18 @@ impl LlamaParams {
             .ok_or(ModelError::MissingField("num_hidden_layers".to_string()))?
             as i32;
 
+        let num_key_value_heads = value["num_key_value_heads"].as_i64().map(|val| val as i32);
+
+        let head_dim = value["head_dim"].as_i64().map(|val| val as i32);
+
         Ok(LlamaParams::new(
             hidden_size,
             intermediate_size,
             max_sequence_length,
             num_attention_heads,
             num_hidden_layers,
+            num_key_value_heads,
+            head_dim,
         ))
     }
 }
@@ -79,6 +99,14 @@ impl LlamaParams {
 pub struct LlamaModelConfig {
     /// Llama model parameters
     params: LlamaParams,
+    /// Llama model vocab_size
+    vocab_size: i32,
+    /// Llama model tie_word_embeddings, whether the LM head shares the token embeddings weights
+    tie_word_embeddings: bool,
+    /// Llama model rotary position embeddings parameters
+    rope_params: RopeParams,
+    /// Llama model weights precision
+    dtype: Dtype,
     /// Llama model type
     model_type: String,
     /// Llama model available libraries
@@ -90,11 +118,19 @@ impl LlamaModelConfig {
     /// Build a new `LlamaModelConfig` struct based on the provided parameters
     pub fn new(
         params: LlamaParams,
+        vocab_size: i32,
+        tie_word_embeddings: bool,
+        rope_params: RopeParams,
+        dtype: Dtype,
         model_type: String,
         available_libraries: Vec<ModelLibraries>,
     ) -> LlamaModelConfig {
         LlamaModelConfig {
             params,
+            vocab_size,
+            tie_word_embeddings,
+            rope_params,
+            dtype,
             model_type,
             available_libraries,
         }
@@ -123,6 +159,30 @@ impl ModelConfigTrait for LlamaModelConfig {
         self.params.num_hidden_layers
     }
 
+    fn num_key_value_heads(&self) -> i32 {
+        self.params.num_key_value_heads
+    }
+
+    fn head_dim(&self) -> i32 {
+        self.params.head_dim
+    }
+
+    fn vocab_size(&self) -> i32 {
+        self.vocab_size
+    }
+
+    fn tie_word_embeddings(&self) -> bool {
+        self.tie_word_embeddings
+    }
+
+    fn rope_params(&self) -> Option<&RopeParams> {
+        Some(&self.rope_params)
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
     fn model_type(&self) -> &str {
         &self.model_type
     }
@@ -134,20 +194,30 @@ impl ModelConfigTrait for LlamaModelConfig {
     fn from_json(value: Value) -> Result<Self, ModelError> {
         let params = LlamaParams::from_json(value.clone())?;
 
+        let vocab_size = value["vocab_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("vocab_size".to_string()))?
+            as i32;
+
+        let tie_word_embeddings = value["tie_word_embeddings"].as_bool().unwrap_or(false);
+
+        let rope_params = RopeParams::from_json(&value)?;
+
+        let dtype = Dtype::from_json(&value);
+
         let model_type = match value["model_type"].as_str() {
             Some(model_type) => model_type.to_string(),
             None => return Err(ModelError::MissingField("model_type".to_string())),
         };
 
-        // TODO: Implement this
-        let available_libraries = vec![ModelLibraries::PyTorch];
-        // let available_libraries = match value["available_libraries"].as_array() {
-        //     Some(al) => al.iter().map(|v| ModelLibraries::from_str(v.as_str().unwrap()).unwrap()).collect(),
-        //     None => return Err(ModelError::MissingField("available_libraries".to_string())),
-        // };
+        let available_libraries = ModelLibraries::from_json(&value);
 
         Ok(LlamaModelConfig::new(
             params,
+            vocab_size,
+            tie_word_embeddings,
+            rope_params,
+            dtype,
             model_type,
             available_libraries,
         ))
@@ -159,6 +229,7 @@ mod tests {
     use std::vec;
 
     use super::*;
+    use serde_json::json;
 
     #[test]
     fn test_llama_model_params() {
@@ -168,6 +239,8 @@ mod tests {
             max_sequence_length: 1024,
             num_attention_heads: 12,
             num_hidden_layers: 12,
+            num_key_value_heads: 12,
+            head_dim: 64,
         };
 
         assert_eq!(llama_params.hidden_size, 768);
@@ -185,10 +258,16 @@ mod tests {
             max_sequence_length: 1024,
             num_attention_heads: 12,
             num_hidden_layers: 12,
+            num_key_value_heads: 12,
+            head_dim: 64,
         };
 
         let llama_model_config = LlamaModelConfig {
             params: llama_params,
+            vocab_size: 32000,
+            tie_word_embeddings: false,
+            rope_params: RopeParams::new(None, None),
+            dtype: Dtype::F32,
             model_type: "llama".to_string(),
             available_libraries: vec![ModelLibraries::PyTorch],
         };
@@ -213,10 +292,16 @@ mod tests {
             max_sequence_length: 1024,
             num_attention_heads: 12,
             num_hidden_layers: 12,
+            num_key_value_heads: 12,
+            head_dim: 64,
         };
 
         let llama_model_config = LlamaModelConfig {
             params: llama_params,
+            vocab_size: 32000,
+            tie_word_embeddings: false,
+            rope_params: RopeParams::new(None, None),
+            dtype: Dtype::F32,
             model_type: "llama".to_string(),
             available_libraries: vec![ModelLibraries::PyTorch],
         };
@@ -226,10 +311,29 @@ mod tests {
         assert_eq!(llama_model_config.max_position_embeddings(), 1024);
         assert_eq!(llama_model_config.num_attention_heads(), 12);
         assert_eq!(llama_model_config.num_hidden_layers(), 12);
+        assert_eq!(llama_model_config.head_dim(), 64);
+        assert_eq!(llama_model_config.kv_dim(), 768);
+        assert_eq!(llama_model_config.ffn_ratio(), 4.0);
         assert_eq!(llama_model_config.model_type(), "llama");
         assert_eq!(
             llama_model_config.available_libraries(),
             vec![ModelLibraries::PyTorch]
         );
+
+        // Llama 3 8B uses GQA, 8 key/value heads for 32 attention heads
+        let value = json!({
+            "model_type": "llama",
+            "hidden_size": 4096,
+            "intermediate_size": 14336,
+            "max_position_embeddings": 8192,
+            "num_attention_heads": 32,
+            "num_hidden_layers": 32,
+            "num_key_value_heads": 8,
+            "vocab_size": 128256
+        });
+        let llama_model_config = LlamaModelConfig::from_json(value).unwrap();
+        assert_eq!(llama_model_config.num_key_value_heads(), 8);
+        assert_eq!(llama_model_config.head_dim(), 128);
+        assert_eq!(llama_model_config.kv_dim(), 1024);
     }
 }
@@ -0,0 +1,277 @@
+//! Module for the LLaVA vision-language model
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::hub::ModelConfig;
+use crate::models::base::merge_with_defaults;
+use crate::models::clip::default_vision_config;
+use crate::models::{
+    Dtype, ModelConfigTrait, ModelError, ModelFamily, ModelLibraries, ParameterBreakdown,
+    ViTModelConfig,
+};
+
+/// Build the language model defaults of a LLaVA config (Llama 7B), used for the fields missing
+/// from `text_config`
+fn default_text_config() -> Value {
+    json!({
+        "model_type": "llama",
+        "hidden_size": 4096,
+        "intermediate_size": 11008,
+        "max_position_embeddings": 4096,
+        "num_attention_heads": 32,
+        "num_hidden_layers": 32,
+        "vocab_size": 32000
+    })
+}
+
+/// A struct representing a LLaVA model configuration, made of a vision tower, a projector and a
+/// language model
+#[derive(Clone, Debug, Deserialize)]
+pub struct LlavaModelConfig {
+    /// LLaVA vision tower configuration
+    vision_config: ViTModelConfig,
+    /// LLaVA language model configuration
+    text_config: Box<ModelConfig>,
+    /// LLaVA model weights precision
+    dtype: Dtype,
+    /// LLaVA model type
+    model_type: String,
+    /// LLaVA model available libraries
+    available_libraries: Vec<ModelLibraries>,
+}
+
+/// LLaVA model implementation
+impl LlavaModelConfig {
+    /// Build a new `LlavaModelConfig` struct based on the provided parameters
+    pub fn new(
+        vision_config: ViTModelConfig,
+        text_config: ModelConfig,
+        dtype: Dtype,
+        model_type: String,
+        available_libraries: Vec<ModelLibraries>,
+    ) -> LlavaModelConfig {
+        LlavaModelConfig {
+            vision_config,
+            text_config: Box::new(text_config),
+            dtype,
+            model_type,
+            available_libraries,
+        }
+    }
+    /// Returns the vision tower configuration
+    pub fn vision_config(&self) -> &ViTModelConfig {
+        &self.vision_config
+    }
+    /// Returns the language model configuration
+    pub fn text_config(&self) -> &ModelConfig {
+        &self.text_config
+    }
+    /// Returns the number of tokens an image takes in the language model context (one per patch,
+    /// the `[CLS]` token of the vision tower is dropped)
+    pub fn image_tokens(&self) -> i32 {
+        self.vision_config.num_patches()
+    }
+    /// Returns the number of parameters of the projector, a two layers MLP mapping the vision
+    /// features to the language model embeddings space
+    pub fn projector_parameters(&self) -> i64 {
+        let vision_hidden_size = self.vision_config.hidden_size() as i64;
+        let text_hidden_size = self.text_config.hidden_size() as i64;
+        vision_hidden_size * text_hidden_size + text_hidden_size * text_hidden_size
+    }
+}
+
+/// Implementation of the `ModelConfigTrait` trait for `LlavaModelConfig`.
+///
+/// The flat accessors describe the language model, use `vision_config` for the vision tower.
+impl ModelConfigTrait for LlavaModelConfig {
+    fn hidden_size(&self) -> i32 {
+        self.text_config.hidden_size()
+    }
+
+    fn intermediate_size(&self) -> i32 {
+        self.text_config.intermediate_size()
+    }
+
+    fn max_position_embeddings(&self) -> i32 {
+        self.text_config.max_position_embeddings()
+    }
+
+    fn num_attention_heads(&self) -> i32 {
+        self.text_config.num_attention_heads()
+    }
+
+    fn num_hidden_layers(&self) -> i32 {
+        self.text_config.num_hidden_layers()
+    }
+
+    fn model_family(&self) -> ModelFamily {
+        ModelFamily::Multimodal
+    }
+
+    fn vocab_size(&self) -> i32 {
+        self.text_config.vocab_size()
+    }
+
+    /// The vision tower, the projector and the language model are counted
+    fn parameter_breakdown(&self) -> ParameterBreakdown {
+        self.vision_config.parameter_breakdown()
+            + ParameterBreakdown::new(0, 0, 0, 0, self.projector_parameters(), 0)
+            + self.text_config.parameter_breakdown()
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn model_type(&self) -> &str {
+        &self.model_type
+    }
+
+    fn available_libraries(&self) -> &[ModelLibraries] {
+        &self.available_libraries
+    }
+
+    fn from_json(value: Value) -> Result<Self, ModelError> {
+        let mut vision_config = merge_with_defaults(
+            merge_with_defaults(
+                default_vision_config(),
+                &json!({"image_size": 336, "patch_size": 14}),
+            ),
+            &value["vision_config"],
+        );
+        vision_config["model_type"] = json!("clip_vision_model");
+        let vision_config = ViTModelConfig::from_json(vision_config)
+            .map_err(|err| err.in_sub_config("vision_config"))?;
+
+        // Only the fields that differ from the defaults are stored for Llama language models,
+        // other architectures are expected to be fully described
+        let text_config = match value["text_config"]["model_type"].as_str() {
+            None | Some("llama") => {
+                merge_with_defaults(default_text_config(), &value["text_config"])
+            }
+            Some(_) => value["text_config"].clone(),
+        };
+        let text_config =
+            ModelConfig::from_json(text_config).map_err(|err| err.in_sub_config("text_config"))?;
+
+        let dtype = Dtype::from_json(&value);
+
+        let model_type = match value["model_type"].as_str() {
+            Some(model_type) => model_type.to_string(),
+            None => return Err(ModelError::MissingField("model_type".to_string())),
+        };
+
+        let available_libraries = ModelLibraries::from_json(&value);
+
+        Ok(LlavaModelConfig::new(
+            vision_config,
+            text_config,
+            dtype,
+            model_type,
+            available_libraries,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{LlamaModelConfig, ViTParams};
+
+    fn create_llava_config() -> Value {
+        json!({
+            "architectures": ["LlavaForConditionalGeneration"],
+            "image_token_index": 32000,
+            "model_type": "llava",
+            "projector_hidden_act": "gelu",
+            "text_config": {
+                "architectures": ["LlamaForCausalLM"],
+                "max_position_embeddings": 4096,
+                "model_type": "llama",
+                "rms_norm_eps": 1e-05,
+                "torch_dtype": "float16",
+                "vocab_size": 32064
+            },
+            "vision_config": {
+                "hidden_size": 1024,
+                "image_size": 336,
+                "intermediate_size": 4096,
+                "model_type": "clip_vision_model",
+                "num_attention_heads": 16,
+                "num_hidden_layers": 24,
+                "patch_size": 14,
+                "projection_dim": 768
+            },
+            "vision_feature_layer": -2,
+            "vision_feature_select_strategy": "default"
+        })
+    }
+
+    #[test]
+    fn test_llava_model_config() {
+        let vision_config = ViTModelConfig::new(
+            ViTParams::new(1024, 4096, 16, 24, 336, 14, None),
+            Dtype::F32,
+            "clip_vision_model".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        let text_config = ModelConfig::Llama(
+            LlamaModelConfig::from_json(json!({
+                "model_type": "llama",
+                "hidden_size": 5120,
+                "intermediate_size": 13824,
+                "max_position_embeddings": 4096,
+                "num_attention_heads": 40,
+                "num_hidden_layers": 40,
+                "vocab_size": 32000
+            }))
+            .unwrap(),
+        );
+        let llava_model_config = LlavaModelConfig::new(
+            vision_config,
+            text_config,
+            Dtype::F32,
+            "llava".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        assert_eq!(llava_model_config.vision_config.hidden_size(), 1024);
+        assert_eq!(llava_model_config.text_config.hidden_size(), 5120);
+        assert_eq!(
+            llava_model_config.projector_parameters(),
+            1024 * 5120 + 5120 * 5120
+        );
+        assert_eq!(llava_model_config.model_type, "llava");
+        assert_eq!(
+            llava_model_config.available_libraries,
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+
+    #[test]
+    fn test_llava_model_trait_implementation() {
+        let llava_model_config = LlavaModelConfig::from_json(create_llava_config()).unwrap();
+        assert_eq!(llava_model_config.hidden_size(), 4096);
+        assert_eq!(llava_model_config.intermediate_size(), 11008);
+        assert_eq!(llava_model_config.max_position_embeddings(), 4096);
+        assert_eq!(llava_model_config.num_attention_heads(), 32);
+        assert_eq!(llava_model_config.num_hidden_layers(), 32);
+        assert_eq!(llava_model_config.text_config().model_type(), "llama");
+        assert_eq!(llava_model_config.vision_config().num_hidden_layers(), 24);
+        assert_eq!(llava_model_config.image_tokens(), 576);
+        assert_eq!(
+            llava_model_config.num_parameters(),
+            llava_model_config.vision_config().num_parameters()
+                + llava_model_config.projector_parameters()
+                + llava_model_config.text_config().num_parameters()
+        );
+        assert_eq!(llava_model_config.model_type(), "llava");
+        assert_eq!(
+            llava_model_config.available_libraries(),
+            vec![ModelLibraries::PyTorch]
+        );
+
+        let mut value = create_llava_config();
+        value["text_config"] = json!({"model_type": "mistral"});
+        assert!(LlavaModelConfig::from_json(value).is_err());
+    }
+}
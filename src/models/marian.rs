@@ -0,0 +1,212 @@
+//! Module for the Marian model
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::models::{BartParams, Dtype, ModelConfigTrait, ModelError, ModelLibraries};
+
+/// A struct representing a Marian model configuration.
+///
+/// Marian shares the BART encoder-decoder layout, so its parameters are parsed as `BartParams`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct MarianModelConfig {
+    /// Marian model parameters
+    params: BartParams,
+    /// Marian model source (encoder) vocab_size
+    vocab_size: i32,
+    /// Marian model tie_word_embeddings, whether the LM head shares the token embeddings weights
+    tie_word_embeddings: bool,
+    /// Marian model target (decoder) vocab_size, which defaults to `vocab_size`
+    decoder_vocab_size: i32,
+    /// Whether the encoder and the decoder share the same embedding matrix
+    share_encoder_decoder_embeddings: bool,
+    /// Marian model weights precision
+    dtype: Dtype,
+    /// Marian model type
+    model_type: String,
+    /// Marian model available libraries
+    available_libraries: Vec<ModelLibraries>,
+}
+
+/// Marian model implementation
+impl MarianModelConfig {
+    /// Build a new `MarianModelConfig` struct based on the provided parameters
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        params: BartParams,
+        vocab_size: i32,
+        tie_word_embeddings: bool,
+        decoder_vocab_size: Option<i32>,
+        share_encoder_decoder_embeddings: Option<bool>,
+        dtype: Dtype,
+        model_type: String,
+        available_libraries: Vec<ModelLibraries>,
+    ) -> MarianModelConfig {
+        let decoder_vocab_size = decoder_vocab_size.unwrap_or(vocab_size);
+        let share_encoder_decoder_embeddings = share_encoder_decoder_embeddings.unwrap_or(true);
+        MarianModelConfig {
+            params,
+            vocab_size,
+            tie_word_embeddings,
+            decoder_vocab_size,
+            share_encoder_decoder_embeddings,
+            dtype,
+            model_type,
+            available_libraries,
+        }
+    }
+    /// Returns the size of the target (decoder) vocabulary
+    pub fn decoder_vocab_size(&self) -> i32 {
+        self.decoder_vocab_size
+    }
+    /// Returns whether the encoder and the decoder share the same embedding matrix
+    pub fn share_encoder_decoder_embeddings(&self) -> bool {
+        self.share_encoder_decoder_embeddings
+    }
+}
+
+/// Implementation of the `ModelConfigTrait` trait for `MarianModelConfig`
+impl ModelConfigTrait for MarianModelConfig {
+    fn hidden_size(&self) -> i32 {
+        self.params.d_model()
+    }
+
+    fn intermediate_size(&self) -> i32 {
+        self.params.encoder_ffn_dim()
+    }
+
+    fn max_position_embeddings(&self) -> i32 {
+        self.params.max_position_embeddings()
+    }
+
+    fn num_attention_heads(&self) -> i32 {
+        self.params.encoder_attention_heads()
+    }
+
+    fn num_hidden_layers(&self) -> i32 {
+        self.params.encoder_layers() + self.params.decoder_layers()
+    }
+
+    fn is_encoder_decoder(&self) -> bool {
+        true
+    }
+
+    fn num_encoder_layers(&self) -> i32 {
+        self.params.encoder_layers()
+    }
+
+    fn vocab_size(&self) -> i32 {
+        self.vocab_size
+    }
+
+    fn tie_word_embeddings(&self) -> bool {
+        self.tie_word_embeddings
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn model_type(&self) -> &str {
+        &self.model_type
+    }
+
+    fn available_libraries(&self) -> &[ModelLibraries] {
+        &self.available_libraries
+    }
+
+    fn from_json(value: Value) -> Result<Self, ModelError> {
+        let params = BartParams::from_json(value.clone())?;
+
+        let vocab_size = value["vocab_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("vocab_size".to_string()))?
+            as i32;
+
+        let tie_word_embeddings = value["tie_word_embeddings"].as_bool().unwrap_or(true);
+
+        let decoder_vocab_size = value["decoder_vocab_size"].as_i64().map(|val| val as i32);
+
+        let share_encoder_decoder_embeddings = value["share_encoder_decoder_embeddings"].as_bool();
+
+        let dtype = Dtype::from_json(&value);
+
+        let model_type = match value["model_type"].as_str() {
+            Some(model_type) => model_type.to_string(),
+            None => return Err(ModelError::MissingField("model_type".to_string())),
+        };
+
+        let available_libraries = ModelLibraries::from_json(&value);
+
+        Ok(MarianModelConfig::new(
+            params,
+            vocab_size,
+            tie_word_embeddings,
+            decoder_vocab_size,
+            share_encoder_decoder_embeddings,
+            dtype,
+            model_type,
+            available_libraries,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_marian_model_config() {
+        let marian_params = BartParams::new(512, 6, 6, 2048, 2048, 8, 512);
+        let marian_model_config = MarianModelConfig::new(
+            marian_params,
+            58101,
+            true,
+            None,
+            None,
+            Dtype::F32,
+            "marian".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        assert_eq!(marian_model_config.vocab_size, 58101);
+        assert_eq!(marian_model_config.decoder_vocab_size, 58101);
+        assert!(marian_model_config.share_encoder_decoder_embeddings);
+        assert_eq!(marian_model_config.model_type, "marian");
+        assert_eq!(
+            marian_model_config.available_libraries,
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+
+    #[test]
+    fn test_marian_model_trait_implementation() {
+        let value = json!({
+            "model_type": "marian",
+            "d_model": 512,
+            "encoder_layers": 6,
+            "decoder_layers": 6,
+            "encoder_ffn_dim": 2048,
+            "decoder_ffn_dim": 2048,
+            "encoder_attention_heads": 8,
+            "decoder_attention_heads": 8,
+            "max_position_embeddings": 512,
+            "vocab_size": 65001,
+            "decoder_vocab_size": 32001,
+            "share_encoder_decoder_embeddings": false
+        });
+        let marian_model_config = MarianModelConfig::from_json(value).unwrap();
+        assert_eq!(marian_model_config.hidden_size(), 512);
+        assert_eq!(marian_model_config.intermediate_size(), 2048);
+        assert_eq!(marian_model_config.max_position_embeddings(), 512);
+        assert_eq!(marian_model_config.num_attention_heads(), 8);
+        assert_eq!(marian_model_config.num_hidden_layers(), 12);
+        assert_eq!(marian_model_config.vocab_size(), 65001);
+        assert_eq!(marian_model_config.decoder_vocab_size(), 32001);
+        assert!(!marian_model_config.share_encoder_decoder_embeddings());
+        assert_eq!(marian_model_config.model_type(), "marian");
+        assert_eq!(
+            marian_model_config.available_libraries(),
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+}
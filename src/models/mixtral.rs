@@ -0,0 +1,343 @@
+//! Module for the Mixtral sparse Mixture-of-Experts model
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::models::{
+    Dtype, ModelConfigTrait, ModelError, ModelLibraries, ParameterBreakdown, RopeParams,
+};
+
+/// A struct representing the Mixtral architecture parameters
+#[derive(Clone, Debug, Deserialize)]
+pub struct MixtralParams {
+    /// Mixtral model hidden_size
+    hidden_size: i32,
+    /// Mixtral model intermediate_size, of every expert
+    intermediate_size: i32,
+    /// Mixtral model max_position_embeddings
+    max_position_embeddings: i32,
+    /// Mixtral model num_attention_heads
+    num_attention_heads: i32,
+    /// Mixtral model num_hidden_layers
+    num_hidden_layers: i32,
+    /// Mixtral model num_key_value_heads
+    num_key_value_heads: i32,
+    /// Mixtral model num_local_experts, the number of experts of every layer
+    num_local_experts: i32,
+    /// Mixtral model num_experts_per_tok, the number of experts activated for each token
+    num_experts_per_tok: i32,
+}
+
+/// Mixtral model parameters implementation
+impl MixtralParams {
+    /// Build a new `MixtralParams` struct based on the provided parameters
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        hidden_size: i32,
+        intermediate_size: i32,
+        max_position_embeddings: i32,
+        num_attention_heads: i32,
+        num_hidden_layers: i32,
+        num_key_value_heads: Option<i32>,
+        num_local_experts: i32,
+        num_experts_per_tok: i32,
+    ) -> MixtralParams {
+        let num_key_value_heads = num_key_value_heads.unwrap_or(num_attention_heads);
+        MixtralParams {
+            hidden_size,
+            intermediate_size,
+            max_position_embeddings,
+            num_attention_heads,
+            num_hidden_layers,
+            num_key_value_heads,
+            num_local_experts,
+            num_experts_per_tok,
+        }
+    }
+    /// Returns the number of experts of every layer
+    pub fn num_local_experts(&self) -> i32 {
+        self.num_local_experts
+    }
+    /// Returns the number of experts activated for each token
+    pub fn num_experts_per_tok(&self) -> i32 {
+        self.num_experts_per_tok
+    }
+    /// Build from a JSON value
+    pub fn from_json(value: Value) -> Result<MixtralParams, ModelError> {
+        let hidden_size = value["hidden_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("hidden_size".to_string()))?
+            as i32;
+
+        let intermediate_size = value["intermediate_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("intermediate_size".to_string()))?
+            as i32;
+
+        let max_position_embeddings =
+            value["max_position_embeddings"]
+                .as_i64()
+                .ok_or(ModelError::MissingField(
+                    "max_position_embeddings".to_string(),
+                ))? as i32;
+
+        let num_attention_heads = value["num_attention_heads"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("num_attention_heads".to_string()))?
+            as i32;
+
+        let num_hidden_layers = value["num_hidden_layers"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("num_hidden_layers".to_string()))?
+            as i32;
+
+        let num_key_value_heads = value["num_key_value_heads"].as_i64().map(|val| val as i32);
+
+        let num_local_experts = value["num_local_experts"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("num_local_experts".to_string()))?
+            as i32;
+
+        let num_experts_per_tok = value["num_experts_per_tok"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("num_experts_per_tok".to_string()))?
+            as i32;
+
+        Ok(MixtralParams::new(
+            hidden_size,
+            intermediate_size,
+            max_position_embeddings,
+            num_attention_heads,
+            num_hidden_layers,
+            num_key_value_heads,
+            num_local_experts,
+            num_experts_per_tok,
+        ))
+    }
+}
+
+/// A struct representing a Mixtral model configuration
+#[derive(Clone, Debug, Deserialize)]
+pub struct MixtralModelConfig {
+    /// Mixtral model parameters
+    params: MixtralParams,
+    /// Mixtral model vocab_size
+    vocab_size: i32,
+    /// Mixtral model tie_word_embeddings, whether the LM head shares the token embeddings weights
+    tie_word_embeddings: bool,
+    /// Mixtral model rotary position embeddings parameters
+    rope_params: RopeParams,
+    /// Mixtral model weights precision
+    dtype: Dtype,
+    /// Mixtral model type
+    model_type: String,
+    /// Mixtral model available libraries
+    available_libraries: Vec<ModelLibraries>,
+}
+
+/// Mixtral model implementation
+impl MixtralModelConfig {
+    /// Build a new `MixtralModelConfig` struct based on the provided parameters
+    pub fn new(
+        params: MixtralParams,
+        vocab_size: i32,
+        tie_word_embeddings: bool,
+        rope_params: RopeParams,
+        dtype: Dtype,
+        model_type: String,
+        available_libraries: Vec<ModelLibraries>,
+    ) -> MixtralModelConfig {
+        MixtralModelConfig {
+            params,
+            vocab_size,
+            tie_word_embeddings,
+            rope_params,
+            dtype,
+            model_type,
+            available_libraries,
+        }
+    }
+    /// Returns the feed-forward parameters of a layer with the given number of experts, the
+    /// experts MLPs are gated (SwiGLU) and the router scores all the experts
+    fn mlp_parameters(&self, num_experts: i32) -> i64 {
+        let hidden_size = self.params.hidden_size as i64;
+        let expert = 3 * hidden_size * self.params.intermediate_size as i64;
+        let router = hidden_size * self.params.num_local_experts as i64;
+        num_experts as i64 * expert + router
+    }
+}
+
+/// Implementation of the `ModelConfigTrait` trait for `MixtralModelConfig`
+impl ModelConfigTrait for MixtralModelConfig {
+    fn hidden_size(&self) -> i32 {
+        self.params.hidden_size
+    }
+
+    fn intermediate_size(&self) -> i32 {
+        self.params.intermediate_size
+    }
+
+    fn max_position_embeddings(&self) -> i32 {
+        self.params.max_position_embeddings
+    }
+
+    fn num_attention_heads(&self) -> i32 {
+        self.params.num_attention_heads
+    }
+
+    fn num_hidden_layers(&self) -> i32 {
+        self.params.num_hidden_layers
+    }
+
+    fn num_key_value_heads(&self) -> i32 {
+        self.params.num_key_value_heads
+    }
+
+    fn vocab_size(&self) -> i32 {
+        self.vocab_size
+    }
+
+    fn tie_word_embeddings(&self) -> bool {
+        self.tie_word_embeddings
+    }
+
+    /// All the experts are counted and positions are encoded with rotary embeddings
+    fn parameter_breakdown(&self) -> ParameterBreakdown {
+        let hidden_size = self.params.hidden_size as i64;
+        let num_layers = self.params.num_hidden_layers as i64;
+        let attention = 2 * hidden_size * hidden_size + 2 * hidden_size * self.kv_dim() as i64;
+        ParameterBreakdown::new(
+            self.vocab_size as i64 * hidden_size,
+            attention * num_layers,
+            self.mlp_parameters(self.params.num_local_experts) * num_layers,
+            self.lm_head_parameters(),
+            0,
+            self.params.num_hidden_layers,
+        )
+    }
+
+    /// Only `num_experts_per_tok` experts of every layer run for each token
+    fn active_parameters_per_token(&self) -> i64 {
+        let inactive_parameters = self.mlp_parameters(self.params.num_local_experts)
+            - self.mlp_parameters(self.params.num_experts_per_tok);
+        self.total_parameters() - inactive_parameters * self.params.num_hidden_layers as i64
+    }
+
+    fn rope_params(&self) -> Option<&RopeParams> {
+        Some(&self.rope_params)
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn model_type(&self) -> &str {
+        &self.model_type
+    }
+
+    fn available_libraries(&self) -> &[ModelLibraries] {
+        &self.available_libraries
+    }
+
+    fn from_json(value: Value) -> Result<Self, ModelError> {
+        let params = MixtralParams::from_json(value.clone())?;
+
+        let vocab_size = value["vocab_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("vocab_size".to_string()))?
+            as i32;
+
+        let tie_word_embeddings = value["tie_word_embeddings"].as_bool().unwrap_or(false);
+
+        let rope_params = RopeParams::from_json(&value)?;
+
+        let dtype = Dtype::from_json(&value);
+
+        let model_type = match value["model_type"].as_str() {
+            Some(model_type) => model_type.to_string(),
+            None => return Err(ModelError::MissingField("model_type".to_string())),
+        };
+
+        let available_libraries = ModelLibraries::from_json(&value);
+
+        Ok(MixtralModelConfig::new(
+            params,
+            vocab_size,
+            tie_word_embeddings,
+            rope_params,
+            dtype,
+            model_type,
+            available_libraries,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn create_mixtral_config() -> Value {
+        json!({
+            "architectures": ["MixtralForCausalLM"],
+            "hidden_size": 4096,
+            "intermediate_size": 14336,
+            "max_position_embeddings": 32768,
+            "model_type": "mixtral",
+            "num_attention_heads": 32,
+            "num_experts_per_tok": 2,
+            "num_hidden_layers": 32,
+            "num_key_value_heads": 8,
+            "num_local_experts": 8,
+            "rope_theta": 1e6,
+            "sliding_window": null,
+            "tie_word_embeddings": false,
+            "torch_dtype": "bfloat16",
+            "vocab_size": 32000
+        })
+    }
+
+    #[test]
+    fn test_mixtral_model_params() {
+        let mixtral_params = MixtralParams::new(4096, 14336, 32768, 32, 32, None, 8, 2);
+        assert_eq!(mixtral_params.hidden_size, 4096);
+        assert_eq!(mixtral_params.num_key_value_heads, 32);
+        assert_eq!(mixtral_params.num_local_experts(), 8);
+        assert_eq!(mixtral_params.num_experts_per_tok(), 2);
+    }
+
+    #[test]
+    fn test_mixtral_model_trait_implementation() {
+        let mixtral_model_config = MixtralModelConfig::from_json(create_mixtral_config()).unwrap();
+        assert_eq!(mixtral_model_config.hidden_size(), 4096);
+        assert_eq!(mixtral_model_config.intermediate_size(), 14336);
+        assert_eq!(mixtral_model_config.max_position_embeddings(), 32768);
+        assert_eq!(mixtral_model_config.num_attention_heads(), 32);
+        assert_eq!(mixtral_model_config.num_hidden_layers(), 32);
+        assert_eq!(mixtral_model_config.num_key_value_heads(), 8);
+        assert_eq!(mixtral_model_config.kv_dim(), 1024);
+        assert!(!mixtral_model_config.tie_word_embeddings());
+        assert_eq!(mixtral_model_config.rope_params().unwrap().theta(), 1e6);
+        assert_eq!(mixtral_model_config.dtype(), Dtype::BF16);
+        assert_eq!(mixtral_model_config.model_type(), "mixtral");
+        assert_eq!(
+            mixtral_model_config.available_libraries(),
+            vec![ModelLibraries::PyTorch]
+        );
+
+        let mut value = create_mixtral_config();
+        value.as_object_mut().unwrap().remove("num_local_experts");
+        assert!(MixtralModelConfig::from_json(value).is_err());
+    }
+
+    #[test]
+    fn test_mixtral_num_parameters() {
+        // Mixtral 8x7B, 46.7B parameters of which 12.9B are active for each token
+        let mixtral_model_config = MixtralModelConfig::from_json(create_mixtral_config()).unwrap();
+        assert_eq!(mixtral_model_config.num_parameters(), 46_702_526_464);
+        assert_eq!(mixtral_model_config.total_parameters(), 46_702_526_464);
+        assert_eq!(
+            mixtral_model_config.active_parameters_per_token(),
+            12_879_659_008
+        );
+    }
+}
@@ -2,13 +2,40 @@
 
 // Base utilities for models
 mod base;
-pub use base::{ModelConfigTrait, ModelError, ModelLibraries};
+pub use base::{
+    CompositeModelConfig, Dtype, ModelConfigTrait, ModelError, ModelLibraries, ParameterBreakdown,
+    Precision, QuantType, SUB_CONFIG_KEYS,
+};
+// BART model
+pub mod bart;
+pub use bart::{BartModelConfig, BartParams};
 // Bert model
 pub mod bert;
 pub use bert::{BertModelConfig, BertParams};
 // BLOOM model
 pub mod bloom;
 pub use bloom::{BloomModelConfig, BloomParams};
+// CLIP model
+pub mod clip;
+pub use clip::CLIPModelConfig;
+// CodeGen model
+pub mod codegen;
+pub use codegen::{CodeGenModelConfig, CodeGenParams};
+// DeepSeek models
+pub mod deepseek;
+pub use deepseek::{DeepSeekMLAParams, DeepSeekMoEParams, DeepSeekModelConfig, DeepSeekParams};
+// DETR model
+pub mod detr;
+pub use detr::{DetrModelConfig, DetrParams};
+// Diffusion pipelines components
+pub mod diffusion;
+pub use diffusion::{DiffusionPipelineConfig, TextEncoderConfig, UNetConfig, VAEConfig};
+// Model families
+pub mod family;
+pub use family::ModelFamily;
+// Generic fallback model
+pub mod generic;
+pub use generic::{GenericModelConfig, GenericParams};
 // GPT2 model
 pub mod gpt2;
 pub use gpt2::{GPT2ModelConfig, GPT2Params};
@@ -18,12 +45,57 @@ pub use gptj::{GPTJModelConfig, GPTJParams};
 // GPT-Neo model
 pub mod gptneo;
 pub use gptneo::{GPTNeoModelConfig, GPTNeoParams};
+// InternLM model
+pub mod internlm;
+pub use internlm::{InternLMModelConfig, InternLMParams};
+// Quantized inference kernels
+pub mod kernels;
+pub use kernels::QuantKernel;
+// Well-known models database
+pub mod known;
+pub use known::KnownModel;
 // Llama model
 pub mod llama;
 pub use llama::{LlamaModelConfig, LlamaParams};
+// LLaVA model
+pub mod llava;
+pub use llava::LlavaModelConfig;
+// Marian model
+pub mod marian;
+pub use marian::MarianModelConfig;
+// Mixtral model
+pub mod mixtral;
+pub use mixtral::{MixtralModelConfig, MixtralParams};
 // OPT model
 pub mod opt;
 pub use opt::{OPTModelConfig, OPTParams};
+// Pegasus model
+pub mod pegasus;
+pub use pegasus::PegasusModelConfig;
+// Rotary position embeddings
+pub mod rope;
+pub use rope::{RopeParams, RopeScaling, RopeScalingType};
+// RoBERTa model
+pub mod roberta;
+pub use roberta::{RobertaModelConfig, RobertaParams};
+// SegFormer model
+pub mod segformer;
+pub use segformer::{SegformerModelConfig, SegformerParams};
+// Swin Transformer model
+pub mod swin;
+pub use swin::{SwinModelConfig, SwinParams};
 // T5 model
 pub mod t5;
 pub use t5::{T5ModelConfig, T5Params};
+// TrOCR model
+pub mod trocr;
+pub use trocr::{TrOCRModelConfig, TrOCRParams};
+// Vision encoder-decoder models
+pub mod vision_encoder_decoder;
+pub use vision_encoder_decoder::VisionEncoderDecoderModelConfig;
+// ViT model
+pub mod vit;
+pub use vit::{ViTModelConfig, ViTParams};
+// Whisper model
+pub mod whisper;
+pub use whisper::WhisperModelConfig;
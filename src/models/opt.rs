@@ -2,7 +2,7 @@
 use serde::Deserialize;
 use serde_json::Value;
 
-use crate::models::{ModelConfigTrait, ModelError, ModelLibraries};
+use crate::models::{Dtype, ModelConfigTrait, ModelError, ModelLibraries};
 
 /// A struct representing the OPT architecture parameters
 #[derive(Clone, Debug, Deserialize)]
@@ -80,6 +80,12 @@ impl OPTParams {
 pub struct OPTModelConfig {
     /// OPT model parameters
     params: OPTParams,
+    /// OPT model vocab_size
+    vocab_size: i32,
+    /// OPT model tie_word_embeddings, whether the LM head shares the token embeddings weights
+    tie_word_embeddings: bool,
+    /// OPT model weights precision
+    dtype: Dtype,
     /// OPT model type
     model_type: String,
     /// OPT model available libraries
@@ -91,11 +97,17 @@ impl OPTModelConfig {
     /// Build a new `OPTModelConfig` struct based on the provided parameters
     pub fn new(
         params: OPTParams,
+        vocab_size: i32,
+        tie_word_embeddings: bool,
+        dtype: Dtype,
         model_type: String,
         available_libraries: Vec<ModelLibraries>,
     ) -> OPTModelConfig {
         OPTModelConfig {
             params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
             model_type,
             available_libraries,
         }
@@ -124,6 +136,18 @@ impl ModelConfigTrait for OPTModelConfig {
         self.params.num_hidden_layers
     }
 
+    fn vocab_size(&self) -> i32 {
+        self.vocab_size
+    }
+
+    fn tie_word_embeddings(&self) -> bool {
+        self.tie_word_embeddings
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
     fn model_type(&self) -> &str {
         &self.model_type
     }
@@ -138,19 +162,30 @@ impl ModelConfigTrait for OPTModelConfig {
     {
         let params = OPTParams::from_json(value.clone())?;
 
+        let vocab_size = value["vocab_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("vocab_size".to_string()))?
+            as i32;
+
+        let tie_word_embeddings = value["tie_word_embeddings"].as_bool().unwrap_or(true);
+
+        let dtype = Dtype::from_json(&value);
+
         let model_type = match value["model_type"].as_str() {
             Some(model_type) => model_type.to_string(),
             None => return Err(ModelError::MissingField("model_type".to_string())),
         };
 
-        // TODO: Implement this
-        let available_libraries = vec![ModelLibraries::PyTorch];
-        // let available_libraries = match value["available_libraries"].as_array() {
-        //     Some(al) => al.iter().map(|v| ModelLibraries::from_str(v.as_str().unwrap()).unwrap()).collect(),
-        //     None => return Err(ModelError::MissingField("available_libraries".to_string())),
-        // };
+        let available_libraries = ModelLibraries::from_json(&value);
 
-        Ok(OPTModelConfig::new(params, model_type, available_libraries))
+        Ok(OPTModelConfig::new(
+            params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
+            model_type,
+            available_libraries,
+        ))
     }
 }
 
@@ -175,6 +210,9 @@ mod tests {
 
         let opt_model_config = OPTModelConfig::new(
             opt_params,
+            50272,
+            true,
+            Dtype::F32,
             String::from("opt"),
             vec![ModelLibraries::TensorFlow, ModelLibraries::PyTorch],
         );
@@ -197,6 +235,9 @@ mod tests {
 
         let opt_model_config = OPTModelConfig::new(
             opt_params,
+            50272,
+            true,
+            Dtype::F32,
             String::from("opt"),
             vec![ModelLibraries::TensorFlow, ModelLibraries::PyTorch],
         );
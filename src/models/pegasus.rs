@@ -0,0 +1,179 @@
+//! Module for the Pegasus model
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::models::{BartParams, Dtype, ModelConfigTrait, ModelError, ModelLibraries};
+
+/// A struct representing a Pegasus model configuration.
+///
+/// Pegasus shares the BART encoder-decoder layout, so its parameters are parsed as `BartParams`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct PegasusModelConfig {
+    /// Pegasus model parameters
+    params: BartParams,
+    /// Pegasus model vocab_size, shared by the encoder, the decoder and the LM head
+    vocab_size: i32,
+    /// Pegasus model tie_word_embeddings, whether the LM head shares the token embeddings weights
+    tie_word_embeddings: bool,
+    /// Pegasus model weights precision
+    dtype: Dtype,
+    /// Pegasus model type
+    model_type: String,
+    /// Pegasus model available libraries
+    available_libraries: Vec<ModelLibraries>,
+}
+
+/// Pegasus model implementation
+impl PegasusModelConfig {
+    /// Build a new `PegasusModelConfig` struct based on the provided parameters
+    pub fn new(
+        params: BartParams,
+        vocab_size: i32,
+        tie_word_embeddings: bool,
+        dtype: Dtype,
+        model_type: String,
+        available_libraries: Vec<ModelLibraries>,
+    ) -> PegasusModelConfig {
+        PegasusModelConfig {
+            params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
+            model_type,
+            available_libraries,
+        }
+    }
+}
+
+/// Implementation of the `ModelConfigTrait` trait for `PegasusModelConfig`
+impl ModelConfigTrait for PegasusModelConfig {
+    fn hidden_size(&self) -> i32 {
+        self.params.d_model()
+    }
+
+    fn intermediate_size(&self) -> i32 {
+        self.params.encoder_ffn_dim()
+    }
+
+    fn max_position_embeddings(&self) -> i32 {
+        self.params.max_position_embeddings()
+    }
+
+    fn num_attention_heads(&self) -> i32 {
+        self.params.encoder_attention_heads()
+    }
+
+    fn num_hidden_layers(&self) -> i32 {
+        self.params.encoder_layers() + self.params.decoder_layers()
+    }
+
+    fn is_encoder_decoder(&self) -> bool {
+        true
+    }
+
+    fn num_encoder_layers(&self) -> i32 {
+        self.params.encoder_layers()
+    }
+
+    fn vocab_size(&self) -> i32 {
+        self.vocab_size
+    }
+
+    fn tie_word_embeddings(&self) -> bool {
+        self.tie_word_embeddings
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn model_type(&self) -> &str {
+        &self.model_type
+    }
+
+    fn available_libraries(&self) -> &[ModelLibraries] {
+        &self.available_libraries
+    }
+
+    fn from_json(value: Value) -> Result<Self, ModelError> {
+        let params = BartParams::from_json(value.clone())?;
+
+        let vocab_size = value["vocab_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("vocab_size".to_string()))?
+            as i32;
+
+        let tie_word_embeddings = value["tie_word_embeddings"].as_bool().unwrap_or(true);
+
+        let dtype = Dtype::from_json(&value);
+
+        let model_type = match value["model_type"].as_str() {
+            Some(model_type) => model_type.to_string(),
+            None => return Err(ModelError::MissingField("model_type".to_string())),
+        };
+
+        let available_libraries = ModelLibraries::from_json(&value);
+
+        Ok(PegasusModelConfig::new(
+            params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
+            model_type,
+            available_libraries,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_pegasus_model_config() {
+        let pegasus_params = BartParams::new(1024, 16, 16, 4096, 4096, 16, 1024);
+        let pegasus_model_config = PegasusModelConfig::new(
+            pegasus_params,
+            96103,
+            true,
+            Dtype::F32,
+            "pegasus".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        assert_eq!(pegasus_model_config.vocab_size, 96103);
+        assert_eq!(pegasus_model_config.model_type, "pegasus");
+        assert_eq!(
+            pegasus_model_config.available_libraries,
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+
+    #[test]
+    fn test_pegasus_model_trait_implementation() {
+        let value = json!({
+            "model_type": "pegasus",
+            "d_model": 1024,
+            "encoder_layers": 16,
+            "decoder_layers": 16,
+            "encoder_ffn_dim": 4096,
+            "decoder_ffn_dim": 4096,
+            "encoder_attention_heads": 16,
+            "decoder_attention_heads": 16,
+            "max_position_embeddings": 1024,
+            "vocab_size": 96103
+        });
+        let pegasus_model_config = PegasusModelConfig::from_json(value).unwrap();
+        assert_eq!(pegasus_model_config.hidden_size(), 1024);
+        assert_eq!(pegasus_model_config.intermediate_size(), 4096);
+        assert_eq!(pegasus_model_config.max_position_embeddings(), 1024);
+        assert_eq!(pegasus_model_config.num_attention_heads(), 16);
+        assert_eq!(pegasus_model_config.num_hidden_layers(), 32);
+        assert_eq!(pegasus_model_config.vocab_size(), 96103);
+        assert_eq!(pegasus_model_config.model_type(), "pegasus");
+        assert_eq!(
+            pegasus_model_config.available_libraries(),
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! Module for the RoBERTa model (also used for XLM-RoBERTa)
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::models::{Dtype, ModelConfigTrait, ModelError, ModelFamily, ModelLibraries};
+
+/// A struct representing the RoBERTa architecture parameters
+#[derive(Clone, Debug, Deserialize)]
+pub struct RobertaParams {
+    /// RoBERTa model hidden_size
+    hidden_size: i32,
+    /// RoBERTa model intermediate_size
+    intermediate_size: i32,
+    /// RoBERTa model max_position_embeddings
+    max_position_embeddings: i32,
+    /// RoBERTa model num_attention_heads
+    num_attention_heads: i32,
+    /// RoBERTa model num_hidden_layers
+    num_hidden_layers: i32,
+    /// RoBERTa model pad_token_id, positions start right after it
+    pad_token_id: i32,
+}
+
+/// RoBERTa model parameters implementation
+impl RobertaParams {
+    /// Build a new `RobertaParams` struct based on the provided parameters
+    pub fn new(
+        hidden_size: i32,
+        intermediate_size: i32,
+        max_position_embeddings: i32,
+        num_attention_heads: i32,
+        num_hidden_layers: i32,
+        pad_token_id: Option<i32>,
+    ) -> RobertaParams {
+        let pad_token_id = pad_token_id.unwrap_or(1);
+        RobertaParams {
+            hidden_size,
+            intermediate_size,
+            max_position_embeddings,
+            num_attention_heads,
+            num_hidden_layers,
+            pad_token_id,
+        }
+    }
+    /// Build from a JSON value
+    pub fn from_json(value: Value) -> Result<RobertaParams, ModelError> {
+        let hidden_size = value["hidden_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("hidden_size".to_string()))?
+            as i32;
+
+        let intermediate_size = value["intermediate_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("intermediate_size".to_string()))?
+            as i32;
+
+        let max_position_embeddings =
+            value["max_position_embeddings"]
+                .as_i64()
+                .ok_or(ModelError::MissingField(
+                    "max_position_embeddings".to_string(),
+                ))? as i32;
+
+        let num_attention_heads = value["num_attention_heads"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("num_attention_heads".to_string()))?
+            as i32;
+
+        let num_hidden_layers = value["num_hidden_layers"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("num_hidden_layers".to_string()))?
+            as i32;
+
+        let pad_token_id = value["pad_token_id"].as_i64().map(|val| val as i32);
+
+        Ok(RobertaParams::new(
+            hidden_size,
+            intermediate_size,
+            max_position_embeddings,
+            num_attention_heads,
+            num_hidden_layers,
+            pad_token_id,
+        ))
+    }
+}
+
+/// A struct representing a RoBERTa model configuration
+#[derive(Clone, Debug, Deserialize)]
+pub struct RobertaModelConfig {
+    /// RoBERTa model parameters
+    params: RobertaParams,
+    /// RoBERTa model vocab_size
+    vocab_size: i32,
+    /// RoBERTa model tie_word_embeddings, whether the LM head shares the token embeddings weights
+    tie_word_embeddings: bool,
+    /// RoBERTa model weights precision
+    dtype: Dtype,
+    /// RoBERTa model type
+    model_type: String,
+    /// RoBERTa model available libraries
+    available_libraries: Vec<ModelLibraries>,
+}
+
+/// RoBERTa model implementation
+impl RobertaModelConfig {
+    /// Build a new `RobertaModelConfig` struct based on the provided parameters
+    pub fn new(
+        params: RobertaParams,
+        vocab_size: i32,
+        tie_word_embeddings: bool,
+        dtype: Dtype,
+        model_type: String,
+        available_libraries: Vec<ModelLibraries>,
+    ) -> RobertaModelConfig {
+        RobertaModelConfig {
+            params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
+            model_type,
+            available_libraries,
+        }
+    }
+}
+
+/// Implementation of the `ModelConfigTrait` trait for `RobertaModelConfig`
+impl ModelConfigTrait for RobertaModelConfig {
+    fn hidden_size(&self) -> i32 {
+        self.params.hidden_size
+    }
+
+    fn intermediate_size(&self) -> i32 {
+        self.params.intermediate_size
+    }
+
+    /// RoBERTa position ids start after the padding index, so the first `pad_token_id + 1`
+    /// position embeddings are never used (e.g. 514 embeddings for a 512 tokens context).
+    fn max_position_embeddings(&self) -> i32 {
+        self.params.max_position_embeddings - (self.params.pad_token_id + 1)
+    }
+
+    fn num_attention_heads(&self) -> i32 {
+        self.params.num_attention_heads
+    }
+
+    fn num_hidden_layers(&self) -> i32 {
+        self.params.num_hidden_layers
+    }
+
+    fn model_family(&self) -> ModelFamily {
+        ModelFamily::EncoderLM
+    }
+
+    fn vocab_size(&self) -> i32 {
+        self.vocab_size
+    }
+
+    fn tie_word_embeddings(&self) -> bool {
+        self.tie_word_embeddings
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn model_type(&self) -> &str {
+        &self.model_type
+    }
+
+    fn available_libraries(&self) -> &[ModelLibraries] {
+        &self.available_libraries
+    }
+
+    fn from_json(value: Value) -> Result<Self, ModelError> {
+        let params = RobertaParams::from_json(value.clone())?;
+
+        let vocab_size = value["vocab_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("vocab_size".to_string()))?
+            as i32;
+
+        let tie_word_embeddings = value["tie_word_embeddings"].as_bool().unwrap_or(true);
+
+        let dtype = Dtype::from_json(&value);
+
+        let model_type = match value["model_type"].as_str() {
+            Some(model_type) => model_type.to_string(),
+            None => return Err(ModelError::MissingField("model_type".to_string())),
+        };
+
+        let available_libraries = ModelLibraries::from_json(&value);
+
+        Ok(RobertaModelConfig::new(
+            params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
+            model_type,
+            available_libraries,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_roberta_model_params() {
+        let roberta_params = RobertaParams::new(768, 3072, 514, 12, 12, None);
+
+        assert_eq!(roberta_params.hidden_size, 768);
+        assert_eq!(roberta_params.intermediate_size, 3072);
+        assert_eq!(roberta_params.max_position_embeddings, 514);
+        assert_eq!(roberta_params.num_attention_heads, 12);
+        assert_eq!(roberta_params.num_hidden_layers, 12);
+        assert_eq!(roberta_params.pad_token_id, 1);
+    }
+
+    #[test]
+    fn test_roberta_model_config() {
+        let roberta_params = RobertaParams::new(768, 3072, 514, 12, 12, None);
+        let roberta_model_config = RobertaModelConfig::new(
+            roberta_params,
+            50265,
+            true,
+            Dtype::F32,
+            "roberta".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        assert_eq!(roberta_model_config.params.hidden_size, 768);
+        assert_eq!(roberta_model_config.params.intermediate_size, 3072);
+        assert_eq!(roberta_model_config.params.max_position_embeddings, 514);
+        assert_eq!(roberta_model_config.params.num_attention_heads, 12);
+        assert_eq!(roberta_model_config.params.num_hidden_layers, 12);
+        assert_eq!(roberta_model_config.model_type, "roberta");
+        assert_eq!(
+            roberta_model_config.available_libraries,
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+
+    #[test]
+    fn test_roberta_model_trait_implementation() {
+        let value = json!({
+            "model_type": "xlm-roberta",
+            "hidden_size": 1024,
+            "intermediate_size": 4096,
+            "max_position_embeddings": 514,
+            "num_attention_heads": 16,
+            "num_hidden_layers": 24,
+            "pad_token_id": 1,
+            "vocab_size": 250002
+        });
+        let roberta_model_config = RobertaModelConfig::from_json(value).unwrap();
+        assert_eq!(roberta_model_config.hidden_size(), 1024);
+        assert_eq!(roberta_model_config.intermediate_size(), 4096);
+        assert_eq!(roberta_model_config.max_position_embeddings(), 512);
+        assert_eq!(roberta_model_config.num_attention_heads(), 16);
+        assert_eq!(roberta_model_config.num_hidden_layers(), 24);
+        assert_eq!(roberta_model_config.model_type(), "xlm-roberta");
+        assert_eq!(
+            roberta_model_config.available_libraries(),
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+}
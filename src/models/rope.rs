@@ -0,0 +1,233 @@
+//! Module for the rotary position embeddings (RoPE) parameters
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::models::ModelError;
+
+/// Base of the rotary embeddings frequencies when the config doesn't set `rope_theta`
+const DEFAULT_ROPE_THETA: f64 = 10000.0;
+
+/// Enumerate the methods used to scale the rotary embeddings to a longer context
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub enum RopeScalingType {
+    /// Linear interpolation of the positions
+    Linear,
+    /// Dynamic NTK-aware scaling, applied once the sequence exceeds the original context
+    Dynamic,
+    /// YaRN scaling
+    Yarn,
+    /// Llama 3.1 frequency-dependent scaling
+    Llama3,
+    /// LongRoPE scaling (e.g. Phi-3)
+    LongRope,
+    /// Any other scaling method, kept as found in the config
+    Other(String),
+}
+
+/// Build from the `rope_type` (or legacy `type`) value of a `rope_scaling` config
+impl From<&str> for RopeScalingType {
+    fn from(rope_type: &str) -> RopeScalingType {
+        match rope_type {
+            "linear" => RopeScalingType::Linear,
+            "dynamic" => RopeScalingType::Dynamic,
+            "yarn" => RopeScalingType::Yarn,
+            "llama3" => RopeScalingType::Llama3,
+            "longrope" | "su" => RopeScalingType::LongRope,
+            other => RopeScalingType::Other(other.to_string()),
+        }
+    }
+}
+
+/// A struct representing the `rope_scaling` parameters of a config
+#[derive(Clone, Debug, Deserialize)]
+pub struct RopeScaling {
+    /// Scaling method
+    rope_type: RopeScalingType,
+    /// Context extension factor
+    factor: f64,
+    /// Context the model was pre-trained with, before the scaling
+    original_max_position_embeddings: Option<i32>,
+}
+
+/// RoPE scaling implementation
+impl RopeScaling {
+    /// Build a new `RopeScaling` struct based on the provided parameters
+    pub fn new(
+        rope_type: RopeScalingType,
+        factor: f64,
+        original_max_position_embeddings: Option<i32>,
+    ) -> RopeScaling {
+        RopeScaling {
+            rope_type,
+            factor,
+            original_max_position_embeddings,
+        }
+    }
+    /// Returns the scaling method
+    pub fn rope_type(&self) -> &RopeScalingType {
+        &self.rope_type
+    }
+    /// Returns the context extension factor
+    pub fn factor(&self) -> f64 {
+        self.factor
+    }
+    /// Returns the context the model was pre-trained with, if the config stores it
+    pub fn original_max_position_embeddings(&self) -> Option<i32> {
+        self.original_max_position_embeddings
+    }
+    /// Build from the `rope_scaling` JSON value, `None` when the rotary embeddings are not scaled
+    pub fn from_json(value: &Value) -> Result<Option<RopeScaling>, ModelError> {
+        let rope_type = match value["rope_type"].as_str().or(value["type"].as_str()) {
+            None | Some("default") => return Ok(None),
+            Some(rope_type) => RopeScalingType::from(rope_type),
+        };
+
+        // LongRoPE stores per-dimension factors instead of a single one
+        let factor = match rope_type {
+            RopeScalingType::LongRope => value["factor"].as_f64().unwrap_or(1.0),
+            _ => value["factor"]
+                .as_f64()
+                .ok_or(ModelError::MissingField("factor".to_string()))?,
+        };
+
+        let original_max_position_embeddings = value["original_max_position_embeddings"]
+            .as_i64()
+            .map(|val| val as i32);
+
+        Ok(Some(RopeScaling::new(
+            rope_type,
+            factor,
+            original_max_position_embeddings,
+        )))
+    }
+}
+
+/// A struct representing the rotary position embeddings parameters of a model
+#[derive(Clone, Debug, Deserialize)]
+pub struct RopeParams {
+    /// Base of the rotary embeddings frequencies
+    theta: f64,
+    /// Scaling applied to extend the context, if any
+    scaling: Option<RopeScaling>,
+}
+
+/// RoPE parameters implementation
+impl RopeParams {
+    /// Build a new `RopeParams` struct based on the provided parameters
+    pub fn new(theta: Option<f64>, scaling: Option<RopeScaling>) -> RopeParams {
+        let theta = theta.unwrap_or(DEFAULT_ROPE_THETA);
+        RopeParams { theta, scaling }
+    }
+    /// Returns the base of the rotary embeddings frequencies
+    pub fn theta(&self) -> f64 {
+        self.theta
+    }
+    /// Returns the scaling applied to extend the context
+    pub fn scaling(&self) -> Option<&RopeScaling> {
+        self.scaling.as_ref()
+    }
+    /// Returns the longest context the model supports with its scaling.
+    ///
+    /// Some configs already store the extended context in `max_position_embeddings` (e.g.
+    /// Llama 3.1), others only add the `rope_scaling` on top of the original one (e.g. Qwen2 with
+    /// YaRN), so the largest of both is returned.
+    pub fn effective_max_context(&self, max_position_embeddings: i32) -> i32 {
+        match &self.scaling {
+            Some(scaling) => {
+                let original = scaling
+                    .original_max_position_embeddings
+                    .unwrap_or(max_position_embeddings);
+                let scaled = (original as f64 * scaling.factor) as i32;
+                scaled.max(max_position_embeddings)
+            }
+            None => max_position_embeddings,
+        }
+    }
+    /// Build from a JSON value, both the `rope_theta` and `rope_scaling` fields and the
+    /// `rope_parameters` field of the recent configs are supported
+    pub fn from_json(value: &Value) -> Result<RopeParams, ModelError> {
+        let theta = value["rope_theta"]
+            .as_f64()
+            .or(value["rope_parameters"]["rope_theta"].as_f64());
+
+        let scaling = if value["rope_scaling"].is_object() {
+            RopeScaling::from_json(&value["rope_scaling"])?
+        } else {
+            RopeScaling::from_json(&value["rope_parameters"])?
+        };
+
+        Ok(RopeParams::new(theta, scaling))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rope_scaling() {
+        let rope_scaling = RopeScaling::new(RopeScalingType::Linear, 4.0, None);
+        assert_eq!(rope_scaling.rope_type(), &RopeScalingType::Linear);
+        assert_eq!(rope_scaling.factor(), 4.0);
+        assert_eq!(rope_scaling.original_max_position_embeddings(), None);
+
+        let value = json!({"type": "dynamic", "factor": 2.0});
+        let rope_scaling = RopeScaling::from_json(&value).unwrap().unwrap();
+        assert_eq!(rope_scaling.rope_type(), &RopeScalingType::Dynamic);
+        assert_eq!(rope_scaling.factor(), 2.0);
+
+        assert!(RopeScaling::from_json(&Value::Null).unwrap().is_none());
+        assert!(RopeScaling::from_json(&json!({"rope_type": "yarn"})).is_err());
+    }
+
+    #[test]
+    fn test_rope_params() {
+        let rope_params = RopeParams::new(None, None);
+        assert_eq!(rope_params.theta(), 10000.0);
+        assert!(rope_params.scaling().is_none());
+        assert_eq!(rope_params.effective_max_context(4096), 4096);
+
+        // Qwen2.5 with YaRN, only the scaling is added to the config
+        let value = json!({
+            "max_position_embeddings": 32768,
+            "rope_theta": 1000000.0,
+            "rope_scaling": {
+                "type": "yarn",
+                "factor": 4.0,
+                "original_max_position_embeddings": 32768
+            }
+        });
+        let rope_params = RopeParams::from_json(&value).unwrap();
+        assert_eq!(rope_params.theta(), 1000000.0);
+        assert_eq!(rope_params.effective_max_context(32768), 131072);
+    }
+
+    #[test]
+    fn test_rope_params_already_extended() {
+        // Llama 3.1, max_position_embeddings is already the extended context
+        let value = json!({
+            "max_position_embeddings": 131072,
+            "rope_theta": 500000.0,
+            "rope_scaling": {
+                "factor": 8.0,
+                "low_freq_factor": 1.0,
+                "high_freq_factor": 4.0,
+                "original_max_position_embeddings": 8192,
+                "rope_type": "llama3"
+            }
+        });
+        let rope_params = RopeParams::from_json(&value).unwrap();
+        assert_eq!(
+            rope_params.scaling().unwrap().rope_type(),
+            &RopeScalingType::Llama3
+        );
+        assert_eq!(rope_params.effective_max_context(131072), 131072);
+
+        let value = json!({
+            "rope_parameters": {"rope_type": "linear", "rope_theta": 10000.0, "factor": 2.0}
+        });
+        let rope_params = RopeParams::from_json(&value).unwrap();
+        assert_eq!(rope_params.effective_max_context(4096), 8192);
+    }
+}
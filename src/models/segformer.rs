@@ -0,0 +1,332 @@
+//! Module for the SegFormer model
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::models::{
+    Dtype, ModelConfigTrait, ModelError, ModelFamily, ModelLibraries, ParameterBreakdown,
+};
+
+/// Parse a JSON array of integers, one value per encoder stage
+fn parse_stages(value: &Value, field: &str) -> Result<Vec<i32>, ModelError> {
+    value[field]
+        .as_array()
+        .and_then(|stages| {
+            stages
+                .iter()
+                .map(|stage| stage.as_i64().map(|val| val as i32))
+                .collect::<Option<Vec<i32>>>()
+        })
+        .ok_or(ModelError::MissingField(field.to_string()))
+}
+
+/// A struct representing the SegFormer architecture parameters.
+///
+/// The SegFormer encoder (Mix Transformer) is hierarchical, so every field but the decoder one
+/// holds a value per stage.
+#[derive(Clone, Debug, Deserialize)]
+pub struct SegformerParams {
+    /// SegFormer model hidden size of every stage
+    hidden_sizes: Vec<i32>,
+    /// SegFormer model number of layers of every stage
+    depths: Vec<i32>,
+    /// SegFormer model number of attention heads of every stage
+    num_attention_heads: Vec<i32>,
+    /// SegFormer model ratio between the Mix-FFN and the hidden sizes of every stage
+    mlp_ratios: Vec<i32>,
+    /// SegFormer model keys/values spatial reduction ratio of every stage
+    sr_ratios: Vec<i32>,
+    /// SegFormer model patch embeddings kernel size of every stage
+    patch_sizes: Vec<i32>,
+    /// SegFormer model hidden size of the all-MLP decode head
+    decoder_hidden_size: i32,
+}
+
+/// SegFormer model parameters implementation
+impl SegformerParams {
+    /// Build a new `SegformerParams` struct based on the provided parameters
+    pub fn new(
+        hidden_sizes: Vec<i32>,
+        depths: Vec<i32>,
+        num_attention_heads: Vec<i32>,
+        mlp_ratios: Vec<i32>,
+        sr_ratios: Vec<i32>,
+        patch_sizes: Vec<i32>,
+        decoder_hidden_size: i32,
+    ) -> SegformerParams {
+        SegformerParams {
+            hidden_sizes,
+            depths,
+            num_attention_heads,
+            mlp_ratios,
+            sr_ratios,
+            patch_sizes,
+            decoder_hidden_size,
+        }
+    }
+    /// Build from a JSON value
+    pub fn from_json(value: Value) -> Result<SegformerParams, ModelError> {
+        let hidden_sizes = parse_stages(&value, "hidden_sizes")?;
+
+        let depths = parse_stages(&value, "depths")?;
+
+        let num_attention_heads = parse_stages(&value, "num_attention_heads")?;
+
+        let mlp_ratios = parse_stages(&value, "mlp_ratios")?;
+
+        let sr_ratios = parse_stages(&value, "sr_ratios")?;
+
+        let patch_sizes = parse_stages(&value, "patch_sizes")?;
+
+        let decoder_hidden_size = value["decoder_hidden_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("decoder_hidden_size".to_string()))?
+            as i32;
+
+        Ok(SegformerParams::new(
+            hidden_sizes,
+            depths,
+            num_attention_heads,
+            mlp_ratios,
+            sr_ratios,
+            patch_sizes,
+            decoder_hidden_size,
+        ))
+    }
+}
+
+/// A struct representing a SegFormer model configuration
+#[derive(Clone, Debug, Deserialize)]
+pub struct SegformerModelConfig {
+    /// SegFormer model parameters
+    params: SegformerParams,
+    /// SegFormer model weights precision
+    dtype: Dtype,
+    /// SegFormer model type
+    model_type: String,
+    /// SegFormer model available libraries
+    available_libraries: Vec<ModelLibraries>,
+}
+
+/// SegFormer model implementation
+impl SegformerModelConfig {
+    /// Build a new `SegformerModelConfig` struct based on the provided parameters
+    pub fn new(
+        params: SegformerParams,
+        dtype: Dtype,
+        model_type: String,
+        available_libraries: Vec<ModelLibraries>,
+    ) -> SegformerModelConfig {
+        SegformerModelConfig {
+            params,
+            dtype,
+            model_type,
+            available_libraries,
+        }
+    }
+    /// Returns the hidden size of every stage
+    pub fn hidden_sizes(&self) -> &[i32] {
+        &self.params.hidden_sizes
+    }
+    /// Returns the number of layers of every stage
+    pub fn depths(&self) -> &[i32] {
+        &self.params.depths
+    }
+    /// Returns the keys/values spatial reduction ratio of every stage
+    pub fn sr_ratios(&self) -> &[i32] {
+        &self.params.sr_ratios
+    }
+    /// Returns the hidden size of the decode head
+    pub fn decoder_hidden_size(&self) -> i32 {
+        self.params.decoder_hidden_size
+    }
+}
+
+/// Implementation of the `ModelConfigTrait` trait for `SegformerModelConfig`.
+///
+/// The flat accessors describe the last stage, which has the widest layers.
+impl ModelConfigTrait for SegformerModelConfig {
+    fn hidden_size(&self) -> i32 {
+        self.params.hidden_sizes.last().copied().unwrap_or_default()
+    }
+
+    fn intermediate_size(&self) -> i32 {
+        self.hidden_size() * self.params.mlp_ratios.last().copied().unwrap_or(4)
+    }
+
+    /// SegFormer doesn't have any position embeddings and handles any image resolution
+    fn max_position_embeddings(&self) -> i32 {
+        0
+    }
+
+    fn num_attention_heads(&self) -> i32 {
+        self.params
+            .num_attention_heads
+            .last()
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn num_hidden_layers(&self) -> i32 {
+        self.params.depths.iter().sum()
+    }
+
+    fn model_family(&self) -> ModelFamily {
+        ModelFamily::Vision
+    }
+
+    /// The encoder stages (patch embeddings, efficient self-attention and Mix-FFN layers) and the
+    /// all-MLP decode head are counted, normalization layers and biases are left out
+    fn parameter_breakdown(&self) -> ParameterBreakdown {
+        let params = &self.params;
+        let (mut embeddings, mut attention, mut mlp) = (0, 0, 0);
+        let mut in_channels = 3;
+        for (stage, &hidden_size) in params.hidden_sizes.iter().enumerate() {
+            let hidden_size = hidden_size as i64;
+            let value_at =
+                |stages: &[i32], default: i32| stages.get(stage).copied().unwrap_or(default) as i64;
+            let sr_ratio = value_at(&params.sr_ratios, 1);
+            let mlp_size = value_at(&params.mlp_ratios, 4) * hidden_size;
+            let patch_size = value_at(&params.patch_sizes, 3);
+
+            let spatial_reduction = if sr_ratio > 1 {
+                hidden_size * hidden_size * sr_ratio * sr_ratio
+            } else {
+                0
+            };
+            let depth = value_at(&params.depths, 0);
+            embeddings += patch_size * patch_size * in_channels * hidden_size;
+            attention += depth * (4 * hidden_size * hidden_size + spatial_reduction);
+            // The 3x3 depthwise convolution of the Mix-FFN is counted with the feed-forward
+            mlp += depth * (2 * hidden_size * mlp_size + 9 * mlp_size);
+            in_channels = hidden_size;
+        }
+        let decoder_hidden_size = params.decoder_hidden_size as i64;
+        let decode_head = params
+            .hidden_sizes
+            .iter()
+            .map(|&hidden_size| hidden_size as i64 * decoder_hidden_size)
+            .sum::<i64>()
+            + params.hidden_sizes.len() as i64 * decoder_hidden_size * decoder_hidden_size;
+        ParameterBreakdown::new(
+            embeddings,
+            attention,
+            mlp,
+            0,
+            decode_head,
+            self.num_hidden_layers(),
+        )
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn model_type(&self) -> &str {
+        &self.model_type
+    }
+
+    fn available_libraries(&self) -> &[ModelLibraries] {
+        &self.available_libraries
+    }
+
+    fn from_json(value: Value) -> Result<Self, ModelError> {
+        let params = SegformerParams::from_json(value.clone())?;
+
+        let dtype = Dtype::from_json(&value);
+
+        let model_type = match value["model_type"].as_str() {
+            Some(model_type) => model_type.to_string(),
+            None => return Err(ModelError::MissingField("model_type".to_string())),
+        };
+
+        let available_libraries = ModelLibraries::from_json(&value);
+
+        Ok(SegformerModelConfig::new(
+            params,
+            dtype,
+            model_type,
+            available_libraries,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn create_segformer_params() -> SegformerParams {
+        SegformerParams::new(
+            vec![32, 64, 160, 256],
+            vec![2, 2, 2, 2],
+            vec![1, 2, 5, 8],
+            vec![4, 4, 4, 4],
+            vec![8, 4, 2, 1],
+            vec![7, 3, 3, 3],
+            256,
+        )
+    }
+
+    #[test]
+    fn test_segformer_model_params() {
+        let segformer_params = create_segformer_params();
+
+        assert_eq!(segformer_params.hidden_sizes, vec![32, 64, 160, 256]);
+        assert_eq!(segformer_params.depths, vec![2, 2, 2, 2]);
+        assert_eq!(segformer_params.num_attention_heads, vec![1, 2, 5, 8]);
+        assert_eq!(segformer_params.mlp_ratios, vec![4, 4, 4, 4]);
+        assert_eq!(segformer_params.sr_ratios, vec![8, 4, 2, 1]);
+        assert_eq!(segformer_params.patch_sizes, vec![7, 3, 3, 3]);
+        assert_eq!(segformer_params.decoder_hidden_size, 256);
+    }
+
+    #[test]
+    fn test_segformer_model_config() {
+        let segformer_model_config = SegformerModelConfig::new(
+            create_segformer_params(),
+            Dtype::F32,
+            "segformer".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        assert_eq!(segformer_model_config.hidden_sizes(), &[32, 64, 160, 256]);
+        assert_eq!(segformer_model_config.depths(), &[2, 2, 2, 2]);
+        assert_eq!(segformer_model_config.sr_ratios(), &[8, 4, 2, 1]);
+        assert_eq!(segformer_model_config.decoder_hidden_size(), 256);
+        // SegFormer-B0 has 3.7M parameters
+        assert_eq!(segformer_model_config.num_parameters(), 3_691_104);
+        assert_eq!(segformer_model_config.model_type, "segformer");
+        assert_eq!(
+            segformer_model_config.available_libraries,
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+
+    #[test]
+    fn test_segformer_model_trait_implementation() {
+        let value = json!({
+            "model_type": "segformer",
+            "decoder_hidden_size": 768,
+            "depths": [3, 6, 40, 3],
+            "hidden_sizes": [64, 128, 320, 512],
+            "mlp_ratios": [4, 4, 4, 4],
+            "num_attention_heads": [1, 2, 5, 8],
+            "num_encoder_blocks": 4,
+            "patch_sizes": [7, 3, 3, 3],
+            "sr_ratios": [8, 4, 2, 1],
+            "strides": [4, 2, 2, 2]
+        });
+        let segformer_model_config = SegformerModelConfig::from_json(value).unwrap();
+        assert_eq!(segformer_model_config.hidden_size(), 512);
+        assert_eq!(segformer_model_config.intermediate_size(), 2048);
+        assert_eq!(segformer_model_config.max_position_embeddings(), 0);
+        assert_eq!(segformer_model_config.num_attention_heads(), 8);
+        assert_eq!(segformer_model_config.num_hidden_layers(), 52);
+        // SegFormer-B5 has 84.7M parameters
+        assert_eq!(segformer_model_config.num_parameters(), 84_282_304);
+        assert_eq!(segformer_model_config.model_type(), "segformer");
+        assert_eq!(
+            segformer_model_config.available_libraries(),
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+}
@@ -0,0 +1,334 @@
+//! Module for the Swin Transformer model
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::models::{
+    Dtype, ModelConfigTrait, ModelError, ModelFamily, ModelLibraries, ParameterBreakdown,
+};
+
+/// A struct representing the Swin Transformer architecture parameters.
+///
+/// Swin is a hierarchical backbone: every stage halves the resolution and doubles the embedding
+/// dimension, so depths and number of heads are given per stage.
+#[derive(Clone, Debug, Deserialize)]
+pub struct SwinParams {
+    /// Swin model embedding dimension of the first stage
+    embed_dim: i32,
+    /// Swin model number of layers of every stage
+    depths: Vec<i32>,
+    /// Swin model number of attention heads of every stage
+    num_heads: Vec<i32>,
+    /// Swin model ratio between the MLP and the embedding dimensions
+    mlp_ratio: f64,
+    /// Swin model input image resolution
+    image_size: i32,
+    /// Swin model resolution of each patch
+    patch_size: i32,
+    /// Swin model size of the attention windows
+    window_size: i32,
+}
+
+/// Swin model parameters implementation
+impl SwinParams {
+    /// Build a new `SwinParams` struct based on the provided parameters
+    pub fn new(
+        embed_dim: i32,
+        depths: Vec<i32>,
+        num_heads: Vec<i32>,
+        mlp_ratio: Option<f64>,
+        image_size: i32,
+        patch_size: i32,
+        window_size: i32,
+    ) -> SwinParams {
+        let mlp_ratio = mlp_ratio.unwrap_or(4.0);
+        SwinParams {
+            embed_dim,
+            depths,
+            num_heads,
+            mlp_ratio,
+            image_size,
+            patch_size,
+            window_size,
+        }
+    }
+    /// Build from a JSON value
+    pub fn from_json(value: Value) -> Result<SwinParams, ModelError> {
+        let embed_dim = value["embed_dim"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("embed_dim".to_string()))?
+            as i32;
+
+        let depths = value["depths"]
+            .as_array()
+            .and_then(|depths| {
+                depths
+                    .iter()
+                    .map(|depth| depth.as_i64().map(|val| val as i32))
+                    .collect::<Option<Vec<i32>>>()
+            })
+            .ok_or(ModelError::MissingField("depths".to_string()))?;
+
+        let num_heads = value["num_heads"]
+            .as_array()
+            .and_then(|num_heads| {
+                num_heads
+                    .iter()
+                    .map(|heads| heads.as_i64().map(|val| val as i32))
+                    .collect::<Option<Vec<i32>>>()
+            })
+            .ok_or(ModelError::MissingField("num_heads".to_string()))?;
+
+        let mlp_ratio = value["mlp_ratio"].as_f64();
+
+        // Donut encoders take non-square images, whose size is given as `[height, width]`
+        let image_size = value["image_size"]
+            .as_i64()
+            .or(value["image_size"]
+                .as_array()
+                .and_then(|sides| sides.iter().filter_map(|side| side.as_i64()).max()))
+            .ok_or(ModelError::MissingField("image_size".to_string()))?
+            as i32;
+
+        let patch_size = value["patch_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("patch_size".to_string()))?
+            as i32;
+
+        let window_size = value["window_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("window_size".to_string()))?
+            as i32;
+
+        Ok(SwinParams::new(
+            embed_dim,
+            depths,
+            num_heads,
+            mlp_ratio,
+            image_size,
+            patch_size,
+            window_size,
+        ))
+    }
+}
+
+/// A struct representing a Swin model configuration
+#[derive(Clone, Debug, Deserialize)]
+pub struct SwinModelConfig {
+    /// Swin model parameters
+    params: SwinParams,
+    /// Swin model weights precision
+    dtype: Dtype,
+    /// Swin model type
+    model_type: String,
+    /// Swin model available libraries
+    available_libraries: Vec<ModelLibraries>,
+}
+
+/// Swin model implementation
+impl SwinModelConfig {
+    /// Build a new `SwinModelConfig` struct based on the provided parameters
+    pub fn new(
+        params: SwinParams,
+        dtype: Dtype,
+        model_type: String,
+        available_libraries: Vec<ModelLibraries>,
+    ) -> SwinModelConfig {
+        SwinModelConfig {
+            params,
+            dtype,
+            model_type,
+            available_libraries,
+        }
+    }
+    /// Returns the number of layers of every stage
+    pub fn depths(&self) -> &[i32] {
+        &self.params.depths
+    }
+    /// Returns the number of attention heads of every stage
+    pub fn num_heads(&self) -> &[i32] {
+        &self.params.num_heads
+    }
+    /// Returns the embedding dimension of every stage
+    pub fn stage_dims(&self) -> Vec<i32> {
+        (0..self.params.depths.len())
+            .map(|stage| self.params.embed_dim << stage)
+            .collect()
+    }
+    /// Returns the input image resolution, the largest side for non-square images
+    pub fn image_size(&self) -> i32 {
+        self.params.image_size
+    }
+    /// Returns the resolution of each patch
+    pub fn patch_size(&self) -> i32 {
+        self.params.patch_size
+    }
+    /// Returns the size of the attention windows
+    pub fn window_size(&self) -> i32 {
+        self.params.window_size
+    }
+}
+
+/// Implementation of the `ModelConfigTrait` trait for `SwinModelConfig`.
+///
+/// The flat accessors describe the last stage, which has the widest layers.
+impl ModelConfigTrait for SwinModelConfig {
+    fn hidden_size(&self) -> i32 {
+        self.stage_dims()
+            .last()
+            .copied()
+            .unwrap_or(self.params.embed_dim)
+    }
+
+    fn intermediate_size(&self) -> i32 {
+        (self.hidden_size() as f64 * self.params.mlp_ratio) as i32
+    }
+
+    /// Attention is computed inside windows, so a sequence is a window of patches
+    fn max_position_embeddings(&self) -> i32 {
+        self.params.window_size.pow(2)
+    }
+
+    fn num_attention_heads(&self) -> i32 {
+        self.params.num_heads.last().copied().unwrap_or_default()
+    }
+
+    fn num_hidden_layers(&self) -> i32 {
+        self.params.depths.iter().sum()
+    }
+
+    fn model_family(&self) -> ModelFamily {
+        ModelFamily::Vision
+    }
+
+    /// The layers are counted stage by stage, with the patch merging layers in-between stages
+    fn parameter_breakdown(&self) -> ParameterBreakdown {
+        let stages = self.params.depths.len();
+        self.stage_dims()
+            .iter()
+            .zip(self.params.depths.iter())
+            .enumerate()
+            .map(|(stage, (&dim, &depth))| {
+                let dim = dim as i64;
+                let intermediate_size = (dim as f64 * self.params.mlp_ratio) as i64;
+                let patch_merging = if stage + 1 < stages {
+                    4 * dim * 2 * dim
+                } else {
+                    0
+                };
+                ParameterBreakdown::new(
+                    0,
+                    depth as i64 * 4 * dim * dim,
+                    depth as i64 * 2 * dim * intermediate_size,
+                    0,
+                    patch_merging,
+                    depth,
+                )
+            })
+            .fold(ParameterBreakdown::default(), |total, stage| total + stage)
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn model_type(&self) -> &str {
+        &self.model_type
+    }
+
+    fn available_libraries(&self) -> &[ModelLibraries] {
+        &self.available_libraries
+    }
+
+    fn from_json(value: Value) -> Result<Self, ModelError> {
+        let params = SwinParams::from_json(value.clone())?;
+
+        let dtype = Dtype::from_json(&value);
+
+        let model_type = match value["model_type"].as_str() {
+            Some(model_type) => model_type.to_string(),
+            None => return Err(ModelError::MissingField("model_type".to_string())),
+        };
+
+        let available_libraries = ModelLibraries::from_json(&value);
+
+        Ok(SwinModelConfig::new(
+            params,
+            dtype,
+            model_type,
+            available_libraries,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_swin_model_params() {
+        let swin_params =
+            SwinParams::new(96, vec![2, 2, 6, 2], vec![3, 6, 12, 24], None, 224, 4, 7);
+
+        assert_eq!(swin_params.embed_dim, 96);
+        assert_eq!(swin_params.depths, vec![2, 2, 6, 2]);
+        assert_eq!(swin_params.num_heads, vec![3, 6, 12, 24]);
+        assert_eq!(swin_params.mlp_ratio, 4.0);
+        assert_eq!(swin_params.image_size, 224);
+        assert_eq!(swin_params.patch_size, 4);
+        assert_eq!(swin_params.window_size, 7);
+    }
+
+    #[test]
+    fn test_swin_model_config() {
+        let swin_params =
+            SwinParams::new(96, vec![2, 2, 6, 2], vec![3, 6, 12, 24], None, 224, 4, 7);
+        let swin_model_config = SwinModelConfig::new(
+            swin_params,
+            Dtype::F32,
+            "swin".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        assert_eq!(swin_model_config.stage_dims(), vec![96, 192, 384, 768]);
+        assert_eq!(swin_model_config.model_type, "swin");
+        assert_eq!(
+            swin_model_config.available_libraries,
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+
+    #[test]
+    fn test_swin_model_trait_implementation() {
+        let value = json!({
+            "model_type": "swin",
+            "embed_dim": 128,
+            "depths": [2, 2, 18, 2],
+            "num_heads": [4, 8, 16, 32],
+            "mlp_ratio": 4.0,
+            "image_size": 224,
+            "patch_size": 4,
+            "window_size": 7
+        });
+        let swin_model_config = SwinModelConfig::from_json(value).unwrap();
+        assert_eq!(swin_model_config.hidden_size(), 1024);
+        assert_eq!(swin_model_config.intermediate_size(), 4096);
+        assert_eq!(swin_model_config.max_position_embeddings(), 49);
+        assert_eq!(swin_model_config.num_attention_heads(), 32);
+        assert_eq!(swin_model_config.num_hidden_layers(), 24);
+        assert_eq!(swin_model_config.depths(), &[2, 2, 18, 2]);
+        assert_eq!(swin_model_config.num_heads(), &[4, 8, 16, 32]);
+        assert_eq!(swin_model_config.image_size(), 224);
+        assert_eq!(swin_model_config.patch_size(), 4);
+        assert_eq!(swin_model_config.window_size(), 7);
+        // Swin-B has ~88M parameters, most of them in the transformer layers
+        assert_eq!(swin_model_config.num_parameters(), 86_507_520);
+        assert_eq!(swin_model_config.model_type(), "swin");
+        assert_eq!(
+            swin_model_config.available_libraries(),
+            vec![ModelLibraries::PyTorch]
+        );
+
+        let value = json!({"model_type": "swin", "embed_dim": 96, "depths": [2, "2"]});
+        assert!(SwinModelConfig::from_json(value).is_err());
+    }
+}
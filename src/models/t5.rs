@@ -2,7 +2,7 @@
 use serde::Deserialize;
 use serde_json::Value;
 
-use crate::models::{ModelConfigTrait, ModelError, ModelLibraries};
+use crate::models::{Dtype, ModelConfigTrait, ModelError, ModelLibraries};
 
 /// A struct representing the T5 architecture parameters
 #[derive(Clone, Debug, Deserialize)]
@@ -15,20 +15,31 @@ pub struct T5Params {
     n_positions: i32,
     /// T5 model num_attention_heads
     n_heads: i32,
-    /// T5 model num_hidden_layers
+    /// T5 model number of encoder layers
     n_layers: i32,
+    /// T5 model number of decoder layers, which defaults to the number of encoder layers
+    num_decoder_layers: i32,
 }
 
 /// T5 model parameters implementation
 impl T5Params {
     /// Build a new `T5Params` struct based on the provided parameters
-    pub fn new(d_model: i32, d_ff: i32, n_positions: i32, n_heads: i32, n_layers: i32) -> T5Params {
+    pub fn new(
+        d_model: i32,
+        d_ff: i32,
+        n_positions: i32,
+        n_heads: i32,
+        n_layers: i32,
+        num_decoder_layers: Option<i32>,
+    ) -> T5Params {
+        let num_decoder_layers = num_decoder_layers.unwrap_or(n_layers);
         T5Params {
             d_model,
             d_ff,
             n_positions,
             n_heads,
             n_layers,
+            num_decoder_layers,
         }
     }
     /// Build from a JSON value
@@ -41,20 +52,32 @@ impl T5Params {
             .as_i64()
             .ok_or(ModelError::MissingField("d_ff".to_string()))? as i32;
 
+        // T5 uses relative positions, n_positions is only the pre-training length and recent
+        // checkpoints (e.g. FLAN-T5) don't store it
         let n_positions = value["n_positions"]
             .as_i64()
-            .ok_or(ModelError::MissingField("n_positions".to_string()))?
-            as i32;
+            .map(|val| val as i32)
+            .unwrap_or(512);
 
-        let n_heads = value["n_heads"]
+        let n_heads = value["num_heads"]
             .as_i64()
-            .ok_or(ModelError::MissingField("n_heads".to_string()))? as i32;
+            .ok_or(ModelError::MissingField("num_heads".to_string()))? as i32;
 
-        let n_layers = value["n_layers"]
+        let n_layers = value["num_layers"]
             .as_i64()
-            .ok_or(ModelError::MissingField("n_layers".to_string()))? as i32;
+            .ok_or(ModelError::MissingField("num_layers".to_string()))?
+            as i32;
+
+        let num_decoder_layers = value["num_decoder_layers"].as_i64().map(|val| val as i32);
 
-        Ok(T5Params::new(d_model, d_ff, n_positions, n_heads, n_layers))
+        Ok(T5Params::new(
+            d_model,
+            d_ff,
+            n_positions,
+            n_heads,
+            n_layers,
+            num_decoder_layers,
+        ))
     }
 }
 
@@ -63,6 +86,12 @@ impl T5Params {
 pub struct T5ModelConfig {
     /// T5 model parameters
     params: T5Params,
+    /// T5 model vocab_size
+    vocab_size: i32,
+    /// T5 model tie_word_embeddings, whether the LM head shares the token embeddings weights
+    tie_word_embeddings: bool,
+    /// T5 model weights precision
+    dtype: Dtype,
     /// T5 model type
     model_type: String,
     /// T5 model available libraries
@@ -74,11 +103,17 @@ impl T5ModelConfig {
     /// Build a new `T5ModelConfig` struct based on the provided parameters
     pub fn new(
         params: T5Params,
+        vocab_size: i32,
+        tie_word_embeddings: bool,
+        dtype: Dtype,
         model_type: String,
         available_libraries: Vec<ModelLibraries>,
     ) -> T5ModelConfig {
         T5ModelConfig {
             params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
             model_type,
             available_libraries,
         }
@@ -103,10 +138,31 @@ impl ModelConfigTrait for T5ModelConfig {
         self.params.n_heads
     }
 
+    /// Both the encoder and the decoder layers are held in memory, so they're summed
     fn num_hidden_layers(&self) -> i32 {
+        self.params.n_layers + self.params.num_decoder_layers
+    }
+
+    fn is_encoder_decoder(&self) -> bool {
+        true
+    }
+
+    fn num_encoder_layers(&self) -> i32 {
         self.params.n_layers
     }
 
+    fn vocab_size(&self) -> i32 {
+        self.vocab_size
+    }
+
+    fn tie_word_embeddings(&self) -> bool {
+        self.tie_word_embeddings
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
     fn model_type(&self) -> &str {
         &self.model_type
     }
@@ -121,41 +177,57 @@ impl ModelConfigTrait for T5ModelConfig {
     {
         let params = T5Params::from_json(value.clone())?;
 
+        let vocab_size = value["vocab_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("vocab_size".to_string()))?
+            as i32;
+
+        let tie_word_embeddings = value["tie_word_embeddings"].as_bool().unwrap_or(true);
+
+        let dtype = Dtype::from_json(&value);
+
         let model_type = match value["model_type"].as_str() {
             Some(model_type) => model_type.to_string(),
             None => return Err(ModelError::MissingField("model_type".to_string())),
         };
 
-        // TODO: Implement this
-        let available_libraries = vec![ModelLibraries::PyTorch];
-        // let available_libraries = match value["available_libraries"].as_array() {
-        //     Some(al) => al.iter().map(|v| ModelLibraries::from_str(v.as_str().unwrap()).unwrap()).collect(),
-        //     None => return Err(ModelError::MissingField("available_libraries".to_string())),
-        // };
+        let available_libraries = ModelLibraries::from_json(&value);
 
-        Ok(T5ModelConfig::new(params, model_type, available_libraries))
+        Ok(T5ModelConfig::new(
+            params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
+            model_type,
+            available_libraries,
+        ))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
 
     #[test]
     fn test_t5_model_params() {
-        let t5_params = T5Params::new(768, 3072, 512, 12, 12);
+        let t5_params = T5Params::new(768, 3072, 512, 12, 12, None);
         assert_eq!(t5_params.d_model, 768);
         assert_eq!(t5_params.d_ff, 3072);
         assert_eq!(t5_params.n_positions, 512);
         assert_eq!(t5_params.n_heads, 12);
         assert_eq!(t5_params.n_layers, 12);
+        assert_eq!(t5_params.num_decoder_layers, 12);
     }
 
     #[test]
     fn test_t5_model_config() {
-        let t5_params = T5Params::new(768, 3072, 512, 12, 12);
+        let t5_params = T5Params::new(768, 3072, 512, 12, 12, None);
         let t5_model_config = T5ModelConfig::new(
             t5_params,
+            32128,
+            true,
+            Dtype::F32,
             "t5".to_string(),
             vec![ModelLibraries::TensorFlow],
         );
@@ -173,9 +245,12 @@ mod tests {
 
     #[test]
     fn test_t5_model_trait_implementation() {
-        let t5_params = T5Params::new(768, 3072, 512, 12, 12);
+        let t5_params = T5Params::new(768, 3072, 512, 12, 12, None);
         let t5_model_config = T5ModelConfig::new(
             t5_params,
+            32128,
+            true,
+            Dtype::F32,
             "t5".to_string(),
             vec![ModelLibraries::TensorFlow],
         );
@@ -183,11 +258,29 @@ mod tests {
         assert_eq!(t5_model_config.intermediate_size(), 3072);
         assert_eq!(t5_model_config.max_position_embeddings(), 512);
         assert_eq!(t5_model_config.num_attention_heads(), 12);
-        assert_eq!(t5_model_config.num_hidden_layers(), 12);
+        assert_eq!(t5_model_config.num_hidden_layers(), 24);
+        assert!(t5_model_config.is_encoder_decoder());
+        assert_eq!(t5_model_config.num_encoder_layers(), 12);
+        assert_eq!(t5_model_config.num_decoder_layers(), 12);
         assert_eq!(t5_model_config.model_type(), "t5");
         assert_eq!(
             t5_model_config.available_libraries(),
             vec![ModelLibraries::TensorFlow]
         );
+
+        // Deeper-decoder variants (e.g. the efficient T5 checkpoints) store their own decoder depth
+        let value = json!({
+            "model_type": "t5",
+            "d_model": 512,
+            "d_ff": 2048,
+            "num_heads": 8,
+            "num_layers": 8,
+            "num_decoder_layers": 4,
+            "vocab_size": 32128
+        });
+        let t5_model_config = T5ModelConfig::from_json(value).unwrap();
+        assert_eq!(t5_model_config.num_hidden_layers(), 12);
+        assert_eq!(t5_model_config.num_encoder_layers(), 8);
+        assert_eq!(t5_model_config.num_decoder_layers(), 4);
     }
 }
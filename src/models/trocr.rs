@@ -0,0 +1,268 @@
+//! Module for the TrOCR text decoder
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::models::{Dtype, ModelConfigTrait, ModelError, ModelLibraries, ParameterBreakdown};
+
+/// A struct representing the TrOCR decoder architecture parameters
+#[derive(Clone, Debug, Deserialize)]
+pub struct TrOCRParams {
+    /// TrOCR model hidden_size
+    d_model: i32,
+    /// TrOCR model intermediate_size
+    decoder_ffn_dim: i32,
+    /// TrOCR model max_position_embeddings
+    max_position_embeddings: i32,
+    /// TrOCR model num_attention_heads
+    decoder_attention_heads: i32,
+    /// TrOCR model num_hidden_layers
+    decoder_layers: i32,
+}
+
+/// TrOCR model parameters implementation
+impl TrOCRParams {
+    /// Build a new `TrOCRParams` struct based on the provided parameters
+    pub fn new(
+        d_model: i32,
+        decoder_ffn_dim: i32,
+        max_position_embeddings: i32,
+        decoder_attention_heads: i32,
+        decoder_layers: i32,
+    ) -> TrOCRParams {
+        TrOCRParams {
+            d_model,
+            decoder_ffn_dim,
+            max_position_embeddings,
+            decoder_attention_heads,
+            decoder_layers,
+        }
+    }
+    /// Build from a JSON value
+    pub fn from_json(value: Value) -> Result<TrOCRParams, ModelError> {
+        let d_model = value["d_model"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("d_model".to_string()))? as i32;
+
+        let decoder_ffn_dim = value["decoder_ffn_dim"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("decoder_ffn_dim".to_string()))?
+            as i32;
+
+        let max_position_embeddings =
+            value["max_position_embeddings"]
+                .as_i64()
+                .ok_or(ModelError::MissingField(
+                    "max_position_embeddings".to_string(),
+                ))? as i32;
+
+        let decoder_attention_heads =
+            value["decoder_attention_heads"]
+                .as_i64()
+                .ok_or(ModelError::MissingField(
+                    "decoder_attention_heads".to_string(),
+                ))? as i32;
+
+        let decoder_layers = value["decoder_layers"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("decoder_layers".to_string()))?
+            as i32;
+
+        Ok(TrOCRParams::new(
+            d_model,
+            decoder_ffn_dim,
+            max_position_embeddings,
+            decoder_attention_heads,
+            decoder_layers,
+        ))
+    }
+}
+
+/// A struct representing a TrOCR decoder configuration
+#[derive(Clone, Debug, Deserialize)]
+pub struct TrOCRModelConfig {
+    /// TrOCR model parameters
+    params: TrOCRParams,
+    /// TrOCR model vocab_size
+    vocab_size: i32,
+    /// TrOCR model tie_word_embeddings, whether the LM head shares the token embeddings weights
+    tie_word_embeddings: bool,
+    /// TrOCR model weights precision
+    dtype: Dtype,
+    /// TrOCR model type
+    model_type: String,
+    /// TrOCR model available libraries
+    available_libraries: Vec<ModelLibraries>,
+}
+
+/// TrOCR model implementation
+impl TrOCRModelConfig {
+    /// Build a new `TrOCRModelConfig` struct based on the provided parameters
+    pub fn new(
+        params: TrOCRParams,
+        vocab_size: i32,
+        tie_word_embeddings: bool,
+        dtype: Dtype,
+        model_type: String,
+        available_libraries: Vec<ModelLibraries>,
+    ) -> TrOCRModelConfig {
+        TrOCRModelConfig {
+            params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
+            model_type,
+            available_libraries,
+        }
+    }
+}
+
+/// Implementation of the `ModelConfigTrait` trait for `TrOCRModelConfig`
+impl ModelConfigTrait for TrOCRModelConfig {
+    fn hidden_size(&self) -> i32 {
+        self.params.d_model
+    }
+
+    fn intermediate_size(&self) -> i32 {
+        self.params.decoder_ffn_dim
+    }
+
+    fn max_position_embeddings(&self) -> i32 {
+        self.params.max_position_embeddings
+    }
+
+    fn num_attention_heads(&self) -> i32 {
+        self.params.decoder_attention_heads
+    }
+
+    fn num_hidden_layers(&self) -> i32 {
+        self.params.decoder_layers
+    }
+
+    fn vocab_size(&self) -> i32 {
+        self.vocab_size
+    }
+
+    fn tie_word_embeddings(&self) -> bool {
+        self.tie_word_embeddings
+    }
+
+    /// Every decoder layer has a cross-attention on top of its self-attention
+    fn parameter_breakdown(&self) -> ParameterBreakdown {
+        let d_model = self.params.d_model as i64;
+        let decoder_layers = self.params.decoder_layers as i64;
+        ParameterBreakdown::new(
+            (self.params.max_position_embeddings + self.vocab_size) as i64 * d_model,
+            8 * d_model * d_model * decoder_layers,
+            2 * d_model * self.params.decoder_ffn_dim as i64 * decoder_layers,
+            self.lm_head_parameters(),
+            0,
+            self.params.decoder_layers,
+        )
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn model_type(&self) -> &str {
+        &self.model_type
+    }
+
+    fn available_libraries(&self) -> &[ModelLibraries] {
+        &self.available_libraries
+    }
+
+    fn from_json(value: Value) -> Result<Self, ModelError> {
+        let params = TrOCRParams::from_json(value.clone())?;
+
+        let vocab_size = value["vocab_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("vocab_size".to_string()))?
+            as i32;
+
+        let tie_word_embeddings = value["tie_word_embeddings"].as_bool().unwrap_or(true);
+
+        let dtype = Dtype::from_json(&value);
+
+        let model_type = match value["model_type"].as_str() {
+            Some(model_type) => model_type.to_string(),
+            None => return Err(ModelError::MissingField("model_type".to_string())),
+        };
+
+        let available_libraries = ModelLibraries::from_json(&value);
+
+        Ok(TrOCRModelConfig::new(
+            params,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
+            model_type,
+            available_libraries,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_trocr_model_params() {
+        let trocr_params = TrOCRParams::new(1024, 4096, 512, 16, 12);
+
+        assert_eq!(trocr_params.d_model, 1024);
+        assert_eq!(trocr_params.decoder_ffn_dim, 4096);
+        assert_eq!(trocr_params.max_position_embeddings, 512);
+        assert_eq!(trocr_params.decoder_attention_heads, 16);
+        assert_eq!(trocr_params.decoder_layers, 12);
+    }
+
+    #[test]
+    fn test_trocr_model_config() {
+        let trocr_params = TrOCRParams::new(1024, 4096, 512, 16, 12);
+        let trocr_model_config = TrOCRModelConfig::new(
+            trocr_params,
+            64044,
+            true,
+            Dtype::F32,
+            "trocr".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        assert_eq!(trocr_model_config.params.d_model, 1024);
+        assert_eq!(trocr_model_config.model_type, "trocr");
+        assert_eq!(
+            trocr_model_config.available_libraries,
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+
+    #[test]
+    fn test_trocr_model_trait_implementation() {
+        let value = json!({
+            "model_type": "trocr",
+            "d_model": 256,
+            "decoder_attention_heads": 8,
+            "decoder_ffn_dim": 1024,
+            "decoder_layers": 6,
+            "max_position_embeddings": 512,
+            "vocab_size": 64044
+        });
+        let trocr_model_config = TrOCRModelConfig::from_json(value).unwrap();
+        assert_eq!(trocr_model_config.hidden_size(), 256);
+        assert_eq!(trocr_model_config.intermediate_size(), 1024);
+        assert_eq!(trocr_model_config.max_position_embeddings(), 512);
+        assert_eq!(trocr_model_config.num_attention_heads(), 8);
+        assert_eq!(trocr_model_config.num_hidden_layers(), 6);
+        assert_eq!(trocr_model_config.vocab_size(), 64044);
+        assert_eq!(
+            trocr_model_config.num_parameters(),
+            6 * (8 * 256 * 256 + 2 * 256 * 1024) + (512 + 64044) * 256
+        );
+        assert_eq!(trocr_model_config.model_type(), "trocr");
+        assert_eq!(
+            trocr_model_config.available_libraries(),
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! Module for the vision encoder-decoder models (e.g. TrOCR, Donut)
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::hub::ModelConfig;
+use crate::models::{
+    Dtype, ModelConfigTrait, ModelError, ModelFamily, ModelLibraries, ParameterBreakdown,
+};
+
+/// A struct representing a vision encoder-decoder model configuration, made of a vision encoder
+/// (e.g. ViT, Swin) and a text decoder (e.g. TrOCR, mBART)
+#[derive(Clone, Debug, Deserialize)]
+pub struct VisionEncoderDecoderModelConfig {
+    /// Vision encoder configuration
+    encoder: Box<ModelConfig>,
+    /// Text decoder configuration
+    decoder: Box<ModelConfig>,
+    /// Vision encoder-decoder model weights precision
+    dtype: Dtype,
+    /// Vision encoder-decoder model type
+    model_type: String,
+    /// Vision encoder-decoder model available libraries
+    available_libraries: Vec<ModelLibraries>,
+}
+
+/// Vision encoder-decoder model implementation
+impl VisionEncoderDecoderModelConfig {
+    /// Build a new `VisionEncoderDecoderModelConfig` struct based on the provided parameters
+    pub fn new(
+        encoder: ModelConfig,
+        decoder: ModelConfig,
+        dtype: Dtype,
+        model_type: String,
+        available_libraries: Vec<ModelLibraries>,
+    ) -> VisionEncoderDecoderModelConfig {
+        VisionEncoderDecoderModelConfig {
+            encoder: Box::new(encoder),
+            decoder: Box::new(decoder),
+            dtype,
+            model_type,
+            available_libraries,
+        }
+    }
+    /// Returns the vision encoder configuration
+    pub fn encoder(&self) -> &ModelConfig {
+        &self.encoder
+    }
+    /// Returns the text decoder configuration
+    pub fn decoder(&self) -> &ModelConfig {
+        &self.decoder
+    }
+}
+
+/// Implementation of the `ModelConfigTrait` trait for `VisionEncoderDecoderModelConfig`.
+///
+/// The flat accessors describe the text decoder, which generates the output tokens, but the
+/// number of layers sums the encoder and the decoder ones as both are held in memory.
+impl ModelConfigTrait for VisionEncoderDecoderModelConfig {
+    fn hidden_size(&self) -> i32 {
+        self.decoder.hidden_size()
+    }
+
+    fn intermediate_size(&self) -> i32 {
+        self.decoder.intermediate_size()
+    }
+
+    fn max_position_embeddings(&self) -> i32 {
+        self.decoder.max_position_embeddings()
+    }
+
+    fn num_attention_heads(&self) -> i32 {
+        self.decoder.num_attention_heads()
+    }
+
+    fn num_hidden_layers(&self) -> i32 {
+        self.encoder.num_hidden_layers() + self.decoder.num_hidden_layers()
+    }
+
+    fn is_encoder_decoder(&self) -> bool {
+        true
+    }
+
+    fn num_encoder_layers(&self) -> i32 {
+        self.encoder.num_hidden_layers()
+    }
+
+    /// An image encoder feeding a text decoder, so the model mixes both modalities
+    fn model_family(&self) -> ModelFamily {
+        ModelFamily::Multimodal
+    }
+
+    fn vocab_size(&self) -> i32 {
+        self.decoder.vocab_size()
+    }
+
+    fn parameter_breakdown(&self) -> ParameterBreakdown {
+        self.encoder.parameter_breakdown() + self.decoder.parameter_breakdown()
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn model_type(&self) -> &str {
+        &self.model_type
+    }
+
+    fn available_libraries(&self) -> &[ModelLibraries] {
+        &self.available_libraries
+    }
+
+    fn from_json(value: Value) -> Result<Self, ModelError> {
+        let encoder = ModelConfig::from_json(value["encoder"].clone())
+            .map_err(|err| err.in_sub_config("encoder"))?;
+
+        let decoder = ModelConfig::from_json(value["decoder"].clone())
+            .map_err(|err| err.in_sub_config("decoder"))?;
+
+        let dtype = Dtype::from_json(&value);
+
+        let model_type = match value["model_type"].as_str() {
+            Some(model_type) => model_type.to_string(),
+            None => return Err(ModelError::MissingField("model_type".to_string())),
+        };
+
+        let available_libraries = ModelLibraries::from_json(&value);
+
+        Ok(VisionEncoderDecoderModelConfig::new(
+            encoder,
+            decoder,
+            dtype,
+            model_type,
+            available_libraries,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TrOCRModelConfig, TrOCRParams, ViTModelConfig, ViTParams};
+    use serde_json::json;
+
+    #[test]
+    fn test_vision_encoder_decoder_model_config() {
+        let encoder = ModelConfig::ViT(ViTModelConfig::new(
+            ViTParams::new(768, 3072, 12, 12, 384, 16, None),
+            Dtype::F32,
+            "vit".to_string(),
+            vec![ModelLibraries::PyTorch],
+        ));
+        let decoder = ModelConfig::TrOCR(TrOCRModelConfig::new(
+            TrOCRParams::new(1024, 4096, 512, 16, 12),
+            64044,
+            true,
+            Dtype::F32,
+            "trocr".to_string(),
+            vec![ModelLibraries::PyTorch],
+        ));
+        let model_config = VisionEncoderDecoderModelConfig::new(
+            encoder,
+            decoder,
+            Dtype::F32,
+            "vision-encoder-decoder".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        assert_eq!(model_config.encoder.hidden_size(), 768);
+        assert_eq!(model_config.decoder.hidden_size(), 1024);
+        assert_eq!(model_config.model_type, "vision-encoder-decoder");
+        assert_eq!(
+            model_config.available_libraries,
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+
+    #[test]
+    fn test_vision_encoder_decoder_model_trait_implementation() {
+        // Donut: a Swin encoder and a standalone mBART decoder
+        let value = json!({
+            "model_type": "vision-encoder-decoder",
+            "encoder": {
+                "model_type": "donut-swin",
+                "depths": [2, 2, 14, 2],
+                "embed_dim": 128,
+                "image_size": [2560, 1920],
+                "num_heads": [4, 8, 16, 32],
+                "patch_size": 4,
+                "window_size": 10
+            },
+            "decoder": {
+                "model_type": "mbart",
+                "is_decoder": true,
+                "d_model": 1024,
+                "decoder_attention_heads": 16,
+                "decoder_ffn_dim": 4096,
+                "decoder_layers": 4,
+                "encoder_attention_heads": 16,
+                "encoder_ffn_dim": 4096,
+                "encoder_layers": 12,
+                "max_position_embeddings": 1536,
+                "vocab_size": 250027
+            }
+        });
+        let model_config = VisionEncoderDecoderModelConfig::from_json(value).unwrap();
+        assert_eq!(model_config.encoder().model_type(), "donut-swin");
+        assert_eq!(model_config.decoder().model_type(), "mbart");
+        assert_eq!(model_config.hidden_size(), 1024);
+        assert_eq!(model_config.intermediate_size(), 4096);
+        assert_eq!(model_config.max_position_embeddings(), 1536);
+        assert_eq!(model_config.num_attention_heads(), 16);
+        assert_eq!(model_config.num_hidden_layers(), 20 + 4);
+        assert_eq!(model_config.num_encoder_layers(), 20);
+        assert_eq!(model_config.num_decoder_layers(), 4);
+        assert_eq!(
+            model_config.num_parameters(),
+            model_config.encoder().num_parameters() + model_config.decoder().num_parameters()
+        );
+        assert_eq!(model_config.model_type(), "vision-encoder-decoder");
+        assert_eq!(
+            model_config.available_libraries(),
+            vec![ModelLibraries::PyTorch]
+        );
+
+        let value = json!({"model_type": "vision-encoder-decoder", "encoder": {}});
+        assert!(VisionEncoderDecoderModelConfig::from_json(value).is_err());
+    }
+}
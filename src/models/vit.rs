@@ -0,0 +1,279 @@
+//! Module for the ViT model (also used for DeiT)
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::models::{Dtype, ModelConfigTrait, ModelError, ModelFamily, ModelLibraries};
+
+/// A struct representing the ViT architecture parameters
+#[derive(Clone, Debug, Deserialize)]
+pub struct ViTParams {
+    /// ViT model hidden_size
+    hidden_size: i32,
+    /// ViT model intermediate_size
+    intermediate_size: i32,
+    /// ViT model num_attention_heads
+    num_attention_heads: i32,
+    /// ViT model num_hidden_layers
+    num_hidden_layers: i32,
+    /// ViT model input image resolution
+    image_size: i32,
+    /// ViT model resolution of each patch
+    patch_size: i32,
+    /// ViT model number of input channels
+    num_channels: i32,
+}
+
+/// ViT model parameters implementation
+impl ViTParams {
+    /// Build a new `ViTParams` struct based on the provided parameters
+    pub fn new(
+        hidden_size: i32,
+        intermediate_size: i32,
+        num_attention_heads: i32,
+        num_hidden_layers: i32,
+        image_size: i32,
+        patch_size: i32,
+        num_channels: Option<i32>,
+    ) -> ViTParams {
+        let num_channels = num_channels.unwrap_or(3);
+        ViTParams {
+            hidden_size,
+            intermediate_size,
+            num_attention_heads,
+            num_hidden_layers,
+            image_size,
+            patch_size,
+            num_channels,
+        }
+    }
+    /// Build from a JSON value
+    pub fn from_json(value: Value) -> Result<ViTParams, ModelError> {
+        let hidden_size = value["hidden_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("hidden_size".to_string()))?
+            as i32;
+
+        let intermediate_size = value["intermediate_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("intermediate_size".to_string()))?
+            as i32;
+
+        let num_attention_heads = value["num_attention_heads"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("num_attention_heads".to_string()))?
+            as i32;
+
+        let num_hidden_layers = value["num_hidden_layers"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("num_hidden_layers".to_string()))?
+            as i32;
+
+        let image_size = value["image_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("image_size".to_string()))?
+            as i32;
+
+        let patch_size = value["patch_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("patch_size".to_string()))?
+            as i32;
+
+        let num_channels = value["num_channels"].as_i64().map(|val| val as i32);
+
+        Ok(ViTParams::new(
+            hidden_size,
+            intermediate_size,
+            num_attention_heads,
+            num_hidden_layers,
+            image_size,
+            patch_size,
+            num_channels,
+        ))
+    }
+}
+
+/// A struct representing a ViT model configuration
+#[derive(Clone, Debug, Deserialize)]
+pub struct ViTModelConfig {
+    /// ViT model parameters
+    params: ViTParams,
+    /// ViT model weights precision
+    dtype: Dtype,
+    /// ViT model type
+    model_type: String,
+    /// ViT model available libraries
+    available_libraries: Vec<ModelLibraries>,
+}
+
+/// ViT model implementation
+impl ViTModelConfig {
+    /// Build a new `ViTModelConfig` struct based on the provided parameters
+    pub fn new(
+        params: ViTParams,
+        dtype: Dtype,
+        model_type: String,
+        available_libraries: Vec<ModelLibraries>,
+    ) -> ViTModelConfig {
+        ViTModelConfig {
+            params,
+            dtype,
+            model_type,
+            available_libraries,
+        }
+    }
+    /// Returns the input image resolution
+    pub fn image_size(&self) -> i32 {
+        self.params.image_size
+    }
+    /// Returns the resolution of each patch
+    pub fn patch_size(&self) -> i32 {
+        self.params.patch_size
+    }
+    /// Returns the number of input channels
+    pub fn num_channels(&self) -> i32 {
+        self.params.num_channels
+    }
+    /// Returns the number of patches the input image is split into
+    pub fn num_patches(&self) -> i32 {
+        (self.params.image_size / self.params.patch_size).pow(2)
+    }
+}
+
+/// Implementation of the `ModelConfigTrait` trait for `ViTModelConfig`
+impl ModelConfigTrait for ViTModelConfig {
+    fn hidden_size(&self) -> i32 {
+        self.params.hidden_size
+    }
+
+    fn intermediate_size(&self) -> i32 {
+        self.params.intermediate_size
+    }
+
+    /// The sequence length is the number of patches plus the `[CLS]` token, DeiT also adds a
+    /// distillation token.
+    fn max_position_embeddings(&self) -> i32 {
+        let special_tokens = if self.model_type == "deit" { 2 } else { 1 };
+        self.num_patches() + special_tokens
+    }
+
+    fn num_attention_heads(&self) -> i32 {
+        self.params.num_attention_heads
+    }
+
+    fn num_hidden_layers(&self) -> i32 {
+        self.params.num_hidden_layers
+    }
+
+    fn model_family(&self) -> ModelFamily {
+        ModelFamily::Vision
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn model_type(&self) -> &str {
+        &self.model_type
+    }
+
+    fn available_libraries(&self) -> &[ModelLibraries] {
+        &self.available_libraries
+    }
+
+    fn from_json(value: Value) -> Result<Self, ModelError> {
+        let params = ViTParams::from_json(value.clone())?;
+
+        let dtype = Dtype::from_json(&value);
+
+        let model_type = match value["model_type"].as_str() {
+            Some(model_type) => model_type.to_string(),
+            None => return Err(ModelError::MissingField("model_type".to_string())),
+        };
+
+        let available_libraries = ModelLibraries::from_json(&value);
+
+        Ok(ViTModelConfig::new(
+            params,
+            dtype,
+            model_type,
+            available_libraries,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_vit_model_params() {
+        let vit_params = ViTParams::new(768, 3072, 12, 12, 224, 16, None);
+
+        assert_eq!(vit_params.hidden_size, 768);
+        assert_eq!(vit_params.intermediate_size, 3072);
+        assert_eq!(vit_params.num_attention_heads, 12);
+        assert_eq!(vit_params.num_hidden_layers, 12);
+        assert_eq!(vit_params.image_size, 224);
+        assert_eq!(vit_params.patch_size, 16);
+        assert_eq!(vit_params.num_channels, 3);
+    }
+
+    #[test]
+    fn test_vit_model_config() {
+        let vit_params = ViTParams::new(768, 3072, 12, 12, 224, 16, None);
+        let vit_model_config = ViTModelConfig::new(
+            vit_params,
+            Dtype::F32,
+            "vit".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        assert_eq!(vit_model_config.params.hidden_size, 768);
+        assert_eq!(vit_model_config.num_patches(), 196);
+        assert_eq!(vit_model_config.model_type, "vit");
+        assert_eq!(
+            vit_model_config.available_libraries,
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+
+    #[test]
+    fn test_vit_model_trait_implementation() {
+        let value = json!({
+            "model_type": "vit",
+            "hidden_size": 1024,
+            "intermediate_size": 4096,
+            "num_attention_heads": 16,
+            "num_hidden_layers": 24,
+            "image_size": 224,
+            "patch_size": 16,
+            "num_channels": 3
+        });
+        let vit_model_config = ViTModelConfig::from_json(value).unwrap();
+        assert_eq!(vit_model_config.hidden_size(), 1024);
+        assert_eq!(vit_model_config.intermediate_size(), 4096);
+        assert_eq!(vit_model_config.max_position_embeddings(), 197);
+        assert_eq!(vit_model_config.num_attention_heads(), 16);
+        assert_eq!(vit_model_config.num_hidden_layers(), 24);
+        assert_eq!(vit_model_config.image_size(), 224);
+        assert_eq!(vit_model_config.patch_size(), 16);
+        assert_eq!(vit_model_config.num_channels(), 3);
+        assert_eq!(vit_model_config.model_type(), "vit");
+        assert_eq!(
+            vit_model_config.available_libraries(),
+            vec![ModelLibraries::PyTorch]
+        );
+
+        let value = json!({
+            "model_type": "deit",
+            "hidden_size": 384,
+            "intermediate_size": 1536,
+            "num_attention_heads": 6,
+            "num_hidden_layers": 12,
+            "image_size": 224,
+            "patch_size": 16
+        });
+        let deit_model_config = ViTModelConfig::from_json(value).unwrap();
+        assert_eq!(deit_model_config.max_position_embeddings(), 198);
+    }
+}
@@ -0,0 +1,266 @@
+//! Module for the Whisper speech recognition model
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::models::{
+    BartParams, Dtype, ModelConfigTrait, ModelError, ModelFamily, ModelLibraries,
+    ParameterBreakdown,
+};
+
+/// A struct representing a Whisper model configuration.
+///
+/// Whisper shares the BART encoder-decoder layout, so its parameters are parsed as `BartParams`,
+/// with the decoder context (`max_target_positions`) as the number of position embeddings.
+#[derive(Clone, Debug, Deserialize)]
+pub struct WhisperModelConfig {
+    /// Whisper model parameters
+    params: BartParams,
+    /// Whisper model max_source_positions, the number of audio frames the encoder attends to
+    max_source_positions: i32,
+    /// Whisper model vocab_size
+    vocab_size: i32,
+    /// Whisper model tie_word_embeddings, whether the LM head shares the token embeddings weights
+    tie_word_embeddings: bool,
+    /// Whisper model weights precision
+    dtype: Dtype,
+    /// Whisper model type
+    model_type: String,
+    /// Whisper model available libraries
+    available_libraries: Vec<ModelLibraries>,
+}
+
+/// Whisper model implementation
+impl WhisperModelConfig {
+    /// Build a new `WhisperModelConfig` struct based on the provided parameters
+    pub fn new(
+        params: BartParams,
+        max_source_positions: i32,
+        vocab_size: i32,
+        tie_word_embeddings: bool,
+        dtype: Dtype,
+        model_type: String,
+        available_libraries: Vec<ModelLibraries>,
+    ) -> WhisperModelConfig {
+        WhisperModelConfig {
+            params,
+            max_source_positions,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
+            model_type,
+            available_libraries,
+        }
+    }
+    /// Returns the number of audio frames the encoder attends to (1500 for 30 seconds of audio)
+    pub fn max_source_positions(&self) -> i32 {
+        self.max_source_positions
+    }
+}
+
+/// Implementation of the `ModelConfigTrait` trait for `WhisperModelConfig`
+impl ModelConfigTrait for WhisperModelConfig {
+    fn hidden_size(&self) -> i32 {
+        self.params.d_model()
+    }
+
+    fn intermediate_size(&self) -> i32 {
+        self.params.encoder_ffn_dim()
+    }
+
+    /// The decoder context, the one the generated tokens are bounded by
+    fn max_position_embeddings(&self) -> i32 {
+        self.params.max_position_embeddings()
+    }
+
+    fn num_attention_heads(&self) -> i32 {
+        self.params.encoder_attention_heads()
+    }
+
+    /// Both the encoder and the decoder layers are held in memory, so they're summed
+    fn num_hidden_layers(&self) -> i32 {
+        self.params.encoder_layers() + self.params.decoder_layers()
+    }
+
+    fn is_encoder_decoder(&self) -> bool {
+        true
+    }
+
+    fn num_encoder_layers(&self) -> i32 {
+        self.params.encoder_layers()
+    }
+
+    fn model_family(&self) -> ModelFamily {
+        ModelFamily::Audio
+    }
+
+    fn vocab_size(&self) -> i32 {
+        self.vocab_size
+    }
+
+    fn tie_word_embeddings(&self) -> bool {
+        self.tie_word_embeddings
+    }
+
+    /// Every decoder layer has a cross-attention on top of its self-attention, and both the
+    /// encoder and the decoder have their own position embeddings. The convolution stem over the
+    /// mel spectrogram is not counted.
+    fn parameter_breakdown(&self) -> ParameterBreakdown {
+        let d_model = self.params.d_model() as i64;
+        let encoder_layers = self.params.encoder_layers() as i64;
+        let decoder_layers = self.params.decoder_layers() as i64;
+        let positions = self.max_source_positions + self.params.max_position_embeddings();
+        ParameterBreakdown::new(
+            (positions + self.vocab_size) as i64 * d_model,
+            (4 * encoder_layers + 8 * decoder_layers) * d_model * d_model,
+            2 * d_model
+                * (self.params.encoder_ffn_dim() as i64 * encoder_layers
+                    + self.params.decoder_ffn_dim() as i64 * decoder_layers),
+            self.lm_head_parameters(),
+            0,
+            self.num_hidden_layers(),
+        )
+    }
+
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn model_type(&self) -> &str {
+        &self.model_type
+    }
+
+    fn available_libraries(&self) -> &[ModelLibraries] {
+        &self.available_libraries
+    }
+
+    fn from_json(value: Value) -> Result<Self, ModelError> {
+        let mut params_value = value.clone();
+        params_value["max_position_embeddings"] = value["max_target_positions"].clone();
+        let params = BartParams::from_json(params_value).map_err(|err| match err {
+            ModelError::MissingField(field) if field == "max_position_embeddings" => {
+                ModelError::MissingField("max_target_positions".to_string())
+            }
+            err => err,
+        })?;
+
+        let max_source_positions = value["max_source_positions"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("max_source_positions".to_string()))?
+            as i32;
+
+        let vocab_size = value["vocab_size"]
+            .as_i64()
+            .ok_or(ModelError::MissingField("vocab_size".to_string()))?
+            as i32;
+
+        let tie_word_embeddings = value["tie_word_embeddings"].as_bool().unwrap_or(true);
+
+        let dtype = Dtype::from_json(&value);
+
+        let model_type = match value["model_type"].as_str() {
+            Some(model_type) => model_type.to_string(),
+            None => return Err(ModelError::MissingField("model_type".to_string())),
+        };
+
+        let available_libraries = ModelLibraries::from_json(&value);
+
+        Ok(WhisperModelConfig::new(
+            params,
+            max_source_positions,
+            vocab_size,
+            tie_word_embeddings,
+            dtype,
+            model_type,
+            available_libraries,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn create_whisper_config() -> Value {
+        json!({
+            "model_type": "whisper",
+            "d_model": 1280,
+            "encoder_layers": 32,
+            "decoder_layers": 32,
+            "encoder_ffn_dim": 5120,
+            "decoder_ffn_dim": 5120,
+            "encoder_attention_heads": 20,
+            "decoder_attention_heads": 20,
+            "max_source_positions": 1500,
+            "max_target_positions": 448,
+            "num_mel_bins": 128,
+            "torch_dtype": "float16",
+            "vocab_size": 51866
+        })
+    }
+
+    #[test]
+    fn test_whisper_model_config() {
+        let whisper_params = BartParams::new(384, 4, 4, 1536, 1536, 6, 448);
+        let whisper_model_config = WhisperModelConfig::new(
+            whisper_params,
+            1500,
+            51865,
+            true,
+            Dtype::F32,
+            "whisper".to_string(),
+            vec![ModelLibraries::PyTorch],
+        );
+        assert_eq!(whisper_model_config.params.d_model(), 384);
+        assert_eq!(whisper_model_config.max_source_positions, 1500);
+        assert_eq!(whisper_model_config.vocab_size, 51865);
+        assert_eq!(whisper_model_config.model_type, "whisper");
+        assert_eq!(
+            whisper_model_config.available_libraries,
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+
+    #[test]
+    fn test_whisper_model_trait_implementation() {
+        let whisper_model_config = WhisperModelConfig::from_json(create_whisper_config()).unwrap();
+        assert_eq!(whisper_model_config.hidden_size(), 1280);
+        assert_eq!(whisper_model_config.intermediate_size(), 5120);
+        assert_eq!(whisper_model_config.max_position_embeddings(), 448);
+        assert_eq!(whisper_model_config.max_source_positions(), 1500);
+        assert_eq!(whisper_model_config.num_attention_heads(), 20);
+        assert_eq!(whisper_model_config.num_hidden_layers(), 64);
+        assert!(whisper_model_config.is_encoder_decoder());
+        assert_eq!(whisper_model_config.num_encoder_layers(), 32);
+        assert_eq!(whisper_model_config.num_decoder_layers(), 32);
+        assert_eq!(whisper_model_config.dtype(), Dtype::F16);
+        assert_eq!(whisper_model_config.model_type(), "whisper");
+        assert_eq!(
+            whisper_model_config.available_libraries(),
+            vec![ModelLibraries::PyTorch]
+        );
+    }
+
+    #[test]
+    fn test_whisper_num_parameters() {
+        // Whisper large-v3, 1.54B parameters
+        let whisper_model_config = WhisperModelConfig::from_json(create_whisper_config()).unwrap();
+        assert_eq!(whisper_model_config.num_parameters(), 1_536_888_320);
+        let breakdown = whisper_model_config.parameter_breakdown();
+        assert_eq!(breakdown.embeddings(), (1500 + 448 + 51866) * 1280);
+        assert_eq!(breakdown.attention(), (32 * 4 + 32 * 8) * 1280 * 1280);
+        assert_eq!(breakdown.mlp(), 64 * 2 * 1280 * 5120);
+        assert_eq!(breakdown.lm_head(), 0);
+        assert_eq!(breakdown.num_layers(), 64);
+
+        let mut value = create_whisper_config();
+        value
+            .as_object_mut()
+            .unwrap()
+            .remove("max_target_positions");
+        match WhisperModelConfig::from_json(value) {
+            Err(ModelError::MissingField(field)) => assert_eq!(field, "max_target_positions"),
+            _ => panic!("expected a missing max_target_positions"),
+        }
+    }
+}
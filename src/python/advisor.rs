@@ -0,0 +1,258 @@
+//! Python bindings of the hardware scan and of the fit checks
+use std::str::FromStr;
+
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::advisor::{check_fit, FitReport, Scenario};
+use crate::estimate::OverheadModel;
+use crate::hardware::{scan_hardware, Hardware};
+use crate::models::Precision;
+use crate::python::PyModelConfig;
+
+/// Python class wrapping the hardware of a system, the running one or a hypothetical one
+#[pyclass(name = "Hardware", module = "aiha")]
+#[derive(Debug)]
+pub struct PyHardware {
+    /// The wrapped hardware
+    hardware: Hardware,
+}
+
+/// PyHardware implementation
+impl PyHardware {
+    /// Returns the wrapped hardware
+    pub fn hardware(&self) -> &Hardware {
+        &self.hardware
+    }
+}
+
+/// Implement the creation of the PyHardware class from a Hardware
+impl From<Hardware> for PyHardware {
+    fn from(hardware: Hardware) -> Self {
+        PyHardware { hardware }
+    }
+}
+
+/// Python methods of the Hardware class
+#[pymethods]
+impl PyHardware {
+    /// Build a hypothetical hardware from a profile, a cloud instance type (e.g. `p4d.24xlarge`)
+    /// or a GPU name with an optional count (e.g. `2x A100-SXM4-80GB`)
+    #[staticmethod]
+    fn from_profile(profile: &str) -> PyResult<Self> {
+        Hardware::from_profile(profile)
+            .map(PyHardware::from)
+            .map_err(PyValueError::new_err)
+    }
+    /// The operating system
+    #[getter]
+    fn os(&self) -> String {
+        self.hardware.os.clone()
+    }
+    /// The architecture of the CPU
+    #[getter]
+    fn arch(&self) -> String {
+        self.hardware.arch.clone()
+    }
+    /// The number of CPU cores
+    #[getter]
+    fn cpu_cores(&self) -> u16 {
+        self.hardware.cpu_cores
+    }
+    /// The number of CPU threads
+    #[getter]
+    fn cpu_threads(&self) -> u16 {
+        self.hardware.cpu_threads
+    }
+    /// The RAM in bytes, 0 when it is unknown
+    #[getter]
+    fn memory(&self) -> u64 {
+        self.hardware.memory
+    }
+    /// The GPUs, as dicts with their name, memory in bytes and compute capability ((0, 0) without
+    /// CUDA)
+    #[getter]
+    fn gpus<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.hardware
+            .gpus()
+            .into_iter()
+            .map(|device| {
+                let gpu = PyDict::new(py);
+                gpu.set_item("name", device.get_name())?;
+                gpu.set_item("memory", device.get_memory_info())?;
+                gpu.set_item("compute_capability", device.get_compute_capability())?;
+                Ok(gpu)
+            })
+            .collect()
+    }
+    fn __repr__(&self) -> String {
+        let gpus: Vec<String> = self
+            .hardware
+            .gpus()
+            .into_iter()
+            .map(|device| device.get_name())
+            .collect();
+        format!(
+            "Hardware(os='{}', arch='{}', gpus={:?})",
+            self.hardware.os, self.hardware.arch, gpus
+        )
+    }
+}
+
+/// Python class wrapping the fit check of a model on a device
+#[pyclass(name = "FitReport", module = "aiha")]
+#[derive(Clone, Debug)]
+pub struct PyFitReport {
+    /// The wrapped fit report
+    report: FitReport,
+}
+
+/// PyFitReport implementation
+impl PyFitReport {
+    /// Returns the wrapped fit report
+    pub fn report(&self) -> &FitReport {
+        &self.report
+    }
+}
+
+/// Implement the creation of the PyFitReport class from a FitReport
+impl From<FitReport> for PyFitReport {
+    fn from(report: FitReport) -> Self {
+        PyFitReport { report }
+    }
+}
+
+/// Python methods of the FitReport class
+#[pymethods]
+impl PyFitReport {
+    /// The name of the device
+    #[getter]
+    fn device(&self) -> String {
+        self.report.device().to_string()
+    }
+    /// The verdict of the check (e.g. `fits` or `requires offload`)
+    #[getter]
+    fn verdict(&self) -> String {
+        self.report.verdict().to_string()
+    }
+    /// The memory the scenario needs, in bytes
+    #[getter]
+    fn required(&self) -> i64 {
+        self.report.required()
+    }
+    /// The memory of the device, in bytes
+    #[getter]
+    fn available(&self) -> i64 {
+        self.report.available()
+    }
+    /// The memory left once the model is loaded, negative when it does not fit, in bytes
+    #[getter]
+    fn headroom(&self) -> i64 {
+        self.report.headroom()
+    }
+    /// The component taking the most memory
+    #[getter]
+    fn limiting_factor(&self) -> String {
+        self.report.limiting_factor().to_string()
+    }
+    /// A warning about the estimate, e.g. a precision the device doesn't support
+    #[getter]
+    fn warning(&self) -> Option<String> {
+        self.report.warning().map(str::to_string)
+    }
+    /// Render the report in JSON, like the Rust API
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string_pretty(&self.report).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+    /// Returns the report as a dict, the explanations of the estimate included
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        py.import("json")?.call_method1("loads", (self.to_json()?,))
+    }
+    fn __repr__(&self) -> String {
+        format!(
+            "FitReport(device='{}', verdict='{}', required={}, available={})",
+            self.report.device(),
+            self.report.verdict(),
+            self.report.required(),
+            self.report.available()
+        )
+    }
+}
+
+/// Scan the hardware of the running system
+#[pyfunction(name = "scan_hardware")]
+pub fn py_scan_hardware() -> PyResult<PyHardware> {
+    scan_hardware()
+        .map(PyHardware::from)
+        .map_err(PyRuntimeError::new_err)
+}
+
+/// Check whether a model fits every GPU of the hardware for generating `batch_size` sequences of
+/// `seq_len` tokens with the weights in `precision` (e.g. `bf16`, `int8` or `awq`)
+#[pyfunction(name = "check_fit")]
+#[pyo3(signature = (hardware, config, precision="bf16", batch_size=1, seq_len=4096))]
+pub fn py_check_fit(
+    hardware: &PyHardware,
+    config: &PyModelConfig,
+    precision: &str,
+    batch_size: i32,
+    seq_len: i32,
+) -> PyResult<Vec<PyFitReport>> {
+    let precision =
+        Precision::from_str(precision).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let scenario = Scenario::Inference {
+        precision,
+        batch_size,
+        seq_len,
+        overhead: OverheadModel::new(),
+        sliding_window: false,
+    };
+    Ok(check_fit(&hardware.hardware, config.config(), scenario)
+        .into_iter()
+        .map(PyFitReport::from)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hub::ModelConfig;
+    use serde_json::json;
+
+    fn create_llama_config() -> PyModelConfig {
+        // Llama 3 8B
+        let content = json!({
+            "model_type": "llama",
+            "hidden_size": 4096,
+            "intermediate_size": 14336,
+            "max_position_embeddings": 8192,
+            "num_attention_heads": 32,
+            "num_hidden_layers": 32,
+            "num_key_value_heads": 8,
+            "vocab_size": 128256
+        });
+        PyModelConfig::from(ModelConfig::from_reader(content.to_string().as_bytes()).unwrap())
+    }
+
+    #[test]
+    fn test_py_hardware_from_profile() {
+        let hardware = PyHardware::from_profile("2x A100-SXM4-80GB").unwrap();
+        assert_eq!(hardware.hardware().nvidia_gpus.len(), 2);
+        assert!(hardware.__repr__().contains("A100-SXM4-80GB"));
+        assert!(PyHardware::from_profile("a toaster").is_err());
+    }
+
+    #[test]
+    fn test_py_check_fit() {
+        let hardware = PyHardware::from_profile("2x A100-SXM4-80GB").unwrap();
+        let reports = py_check_fit(&hardware, &create_llama_config(), "bf16", 1, 4096).unwrap();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].verdict(), "fits");
+        assert_eq!(reports[0].limiting_factor(), "weights");
+        assert!(reports[0].headroom() > 0);
+        let json: serde_json::Value = serde_json::from_str(&reports[0].to_json().unwrap()).unwrap();
+        assert_eq!(json["verdict"], "fits");
+        assert!(py_check_fit(&hardware, &create_llama_config(), "fp7", 1, 4096).is_err());
+    }
+}
@@ -0,0 +1,334 @@
+//! Python bindings of the hub client, the model info and the model configs
+use std::path::PathBuf;
+
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+
+use crate::hub::{BlockingHubClient, HubClient, ModelConfig, ModelInfo};
+use crate::models::ModelConfigTrait;
+
+/// Returns the Python exception of a failed hub call
+fn hub_error(error: String) -> PyErr {
+    PyRuntimeError::new_err(error)
+}
+
+/// Python class wrapping the config of a model
+#[pyclass(name = "ModelConfig", module = "aiha")]
+#[derive(Clone, Debug)]
+pub struct PyModelConfig {
+    /// The wrapped model config
+    config: ModelConfig,
+}
+
+/// PyModelConfig implementation
+impl PyModelConfig {
+    /// Returns the wrapped model config
+    pub fn config(&self) -> &ModelConfig {
+        &self.config
+    }
+}
+
+/// Implement the creation of the PyModelConfig class from a ModelConfig
+impl From<ModelConfig> for PyModelConfig {
+    fn from(config: ModelConfig) -> Self {
+        PyModelConfig { config }
+    }
+}
+
+/// Python methods of the ModelConfig class
+#[pymethods]
+impl PyModelConfig {
+    /// Load a model config from a local `config.json` file
+    #[staticmethod]
+    fn from_file(path: PathBuf) -> PyResult<Self> {
+        ModelConfig::from_file(path)
+            .map(PyModelConfig::from)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+    /// Load a model config from the JSON content of a `config.json` file
+    #[staticmethod]
+    fn from_json(content: &str) -> PyResult<Self> {
+        ModelConfig::from_reader(content.as_bytes())
+            .map(PyModelConfig::from)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+    /// The model type (e.g. `llama`)
+    #[getter]
+    fn model_type(&self) -> String {
+        self.config.model_type().to_string()
+    }
+    /// The number of parameters of the model
+    #[getter]
+    fn num_parameters(&self) -> i64 {
+        self.config.num_parameters()
+    }
+    /// The size of the hidden states
+    #[getter]
+    fn hidden_size(&self) -> i32 {
+        self.config.hidden_size()
+    }
+    /// The number of hidden layers
+    #[getter]
+    fn num_hidden_layers(&self) -> i32 {
+        self.config.num_hidden_layers()
+    }
+    /// The number of attention heads
+    #[getter]
+    fn num_attention_heads(&self) -> i32 {
+        self.config.num_attention_heads()
+    }
+    /// The number of key and value heads
+    #[getter]
+    fn num_key_value_heads(&self) -> i32 {
+        self.config.num_key_value_heads()
+    }
+    /// The size of the vocabulary
+    #[getter]
+    fn vocab_size(&self) -> i32 {
+        self.config.vocab_size()
+    }
+    /// The longest sequence the model was trained on
+    #[getter]
+    fn max_position_embeddings(&self) -> i32 {
+        self.config.max_position_embeddings()
+    }
+    /// Whether the fields were guessed because the model type is not supported
+    #[getter]
+    fn is_low_confidence(&self) -> bool {
+        self.config.is_low_confidence()
+    }
+    fn __repr__(&self) -> String {
+        format!(
+            "ModelConfig(model_type='{}', num_parameters={})",
+            self.config.model_type(),
+            self.config.num_parameters()
+        )
+    }
+}
+
+/// Python class wrapping the info of a model repository
+#[pyclass(name = "ModelInfo", module = "aiha")]
+#[derive(Clone, Debug)]
+pub struct PyModelInfo {
+    /// The wrapped model info
+    info: ModelInfo,
+}
+
+/// PyModelInfo implementation
+impl PyModelInfo {
+    /// Returns the wrapped model info
+    pub fn info(&self) -> &ModelInfo {
+        &self.info
+    }
+}
+
+/// Implement the creation of the PyModelInfo class from a ModelInfo
+impl From<ModelInfo> for PyModelInfo {
+    fn from(info: ModelInfo) -> Self {
+        PyModelInfo { info }
+    }
+}
+
+/// Python methods of the ModelInfo class
+#[pymethods]
+impl PyModelInfo {
+    /// The model ID of the repository
+    #[getter]
+    fn model_id(&self) -> Option<String> {
+        self.info.model_id.clone()
+    }
+    /// The commit SHA of the revision the info was retrieved from
+    #[getter]
+    fn sha(&self) -> Option<String> {
+        self.info.sha.clone()
+    }
+    /// The tags of the repository
+    #[getter]
+    fn tags(&self) -> Option<Vec<String>> {
+        self.info.tags.clone()
+    }
+    /// The pipeline tag of the repository
+    #[getter]
+    fn pipeline_tag(&self) -> Option<String> {
+        self.info.pipeline_tag.clone()
+    }
+    /// The names of the files of the repository
+    #[getter]
+    fn files(&self) -> Vec<String> {
+        self.info
+            .get_siblings()
+            .map(|siblings| siblings.get_sibling_names().into_iter().cloned().collect())
+            .unwrap_or_default()
+    }
+    /// The config of the model, when the repository has a supported one
+    #[getter]
+    fn config(&self) -> Option<PyModelConfig> {
+        self.info.config.clone().map(PyModelConfig::from)
+    }
+    fn __repr__(&self) -> String {
+        format!(
+            "ModelInfo(model_id={:?}, sha={:?})",
+            self.info.model_id, self.info.sha
+        )
+    }
+}
+
+/// Python class wrapping the blocking hub client. The GIL is released while a request runs.
+#[pyclass(name = "HubClient", module = "aiha")]
+pub struct PyHubClient {
+    /// The wrapped hub client
+    client: BlockingHubClient,
+}
+
+/// PyHubClient implementation
+impl PyHubClient {
+    /// Wrap a hub client in a blocking one
+    fn wrap(client: HubClient) -> PyResult<Self> {
+        let client =
+            BlockingHubClient::new(client).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(PyHubClient { client })
+    }
+    /// Returns the wrapped hub client
+    pub fn client(&self) -> &HubClient {
+        self.client.client()
+    }
+}
+
+/// Python methods of the HubClient class
+#[pymethods]
+impl PyHubClient {
+    /// Create a hub client with an optional token, endpoints tried in order and request timeout
+    /// in seconds
+    #[new]
+    #[pyo3(signature = (token=None, endpoints=None, timeout=None))]
+    fn new(
+        token: Option<String>,
+        endpoints: Option<Vec<String>>,
+        timeout: Option<f32>,
+    ) -> PyResult<Self> {
+        let mut client = HubClient::new(token);
+        if let Some(endpoints) = endpoints {
+            client = client.with_endpoints(endpoints);
+        }
+        if let Some(timeout) = timeout {
+            client = client.with_timeout(timeout);
+        }
+        PyHubClient::wrap(client)
+    }
+    /// Create a hub client from the `HF_ENDPOINT` and `HF_TOKEN` environment variables
+    #[staticmethod]
+    fn from_env() -> PyResult<Self> {
+        PyHubClient::wrap(HubClient::from_env())
+    }
+    /// The endpoints the client tries, in order
+    #[getter]
+    fn endpoints(&self) -> Vec<String> {
+        self.client().get_endpoints().to_vec()
+    }
+    /// Retrieve the info of a model repository
+    #[pyo3(signature = (repo_id, revision=None, files_metadata=None))]
+    fn retrieve_model_info(
+        &self,
+        py: Python<'_>,
+        repo_id: &str,
+        revision: Option<&str>,
+        files_metadata: Option<bool>,
+    ) -> PyResult<PyModelInfo> {
+        py.allow_threads(|| {
+            self.client
+                .retrieve_model_info(repo_id, revision, files_metadata)
+                .map_err(|e| e.to_string())
+        })
+        .map(PyModelInfo::from)
+        .map_err(hub_error)
+    }
+    /// Get the config of a model repository, `None` when it has no supported config
+    #[pyo3(signature = (repo_id, revision=None))]
+    fn get_model_config(
+        &self,
+        py: Python<'_>,
+        repo_id: &str,
+        revision: Option<&str>,
+    ) -> PyResult<Option<PyModelConfig>> {
+        py.allow_threads(|| {
+            let mut config = None;
+            self.client
+                .get_model_config(repo_id, revision, None, &mut config)
+                .map(|_| config)
+                .map_err(|e| e.to_string())
+        })
+        .map(|config| config.map(PyModelConfig::from))
+        .map_err(hub_error)
+    }
+    /// Find the kind of a repository (`model`, `dataset` or `space`)
+    fn repo_exists(&self, py: Python<'_>, repo_id: &str) -> PyResult<String> {
+        py.allow_threads(|| {
+            self.client
+                .repo_exists(repo_id)
+                .map(|kind| kind.to_string())
+                .map_err(|e| e.to_string())
+        })
+        .map_err(hub_error)
+    }
+    /// Resolve a revision of a model repository to its full commit SHA
+    #[pyo3(signature = (repo_id, revision=None))]
+    fn resolve_revision(
+        &self,
+        py: Python<'_>,
+        repo_id: &str,
+        revision: Option<&str>,
+    ) -> PyResult<String> {
+        py.allow_threads(|| {
+            self.client
+                .resolve_revision(repo_id, revision)
+                .map(|sha| sha.to_string())
+                .map_err(|e| e.to_string())
+        })
+        .map_err(hub_error)
+    }
+    fn __repr__(&self) -> String {
+        format!("HubClient(endpoints={:?})", self.client().get_endpoints())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_py_model_config_from_json() {
+        let content = json!({
+            "model_type": "llama",
+            "hidden_size": 4096,
+            "intermediate_size": 14336,
+            "max_position_embeddings": 8192,
+            "num_attention_heads": 32,
+            "num_hidden_layers": 32,
+            "num_key_value_heads": 8,
+            "vocab_size": 128256
+        });
+        let config = PyModelConfig::from_json(&content.to_string()).unwrap();
+        assert_eq!(config.model_type(), "llama");
+        assert_eq!(config.num_key_value_heads(), 8);
+        assert_eq!(config.num_parameters(), config.config().num_parameters());
+        assert!(!config.is_low_confidence());
+        assert!(PyModelConfig::from_json("not json").is_err());
+    }
+
+    #[test]
+    fn test_py_hub_client_new() {
+        let client = PyHubClient::new(
+            Some("hf_token".to_string()),
+            Some(vec!["https://hf-mirror.com".to_string()]),
+            Some(5.0),
+        )
+        .unwrap();
+        assert_eq!(client.endpoints(), ["https://hf-mirror.com"]);
+        assert_eq!(client.client().get_token(), Some("hf_token"));
+        assert_eq!(
+            client.__repr__(),
+            "HubClient(endpoints=[\"https://hf-mirror.com\"])"
+        );
+    }
+}
@@ -0,0 +1,21 @@
+//! Module for the Python bindings of AIHA, built with the `python` feature into the `aiha`
+//! extension module of the Python package
+use pyo3::prelude::*;
+
+mod advisor;
+pub use advisor::{py_check_fit, py_scan_hardware, PyFitReport, PyHardware};
+mod hub;
+pub use hub::{PyHubClient, PyModelConfig, PyModelInfo};
+
+/// The `aiha` Python extension module
+#[pymodule]
+fn aiha(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyFitReport>()?;
+    module.add_class::<PyHardware>()?;
+    module.add_class::<PyHubClient>()?;
+    module.add_class::<PyModelConfig>()?;
+    module.add_class::<PyModelInfo>()?;
+    module.add_function(wrap_pyfunction!(py_check_fit, module)?)?;
+    module.add_function(wrap_pyfunction!(py_scan_hardware, module)?)?;
+    Ok(())
+}
@@ -0,0 +1,1610 @@
+//! Module for the reports shared by every surface of the advisor, rendered in markdown, HTML,
+//! JSON or YAML.
+use std::path::Path;
+use std::str::FromStr;
+
+use comfy_table::Cell;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::advisor::{FitReport, FitVerdict, InstanceRecommendation, MemoryComponent, Scenario};
+use crate::estimate::{ServingCost, Throughput};
+use crate::table::{build_table, verdict_cell, TableOptions};
+
+/// Number of bytes in a GiB
+const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+/// Enumerate the formats the reports are rendered in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    /// Markdown, for humans
+    Markdown,
+    /// A standalone HTML document, to share
+    Html,
+    /// Pretty-printed JSON, for scripts and CI pipelines
+    Json,
+    /// YAML, for scripts and CI pipelines
+    Yaml,
+}
+
+/// Build from the name of a format (e.g. `md`, `json` or `yaml`)
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.to_lowercase().as_str() {
+            "markdown" | "md" | "text" => Ok(OutputFormat::Markdown),
+            "html" | "htm" => Ok(OutputFormat::Html),
+            "json" => Ok(OutputFormat::Json),
+            "yaml" | "yml" => Ok(OutputFormat::Yaml),
+            _ => Err(format!("Unknown output format: {}", format)),
+        }
+    }
+}
+
+/// OutputFormat implementation
+impl OutputFormat {
+    /// Returns the format of a file from its extension (e.g. `report.md` or `report.html`),
+    /// `None` for an unknown extension
+    pub fn from_path(path: &Path) -> Option<OutputFormat> {
+        path.extension()?.to_str()?.parse().ok()
+    }
+}
+
+/// Render a markdown report (headings, paragraphs, lists and tables) as a standalone HTML
+/// document
+fn to_html(markdown: &str) -> String {
+    let mut title = String::new();
+    let mut body = String::new();
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if let Some(heading) = line.strip_prefix("# ") {
+            title = escape_html(heading);
+            body.push_str(&format!("<h1>{}</h1>\n", inline_html(heading)));
+        } else if let Some(heading) = line.strip_prefix("## ") {
+            body.push_str(&format!("<h2>{}</h2>\n", inline_html(heading)));
+        } else if line.starts_with('|') {
+            let end = (i..lines.len())
+                .find(|&j| !lines[j].starts_with('|'))
+                .unwrap_or(lines.len());
+            body.push_str(&table_html(&lines[i..end]));
+            i = end;
+            continue;
+        } else if line.starts_with("- ") {
+            body.push_str("<ul>\n");
+            while let Some(item) = lines.get(i).and_then(|line| line.strip_prefix("- ")) {
+                body.push_str(&format!("<li>{}</li>\n", inline_html(item)));
+                i += 1;
+            }
+            body.push_str("</ul>\n");
+            continue;
+        } else if !line.trim().is_empty() {
+            body.push_str(&format!("<p>{}</p>\n", inline_html(line)));
+        }
+        i += 1;
+    }
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>table {{ border-collapse: collapse; }} th, td {{ border: 1px solid #ccc; \
+         padding: 4px 8px; }}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        title, body
+    )
+}
+
+/// Render a markdown table, its header, alignment row and rows, in HTML
+fn table_html(lines: &[&str]) -> String {
+    let cells = |line: &str| -> Vec<String> {
+        line.trim()
+            .trim_matches('|')
+            .split('|')
+            .map(|cell| cell.trim().to_string())
+            .collect()
+    };
+    let header = lines.first().map(|line| cells(line)).unwrap_or_default();
+    let aligns: Vec<&str> = lines
+        .get(1)
+        .map(|line| cells(line))
+        .unwrap_or_default()
+        .iter()
+        .map(|cell| match cell.ends_with(':') {
+            true => " style=\"text-align: right\"",
+            false => "",
+        })
+        .collect();
+    let align = |index: usize| aligns.get(index).copied().unwrap_or_default();
+
+    let mut html = "<table>\n<thead>\n<tr>".to_string();
+    for (index, cell) in header.iter().enumerate() {
+        html.push_str(&format!("<th{}>{}</th>", align(index), inline_html(cell)));
+    }
+    html.push_str("</tr>\n</thead>\n<tbody>\n");
+    for line in lines.iter().skip(2) {
+        html.push_str("<tr>");
+        for (index, cell) in cells(line).iter().enumerate() {
+            html.push_str(&format!("<td{}>{}</td>", align(index), inline_html(cell)));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</tbody>\n</table>\n");
+    html
+}
+
+/// Render a markdown line in HTML, the `**bold**` spans included
+fn inline_html(text: &str) -> String {
+    escape_html(text)
+        .split("**")
+        .enumerate()
+        .map(|(index, span)| match index % 2 {
+            1 => format!("<strong>{}</strong>", span),
+            _ => span.to_string(),
+        })
+        .collect()
+}
+
+/// Escape the characters of a text which have a meaning in HTML
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Render a serialized report in YAML, in block style, the strings being quoted like in JSON
+fn to_yaml(report: &impl Serialize) -> String {
+    let mut yaml = String::new();
+    // The reports only hold strings, numbers and sequences, they can't fail to serialize
+    write_yaml(
+        &serde_json::to_value(report).unwrap_or_default(),
+        0,
+        &mut yaml,
+    );
+    yaml
+}
+
+/// Write the YAML of a value at the given indentation
+fn write_yaml(value: &Value, indent: usize, yaml: &mut String) {
+    let padding = " ".repeat(indent);
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                yaml.push_str(&format!("{}{}:", padding, key));
+                write_yaml_nested(value, indent, yaml);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for item in items {
+                yaml.push_str(&format!("{}-", padding));
+                write_yaml_nested(item, indent, yaml);
+            }
+        }
+        scalar => yaml.push_str(&format!("{}{}\n", padding, yaml_scalar(scalar))),
+    }
+}
+
+/// Write the YAML of a mapping value or a sequence item, on the next lines when it is a
+/// non-empty collection
+fn write_yaml_nested(value: &Value, indent: usize, yaml: &mut String) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            yaml.push('\n');
+            write_yaml(value, indent + 2, yaml);
+        }
+        Value::Array(items) if !items.is_empty() => {
+            yaml.push('\n');
+            write_yaml(value, indent + 2, yaml);
+        }
+        scalar => yaml.push_str(&format!(" {}\n", yaml_scalar(scalar))),
+    }
+}
+
+/// Returns the YAML of a scalar or an empty collection
+fn yaml_scalar(value: &Value) -> String {
+    match value {
+        Value::Array(_) => "[]".to_string(),
+        Value::Object(_) => "{}".to_string(),
+        scalar => scalar.to_string(),
+    }
+}
+
+/// A struct representing the section of a report about a single device
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DeviceSection {
+    /// The fit of the model on the device
+    #[serde(flatten)]
+    fit: FitReport,
+    /// The expected generation speed of the model on the device, when it is known
+    throughput: Option<Throughput>,
+    /// The cost of serving the model on the device, when it is known
+    serving_cost: Option<ServingCost>,
+}
+
+/// DeviceSection implementation
+impl DeviceSection {
+    /// Build a new `DeviceSection` struct based on the provided parameters
+    pub fn new(
+        fit: FitReport,
+        throughput: Option<Throughput>,
+        serving_cost: Option<ServingCost>,
+    ) -> DeviceSection {
+        DeviceSection {
+            fit,
+            throughput,
+            serving_cost,
+        }
+    }
+    /// Returns the fit of the model on the device
+    pub fn fit(&self) -> &FitReport {
+        &self.fit
+    }
+    /// Returns the expected generation speed of the model on the device
+    pub fn throughput(&self) -> Option<Throughput> {
+        self.throughput
+    }
+    /// Returns the cost of serving the model on the device
+    pub fn serving_cost(&self) -> Option<ServingCost> {
+        self.serving_cost
+    }
+}
+
+/// A struct representing the report of a model on the hardware, with one section per device, the
+/// assumptions the estimates rely on and the warnings for the user
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Report {
+    /// The name of the model
+    model: String,
+    /// The description of the scenario
+    scenario: String,
+    /// The sections of the devices
+    devices: Vec<DeviceSection>,
+    /// The assumptions the estimates rely on
+    assumptions: Vec<String>,
+    /// The warnings for the user
+    warnings: Vec<String>,
+}
+
+/// Report implementation
+impl Report {
+    /// Build a new `Report` struct based on the provided parameters
+    pub fn new(
+        model: String,
+        scenario: String,
+        devices: Vec<DeviceSection>,
+        assumptions: Vec<String>,
+        warnings: Vec<String>,
+    ) -> Report {
+        Report {
+            model,
+            scenario,
+            devices,
+            assumptions,
+            warnings,
+        }
+    }
+    /// Build the report of the fit checks of a model, with the assumptions of the estimates and a
+    /// warning for every device where the model does not fit comfortably
+    pub fn from_fit_reports(model: &str, scenario: &Scenario, reports: Vec<FitReport>) -> Report {
+        let devices: Vec<DeviceSection> = reports
+            .into_iter()
+            .map(|report| DeviceSection::new(report, None, None))
+            .collect();
+        Report::from_devices(model, scenario, devices)
+    }
+    /// Build the report of a model from the sections of the devices, with the assumptions of the
+    /// estimates and a warning for every device where the model does not fit comfortably
+    pub fn from_devices(model: &str, scenario: &Scenario, devices: Vec<DeviceSection>) -> Report {
+        let overhead = scenario.overhead();
+        let mut assumptions = vec![
+            format!(
+                "The framework overhead is {} MiB plus {}% of the weights and {}% of the KV cache \
+                 and activations",
+                overhead.cuda_context / 1024 / 1024,
+                overhead.allocator_headroom * 100.0,
+                overhead.fragmentation * 100.0
+            ),
+            format!(
+                "A model fits when {}% of the device memory is left for the memory spikes",
+                overhead.safety_margin * 100.0
+            ),
+        ];
+        let mut warnings = Vec::new();
+        if devices.is_empty() {
+            warnings.push("No GPU was found, the model would run on the CPU".to_string());
+        }
+        for section in &devices {
+            let fit = section.fit();
+            warnings.extend(fit.warning().map(str::to_string));
+            let fits = matches!(fit.verdict(), FitVerdict::Fits | FitVerdict::Tight);
+            if let (true, Some(quality_loss)) = (fits, fit.quality_loss()) {
+                assumptions.push(format!(
+                    "{}: fits in {} with {} against FP16 ({})",
+                    fit.device(),
+                    scenario.precision(),
+                    quality_loss,
+                    quality_loss.source()
+                ));
+            }
+            let warning = match fit.verdict() {
+                FitVerdict::Fits => continue,
+                FitVerdict::Tight => format!(
+                    "{}: less than {}% of the memory is left, a memory spike may run out of memory",
+                    fit.device(),
+                    overhead.safety_margin * 100.0
+                ),
+                FitVerdict::RequiresOffload => format!(
+                    "{}: the weights have to be offloaded to the CPU, expect a much slower run",
+                    fit.device()
+                ),
+                FitVerdict::DoesNotFit => format!(
+                    "{}: the model does not fit, even with offloading ({} is the largest)",
+                    fit.device(),
+                    fit.limiting_factor()
+                ),
+            };
+            warnings.push(warning);
+        }
+        Report::new(
+            model.to_string(),
+            scenario.to_string(),
+            devices,
+            assumptions,
+            warnings,
+        )
+    }
+    /// Returns the same report with another assumption the estimates rely on
+    pub fn with_assumption(mut self, assumption: String) -> Report {
+        self.assumptions.push(assumption);
+        self
+    }
+    /// Returns the name of the model
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+    /// Returns the description of the scenario
+    pub fn scenario(&self) -> &str {
+        &self.scenario
+    }
+    /// Returns the sections of the devices
+    pub fn devices(&self) -> &[DeviceSection] {
+        &self.devices
+    }
+    /// Returns the assumptions the estimates rely on
+    pub fn assumptions(&self) -> &[String] {
+        &self.assumptions
+    }
+    /// Returns the warnings for the user
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+    /// Render the report in JSON
+    pub fn to_json(&self) -> String {
+        // The report only holds strings, numbers and sequences, it can't fail to serialize
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+    /// Render the report in YAML
+    pub fn to_yaml(&self) -> String {
+        to_yaml(self)
+    }
+    /// Render the report as a standalone HTML document
+    pub fn to_html(&self) -> String {
+        to_html(&self.to_markdown())
+    }
+    /// Render the report in the given format
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Markdown => self.to_markdown(),
+            OutputFormat::Html => self.to_html(),
+            OutputFormat::Json => self.to_json(),
+            OutputFormat::Yaml => self.to_yaml(),
+        }
+    }
+    /// Render the report in markdown, with a table of the devices
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("# {}\n\n**Scenario:** {}\n\n", self.model, self.scenario);
+
+        markdown.push_str(concat!(
+            "| Device | Verdict | Required | Available | Headroom | Limiting factor | Decode |",
+            " Cost / 1M output tokens |\n",
+            "|---|---|---:|---:|---:|---|---:|---:|\n",
+        ));
+        for section in &self.devices {
+            let fit = section.fit();
+            let decode = match section.throughput() {
+                Some(throughput) => {
+                    format!("{:.1} tokens/s", throughput.decode_tokens_per_second())
+                }
+                None => "-".to_string(),
+            };
+            let cost = match section.serving_cost() {
+                Some(cost) => format!("${:.2}", cost.output_per_million()),
+                None => "-".to_string(),
+            };
+            markdown.push_str(&format!(
+                "| {} | {} | {:.2} GiB | {:.2} GiB | {:.2} GiB | {} | {} | {} |\n",
+                fit.device(),
+                fit.verdict(),
+                fit.required() as f64 / GIB,
+                fit.available() as f64 / GIB,
+                fit.headroom() as f64 / GIB,
+                fit.limiting_factor(),
+                decode,
+                cost
+            ));
+        }
+
+        for (title, items) in [
+            ("Assumptions", &self.assumptions),
+            ("Warnings", &self.warnings),
+        ] {
+            if items.is_empty() {
+                continue;
+            }
+            markdown.push_str(&format!("\n## {}\n\n", title));
+            for item in items {
+                markdown.push_str(&format!("- {}\n", item));
+            }
+        }
+        markdown
+    }
+}
+
+/// A struct representing a model in a comparison
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ComparisonRow {
+    /// The name of the model
+    model: String,
+    /// The number of parameters of the model
+    parameters: i64,
+    /// The memory the scenario needs, in bytes
+    required: i64,
+    /// The memory the scenario needs with FP16 weights, in bytes
+    fp16_required: i64,
+    /// The memory the scenario needs with 4-bit (GPTQ) weights, in bytes
+    int4_required: i64,
+    /// The longest context the model reaches, for inference
+    max_context: Option<i32>,
+    /// The expected generation speed of the model, for inference
+    throughput: Option<Throughput>,
+    /// The verdict of the fit check, when there is a device
+    verdict: Option<FitVerdict>,
+}
+
+/// ComparisonRow implementation
+impl ComparisonRow {
+    /// Build a new `ComparisonRow` struct based on the provided parameters
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        model: String,
+        parameters: i64,
+        required: i64,
+        fp16_required: i64,
+        int4_required: i64,
+        max_context: Option<i32>,
+        throughput: Option<Throughput>,
+        verdict: Option<FitVerdict>,
+    ) -> ComparisonRow {
+        ComparisonRow {
+            model,
+            parameters,
+            required,
+            fp16_required,
+            int4_required,
+            max_context,
+            throughput,
+            verdict,
+        }
+    }
+    /// Returns the name of the model
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+    /// Returns the number of parameters of the model
+    pub fn parameters(&self) -> i64 {
+        self.parameters
+    }
+    /// Returns the memory the scenario needs
+    pub fn required(&self) -> i64 {
+        self.required
+    }
+    /// Returns the memory the scenario needs with FP16 weights
+    pub fn fp16_required(&self) -> i64 {
+        self.fp16_required
+    }
+    /// Returns the memory the scenario needs with 4-bit weights
+    pub fn int4_required(&self) -> i64 {
+        self.int4_required
+    }
+    /// Returns the longest context the model reaches
+    pub fn max_context(&self) -> Option<i32> {
+        self.max_context
+    }
+    /// Returns the expected generation speed of the model
+    pub fn throughput(&self) -> Option<Throughput> {
+        self.throughput
+    }
+    /// Returns the verdict of the fit check
+    pub fn verdict(&self) -> Option<FitVerdict> {
+        self.verdict
+    }
+}
+
+/// A struct representing models compared side by side on a device
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Comparison {
+    /// The description of the scenario
+    scenario: String,
+    /// The name of the device the models are compared on, if any
+    device: Option<String>,
+    /// The models, in the order they were given
+    rows: Vec<ComparisonRow>,
+}
+
+/// Comparison implementation
+impl Comparison {
+    /// Build a new `Comparison` struct based on the provided parameters
+    pub fn new(scenario: String, device: Option<String>, rows: Vec<ComparisonRow>) -> Comparison {
+        Comparison {
+            scenario,
+            device,
+            rows,
+        }
+    }
+    /// Returns the description of the scenario
+    pub fn scenario(&self) -> &str {
+        &self.scenario
+    }
+    /// Returns the name of the device the models are compared on
+    pub fn device(&self) -> Option<&str> {
+        self.device.as_deref()
+    }
+    /// Returns the models
+    pub fn rows(&self) -> &[ComparisonRow] {
+        &self.rows
+    }
+    /// Render the comparison in JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+    /// Render the comparison in YAML
+    pub fn to_yaml(&self) -> String {
+        to_yaml(self)
+    }
+    /// Render the comparison as a standalone HTML document
+    pub fn to_html(&self) -> String {
+        to_html(&self.to_markdown())
+    }
+    /// Render the comparison in the given format
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Markdown => self.to_markdown(),
+            OutputFormat::Html => self.to_html(),
+            OutputFormat::Json => self.to_json(),
+            OutputFormat::Yaml => self.to_yaml(),
+        }
+    }
+    /// Render the comparison in markdown, one row per model
+    pub fn to_markdown(&self) -> String {
+        let title = match &self.device {
+            Some(device) => format!("Comparison on {}", device),
+            None => "Comparison".to_string(),
+        };
+        let mut markdown = format!("# {}\n\n**Scenario:** {}\n\n", title, self.scenario);
+        markdown.push_str(
+            "| Model | Params | Memory | FP16 | INT4 | Max context | Decode | Verdict |\n",
+        );
+        markdown.push_str("|---|---:|---:|---:|---:|---:|---:|---|\n");
+        for row in &self.rows {
+            let max_context = match row.max_context() {
+                Some(max_context) => max_context.to_string(),
+                None => "-".to_string(),
+            };
+            let decode = match row.throughput() {
+                Some(throughput) => {
+                    format!("{:.1} tokens/s", throughput.decode_tokens_per_second())
+                }
+                None => "-".to_string(),
+            };
+            let verdict = match row.verdict() {
+                Some(verdict) => verdict.to_string(),
+                None => "-".to_string(),
+            };
+            markdown.push_str(&format!(
+                "| {} | {:.1}B | {:.2} GiB | {:.2} GiB | {:.2} GiB | {} | {} | {} |\n",
+                row.model(),
+                row.parameters() as f64 / 1e9,
+                row.required() as f64 / GIB,
+                row.fp16_required() as f64 / GIB,
+                row.int4_required() as f64 / GIB,
+                max_context,
+                decode,
+                verdict
+            ));
+        }
+        markdown
+    }
+    /// Render the comparison as a terminal table, one row per model
+    pub fn to_table(&self, options: &TableOptions) -> String {
+        let title = match &self.device {
+            Some(device) => format!("Comparison on {}", device),
+            None => "Comparison".to_string(),
+        };
+        let mut table = build_table(
+            options,
+            &[
+                "Model",
+                "Params",
+                "Memory",
+                "FP16",
+                "INT4",
+                "Max context",
+                "Decode",
+                "Verdict",
+            ],
+            &[1, 2, 3, 4, 5, 6],
+        );
+        for row in &self.rows {
+            let max_context = match row.max_context() {
+                Some(max_context) => max_context.to_string(),
+                None => "-".to_string(),
+            };
+            let decode = match row.throughput() {
+                Some(throughput) => {
+                    format!("{:.1} tokens/s", throughput.decode_tokens_per_second())
+                }
+                None => "-".to_string(),
+            };
+            table.add_row(vec![
+                Cell::new(row.model()),
+                Cell::new(format!("{:.1}B", row.parameters() as f64 / 1e9)),
+                Cell::new(format!("{:.2} GiB", row.required() as f64 / GIB)),
+                Cell::new(format!("{:.2} GiB", row.fp16_required() as f64 / GIB)),
+                Cell::new(format!("{:.2} GiB", row.int4_required() as f64 / GIB)),
+                Cell::new(max_context),
+                Cell::new(decode),
+                verdict_cell(row.verdict(), options),
+            ]);
+        }
+        format!("{}\nScenario: {}\n\n{}\n", title, self.scenario, table)
+    }
+}
+
+/// A struct representing a cloud instance able to serve a model
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CloudRow {
+    /// The name of the instance type
+    instance: String,
+    /// The cloud provider
+    provider: String,
+    /// The GPUs the model is split across, e.g. `2 × NVIDIA L4`
+    device: String,
+    /// The memory left on every GPU once the model is loaded, in bytes
+    headroom: i64,
+    /// The on-demand price of the instance, in US dollars per hour
+    hourly_price: f64,
+    /// The expected generation speed on the instance
+    throughput: Throughput,
+    /// The cost of a million prompt and generated tokens
+    serving_cost: ServingCost,
+}
+
+/// CloudRow implementation
+impl CloudRow {
+    /// Build the row of a recommended instance
+    pub fn from_recommendation(recommendation: &InstanceRecommendation) -> CloudRow {
+        let instance = recommendation.instance();
+        CloudRow {
+            instance: instance.name().to_string(),
+            provider: instance.provider().to_string(),
+            device: recommendation.report().device().to_string(),
+            headroom: recommendation.report().headroom(),
+            hourly_price: instance.hourly_price(),
+            throughput: recommendation.throughput(),
+            serving_cost: recommendation.serving_cost(),
+        }
+    }
+    /// Returns the name of the instance type
+    pub fn instance(&self) -> &str {
+        &self.instance
+    }
+    /// Returns the cloud provider
+    pub fn provider(&self) -> &str {
+        &self.provider
+    }
+    /// Returns the GPUs the model is split across
+    pub fn device(&self) -> &str {
+        &self.device
+    }
+    /// Returns the memory left on every GPU once the model is loaded
+    pub fn headroom(&self) -> i64 {
+        self.headroom
+    }
+    /// Returns the on-demand price of the instance per hour
+    pub fn hourly_price(&self) -> f64 {
+        self.hourly_price
+    }
+    /// Returns the expected generation speed on the instance
+    pub fn throughput(&self) -> Throughput {
+        self.throughput
+    }
+    /// Returns the cost of a million prompt and generated tokens
+    pub fn serving_cost(&self) -> ServingCost {
+        self.serving_cost
+    }
+}
+
+/// A struct representing the cloud instances able to serve a model for a workload, from the
+/// cheapest per hour to the most expensive
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CloudReport {
+    /// The name of the model
+    model: String,
+    /// The description of the workload
+    scenario: String,
+    /// The instances
+    rows: Vec<CloudRow>,
+}
+
+/// CloudReport implementation
+impl CloudReport {
+    /// Build a new `CloudReport` struct based on the provided parameters
+    pub fn new(model: String, scenario: String, rows: Vec<CloudRow>) -> CloudReport {
+        CloudReport {
+            model,
+            scenario,
+            rows,
+        }
+    }
+    /// Returns the name of the model
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+    /// Returns the description of the workload
+    pub fn scenario(&self) -> &str {
+        &self.scenario
+    }
+    /// Returns the instances
+    pub fn rows(&self) -> &[CloudRow] {
+        &self.rows
+    }
+    /// Render the instances in JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+    /// Render the instances in YAML
+    pub fn to_yaml(&self) -> String {
+        to_yaml(self)
+    }
+    /// Render the instances as a standalone HTML document
+    pub fn to_html(&self) -> String {
+        to_html(&self.to_markdown())
+    }
+    /// Render the instances in the given format
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Markdown => self.to_markdown(),
+            OutputFormat::Html => self.to_html(),
+            OutputFormat::Json => self.to_json(),
+            OutputFormat::Yaml => self.to_yaml(),
+        }
+    }
+    /// Render the instances in markdown, one row per instance
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!(
+            "# Cloud instances for {}\n\n**Scenario:** {}\n\n",
+            self.model, self.scenario
+        );
+        if self.rows.is_empty() {
+            markdown.push_str("No instance serves the model for this workload.\n");
+            return markdown;
+        }
+        markdown.push_str(
+            "| Instance | Provider | GPUs | Headroom | Price / hour | Decode | \
+             Cost / 1M output tokens |\n",
+        );
+        markdown.push_str("|---|---|---|---:|---:|---:|---:|\n");
+        for row in &self.rows {
+            markdown.push_str(&format!(
+                "| {} | {} | {} | {:.2} GiB | ${:.2} | {:.1} tokens/s | ${:.2} |\n",
+                row.instance,
+                row.provider,
+                row.device,
+                row.headroom as f64 / GIB,
+                row.hourly_price,
+                row.throughput.decode_tokens_per_second(),
+                row.serving_cost.output_per_million()
+            ));
+        }
+        markdown
+    }
+    /// Render the instances as a terminal table, one row per instance
+    pub fn to_table(&self, options: &TableOptions) -> String {
+        let title = format!(
+            "Cloud instances for {}\nScenario: {}\n\n",
+            self.model, self.scenario
+        );
+        if self.rows.is_empty() {
+            return format!("{}No instance serves the model for this workload.\n", title);
+        }
+        let mut table = build_table(
+            options,
+            &[
+                "Instance",
+                "Provider",
+                "GPUs",
+                "Headroom",
+                "Price / hour",
+                "Decode",
+                "Cost / 1M output tokens",
+            ],
+            &[3, 4, 5, 6],
+        );
+        for row in &self.rows {
+            table.add_row(vec![
+                row.instance.clone(),
+                row.provider.clone(),
+                row.device.clone(),
+                format!("{:.2} GiB", row.headroom as f64 / GIB),
+                format!("${:.2}", row.hourly_price),
+                format!("{:.1} tokens/s", row.throughput.decode_tokens_per_second()),
+                format!("${:.2}", row.serving_cost.output_per_million()),
+            ]);
+        }
+        format!("{}{}\n", title, table)
+    }
+}
+
+/// Format a duration in seconds with the unit its size calls for (e.g. `45 min` or `3.2 days`)
+fn format_duration(seconds: f64) -> String {
+    match seconds {
+        s if s < 60.0 => format!("{:.0} s", s),
+        s if s < 3600.0 => format!("{:.0} min", s / 60.0),
+        s if s < 86400.0 => format!("{:.1} h", s / 3600.0),
+        s => format!("{:.1} days", s / 86400.0),
+    }
+}
+
+/// A struct representing the estimate of a training run: the memory every GPU needs, the time to
+/// go through the dataset and its cost
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TrainingReport {
+    /// The name of the model
+    model: String,
+    /// The description of the training scenario
+    scenario: String,
+    /// The number of GPUs the run is spread over
+    num_gpus: i32,
+    /// The fit of the run on every GPU
+    fit: FitReport,
+    /// The memory of every component on every GPU, in bytes
+    components: Vec<(MemoryComponent, i64)>,
+    /// The number of tokens the run trains on
+    tokens: i64,
+    /// The time the run takes, in seconds
+    time: f64,
+    /// The cost of an hour of a single GPU, in US dollars
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gpu_hourly_cost: Option<f64>,
+    /// The assumptions the estimates rely on
+    assumptions: Vec<String>,
+}
+
+/// TrainingReport implementation
+impl TrainingReport {
+    /// Build a new `TrainingReport` struct based on the provided parameters
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        model: String,
+        scenario: String,
+        num_gpus: i32,
+        fit: FitReport,
+        components: Vec<(MemoryComponent, i64)>,
+        tokens: i64,
+        time: f64,
+        gpu_hourly_cost: Option<f64>,
+    ) -> TrainingReport {
+        TrainingReport {
+            model,
+            scenario,
+            num_gpus,
+            fit,
+            components,
+            tokens,
+            time,
+            gpu_hourly_cost,
+            assumptions: Vec::new(),
+        }
+    }
+    /// Returns the same report with one more assumption
+    pub fn with_assumption(mut self, assumption: String) -> TrainingReport {
+        self.assumptions.push(assumption);
+        self
+    }
+    /// Returns the name of the model
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+    /// Returns the description of the training scenario
+    pub fn scenario(&self) -> &str {
+        &self.scenario
+    }
+    /// Returns the number of GPUs the run is spread over
+    pub fn num_gpus(&self) -> i32 {
+        self.num_gpus
+    }
+    /// Returns the fit of the run on every GPU
+    pub fn fit(&self) -> &FitReport {
+        &self.fit
+    }
+    /// Returns the memory of every component on every GPU
+    pub fn components(&self) -> &[(MemoryComponent, i64)] {
+        &self.components
+    }
+    /// Returns the number of tokens the run trains on
+    pub fn tokens(&self) -> i64 {
+        self.tokens
+    }
+    /// Returns the time the run takes, in seconds
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+    /// Returns the number of GPU hours the run takes
+    pub fn gpu_hours(&self) -> f64 {
+        self.time / 3600.0 * self.num_gpus as f64
+    }
+    /// Returns the cost of the run in US dollars, when the GPUs have a price
+    pub fn cost(&self) -> Option<f64> {
+        self.gpu_hourly_cost.map(|price| price * self.gpu_hours())
+    }
+    /// Returns the assumptions the estimates rely on
+    pub fn assumptions(&self) -> &[String] {
+        &self.assumptions
+    }
+    /// Render the estimate in JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+    /// Render the estimate in YAML
+    pub fn to_yaml(&self) -> String {
+        to_yaml(self)
+    }
+    /// Render the estimate as a standalone HTML document
+    pub fn to_html(&self) -> String {
+        to_html(&self.to_markdown())
+    }
+    /// Render the estimate in the given format
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Markdown => self.to_markdown(),
+            OutputFormat::Html => self.to_html(),
+            OutputFormat::Json => self.to_json(),
+            OutputFormat::Yaml => self.to_yaml(),
+        }
+    }
+    /// Render the estimate in markdown, with a table of the memory and one of the time and cost
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!(
+            "# Training {}\n\n**Scenario:** {}\n\n**Hardware:** {} × {}\n\n",
+            self.model,
+            self.scenario,
+            self.num_gpus,
+            self.fit.device()
+        );
+        markdown.push_str("## Memory per GPU\n\n| Component | Memory |\n|---|---:|\n");
+        for (component, bytes) in &self.components {
+            markdown.push_str(&format!(
+                "| {} | {:.2} GiB |\n",
+                component,
+                *bytes as f64 / GIB
+            ));
+        }
+        markdown.push_str(&format!(
+            "| **Total** | **{:.2} GiB** |\n\n**Verdict:** {} ({:.2} GiB available)\n\n",
+            self.fit.required() as f64 / GIB,
+            self.fit.verdict(),
+            self.fit.available() as f64 / GIB
+        ));
+        let cost = match self.cost() {
+            Some(cost) => format!("${:.2}", cost),
+            None => "-".to_string(),
+        };
+        markdown.push_str("## Time and cost\n\n| Tokens | Time | GPU hours | Cost |\n");
+        markdown.push_str("|---:|---:|---:|---:|\n");
+        markdown.push_str(&format!(
+            "| {} | {} | {:.1} | {} |\n",
+            self.tokens,
+            format_duration(self.time),
+            self.gpu_hours(),
+            cost
+        ));
+        if !self.assumptions.is_empty() {
+            markdown.push_str("\n## Assumptions\n\n");
+            for assumption in &self.assumptions {
+                markdown.push_str(&format!("- {}\n", assumption));
+            }
+        }
+        markdown
+    }
+}
+
+/// A struct representing a model of a batch check, with its report or the error it failed with
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BatchEntry {
+    /// The name of the model
+    model: String,
+    /// The report of the model, `None` when it couldn't be checked
+    report: Option<Report>,
+    /// The error the check failed with
+    error: Option<String>,
+}
+
+/// BatchEntry implementation
+impl BatchEntry {
+    /// Build the entry of a checked model
+    pub fn checked(report: Report) -> BatchEntry {
+        BatchEntry {
+            model: report.model().to_string(),
+            report: Some(report),
+            error: None,
+        }
+    }
+    /// Build the entry of a model whose check failed
+    pub fn failed(model: &str, error: String) -> BatchEntry {
+        BatchEntry {
+            model: model.to_string(),
+            report: None,
+            error: Some(error),
+        }
+    }
+    /// Returns the name of the model
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+    /// Returns the report of the model
+    pub fn report(&self) -> Option<&Report> {
+        self.report.as_ref()
+    }
+    /// Returns the error the check failed with
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+    /// Returns the device the model runs best on, the one with the best verdict and then the most
+    /// headroom
+    pub fn best_device(&self) -> Option<&FitReport> {
+        self.report
+            .as_ref()?
+            .devices()
+            .iter()
+            .map(DeviceSection::fit)
+            .min_by_key(|fit| (fit.verdict() as u8, -fit.headroom()))
+    }
+}
+
+/// A struct representing the reports of a list of models checked at once, e.g. to audit a model
+/// catalog
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BatchReport {
+    /// The description of the scenario
+    scenario: String,
+    /// The models, in the order they were given
+    entries: Vec<BatchEntry>,
+}
+
+/// BatchReport implementation
+impl BatchReport {
+    /// Build a new `BatchReport` struct based on the provided parameters
+    pub fn new(scenario: String, entries: Vec<BatchEntry>) -> BatchReport {
+        BatchReport { scenario, entries }
+    }
+    /// Returns the description of the scenario
+    pub fn scenario(&self) -> &str {
+        &self.scenario
+    }
+    /// Returns the models
+    pub fn entries(&self) -> &[BatchEntry] {
+        &self.entries
+    }
+    /// Returns the number of models whose check failed
+    pub fn failed(&self) -> usize {
+        self.entries.iter().filter(|e| e.error.is_some()).count()
+    }
+    /// Render the reports in JSON, every model with its whole report
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+    /// Render the reports in YAML
+    pub fn to_yaml(&self) -> String {
+        to_yaml(self)
+    }
+    /// Render the summary as a standalone HTML document
+    pub fn to_html(&self) -> String {
+        to_html(&self.to_markdown())
+    }
+    /// Render the reports in the given format
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Markdown => self.to_markdown(),
+            OutputFormat::Html => self.to_html(),
+            OutputFormat::Json => self.to_json(),
+            OutputFormat::Yaml => self.to_yaml(),
+        }
+    }
+    /// Render the summary in markdown, one row per model with its best device, and the errors
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("# Batch check\n\n**Scenario:** {}\n\n", self.scenario);
+        markdown.push_str("| Model | Best device | Verdict | Required | Available |\n");
+        markdown.push_str("|---|---|---|---:|---:|\n");
+        for entry in &self.entries {
+            let row = match (entry.best_device(), &entry.error) {
+                (Some(fit), _) => format!(
+                    "| {} | {} | {} | {:.2} GiB | {:.2} GiB |\n",
+                    entry.model,
+                    fit.device(),
+                    fit.verdict(),
+                    fit.required() as f64 / GIB,
+                    fit.available() as f64 / GIB
+                ),
+                (None, Some(_)) => format!("| {} | - | error | - | - |\n", entry.model),
+                (None, None) => format!("| {} | - | no GPU | - | - |\n", entry.model),
+            };
+            markdown.push_str(&row);
+        }
+
+        if self.failed() > 0 {
+            markdown.push_str("\n## Errors\n\n");
+            for entry in &self.entries {
+                if let Some(error) = &entry.error {
+                    markdown.push_str(&format!("- {}: {}\n", entry.model, error));
+                }
+            }
+        }
+        markdown
+    }
+    /// Render the summary as a terminal table, one row per model with its best device, followed
+    /// by the errors
+    pub fn to_table(&self, options: &TableOptions) -> String {
+        let mut table = build_table(
+            options,
+            &["Model", "Best device", "Verdict", "Required", "Available"],
+            &[3, 4],
+        );
+        for entry in &self.entries {
+            let row = match (entry.best_device(), &entry.error) {
+                (Some(fit), _) => vec![
+                    Cell::new(&entry.model),
+                    Cell::new(fit.device()),
+                    verdict_cell(Some(fit.verdict()), options),
+                    Cell::new(format!("{:.2} GiB", fit.required() as f64 / GIB)),
+                    Cell::new(format!("{:.2} GiB", fit.available() as f64 / GIB)),
+                ],
+                (None, error) => {
+                    let status = match error {
+                        Some(_) => "error",
+                        None => "no GPU",
+                    };
+                    vec![
+                        Cell::new(&entry.model),
+                        Cell::new("-"),
+                        Cell::new(status),
+                        Cell::new("-"),
+                        Cell::new("-"),
+                    ]
+                }
+            };
+            table.add_row(row);
+        }
+        let mut output = format!("Batch check\nScenario: {}\n\n{}\n", self.scenario, table);
+        if self.failed() > 0 {
+            output.push_str("\nErrors:\n");
+            for entry in &self.entries {
+                if let Some(error) = &entry.error {
+                    output.push_str(&format!("- {}: {}\n", entry.model, error));
+                }
+            }
+        }
+        output
+    }
+}
+
+/// A struct representing the minimum hardware a scenario of a model needs, for a model card or a
+/// deployment doc
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Requirements {
+    /// The description of the scenario
+    scenario: String,
+    /// The memory of every GPU, in bytes
+    vram_per_gpu: i64,
+    /// The number of GPUs
+    num_gpus: i32,
+    /// The RAM of the host, in bytes
+    ram: i64,
+    /// The disk space of the checkpoint, in bytes
+    disk: i64,
+    /// The recommended CUDA compute capability, major and minor
+    compute_capability: (u32, u32),
+}
+
+/// Requirements implementation
+impl Requirements {
+    /// Build a new `Requirements` struct based on the provided parameters
+    pub fn new(
+        scenario: String,
+        vram_per_gpu: i64,
+        num_gpus: i32,
+        ram: i64,
+        disk: i64,
+        compute_capability: (u32, u32),
+    ) -> Requirements {
+        Requirements {
+            scenario,
+            vram_per_gpu,
+            num_gpus,
+            ram,
+            disk,
+            compute_capability,
+        }
+    }
+    /// Returns the description of the scenario
+    pub fn scenario(&self) -> &str {
+        &self.scenario
+    }
+    /// Returns the minimum memory of every GPU
+    pub fn vram_per_gpu(&self) -> i64 {
+        self.vram_per_gpu
+    }
+    /// Returns the minimum number of GPUs
+    pub fn num_gpus(&self) -> i32 {
+        self.num_gpus
+    }
+    /// Returns the minimum RAM of the host
+    pub fn ram(&self) -> i64 {
+        self.ram
+    }
+    /// Returns the minimum disk space
+    pub fn disk(&self) -> i64 {
+        self.disk
+    }
+    /// Returns the recommended CUDA compute capability
+    pub fn compute_capability(&self) -> (u32, u32) {
+        self.compute_capability
+    }
+    /// Render the requirements in JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+    /// Render the requirements in YAML
+    pub fn to_yaml(&self) -> String {
+        to_yaml(self)
+    }
+    /// Render the requirements as a standalone HTML document
+    pub fn to_html(&self) -> String {
+        to_html(&self.to_markdown())
+    }
+    /// Render the requirements in the given format
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Markdown => self.to_markdown(),
+            OutputFormat::Html => self.to_html(),
+            OutputFormat::Json => self.to_json(),
+            OutputFormat::Yaml => self.to_yaml(),
+        }
+    }
+    /// Render the requirements in markdown, as a section to paste into a model card
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!(
+            "## Hardware requirements\n\n**Scenario:** {}\n\n",
+            self.scenario
+        );
+        markdown.push_str("| Requirement | Minimum |\n");
+        markdown.push_str("|---|---:|\n");
+        markdown.push_str(&format!(
+            "| GPU memory | {} × {:.2} GiB |\n",
+            self.num_gpus,
+            self.vram_per_gpu as f64 / GIB
+        ));
+        markdown.push_str(&format!("| RAM | {:.2} GiB |\n", self.ram as f64 / GIB));
+        markdown.push_str(&format!("| Disk | {:.2} GiB |\n", self.disk as f64 / GIB));
+        markdown.push_str(&format!(
+            "| Compute capability | {}.{} |\n",
+            self.compute_capability.0, self.compute_capability.1
+        ));
+        markdown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimate::OverheadModel;
+    use crate::models::Precision;
+
+    fn create_report() -> Report {
+        let scenario = Scenario::Inference {
+            precision: Precision::FP16,
+            batch_size: 1,
+            seq_len: 4096,
+            overhead: OverheadModel::new(),
+            sliding_window: false,
+        };
+        let devices = vec![
+            DeviceSection::new(
+                FitReport::new(
+                    "NVIDIA GeForce RTX 4090".to_string(),
+                    FitVerdict::Fits,
+                    17_179_869_184,
+                    25_769_803_776,
+                    MemoryComponent::Weights,
+                ),
+                Some(Throughput::new(5000.0, 50.0)),
+                Some(ServingCost::new(0.005, 0.5)),
+            ),
+            DeviceSection::new(
+                FitReport::new(
+                    "NVIDIA GeForce RTX 3060".to_string(),
+                    FitVerdict::RequiresOffload,
+                    17_179_869_184,
+                    12_884_901_888,
+                    MemoryComponent::Weights,
+                ),
+                None,
+                None,
+            ),
+        ];
+        Report::from_devices("meta-llama/Meta-Llama-3-8B", &scenario, devices)
+    }
+
+    #[test]
+    fn test_report_from_devices() {
+        let report = create_report();
+        assert_eq!(report.model(), "meta-llama/Meta-Llama-3-8B");
+        assert_eq!(report.scenario(), "inference in fp16, 1 × 4096 tokens");
+        assert_eq!(report.devices().len(), 2);
+        assert_eq!(report.assumptions().len(), 2);
+        assert_eq!(
+            report.warnings(),
+            ["NVIDIA GeForce RTX 3060: the weights have to be offloaded to the CPU, expect a much slower run"]
+        );
+
+        let empty = Report::from_fit_reports(
+            "gpt2",
+            &Scenario::Inference {
+                precision: Precision::FP32,
+                batch_size: 1,
+                seq_len: 1024,
+                overhead: OverheadModel::new(),
+                sliding_window: false,
+            },
+            vec![],
+        );
+        assert_eq!(
+            empty.warnings(),
+            ["No GPU was found, the model would run on the CPU"]
+        );
+    }
+
+    #[test]
+    fn test_report_to_json() {
+        let report = create_report();
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["model"], "meta-llama/Meta-Llama-3-8B");
+        let device = &json["devices"][0];
+        assert_eq!(device["device"], "NVIDIA GeForce RTX 4090");
+        assert_eq!(device["verdict"], "fits");
+        assert_eq!(device["required"], 17_179_869_184i64);
+        assert_eq!(device["limiting_factor"], "weights");
+        assert_eq!(device["throughput"]["decode_tokens_per_second"], 50.0);
+        assert_eq!(device["serving_cost"]["output_per_million"], 0.5);
+        assert_eq!(json["devices"][1]["verdict"], "requires_offload");
+        assert!(json["devices"][1]["throughput"].is_null());
+        assert_eq!(json["warnings"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_report_to_yaml() {
+        let report = create_report();
+        let yaml = report.render("yaml".parse().unwrap());
+        assert!(yaml.contains("model: \"meta-llama/Meta-Llama-3-8B\"\n"));
+        assert!(yaml.contains(
+            "devices:\n  -\n    available: 25769803776\n    device: \"NVIDIA GeForce RTX 4090\"\n"
+        ));
+        assert!(yaml.contains("    throughput: null\n"));
+        assert!(yaml.contains("warnings:\n  - \"NVIDIA GeForce RTX 3060:"));
+        assert_eq!(report.render(OutputFormat::Json), report.to_json());
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+
+    #[test]
+    fn test_report_to_html() {
+        let html = create_report().to_html();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>meta-llama/Meta-Llama-3-8B</title>"));
+        assert!(
+            html.contains("<p><strong>Scenario:</strong> inference in fp16, 1 × 4096 tokens</p>\n")
+        );
+        assert!(html.contains(
+            "<th>Device</th><th>Verdict</th><th style=\"text-align: right\">Required</th>"
+        ));
+        assert!(html.contains("<tr><td>NVIDIA GeForce RTX 4090</td><td>fits</td>"));
+        assert!(html.contains("<h2>Warnings</h2>\n<ul>\n<li>NVIDIA GeForce RTX 3060:"));
+        assert_eq!(
+            OutputFormat::from_path(Path::new("report.html")),
+            Some(OutputFormat::Html)
+        );
+        assert_eq!(
+            OutputFormat::from_path(Path::new("out/report.md")),
+            Some(OutputFormat::Markdown)
+        );
+        assert_eq!(OutputFormat::from_path(Path::new("report")), None);
+    }
+
+    #[test]
+    fn test_report_to_markdown() {
+        let markdown = create_report().to_markdown();
+        assert!(markdown.starts_with(
+            "# meta-llama/Meta-Llama-3-8B\n\n**Scenario:** inference in fp16, 1 × 4096 tokens\n\n"
+        ));
+        assert!(markdown.contains(
+            "| NVIDIA GeForce RTX 4090 | fits | 16.00 GiB | 24.00 GiB | 8.00 GiB | weights | 50.0 tokens/s | $0.50 |\n"
+        ));
+        assert!(markdown.contains(
+            "| NVIDIA GeForce RTX 3060 | requires offload | 16.00 GiB | 12.00 GiB | -4.00 GiB | weights | - | - |\n"
+        ));
+        assert!(markdown.contains("\n## Assumptions\n\n- The framework overhead is 512 MiB"));
+        assert!(markdown.contains("\n## Warnings\n\n- NVIDIA GeForce RTX 3060:"));
+    }
+
+    #[test]
+    fn test_comparison_to_markdown() {
+        let comparison = Comparison::new(
+            "inference in fp16, 1 × 4096 tokens".to_string(),
+            Some("NVIDIA GeForce RTX 4090".to_string()),
+            vec![
+                ComparisonRow::new(
+                    "mistralai/Mistral-7B-v0.1".to_string(),
+                    7_241_732_096,
+                    16_106_127_360,
+                    16_106_127_360,
+                    5_368_709_120,
+                    Some(32768),
+                    Some(Throughput::new(5000.0, 52.25)),
+                    Some(FitVerdict::Fits),
+                ),
+                ComparisonRow::new(
+                    "meta-llama/Meta-Llama-3-70B".to_string(),
+                    70_553_706_496,
+                    150_323_855_360,
+                    150_323_855_360,
+                    42_949_672_960,
+                    None,
+                    None,
+                    Some(FitVerdict::RequiresOffload),
+                ),
+            ],
+        );
+        assert_eq!(
+            comparison.to_markdown(),
+            concat!(
+                "# Comparison on NVIDIA GeForce RTX 4090\n\n",
+                "**Scenario:** inference in fp16, 1 × 4096 tokens\n\n",
+                "| Model | Params | Memory | FP16 | INT4 | Max context | Decode | Verdict |\n",
+                "|---|---:|---:|---:|---:|---:|---:|---|\n",
+                "| mistralai/Mistral-7B-v0.1 | 7.2B | 15.00 GiB | 15.00 GiB | 5.00 GiB | 32768 | \
+                 52.2 tokens/s | fits |\n",
+                "| meta-llama/Meta-Llama-3-70B | 70.6B | 140.00 GiB | 140.00 GiB | 40.00 GiB | - | - | \
+                 requires offload |\n",
+            )
+        );
+        let json: serde_json::Value = serde_json::from_str(&comparison.to_json()).unwrap();
+        assert_eq!(json["rows"][1]["verdict"], "requires_offload");
+        assert!(json["rows"][1]["max_context"].is_null());
+    }
+
+    #[test]
+    fn test_batch_report_to_markdown() {
+        let report = create_report();
+        let batch = BatchReport::new(
+            report.scenario().to_string(),
+            vec![
+                BatchEntry::checked(report),
+                BatchEntry::failed(
+                    "user/missing",
+                    "Repository not found: user/missing".to_string(),
+                ),
+            ],
+        );
+        assert_eq!(batch.failed(), 1);
+        assert_eq!(
+            batch.entries()[0].best_device().unwrap().device(),
+            "NVIDIA GeForce RTX 4090"
+        );
+        assert_eq!(
+            batch.to_markdown(),
+            concat!(
+                "# Batch check\n\n",
+                "**Scenario:** inference in fp16, 1 × 4096 tokens\n\n",
+                "| Model | Best device | Verdict | Required | Available |\n",
+                "|---|---|---|---:|---:|\n",
+                "| meta-llama/Meta-Llama-3-8B | NVIDIA GeForce RTX 4090 | fits | 16.00 GiB | \
+                 24.00 GiB |\n",
+                "| user/missing | - | error | - | - |\n",
+                "\n## Errors\n\n",
+                "- user/missing: Repository not found: user/missing\n",
+            )
+        );
+        let json: serde_json::Value = serde_json::from_str(&batch.to_json()).unwrap();
+        assert_eq!(
+            json["entries"][0]["report"]["devices"][0]["device"],
+            "NVIDIA GeForce RTX 4090"
+        );
+        assert!(json["entries"][1]["report"].is_null());
+        assert_eq!(
+            batch.to_table(&TableOptions::new().plain()),
+            concat!(
+                "Batch check\n",
+                "Scenario: inference in fp16, 1 × 4096 tokens\n\n",
+                "| Model                      | Best device             | Verdict |  Required | \
+                 Available |\n",
+                "|----------------------------|-------------------------|---------|-----------|\
+                 -----------|\n",
+                "| meta-llama/Meta-Llama-3-8B | NVIDIA GeForce RTX 4090 | fits    | 16.00 GiB | \
+                 24.00 GiB |\n",
+                "| user/missing               | -                       | error   |         - |",
+                "         - |\n",
+                "\nErrors:\n",
+                "- user/missing: Repository not found: user/missing\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_training_report_to_markdown() {
+        let gib = 1024 * 1024 * 1024;
+        let fit = FitReport::new(
+            "NVIDIA A100-SXM4-80GB".to_string(),
+            FitVerdict::Fits,
+            40 * gib,
+            80 * gib,
+            MemoryComponent::OptimizerStates,
+        );
+        let report = TrainingReport::new(
+            "meta-llama/Meta-Llama-3-8B".to_string(),
+            "training in bf16, 1 × 2048 tokens on 8 GPU(s)".to_string(),
+            8,
+            fit,
+            vec![
+                (MemoryComponent::Weights, 16 * gib),
+                (MemoryComponent::OptimizerStates, 24 * gib),
+            ],
+            1_000_000_000,
+            5400.0,
+            Some(2.0),
+        )
+        .with_assumption("The GPUs reach 40% of their peak throughput".to_string());
+        assert_eq!(report.gpu_hours(), 12.0);
+        assert_eq!(report.cost(), Some(24.0));
+        assert_eq!(
+            report.to_markdown(),
+            concat!(
+                "# Training meta-llama/Meta-Llama-3-8B\n\n",
+                "**Scenario:** training in bf16, 1 × 2048 tokens on 8 GPU(s)\n\n",
+                "**Hardware:** 8 × NVIDIA A100-SXM4-80GB\n\n",
+                "## Memory per GPU\n\n",
+                "| Component | Memory |\n",
+                "|---|---:|\n",
+                "| weights | 16.00 GiB |\n",
+                "| optimizer states | 24.00 GiB |\n",
+                "| **Total** | **40.00 GiB** |\n\n",
+                "**Verdict:** fits (80.00 GiB available)\n\n",
+                "## Time and cost\n\n",
+                "| Tokens | Time | GPU hours | Cost |\n",
+                "|---:|---:|---:|---:|\n",
+                "| 1000000000 | 1.5 h | 12.0 | $24.00 |\n",
+                "\n## Assumptions\n\n",
+                "- The GPUs reach 40% of their peak throughput\n",
+            )
+        );
+        assert_eq!(format_duration(45.0), "45 s");
+        assert_eq!(format_duration(2700.0), "45 min");
+        assert_eq!(format_duration(3.0 * 86400.0), "3.0 days");
+    }
+}
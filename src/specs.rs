@@ -0,0 +1,483 @@
+//! Module for the database of GPU and cloud instance specifications, the figures the hardware
+//! scan can't report.
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::hardware::GPUDevice;
+
+/// Well-known GPUs, with their memory (bytes), their memory bandwidth (bytes/s), their dense FP16
+/// tensor throughput with FP32 accumulation (TFLOPS), their board power (W) and their CUDA compute
+/// capability, as published by NVIDIA
+const GPU_SPECS: [GpuSpecEntry; 21] = [
+    ("H100 80GB HBM3", 80 * GIB, 3.35e12, 989.0, 700.0, (9, 0)),
+    ("H100 PCIe", 80 * GIB, 2.0e12, 756.0, 350.0, (9, 0)),
+    ("A100-SXM4-80GB", 80 * GIB, 2.039e12, 312.0, 400.0, (8, 0)),
+    ("A100-SXM4-40GB", 40 * GIB, 1.555e12, 312.0, 400.0, (8, 0)),
+    ("A100 80GB PCIe", 80 * GIB, 1.935e12, 312.0, 300.0, (8, 0)),
+    ("A100-PCIE-40GB", 40 * GIB, 1.555e12, 312.0, 250.0, (8, 0)),
+    ("L40S", 48 * GIB, 864.0e9, 362.0, 350.0, (8, 9)),
+    ("L4", 24 * GIB, 300.0e9, 121.0, 72.0, (8, 9)),
+    ("A10G", 24 * GIB, 600.0e9, 70.0, 300.0, (8, 6)),
+    ("A10", 24 * GIB, 600.0e9, 125.0, 150.0, (8, 6)),
+    ("T4", 16 * GIB, 320.0e9, 65.0, 70.0, (7, 5)),
+    ("V100-SXM2-16GB", 16 * GIB, 900.0e9, 125.0, 300.0, (7, 0)),
+    ("V100-SXM2-32GB", 32 * GIB, 900.0e9, 125.0, 300.0, (7, 0)),
+    ("RTX 6000 Ada", 48 * GIB, 960.0e9, 364.0, 300.0, (8, 9)),
+    ("RTX A6000", 48 * GIB, 768.0e9, 155.0, 300.0, (8, 6)),
+    ("RTX 4090", 24 * GIB, 1.008e12, 165.0, 450.0, (8, 9)),
+    ("RTX 4080", 16 * GIB, 717.0e9, 97.0, 320.0, (8, 9)),
+    ("RTX 3090 Ti", 24 * GIB, 1.008e12, 80.0, 450.0, (8, 6)),
+    ("RTX 3090", 24 * GIB, 936.0e9, 71.0, 350.0, (8, 6)),
+    ("RTX 3080", 10 * GIB, 760.0e9, 59.5, 320.0, (8, 6)),
+    ("RTX 3060", 12 * GIB, 360.0e9, 25.5, 170.0, (8, 6)),
+];
+
+/// An entry of the GPU database
+type GpuSpecEntry = (&'static str, u64, f64, f64, f64, (u32, u32));
+
+/// Apple Silicon chips, with the bandwidth of their unified memory (bytes/s) and the FP16
+/// throughput of their GPU (TFLOPS), as published by Apple for the variants with the most GPU
+/// cores
+const APPLE_CHIP_SPECS: [(&str, f64, f64); 15] = [
+    ("M1", 68.25e9, 2.6),
+    ("M1 Pro", 200.0e9, 5.2),
+    ("M1 Max", 400.0e9, 10.4),
+    ("M1 Ultra", 800.0e9, 21.0),
+    ("M2", 100.0e9, 3.6),
+    ("M2 Pro", 200.0e9, 6.8),
+    ("M2 Max", 400.0e9, 13.6),
+    ("M2 Ultra", 800.0e9, 27.2),
+    ("M3", 100.0e9, 4.1),
+    ("M3 Pro", 150.0e9, 7.4),
+    ("M3 Max", 400.0e9, 16.4),
+    ("M3 Ultra", 819.0e9, 28.3),
+    ("M4", 120.0e9, 4.3),
+    ("M4 Pro", 273.0e9, 9.2),
+    ("M4 Max", 546.0e9, 18.4),
+];
+
+/// Well-known cloud GPU instances, with their GPU (named as in `GPU_SPECS`), their number of GPUs
+/// and their on-demand hourly price in US dollars, as listed by the providers in 2024
+const CLOUD_INSTANCES: [(CloudProvider, &str, &str, i32, f64); 23] = [
+    (
+        CloudProvider::Aws,
+        "p5.48xlarge",
+        "H100 80GB HBM3",
+        8,
+        98.32,
+    ),
+    (
+        CloudProvider::Aws,
+        "p4de.24xlarge",
+        "A100-SXM4-80GB",
+        8,
+        40.97,
+    ),
+    (
+        CloudProvider::Aws,
+        "p4d.24xlarge",
+        "A100-SXM4-40GB",
+        8,
+        32.77,
+    ),
+    (CloudProvider::Aws, "g6e.xlarge", "L40S", 1, 1.861),
+    (CloudProvider::Aws, "g6.xlarge", "L4", 1, 0.805),
+    (CloudProvider::Aws, "g5.xlarge", "A10G", 1, 1.006),
+    (CloudProvider::Aws, "g5.12xlarge", "A10G", 4, 5.672),
+    (CloudProvider::Aws, "g5.48xlarge", "A10G", 8, 16.288),
+    (CloudProvider::Aws, "g4dn.xlarge", "T4", 1, 0.526),
+    (
+        CloudProvider::Gcp,
+        "a3-highgpu-8g",
+        "H100 80GB HBM3",
+        8,
+        88.25,
+    ),
+    (
+        CloudProvider::Gcp,
+        "a2-ultragpu-1g",
+        "A100-SXM4-80GB",
+        1,
+        5.07,
+    ),
+    (
+        CloudProvider::Gcp,
+        "a2-highgpu-1g",
+        "A100-SXM4-40GB",
+        1,
+        3.67,
+    ),
+    (CloudProvider::Gcp, "g2-standard-4", "L4", 1, 0.71),
+    (
+        CloudProvider::Azure,
+        "Standard_ND96isr_H100_v5",
+        "H100 80GB HBM3",
+        8,
+        98.32,
+    ),
+    (
+        CloudProvider::Azure,
+        "Standard_ND96amsr_A100_v4",
+        "A100-SXM4-80GB",
+        8,
+        32.77,
+    ),
+    (
+        CloudProvider::Azure,
+        "Standard_NC24ads_A100_v4",
+        "A100 80GB PCIe",
+        1,
+        3.673,
+    ),
+    (CloudProvider::Azure, "Standard_NC4as_T4_v3", "T4", 1, 0.526),
+    (
+        CloudProvider::Lambda,
+        "gpu_8x_h100_sxm5",
+        "H100 80GB HBM3",
+        8,
+        23.92,
+    ),
+    (
+        CloudProvider::Lambda,
+        "gpu_1x_h100_pcie",
+        "H100 PCIe",
+        1,
+        2.49,
+    ),
+    (
+        CloudProvider::Lambda,
+        "gpu_8x_a100_80gb_sxm4",
+        "A100-SXM4-80GB",
+        8,
+        14.32,
+    ),
+    (
+        CloudProvider::Lambda,
+        "gpu_1x_a100_sxm4",
+        "A100-SXM4-40GB",
+        1,
+        1.29,
+    ),
+    (CloudProvider::Lambda, "gpu_1x_a6000", "RTX A6000", 1, 0.80),
+    (CloudProvider::Lambda, "gpu_1x_a10", "A10", 1, 0.75),
+];
+
+/// Number of bytes in a GiB
+const GIB: u64 = 1024 * 1024 * 1024;
+
+/// A struct representing the specifications of a GPU
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GpuSpec {
+    /// The name of the GPU, as found in the names reported by the drivers (e.g. `RTX 4090`)
+    name: &'static str,
+    /// The memory of the GPU, in bytes
+    memory: u64,
+    /// The bandwidth of the GPU memory, in bytes/s
+    memory_bandwidth: f64,
+    /// The dense FP16 tensor throughput, in TFLOPS
+    fp16_tflops: f64,
+    /// The maximum power draw of the board, in watts
+    power: f64,
+    /// The CUDA compute capability, major and minor
+    compute_capability: (u32, u32),
+}
+
+/// GpuSpec implementation
+impl GpuSpec {
+    /// Find the specifications of a GPU from its name as reported by the drivers (e.g.
+    /// `NVIDIA GeForce RTX 4090`), case insensitive. The longest matching name wins, so an
+    /// `A100-SXM4-80GB` is not mistaken for an `A10`.
+    pub fn from_name(name: &str) -> Option<GpuSpec> {
+        let name = name.to_lowercase();
+        GPU_SPECS
+            .iter()
+            .filter(|(spec_name, _, _, _, _, _)| name.contains(&spec_name.to_lowercase()))
+            .max_by_key(|(spec_name, _, _, _, _, _)| spec_name.len())
+            .map(|&spec| spec.into())
+    }
+    /// Find the specifications of a scanned GPU device
+    pub fn from_device(device: &dyn GPUDevice) -> Option<GpuSpec> {
+        GpuSpec::from_name(&device.get_name())
+    }
+    /// Returns the specifications of all the well-known GPUs
+    pub fn all() -> Vec<GpuSpec> {
+        GPU_SPECS.iter().map(|&spec| spec.into()).collect()
+    }
+    /// Returns the name of the GPU
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+    /// Returns the memory of the GPU
+    pub fn memory(&self) -> u64 {
+        self.memory
+    }
+    /// Returns the bandwidth of the GPU memory
+    pub fn memory_bandwidth(&self) -> f64 {
+        self.memory_bandwidth
+    }
+    /// Returns the dense FP16 tensor throughput
+    pub fn fp16_tflops(&self) -> f64 {
+        self.fp16_tflops
+    }
+    /// Returns the maximum power draw of the board
+    pub fn power(&self) -> f64 {
+        self.power
+    }
+    /// Returns the CUDA compute capability
+    pub fn compute_capability(&self) -> (u32, u32) {
+        self.compute_capability
+    }
+}
+
+/// Convert an entry of the database
+impl From<GpuSpecEntry> for GpuSpec {
+    fn from(
+        (name, memory, memory_bandwidth, fp16_tflops, power, compute_capability): GpuSpecEntry,
+    ) -> Self {
+        GpuSpec {
+            name,
+            memory,
+            memory_bandwidth,
+            fp16_tflops,
+            power,
+            compute_capability,
+        }
+    }
+}
+
+/// A struct representing the specifications of an Apple Silicon chip
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AppleChipSpec {
+    /// The name of the chip, as found in the CPU brand reported by macOS (e.g. `M2 Max`)
+    name: &'static str,
+    /// The bandwidth of the unified memory, in bytes/s
+    memory_bandwidth: f64,
+    /// The FP16 throughput of the GPU, in TFLOPS
+    fp16_tflops: f64,
+}
+
+/// AppleChipSpec implementation
+impl AppleChipSpec {
+    /// Find the specifications of a chip from its name as reported by macOS (e.g.
+    /// `Apple M2 Max`), case insensitive. The longest matching name wins, so an `M2 Max` is not
+    /// mistaken for an `M2`.
+    pub fn from_name(name: &str) -> Option<AppleChipSpec> {
+        let name = name.to_lowercase();
+        APPLE_CHIP_SPECS
+            .iter()
+            .filter(|(spec_name, _, _)| {
+                name.split_whitespace()
+                    .collect::<Vec<_>>()
+                    .windows(spec_name.split_whitespace().count())
+                    .any(|words| words.join(" ") == spec_name.to_lowercase())
+            })
+            .max_by_key(|(spec_name, _, _)| spec_name.len())
+            .map(|&(name, memory_bandwidth, fp16_tflops)| AppleChipSpec {
+                name,
+                memory_bandwidth,
+                fp16_tflops,
+            })
+    }
+    /// Returns the specifications of all the well-known chips
+    pub fn all() -> Vec<AppleChipSpec> {
+        APPLE_CHIP_SPECS
+            .iter()
+            .map(|&(name, memory_bandwidth, fp16_tflops)| AppleChipSpec {
+                name,
+                memory_bandwidth,
+                fp16_tflops,
+            })
+            .collect()
+    }
+    /// Returns the name of the chip
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+    /// Returns the bandwidth of the unified memory
+    pub fn memory_bandwidth(&self) -> f64 {
+        self.memory_bandwidth
+    }
+    /// Returns the FP16 throughput of the GPU
+    pub fn fp16_tflops(&self) -> f64 {
+        self.fp16_tflops
+    }
+}
+
+/// Enumerate the cloud providers of the instance database
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CloudProvider {
+    /// Amazon Web Services
+    Aws,
+    /// Google Cloud Platform
+    Gcp,
+    /// Microsoft Azure
+    Azure,
+    /// Lambda Cloud
+    Lambda,
+}
+
+/// Implement the display of the CloudProvider enum
+impl Display for CloudProvider {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let provider = match self {
+            CloudProvider::Aws => "AWS",
+            CloudProvider::Gcp => "GCP",
+            CloudProvider::Azure => "Azure",
+            CloudProvider::Lambda => "Lambda",
+        };
+        write!(f, "{}", provider)
+    }
+}
+
+/// A struct representing the specifications of a cloud GPU instance
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InstanceSpec {
+    /// The cloud provider
+    provider: CloudProvider,
+    /// The name of the instance type, as the provider lists it
+    name: &'static str,
+    /// The GPU of the instance
+    gpu: GpuSpec,
+    /// The number of GPUs of the instance
+    num_gpus: i32,
+    /// The on-demand price of the instance, in US dollars per hour
+    hourly_price: f64,
+}
+
+/// InstanceSpec implementation
+impl InstanceSpec {
+    /// Build a new `InstanceSpec` struct based on the provided parameters
+    pub fn new(
+        provider: CloudProvider,
+        name: &'static str,
+        gpu: GpuSpec,
+        num_gpus: i32,
+        hourly_price: f64,
+    ) -> InstanceSpec {
+        InstanceSpec {
+            provider,
+            name,
+            gpu,
+            num_gpus,
+            hourly_price,
+        }
+    }
+    /// Returns the specifications of all the well-known cloud instances
+    pub fn all() -> Vec<InstanceSpec> {
+        CLOUD_INSTANCES
+            .iter()
+            .filter_map(|&(provider, name, gpu, num_gpus, hourly_price)| {
+                let gpu = GpuSpec::from_name(gpu)?;
+                Some(InstanceSpec::new(
+                    provider,
+                    name,
+                    gpu,
+                    num_gpus,
+                    hourly_price,
+                ))
+            })
+            .collect()
+    }
+    /// Find a cloud instance from its type name (e.g. `p4d.24xlarge`), case insensitive
+    pub fn from_name(name: &str) -> Option<InstanceSpec> {
+        InstanceSpec::all()
+            .into_iter()
+            .find(|instance| instance.name().eq_ignore_ascii_case(name))
+    }
+    /// Returns the cloud provider
+    pub fn provider(&self) -> CloudProvider {
+        self.provider
+    }
+    /// Returns the name of the instance type
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+    /// Returns the GPU of the instance
+    pub fn gpu(&self) -> GpuSpec {
+        self.gpu
+    }
+    /// Returns the number of GPUs of the instance
+    pub fn num_gpus(&self) -> i32 {
+        self.num_gpus
+    }
+    /// Returns the on-demand price of the instance per hour
+    pub fn hourly_price(&self) -> f64 {
+        self.hourly_price
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gpu_spec_from_name() {
+        let spec = GpuSpec::from_name("NVIDIA GeForce RTX 4090").unwrap();
+        assert_eq!(spec.name(), "RTX 4090");
+        assert_eq!(spec.memory(), 25769803776);
+        assert_eq!(spec.memory_bandwidth(), 1.008e12);
+        assert_eq!(spec.fp16_tflops(), 165.0);
+        assert_eq!(spec.power(), 450.0);
+        assert_eq!(spec.compute_capability(), (8, 9));
+
+        assert_eq!(GpuSpec::from_name("Tesla T4").unwrap().name(), "T4");
+        assert!(GpuSpec::from_name("Radeon RX 7900 XTX").is_none());
+    }
+
+    #[test]
+    fn test_gpu_spec_longest_match() {
+        assert_eq!(
+            GpuSpec::from_name("NVIDIA A100-SXM4-80GB").unwrap().name(),
+            "A100-SXM4-80GB"
+        );
+        assert_eq!(GpuSpec::from_name("NVIDIA A10").unwrap().name(), "A10");
+        assert_eq!(GpuSpec::from_name("NVIDIA L40S").unwrap().name(), "L40S");
+        assert_eq!(
+            GpuSpec::from_name("NVIDIA GeForce RTX 3090 Ti")
+                .unwrap()
+                .name(),
+            "RTX 3090 Ti"
+        );
+    }
+
+    #[test]
+    fn test_gpu_specs_are_unique() {
+        let specs = GpuSpec::all();
+        assert_eq!(specs.len(), GPU_SPECS.len());
+        for spec in specs {
+            assert_eq!(GpuSpec::from_name(spec.name()), Some(spec));
+        }
+    }
+
+    #[test]
+    fn test_apple_chip_spec_from_name() {
+        let spec = AppleChipSpec::from_name("Apple M2 Max").unwrap();
+        assert_eq!(spec.name(), "M2 Max");
+        assert_eq!(spec.memory_bandwidth(), 400.0e9);
+        // Whole words only, an `M1` is not an `M1 Pro`, nor an `M10`
+        assert_eq!(AppleChipSpec::from_name("Apple M1").unwrap().name(), "M1");
+        assert!(AppleChipSpec::from_name("Apple M10").is_none());
+        assert!(AppleChipSpec::from_name("Intel Core i9").is_none());
+        for spec in AppleChipSpec::all() {
+            assert_eq!(AppleChipSpec::from_name(spec.name()), Some(spec));
+        }
+    }
+
+    #[test]
+    fn test_instance_specs() {
+        // Every instance GPU is in the GPU database
+        let instances = InstanceSpec::all();
+        assert_eq!(instances.len(), CLOUD_INSTANCES.len());
+
+        let p5 = instances
+            .iter()
+            .find(|instance| instance.name() == "p5.48xlarge")
+            .unwrap();
+        assert_eq!(p5.provider(), CloudProvider::Aws);
+        assert_eq!(p5.provider().to_string(), "AWS");
+        assert_eq!(p5.gpu().name(), "H100 80GB HBM3");
+        assert_eq!(p5.num_gpus(), 8);
+        assert_eq!(p5.hourly_price(), 98.32);
+        assert_eq!(InstanceSpec::from_name("P5.48XLARGE"), Some(*p5));
+        assert!(InstanceSpec::from_name("p5").is_none());
+    }
+}
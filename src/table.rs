@@ -0,0 +1,173 @@
+//! Module for rendering the reports as aligned tables in a terminal, wrapped to its width, or as
+//! plain text without borders nor colors for the logs. A `wasm32` target has no terminal: its
+//! tables are never colored.
+use std::env;
+
+use comfy_table::modifiers::UTF8_ROUND_CORNERS;
+use comfy_table::presets::{ASCII_MARKDOWN, UTF8_FULL_CONDENSED};
+#[cfg(not(target_arch = "wasm32"))]
+use comfy_table::Color;
+use comfy_table::{Cell, CellAlignment, ContentArrangement, Table};
+
+use crate::advisor::FitVerdict;
+
+/// A struct representing how the tables are rendered in a terminal
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TableOptions {
+    /// Whether the tables are plain ASCII, neither wrapped nor colored
+    plain: bool,
+    /// Whether the verdicts are colored, when the output is a terminal
+    color: bool,
+    /// The width the tables are wrapped to, the width of the terminal when not set
+    width: Option<u16>,
+}
+
+/// TableOptions implementation
+impl TableOptions {
+    /// Build new `TableOptions` with borders and colored verdicts, the colors being disabled by
+    /// the `NO_COLOR` environment variable
+    pub fn new() -> TableOptions {
+        TableOptions {
+            plain: false,
+            color: env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()),
+            width: None,
+        }
+    }
+    /// Render plain ASCII tables, neither wrapped nor colored, e.g. for the logs
+    pub fn plain(mut self) -> Self {
+        self.plain = true;
+        self.color = false;
+        self
+    }
+    /// Disable the colors of the verdicts
+    pub fn no_color(mut self) -> Self {
+        self.color = false;
+        self
+    }
+    /// Set the width the tables are wrapped to
+    pub fn with_width(mut self, width: u16) -> Self {
+        self.width = Some(width);
+        self
+    }
+    /// Returns whether the tables are plain ASCII
+    pub fn is_plain(&self) -> bool {
+        self.plain
+    }
+    /// Returns whether the verdicts are colored
+    pub fn color(&self) -> bool {
+        self.color
+    }
+    /// Returns the width the tables are wrapped to
+    pub fn width(&self) -> Option<u16> {
+        self.width
+    }
+}
+
+/// Implement the default TableOptions, see `TableOptions::new`
+impl Default for TableOptions {
+    fn default() -> Self {
+        TableOptions::new()
+    }
+}
+
+/// Build an empty table with the given header, the columns of `right_aligned` (the numbers) being
+/// aligned to the right
+pub(crate) fn build_table(
+    options: &TableOptions,
+    header: &[&str],
+    right_aligned: &[usize],
+) -> Table {
+    let mut table = Table::new();
+    match options.plain {
+        true => {
+            table
+                .load_preset(ASCII_MARKDOWN)
+                .set_content_arrangement(ContentArrangement::Disabled);
+            #[cfg(not(target_arch = "wasm32"))]
+            table.force_no_tty();
+        }
+        false => {
+            table
+                .load_preset(UTF8_FULL_CONDENSED)
+                .apply_modifier(UTF8_ROUND_CORNERS)
+                .set_content_arrangement(ContentArrangement::Dynamic);
+        }
+    }
+    if let Some(width) = options.width {
+        table.set_width(width);
+    }
+    table.set_header(header.to_vec());
+    for index in right_aligned {
+        if let Some(column) = table.column_mut(*index) {
+            column.set_cell_alignment(CellAlignment::Right);
+        }
+    }
+    table
+}
+
+/// Returns the cell of a verdict, green when the model fits, yellow when it is tight and red
+/// otherwise, `-` without verdict
+#[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+pub(crate) fn verdict_cell(verdict: Option<FitVerdict>, options: &TableOptions) -> Cell {
+    let Some(verdict) = verdict else {
+        return Cell::new("-");
+    };
+    let cell = Cell::new(verdict);
+    #[cfg(not(target_arch = "wasm32"))]
+    let cell = match (options.color, verdict) {
+        (false, _) => cell,
+        (true, FitVerdict::Fits) => cell.fg(Color::Green),
+        (true, FitVerdict::Tight) => cell.fg(Color::Yellow),
+        (true, _) => cell.fg(Color::Red),
+    };
+    cell
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_options() {
+        let options = TableOptions::new().with_width(80);
+        assert!(!options.is_plain());
+        assert_eq!(options.width(), Some(80));
+        assert!(!options.no_color().color());
+        // The plain tables are never colored
+        let plain = TableOptions::new().plain();
+        assert!(plain.is_plain());
+        assert!(!plain.color());
+    }
+
+    #[test]
+    fn test_build_table_plain() {
+        let mut table = build_table(&TableOptions::new().plain(), &["Model", "Memory"], &[1]);
+        table.add_row(vec!["meta-llama/Meta-Llama-3-8B", "16.00 GiB"]);
+        table.add_row(vec!["gpt2", "0.50 GiB"]);
+        assert_eq!(
+            table.to_string(),
+            concat!(
+                "| Model                      |    Memory |\n",
+                "|----------------------------|-----------|\n",
+                "| meta-llama/Meta-Llama-3-8B | 16.00 GiB |\n",
+                "| gpt2                       |  0.50 GiB |",
+            )
+        );
+    }
+
+    #[test]
+    fn test_build_table_wraps() {
+        let options = TableOptions::new().no_color().with_width(30);
+        let mut table = build_table(&options, &["Model", "Verdict"], &[]);
+        table.add_row(vec![
+            Cell::new("a-very-long-organization/a-very-long-model-name"),
+            verdict_cell(Some(FitVerdict::DoesNotFit), &options),
+        ]);
+        let rendered = table.to_string();
+        assert!(rendered.starts_with('╭'));
+        assert!(rendered.lines().all(|line| line.chars().count() <= 30));
+        // The model and the verdict are wrapped on several lines
+        assert_eq!(rendered.lines().count(), 8);
+        assert!(rendered.contains("│ a-very-long-org ┆ does not │"));
+    }
+}
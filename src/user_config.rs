@@ -0,0 +1,315 @@
+//! Module for the user configuration file (`~/.config/aiha/config.toml`), holding the defaults
+//! the command line flags override:
+//!
+//! ```toml
+//! # Where the Hub token is read from: "env" (HF_TOKEN), "huggingface" (the token saved by
+//! # `huggingface-cli login`) or the path of a file
+//! token_source = "huggingface"
+//! endpoint = "https://hf-mirror.com"
+//! precision = "bf16"
+//! safety_margin = 0.1
+//! output_format = "markdown"
+//! ```
+//!
+//! Built with the `cli` feature, which brings the TOML parser.
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::Deserialize;
+use toml::Spanned;
+
+use crate::estimate::OverheadModel;
+use crate::hub::{HubClient, HUB_ENDPOINT};
+use crate::models::Precision;
+use crate::report::OutputFormat;
+
+/// Name of the user configuration file in the AIHA config folder
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// Get the path of the user configuration file: `AIHA_CONFIG`, then `config.toml` in
+/// `XDG_CONFIG_HOME/aiha` or `~/.config/aiha`
+pub fn default_config_path() -> Option<PathBuf> {
+    let var = |name: &str| {
+        env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+    if let Some(path) = var("AIHA_CONFIG") {
+        return Some(path);
+    }
+    let config_home = var("XDG_CONFIG_HOME").or_else(|| {
+        var("HOME")
+            .or_else(|| var("USERPROFILE"))
+            .map(|home| home.join(".config"))
+    })?;
+    Some(config_home.join("aiha").join(CONFIG_FILE))
+}
+
+/// Enumerate the places the Hub token is read from
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenSource {
+    /// The `HF_TOKEN` environment variable
+    Env,
+    /// The token saved by `huggingface-cli login`, in `HF_HOME` or `~/.cache/huggingface`
+    HuggingFace,
+    /// A file holding the token
+    File(PathBuf),
+}
+
+/// Build from the name of a token source: `env`, `huggingface`, or the path of a file
+impl FromStr for TokenSource {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        match source.trim() {
+            "" => Err("Empty token source".to_string()),
+            "env" => Ok(TokenSource::Env),
+            "huggingface" | "hf" => Ok(TokenSource::HuggingFace),
+            path => Ok(TokenSource::File(PathBuf::from(path))),
+        }
+    }
+}
+
+/// TokenSource implementation
+impl TokenSource {
+    /// Read the token, `None` when it isn't set
+    pub fn read(&self) -> Option<String> {
+        let token = match self {
+            TokenSource::Env => env::var("HF_TOKEN").ok()?,
+            TokenSource::HuggingFace => {
+                let hf_home = env::var_os("HF_HOME")
+                    .filter(|v| !v.is_empty())
+                    .map(PathBuf::from)
+                    .or_else(|| {
+                        env::var_os("HOME")
+                            .or_else(|| env::var_os("USERPROFILE"))
+                            .map(|home| PathBuf::from(home).join(".cache").join("huggingface"))
+                    })?;
+                fs::read_to_string(hf_home.join("token")).ok()?
+            }
+            TokenSource::File(path) => fs::read_to_string(path).ok()?,
+        };
+        let token = token.trim();
+        match token.is_empty() {
+            true => None,
+            false => Some(token.to_string()),
+        }
+    }
+}
+
+/// A struct representing the defaults of the user, every unset one falling back to the defaults
+/// of AIHA
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UserConfig {
+    /// Where the Hub token is read from
+    pub token_source: Option<TokenSource>,
+    /// The Hub endpoint, tried before the default Hugging Face Hub endpoint
+    pub endpoint: Option<String>,
+    /// The precision of the checks
+    pub precision: Option<Precision>,
+    /// The share of the device memory kept free (see `OverheadModel::safety_margin`)
+    pub safety_margin: Option<f64>,
+    /// The format of the reports
+    pub output_format: Option<OutputFormat>,
+}
+
+/// UserConfig implementation
+impl UserConfig {
+    /// Load the user configuration of a TOML file, empty when the file doesn't exist
+    pub fn load(path: &Path) -> io::Result<UserConfig> {
+        match fs::read_to_string(path) {
+            Ok(content) => UserConfig::from_toml(&content),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(UserConfig::default()),
+            Err(e) => Err(e),
+        }
+    }
+    /// Load the user configuration file (see `default_config_path`), empty without one
+    pub fn load_default() -> io::Result<UserConfig> {
+        match default_config_path() {
+            Some(path) => UserConfig::load(&path),
+            None => Ok(UserConfig::default()),
+        }
+    }
+    /// Parse the TOML content of a user configuration file, an unknown key or an invalid value
+    /// failing with its line number
+    pub fn from_toml(content: &str) -> io::Result<UserConfig> {
+        let invalid = |(message, span): (String, Option<Range<usize>>)| {
+            let message = match span {
+                Some(span) => {
+                    let line = content[..span.start.min(content.len())]
+                        .matches('\n')
+                        .count();
+                    format!("{}, line {}", message, line + 1)
+                }
+                None => message,
+            };
+            io::Error::new(io::ErrorKind::InvalidData, message)
+        };
+        let file: UserConfigFile =
+            toml::from_str(content).map_err(|e| invalid((e.message().to_string(), e.span())))?;
+        let safety_margin = match file.safety_margin {
+            Some(margin) if !(0.0..1.0).contains(margin.get_ref()) => {
+                let message = "Expected a share in [0, 1) for safety_margin".to_string();
+                return Err(invalid((message, Some(margin.span()))));
+            }
+            margin => margin.map(Spanned::into_inner),
+        };
+        Ok(UserConfig {
+            token_source: parse_value(file.token_source).map_err(&invalid)?,
+            endpoint: file.endpoint,
+            precision: parse_value(file.precision).map_err(&invalid)?,
+            safety_margin,
+            output_format: parse_value(file.output_format).map_err(&invalid)?,
+        })
+    }
+    /// Merge the values given on the command line, which take precedence over the file
+    pub fn merge(self, flags: UserConfig) -> UserConfig {
+        UserConfig {
+            token_source: flags.token_source.or(self.token_source),
+            endpoint: flags.endpoint.or(self.endpoint),
+            precision: flags.precision.or(self.precision),
+            safety_margin: flags.safety_margin.or(self.safety_margin),
+            output_format: flags.output_format.or(self.output_format),
+        }
+    }
+    /// Build the Hub client: the token of the token source, or `HF_TOKEN`, and the endpoint, or
+    /// `HF_ENDPOINT`, tried before the default Hugging Face Hub endpoint
+    pub fn hub_client(&self) -> HubClient {
+        let token_source = self.token_source.clone().unwrap_or(TokenSource::Env);
+        let endpoint = self
+            .endpoint
+            .clone()
+            .or_else(|| env::var("HF_ENDPOINT").ok());
+        let client = HubClient::new(token_source.read());
+        match endpoint {
+            Some(endpoint) if !endpoint.trim().is_empty() => {
+                client.with_endpoints(vec![endpoint, HUB_ENDPOINT.to_string()])
+            }
+            _ => client,
+        }
+    }
+    /// Returns the overhead model of PyTorch on CUDA with the safety margin of the user
+    pub fn overhead(&self) -> OverheadModel {
+        let mut overhead = OverheadModel::new();
+        if let Some(safety_margin) = self.safety_margin {
+            overhead.safety_margin = safety_margin;
+        }
+        overhead
+    }
+}
+
+/// The values of the configuration file, as written
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UserConfigFile {
+    token_source: Option<Spanned<String>>,
+    endpoint: Option<String>,
+    precision: Option<Spanned<String>>,
+    safety_margin: Option<Spanned<f64>>,
+    output_format: Option<Spanned<String>>,
+}
+
+/// Parse a string value of the configuration file, failing with the span of the value
+fn parse_value<T>(
+    value: Option<Spanned<String>>,
+) -> Result<Option<T>, (String, Option<Range<usize>>)>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    match value {
+        Some(value) => {
+            let span = value.span();
+            let parsed = value.into_inner().parse();
+            parsed
+                .map(Some)
+                .map_err(|e: T::Err| (e.to_string(), Some(span)))
+        }
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::QuantType;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_user_config_from_toml() {
+        let content = "# Defaults of the team\n\
+                       token_source = \"env\"\n\
+                       endpoint = 'https://hf-mirror.com' # closer\n\
+                       precision = \"Q4_K_M\"\n\
+                       safety_margin = 0.15\n\
+                       \n\
+                       output_format = \"json\"\n";
+        let config = UserConfig::from_toml(content).unwrap();
+        assert_eq!(config.token_source, Some(TokenSource::Env));
+        assert_eq!(config.endpoint.as_deref(), Some("https://hf-mirror.com"));
+        assert_eq!(config.precision, Some(Precision::GGUF(QuantType::Q4_K)));
+        assert_eq!(config.safety_margin, Some(0.15));
+        assert_eq!(config.output_format, Some(OutputFormat::Json));
+        assert_eq!(config.overhead().safety_margin, 0.15);
+
+        let client = config.hub_client();
+        assert_eq!(
+            client.get_endpoints(),
+            ["https://hf-mirror.com", "https://huggingface.co"]
+        );
+    }
+
+    #[test]
+    fn test_user_config_invalid() {
+        let error = UserConfig::from_toml("precision = \"bf16\"\nsafety_margin = 1.5\n")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "Expected a share in [0, 1) for safety_margin, line 2"
+        );
+        let error = UserConfig::from_toml("endpoint = 'https://hf-mirror.com'\ncolor = true")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("unknown field `color`"), "{}", error);
+        assert!(error.ends_with("line 2"), "{}", error);
+        let error = UserConfig::from_toml("precision = \"int3\"")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(error, "Unknown quantization type: int3, line 1");
+        assert!(UserConfig::from_toml("endpoint = 3").is_err());
+        assert!(UserConfig::from_toml("[hub]").is_err());
+
+        let missing = env::temp_dir().join(format!("aiha-missing-{}.toml", std::process::id()));
+        assert_eq!(UserConfig::load(&missing).unwrap(), UserConfig::default());
+    }
+
+    #[test]
+    fn test_user_config_merge() {
+        let file = UserConfig {
+            token_source: Some(TokenSource::HuggingFace),
+            precision: Some(Precision::BF16),
+            output_format: Some(OutputFormat::Markdown),
+            ..UserConfig::default()
+        };
+        let flags = UserConfig {
+            precision: Some(Precision::FP16),
+            ..UserConfig::default()
+        };
+        let merged = file.merge(flags);
+        assert_eq!(merged.precision, Some(Precision::FP16));
+        assert_eq!(merged.token_source, Some(TokenSource::HuggingFace));
+        assert_eq!(merged.output_format, Some(OutputFormat::Markdown));
+
+        let path = env::temp_dir().join(format!("aiha-token-{}", std::process::id()));
+        fs::write(&path, "hf_abc\n").unwrap();
+        let source: TokenSource = path.to_str().unwrap().parse().unwrap();
+        assert_eq!(source.read().as_deref(), Some("hf_abc"));
+        fs::remove_file(&path).unwrap();
+    }
+}
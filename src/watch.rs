@@ -0,0 +1,321 @@
+//! Module for watching the GPUs while a workload runs, comparing the memory they use with the
+//! estimates and recording the measurements in the calibration store. The GPUs are sampled through
+//! NVML, with the `nvml` feature on the native targets.
+use std::fmt::{Display, Formatter, Result as FmtResult};
+#[cfg(all(feature = "nvml", not(target_arch = "wasm32")))]
+use std::time::Duration;
+
+#[cfg(all(feature = "nvml", not(target_arch = "wasm32")))]
+use nvml_wrapper::Nvml;
+
+use crate::advisor::{FitReport, Scenario};
+use crate::calibration::CalibrationStore;
+
+/// Default share of the estimate the used memory can diverge by before it is flagged
+pub const DEFAULT_DIVERGENCE_TOLERANCE: f64 = 0.15;
+
+/// A struct representing the memory and the utilization of a GPU at a point in time
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GpuSample {
+    /// The index of the GPU, in the order of `scan_hardware`
+    index: u32,
+    /// The memory used on the GPU by every process, in bytes
+    used_memory: i64,
+    /// The share of the time a kernel ran on the GPU during the last sample period, in percent
+    utilization: u32,
+}
+
+/// GpuSample implementation
+impl GpuSample {
+    /// Build a new `GpuSample` struct based on the provided parameters
+    pub fn new(index: u32, used_memory: i64, utilization: u32) -> GpuSample {
+        GpuSample {
+            index,
+            used_memory,
+            utilization,
+        }
+    }
+    /// Returns the index of the GPU
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+    /// Returns the memory used on the GPU
+    pub fn used_memory(&self) -> i64 {
+        self.used_memory
+    }
+    /// Returns the utilization of the GPU, in percent
+    pub fn utilization(&self) -> u32 {
+        self.utilization
+    }
+}
+
+/// Sample the memory used on every NVIDIA GPU of the running system and their utilization
+#[cfg(all(feature = "nvml", not(target_arch = "wasm32")))]
+#[tracing::instrument(level = "trace")]
+pub fn sample_gpus() -> Result<Vec<GpuSample>, String> {
+    let nvml = Nvml::init().map_err(|e| e.to_string())?;
+    let count = nvml.device_count().map_err(|e| e.to_string())?;
+    (0..count)
+        .map(|index| {
+            let device = nvml.device_by_index(index).map_err(|e| e.to_string())?;
+            let used_memory = device.memory_info().map_err(|e| e.to_string())?.used;
+            // Some GPUs don't report their utilization
+            let utilization = device.utilization_rates().map(|u| u.gpu).unwrap_or(0);
+            Ok(GpuSample::new(index, used_memory as i64, utilization))
+        })
+        .collect()
+}
+
+/// A struct representing a GPU using more or less memory than estimated
+#[derive(Clone, Debug, PartialEq)]
+pub struct Divergence {
+    /// The name of the device
+    device: String,
+    /// The estimated memory, in bytes
+    estimated: i64,
+    /// The peak of the used memory, in bytes
+    measured: i64,
+}
+
+/// Divergence implementation
+impl Divergence {
+    /// Build a new `Divergence` struct based on the provided parameters
+    pub fn new(device: String, estimated: i64, measured: i64) -> Divergence {
+        Divergence {
+            device,
+            estimated,
+            measured,
+        }
+    }
+    /// Returns the name of the device
+    pub fn device(&self) -> &str {
+        &self.device
+    }
+    /// Returns the estimated memory
+    pub fn estimated(&self) -> i64 {
+        self.estimated
+    }
+    /// Returns the peak of the used memory
+    pub fn measured(&self) -> i64 {
+        self.measured
+    }
+    /// Returns the ratio of the measured memory to the estimated one
+    pub fn ratio(&self) -> f64 {
+        self.measured as f64 / self.estimated.max(1) as f64
+    }
+}
+
+/// Implement the display of the Divergence struct
+impl Display for Divergence {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let gib = |bytes: i64| bytes as f64 / 1024.0 / 1024.0 / 1024.0;
+        let (share, direction) = match self.ratio() >= 1.0 {
+            true => (self.ratio() - 1.0, "more"),
+            false => (1.0 - self.ratio(), "less"),
+        };
+        write!(
+            f,
+            "{}: {:.2} GiB used, {:.0}% {} than the {:.2} GiB estimated",
+            self.device,
+            gib(self.measured),
+            share * 100.0,
+            direction,
+            gib(self.estimated)
+        )
+    }
+}
+
+/// A struct representing the watch of a workload: the estimate of every GPU and the peak of the
+/// memory it used so far.
+///
+/// The used memory is the one of every process of the GPU, the workload should run alone.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Watcher {
+    /// The name of the model, its repository ID for the calibration store
+    model: String,
+    /// The scenario the workload runs
+    scenario: Scenario,
+    /// The fit of the model on every GPU, in the order of `scan_hardware`
+    reports: Vec<FitReport>,
+    /// The peak of the memory used on every GPU, in bytes
+    peaks: Vec<i64>,
+    /// The share of the estimate the used memory can diverge by
+    tolerance: f64,
+}
+
+/// Watcher implementation
+impl Watcher {
+    /// Build a new `Watcher` from the fit checks of the model (see `check_fit`), flagging the GPUs
+    /// diverging from the estimates by more than `DEFAULT_DIVERGENCE_TOLERANCE`
+    pub fn new(model: &str, scenario: Scenario, reports: Vec<FitReport>) -> Watcher {
+        let peaks = vec![0; reports.len()];
+        Watcher {
+            model: model.to_string(),
+            scenario,
+            reports,
+            peaks,
+            tolerance: DEFAULT_DIVERGENCE_TOLERANCE,
+        }
+    }
+    /// Set the share of the estimate the used memory can diverge by
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+    /// Returns the peak of the memory used on every GPU
+    pub fn peaks(&self) -> &[i64] {
+        &self.peaks
+    }
+    /// Update the peaks with the samples of the GPUs, and returns the GPUs already using more
+    /// memory than estimated, beyond the tolerance
+    pub fn observe(&mut self, samples: &[GpuSample]) -> Vec<Divergence> {
+        for sample in samples {
+            if let Some(peak) = self.peaks.get_mut(sample.index as usize) {
+                *peak = (*peak).max(sample.used_memory);
+            }
+        }
+        self.divergences()
+            .into_iter()
+            .filter(|divergence| divergence.ratio() > 1.0)
+            .collect()
+    }
+    /// Returns the GPUs whose peak diverges from the estimate beyond the tolerance, either way.
+    /// The GPUs not used yet are left out.
+    pub fn divergences(&self) -> Vec<Divergence> {
+        self.reports
+            .iter()
+            .zip(&self.peaks)
+            .filter(|(report, peak)| {
+                let estimated = report.required().max(1) as f64;
+                **peak > 0 && (**peak as f64 / estimated - 1.0).abs() > self.tolerance
+            })
+            .map(|(report, peak)| {
+                Divergence::new(report.device().to_string(), report.required(), *peak)
+            })
+            .collect()
+    }
+    /// Record the peak of every used GPU against its estimate in the calibration store
+    pub fn record(&self, calibrations: &mut CalibrationStore) {
+        for (report, peak) in self.reports.iter().zip(&self.peaks) {
+            if *peak == 0 {
+                continue;
+            }
+            calibrations.record_memory(
+                &self.model,
+                report.device(),
+                &self.scenario,
+                report.required(),
+                *peak,
+            );
+        }
+    }
+}
+
+/// Sample the GPUs every `interval` until `duration` is over, updating the watcher and calling
+/// `on_sample` with the samples and the GPUs already using more memory than estimated
+#[cfg(all(feature = "nvml", not(target_arch = "wasm32")))]
+pub async fn watch(
+    watcher: &mut Watcher,
+    interval: Duration,
+    duration: Duration,
+    mut on_sample: impl FnMut(&[GpuSample], &[Divergence]),
+) -> Result<(), String> {
+    let mut ticks = tokio::time::interval(interval);
+    let deadline = tokio::time::Instant::now() + duration;
+    while tokio::time::Instant::now() < deadline {
+        ticks.tick().await;
+        let samples = sample_gpus()?;
+        let divergences = watcher.observe(&samples);
+        on_sample(&samples, &divergences);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advisor::{FitVerdict, MemoryComponent};
+    use crate::estimate::OverheadModel;
+    use crate::models::Precision;
+    use std::env;
+
+    const GIB: i64 = 1024 * 1024 * 1024;
+
+    fn create_watcher() -> Watcher {
+        let scenario = Scenario::Inference {
+            precision: Precision::BF16,
+            batch_size: 1,
+            seq_len: 4096,
+            overhead: OverheadModel::new(),
+            sliding_window: false,
+        };
+        let reports = ["NVIDIA A100-SXM4-80GB", "NVIDIA L4"]
+            .iter()
+            .map(|device| {
+                FitReport::new(
+                    device.to_string(),
+                    FitVerdict::Fits,
+                    20 * GIB,
+                    80 * GIB,
+                    MemoryComponent::Weights,
+                )
+            })
+            .collect();
+        Watcher::new("meta-llama/Meta-Llama-3-8B", scenario, reports)
+    }
+
+    #[test]
+    fn test_watcher_observe() {
+        let mut watcher = create_watcher();
+        assert!(watcher
+            .observe(&[GpuSample::new(0, 21 * GIB, 90)])
+            .is_empty());
+        // 25 GiB is 25% more than estimated
+        let divergences = watcher.observe(&[GpuSample::new(0, 25 * GIB, 95)]);
+        assert_eq!(divergences.len(), 1);
+        assert_eq!(
+            divergences[0].to_string(),
+            "NVIDIA A100-SXM4-80GB: 25.00 GiB used, 25% more than the 20.00 GiB estimated"
+        );
+        // The peak is kept, an unknown GPU is ignored
+        watcher.observe(&[GpuSample::new(0, 10 * GIB, 0), GpuSample::new(5, GIB, 0)]);
+        assert_eq!(watcher.peaks(), [25 * GIB, 0]);
+    }
+
+    #[test]
+    fn test_watcher_divergences() {
+        let mut watcher = create_watcher().with_tolerance(0.3);
+        // Less memory than estimated is only flagged at the end of the run
+        assert!(watcher
+            .observe(&[
+                GpuSample::new(0, 25 * GIB, 0),
+                GpuSample::new(1, 10 * GIB, 0)
+            ])
+            .is_empty());
+        let divergences = watcher.divergences();
+        assert_eq!(divergences.len(), 1);
+        assert_eq!(divergences[0].device(), "NVIDIA L4");
+        assert_eq!(divergences[0].ratio(), 0.5);
+    }
+
+    #[test]
+    fn test_watcher_record() {
+        let mut watcher = create_watcher();
+        watcher.observe(&[GpuSample::new(0, 22 * GIB, 0)]);
+        let path = env::temp_dir()
+            .join(format!("aiha-watch-{}", std::process::id()))
+            .join("calibration.json");
+        let mut calibrations = CalibrationStore::load(&path).unwrap();
+        watcher.record(&mut calibrations);
+        // The unused GPU isn't recorded
+        assert_eq!(calibrations.len(), 1);
+        let calibration = calibrations
+            .get(
+                "meta-llama/Meta-Llama-3-8B",
+                "NVIDIA A100-SXM4-80GB",
+                &watcher.scenario,
+            )
+            .unwrap();
+        assert!((calibration.memory_factor() - 1.1).abs() < 1e-9);
+    }
+}